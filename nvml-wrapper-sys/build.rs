@@ -0,0 +1,42 @@
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if env::var_os("CARGO_FEATURE_LINK_STUB").is_some() {
+        link_stub();
+    }
+}
+
+// Links against the stub `libnvidia-ml` from the CUDA toolkit. See
+// `src/link_check.rs`.
+fn link_stub() {
+    println!("cargo:rerun-if-env-changed=NVML_STUB_DIR");
+    println!("cargo:rerun-if-env-changed=CUDA_PATH");
+    println!("cargo:rerun-if-env-changed=CUDA_HOME");
+
+    let windows = env::var("CARGO_CFG_TARGET_OS").map_or(false, |os| os == "windows");
+
+    let dir = match env::var_os("NVML_STUB_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let cuda = env::var_os("CUDA_PATH")
+                .or_else(|| env::var_os("CUDA_HOME"))
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("/usr/local/cuda"));
+
+            if windows {
+                cuda.join("lib").join("x64")
+            } else {
+                cuda.join("lib64").join("stubs")
+            }
+        }
+    };
+
+    println!("cargo:rustc-link-search=native={}", dir.display());
+    println!(
+        "cargo:rustc-link-lib=dylib={}",
+        if windows { "nvml" } else { "nvidia-ml" }
+    );
+}
@@ -243,6 +243,13 @@ pub const nvmlClocksThrottleReasonHwThermalSlowdown: u32 = 64;
 pub const nvmlClocksThrottleReasonHwPowerBrakeSlowdown: u32 = 128;
 pub const nvmlClocksThrottleReasonDisplayClockSetting: u32 = 256;
 pub const nvmlClocksThrottleReasonNone: u32 = 0;
+pub const nvmlClocksEventReasonGpuIdle: u32 = 1;
+pub const nvmlClocksEventReasonApplicationsClocksSetting: u32 = 2;
+pub const nvmlClocksEventReasonSwPowerCap: u32 = 4;
+pub const nvmlClocksEventReasonSyncBoost: u32 = 16;
+pub const nvmlClocksEventReasonSwThermalSlowdown: u32 = 32;
+pub const nvmlClocksEventReasonDisplayClockSetting: u32 = 256;
+pub const nvmlClocksEventReasonNone: u32 = 0;
 pub const NVML_NVFBC_SESSION_FLAG_DIFFMAP_ENABLED: u32 = 1;
 pub const NVML_NVFBC_SESSION_FLAG_CLASSIFICATIONMAP_ENABLED: u32 = 2;
 pub const NVML_NVFBC_SESSION_FLAG_CAPTURE_WITH_WAIT_NO_WAIT: u32 = 4;
@@ -990,6 +997,7 @@ pub struct nvmlAccountingStats_st {
 pub type nvmlAccountingStats_t = nvmlAccountingStats_st;
 pub const nvmlEncoderQueryType_enum_NVML_ENCODER_QUERY_H264: nvmlEncoderQueryType_enum = 0;
 pub const nvmlEncoderQueryType_enum_NVML_ENCODER_QUERY_HEVC: nvmlEncoderQueryType_enum = 1;
+pub const nvmlEncoderQueryType_enum_NVML_ENCODER_QUERY_AV1: nvmlEncoderQueryType_enum = 2;
 pub type nvmlEncoderQueryType_enum = raw::c_uint;
 pub use self::nvmlEncoderQueryType_enum as nvmlEncoderType_t;
 #[repr(C)]
@@ -1233,6 +1241,132 @@ pub struct nvmlComputeInstance_st {
     _unused: [u8; 0],
 }
 pub type nvmlComputeInstance_t = *mut nvmlComputeInstance_st;
+pub const NVML_255_MASK_BITS_PER_ELEM: u32 = 32;
+pub const NVML_255_MASK_NUM_ELEMS: u32 = 8;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlMask255_t {
+    pub mask: [raw::c_uint; 8usize],
+}
+pub const NVML_WORKLOAD_POWER_MAX_PROFILES: u32 = 255;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_MAX_P: nvmlPowerProfileType_t = 0;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_MAX_Q: nvmlPowerProfileType_t = 1;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_COMPUTE: nvmlPowerProfileType_t = 2;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_MEMORY_BOUND: nvmlPowerProfileType_t = 3;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_NETWORK: nvmlPowerProfileType_t = 4;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_BALANCED: nvmlPowerProfileType_t = 5;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_LLM_INFERENCE: nvmlPowerProfileType_t = 6;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_LLM_TRAINING: nvmlPowerProfileType_t = 7;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_RBM: nvmlPowerProfileType_t = 8;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_DCPCIE: nvmlPowerProfileType_t = 9;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_HMMA_SPARSE: nvmlPowerProfileType_t = 10;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_HMMA_DENSE: nvmlPowerProfileType_t = 11;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_SYNC_BALANCED: nvmlPowerProfileType_t = 12;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_HPC: nvmlPowerProfileType_t = 13;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_MIG: nvmlPowerProfileType_t = 14;
+pub const nvmlPowerProfileType_t_NVML_POWER_PROFILE_MAX: nvmlPowerProfileType_t = 15;
+pub type nvmlPowerProfileType_t = raw::c_uint;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlWorkloadPowerProfileInfo_v1_t {
+    pub version: raw::c_uint,
+    pub profileId: raw::c_uint,
+    pub priority: raw::c_uint,
+    pub conflictingMask: nvmlMask255_t,
+}
+pub type nvmlWorkloadPowerProfileInfo_t = nvmlWorkloadPowerProfileInfo_v1_t;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlWorkloadPowerProfileProfilesInfo_v1_t {
+    pub version: raw::c_uint,
+    pub perfProfilesMask: nvmlMask255_t,
+    pub perfProfile: [nvmlWorkloadPowerProfileInfo_t; 255usize],
+}
+pub type nvmlWorkloadPowerProfileProfilesInfo_t = nvmlWorkloadPowerProfileProfilesInfo_v1_t;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlWorkloadPowerProfileCurrentProfiles_v1_t {
+    pub version: raw::c_uint,
+    pub perfProfilesMask: nvmlMask255_t,
+    pub requestedProfilesMask: nvmlMask255_t,
+    pub enforcedProfilesMask: nvmlMask255_t,
+}
+pub type nvmlWorkloadPowerProfileCurrentProfiles_t = nvmlWorkloadPowerProfileCurrentProfiles_v1_t;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlWorkloadPowerProfileRequestedProfiles_v1_t {
+    pub version: raw::c_uint,
+    pub requestedProfilesMask: nvmlMask255_t,
+}
+pub type nvmlWorkloadPowerProfileRequestedProfiles_t =
+    nvmlWorkloadPowerProfileRequestedProfiles_v1_t;
+pub const NVML_GPU_FABRIC_UUID_LEN: u32 = 16;
+pub const NVML_GPU_FABRIC_STATE_NOT_SUPPORTED: u32 = 0;
+pub const NVML_GPU_FABRIC_STATE_NOT_STARTED: u32 = 1;
+pub const NVML_GPU_FABRIC_STATE_IN_PROGRESS: u32 = 2;
+pub const NVML_GPU_FABRIC_STATE_COMPLETED: u32 = 3;
+pub type nvmlGpuFabricState_t = raw::c_uchar;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlGpuFabricInfo_t {
+    pub clusterUuid: [raw::c_uchar; 16usize],
+    pub status: nvmlReturn_t,
+    pub cliqueId: raw::c_uint,
+    pub state: nvmlGpuFabricState_t,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlFanSpeedInfo_v1_t {
+    pub version: raw::c_uint,
+    pub fan: raw::c_uint,
+    pub speed: raw::c_uint,
+}
+pub type nvmlFanSpeedInfo_t = nvmlFanSpeedInfo_v1_t;
+pub const NVML_FAN_POLICY_TEMPERATURE_CONTINOUS_SW: u32 = 0;
+pub const NVML_FAN_POLICY_MANUAL: u32 = 1;
+pub type nvmlFanControlPolicy_t = raw::c_uint;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlVgpuHeterogeneousMode_v1_t {
+    pub version: raw::c_uint,
+    pub mode: raw::c_uint,
+}
+pub type nvmlVgpuHeterogeneousMode_t = nvmlVgpuHeterogeneousMode_v1_t;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlVgpuPlacementList_v1_t {
+    pub version: raw::c_uint,
+    pub placementSize: raw::c_uint,
+    pub count: raw::c_uint,
+    pub placementIds: *mut raw::c_uint,
+}
+pub type nvmlVgpuPlacementList_t = nvmlVgpuPlacementList_v1_t;
+pub type nvmlPowerScopeType_t = raw::c_uchar;
+pub const NVML_POWER_SCOPE_GPU: u32 = 0;
+pub const NVML_POWER_SCOPE_MODULE: u32 = 1;
+pub const NVML_POWER_SCOPE_MEMORY: u32 = 2;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlPowerValue_v2_t {
+    pub version: raw::c_uint,
+    pub powerScope: nvmlPowerScopeType_t,
+    pub powerValueMw: raw::c_uint,
+}
+pub const NVML_PERF_MODES_BUFFER_SIZE: u32 = 2048;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlDevicePerfModes_v1_t {
+    pub version: raw::c_uint,
+    pub str: [raw::c_char; 2048usize],
+}
+pub type nvmlDevicePerfModes_t = nvmlDevicePerfModes_v1_t;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct nvmlDeviceCurrentClockFreqs_v1_t {
+    pub version: raw::c_uint,
+    pub str: [raw::c_char; 2048usize],
+}
+pub type nvmlDeviceCurrentClockFreqs_t = nvmlDeviceCurrentClockFreqs_v1_t;
 extern crate libloading;
 pub struct NvmlLib {
     pub __library: ::libloading::Library,
@@ -1677,6 +1811,20 @@ pub struct NvmlLib {
         ) -> nvmlReturn_t,
         ::libloading::Error,
     >,
+    pub nvmlDeviceGetCurrentClocksEventReasons: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            clocksEventReasons: *mut raw::c_ulonglong,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetSupportedClocksEventReasons: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            supportedClocksEventReasons: *mut raw::c_ulonglong,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
     pub nvmlDeviceGetPowerState: Result<
         unsafe extern "C" fn(device: nvmlDevice_t, pState: *mut nvmlPstates_t) -> nvmlReturn_t,
         ::libloading::Error,
@@ -2845,6 +2993,159 @@ pub struct NvmlLib {
         unsafe extern "C" fn(device: nvmlDevice_t, type_: *mut nvmlBusType_t) -> nvmlReturn_t,
         ::libloading::Error,
     >,
+    pub nvmlDeviceWorkloadPowerProfileGetProfilesInfo: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            profilesInfo: *mut nvmlWorkloadPowerProfileProfilesInfo_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceWorkloadPowerProfileGetCurrentProfiles: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            currentProfiles: *mut nvmlWorkloadPowerProfileCurrentProfiles_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceWorkloadPowerProfileSetRequestedProfiles: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            requestedProfiles: *mut nvmlWorkloadPowerProfileRequestedProfiles_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceWorkloadPowerProfileClearRequestedProfiles: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            requestedProfiles: *mut nvmlWorkloadPowerProfileRequestedProfiles_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetGpuFabricInfo: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            gpuFabricInfo: *mut nvmlGpuFabricInfo_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetFanSpeedRPM: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            fanSpeed: *mut nvmlFanSpeedInfo_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceSetDefaultFanSpeed_v2: Result<
+        unsafe extern "C" fn(device: nvmlDevice_t, fan: raw::c_uint) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceSetFanSpeed_v2: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            fan: raw::c_uint,
+            speed: raw::c_uint,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetTargetFanSpeed: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            fan: raw::c_uint,
+            targetSpeed: *mut raw::c_uint,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetMinMaxFanSpeed: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            minSpeed: *mut raw::c_uint,
+            maxSpeed: *mut raw::c_uint,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetFanControlPolicy_v2: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            fan: raw::c_uint,
+            policy: *mut nvmlFanControlPolicy_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceSetFanControlPolicy: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            fan: raw::c_uint,
+            policy: nvmlFanControlPolicy_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetVgpuHeterogeneousMode: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            pHeterogeneousMode: *mut nvmlVgpuHeterogeneousMode_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceSetVgpuHeterogeneousMode: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            pHeterogeneousMode: *const nvmlVgpuHeterogeneousMode_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetVgpuTypeSupportedPlacements: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            vgpuTypeId: nvmlVgpuTypeId_t,
+            pPlacementList: *mut nvmlVgpuPlacementList_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetVgpuTypeCreatablePlacements: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            vgpuTypeId: nvmlVgpuTypeId_t,
+            pPlacementList: *mut nvmlVgpuPlacementList_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceSetPowerManagementLimit_v2: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            powerValue: *mut nvmlPowerValue_v2_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetGpcClkVfOffset: Result<
+        unsafe extern "C" fn(device: nvmlDevice_t, offset: *mut raw::c_int) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceSetGpcClkVfOffset: Result<
+        unsafe extern "C" fn(device: nvmlDevice_t, offset: raw::c_int) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetMemClkVfOffset: Result<
+        unsafe extern "C" fn(device: nvmlDevice_t, offset: *mut raw::c_int) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceSetMemClkVfOffset: Result<
+        unsafe extern "C" fn(device: nvmlDevice_t, offset: raw::c_int) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetPerformanceModes: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            perfModes: *mut nvmlDevicePerfModes_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
+    pub nvmlDeviceGetCurrentClockFreqs: Result<
+        unsafe extern "C" fn(
+            device: nvmlDevice_t,
+            currentClockFreqs: *mut nvmlDeviceCurrentClockFreqs_t,
+        ) -> nvmlReturn_t,
+        ::libloading::Error,
+    >,
 }
 impl NvmlLib {
     pub unsafe fn new<P>(path: P) -> Result<Self, ::libloading::Error>
@@ -3021,6 +3322,12 @@ impl NvmlLib {
         let nvmlDeviceGetSupportedClocksThrottleReasons = __library
             .get(b"nvmlDeviceGetSupportedClocksThrottleReasons\0")
             .map(|sym| *sym);
+        let nvmlDeviceGetCurrentClocksEventReasons = __library
+            .get(b"nvmlDeviceGetCurrentClocksEventReasons\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetSupportedClocksEventReasons = __library
+            .get(b"nvmlDeviceGetSupportedClocksEventReasons\0")
+            .map(|sym| *sym);
         let nvmlDeviceGetPowerState = __library.get(b"nvmlDeviceGetPowerState\0").map(|sym| *sym);
         let nvmlDeviceGetPowerManagementMode = __library
             .get(b"nvmlDeviceGetPowerManagementMode\0")
@@ -3447,6 +3754,71 @@ impl NvmlLib {
             .get(b"nvmlDeviceGetDeviceHandleFromMigDeviceHandle\0")
             .map(|sym| *sym);
         let nvmlDeviceGetBusType = __library.get(b"nvmlDeviceGetBusType\0").map(|sym| *sym);
+        let nvmlDeviceWorkloadPowerProfileGetProfilesInfo = __library
+            .get(b"nvmlDeviceWorkloadPowerProfileGetProfilesInfo\0")
+            .map(|sym| *sym);
+        let nvmlDeviceWorkloadPowerProfileGetCurrentProfiles = __library
+            .get(b"nvmlDeviceWorkloadPowerProfileGetCurrentProfiles\0")
+            .map(|sym| *sym);
+        let nvmlDeviceWorkloadPowerProfileSetRequestedProfiles = __library
+            .get(b"nvmlDeviceWorkloadPowerProfileSetRequestedProfiles\0")
+            .map(|sym| *sym);
+        let nvmlDeviceWorkloadPowerProfileClearRequestedProfiles = __library
+            .get(b"nvmlDeviceWorkloadPowerProfileClearRequestedProfiles\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetGpuFabricInfo = __library
+            .get(b"nvmlDeviceGetGpuFabricInfo\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetFanSpeedRPM = __library.get(b"nvmlDeviceGetFanSpeedRPM\0").map(|sym| *sym);
+        let nvmlDeviceSetDefaultFanSpeed_v2 = __library
+            .get(b"nvmlDeviceSetDefaultFanSpeed_v2\0")
+            .map(|sym| *sym);
+        let nvmlDeviceSetFanSpeed_v2 = __library.get(b"nvmlDeviceSetFanSpeed_v2\0").map(|sym| *sym);
+        let nvmlDeviceGetTargetFanSpeed = __library
+            .get(b"nvmlDeviceGetTargetFanSpeed\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetMinMaxFanSpeed = __library
+            .get(b"nvmlDeviceGetMinMaxFanSpeed\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetFanControlPolicy_v2 = __library
+            .get(b"nvmlDeviceGetFanControlPolicy_v2\0")
+            .map(|sym| *sym);
+        let nvmlDeviceSetFanControlPolicy = __library
+            .get(b"nvmlDeviceSetFanControlPolicy\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetVgpuHeterogeneousMode = __library
+            .get(b"nvmlDeviceGetVgpuHeterogeneousMode\0")
+            .map(|sym| *sym);
+        let nvmlDeviceSetVgpuHeterogeneousMode = __library
+            .get(b"nvmlDeviceSetVgpuHeterogeneousMode\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetVgpuTypeSupportedPlacements = __library
+            .get(b"nvmlDeviceGetVgpuTypeSupportedPlacements\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetVgpuTypeCreatablePlacements = __library
+            .get(b"nvmlDeviceGetVgpuTypeCreatablePlacements\0")
+            .map(|sym| *sym);
+        let nvmlDeviceSetPowerManagementLimit_v2 = __library
+            .get(b"nvmlDeviceSetPowerManagementLimit_v2\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetGpcClkVfOffset = __library
+            .get(b"nvmlDeviceGetGpcClkVfOffset\0")
+            .map(|sym| *sym);
+        let nvmlDeviceSetGpcClkVfOffset = __library
+            .get(b"nvmlDeviceSetGpcClkVfOffset\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetMemClkVfOffset = __library
+            .get(b"nvmlDeviceGetMemClkVfOffset\0")
+            .map(|sym| *sym);
+        let nvmlDeviceSetMemClkVfOffset = __library
+            .get(b"nvmlDeviceSetMemClkVfOffset\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetPerformanceModes = __library
+            .get(b"nvmlDeviceGetPerformanceModes\0")
+            .map(|sym| *sym);
+        let nvmlDeviceGetCurrentClockFreqs = __library
+            .get(b"nvmlDeviceGetCurrentClockFreqs\0")
+            .map(|sym| *sym);
         Ok(NvmlLib {
             __library,
             nvmlInit_v2,
@@ -3525,6 +3897,8 @@ impl NvmlLib {
             nvmlDeviceGetPerformanceState,
             nvmlDeviceGetCurrentClocksThrottleReasons,
             nvmlDeviceGetSupportedClocksThrottleReasons,
+            nvmlDeviceGetCurrentClocksEventReasons,
+            nvmlDeviceGetSupportedClocksEventReasons,
             nvmlDeviceGetPowerState,
             nvmlDeviceGetPowerManagementMode,
             nvmlDeviceGetPowerManagementLimit,
@@ -3695,6 +4069,29 @@ impl NvmlLib {
             nvmlDeviceGetMigDeviceHandleByIndex,
             nvmlDeviceGetDeviceHandleFromMigDeviceHandle,
             nvmlDeviceGetBusType,
+            nvmlDeviceWorkloadPowerProfileGetProfilesInfo,
+            nvmlDeviceWorkloadPowerProfileGetCurrentProfiles,
+            nvmlDeviceWorkloadPowerProfileSetRequestedProfiles,
+            nvmlDeviceWorkloadPowerProfileClearRequestedProfiles,
+            nvmlDeviceGetGpuFabricInfo,
+            nvmlDeviceGetFanSpeedRPM,
+            nvmlDeviceSetDefaultFanSpeed_v2,
+            nvmlDeviceSetFanSpeed_v2,
+            nvmlDeviceGetTargetFanSpeed,
+            nvmlDeviceGetMinMaxFanSpeed,
+            nvmlDeviceGetFanControlPolicy_v2,
+            nvmlDeviceSetFanControlPolicy,
+            nvmlDeviceGetVgpuHeterogeneousMode,
+            nvmlDeviceSetVgpuHeterogeneousMode,
+            nvmlDeviceGetVgpuTypeSupportedPlacements,
+            nvmlDeviceGetVgpuTypeCreatablePlacements,
+            nvmlDeviceSetPowerManagementLimit_v2,
+            nvmlDeviceGetGpcClkVfOffset,
+            nvmlDeviceSetGpcClkVfOffset,
+            nvmlDeviceGetMemClkVfOffset,
+            nvmlDeviceSetMemClkVfOffset,
+            nvmlDeviceGetPerformanceModes,
+            nvmlDeviceGetCurrentClockFreqs,
         })
     }
     pub unsafe fn nvmlInit_v2(&self) -> nvmlReturn_t {
@@ -4456,6 +4853,26 @@ impl NvmlLib {
             .as_ref()
             .expect("Expected function, got error."))(device, supportedClocksThrottleReasons)
     }
+    pub unsafe fn nvmlDeviceGetCurrentClocksEventReasons(
+        &self,
+        device: nvmlDevice_t,
+        clocksEventReasons: *mut raw::c_ulonglong,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetCurrentClocksEventReasons
+            .as_ref()
+            .expect("Expected function, got error."))(device, clocksEventReasons)
+    }
+    pub unsafe fn nvmlDeviceGetSupportedClocksEventReasons(
+        &self,
+        device: nvmlDevice_t,
+        supportedClocksEventReasons: *mut raw::c_ulonglong,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetSupportedClocksEventReasons
+            .as_ref()
+            .expect("Expected function, got error."))(device, supportedClocksEventReasons)
+    }
     pub unsafe fn nvmlDeviceGetPowerState(
         &self,
         device: nvmlDevice_t,
@@ -6313,4 +6730,241 @@ impl NvmlLib {
             .as_ref()
             .expect("Expected function, got error."))(device, type_)
     }
+    pub unsafe fn nvmlDeviceWorkloadPowerProfileGetProfilesInfo(
+        &self,
+        device: nvmlDevice_t,
+        profilesInfo: *mut nvmlWorkloadPowerProfileProfilesInfo_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceWorkloadPowerProfileGetProfilesInfo
+            .as_ref()
+            .expect("Expected function, got error."))(device, profilesInfo)
+    }
+    pub unsafe fn nvmlDeviceWorkloadPowerProfileGetCurrentProfiles(
+        &self,
+        device: nvmlDevice_t,
+        currentProfiles: *mut nvmlWorkloadPowerProfileCurrentProfiles_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceWorkloadPowerProfileGetCurrentProfiles
+            .as_ref()
+            .expect("Expected function, got error."))(device, currentProfiles)
+    }
+    pub unsafe fn nvmlDeviceWorkloadPowerProfileSetRequestedProfiles(
+        &self,
+        device: nvmlDevice_t,
+        requestedProfiles: *mut nvmlWorkloadPowerProfileRequestedProfiles_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceWorkloadPowerProfileSetRequestedProfiles
+            .as_ref()
+            .expect("Expected function, got error."))(device, requestedProfiles)
+    }
+    pub unsafe fn nvmlDeviceWorkloadPowerProfileClearRequestedProfiles(
+        &self,
+        device: nvmlDevice_t,
+        requestedProfiles: *mut nvmlWorkloadPowerProfileRequestedProfiles_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceWorkloadPowerProfileClearRequestedProfiles
+            .as_ref()
+            .expect("Expected function, got error."))(device, requestedProfiles)
+    }
+    pub unsafe fn nvmlDeviceGetGpuFabricInfo(
+        &self,
+        device: nvmlDevice_t,
+        gpuFabricInfo: *mut nvmlGpuFabricInfo_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetGpuFabricInfo
+            .as_ref()
+            .expect("Expected function, got error."))(device, gpuFabricInfo)
+    }
+    pub unsafe fn nvmlDeviceGetFanSpeedRPM(
+        &self,
+        device: nvmlDevice_t,
+        fanSpeed: *mut nvmlFanSpeedInfo_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetFanSpeedRPM
+            .as_ref()
+            .expect("Expected function, got error."))(device, fanSpeed)
+    }
+    pub unsafe fn nvmlDeviceSetDefaultFanSpeed_v2(
+        &self,
+        device: nvmlDevice_t,
+        fan: raw::c_uint,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceSetDefaultFanSpeed_v2
+            .as_ref()
+            .expect("Expected function, got error."))(device, fan)
+    }
+    pub unsafe fn nvmlDeviceSetFanSpeed_v2(
+        &self,
+        device: nvmlDevice_t,
+        fan: raw::c_uint,
+        speed: raw::c_uint,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceSetFanSpeed_v2
+            .as_ref()
+            .expect("Expected function, got error."))(device, fan, speed)
+    }
+    pub unsafe fn nvmlDeviceGetTargetFanSpeed(
+        &self,
+        device: nvmlDevice_t,
+        fan: raw::c_uint,
+        targetSpeed: *mut raw::c_uint,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetTargetFanSpeed
+            .as_ref()
+            .expect("Expected function, got error."))(device, fan, targetSpeed)
+    }
+    pub unsafe fn nvmlDeviceGetMinMaxFanSpeed(
+        &self,
+        device: nvmlDevice_t,
+        minSpeed: *mut raw::c_uint,
+        maxSpeed: *mut raw::c_uint,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetMinMaxFanSpeed
+            .as_ref()
+            .expect("Expected function, got error."))(device, minSpeed, maxSpeed)
+    }
+    pub unsafe fn nvmlDeviceGetFanControlPolicy_v2(
+        &self,
+        device: nvmlDevice_t,
+        fan: raw::c_uint,
+        policy: *mut nvmlFanControlPolicy_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetFanControlPolicy_v2
+            .as_ref()
+            .expect("Expected function, got error."))(device, fan, policy)
+    }
+    pub unsafe fn nvmlDeviceSetFanControlPolicy(
+        &self,
+        device: nvmlDevice_t,
+        fan: raw::c_uint,
+        policy: nvmlFanControlPolicy_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceSetFanControlPolicy
+            .as_ref()
+            .expect("Expected function, got error."))(device, fan, policy)
+    }
+    pub unsafe fn nvmlDeviceGetVgpuHeterogeneousMode(
+        &self,
+        device: nvmlDevice_t,
+        pHeterogeneousMode: *mut nvmlVgpuHeterogeneousMode_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetVgpuHeterogeneousMode
+            .as_ref()
+            .expect("Expected function, got error."))(device, pHeterogeneousMode)
+    }
+    pub unsafe fn nvmlDeviceSetVgpuHeterogeneousMode(
+        &self,
+        device: nvmlDevice_t,
+        pHeterogeneousMode: *const nvmlVgpuHeterogeneousMode_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceSetVgpuHeterogeneousMode
+            .as_ref()
+            .expect("Expected function, got error."))(device, pHeterogeneousMode)
+    }
+    pub unsafe fn nvmlDeviceGetVgpuTypeSupportedPlacements(
+        &self,
+        device: nvmlDevice_t,
+        vgpuTypeId: nvmlVgpuTypeId_t,
+        pPlacementList: *mut nvmlVgpuPlacementList_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetVgpuTypeSupportedPlacements
+            .as_ref()
+            .expect("Expected function, got error."))(device, vgpuTypeId, pPlacementList)
+    }
+    pub unsafe fn nvmlDeviceGetVgpuTypeCreatablePlacements(
+        &self,
+        device: nvmlDevice_t,
+        vgpuTypeId: nvmlVgpuTypeId_t,
+        pPlacementList: *mut nvmlVgpuPlacementList_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetVgpuTypeCreatablePlacements
+            .as_ref()
+            .expect("Expected function, got error."))(device, vgpuTypeId, pPlacementList)
+    }
+    pub unsafe fn nvmlDeviceSetPowerManagementLimit_v2(
+        &self,
+        device: nvmlDevice_t,
+        powerValue: *mut nvmlPowerValue_v2_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceSetPowerManagementLimit_v2
+            .as_ref()
+            .expect("Expected function, got error."))(device, powerValue)
+    }
+    pub unsafe fn nvmlDeviceGetGpcClkVfOffset(
+        &self,
+        device: nvmlDevice_t,
+        offset: *mut raw::c_int,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetGpcClkVfOffset
+            .as_ref()
+            .expect("Expected function, got error."))(device, offset)
+    }
+    pub unsafe fn nvmlDeviceSetGpcClkVfOffset(
+        &self,
+        device: nvmlDevice_t,
+        offset: raw::c_int,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceSetGpcClkVfOffset
+            .as_ref()
+            .expect("Expected function, got error."))(device, offset)
+    }
+    pub unsafe fn nvmlDeviceGetMemClkVfOffset(
+        &self,
+        device: nvmlDevice_t,
+        offset: *mut raw::c_int,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetMemClkVfOffset
+            .as_ref()
+            .expect("Expected function, got error."))(device, offset)
+    }
+    pub unsafe fn nvmlDeviceSetMemClkVfOffset(
+        &self,
+        device: nvmlDevice_t,
+        offset: raw::c_int,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceSetMemClkVfOffset
+            .as_ref()
+            .expect("Expected function, got error."))(device, offset)
+    }
+    pub unsafe fn nvmlDeviceGetPerformanceModes(
+        &self,
+        device: nvmlDevice_t,
+        perfModes: *mut nvmlDevicePerfModes_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetPerformanceModes
+            .as_ref()
+            .expect("Expected function, got error."))(device, perfModes)
+    }
+    pub unsafe fn nvmlDeviceGetCurrentClockFreqs(
+        &self,
+        device: nvmlDevice_t,
+        currentClockFreqs: *mut nvmlDeviceCurrentClockFreqs_t,
+    ) -> nvmlReturn_t {
+        (self
+            .nvmlDeviceGetCurrentClockFreqs
+            .as_ref()
+            .expect("Expected function, got error."))(device, currentClockFreqs)
+    }
 }
@@ -29,6 +29,11 @@ on systems that don't have relevant hardware.
 I would be willing to consider maintaining both types of bindings in this crate if
 there's a convincing reason to do so; please file an issue.
 
+The `link-stub` feature can be used to verify the functions in these bindings against
+the stub `libnvidia-ml` shipped with the CUDA toolkit at build time (see the
+`link_check` module). Loading NVML at runtime remains the only supported way of
+calling into it.
+
 ## NVML Support
 
 These bindings were generated for NVML version 11. Each new version of NVML is
@@ -51,3 +56,5 @@ should be useful regardless of NVML version bumps.
 // warnings and according to commentary are not useful. See
 // https://github.com/rust-lang/rust-bindgen/issues/1651 for more.
 pub mod bindings;
+#[cfg(feature = "link-stub")]
+pub mod link_check;
@@ -0,0 +1,331 @@
+/*!
+Build-time verification of the symbols in `bindings::NvmlLib`.
+
+Enabled by the `link-stub` feature, which links this crate against the stub
+`libnvidia-ml` shipped with the CUDA toolkit. Every function that
+`bindings::NvmlLib` can load is declared here and referenced from `SYMBOLS`,
+so linking anything that uses `SYMBOLS` fails if the stub (and therefore the
+NVML version it corresponds to) is missing a function.
+
+The stub is located via the `NVML_STUB_DIR` environment variable if set, and
+the `stubs` directory of the CUDA toolkit found via `CUDA_PATH` or
+`CUDA_HOME` (falling back to `/usr/local/cuda`) otherwise.
+
+Loading NVML at runtime through `bindings::NvmlLib` is unaffected by this
+feature. Note that, because the stub is linked dynamically, binaries that
+reference `SYMBOLS` do require NVML to be present at runtime; the feature is
+intended for use in tests and CI. Where no driver is installed, building the
+tests is enough to check every symbol:
+
+```text
+cargo test -p nvml-wrapper-sys --features link-stub --no-run
+```
+*/
+
+// Signatures don't matter for symbol resolution, so every function is
+// declared as taking and returning nothing. These must never be called.
+macro_rules! link_check {
+    ($($name:ident),* $(,)?) => {
+        extern "C" {
+            $(fn $name();)*
+        }
+
+        /// The name and address of every function in `bindings::NvmlLib`.
+        ///
+        /// The addresses must not be called.
+        pub static SYMBOLS: &[(&str, unsafe extern "C" fn())] = &[
+            $((stringify!($name), $name as unsafe extern "C" fn()),)*
+        ];
+    };
+}
+
+link_check! {
+    nvmlInit_v2,
+    nvmlInitWithFlags,
+    nvmlShutdown,
+    nvmlErrorString,
+    nvmlSystemGetDriverVersion,
+    nvmlSystemGetNVMLVersion,
+    nvmlSystemGetCudaDriverVersion,
+    nvmlSystemGetCudaDriverVersion_v2,
+    nvmlSystemGetProcessName,
+    nvmlUnitGetCount,
+    nvmlUnitGetHandleByIndex,
+    nvmlUnitGetUnitInfo,
+    nvmlUnitGetLedState,
+    nvmlUnitGetPsuInfo,
+    nvmlUnitGetTemperature,
+    nvmlUnitGetFanSpeedInfo,
+    nvmlUnitGetDevices,
+    nvmlSystemGetHicVersion,
+    nvmlDeviceGetCount_v2,
+    nvmlDeviceGetAttributes_v2,
+    nvmlDeviceGetHandleByIndex_v2,
+    nvmlDeviceGetHandleBySerial,
+    nvmlDeviceGetHandleByUUID,
+    nvmlDeviceGetHandleByPciBusId_v2,
+    nvmlDeviceGetName,
+    nvmlDeviceGetBrand,
+    nvmlDeviceGetIndex,
+    nvmlDeviceGetSerial,
+    nvmlDeviceGetMemoryAffinity,
+    nvmlDeviceGetCpuAffinityWithinScope,
+    nvmlDeviceGetCpuAffinity,
+    nvmlDeviceSetCpuAffinity,
+    nvmlDeviceClearCpuAffinity,
+    nvmlDeviceGetTopologyCommonAncestor,
+    nvmlDeviceGetTopologyNearestGpus,
+    nvmlSystemGetTopologyGpuSet,
+    nvmlDeviceGetP2PStatus,
+    nvmlDeviceGetUUID,
+    nvmlVgpuInstanceGetMdevUUID,
+    nvmlDeviceGetMinorNumber,
+    nvmlDeviceGetBoardPartNumber,
+    nvmlDeviceGetInforomVersion,
+    nvmlDeviceGetInforomImageVersion,
+    nvmlDeviceGetInforomConfigurationChecksum,
+    nvmlDeviceValidateInforom,
+    nvmlDeviceGetDisplayMode,
+    nvmlDeviceGetDisplayActive,
+    nvmlDeviceGetPersistenceMode,
+    nvmlDeviceGetPciInfo_v3,
+    nvmlDeviceGetMaxPcieLinkGeneration,
+    nvmlDeviceGetMaxPcieLinkWidth,
+    nvmlDeviceGetCurrPcieLinkGeneration,
+    nvmlDeviceGetCurrPcieLinkWidth,
+    nvmlDeviceGetPcieThroughput,
+    nvmlDeviceGetPcieReplayCounter,
+    nvmlDeviceGetClockInfo,
+    nvmlDeviceGetMaxClockInfo,
+    nvmlDeviceGetApplicationsClock,
+    nvmlDeviceGetDefaultApplicationsClock,
+    nvmlDeviceResetApplicationsClocks,
+    nvmlDeviceGetClock,
+    nvmlDeviceGetMaxCustomerBoostClock,
+    nvmlDeviceGetSupportedMemoryClocks,
+    nvmlDeviceGetSupportedGraphicsClocks,
+    nvmlDeviceGetAutoBoostedClocksEnabled,
+    nvmlDeviceSetAutoBoostedClocksEnabled,
+    nvmlDeviceSetDefaultAutoBoostedClocksEnabled,
+    nvmlDeviceGetFanSpeed,
+    nvmlDeviceGetFanSpeed_v2,
+    nvmlDeviceGetNumFans,
+    nvmlDeviceGetTemperature,
+    nvmlDeviceGetTemperatureThreshold,
+    nvmlDeviceSetTemperatureThreshold,
+    nvmlDeviceGetPerformanceState,
+    nvmlDeviceGetCurrentClocksThrottleReasons,
+    nvmlDeviceGetSupportedClocksThrottleReasons,
+    nvmlDeviceGetCurrentClocksEventReasons,
+    nvmlDeviceGetSupportedClocksEventReasons,
+    nvmlDeviceGetPowerState,
+    nvmlDeviceGetPowerManagementMode,
+    nvmlDeviceGetPowerManagementLimit,
+    nvmlDeviceGetPowerManagementLimitConstraints,
+    nvmlDeviceGetPowerManagementDefaultLimit,
+    nvmlDeviceGetPowerUsage,
+    nvmlDeviceGetTotalEnergyConsumption,
+    nvmlDeviceGetEnforcedPowerLimit,
+    nvmlDeviceGetGpuOperationMode,
+    nvmlDeviceGetMemoryInfo,
+    nvmlDeviceGetMemoryInfo_v2,
+    nvmlDeviceGetComputeMode,
+    nvmlDeviceGetCudaComputeCapability,
+    nvmlDeviceGetEccMode,
+    nvmlDeviceGetBoardId,
+    nvmlDeviceGetMultiGpuBoard,
+    nvmlDeviceGetTotalEccErrors,
+    nvmlDeviceGetDetailedEccErrors,
+    nvmlDeviceGetMemoryErrorCounter,
+    nvmlDeviceGetUtilizationRates,
+    nvmlDeviceGetEncoderUtilization,
+    nvmlDeviceGetEncoderCapacity,
+    nvmlDeviceGetEncoderStats,
+    nvmlDeviceGetEncoderSessions,
+    nvmlDeviceGetDecoderUtilization,
+    nvmlDeviceGetFBCStats,
+    nvmlDeviceGetFBCSessions,
+    nvmlDeviceGetDriverModel,
+    nvmlDeviceGetVbiosVersion,
+    nvmlDeviceGetBridgeChipInfo,
+    nvmlDeviceGetComputeRunningProcesses_v3,
+    nvmlDeviceGetGraphicsRunningProcesses_v3,
+    nvmlDeviceGetMPSComputeRunningProcesses_v3,
+    nvmlDeviceOnSameBoard,
+    nvmlDeviceGetAPIRestriction,
+    nvmlDeviceGetSamples,
+    nvmlDeviceGetBAR1MemoryInfo,
+    nvmlDeviceGetViolationStatus,
+    nvmlDeviceGetIrqNum,
+    nvmlDeviceGetNumGpuCores,
+    nvmlDeviceGetPowerSource,
+    nvmlDeviceGetMemoryBusWidth,
+    nvmlDeviceGetPcieLinkMaxSpeed,
+    nvmlDeviceGetAdaptiveClockInfoStatus,
+    nvmlDeviceGetAccountingMode,
+    nvmlDeviceGetAccountingStats,
+    nvmlDeviceGetAccountingPids,
+    nvmlDeviceGetAccountingBufferSize,
+    nvmlDeviceGetRetiredPages,
+    nvmlDeviceGetRetiredPages_v2,
+    nvmlDeviceGetRetiredPagesPendingStatus,
+    nvmlDeviceGetRemappedRows,
+    nvmlDeviceGetRowRemapperHistogram,
+    nvmlDeviceGetArchitecture,
+    nvmlUnitSetLedState,
+    nvmlDeviceSetPersistenceMode,
+    nvmlDeviceSetComputeMode,
+    nvmlDeviceSetEccMode,
+    nvmlDeviceClearEccErrorCounts,
+    nvmlDeviceSetDriverModel,
+    nvmlDeviceSetGpuLockedClocks,
+    nvmlDeviceResetGpuLockedClocks,
+    nvmlDeviceSetMemoryLockedClocks,
+    nvmlDeviceResetMemoryLockedClocks,
+    nvmlDeviceSetApplicationsClocks,
+    nvmlDeviceGetClkMonStatus,
+    nvmlDeviceSetPowerManagementLimit,
+    nvmlDeviceSetGpuOperationMode,
+    nvmlDeviceSetAPIRestriction,
+    nvmlDeviceSetAccountingMode,
+    nvmlDeviceClearAccountingPids,
+    nvmlDeviceGetNvLinkState,
+    nvmlDeviceGetNvLinkVersion,
+    nvmlDeviceGetNvLinkCapability,
+    nvmlDeviceGetNvLinkRemotePciInfo_v2,
+    nvmlDeviceGetNvLinkErrorCounter,
+    nvmlDeviceResetNvLinkErrorCounters,
+    nvmlDeviceSetNvLinkUtilizationControl,
+    nvmlDeviceGetNvLinkUtilizationControl,
+    nvmlDeviceGetNvLinkUtilizationCounter,
+    nvmlDeviceFreezeNvLinkUtilizationCounter,
+    nvmlDeviceResetNvLinkUtilizationCounter,
+    nvmlDeviceGetNvLinkRemoteDeviceType,
+    nvmlEventSetCreate,
+    nvmlDeviceRegisterEvents,
+    nvmlDeviceGetSupportedEventTypes,
+    nvmlEventSetWait_v2,
+    nvmlEventSetFree,
+    nvmlDeviceModifyDrainState,
+    nvmlDeviceQueryDrainState,
+    nvmlDeviceRemoveGpu_v2,
+    nvmlDeviceDiscoverGpus,
+    nvmlDeviceGetFieldValues,
+    nvmlDeviceGetVirtualizationMode,
+    nvmlDeviceGetHostVgpuMode,
+    nvmlDeviceSetVirtualizationMode,
+    nvmlDeviceGetGridLicensableFeatures_v4,
+    nvmlDeviceGetProcessUtilization,
+    nvmlDeviceGetSupportedVgpus,
+    nvmlDeviceGetCreatableVgpus,
+    nvmlVgpuTypeGetClass,
+    nvmlVgpuTypeGetName,
+    nvmlVgpuTypeGetGpuInstanceProfileId,
+    nvmlVgpuTypeGetDeviceID,
+    nvmlVgpuTypeGetFramebufferSize,
+    nvmlVgpuTypeGetNumDisplayHeads,
+    nvmlVgpuTypeGetResolution,
+    nvmlVgpuTypeGetLicense,
+    nvmlVgpuTypeGetFrameRateLimit,
+    nvmlVgpuTypeGetMaxInstances,
+    nvmlVgpuTypeGetMaxInstancesPerVm,
+    nvmlDeviceGetActiveVgpus,
+    nvmlVgpuInstanceGetVmID,
+    nvmlVgpuInstanceGetUUID,
+    nvmlVgpuInstanceGetVmDriverVersion,
+    nvmlVgpuInstanceGetFbUsage,
+    nvmlVgpuInstanceGetLicenseStatus,
+    nvmlVgpuInstanceGetType,
+    nvmlVgpuInstanceGetFrameRateLimit,
+    nvmlVgpuInstanceGetEccMode,
+    nvmlVgpuInstanceGetEncoderCapacity,
+    nvmlVgpuInstanceSetEncoderCapacity,
+    nvmlVgpuInstanceGetEncoderStats,
+    nvmlVgpuInstanceGetEncoderSessions,
+    nvmlVgpuInstanceGetFBCStats,
+    nvmlVgpuInstanceGetFBCSessions,
+    nvmlVgpuInstanceGetGpuInstanceId,
+    nvmlVgpuInstanceGetGpuPciId,
+    nvmlVgpuInstanceGetMetadata,
+    nvmlDeviceGetVgpuMetadata,
+    nvmlGetVgpuCompatibility,
+    nvmlDeviceGetPgpuMetadataString,
+    nvmlGetVgpuVersion,
+    nvmlSetVgpuVersion,
+    nvmlDeviceGetVgpuUtilization,
+    nvmlDeviceGetVgpuProcessUtilization,
+    nvmlVgpuInstanceGetAccountingMode,
+    nvmlVgpuInstanceGetAccountingPids,
+    nvmlVgpuInstanceGetAccountingStats,
+    nvmlVgpuInstanceClearAccountingPids,
+    nvmlVgpuInstanceGetLicenseInfo_v2,
+    nvmlGetExcludedDeviceCount,
+    nvmlGetExcludedDeviceInfoByIndex,
+    nvmlDeviceSetMigMode,
+    nvmlDeviceGetMigMode,
+    nvmlDeviceGetGpuInstanceProfileInfo,
+    nvmlDeviceGetGpuInstanceProfileInfoV,
+    nvmlDeviceGetGpuInstancePossiblePlacements_v2,
+    nvmlDeviceGetGpuInstanceRemainingCapacity,
+    nvmlDeviceCreateGpuInstance,
+    nvmlDeviceCreateGpuInstanceWithPlacement,
+    nvmlGpuInstanceDestroy,
+    nvmlDeviceGetGpuInstances,
+    nvmlDeviceGetGpuInstanceById,
+    nvmlGpuInstanceGetInfo,
+    nvmlGpuInstanceGetComputeInstanceProfileInfo,
+    nvmlGpuInstanceGetComputeInstanceProfileInfoV,
+    nvmlGpuInstanceGetComputeInstanceRemainingCapacity,
+    nvmlGpuInstanceCreateComputeInstance,
+    nvmlComputeInstanceDestroy,
+    nvmlGpuInstanceGetComputeInstances,
+    nvmlGpuInstanceGetComputeInstanceById,
+    nvmlComputeInstanceGetInfo_v2,
+    nvmlDeviceIsMigDeviceHandle,
+    nvmlDeviceGetGpuInstanceId,
+    nvmlDeviceGetComputeInstanceId,
+    nvmlDeviceGetMaxMigDeviceCount,
+    nvmlDeviceGetMigDeviceHandleByIndex,
+    nvmlDeviceGetDeviceHandleFromMigDeviceHandle,
+    nvmlDeviceGetBusType,
+    nvmlDeviceWorkloadPowerProfileGetProfilesInfo,
+    nvmlDeviceWorkloadPowerProfileGetCurrentProfiles,
+    nvmlDeviceWorkloadPowerProfileSetRequestedProfiles,
+    nvmlDeviceWorkloadPowerProfileClearRequestedProfiles,
+    nvmlDeviceGetGpuFabricInfo,
+    nvmlDeviceGetFanSpeedRPM,
+    nvmlDeviceSetDefaultFanSpeed_v2,
+    nvmlDeviceSetFanSpeed_v2,
+    nvmlDeviceGetTargetFanSpeed,
+    nvmlDeviceGetMinMaxFanSpeed,
+    nvmlDeviceGetFanControlPolicy_v2,
+    nvmlDeviceSetFanControlPolicy,
+    nvmlDeviceGetVgpuHeterogeneousMode,
+    nvmlDeviceSetVgpuHeterogeneousMode,
+    nvmlDeviceGetVgpuTypeSupportedPlacements,
+    nvmlDeviceGetVgpuTypeCreatablePlacements,
+    nvmlDeviceSetPowerManagementLimit_v2,
+    nvmlDeviceGetGpcClkVfOffset,
+    nvmlDeviceSetGpcClkVfOffset,
+    nvmlDeviceGetMemClkVfOffset,
+    nvmlDeviceSetMemClkVfOffset,
+    nvmlDeviceGetPerformanceModes,
+    nvmlDeviceGetCurrentClockFreqs,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::ptr;
+
+    // Reading each address through a volatile load keeps the references from
+    // being optimized out, so an unresolved symbol fails the link.
+    #[test]
+    fn all_symbols_resolve() {
+        for (name, function) in SYMBOLS {
+            let address = unsafe { ptr::read_volatile(function) } as usize;
+            assert_ne!(address, 0, "{} did not resolve", name);
+        }
+    }
+}
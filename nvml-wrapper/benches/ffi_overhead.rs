@@ -0,0 +1,61 @@
+//! Measures the latency of calls that are typically made on every poll, for
+//! every device in the system.
+//!
+//! Run with `cargo bench --bench ffi_overhead`. Needs an NVIDIA GPU and driver;
+//! nothing is measured if NVML fails to initialize.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use nvml_wrapper::high_level::DeviceQuery;
+use nvml_wrapper::structs::device::FieldId;
+use nvml_wrapper::sys_exports::field_id::*;
+use nvml_wrapper::Nvml;
+
+fn ffi_overhead(c: &mut Criterion) {
+    let nvml = match Nvml::init() {
+        Ok(nvml) => nvml,
+        Err(e) => {
+            eprintln!("skipping benchmarks, failed to initialize NVML: {}", e);
+            return;
+        }
+    };
+
+    let count = nvml.device_count().expect("failed to get the device count");
+    let snapshot = DeviceQuery::builder().all().build();
+    let fields = [
+        FieldId(NVML_FI_DEV_MEMORY_TEMP),
+        FieldId(NVML_FI_DEV_TOTAL_ENERGY_CONSUMPTION),
+        FieldId(NVML_FI_DEV_PCIE_REPLAY_COUNTER),
+    ];
+
+    let mut group = c.benchmark_group("ffi_overhead");
+
+    for index in 0..count {
+        let device = nvml
+            .device_by_index(index)
+            .expect("failed to get a device handle");
+
+        group.bench_with_input(
+            BenchmarkId::new("utilization_rates", index),
+            &device,
+            |b, device| b.iter(|| device.utilization_rates()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("power_usage", index),
+            &device,
+            |b, device| b.iter(|| device.power_usage()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("field_values_for", index),
+            &device,
+            |b, device| b.iter(|| device.field_values_for(&fields)),
+        );
+        group.bench_with_input(BenchmarkId::new("snapshot", index), &device, |b, device| {
+            b.iter(|| snapshot.run(device))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, ffi_overhead);
+criterion_main!(benches);
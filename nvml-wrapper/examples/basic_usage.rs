@@ -30,8 +30,8 @@ fn main() -> Result<(), NvmlError> {
     print!("\n\n");
     println!(
         "Your {name} (architecture: {architecture}, CUDA cores: {cuda_cores}) \
-        is currently sitting at {temperature} °C with a graphics clock of \
-        {graphics_clock} MHz and a memory clock of {mem_clock} MHz. Memory \
+        is currently sitting at {temperature} with a graphics clock of \
+        {graphics_clock} and a memory clock of {mem_clock}. Memory \
         usage is {used_mem} out of an available {total_mem}. Right now the \
         device is connected via a PCIe gen {link_gen} x{link_width} interface; \
         the max your hardware supports is PCIe gen {max_link_gen} \
@@ -0,0 +1,17 @@
+use nvml_wrapper::error::NvmlError;
+use nvml_wrapper::high_level::{table::Table, DeviceQuery};
+use nvml_wrapper::Nvml;
+use std::env;
+
+fn main() -> Result<(), NvmlError> {
+    let nvml = Nvml::init()?;
+    let snapshots = DeviceQuery::builder().all().build().run_all(&nvml)?;
+
+    // Respect https://no-color.org
+    let mut table = Table::from_snapshots(&snapshots);
+    table.color(env::var_os("NO_COLOR").is_none());
+
+    print!("{}", table);
+
+    Ok(())
+}
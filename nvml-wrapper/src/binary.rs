@@ -0,0 +1,128 @@
+/*!
+Compact binary encoding of this crate's types.
+
+Intended for shipping telemetry between processes, e.g. from a privileged
+collector process that talks to NVML to an unprivileged UI process that
+doesn't. Any type that implements `Serialize` / `Deserialize` with the `serde`
+feature enabled can be encoded, which includes the wrapper structs, enums and
+bitmasks as well as the `high_level` types such as `RegistryEvent`.
+
+Values are encoded with [bincode] behind a short header consisting of a magic
+number and a format version. Decoding fails with an error rather than
+producing garbage if the header doesn't match, e.g. because the two processes
+were built against different versions of this crate.
+
+```
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use nvml_wrapper::binary;
+use nvml_wrapper::high_level::RegistryEvent;
+
+let event = RegistryEvent::Removed { uuid: "GPU-...".into() };
+
+let bytes = binary::encode(&event)?;
+let decoded: RegistryEvent = binary::decode(&bytes)?;
+
+assert_eq!(decoded, event);
+# Ok(())
+# }
+```
+
+Note that the encoding is not self-describing: the receiving process must know
+which type to decode.
+
+[bincode]: https://docs.rs/bincode
+*/
+
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use bincode::{Error, ErrorKind};
+
+/// Leading bytes of every encoded value.
+pub const MAGIC: [u8; 4] = *b"NVMW";
+
+/**
+Version of the encoding format.
+
+Bumped whenever a change to this crate changes the encoding of an existing type.
+*/
+pub const FORMAT_VERSION: u8 = 1;
+
+const HEADER_LEN: usize = MAGIC.len() + 1;
+
+/**
+Encode the given value.
+
+# Errors
+
+* `ErrorKind::Custom`, if the value's `Serialize` impl fails
+*/
+pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + bincode::serialized_size(value)? as usize);
+
+    bytes.extend_from_slice(&MAGIC);
+    bytes.push(FORMAT_VERSION);
+    bincode::serialize_into(&mut bytes, value)?;
+
+    Ok(bytes)
+}
+
+/**
+Decode a value previously encoded with `encode()`.
+
+# Errors
+
+* `ErrorKind::Custom`, if the header is missing or the format version doesn't
+  match `FORMAT_VERSION`
+* Any other `ErrorKind`, if the bytes following the header are not a valid
+  encoding of `T`
+*/
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    if bytes.len() < HEADER_LEN || bytes[..MAGIC.len()] != MAGIC {
+        return Err(Box::new(ErrorKind::Custom(
+            "missing nvml-wrapper binary header".into(),
+        )));
+    }
+
+    let version = bytes[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(Box::new(ErrorKind::Custom(format!(
+            "unsupported format version {} (expected {})",
+            version, FORMAT_VERSION
+        ))));
+    }
+
+    bincode::deserialize(&bytes[HEADER_LEN..])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::enum_wrappers::device::TemperatureSensor;
+    use crate::struct_wrappers::device::Utilization;
+
+    #[test]
+    fn roundtrip() {
+        let utilization = Utilization { gpu: 42, memory: 7 };
+        let bytes = encode(&utilization).unwrap();
+
+        assert_eq!(&bytes[..MAGIC.len()], &MAGIC);
+        assert_eq!(decode::<Utilization>(&bytes).unwrap(), utilization);
+
+        let sensor = TemperatureSensor::Gpu;
+        assert_eq!(
+            decode::<TemperatureSensor>(&encode(&sensor).unwrap()).unwrap(),
+            sensor
+        );
+    }
+
+    #[test]
+    fn bad_header() {
+        let mut bytes = encode(&1u32).unwrap();
+
+        assert!(decode::<u32>(&bytes[1..]).is_err());
+        assert!(decode::<u32>(&[]).is_err());
+
+        bytes[MAGIC.len()] = FORMAT_VERSION + 1;
+        assert!(decode::<u32>(&bytes).is_err());
+    }
+}
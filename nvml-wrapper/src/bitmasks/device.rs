@@ -6,20 +6,25 @@ use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 
 bitflags! {
-    /// Flags used to specify why a GPU is throttling.
+    /**
+    Flags used to specify why the clocks of a GPU are being limited.
+
+    NVML used to call these "clocks throttle reasons"; `ThrottleReasons` is
+    kept as an alias.
+    */
     // Checked against local
     #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-    pub struct ThrottleReasons: u64 {
+    pub struct ClocksEventReasons: u64 {
         /// Nothing is running on the GPU.
         ///
         /// This limiter may be removed in a future release.
-        const GPU_IDLE                    = nvmlClocksThrottleReasonGpuIdle as u64;
+        const GPU_IDLE                    = nvmlClocksEventReasonGpuIdle as u64;
         /// GPU clocks are limited by the current applications clocks setting.
-        const APPLICATIONS_CLOCKS_SETTING = nvmlClocksThrottleReasonApplicationsClocksSetting as u64;
+        const APPLICATIONS_CLOCKS_SETTING = nvmlClocksEventReasonApplicationsClocksSetting as u64;
         #[deprecated(note = "Renamed to `APPLICATIONS_CLOCKS_SETTING`.")]
         const USER_DEFINED_CLOCKS         = nvmlClocksThrottleReasonUserDefinedClocks as u64;
         /// Software power scaling algorithm is reducing clocks.
-        const SW_POWER_CAP                = nvmlClocksThrottleReasonSwPowerCap as u64;
+        const SW_POWER_CAP                = nvmlClocksEventReasonSwPowerCap as u64;
         /**
         Hardware slowdown (reducing the core clocks by a factor of 2 or more)
         is engaged.
@@ -39,10 +44,10 @@ bitflags! {
 
         Sync boost groups can be used to maximize performance per watt. All GPUs
         in a sync boost group will boost to the minimum possible clocks across
-        the entire group. Look at the throttle reasons for other GPUs in the
+        the entire group. Look at the clocks event reasons for other GPUs in the
         system to find out why this GPU is being held at lower clocks.
         */
-        const SYNC_BOOST                  = nvmlClocksThrottleReasonSyncBoost as u64;
+        const SYNC_BOOST                  = nvmlClocksEventReasonSyncBoost as u64;
         /**
         Software thermal slowdown.
 
@@ -51,7 +56,7 @@ bitflags! {
         * The current GPU temperature is above the max GPU operating temperature
         * The current memory temperature is above the max memory operating temperature
         */
-        const SW_THERMAL_SLOWDOWN         = nvmlClocksThrottleReasonSwThermalSlowdown as u64;
+        const SW_THERMAL_SLOWDOWN         = nvmlClocksEventReasonSwThermalSlowdown as u64;
         /**
         Hardware thermal slowdown is engaged, reducing core clocks by 2x or more.
 
@@ -66,12 +71,17 @@ bitflags! {
         */
         const HW_POWER_BRAKE_SLOWDOWN     = nvmlClocksThrottleReasonHwPowerBrakeSlowdown as u64;
         /// GPU clocks are limited by the current setting of display clocks.
-        const DISPLAY_CLOCK_SETTING       = nvmlClocksThrottleReasonDisplayClockSetting as u64;
-        /// Clocks are as high as possible and are not being throttled.
-        const NONE                        = nvmlClocksThrottleReasonNone as u64;
+        const DISPLAY_CLOCK_SETTING       = nvmlClocksEventReasonDisplayClockSetting as u64;
+        /// Clocks are as high as possible and are not being limited.
+        const NONE                        = nvmlClocksEventReasonNone as u64;
     }
 }
 
+/// The name `ClocksEventReasons` had before NVML renamed throttle reasons to
+/// clocks event reasons.
+#[deprecated(note = "Renamed to `ClocksEventReasons`.")]
+pub type ThrottleReasons = ClocksEventReasons;
+
 bitflags! {
     /// Flags that specify info about a frame capture session
     #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -86,3 +96,8 @@ bitflags! {
         const CAPTURE_WITH_WAIT_TIMEOUT   = NVML_NVFBC_SESSION_FLAG_CAPTURE_WITH_WAIT_TIMEOUT;
     }
 }
+
+retain_unknown_bits! {
+    ClocksEventReasons: u64,
+    FbcFlags: u32,
+}
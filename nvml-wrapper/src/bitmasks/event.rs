@@ -38,3 +38,7 @@ bitflags! {
         const MIG_CONFIG_CHANGE     = nvmlEventMigConfigChange as u64;
     }
 }
+
+retain_unknown_bits! {
+    EventTypes: u64,
+}
@@ -1,3 +1,35 @@
+/*
+Adds `from_bits_retain()` and `unknown_bits()` to bitflags types that hold
+bitmasks returned by NVML.
+
+New drivers keep adding bits to these, so interpreting them keeps bits this
+version of the wrapper doesn't know about instead of dropping them or failing.
+*/
+macro_rules! retain_unknown_bits {
+    ($($flags:ident: $bits:ty),+ $(,)?) => {
+        $(
+            impl $flags {
+                /// Convert from underlying bit representation, keeping all
+                /// bits, including ones that don't correspond to flags.
+                pub(crate) fn from_bits_retain(bits: $bits) -> Self {
+                    // SAFETY: bitflags 1.x places no requirements on the bits
+                    // of a flags value; unknown bits are carried along by
+                    // every operation except `complement()` and `!`.
+                    unsafe { Self::from_bits_unchecked(bits) }
+                }
+
+                /// Get the bits that don't correspond to flags known to this
+                /// version of the wrapper, e.g. ones added by a newer driver.
+                ///
+                /// Returns `0` if every set bit is known.
+                pub fn unknown_bits(&self) -> $bits {
+                    self.bits() & !Self::all().bits()
+                }
+            }
+        )+
+    };
+}
+
 pub mod device;
 pub mod event;
 pub mod nv_link;
@@ -23,8 +55,29 @@ bitflags! {
     #[derive(Default)]
     pub struct InitFlags: u32 {
         /// Don't fail to initialize when no NVIDIA GPUs are found.
+        ///
+        /// `Nvml.device_count()` returns 0 on such systems.
         const NO_GPUS = NVML_INIT_FLAG_NO_GPUS;
         /// Don't attach GPUs during initialization.
+        ///
+        /// Speeds up initialization and keeps a broken GPU from failing it;
+        /// GPUs are instead attached when they are first accessed.
         const NO_ATTACH = NVML_INIT_FLAG_NO_ATTACH;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::device::ClocksEventReasons;
+
+    #[test]
+    fn unknown_bits_are_retained() {
+        let unknown = 1 << 40;
+        let reasons =
+            ClocksEventReasons::from_bits_retain(ClocksEventReasons::GPU_IDLE.bits() | unknown);
+
+        assert!(reasons.contains(ClocksEventReasons::GPU_IDLE));
+        assert_eq!(reasons.unknown_bits(), unknown);
+        assert_eq!(ClocksEventReasons::all().unknown_bits(), 0);
+    }
+}
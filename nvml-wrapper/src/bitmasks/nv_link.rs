@@ -28,3 +28,7 @@ bitflags! {
         const NO_DATA    = nvmlNvLinkUtilizationCountPktTypes_enum_NVML_NVLINK_COUNTER_PKTFILTER_RESPNODATA as u32;
     }
 }
+
+retain_unknown_bits! {
+    PacketTypes: u32,
+}
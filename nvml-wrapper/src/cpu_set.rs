@@ -0,0 +1,206 @@
+/*!
+Sets of logical CPUs, as returned by `Device.cpu_affinity()`.
+
+NVML reports CPU sets as arrays of `unsigned long` words, with CPU `n` stored
+in bit `n % BITS` of word `n / BITS`, where `BITS` is 64 on 64-bit platforms
+and 32 on 32-bit ones. `CpuSet` keeps that representation but takes care of
+the indexing:
+
+```
+use nvml_wrapper::cpu_set::CpuSet;
+
+let set: CpuSet = vec![0, 1, 64].into_iter().collect();
+
+assert!(set.contains(64));
+assert!(!set.contains(2));
+assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 1, 64]);
+```
+
+With the `libc` feature enabled on Linux, a `CpuSet` can be converted to a
+`libc::cpu_set_t` for use with `sched_setaffinity` and friends.
+*/
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    mem,
+    os::raw::c_ulong,
+};
+
+/// The number of CPUs stored in each word.
+const BITS: usize = 8 * mem::size_of::<c_ulong>();
+
+/// A set of logical CPUs. See the module-level docs.
+///
+/// Sets compare equal if they contain the same CPUs, however many words back
+/// them.
+#[derive(Debug, Clone, Default, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CpuSet {
+    words: Vec<c_ulong>,
+}
+
+impl CpuSet {
+    /// Create a set from words in the layout NVML uses.
+    pub fn from_words(words: Vec<c_ulong>) -> Self {
+        Self { words }
+    }
+
+    /// The words backing this set, in the layout NVML uses.
+    pub fn words(&self) -> &[c_ulong] {
+        &self.words
+    }
+
+    /// Whether or not the given CPU is in this set.
+    pub fn contains(&self, cpu: usize) -> bool {
+        self.words
+            .get(cpu / BITS)
+            .map_or(false, |word| word & (1 << (cpu % BITS)) != 0)
+    }
+
+    /// Add the given CPU to this set.
+    pub fn insert(&mut self, cpu: usize) {
+        if self.words.len() <= cpu / BITS {
+            self.words.resize(cpu / BITS + 1, 0);
+        }
+
+        self.words[cpu / BITS] |= 1 << (cpu % BITS);
+    }
+
+    /// The CPUs in this set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            (0..BITS)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| i * BITS + bit)
+        })
+    }
+
+    /// The number of CPUs in this set.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Whether or not this set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    // The words without trailing zero words, which don't add any CPUs.
+    fn significant_words(&self) -> &[c_ulong] {
+        let len = self
+            .words
+            .iter()
+            .rposition(|&w| w != 0)
+            .map_or(0, |i| i + 1);
+
+        &self.words[..len]
+    }
+}
+
+impl PartialEq for CpuSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant_words() == other.significant_words()
+    }
+}
+
+impl Hash for CpuSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant_words().hash(state);
+    }
+}
+
+impl FromIterator<usize> for CpuSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::default();
+
+        for cpu in iter {
+            set.insert(cpu);
+        }
+
+        set
+    }
+}
+
+impl From<&CpuSet> for Vec<usize> {
+    fn from(set: &CpuSet) -> Self {
+        set.iter().collect()
+    }
+}
+
+/// CPUs that don't fit in a `cpu_set_t` (1024 and up with glibc) are left out.
+#[cfg(all(feature = "libc", target_os = "linux"))]
+impl From<&CpuSet> for libc::cpu_set_t {
+    fn from(set: &CpuSet) -> Self {
+        // SAFETY: `cpu_set_t` is a plain bitmask, for which all zeroes is the
+        // empty set
+        let mut cpu_set: libc::cpu_set_t = unsafe { mem::zeroed() };
+        let capacity = 8 * mem::size_of::<libc::cpu_set_t>();
+
+        for cpu in set.iter().take_while(|&cpu| cpu < capacity) {
+            // SAFETY: `cpu` is within the bounds of `cpu_set`
+            unsafe { libc::CPU_SET(cpu, &mut cpu_set) };
+        }
+
+        cpu_set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn word_layout() {
+        let set = CpuSet::from_words(vec![0b11, 0b1]);
+
+        assert!(set.contains(0) && set.contains(1) && set.contains(BITS));
+        assert!(!set.contains(2) && !set.contains(BITS + 1));
+        assert!(!set.contains(10 * BITS));
+        assert_eq!(Vec::from(&set), vec![0, 1, BITS]);
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn collect() {
+        let set: CpuSet = vec![3, 2 * BITS].into_iter().collect();
+
+        assert_eq!(set.words(), &[0b1000, 0, 0b1]);
+        assert!(!set.is_empty());
+        assert!(CpuSet::from_words(vec![0, 0]).is_empty());
+    }
+
+    #[test]
+    fn eq_ignores_trailing_words() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let hash = |set: &CpuSet| {
+            let mut hasher = DefaultHasher::new();
+            set.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let short = CpuSet::from_words(vec![0b11]);
+        let long = CpuSet::from_words(vec![0b11, 0, 0]);
+
+        assert_eq!(short, long);
+        assert_eq!(hash(&short), hash(&long));
+        assert_eq!(CpuSet::default(), CpuSet::from_words(vec![0]));
+        assert_ne!(short, CpuSet::from_words(vec![0b11, 0b1]));
+    }
+
+    #[cfg(all(feature = "libc", target_os = "linux"))]
+    #[test]
+    fn to_libc() {
+        let set: CpuSet = vec![1, 70, 5000].into_iter().collect();
+        let cpu_set = libc::cpu_set_t::from(&set);
+
+        unsafe {
+            assert!(libc::CPU_ISSET(1, &cpu_set));
+            assert!(libc::CPU_ISSET(70, &cpu_set));
+            assert_eq!(libc::CPU_COUNT(&cpu_set), 2);
+        }
+    }
+}
@@ -3,39 +3,61 @@ use crate::EventSet;
 use crate::NvLink;
 use crate::Nvml;
 
-use crate::bitmasks::device::ThrottleReasons;
+use crate::bitmasks::device::ClocksEventReasons;
 #[cfg(target_os = "linux")]
 use crate::bitmasks::event::EventTypes;
 #[cfg(target_os = "windows")]
 use crate::bitmasks::Behavior;
+#[cfg(target_os = "linux")]
+use crate::cpu_set::CpuSet;
 
 use crate::enum_wrappers::{bool_from_state, device::*, state_from_bool};
 
 use crate::enums::device::BusType;
 use crate::enums::device::DeviceArchitecture;
+use crate::enums::device::DisplayState;
+use crate::enums::device::FabricState;
+use crate::enums::device::FanControlPolicy;
+use crate::enums::device::GpuInstanceProfile;
 use crate::enums::device::GpuLockedClocksSetting;
+use crate::enums::device::LockedClocks;
 use crate::enums::device::PcieLinkMaxSpeed;
+use crate::enums::device::PowerScope;
 use crate::enums::device::PowerSource;
+use crate::enums::device::UsedGpuMemory;
 #[cfg(target_os = "linux")]
 use crate::error::NvmlErrorWithSource;
-use crate::error::{nvml_sym, nvml_try, Bits, NvmlError};
+use crate::error::{nvml_fn, nvml_try_for, Bits, NvmlError, NvmlResultExt, UnsupportedContext};
+use crate::gpu_model::GpuModel;
+use crate::high_level::process_utilization::{self, ProcessUtilization};
+use crate::high_level::query::{DeviceQuery, DeviceSnapshot};
+use crate::legacy;
+use crate::mig_device::{GpuInstance, MigDevice};
 
 use crate::ffi::bindings::*;
 
 use crate::struct_wrappers::device::*;
 use crate::structs::device::*;
+use crate::structs::nv_link::NvLinkThroughput;
+use crate::units::{Celsius, Megahertz, Milliwatts};
+use crate::uuid::DeviceUuid;
+use crate::wsl;
 
 #[cfg(target_os = "linux")]
 use std::convert::TryInto;
-#[cfg(target_os = "linux")]
-use std::os::raw::c_ulong;
 use std::{
+    collections::HashMap,
     convert::TryFrom,
     ffi::CStr,
+    hash::{Hash, Hasher},
     mem,
-    os::raw::{c_int, c_uint, c_ulonglong},
+    os::raw::{c_char, c_int, c_uint, c_ulonglong},
     ptr,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
 };
+#[cfg(target_os = "linux")]
+use std::{io, os::raw::c_ulong};
 
 use static_assertions::assert_impl_all;
 
@@ -71,6 +93,8 @@ This means you shouldn't have to worry about calls to `Device` methods returning
 pub struct Device<'nvml> {
     device: nvmlDevice_t,
     nvml: &'nvml Nvml,
+    // Known if this device was obtained by index; see `.index()`
+    index: Option<u32>,
 }
 
 unsafe impl<'nvml> Send for Device<'nvml> {}
@@ -78,6 +102,32 @@ unsafe impl<'nvml> Sync for Device<'nvml> {}
 
 assert_impl_all!(Device: Send, Sync);
 
+/**
+`Device`s are equal if they wrap the same NVML handle.
+
+NVML hands out the same handle for a given GPU no matter how it was looked up
+(by index, UUID, serial, or PCI bus ID), so this identifies the physical GPU
+and allows `Device`s to be deduplicated or used as map keys. Handles are only
+meaningful within the lifetime of the `Nvml` instance they were obtained from.
+
+Clippy's `mutable_key_type` lint will flag such maps because the `Nvml`
+reference held by a `Device` leads to interior mutability. Hashing and
+comparison never look at the `Nvml` instance, so it's safe to allow.
+*/
+impl<'nvml> PartialEq for Device<'nvml> {
+    fn eq(&self, other: &Self) -> bool {
+        self.device == other.device
+    }
+}
+
+impl<'nvml> Eq for Device<'nvml> {}
+
+impl<'nvml> Hash for Device<'nvml> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.device.hash(state);
+    }
+}
+
 impl<'nvml> Device<'nvml> {
     /**
     Create a new `Device` wrapper.
@@ -93,7 +143,20 @@ impl<'nvml> Device<'nvml> {
     // Clippy bug, see https://github.com/rust-lang/rust-clippy/issues/5593
     #[allow(clippy::missing_safety_doc)]
     pub unsafe fn new(device: nvmlDevice_t, nvml: &'nvml Nvml) -> Self {
-        Self { device, nvml }
+        Self {
+            device,
+            nvml,
+            index: None,
+        }
+    }
+
+    // For callers that already know the index of the device.
+    pub(crate) unsafe fn with_index(device: nvmlDevice_t, nvml: &'nvml Nvml, index: u32) -> Self {
+        Self {
+            device,
+            nvml,
+            index: Some(index),
+        }
     }
 
     /// Access the `Nvml` reference this struct wraps
@@ -112,6 +175,38 @@ impl<'nvml> Device<'nvml> {
         self.device
     }
 
+    /**
+    Wrap a device handle obtained from other NVML FFI code.
+
+    Equivalent to `Device::new()`; the handle is not looked up again, so this is
+    the cheap way to go from a handle some other library gave you to a `Device`.
+
+    # Safety
+
+    The handle must be valid and obtained from the same NVML library `nvml`
+    has loaded. The returned `Device` must not outlive that library's
+    initialization.
+    */
+    pub unsafe fn from_raw(device: nvmlDevice_t, nvml: &'nvml Nvml) -> Self {
+        Self::new(device, nvml)
+    }
+
+    /**
+    Get the raw device handle, to pass to other NVML FFI code.
+
+    The handle stays valid for as long as the `Nvml` instance this `Device`
+    came from.
+
+    # Safety
+
+    This is unsafe to prevent it from being used without care; NVML calls made
+    through the handle bypass this wrapper's caching (e.g. of unsupported
+    functions and the device's index).
+    */
+    pub unsafe fn as_raw(&self) -> nvmlDevice_t {
+        self.device
+    }
+
     /**
     Clear all affinity bindings for the calling thread.
 
@@ -137,9 +232,9 @@ impl<'nvml> Device<'nvml> {
     #[cfg(target_os = "linux")]
     #[doc(alias = "nvmlDeviceClearCpuAffinity")]
     pub fn clear_cpu_affinity(&mut self) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceClearCpuAffinity.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceClearCpuAffinity)?;
 
-        unsafe { nvml_try(sym(self.device)) }
+        unsafe { nvml_try_for(function, sym(self.device)) }
     }
 
     /**
@@ -171,17 +266,49 @@ impl<'nvml> Device<'nvml> {
     // Tested (except for AutoBoostedClocks)
     #[doc(alias = "nvmlDeviceGetAPIRestriction")]
     pub fn is_api_restricted(&self, api: Api) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetAPIRestriction.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetAPIRestriction)?;
 
         unsafe {
             let mut restricted_state: nvmlEnableState_t = mem::zeroed();
 
-            nvml_try(sym(self.device, api.as_c(), &mut restricted_state))?;
+            self.try_for_args(function, &[api.as_c() as u64], || {
+                sym(self.device, api.as_c(), &mut restricted_state)
+            })?;
 
             bool_from_state(restricted_state)
         }
     }
 
+    /**
+    Checks whether the calling process may use the methods belonging to the
+    given restricted API, e.g. to disable UI controls the current user cannot
+    use instead of failing when they are used.
+
+    This is the case if the API is not restricted (see `.is_api_restricted()`),
+    or if it is and the process is running as root. On Windows, where this
+    crate cannot tell whether the process is elevated, restricted APIs are
+    reported as unusable.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support the feature being queried
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports all _fully supported_ products.
+    */
+    // Tested
+    pub fn can_set(&self, api: Api) -> Result<bool, NvmlError> {
+        let restricted = self.is_api_restricted(api)?;
+
+        Ok(!restricted || is_privileged())
+    }
+
     /**
     Gets the current clock setting that all applications will use unless an overspec
     situation occurs.
@@ -204,15 +331,17 @@ impl<'nvml> Device<'nvml> {
     // Checked against local
     // Tested
     #[doc(alias = "nvmlDeviceGetApplicationsClock")]
-    pub fn applications_clock(&self, clock_type: Clock) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetApplicationsClock.as_ref())?;
+    pub fn applications_clock(&self, clock_type: Clock) -> Result<Megahertz, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetApplicationsClock)?;
 
         unsafe {
             let mut clock: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, clock_type.as_c(), &mut clock))?;
+            self.try_for_args(function, &[clock_type.as_c() as u64], || {
+                sym(self.device, clock_type.as_c(), &mut clock)
+            })?;
 
-            Ok(clock)
+            Ok(Megahertz(clock))
         }
     }
 
@@ -243,13 +372,15 @@ impl<'nvml> Device<'nvml> {
     // Tested on machines other than my own
     #[doc(alias = "nvmlDeviceGetAutoBoostedClocksEnabled")]
     pub fn auto_boosted_clocks_enabled(&self) -> Result<AutoBoostClocksEnabledInfo, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetAutoBoostedClocksEnabled.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetAutoBoostedClocksEnabled)?;
 
         unsafe {
             let mut is_enabled: nvmlEnableState_t = mem::zeroed();
             let mut is_enabled_default: nvmlEnableState_t = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut is_enabled, &mut is_enabled_default))?;
+            self.try_for(function, || {
+                sym(self.device, &mut is_enabled, &mut is_enabled_default)
+            })?;
 
             Ok(AutoBoostClocksEnabledInfo {
                 is_enabled: bool_from_state(is_enabled)?,
@@ -280,11 +411,11 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetBAR1MemoryInfo")]
     pub fn bar1_memory_info(&self) -> Result<BAR1MemoryInfo, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetBAR1MemoryInfo.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetBAR1MemoryInfo)?;
 
         unsafe {
             let mut mem_info: nvmlBAR1Memory_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut mem_info))?;
+            self.try_for(function, || sym(self.device, &mut mem_info))?;
 
             Ok(mem_info.into())
         }
@@ -319,11 +450,11 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetBoardId")]
     pub fn board_id(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetBoardId.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetBoardId)?;
 
         unsafe {
             let mut id: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut id))?;
+            self.try_for(function, || sym(self.device, &mut id))?;
 
             Ok(id)
         }
@@ -346,16 +477,65 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetBrand")]
     pub fn brand(&self) -> Result<Brand, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetBrand.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetBrand)?;
 
         unsafe {
             let mut brand: nvmlBrandType_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut brand))?;
+            self.try_for(function, || sym(self.device, &mut brand))?;
 
             Brand::try_from(brand)
         }
     }
 
+    /**
+    Gets the model of this `Device`, parsed from its name, brand and
+    architecture.
+
+    See the `gpu_model` module for details. The architecture is reported as
+    `DeviceArchitecture::Unknown` if this `Device` doesn't report it.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, check that error's docs for more info
+    * `Utf8Error`, if the name obtained from the C function is not valid UTF-8
+    * `Unknown`, on any unexpected error
+    */
+    // Tested
+    pub fn model(&self) -> Result<GpuModel, NvmlError> {
+        let architecture = self
+            .architecture()
+            .optional()?
+            .unwrap_or(DeviceArchitecture::Unknown);
+
+        Ok(GpuModel::new(&self.name()?, &self.brand()?, architecture))
+    }
+
+    /**
+    Gets a snapshot of every commonly monitored metric of this `Device` at once:
+    temperature, clocks, utilization, memory, power, fan speeds, performance
+    state, clocks event (throttle) reasons and running processes.
+
+    Metrics this `Device` doesn't support are left `None` in the returned
+    `DeviceSnapshot.metrics` rather than failing the whole snapshot. Use a
+    `high_level::DeviceQuery` to fetch only some of them.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, check that error's docs for more info
+    * `Utf8Error`, if a string obtained from the C library is not valid UTF-8
+    * `Unknown`, on any unexpected error
+    */
+    // Tested
+    pub fn snapshot(&self) -> Result<DeviceSnapshot, NvmlError> {
+        DeviceQuery::builder().all().build().snapshot(self)
+    }
+
     /**
     Gets bridge chip information for all bridge chips on the board.
 
@@ -378,11 +558,11 @@ impl<'nvml> Device<'nvml> {
     // Tested on machines other than my own
     #[doc(alias = "nvmlDeviceGetBridgeChipInfo")]
     pub fn bridge_chip_info(&self) -> Result<BridgeChipHierarchy, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetBridgeChipInfo.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetBridgeChipInfo)?;
 
         unsafe {
             let mut info: nvmlBridgeChipHierarchy_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut info))?;
+            self.try_for(function, || sym(self.device, &mut info))?;
 
             BridgeChipHierarchy::try_from(info)
         }
@@ -406,20 +586,19 @@ impl<'nvml> Device<'nvml> {
     // Checked against local
     // Tested (except for CustomerMaxBoost)
     #[doc(alias = "nvmlDeviceGetClock")]
-    pub fn clock(&self, clock_type: Clock, clock_id: ClockId) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetClock.as_ref())?;
+    pub fn clock(&self, clock_type: Clock, clock_id: ClockId) -> Result<Megahertz, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetClock)?;
 
         unsafe {
             let mut clock: c_uint = mem::zeroed();
 
-            nvml_try(sym(
-                self.device,
-                clock_type.as_c(),
-                clock_id.as_c(),
-                &mut clock,
-            ))?;
+            self.try_for_args(
+                function,
+                &[clock_type.as_c() as u64, clock_id.as_c() as u64],
+                || sym(self.device, clock_type.as_c(), clock_id.as_c(), &mut clock),
+            )?;
 
-            Ok(clock)
+            Ok(Megahertz(clock))
         }
     }
 
@@ -443,15 +622,17 @@ impl<'nvml> Device<'nvml> {
     // Checked against local
     // Tested on machines other than my own
     #[doc(alias = "nvmlDeviceGetMaxCustomerBoostClock")]
-    pub fn max_customer_boost_clock(&self, clock_type: Clock) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetMaxCustomerBoostClock.as_ref())?;
+    pub fn max_customer_boost_clock(&self, clock_type: Clock) -> Result<Megahertz, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMaxCustomerBoostClock)?;
 
         unsafe {
             let mut clock: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, clock_type.as_c(), &mut clock))?;
+            self.try_for_args(function, &[clock_type.as_c() as u64], || {
+                sym(self.device, clock_type.as_c(), &mut clock)
+            })?;
 
-            Ok(clock)
+            Ok(Megahertz(clock))
         }
     }
 
@@ -471,11 +652,11 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetComputeMode")]
     pub fn compute_mode(&self) -> Result<ComputeMode, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetComputeMode.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetComputeMode)?;
 
         unsafe {
             let mut mode: nvmlComputeMode_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut mode))?;
+            self.try_for(function, || sym(self.device, &mut mode))?;
 
             ComputeMode::try_from(mode)
         }
@@ -496,13 +677,13 @@ impl<'nvml> Device<'nvml> {
     */
     #[doc(alias = "nvmlDeviceGetCudaComputeCapability")]
     pub fn cuda_compute_capability(&self) -> Result<CudaComputeCapability, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetCudaComputeCapability.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetCudaComputeCapability)?;
 
         unsafe {
             let mut major: c_int = mem::zeroed();
             let mut minor: c_int = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut major, &mut minor))?;
+            self.try_for(function, || sym(self.device, &mut major, &mut minor))?;
 
             Ok(CudaComputeCapability { major, minor })
         }
@@ -526,18 +707,60 @@ impl<'nvml> Device<'nvml> {
     // Checked against local
     // Tested
     #[doc(alias = "nvmlDeviceGetClockInfo")]
-    pub fn clock_info(&self, clock_type: Clock) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetClockInfo.as_ref())?;
+    pub fn clock_info(&self, clock_type: Clock) -> Result<Megahertz, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetClockInfo)?;
 
         unsafe {
             let mut clock: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, clock_type.as_c(), &mut clock))?;
+            self.try_for_args(function, &[clock_type.as_c() as u64], || {
+                sym(self.device, clock_type.as_c(), &mut clock)
+            })?;
 
-            Ok(clock)
+            Ok(Megahertz(clock))
         }
     }
 
+    /**
+    Gets this `Device`'s clock-related settings as a `ClockProfile`, e.g. to
+    restore them with `.apply_clock_profile()` after trying out a preset.
+
+    Settings this `Device` (or the loaded NVML library) doesn't support are
+    left `None`. NVML can't read locked clocks back, so they are recorded as
+    `LockedClocks::Reset`; see `ClockProfile`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports all _fully supported_ products.
+    */
+    // Tested
+    pub fn clock_profile(&self) -> Result<ClockProfile, NvmlError> {
+        let applications_clocks = match (
+            supported(self.applications_clock(Clock::Memory))?,
+            supported(self.applications_clock(Clock::Graphics))?,
+        ) {
+            (Some(memory), Some(graphics)) => Some(ApplicationsClocks { memory, graphics }),
+            _ => None,
+        };
+
+        Ok(ClockProfile {
+            applications_clocks,
+            auto_boosted_clocks: supported(self.auto_boosted_clocks_enabled())?
+                .map(|info| info.is_enabled),
+            gpu_locked_clocks: LockedClocks::Reset,
+            mem_locked_clocks: LockedClocks::Reset,
+            gpc_clk_vf_offset: supported(self.gpc_clk_vf_offset())?,
+            mem_clk_vf_offset: supported(self.mem_clk_vf_offset())?,
+        })
+    }
+
     /**
     Gets information about processes with a compute context running on this `Device`.
 
@@ -545,6 +768,9 @@ impl<'nvml> Device<'nvml> {
     with an active context). Graphics applications (OpenGL, DirectX) won't be listed by this
     function.
 
+    On legacy drivers that predate MIG support, the returned `ProcessInfo`s
+    never contain instance IDs (see the `legacy` module).
+
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
@@ -555,27 +781,65 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetComputeRunningProcesses_v3")]
     pub fn running_compute_processes(&self) -> Result<Vec<ProcessInfo>, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetComputeRunningProcesses_v3
-                .as_ref(),
-        )?;
+        let mut processes = vec![];
+        self.running_compute_processes_into(&mut processes)?;
+
+        Ok(processes)
+    }
+
+    /**
+    Like `.running_compute_processes()`, but writes the processes into the given
+    `Vec` (clearing it first) instead of allocating a new one.
+
+    No heap allocations are made if `processes` has enough capacity and there
+    are no more than a few dozen processes, making this suitable for polling
+    in a loop.
+
+    # Errors
+
+    See `.running_compute_processes()`.
+    */
+    #[doc(alias = "nvmlDeviceGetComputeRunningProcesses_v3")]
+    pub fn running_compute_processes_into(
+        &self,
+        processes: &mut Vec<ProcessInfo>,
+    ) -> Result<(), NvmlError> {
+        processes.clear();
+
+        if let Some((sym, function)) = self.nvml.legacy.compute_running_processes {
+            processes.extend(unsafe { legacy::running_processes(function, sym, self.device)? });
+            self.resolve_unavailable_memory(processes);
+            return Ok(());
+        }
+
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetComputeRunningProcesses_v3)?;
 
         unsafe {
             let mut count: c_uint = match self.running_compute_processes_count()? {
-                0 => return Ok(vec![]),
-                value => value + 5,
+                0 => return Ok(()),
+                value => value,
             };
             // Add a bit of headroom in case more processes are launched in
             // between the above call to get the expected count and the time we
             // actually make the call to get data below.
             count += 5;
-            let mut processes: Vec<nvmlProcessInfo_t> = vec![mem::zeroed(); count as usize];
 
-            nvml_try(sym(self.device, &mut count, processes.as_mut_ptr()))?;
-
-            Ok(processes.into_iter().map(ProcessInfo::from).collect())
+            with_buffer(count as usize, |buffer: &mut [nvmlProcessInfo_t]| {
+                self.try_for(function, || {
+                    self.nvml.legacy.call(function, sym, |sym| {
+                        sym(self.device, &mut count, buffer.as_mut_ptr())
+                    })
+                })
+                .map_err(|e| e.with_required_size(count))?;
+
+                processes.extend(
+                    buffer[..count as usize]
+                        .iter()
+                        .map(|p| ProcessInfo::from(*p)),
+                );
+                self.resolve_unavailable_memory(processes);
+                Ok(())
+            })
         }
     }
 
@@ -596,33 +860,52 @@ impl<'nvml> Device<'nvml> {
     // Tested as part of `.running_compute_processes()`
     #[doc(alias = "nvmlDeviceGetComputeRunningProcesses_v3")]
     pub fn running_compute_processes_count(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetComputeRunningProcesses_v3
-                .as_ref(),
-        )?;
+        if let Some((sym, function)) = self.nvml.legacy.compute_running_processes {
+            return unsafe { legacy::running_processes_count(function, sym, self.device) };
+        }
+
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetComputeRunningProcesses_v3)?;
 
         unsafe {
             // Indicates that we want the count
             let mut count: c_uint = 0;
 
             // Passing null doesn't mean we want the count, it's just allowed
-            match sym(self.device, &mut count, ptr::null_mut()) {
+            let result = self.nvml.legacy.call(function, sym, |sym| {
+                sym(self.device, &mut count, ptr::null_mut())
+            });
+
+            match result {
                 nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => Ok(count),
                 // If success, return 0; otherwise, return error
-                other => nvml_try(other).map(|_| 0),
+                other => self.try_for(function, || other).map(|_| 0),
             }
         }
     }
 
-    /**
-    Gets a vector of bitmasks with the ideal CPU affinity for this `Device`.
+    // NVML reports `NVML_VALUE_NOT_AVAILABLE` as the used memory of processes
+    // it hides the usage of; work out why it did so from the MIG mode.
+    fn resolve_unavailable_memory(&self, processes: &mut [ProcessInfo]) {
+        let unavailable = |p: &ProcessInfo| p.used_gpu_memory == UsedGpuMemory::Unavailable;
+        if !processes.iter().any(unavailable) {
+            return;
+        }
+
+        // Fails for MIG device handles and on devices without MIG support
+        let mig_enabled = self
+            .is_mig_enabled()
+            .map(|mode| mode.currently_enabled)
+            .unwrap_or(false);
 
-    The results are sized to `size`. For example, if processors 0, 1, 32, and 33 are
-    ideal for this `Device` and `size` == 2, result\[0\] = 0x3, result\[1\] = 0x3.
+        resolve_unavailable_memory(processes, mig_enabled, is_privileged());
+    }
+
+    /**
+    Gets the set of CPUs with an ideal affinity for this `Device`.
 
-    64 CPUs per unsigned long on 64-bit machines, 32 on 32-bit machines.
+    `size` is the number of words of the underlying bitmask to query, with 64
+    CPUs per word on 64-bit machines and 32 on 32-bit machines. CPUs beyond
+    those words are not reported.
 
     # Errors
 
@@ -643,11 +926,10 @@ impl<'nvml> Device<'nvml> {
     */
     // Checked against local
     // Tested
-    // TODO: Should we trim zeros here or leave it to the caller?
     #[cfg(target_os = "linux")]
     #[doc(alias = "nvmlDeviceGetCpuAffinity")]
-    pub fn cpu_affinity(&self, size: usize) -> Result<Vec<c_ulong>, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetCpuAffinity.as_ref())?;
+    pub fn cpu_affinity(&self, size: usize) -> Result<CpuSet, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetCpuAffinity)?;
 
         unsafe {
             if size == 0 {
@@ -657,9 +939,49 @@ impl<'nvml> Device<'nvml> {
 
             let mut affinities: Vec<c_ulong> = vec![mem::zeroed(); size];
 
-            nvml_try(sym(self.device, size as c_uint, affinities.as_mut_ptr()))?;
+            self.try_for(function, || {
+                sym(self.device, size as c_uint, affinities.as_mut_ptr())
+            })?;
+
+            Ok(CpuSet::from_words(affinities))
+        }
+    }
+
+    /**
+    Gets the performance state and clocks this `Device` is currently running
+    at, as reported by the driver's current clock frequencies string.
+
+    The clocks take any VF offsets set on this `Device` into account. See
+    `.performance_modes()` for the clock ranges of each performance state.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
+    * `Unknown`, on any unexpected error
+
+    Drivers older than this function return `FailedToLoadSymbol`.
+
+    # Device Support
+
+    NVIDIA doesn't document which devices support this.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetCurrentClockFreqs")]
+    pub fn current_clock_freqs(&self) -> Result<CurrentClockFreqs, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetCurrentClockFreqs)?;
+
+        unsafe {
+            let mut freqs: nvmlDeviceCurrentClockFreqs_t = mem::zeroed();
+            freqs.version = v1_struct_version::<nvmlDeviceCurrentClockFreqs_t>();
+
+            self.try_for(function, || sym(self.device, &mut freqs))?;
 
-            Ok(affinities)
+            CurrentClockFreqs::try_from(freqs)
         }
     }
 
@@ -682,12 +1004,12 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetCurrPcieLinkGeneration")]
     pub fn current_pcie_link_gen(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetCurrPcieLinkGeneration.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetCurrPcieLinkGeneration)?;
 
         unsafe {
             let mut link_gen: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut link_gen))?;
+            self.try_for(function, || sym(self.device, &mut link_gen))?;
 
             Ok(link_gen)
         }
@@ -712,11 +1034,11 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetCurrPcieLinkWidth")]
     pub fn current_pcie_link_width(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetCurrPcieLinkWidth.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetCurrPcieLinkWidth)?;
 
         unsafe {
             let mut link_width: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut link_width))?;
+            self.try_for(function, || sym(self.device, &mut link_width))?;
 
             Ok(link_width)
         }
@@ -741,13 +1063,15 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetDecoderUtilization")]
     pub fn decoder_utilization(&self) -> Result<UtilizationInfo, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetDecoderUtilization.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetDecoderUtilization)?;
 
         unsafe {
             let mut utilization: c_uint = mem::zeroed();
             let mut sampling_period: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut utilization, &mut sampling_period))?;
+            self.try_for(function, || {
+                sym(self.device, &mut utilization, &mut sampling_period)
+            })?;
 
             Ok(UtilizationInfo {
                 utilization,
@@ -773,11 +1097,11 @@ impl<'nvml> Device<'nvml> {
     // tested
     #[doc(alias = "nvmlDeviceGetFBCStats")]
     pub fn fbc_stats(&self) -> Result<FbcStats, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetFBCStats.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetFBCStats)?;
 
         unsafe {
             let mut fbc_stats: nvmlFBCStats_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut fbc_stats))?;
+            self.try_for(function, || sym(self.device, &mut fbc_stats))?;
 
             Ok(fbc_stats.into())
         }
@@ -793,8 +1117,6 @@ impl<'nvml> Device<'nvml> {
     # Errors
 
     * `UnexpectedVariant`, for which you can read the docs for
-    * `IncorrectBits`, if bits are found in a session's info flags that don't
-        match the flags in this wrapper
     * `Uninitialized`, if the library has not been successfully initialized
     * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
@@ -807,7 +1129,7 @@ impl<'nvml> Device<'nvml> {
     // tested
     #[doc(alias = "nvmlDeviceGetFBCSessions")]
     pub fn fbc_sessions_info(&self) -> Result<Vec<FbcSessionInfo>, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetFBCSessions.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetFBCSessions)?;
 
         unsafe {
             let mut count: c_uint = match self.fbc_session_count()? {
@@ -816,7 +1138,8 @@ impl<'nvml> Device<'nvml> {
             };
             let mut info: Vec<nvmlFBCSessionInfo_t> = vec![mem::zeroed(); count as usize];
 
-            nvml_try(sym(self.device, &mut count, info.as_mut_ptr()))?;
+            self.try_for(function, || sym(self.device, &mut count, info.as_mut_ptr()))
+                .map_err(|e| e.with_required_size(count))?;
 
             info.into_iter().map(FbcSessionInfo::try_from).collect()
         }
@@ -835,12 +1158,12 @@ impl<'nvml> Device<'nvml> {
     // tested as part of the above
     #[doc(alias = "nvmlDeviceGetFBCSessions")]
     pub fn fbc_session_count(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetFBCSessions.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetFBCSessions)?;
 
         unsafe {
             let mut count: c_uint = 0;
 
-            nvml_try(sym(self.device, &mut count, ptr::null_mut()))?;
+            self.try_for(function, || sym(self.device, &mut count, ptr::null_mut()))?;
 
             Ok(count)
         }
@@ -865,15 +1188,17 @@ impl<'nvml> Device<'nvml> {
     // Checked against local
     // Tested
     #[doc(alias = "nvmlDeviceGetDefaultApplicationsClock")]
-    pub fn default_applications_clock(&self, clock_type: Clock) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetDefaultApplicationsClock.as_ref())?;
+    pub fn default_applications_clock(&self, clock_type: Clock) -> Result<Megahertz, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetDefaultApplicationsClock)?;
 
         unsafe {
             let mut clock: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, clock_type.as_c(), &mut clock))?;
+            self.try_for_args(function, &[clock_type.as_c() as u64], || {
+                sym(self.device, clock_type.as_c(), &mut clock)
+            })?;
 
-            Ok(clock)
+            Ok(Megahertz(clock))
         }
     }
 
@@ -886,17 +1211,23 @@ impl<'nvml> Device<'nvml> {
         error_type: MemoryError,
         counter_type: EccCounter,
     ) -> Result<EccErrorCounts, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetDetailedEccErrors.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetDetailedEccErrors)?;
 
         unsafe {
             let mut counts: nvmlEccErrorCounts_t = mem::zeroed();
 
-            nvml_try(sym(
-                self.device,
-                error_type.as_c(),
-                counter_type.as_c(),
-                &mut counts,
-            ))?;
+            self.try_for_args(
+                function,
+                &[error_type.as_c() as u64, counter_type.as_c() as u64],
+                || {
+                    sym(
+                        self.device,
+                        error_type.as_c(),
+                        counter_type.as_c(),
+                        &mut counts,
+                    )
+                },
+            )?;
 
             Ok(counts.into())
         }
@@ -909,7 +1240,9 @@ impl<'nvml> Device<'nvml> {
     For example, whether or not an X Server is attached to this device and
     has allocated memory for the screen.
 
-    A display can be active even when no monitor is physically attached to this `Device`.
+    A display can be active even when no monitor is physically attached to this
+    `Device`, so this does not tell whether a `Device` is headless; use
+    `.is_display_connected()` for that, or `.display_state()` for both.
 
     # Errors
 
@@ -924,11 +1257,11 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetDisplayActive")]
     pub fn is_display_active(&self) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetDisplayActive.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetDisplayActive)?;
 
         unsafe {
             let mut state: nvmlEnableState_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut state))?;
+            self.try_for(function, || sym(self.device, &mut state))?;
 
             bool_from_state(state)
         }
@@ -938,7 +1271,9 @@ impl<'nvml> Device<'nvml> {
     Gets whether a physical display is currently connected to any of this `Device`'s
     connectors.
 
-    This calls the C function `nvmlDeviceGetDisplayMode`.
+    This calls the C function `nvmlDeviceGetDisplayMode`. Whether a display is
+    initialized on this `Device` is independent of this and reported by
+    `.is_display_active()`; see also `.display_state()`.
 
     # Errors
 
@@ -953,16 +1288,37 @@ impl<'nvml> Device<'nvml> {
     // Tested
     #[doc(alias = "nvmlDeviceGetDisplayMode")]
     pub fn is_display_connected(&self) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetDisplayMode.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetDisplayMode)?;
 
         unsafe {
             let mut state: nvmlEnableState_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut state))?;
+            self.try_for(function, || sym(self.device, &mut state))?;
 
             bool_from_state(state)
         }
     }
 
+    /**
+    Gets whether a monitor is attached to this `Device` and whether a display
+    is initialized on it, combining `.is_display_connected()` and
+    `.is_display_active()`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support one of the queries
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+    */
+    pub fn display_state(&self) -> Result<DisplayState, NvmlError> {
+        Ok(DisplayState::new(
+            self.is_display_connected()?,
+            self.is_display_active()?,
+        ))
+    }
+
     /**
     Gets the current and pending driver model for this `Device`.
 
@@ -992,13 +1348,13 @@ impl<'nvml> Device<'nvml> {
     #[cfg(target_os = "windows")]
     #[doc(alias = "nvmlDeviceGetDriverModel")]
     pub fn driver_model(&self) -> Result<DriverModelState, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetDriverModel.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetDriverModel)?;
 
         unsafe {
             let mut current: nvmlDriverModel_t = mem::zeroed();
             let mut pending: nvmlDriverModel_t = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut current, &mut pending))?;
+            self.try_for(function, || sym(self.device, &mut current, &mut pending))?;
 
             Ok(DriverModelState {
                 current: DriverModel::try_from(current)?,
@@ -1031,13 +1387,13 @@ impl<'nvml> Device<'nvml> {
     // Tested on machines other than my own
     #[doc(alias = "nvmlDeviceGetEccMode")]
     pub fn is_ecc_enabled(&self) -> Result<EccModeState, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetEccMode.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetEccMode)?;
 
         unsafe {
             let mut current: nvmlEnableState_t = mem::zeroed();
             let mut pending: nvmlEnableState_t = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut current, &mut pending))?;
+            self.try_for(function, || sym(self.device, &mut current, &mut pending))?;
 
             Ok(EccModeState {
                 currently_enabled: bool_from_state(current)?,
@@ -1047,899 +1403,952 @@ impl<'nvml> Device<'nvml> {
     }
 
     /**
-    Gets the current utilization and sampling size (sampling size in μs) for the Encoder.
+    Get the current and pending MIG modes for this `Device`.
+
+    Changing MIG modes may require a GPU reset. The "pending" MIG mode refers to
+    the target mode following the next reset.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `NotSupported`, if this `Device` does not support MIG mode
+    * `UnexpectedVariant`, for which you can read the docs for
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler or newer fully supported devices.
+    Supports Ampere and newer fully supported devices.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetEncoderUtilization")]
-    pub fn encoder_utilization(&self) -> Result<UtilizationInfo, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetEncoderUtilization.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetMigMode")]
+    pub fn is_mig_enabled(&self) -> Result<MigModeState, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMigMode)?;
+
+        let mode = |mode: c_uint| match mode {
+            NVML_DEVICE_MIG_DISABLE => Ok(false),
+            NVML_DEVICE_MIG_ENABLE => Ok(true),
+            _ => Err(NvmlError::UnexpectedVariant(mode)),
+        };
 
         unsafe {
-            let mut utilization: c_uint = mem::zeroed();
-            let mut sampling_period: c_uint = mem::zeroed();
+            let mut current: c_uint = mem::zeroed();
+            let mut pending: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut utilization, &mut sampling_period))?;
+            self.try_for(function, || sym(self.device, &mut current, &mut pending))?;
 
-            Ok(UtilizationInfo {
-                utilization,
-                sampling_period,
+            Ok(MigModeState {
+                currently_enabled: mode(current)?,
+                pending_enabled: mode(pending)?,
             })
         }
     }
 
     /**
-    Gets the current capacity of this device's encoder in macroblocks per second.
+    Gets the maximum number of MIG devices that can exist on this `Device`.
+
+    Returns 0 if this `Device` is not MIG-capable.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this device is invalid
-    * `NotSupported`, if this `Device` does not support the given `for_type`
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Maxwell or newer fully supported devices.
+    Supports Ampere and newer fully supported devices.
     */
     // Tested
-    #[doc(alias = "nvmlDeviceGetEncoderCapacity")]
-    pub fn encoder_capacity(&self, for_type: EncoderType) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetEncoderCapacity.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetMaxMigDeviceCount")]
+    pub fn max_mig_device_count(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMaxMigDeviceCount)?;
 
         unsafe {
-            let mut capacity: c_uint = mem::zeroed();
+            let mut count: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, for_type.as_c(), &mut capacity))?;
+            self.try_for(function, || sym(self.device, &mut count))?;
 
-            Ok(capacity)
+            Ok(count)
         }
     }
 
     /**
-    Gets the current encoder stats for this device.
+    Gets the MIG device at the given index on this `Device`.
+
+    Valid indices are less than `.max_mig_device_count()`; not every index
+    has a MIG device, depending on how the GPU has been partitioned.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this device is invalid
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `InvalidArg`, if this `Device` is invalid or `index` is out of range
+    * `NotSupported`, if this `Device` does not support this feature
+    * `NotFound`, if there is no MIG device at `index`
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Maxwell or newer fully supported devices.
+    Supports Ampere and newer fully supported devices.
     */
-    // Tested
-    #[doc(alias = "nvmlDeviceGetEncoderStats")]
-    pub fn encoder_stats(&self) -> Result<EncoderStats, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetEncoderStats.as_ref())?;
+    // Tested (via `mig_devices()`)
+    #[doc(alias = "nvmlDeviceGetMigDeviceHandleByIndex")]
+    pub fn mig_device_by_index(&self, index: u32) -> Result<MigDevice<'nvml>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMigDeviceHandleByIndex)?;
 
         unsafe {
-            let mut session_count: c_uint = mem::zeroed();
-            let mut average_fps: c_uint = mem::zeroed();
-            let mut average_latency: c_uint = mem::zeroed();
+            let mut handle: nvmlDevice_t = mem::zeroed();
 
-            nvml_try(sym(
-                self.device,
-                &mut session_count,
-                &mut average_fps,
-                &mut average_latency,
-            ))?;
+            nvml_try_for(function, sym(self.device, index, &mut handle))?;
 
-            Ok(EncoderStats {
-                session_count,
-                average_fps,
-                average_latency,
-            })
+            Ok(MigDevice::new(Device::new(handle, self.nvml), index))
         }
     }
 
     /**
-    Gets information about active encoder sessions on this device.
+    Gets every MIG device that currently exists on this `Device`, ordered by
+    index.
+
+    Empty if MIG mode is enabled but no GPU or compute instances have been
+    created.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `UnexpectedVariant`, if an enum variant not defined in this wrapper gets
-    returned in a field of an `EncoderSessionInfo` struct
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support MIG
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Maxwell or newer fully supported devices.
+    Supports Ampere and newer fully supported devices.
     */
     // Tested
-    // TODO: Test this with an active session and make sure it works
-    #[doc(alias = "nvmlDeviceGetEncoderSessions")]
-    pub fn encoder_sessions(&self) -> Result<Vec<EncoderSessionInfo>, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetEncoderSessions.as_ref())?;
-
-        unsafe {
-            let mut count = match self.encoder_sessions_count()? {
-                0 => return Ok(vec![]),
-                value => value,
-            };
-            let mut sessions: Vec<nvmlEncoderSessionInfo_t> = vec![mem::zeroed(); count as usize];
-
-            nvml_try(sym(self.device, &mut count, sessions.as_mut_ptr()))?;
-
-            sessions.truncate(count as usize);
-            sessions
-                .into_iter()
-                .map(EncoderSessionInfo::try_from)
-                .collect::<Result<_, NvmlError>>()
+    pub fn mig_devices(&self) -> Result<Vec<MigDevice<'nvml>>, NvmlError> {
+        let mut mig_devices = vec![];
+
+        for index in 0..self.max_mig_device_count()? {
+            match self.mig_device_by_index(index) {
+                Ok(mig_device) => mig_devices.push(mig_device),
+                Err(NvmlError::NotFound) => {}
+                Err(e) => return Err(e),
+            }
         }
+
+        Ok(mig_devices)
     }
 
     /**
-    Gets the number of active encoder sessions on this device.
+    Gets the resources (multiprocessors, engines, memory) of this `Device`.
+
+    NVML only reports these for MIG devices, so this `Device` must be a MIG
+    device handle (see `MigDevice.device()`); see `.engine_counts()` for the
+    engine counts of full GPUs.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `NotSupported`, if this `Device` is not a MIG device
     * `Unknown`, on any unexpected error
-    */
-    // tested as part of the above
-    fn encoder_sessions_count(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetEncoderSessions.as_ref())?;
 
-        unsafe {
-            let mut count: c_uint = 0;
+    # Device Support
 
-            nvml_try(sym(self.device, &mut count, ptr::null_mut()))?;
+    Supports Ampere and newer fully supported devices.
 
-            Ok(count)
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested (via `MigDevice.attributes()`)
+    #[doc(alias = "nvmlDeviceGetAttributes_v2")]
+    pub fn attributes(&self) -> Result<DeviceAttributes, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetAttributes_v2)?;
+
+        unsafe {
+            let mut attributes: nvmlDeviceAttributes_t = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut attributes))?;
+
+            Ok(attributes.into())
         }
     }
 
     /**
-    Gets the effective power limit in milliwatts that the driver enforces after taking
-    into account all limiters.
+    Gets the number of copy engines, video encoders (NVENC), video decoders
+    (NVDEC), JPEG decoders and optical flow accelerators of this `Device`.
 
-    Note: This can be different from the `.power_management_limit()` if other limits
-    are set elswhere. This includes the out-of-band power limit interface.
+    Combined with `.encoder_utilization()` and `.decoder_utilization()`, these
+    let schedulers tell one saturated engine apart from several busy ones.
+
+    For MIG devices, these are the engines available to the MIG device (see
+    `.attributes()`). For full GPUs, NVML only reports engine counts while
+    MIG mode is enabled; they are then taken from the largest GPU instance
+    profile, which spans the whole GPU.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `NotSupported`, if this `Device` is neither a MIG device nor a GPU with
+      MIG mode enabled
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler or newer fully supported devices.
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
     */
-    // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetEnforcedPowerLimit")]
-    pub fn enforced_power_limit(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetEnforcedPowerLimit.as_ref())?;
+    pub fn engine_counts(&self) -> Result<EngineCounts, NvmlError> {
+        match self.attributes() {
+            Ok(attributes) => {
+                return Ok(EngineCounts {
+                    copy_engines: attributes.shared_copy_engine_count,
+                    decoders: attributes.shared_decoder_count,
+                    encoders: attributes.shared_encoder_count,
+                    jpeg_decoders: attributes.shared_jpeg_count,
+                    ofas: attributes.shared_ofa_count,
+                })
+            }
+            // Not a MIG device handle
+            Err(NvmlError::NotSupported(_)) | Err(NvmlError::InvalidArg) => {}
+            Err(e) => return Err(e),
+        }
 
-        unsafe {
-            let mut limit: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut limit))?;
+        let not_supported = || {
+            NvmlError::NotSupported(Some(UnsupportedContext::for_function(
+                "nvmlDeviceGetAttributes_v2",
+            )))
+        };
 
-            Ok(limit)
+        match self.is_mig_enabled().optional()? {
+            Some(mode) if mode.currently_enabled => {}
+            _ => return Err(not_supported()),
         }
-    }
 
-    /**
-    Gets the intended operating speed of the specified fan as a percentage of the
-    maximum fan speed (100%).
+        for profile in [GpuInstanceProfile::Slice8, GpuInstanceProfile::Slice7] {
+            match self.gpu_instance_profile_info(profile) {
+                Ok(info) => {
+                    return Ok(EngineCounts {
+                        copy_engines: info.copy_engine_count,
+                        decoders: info.decoder_count,
+                        encoders: info.encoder_count,
+                        jpeg_decoders: info.jpeg_count,
+                        ofas: info.ofa_count,
+                    })
+                }
+                Err(NvmlError::NotSupported(_)) | Err(NvmlError::InvalidArg) => {}
+                Err(e) => return Err(e),
+            }
+        }
 
-    Note: The reported speed is the intended fan speed. If the fan is physically blocked
-    and unable to spin, the output will not match the actual fan speed.
+        Err(not_supported())
+    }
 
-    You can determine valid fan indices using [`Self::num_fans()`].
+    /**
+    Gets the offset applied to the voltage/frequency curve of this `Device`'s
+    GPU clock (GPCCLK), in MHz.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid or `fan_idx` is invalid
-    * `NotSupported`, if this `Device` does not have a fan or is newer than Maxwell
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports all discrete products with dedicated fans.
+    NVIDIA doesn't document which devices support this.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetFanSpeed_v2")]
-    pub fn fan_speed(&self, fan_idx: u32) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetFanSpeed_v2.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetGpcClkVfOffset")]
+    pub fn gpc_clk_vf_offset(&self) -> Result<i32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetGpcClkVfOffset)?;
 
         unsafe {
-            let mut speed: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, fan_idx, &mut speed))?;
+            let mut offset: c_int = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut offset))?;
 
-            Ok(speed)
+            Ok(offset)
         }
     }
 
     /**
-    Gets the number of fans on this [`Device`].
+    Gets the resources of the given MIG GPU instance profile on this `Device`.
+
+    MIG mode must be enabled.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotSupported`, if this `Device` does not have a fan
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't have MIG mode enabled or
+      doesn't support `profile`
+    * `NoPermission`, if the user doesn't have permission to perform this operation
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports all discrete products with dedicated fans.
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
     */
-    #[doc(alias = "nvmlDeviceGetNumFans")]
-    pub fn num_fans(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetNumFans.as_ref())?;
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetGpuInstanceProfileInfo")]
+    pub fn gpu_instance_profile_info(
+        &self,
+        profile: GpuInstanceProfile,
+    ) -> Result<GpuInstanceProfileInfo, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetGpuInstanceProfileInfo)?;
 
         unsafe {
-            let mut count: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut count))?;
+            let mut info: nvmlGpuInstanceProfileInfo_t = mem::zeroed();
 
-            Ok(count)
+            self.try_for(function, || sym(self.device, profile.as_c(), &mut info))?;
+
+            Ok(info.into())
         }
     }
 
     /**
-    Gets the current GPU operation mode and the pending one (that it will switch to
-    after a reboot).
+    Gets the GPU instances of the given profile that currently exist on this
+    `Device`.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `UnexpectedVariant`, for which you can read the docs for
+    * `InvalidArg`, if this `Device` or `profile` is invalid
+    * `NotSupported`, if this `Device` doesn't have MIG mode enabled
+    * `NoPermission`, if the user doesn't have permission to perform this operation
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports GK110 M-class and X-class Tesla products from the Kepler family. Modes `LowDP`
-    and `AllOn` are supported on fully supported GeForce products. Not supported
-    on Quadro and Tesla C-class products.
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux. Requires root/admin permissions.
     */
     // Checked against local
-    // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetGpuOperationMode")]
-    pub fn gpu_operation_mode(&self) -> Result<OperationModeState, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetGpuOperationMode.as_ref())?;
+    // Tested
+    #[doc(alias = "nvmlDeviceGetGpuInstances")]
+    pub fn gpu_instances(
+        &self,
+        profile: &GpuInstanceProfileInfo,
+    ) -> Result<Vec<GpuInstance<'nvml>>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetGpuInstances)?;
 
         unsafe {
-            let mut current: nvmlGpuOperationMode_t = mem::zeroed();
-            let mut pending: nvmlGpuOperationMode_t = mem::zeroed();
+            let mut count: c_uint = profile.instance_count;
+            let mut instances: Vec<nvmlGpuInstance_t> =
+                vec![mem::zeroed(); profile.instance_count as usize];
 
-            nvml_try(sym(self.device, &mut current, &mut pending))?;
+            self.try_for(function, || {
+                sym(self.device, profile.id, instances.as_mut_ptr(), &mut count)
+            })?;
 
-            Ok(OperationModeState {
-                current: OperationMode::try_from(current)?,
-                pending: OperationMode::try_from(pending)?,
-            })
+            instances.truncate(count as usize);
+
+            Ok(instances
+                .into_iter()
+                .map(|instance| GpuInstance::new(instance, self.nvml))
+                .collect())
         }
     }
 
     /**
-    Gets information about processes with a graphics context running on this `Device`.
-
-    This only returns information about graphics based processes (OpenGL, DirectX, etc.).
+    Gets the GPU instance with the given ID on this `Device`.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `InvalidArg`, if this `Device` or `id` is invalid
+    * `NotSupported`, if this `Device` doesn't have MIG mode enabled
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `NotFound`, if there is no GPU instance with the given ID
     * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux. Requires root/admin permissions.
     */
-    // Tested
-    #[doc(alias = "nvmlDeviceGetGraphicsRunningProcesses_v3")]
-    pub fn running_graphics_processes(&self) -> Result<Vec<ProcessInfo>, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetGraphicsRunningProcesses_v3
-                .as_ref(),
-        )?;
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceGetGpuInstanceById")]
+    pub fn gpu_instance_by_id(&self, id: u32) -> Result<GpuInstance<'nvml>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetGpuInstanceById)?;
 
         unsafe {
-            let mut count: c_uint = match self.running_graphics_processes_count()? {
-                0 => return Ok(vec![]),
-                value => value,
-            };
-            // Add a bit of headroom in case more processes are launched in
-            // between the above call to get the expected count and the time we
-            // actually make the call to get data below.
-            count += 5;
-            let mut processes: Vec<nvmlProcessInfo_t> = vec![mem::zeroed(); count as usize];
+            let mut instance: nvmlGpuInstance_t = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut count, processes.as_mut_ptr()))?;
-            processes.truncate(count as usize);
+            self.try_for(function, || sym(self.device, id, &mut instance))?;
 
-            Ok(processes.into_iter().map(ProcessInfo::from).collect())
+            Ok(GpuInstance::new(instance, self.nvml))
         }
     }
 
     /**
-    Gets the number of processes with a graphics context running on this `Device`.
+    Creates a GPU instance of the given profile on this `Device`, letting the
+    driver pick where it goes.
 
-    This only returns the count of graphics based processes (OpenGL, DirectX).
+    The new GPU instance has no compute instances; create them with
+    `GpuInstance.create_compute_instance()` before running work on it.
+
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `UnexpectedVariant`, for which you can read the docs for
+    * `InvalidArg`, if this `Device` or `profile` is invalid
+    * `NotSupported`, if this `Device` doesn't have MIG mode enabled
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `UnrecognizedCode`, with `NVML_ERROR_INSUFFICIENT_RESOURCES`, if there is no
+      room left for the GPU instance
     * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
     */
-    // Tested as part of `.running_graphics_processes()`
-    #[doc(alias = "nvmlDeviceGetGraphicsRunningProcesses_v3")]
-    pub fn running_graphics_processes_count(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetGraphicsRunningProcesses_v3
-                .as_ref(),
-        )?;
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceCreateGpuInstance")]
+    pub fn create_gpu_instance(
+        &mut self,
+        profile: &GpuInstanceProfileInfo,
+    ) -> Result<GpuInstance<'nvml>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceCreateGpuInstance)?;
 
         unsafe {
-            // Indicates that we want the count
-            let mut count: c_uint = 0;
+            let mut instance: nvmlGpuInstance_t = mem::zeroed();
 
-            // Passing null doesn't indicate that we want the count. It's just allowed.
-            match sym(self.device, &mut count, ptr::null_mut()) {
-                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => Ok(count),
-                // If success, return 0; otherwise, return error
-                other => nvml_try(other).map(|_| 0),
-            }
+            nvml_try_for(function, sym(self.device, profile.id, &mut instance))?;
+
+            Ok(GpuInstance::new(instance, self.nvml))
         }
     }
 
     /**
-    Gets utilization stats for relevant currently running processes.
+    Creates a GPU instance of the given profile on this `Device` at the given
+    placement.
 
-    Utilization stats are returned for processes that had a non-zero utilization stat
-    at some point during the target sample period. Passing `None` as the
-    `last_seen_timestamp` will target all samples that the driver has buffered; passing
-    a timestamp retrieved from a previous query will target samples taken since that
-    timestamp.
+    Like `.create_gpu_instance()`, except the caller picks where the GPU
+    instance goes; useful for recreating a layout exactly.
+
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `InvalidArg`, if this `Device`, `profile` or `placement` is invalid
+    * `NotSupported`, if this `Device` doesn't have MIG mode enabled
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `UnrecognizedCode`, with `NVML_ERROR_INSUFFICIENT_RESOURCES`, if the
+      placement is already taken
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Maxwell or newer fully supported devices.
-    */
-    #[doc(alias = "nvmlDeviceGetProcessUtilization")]
-    pub fn process_utilization_stats<T>(
-        &self,
-        last_seen_timestamp: T,
-    ) -> Result<Vec<ProcessUtilizationSample>, NvmlError>
-    where
-        T: Into<Option<u64>>,
-    {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetProcessUtilization.as_ref())?;
+    Supports Ampere and newer fully supported devices.
 
-        unsafe {
-            let last_seen_timestamp = last_seen_timestamp.into().unwrap_or(0);
-            let mut count = match self.process_utilization_stats_count()? {
-                0 => return Ok(vec![]),
-                v => v,
-            };
-            let mut utilization_samples: Vec<nvmlProcessUtilizationSample_t> =
-                vec![mem::zeroed(); count as usize];
-
-            nvml_try(sym(
-                self.device,
-                utilization_samples.as_mut_ptr(),
-                &mut count,
-                last_seen_timestamp,
-            ))?;
-            utilization_samples.truncate(count as usize);
-
-            Ok(utilization_samples
-                .into_iter()
-                .map(ProcessUtilizationSample::from)
-                .collect())
-        }
-    }
+    # Platform Support
 
-    fn process_utilization_stats_count(&self) -> Result<c_uint, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetProcessUtilization.as_ref())?;
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceCreateGpuInstanceWithPlacement")]
+    pub fn create_gpu_instance_with_placement(
+        &mut self,
+        profile: &GpuInstanceProfileInfo,
+        placement: &InstancePlacement,
+    ) -> Result<GpuInstance<'nvml>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceCreateGpuInstanceWithPlacement)?;
 
         unsafe {
-            let mut count: c_uint = 0;
+            let mut instance: nvmlGpuInstance_t = mem::zeroed();
+            let placement: nvmlGpuInstancePlacement_t = (*placement).into();
 
-            match sym(self.device, ptr::null_mut(), &mut count, 0) {
-                // Despite being undocumented, this appears to be the correct behavior
-                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => Ok(count),
-                other => nvml_try(other).map(|_| 0),
-            }
+            nvml_try_for(
+                function,
+                sym(self.device, profile.id, &placement, &mut instance),
+            )?;
+
+            Ok(GpuInstance::new(instance, self.nvml))
         }
     }
 
     /**
-    Gets the NVML index of this `Device`.
-
-    Keep in mind that the order in which NVML enumerates devices has no guarantees of
-    consistency between reboots. Also, the NVML index may not correlate with other APIs,
-    such as the CUDA device index.
+    Gets the current utilization and sampling size (sampling size in μs) for the Encoder.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler or newer fully supported devices.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetIndex")]
-    pub fn index(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetIndex.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetEncoderUtilization")]
+    pub fn encoder_utilization(&self) -> Result<UtilizationInfo, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetEncoderUtilization)?;
 
         unsafe {
-            let mut index: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut index))?;
+            let mut utilization: c_uint = mem::zeroed();
+            let mut sampling_period: c_uint = mem::zeroed();
 
-            Ok(index)
+            self.try_for(function, || {
+                sym(self.device, &mut utilization, &mut sampling_period)
+            })?;
+
+            Ok(UtilizationInfo {
+                utilization,
+                sampling_period,
+            })
         }
     }
 
     /**
-    Gets the checksum of the config stored in this `Device`'s infoROM.
+    Gets the current capacity of this device's encoder for the given codec, as a
+    percentage (0-100) of its maximum capacity.
 
-    Can be used to make sure that two GPUs have the exact same configuration.
-    The current checksum takes into account configuration stored in PWR and ECC
-    infoROM objects. The checksum can change between driver released or when the
-    user changes the configuration (e.g. disabling/enabling ECC).
+    This is the encoder headroom that's still available for new sessions of
+    that codec, which is a better basis for placing sessions than the number of
+    sessions that are already running.
 
     # Errors
 
-    * `CorruptedInfoROM`, if this `Device`'s checksum couldn't be retrieved due to infoROM corruption
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotSupported`, if this `Device` does not support this feature
+    * `InvalidArg`, if this device is invalid
+    * `NotSupported`, if this `Device` does not support the given `for_type`
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports all devices with an infoROM.
+    Supports Maxwell or newer fully supported devices. `EncoderType::AV1`
+    requires Ada or newer.
     */
-    // Checked against local
-    // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetInforomConfigurationChecksum")]
-    pub fn config_checksum(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetInforomConfigurationChecksum
-                .as_ref(),
-        )?;
+    // Tested
+    #[doc(alias = "nvmlDeviceGetEncoderCapacity")]
+    pub fn encoder_capacity(&self, for_type: EncoderType) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetEncoderCapacity)?;
 
         unsafe {
-            let mut checksum: c_uint = mem::zeroed();
+            let mut capacity: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut checksum))?;
+            self.try_for_args(function, &[for_type.as_c() as u64], || {
+                sym(self.device, for_type.as_c(), &mut capacity)
+            })?;
 
-            Ok(checksum)
+            Ok(capacity)
         }
     }
 
     /**
-    Gets the global infoROM image version.
-
-    This image version, just like the VBIOS version, uniquely describes the exact version
-    of the infoROM flashed on the board, in contrast to the infoROM object version which
-    is only an indicator of supported features.
+    Gets the current encoder stats for this device.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not have an infoROM
+    * `InvalidArg`, if this device is invalid
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports all devices with an infoROM.
+    Supports Maxwell or newer fully supported devices.
     */
-    // Checked against local
-    // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetInforomImageVersion")]
-    pub fn info_rom_image_version(&self) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetInforomImageVersion.as_ref())?;
+    // Tested
+    #[doc(alias = "nvmlDeviceGetEncoderStats")]
+    pub fn encoder_stats(&self) -> Result<EncoderStats, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetEncoderStats)?;
 
         unsafe {
-            let mut version_vec = vec![0; NVML_DEVICE_INFOROM_VERSION_BUFFER_SIZE as usize];
+            let mut session_count: c_uint = mem::zeroed();
+            let mut average_fps: c_uint = mem::zeroed();
+            let mut average_latency: c_uint = mem::zeroed();
 
-            nvml_try(sym(
-                self.device,
-                version_vec.as_mut_ptr(),
-                NVML_DEVICE_INFOROM_VERSION_BUFFER_SIZE,
-            ))?;
+            self.try_for(function, || {
+                sym(
+                    self.device,
+                    &mut session_count,
+                    &mut average_fps,
+                    &mut average_latency,
+                )
+            })?;
 
-            let version_raw = CStr::from_ptr(version_vec.as_ptr());
-            Ok(version_raw.to_str()?.into())
+            Ok(EncoderStats {
+                session_count,
+                average_fps,
+                average_latency,
+            })
         }
     }
 
     /**
-    Gets the version information for this `Device`'s infoROM object, for the passed in
-    object type.
+    Gets information about active encoder sessions on this device.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not have an infoROM
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `Utf8Error`, if the string obtained from the C function is not valid UTF-8
+    * `UnexpectedVariant`, if an enum variant not defined in this wrapper gets
+    returned in a field of an `EncoderSessionInfo` struct
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports all devices with an infoROM.
-
-    Fermi and higher parts have non-volatile on-board memory for persisting device info,
-    such as aggregate ECC counts. The version of the data structures in this memory may
-    change from time to time.
+    Supports Maxwell or newer fully supported devices.
     */
-    // Checked against local
-    // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetInforomVersion")]
-    pub fn info_rom_version(&self, object: InfoRom) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetInforomVersion.as_ref())?;
+    // Tested
+    // TODO: Test this with an active session and make sure it works
+    #[doc(alias = "nvmlDeviceGetEncoderSessions")]
+    pub fn encoder_sessions(&self) -> Result<Vec<EncoderSessionInfo>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetEncoderSessions)?;
 
         unsafe {
-            let mut version_vec = vec![0; NVML_DEVICE_INFOROM_VERSION_BUFFER_SIZE as usize];
+            let mut count = match self.encoder_sessions_count()? {
+                0 => return Ok(vec![]),
+                value => value,
+            };
+            let mut sessions: Vec<nvmlEncoderSessionInfo_t> = vec![mem::zeroed(); count as usize];
 
-            nvml_try(sym(
-                self.device,
-                object.as_c(),
-                version_vec.as_mut_ptr(),
-                NVML_DEVICE_INFOROM_VERSION_BUFFER_SIZE,
-            ))?;
+            self.try_for(function, || {
+                sym(self.device, &mut count, sessions.as_mut_ptr())
+            })
+            .map_err(|e| e.with_required_size(count))?;
 
-            let version_raw = CStr::from_ptr(version_vec.as_ptr());
-            Ok(version_raw.to_str()?.into())
+            sessions.truncate(count as usize);
+            sessions
+                .into_iter()
+                .map(EncoderSessionInfo::try_from)
+                .collect::<Result<_, NvmlError>>()
         }
     }
 
     /**
-    Gets the maximum clock speeds for this `Device`.
+    Gets the number of active encoder sessions on this device.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` cannot report the specified `Clock`
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
-
-    # Device Support
-
-    Supports Fermi and newer fully supported devices.
-
-    Note: On GPUs from the Fermi family, current P0 (Performance state 0?) clocks
-    (reported by `.clock_info()`) can differ from max clocks by a few MHz.
     */
-    // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetMaxClockInfo")]
-    pub fn max_clock_info(&self, clock_type: Clock) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetMaxClockInfo.as_ref())?;
+    // tested as part of the above
+    fn encoder_sessions_count(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetEncoderSessions)?;
 
         unsafe {
-            let mut clock: c_uint = mem::zeroed();
+            let mut count: c_uint = 0;
 
-            nvml_try(sym(self.device, clock_type.as_c(), &mut clock))?;
+            self.try_for(function, || sym(self.device, &mut count, ptr::null_mut()))?;
 
-            Ok(clock)
+            Ok(count)
         }
     }
 
     /**
-    Gets the max PCIe link generation possible with this `Device` and system.
+    Gets the effective power limit in milliwatts that the driver enforces after taking
+    into account all limiters.
 
-    For a gen 2 PCIe device attached to a gen 1 PCIe bus, the max link generation
-    this function will report is generation 1.
+    Note: This can be different from the `.power_management_limit()` if other limits
+    are set elswhere. This includes the out-of-band power limit interface.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if PCIe link information is not available
+    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Fermi and newer fully supported devices.
+    Supports Kepler or newer fully supported devices.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetMaxPcieLinkGeneration")]
-    pub fn max_pcie_link_gen(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetMaxPcieLinkGeneration.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetEnforcedPowerLimit")]
+    pub fn enforced_power_limit(&self) -> Result<Milliwatts, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetEnforcedPowerLimit)?;
 
         unsafe {
-            let mut max_gen: c_uint = mem::zeroed();
-
-            nvml_try(sym(self.device, &mut max_gen))?;
+            let mut limit: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut limit))?;
 
-            Ok(max_gen)
+            Ok(Milliwatts(limit))
         }
     }
 
     /**
-    Gets the maximum PCIe link width possible with this `Device` and system.
+    Gets the intended operating speed of the specified fan as a percentage of the
+    maximum fan speed (100%).
 
-    For a device with a 16x PCie bus width attached to an 8x PCIe system bus,
-    this method will report a max link width of 8.
+    Note: The reported speed is the intended fan speed. If the fan is physically blocked
+    and unable to spin, the output will not match the actual fan speed.
+
+    You can determine valid fan indices using [`Self::num_fans()`].
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if PCIe link information is not available
+    * `InvalidArg`, if this `Device` is invalid or `fan_idx` is invalid
+    * `NotSupported`, if this `Device` does not have a fan or is newer than Maxwell
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Fermi and newer fully supported devices.
+    Supports all discrete products with dedicated fans.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetMaxPcieLinkWidth")]
-    pub fn max_pcie_link_width(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetMaxPcieLinkWidth.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetFanSpeed_v2")]
+    pub fn fan_speed(&self, fan_idx: u32) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetFanSpeed_v2)?;
 
         unsafe {
-            let mut max_width: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut max_width))?;
+            let mut speed: c_uint = mem::zeroed();
+            self.try_for_args(function, &[fan_idx as u64], || {
+                sym(self.device, fan_idx, &mut speed)
+            })?;
 
-            Ok(max_width)
+            Ok(speed)
         }
     }
 
     /**
-    Gets the requested memory error counter for this `Device`.
+    Gets the intended operating speed of the specified fan in rotations per
+    minute (RPM).
 
-    Only applicable to devices with ECC. Requires ECC mode to be enabled.
+    Note: The reported speed is the intended fan speed. If the fan is physically blocked
+    and unable to spin, the output will not match the actual fan speed.
+
+    You can determine valid fan indices using [`Self::num_fans()`].
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if `error_type`, `counter_type`, or `location` is invalid (shouldn't occur?)
-    * `NotSupported`, if this `Device` does not support ECC error reporting for the specified
-    memory
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `InvalidArg`, if this `Device` is invalid or `fan_idx` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Fermi and newer fully supported devices. Requires `InfoRom::ECC` version
-    2.0 or higher to report aggregate location-based memory error counts. Requires
-    `InfoRom::ECC version 1.0 or higher to report all other memory error counts.
+    Supports Maxwell and newer fully supported devices with dedicated fans.
     */
     // Checked against local
-    // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetMemoryErrorCounter")]
-    pub fn memory_error_counter(
-        &self,
-        error_type: MemoryError,
-        counter_type: EccCounter,
-        location: MemoryLocation,
-    ) -> Result<u64, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetMemoryErrorCounter.as_ref())?;
+    // Tested
+    #[doc(alias = "nvmlDeviceGetFanSpeedRPM")]
+    pub fn fan_speed_rpm(&self, fan_idx: u32) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetFanSpeedRPM)?;
 
         unsafe {
-            let mut count: c_ulonglong = mem::zeroed();
+            let mut info = nvmlFanSpeedInfo_t {
+                version: v1_struct_version::<nvmlFanSpeedInfo_t>(),
+                fan: fan_idx,
+                speed: 0,
+            };
 
-            nvml_try(sym(
-                self.device,
-                error_type.as_c(),
-                counter_type.as_c(),
-                location.as_c(),
-                &mut count,
-            ))?;
+            self.try_for_args(function, &[fan_idx as u64], || sym(self.device, &mut info))?;
 
-            Ok(count)
+            Ok(info.speed)
         }
     }
 
     /**
-    Gets the amount of used, free and total memory available on this `Device`, in bytes.
-
-    Note that enabling ECC reduces the amount of total available memory due to the
-    extra required parity bits.
+    Gets the intended operating speed of each fan on this `Device` as a
+    percentage of the maximum fan speed (100%), indexed by fan.
 
-    Also note that on Windows, most device memory is allocated and managed on startup
-    by Windows.
-
-    Under Linux and Windows TCC (no physical display connected), the reported amount
-    of used memory is equal to the sum of memory allocated by all active channels on
-    this `Device`.
+    This is `.fan_speed()` for every fan reported by `.num_fans()`, so boards
+    with several fans report each one separately.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not have a fan or is newer than Maxwell
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
-    */
-    // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetMemoryInfo")]
-    pub fn memory_info(&self) -> Result<MemoryInfo, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetMemoryInfo.as_ref())?;
 
-        unsafe {
-            let mut info: nvmlMemory_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut info))?;
+    # Device Support
 
-            Ok(info.into())
-        }
+    Supports all discrete products with dedicated fans.
+    */
+    // Tested
+    pub fn fan_speeds(&self) -> Result<Vec<u32>, NvmlError> {
+        (0..self.num_fans()?)
+            .map(|fan| self.fan_speed(fan))
+            .collect()
     }
 
     /**
-    Gets the minor number for this `Device`.
-
-    The minor number is such that the NVIDIA device node file for each GPU will
-    have the form `/dev/nvidia[minor number]`.
+    Gets the number of fans on this [`Device`].
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this query is not supported by this `Device`
+    * `NotSupported`, if this `Device` does not have a fan
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
-    # Platform Support
+    # Device Support
 
-    Only supports Linux.
+    Supports all discrete products with dedicated fans.
     */
-    // Checked against local
-    // Tested
-    #[cfg(target_os = "linux")]
-    #[doc(alias = "nvmlDeviceGetMinorNumber")]
-    pub fn minor_number(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetMinorNumber.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetNumFans")]
+    pub fn num_fans(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetNumFans)?;
 
         unsafe {
-            let mut number: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut number))?;
+            let mut count: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut count))?;
 
-            Ok(number)
+            Ok(count)
         }
     }
 
     /**
-    Identifies whether or not this `Device` is on a multi-GPU board.
+    Gets the speed the specified fan is being driven towards as a percentage of
+    the maximum fan speed, which may differ from `.fan_speed()` while the fan
+    spins up or down.
+
+    The value may exceed 100% in certain cases.
+
+    You can determine valid fan indices using [`Self::num_fans()`].
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
+    * `InvalidArg`, if this `Device` is invalid or `fan_idx` is invalid
+    * `NotSupported`, if this `Device` does not have a fan
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Fermi or newer fully supported devices.
+    Supports all discrete products with dedicated fans.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetMultiGpuBoard")]
-    pub fn is_multi_gpu_board(&self) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetMultiGpuBoard.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetTargetFanSpeed")]
+    pub fn target_fan_speed(&self, fan_idx: u32) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetTargetFanSpeed)?;
 
         unsafe {
-            let mut int_bool: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut int_bool))?;
+            let mut speed: c_uint = mem::zeroed();
+            self.try_for_args(function, &[fan_idx as u64], || {
+                sym(self.device, fan_idx, &mut speed)
+            })?;
 
-            match int_bool {
-                0 => Ok(false),
-                _ => Ok(true),
-            }
+            Ok(speed)
         }
     }
 
     /**
-    The name of this `Device`, e.g. "Tesla C2070".
-
-    The name is an alphanumeric string that denotes a particular product.
+    Gets the minimum and maximum fan speeds that can be set with
+    `.set_fan_speed()`, as percentages of the maximum fan speed.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
+    * `NotSupported`, if this `Device` does not have a fan
     * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports all CUDA-capable discrete products with fans.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetName")]
-    pub fn name(&self) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetName.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetMinMaxFanSpeed")]
+    pub fn fan_speed_constraints(&self) -> Result<FanSpeedConstraints, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMinMaxFanSpeed)?;
 
         unsafe {
-            let mut name_vec = vec![0; NVML_DEVICE_NAME_V2_BUFFER_SIZE as usize];
+            let mut min_speed: c_uint = mem::zeroed();
+            let mut max_speed: c_uint = mem::zeroed();
 
-            nvml_try(sym(
-                self.device,
-                name_vec.as_mut_ptr(),
-                NVML_DEVICE_NAME_V2_BUFFER_SIZE,
-            ))?;
+            self.try_for(function, || {
+                sym(self.device, &mut min_speed, &mut max_speed)
+            })?;
 
-            let name_raw = CStr::from_ptr(name_vec.as_ptr());
-            Ok(name_raw.to_str()?.into())
+            Ok(FanSpeedConstraints {
+                min_speed,
+                max_speed,
+            })
         }
     }
 
     /**
-    Gets the PCI attributes of this `Device`.
+    Gets the control policy of the specified fan, i.e. whether the driver
+    (`TemperatureContinuousSw`) or the user (`Manual`) currently sets its speed.
 
-    See `PciInfo` for details about the returned attributes.
+    You can determine valid fan indices using [`Self::num_fans()`].
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `GpuLost`, if the GPU has fallen off the bus or is otherwise inaccessible
-    * `Utf8Error`, if a string obtained from the C function is not valid Utf8
+    * `InvalidArg`, if this `Device` is invalid or `fan_idx` is invalid
+    * `NotSupported`, if this `Device` does not have a fan or is older than
+      Maxwell
+    * `UnexpectedVariant`, for which you can read the docs for
     * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Maxwell or newer fully supported devices with fans.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetPciInfo_v3")]
-    pub fn pci_info(&self) -> Result<PciInfo, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPciInfo_v3.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetFanControlPolicy_v2")]
+    pub fn fan_control_policy(&self, fan_idx: u32) -> Result<FanControlPolicy, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetFanControlPolicy_v2)?;
 
         unsafe {
-            let mut pci_info: nvmlPciInfo_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut pci_info))?;
+            let mut policy: nvmlFanControlPolicy_t = mem::zeroed();
+            self.try_for_args(function, &[fan_idx as u64], || {
+                sym(self.device, fan_idx, &mut policy)
+            })?;
 
-            PciInfo::try_from(pci_info, true)
+            FanControlPolicy::try_from(policy)
         }
     }
 
     /**
-    Gets the PCIe replay counter.
+    Gets the current GPU operation mode and the pending one (that it will switch to
+    after a reboot).
 
     # Errors
 
@@ -1947,131 +2356,201 @@ impl<'nvml> Device<'nvml> {
     * `InvalidArg`, if this `Device` is invalid
     * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler or newer fully supported devices.
+    Supports GK110 M-class and X-class Tesla products from the Kepler family. Modes `LowDP`
+    and `AllOn` are supported on fully supported GeForce products. Not supported
+    on Quadro and Tesla C-class products.
     */
     // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetPcieReplayCounter")]
-    pub fn pcie_replay_counter(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPcieReplayCounter.as_ref())?;
+    // Tested on machines other than my own
+    #[doc(alias = "nvmlDeviceGetGpuOperationMode")]
+    pub fn gpu_operation_mode(&self) -> Result<OperationModeState, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetGpuOperationMode)?;
 
         unsafe {
-            let mut value: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut value))?;
+            let mut current: nvmlGpuOperationMode_t = mem::zeroed();
+            let mut pending: nvmlGpuOperationMode_t = mem::zeroed();
 
-            Ok(value)
-        }
+            self.try_for(function, || sym(self.device, &mut current, &mut pending))?;
+
+            Ok(OperationModeState {
+                current: OperationMode::try_from(current)?,
+                pending: OperationMode::try_from(pending)?,
+            })
+        }
     }
 
     /**
-    Gets PCIe utilization information in KB/s.
+    Gets the settings of this `Device` whose pending values differ from their
+    current ones, i.e. the ones that will change after the next reboot or GPU
+    reset.
 
-    The function called within this method is querying a byte counter over a 20ms
-    interval and thus is the PCIE throughput over that interval.
+    Covers the ECC mode, MIG mode, GPU operation mode and, on Windows, the
+    driver model. Settings that this `Device` doesn't support are left out
+    rather than causing an error. Use `PendingChanges.requires_reboot()` to
+    decide whether a reboot is needed.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid or `counter` is invalid (shouldn't occur?)
-    * `NotSupported`, if this `Device` does not support this feature
+    * `InvalidArg`, if this `Device` is invalid
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
     * `Unknown`, on any unexpected error
+    */
+    pub fn pending_changes(&self) -> Result<PendingChanges, NvmlError> {
+        Ok(PendingChanges {
+            ecc: self
+                .is_ecc_enabled()
+                .optional()?
+                .filter(|s| s.currently_enabled != s.pending_enabled),
+            mig: self
+                .is_mig_enabled()
+                .optional()?
+                .filter(|s| s.currently_enabled != s.pending_enabled),
+            operation_mode: self
+                .gpu_operation_mode()
+                .optional()?
+                .filter(|s| s.current != s.pending),
+            #[cfg(target_os = "windows")]
+            driver_model: self
+                .driver_model()
+                .optional()?
+                .filter(|s| s.current != s.pending),
+        })
+    }
 
-    # Device Support
+    /**
+    Gets information about processes with a graphics context running on this `Device`.
 
-    Supports Maxwell and newer fully supported devices.
+    This only returns information about graphics based processes (OpenGL, DirectX, etc.).
 
-    # Environment Support
+    On legacy drivers that predate MIG support, the returned `ProcessInfo`s
+    never contain instance IDs (see the `legacy` module).
 
-    This method is not supported on virtual machines running vGPUs.
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
     */
-    // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetPcieThroughput")]
-    pub fn pcie_throughput(&self, counter: PcieUtilCounter) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPcieThroughput.as_ref())?;
-
-        unsafe {
-            let mut throughput: c_uint = mem::zeroed();
-
-            nvml_try(sym(self.device, counter.as_c(), &mut throughput))?;
+    #[doc(alias = "nvmlDeviceGetGraphicsRunningProcesses_v3")]
+    pub fn running_graphics_processes(&self) -> Result<Vec<ProcessInfo>, NvmlError> {
+        let mut processes = vec![];
+        self.running_graphics_processes_into(&mut processes)?;
 
-            Ok(throughput)
-        }
+        Ok(processes)
     }
 
     /**
-    Gets the current performance state for this `Device`. 0 == max, 15 == min.
+    Like `.running_graphics_processes()`, but writes the processes into the given
+    `Vec` (clearing it first) instead of allocating a new one.
+
+    No heap allocations are made if `processes` has enough capacity and there
+    are no more than a few dozen processes, making this suitable for polling
+    in a loop.
 
     # Errors
 
-    * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `UnexpectedVariant`, for which you can read the docs for
-    * `Unknown`, on any unexpected error
+    See `.running_graphics_processes()`.
+    */
+    #[doc(alias = "nvmlDeviceGetGraphicsRunningProcesses_v3")]
+    pub fn running_graphics_processes_into(
+        &self,
+        processes: &mut Vec<ProcessInfo>,
+    ) -> Result<(), NvmlError> {
+        processes.clear();
 
-    # Device Support
+        if let Some((sym, function)) = self.nvml.legacy.graphics_running_processes {
+            processes.extend(unsafe { legacy::running_processes(function, sym, self.device)? });
+            self.resolve_unavailable_memory(processes);
+            return Ok(());
+        }
 
-    Supports Fermi or newer fully supported devices.
-    */
-    // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetPerformanceState")]
-    pub fn performance_state(&self) -> Result<PerformanceState, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPerformanceState.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetGraphicsRunningProcesses_v3)?;
 
         unsafe {
-            let mut state: nvmlPstates_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut state))?;
+            let mut count: c_uint = match self.running_graphics_processes_count()? {
+                0 => return Ok(()),
+                value => value,
+            };
+            // Add a bit of headroom in case more processes are launched in
+            // between the above call to get the expected count and the time we
+            // actually make the call to get data below.
+            count += 5;
 
-            PerformanceState::try_from(state)
+            with_buffer(count as usize, |buffer: &mut [nvmlProcessInfo_t]| {
+                self.try_for(function, || {
+                    self.nvml.legacy.call(function, sym, |sym| {
+                        sym(self.device, &mut count, buffer.as_mut_ptr())
+                    })
+                })
+                .map_err(|e| e.with_required_size(count))?;
+
+                processes.extend(
+                    buffer[..count as usize]
+                        .iter()
+                        .map(|p| ProcessInfo::from(*p)),
+                );
+                self.resolve_unavailable_memory(processes);
+                Ok(())
+            })
         }
     }
 
     /**
-    Gets whether or not persistent mode is enabled for this `Device`.
+    Gets the number of processes with a graphics context running on this `Device`.
 
-    When driver persistence mode is enabled the driver software is not torn down
-    when the last client disconnects. This feature is disabled by default.
+    This only returns the count of graphics based processes (OpenGL, DirectX).
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `UnexpectedVariant`, for which you can read the docs for
     * `Unknown`, on any unexpected error
-
-    # Platform Support
-
-    Only supports Linux.
     */
-    // Checked against local
-    // Tested
-    #[cfg(target_os = "linux")]
-    #[doc(alias = "nvmlDeviceGetPersistenceMode")]
-    pub fn is_in_persistent_mode(&self) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPersistenceMode.as_ref())?;
+    // Tested as part of `.running_graphics_processes()`
+    #[doc(alias = "nvmlDeviceGetGraphicsRunningProcesses_v3")]
+    pub fn running_graphics_processes_count(&self) -> Result<u32, NvmlError> {
+        if let Some((sym, function)) = self.nvml.legacy.graphics_running_processes {
+            return unsafe { legacy::running_processes_count(function, sym, self.device) };
+        }
+
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetGraphicsRunningProcesses_v3)?;
 
         unsafe {
-            let mut state: nvmlEnableState_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut state))?;
+            // Indicates that we want the count
+            let mut count: c_uint = 0;
 
-            bool_from_state(state)
+            // Passing null doesn't indicate that we want the count. It's just allowed.
+            let result = self.nvml.legacy.call(function, sym, |sym| {
+                sym(self.device, &mut count, ptr::null_mut())
+            });
+
+            match result {
+                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => Ok(count),
+                // If success, return 0; otherwise, return error
+                other => self.try_for(function, || other).map(|_| 0),
+            }
         }
     }
 
     /**
-    Gets the default power management limit for this `Device`, in milliwatts.
+    Gets utilization stats for relevant currently running processes.
 
-    This is the limit that this `Device` boots with.
+    Utilization stats are returned for processes that had a non-zero utilization stat
+    at some point during the target sample period. Passing `None` as the
+    `last_seen_timestamp` will target all samples that the driver has buffered; passing
+    a timestamp retrieved from a previous query will target samples taken since that
+    timestamp.
 
     # Errors
 
@@ -2083,65 +2562,98 @@ impl<'nvml> Device<'nvml> {
 
     # Device Support
 
-    Supports Kepler or newer fully supported devices.
+    Supports Maxwell or newer fully supported devices.
     */
-    // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetPowerManagementDefaultLimit")]
-    pub fn power_management_limit_default(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetPowerManagementDefaultLimit
-                .as_ref(),
-        )?;
-
-        unsafe {
-            let mut limit: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut limit))?;
+    #[doc(alias = "nvmlDeviceGetProcessUtilization")]
+    pub fn process_utilization_stats<T>(
+        &self,
+        last_seen_timestamp: T,
+    ) -> Result<Vec<ProcessUtilizationSample>, NvmlError>
+    where
+        T: Into<Option<u64>>,
+    {
+        let mut samples = vec![];
+        self.process_utilization_stats_into(last_seen_timestamp, &mut samples)?;
 
-            Ok(limit)
-        }
+        Ok(samples)
     }
 
     /**
-    Gets the power management limit associated with this `Device`.
+    Like `.process_utilization_stats()`, but writes the samples into the given
+    `Vec` (clearing it first) instead of allocating a new one.
 
-    The power limit defines the upper boundary for the card's power draw. If the card's
-    total power draw reaches this limit, the power management algorithm kicks in.
+    No heap allocations are made if `samples` has enough capacity and there
+    are no more than a few dozen samples, making this suitable for polling in
+    a loop.
 
     # Errors
 
-    * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `Unknown`, on any unexpected error
+    See `.process_utilization_stats()`.
+    */
+    #[doc(alias = "nvmlDeviceGetProcessUtilization")]
+    pub fn process_utilization_stats_into<T>(
+        &self,
+        last_seen_timestamp: T,
+        samples: &mut Vec<ProcessUtilizationSample>,
+    ) -> Result<(), NvmlError>
+    where
+        T: Into<Option<u64>>,
+    {
+        samples.clear();
 
-    # Device Support
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetProcessUtilization)?;
 
-    Supports Fermi or newer fully supported devices.
+        unsafe {
+            let last_seen_timestamp = last_seen_timestamp.into().unwrap_or(0);
+            let mut count = match self.process_utilization_stats_count()? {
+                0 => return Ok(()),
+                v => v,
+            };
 
-    This reading is only supported if power management mode is supported. See
-    `.is_power_management_algo_active()`. Yes, it's deprecated, but that's what
-    NVIDIA's docs said to see.
-    */
-    // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetPowerManagementLimit")]
-    pub fn power_management_limit(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPowerManagementLimit.as_ref())?;
+            with_buffer(
+                count as usize,
+                |buffer: &mut [nvmlProcessUtilizationSample_t]| {
+                    self.try_for(function, || {
+                        sym(
+                            self.device,
+                            buffer.as_mut_ptr(),
+                            &mut count,
+                            last_seen_timestamp,
+                        )
+                    })
+                    .map_err(|e| e.with_required_size(count))?;
+
+                    samples.extend(
+                        buffer[..count as usize]
+                            .iter()
+                            .map(|s| ProcessUtilizationSample::from(*s)),
+                    );
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn process_utilization_stats_count(&self) -> Result<c_uint, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetProcessUtilization)?;
 
         unsafe {
-            let mut limit: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut limit))?;
+            let mut count: c_uint = 0;
 
-            Ok(limit)
+            match sym(self.device, ptr::null_mut(), &mut count, 0) {
+                // Despite being undocumented, this appears to be the correct behavior
+                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => Ok(count),
+                other => self.try_for(function, || other).map(|_| 0),
+            }
         }
     }
 
     /**
-    Gets information about possible power management limit values for this `Device`, in milliwatts.
+    Samples process utilization for `window`, polling once per `interval`, and
+    returns the average utilization of every process seen, ordered by PID.
+
+    Blocks for the whole window. See the `high_level::process_utilization`
+    module for details, including how to poll without blocking.
 
     # Errors
 
@@ -2153,806 +2665,651 @@ impl<'nvml> Device<'nvml> {
 
     # Device Support
 
-    Supports Kepler or newer fully supported devices.
+    Supports Maxwell or newer fully supported devices.
     */
-    // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetPowerManagementLimitConstraints")]
-    pub fn power_management_limit_constraints(
+    pub fn average_process_utilization(
         &self,
-    ) -> Result<PowerManagementConstraints, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetPowerManagementLimitConstraints
-                .as_ref(),
-        )?;
-
-        unsafe {
-            let mut min_limit: c_uint = mem::zeroed();
-            let mut max_limit: c_uint = mem::zeroed();
+        window: Duration,
+        interval: Duration,
+    ) -> Result<Vec<ProcessUtilization>, NvmlError> {
+        process_utilization::average_over(self, window, interval)
+    }
 
-            nvml_try(sym(self.device, &mut min_limit, &mut max_limit))?;
+    /**
+    Gets the NVML index of this `Device`.
 
-            Ok(PowerManagementConstraints {
-                min_limit,
-                max_limit,
-            })
-        }
-    }
+    Keep in mind that the order in which NVML enumerates devices has no guarantees of
+    consistency between reboots. Also, the NVML index may not correlate with other APIs,
+    such as the CUDA device index.
 
-    /// Not documenting this because it's deprecated. Read NVIDIA's docs if you
-    /// must use it.
-    // Tested
-    #[deprecated(note = "NVIDIA states that \"this API has been deprecated.\"")]
-    #[doc(alias = "nvmlDeviceGetPowerManagementMode")]
-    pub fn is_power_management_algo_active(&self) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPowerManagementMode.as_ref())?;
+    `Device`s obtained through `Nvml.device_by_index()` cache their index, making
+    this free for them (e.g. to label log messages); it is fetched from NVML
+    otherwise. Since removing a GPU (see `.remove()`) may renumber the remaining
+    ones, obtain fresh `Device`s after doing so; `high_level::DeviceRegistry` does
+    this for you.
 
-        unsafe {
-            let mut state: nvmlEnableState_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut state))?;
+    # Errors
 
-            bool_from_state(state)
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetIndex")]
+    pub fn index(&self) -> Result<u32, NvmlError> {
+        if let Some(index) = self.index {
+            return Ok(index);
         }
-    }
 
-    /// Not documenting this because it's deprecated. Read NVIDIA's docs if you
-    /// must use it.
-    // Tested
-    #[deprecated(note = "use `.performance_state()`.")]
-    #[doc(alias = "nvmlDeviceGetPowerState")]
-    pub fn power_state(&self) -> Result<PerformanceState, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPowerState.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetIndex)?;
 
         unsafe {
-            let mut state: nvmlPstates_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut state))?;
+            let mut index: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut index))?;
 
-            PerformanceState::try_from(state)
+            Ok(index)
         }
     }
 
     /**
-    Gets the power usage for this GPU and its associated circuitry (memory) in milliwatts.
+    Gets the checksum of the config stored in this `Device`'s infoROM.
+
+    Can be used to make sure that two GPUs have the exact same configuration.
+    The current checksum takes into account configuration stored in PWR and ECC
+    infoROM objects. The checksum can change between driver released or when the
+    user changes the configuration (e.g. disabling/enabling ECC).
 
     # Errors
 
+    * `CorruptedInfoROM`, if this `Device`'s checksum couldn't be retrieved due to infoROM corruption
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support power readings
+    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Fermi and newer fully supported devices.
-
-    This reading is accurate to within +/- 5% of current power draw on Fermi and Kepler GPUs.
-    It is only supported if power management mode is supported. See `.is_power_management_algo_active()`.
-    Yes, that is deprecated, but that's what NVIDIA's docs say to see.
+    Supports all devices with an infoROM.
     */
     // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetPowerUsage")]
-    pub fn power_usage(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPowerUsage.as_ref())?;
+    // Tested on machines other than my own
+    #[doc(alias = "nvmlDeviceGetInforomConfigurationChecksum")]
+    pub fn config_checksum(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetInforomConfigurationChecksum)?;
 
         unsafe {
-            let mut usage: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut usage))?;
+            let mut checksum: c_uint = mem::zeroed();
 
-            Ok(usage)
+            self.try_for(function, || sym(self.device, &mut checksum))?;
+
+            Ok(checksum)
         }
     }
 
     /**
-    Gets this device's total energy consumption in millijoules (mJ) since the last
-    driver reload.
+    Gets the global infoROM image version.
+
+    This image version, just like the VBIOS version, uniquely describes the exact version
+    of the infoROM flashed on the board, in contrast to the infoROM object version which
+    is only an indicator of supported features.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support energy readings
+    * `NotSupported`, if this `Device` does not have an infoROM
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Pascal and newer fully supported devices.
+    Supports all devices with an infoROM.
     */
-    #[doc(alias = "nvmlDeviceGetTotalEnergyConsumption")]
-    pub fn total_energy_consumption(&self) -> Result<u64, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetTotalEnergyConsumption.as_ref())?;
+    // Checked against local
+    // Tested on machines other than my own
+    #[doc(alias = "nvmlDeviceGetInforomImageVersion")]
+    pub fn info_rom_image_version(&self) -> Result<String, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetInforomImageVersion)?;
 
         unsafe {
-            let mut total: c_ulonglong = mem::zeroed();
-            nvml_try(sym(self.device, &mut total))?;
-
-            Ok(total)
+            with_string_buffer(NVML_DEVICE_INFOROM_VERSION_BUFFER_SIZE, |buffer, len| {
+                self.try_for(function, || sym(self.device, buffer, len))
+            })
         }
     }
 
     /**
-    Gets the list of retired pages filtered by `cause`, including pages pending retirement.
-
-    **I cannot verify that this method will work because the call within is not supported
-    on my dev machine**. Please **verify for yourself** that it works before you use it.
-    If you are able to test it on your machine, please let me know if it works; if it
-    doesn't, I would love a PR.
+    Gets the version information for this `Device`'s infoROM object, for the passed in
+    object type.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` doesn't support this feature
+    * `NotSupported`, if this `Device` does not have an infoROM
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid UTF-8
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
+    Supports all devices with an infoROM.
+
+    Fermi and higher parts have non-volatile on-board memory for persisting device info,
+    such as aggregate ECC counts. The version of the data structures in this memory may
+    change from time to time.
     */
     // Checked against local
     // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetRetiredPages_v2")]
-    pub fn retired_pages(&self, cause: RetirementCause) -> Result<Vec<RetiredPage>, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetRetiredPages_v2.as_ref())?;
-
-        unsafe {
-            let mut count = match self.retired_pages_count(&cause)? {
-                0 => return Ok(vec![]),
-                value => value,
-            };
-            let mut addresses: Vec<c_ulonglong> = vec![mem::zeroed(); count as usize];
-            let mut timestamps: Vec<c_ulonglong> = vec![mem::zeroed(); count as usize];
-
-            nvml_try(sym(
-                self.device,
-                cause.as_c(),
-                &mut count,
-                addresses.as_mut_ptr(),
-                timestamps.as_mut_ptr(),
-            ))?;
-
-            Ok(addresses
-                .into_iter()
-                .zip(timestamps.into_iter())
-                .map(|(address, timestamp)| RetiredPage { address, timestamp })
-                .collect())
-        }
-    }
-
-    // Helper for the above function. Returns # of samples that can be queried.
-    fn retired_pages_count(&self, cause: &RetirementCause) -> Result<c_uint, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetRetiredPages.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetInforomVersion")]
+    pub fn info_rom_version(&self, object: InfoRom) -> Result<String, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetInforomVersion)?;
 
         unsafe {
-            let mut count: c_uint = 0;
-
-            nvml_try(sym(
-                self.device,
-                cause.as_c(),
-                &mut count,
-                // All NVIDIA says is that this
-                // can't be null.
-                &mut mem::zeroed(),
-            ))?;
-
-            Ok(count)
+            with_string_buffer(NVML_DEVICE_INFOROM_VERSION_BUFFER_SIZE, |buffer, len| {
+                self.try_for_args(function, &[object.as_c() as u64], || {
+                    sym(self.device, object.as_c(), buffer, len)
+                })
+            })
         }
     }
 
     /**
-    Gets whether there are pages pending retirement (they need a reboot to fully retire).
+    Gets the maximum clock speeds for this `Device`.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` doesn't support this feature
+    * `NotSupported`, if this `Device` cannot report the specified `Clock`
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `UnexpectedVariant`, for which you can read the docs for
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
+    Supports Fermi and newer fully supported devices.
+
+    Note: On GPUs from the Fermi family, current P0 (Performance state 0?) clocks
+    (reported by `.clock_info()`) can differ from max clocks by a few MHz.
     */
     // Checked against local
-    // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetRetiredPagesPendingStatus")]
-    pub fn are_pages_pending_retired(&self) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetRetiredPagesPendingStatus
-                .as_ref(),
-        )?;
+    // Tested
+    #[doc(alias = "nvmlDeviceGetMaxClockInfo")]
+    pub fn max_clock_info(&self, clock_type: Clock) -> Result<Megahertz, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMaxClockInfo)?;
 
         unsafe {
-            let mut state: nvmlEnableState_t = mem::zeroed();
+            let mut clock: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.device, &mut state))?;
+            self.try_for_args(function, &[clock_type.as_c() as u64], || {
+                sym(self.device, clock_type.as_c(), &mut clock)
+            })?;
 
-            bool_from_state(state)
+            Ok(Megahertz(clock))
         }
     }
 
     /**
-    Gets recent samples for this `Device`.
-
-    `last_seen_timestamp` represents the CPU timestamp in μs. Passing in `None`
-    will fetch all samples maintained in the underlying buffer; you can
-    alternatively pass in a timestamp retrieved from the date of the previous
-    query in order to obtain more recent samples.
+    Gets the max PCIe link generation possible with this `Device` and system.
 
-    The advantage of using this method for samples in contrast to polling via
-    existing methods is to get higher frequency data at a lower polling cost.
+    For a gen 2 PCIe device attached to a gen 1 PCIe bus, the max link generation
+    this function will report is generation 1.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this query is not supported by this `Device`
+    * `NotSupported`, if PCIe link information is not available
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `NotFound`, if sample entries are not found
-    * `UnexpectedVariant`, check that error's docs for more info
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
-
-    # Examples
-
-    ```
-    # use nvml_wrapper::Nvml;
-    # use nvml_wrapper::error::*;
-    # fn main() -> Result<(), NvmlError> {
-    # match test() {
-    # Err(NvmlError::NotFound) => Ok(()),
-    # other => other,
-    # }
-    # }
-    # fn test() -> Result<(), NvmlError> {
-    # let nvml = Nvml::init()?;
-    # let device = nvml.device_by_index(0)?;
-    use nvml_wrapper::enum_wrappers::device::Sampling;
-
-    // Passing `None` indicates that we want all `Power` samples in the sample buffer
-    let power_samples = device.samples(Sampling::Power, None)?;
-
-    // Take the first sample from the vector, if it exists...
-    if let Some(sample) = power_samples.get(0) {
-        // ...and now we can get all `ProcessorClock` samples that exist with a later
-        // timestamp than the `Power` sample.
-        let newer_clock_samples = device.samples(Sampling::ProcessorClock, sample.timestamp)?;
-    }
-    # Ok(())
-    # }
-    ```
+    Supports Fermi and newer fully supported devices.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetSamples")]
-    pub fn samples<T>(
-        &self,
-        sample_type: Sampling,
-        last_seen_timestamp: T,
-    ) -> Result<Vec<Sample>, NvmlError>
-    where
-        T: Into<Option<u64>>,
-    {
-        let timestamp = last_seen_timestamp.into().unwrap_or(0);
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetSamples.as_ref())?;
-
-        unsafe {
-            let mut val_type: nvmlValueType_t = mem::zeroed();
-            let mut count = match self.samples_count(&sample_type, timestamp)? {
-                0 => return Ok(vec![]),
-                value => value,
-            };
-            let mut samples: Vec<nvmlSample_t> = vec![mem::zeroed(); count as usize];
-
-            nvml_try(sym(
-                self.device,
-                sample_type.as_c(),
-                timestamp,
-                &mut val_type,
-                &mut count,
-                samples.as_mut_ptr(),
-            ))?;
-
-            let val_type_rust = SampleValueType::try_from(val_type)?;
-            Ok(samples
-                .into_iter()
-                .map(|s| Sample::from_tag_and_struct(&val_type_rust, s))
-                .collect())
-        }
-    }
-
-    // Helper for the above function. Returns # of samples that can be queried.
-    fn samples_count(&self, sample_type: &Sampling, timestamp: u64) -> Result<c_uint, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetSamples.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetMaxPcieLinkGeneration")]
+    pub fn max_pcie_link_gen(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMaxPcieLinkGeneration)?;
 
         unsafe {
-            let mut val_type: nvmlValueType_t = mem::zeroed();
-            let mut count: c_uint = mem::zeroed();
+            let mut max_gen: c_uint = mem::zeroed();
 
-            nvml_try(sym(
-                self.device,
-                sample_type.as_c(),
-                timestamp,
-                &mut val_type,
-                &mut count,
-                // Indicates that we want the count
-                ptr::null_mut(),
-            ))?;
+            self.try_for(function, || sym(self.device, &mut max_gen))?;
 
-            Ok(count)
+            Ok(max_gen)
         }
     }
 
     /**
-    Get values for the given slice of `FieldId`s.
-
-    NVIDIA's docs say that if any of the `FieldId`s are populated by the same driver
-    call, the samples for those IDs will be populated by a single call instead of
-    a call per ID. It would appear, then, that this is essentially a "batch-request"
-    API path for better performance.
+    Gets the maximum PCIe link width possible with this `Device` and system.
 
-    There are too many field ID constants defined in the header to reasonably
-    wrap them with an enum in this crate. Instead, I've re-exported the defined
-    ID constants at `nvml_wrapper::sys_exports::field_id::*`; stick those
-    constants in `FieldId`s for use with this function.
+    For a device with a 16x PCie bus width attached to an 8x PCIe system bus,
+    this method will report a max link width of 8.
 
     # Errors
 
-    ## Outer `Result`
-
-    * `InvalidArg`, if `id_slice` has a length of zero
-
-    ## Inner `Result`
-
-    * `UnexpectedVariant`, check that error's docs for more info
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if PCIe link information is not available
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Device support varies per `FieldId` that you pass in.
+    Supports Fermi and newer fully supported devices.
     */
-    // TODO: Example
-    #[doc(alias = "nvmlDeviceGetFieldValues")]
-    pub fn field_values_for(
-        &self,
-        id_slice: &[FieldId],
-    ) -> Result<Vec<Result<FieldValueSample, NvmlError>>, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetFieldValues.as_ref())?;
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetMaxPcieLinkWidth")]
+    pub fn max_pcie_link_width(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMaxPcieLinkWidth)?;
 
         unsafe {
-            let values_count = id_slice.len();
-            let mut field_values: Vec<nvmlFieldValue_t> = Vec::with_capacity(values_count);
-
-            for id in id_slice.iter() {
-                let mut raw: nvmlFieldValue_t = mem::zeroed();
-                raw.fieldId = id.0;
-
-                field_values.push(raw);
-            }
-
-            nvml_try(sym(
-                self.device,
-                values_count as i32,
-                field_values.as_mut_ptr(),
-            ))?;
+            let mut max_width: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut max_width))?;
 
-            Ok(field_values
-                .into_iter()
-                .map(FieldValueSample::try_from)
-                .collect())
+            Ok(max_width)
         }
     }
 
     /**
-    Gets the globally unique board serial number associated with this `Device`'s board
-    as an alphanumeric string.
+    Gets the requested memory error counter for this `Device`.
 
-    This serial number matches the serial number tag that is physically attached to the board.
+    Only applicable to devices with ECC. Requires ECC mode to be enabled.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` doesn't support this feature
+    * `InvalidArg`, if `error_type`, `counter_type`, or `location` is invalid (shouldn't occur?)
+    * `NotSupported`, if this `Device` does not support ECC error reporting for the specified
+    memory
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports all products with an infoROM.
+    Supports Fermi and newer fully supported devices. Requires `InfoRom::ECC` version
+    2.0 or higher to report aggregate location-based memory error counts. Requires
+    `InfoRom::ECC version 1.0 or higher to report all other memory error counts.
     */
     // Checked against local
     // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetSerial")]
-    pub fn serial(&self) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetSerial.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetMemoryErrorCounter")]
+    pub fn memory_error_counter(
+        &self,
+        error_type: MemoryError,
+        counter_type: EccCounter,
+        location: MemoryLocation,
+    ) -> Result<u64, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMemoryErrorCounter)?;
 
         unsafe {
-            let mut serial_vec = vec![0; NVML_DEVICE_SERIAL_BUFFER_SIZE as usize];
+            let mut count: c_ulonglong = mem::zeroed();
 
-            nvml_try(sym(
-                self.device,
-                serial_vec.as_mut_ptr(),
-                NVML_DEVICE_SERIAL_BUFFER_SIZE,
-            ))?;
+            self.try_for_args(
+                function,
+                &[
+                    error_type.as_c() as u64,
+                    counter_type.as_c() as u64,
+                    location.as_c() as u64,
+                ],
+                || {
+                    sym(
+                        self.device,
+                        error_type.as_c(),
+                        counter_type.as_c(),
+                        location.as_c(),
+                        &mut count,
+                    )
+                },
+            )?;
 
-            let serial_raw = CStr::from_ptr(serial_vec.as_ptr());
-            Ok(serial_raw.to_str()?.into())
+            Ok(count)
         }
     }
 
     /**
-    Gets the board part number for this `Device`.
-
-    The board part number is programmed into the board's infoROM.
+    Gets the offset applied to the voltage/frequency curve of this `Device`'s
+    memory clock (MEMCLK), in MHz.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotSupported`, if the necessary VBIOS fields have not been filled
-    * `GpuLost`, if the target GPU has fellen off the bus or is otherwise inaccessible
-    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    NVIDIA doesn't document which devices support this.
     */
     // Checked against local
-    // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetBoardPartNumber")]
-    pub fn board_part_number(&self) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetBoardPartNumber.as_ref())?;
+    // Tested
+    #[doc(alias = "nvmlDeviceGetMemClkVfOffset")]
+    pub fn mem_clk_vf_offset(&self) -> Result<i32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMemClkVfOffset)?;
 
         unsafe {
-            let mut part_num_vec = vec![0; NVML_DEVICE_PART_NUMBER_BUFFER_SIZE as usize];
-
-            nvml_try(sym(
-                self.device,
-                part_num_vec.as_mut_ptr(),
-                NVML_DEVICE_PART_NUMBER_BUFFER_SIZE,
-            ))?;
+            let mut offset: c_int = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut offset))?;
 
-            let part_num_raw = CStr::from_ptr(part_num_vec.as_ptr());
-            Ok(part_num_raw.to_str()?.into())
+            Ok(offset)
         }
     }
 
     /**
-    Gets current throttling reasons.
+    Gets the amount of used, free and total memory available on this `Device`, in bytes.
 
-    Note that multiple reasons can be affecting clocks at once.
+    Note that enabling ECC reduces the amount of total available memory due to the
+    extra required parity bits.
+
+    Also note that on Windows, most device memory is allocated and managed on startup
+    by Windows.
 
-    The returned bitmask is created via the `ThrottleReasons::from_bits_truncate`
-    method, meaning that any bits that don't correspond to flags present in this
-    version of the wrapper will be dropped.
+    Under Linux and Windows TCC (no physical display connected), the reported amount
+    of used memory is equal to the sum of memory allocated by all active channels on
+    this `Device`.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotSupported`, if this `Device` does not support this feature
+    * `InvalidArg`, if this `Device` is invalid
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
-
-    # Device Support
-
-    Supports all _fully supported_ devices.
     */
-    // Checked against local.
+    // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetCurrentClocksThrottleReasons")]
-    pub fn current_throttle_reasons(&self) -> Result<ThrottleReasons, NvmlError> {
-        Ok(ThrottleReasons::from_bits_truncate(
-            self.current_throttle_reasons_raw()?,
-        ))
+    #[doc(alias = "nvmlDeviceGetMemoryInfo")]
+    pub fn memory_info(&self) -> Result<MemoryInfo, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMemoryInfo)?;
+
+        unsafe {
+            let mut info: nvmlMemory_t = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut info))?;
+
+            Ok(info.into())
+        }
     }
 
     /**
-    Gets current throttling reasons, erroring if any bits correspond to
-    non-present flags.
+    Gets the minor number for this `Device`.
 
-    Note that multiple reasons can be affecting clocks at once.
+    The minor number is such that the NVIDIA device node file for each GPU will
+    have the form `/dev/nvidia[minor number]`.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `IncorrectBits`, if NVML returns any bits that do not correspond to flags in
-    `ThrottleReasons`
-    * `NotSupported`, if this `Device` does not support this feature
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this query is not supported by this `Device`
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
-    # Device Support
+    # Platform Support
 
-    Supports all _fully supported_ devices.
+    Only supports Linux.
     */
-    // Checked against local.
+    // Checked against local
     // Tested
-    pub fn current_throttle_reasons_strict(&self) -> Result<ThrottleReasons, NvmlError> {
-        let reasons = self.current_throttle_reasons_raw()?;
-
-        ThrottleReasons::from_bits(reasons).ok_or(NvmlError::IncorrectBits(Bits::U64(reasons)))
-    }
-
-    // Helper for the above methods.
-    fn current_throttle_reasons_raw(&self) -> Result<c_ulonglong, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetCurrentClocksThrottleReasons
-                .as_ref(),
-        )?;
+    #[cfg(target_os = "linux")]
+    #[doc(alias = "nvmlDeviceGetMinorNumber")]
+    pub fn minor_number(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMinorNumber)?;
 
         unsafe {
-            let mut reasons: c_ulonglong = mem::zeroed();
-
-            nvml_try(sym(self.device, &mut reasons))?;
+            let mut number: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut number))?;
 
-            Ok(reasons)
+            Ok(number)
         }
     }
 
     /**
-    Gets a bitmask of the supported throttle reasons.
-
-    These reasons can be returned by `.current_throttle_reasons()`.
-
-    The returned bitmask is created via the `ThrottleReasons::from_bits_truncate`
-    method, meaning that any bits that don't correspond to flags present in this
-    version of the wrapper will be dropped.
+    Identifies whether or not this `Device` is on a multi-GPU board.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports all _fully supported_ devices.
-
-    # Environment Support
-
-    This method is not supported on virtual machines running vGPUs.
+    Supports Fermi or newer fully supported devices.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetSupportedClocksThrottleReasons")]
-    pub fn supported_throttle_reasons(&self) -> Result<ThrottleReasons, NvmlError> {
-        Ok(ThrottleReasons::from_bits_truncate(
-            self.supported_throttle_reasons_raw()?,
-        ))
+    #[doc(alias = "nvmlDeviceGetMultiGpuBoard")]
+    pub fn is_multi_gpu_board(&self) -> Result<bool, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMultiGpuBoard)?;
+
+        unsafe {
+            let mut int_bool: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut int_bool))?;
+
+            match int_bool {
+                0 => Ok(false),
+                _ => Ok(true),
+            }
+        }
     }
 
     /**
-    Gets a bitmask of the supported throttle reasons, erroring if any bits
-    correspond to non-present flags.
+    The name of this `Device`, e.g. "Tesla C2070".
 
-    These reasons can be returned by `.current_throttle_reasons()`.
+    The name is an alphanumeric string that denotes a particular product.
+    Long names of newer products are read in full and surrounding whitespace is
+    trimmed.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `IncorrectBits`, if NVML returns any bits that do not correspond to flags in
-    `ThrottleReasons`
+    * `InvalidArg`, if this `Device` is invalid
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
     * `Unknown`, on any unexpected error
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetName")]
+    pub fn name(&self) -> Result<String, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetName)?;
 
-    # Device Support
+        unsafe {
+            with_string_buffer(NVML_DEVICE_NAME_V2_BUFFER_SIZE, |buffer, len| {
+                self.try_for(function, || sym(self.device, buffer, len))
+            })
+        }
+    }
 
-    Supports all _fully supported_ devices.
+    /**
+    Gets the PCI attributes of this `Device`.
 
-    # Environment Support
+    See `PciInfo` for details about the returned attributes.
 
-    This method is not supported on virtual machines running vGPUs.
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `GpuLost`, if the GPU has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if a string obtained from the C function is not valid Utf8
+    * `Unknown`, on any unexpected error
     */
     // Checked against local
     // Tested
-    pub fn supported_throttle_reasons_strict(&self) -> Result<ThrottleReasons, NvmlError> {
-        let reasons = self.supported_throttle_reasons_raw()?;
-
-        ThrottleReasons::from_bits(reasons).ok_or(NvmlError::IncorrectBits(Bits::U64(reasons)))
-    }
+    #[doc(alias = "nvmlDeviceGetPciInfo_v3")]
+    pub fn pci_info(&self) -> Result<PciInfo, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPciInfo_v3)?;
 
-    // Helper for the above methods.
-    fn supported_throttle_reasons_raw(&self) -> Result<c_ulonglong, NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceGetSupportedClocksThrottleReasons
-                .as_ref(),
-        )?;
         unsafe {
-            let mut reasons: c_ulonglong = mem::zeroed();
-
-            nvml_try(sym(self.device, &mut reasons))?;
+            let mut pci_info: nvmlPciInfo_t = mem::zeroed();
+            self.try_for(function, || {
+                self.nvml
+                    .legacy
+                    .call(function, sym, |sym| sym(self.device, &mut pci_info))
+            })?;
 
-            Ok(reasons)
+            PciInfo::try_from(pci_info, true)
         }
     }
 
     /**
-    Gets a `Vec` of possible graphics clocks that can be used as an arg for
-    `set_applications_clocks()`.
+    Gets the PCIe replay counter.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotFound`, if the specified `for_mem_clock` is not a supported frequency
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` doesn't support this feature
+    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
+    Supports Kepler or newer fully supported devices.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetSupportedGraphicsClocks")]
-    pub fn supported_graphics_clocks(&self, for_mem_clock: u32) -> Result<Vec<u32>, NvmlError> {
-        match self.supported_graphics_clocks_manual(for_mem_clock, 128) {
-            Err(NvmlError::InsufficientSize(Some(s))) =>
-            // `s` is the required size for the call; make the call a second time
-            {
-                self.supported_graphics_clocks_manual(for_mem_clock, s)
-            }
-            value => value,
-        }
-    }
-
-    // Removes code duplication in the above function.
-    fn supported_graphics_clocks_manual(
-        &self,
-        for_mem_clock: u32,
-        size: usize,
-    ) -> Result<Vec<u32>, NvmlError> {
-        let mut items: Vec<c_uint> = vec![0; size];
-        let mut count = size as c_uint;
-
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetSupportedGraphicsClocks.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetPcieReplayCounter")]
+    pub fn pcie_replay_counter(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPcieReplayCounter)?;
 
         unsafe {
-            match sym(self.device, for_mem_clock, &mut count, items.as_mut_ptr()) {
-                // `count` is now the size that is required. Return it in the error.
-                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => {
-                    return Err(NvmlError::InsufficientSize(Some(count as usize)))
-                }
-                value => nvml_try(value)?,
-            }
-        }
+            let mut value: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut value))?;
 
-        items.truncate(count as usize);
-        Ok(items)
+            Ok(value)
+        }
     }
 
     /**
-    Gets a `Vec` of possible memory clocks that can be used as an arg for
-    `set_applications_clocks()`.
+    Gets PCIe utilization information in KB/s.
+
+    The function called within this method is querying a byte counter over a 20ms
+    interval and thus is the PCIE throughput over that interval.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` doesn't support this feature
+    * `InvalidArg`, if this `Device` is invalid or `counter` is invalid (shouldn't occur?)
+    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
+    Supports Maxwell and newer fully supported devices.
+
+    # Environment Support
+
+    This method is not supported on virtual machines running vGPUs.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetSupportedMemoryClocks")]
-    pub fn supported_memory_clocks(&self) -> Result<Vec<u32>, NvmlError> {
-        match self.supported_memory_clocks_manual(16) {
-            Err(NvmlError::InsufficientSize(Some(s))) => {
-                // `s` is the required size for the call; make the call a second time
-                self.supported_memory_clocks_manual(s)
-            }
-            value => value,
-        }
-    }
+    #[doc(alias = "nvmlDeviceGetPcieThroughput")]
+    pub fn pcie_throughput(&self, counter: PcieUtilCounter) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPcieThroughput)?;
 
-    // Removes code duplication in the above function.
-    fn supported_memory_clocks_manual(&self, size: usize) -> Result<Vec<u32>, NvmlError> {
-        let mut items: Vec<c_uint> = vec![0; size];
-        let mut count = size as c_uint;
+        unsafe {
+            let mut throughput: c_uint = mem::zeroed();
 
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetSupportedMemoryClocks.as_ref())?;
+            self.try_for_args(function, &[counter.as_c() as u64], || {
+                sym(self.device, counter.as_c(), &mut throughput)
+            })?;
 
-        unsafe {
-            match sym(self.device, &mut count, items.as_mut_ptr()) {
-                // `count` is now the size that is required. Return it in the error.
-                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => {
-                    return Err(NvmlError::InsufficientSize(Some(count as usize)))
-                }
-                value => nvml_try(value)?,
-            }
+            Ok(throughput)
         }
-
-        items.truncate(count as usize);
-        Ok(items)
     }
 
     /**
-    Gets the current temperature readings for the given sensor, in °C.
+    Gets the performance modes (P-states) defined for this `Device` along with
+    the graphics and memory clock ranges of each, as `nvidia-settings`
+    reports them.
+
+    The clocks take any VF offsets set on this `Device` into account.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid or `sensor` is invalid (shouldn't occur?)
-    * `NotSupported`, if this `Device` does not have the specified sensor
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
     * `Unknown`, on any unexpected error
+
+    Drivers older than this function return `FailedToLoadSymbol`.
+
+    # Device Support
+
+    NVIDIA doesn't document which devices support this.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetTemperature")]
-    pub fn temperature(&self, sensor: TemperatureSensor) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetTemperature.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetPerformanceModes")]
+    pub fn performance_modes(&self) -> Result<Vec<PerformanceMode>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPerformanceModes)?;
 
         unsafe {
-            let mut temp: c_uint = mem::zeroed();
+            let mut modes: nvmlDevicePerfModes_t = mem::zeroed();
+            modes.version = v1_struct_version::<nvmlDevicePerfModes_t>();
 
-            nvml_try(sym(self.device, sensor.as_c(), &mut temp))?;
+            self.try_for(function, || sym(self.device, &mut modes))?;
 
-            Ok(temp)
+            performance_modes_from_c(&modes)
         }
     }
 
     /**
-    Gets the temperature threshold for this `Device` and the specified `threshold_type`, in °C.
+    Gets the current performance state for this `Device`. 0 == max, 15 == min.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid or `threshold_type` is invalid (shouldn't occur?)
-    * `NotSupported`, if this `Device` does not have a temperature sensor or is unsupported
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
+    Supports Fermi or newer fully supported devices.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetTemperatureThreshold")]
-    pub fn temperature_threshold(
-        &self,
-        threshold_type: TemperatureThreshold,
-    ) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetTemperatureThreshold.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetPerformanceState")]
+    pub fn performance_state(&self) -> Result<PerformanceState, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPerformanceState)?;
 
         unsafe {
-            let mut temp: c_uint = mem::zeroed();
-
-            nvml_try(sym(self.device, threshold_type.as_c(), &mut temp))?;
+            let mut state: nvmlPstates_t = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut state))?;
 
-            Ok(temp)
+            PerformanceState::try_from(state)
         }
     }
 
     /**
-    Gets the common ancestor for two devices.
+    Gets whether or not persistent mode is enabled for this `Device`.
+
+    When driver persistence mode is enabled the driver software is not torn down
+    when the last client disconnects. This feature is disabled by default.
 
     # Errors
 
-    * `InvalidArg`, if either `Device` is invalid
-    * `NotSupported`, if this `Device` or the OS does not support this feature
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `UnexpectedVariant`, for which you can read the docs for
-    * `Unknown`, an error has occurred in the underlying topology discovery
+    * `Unknown`, on any unexpected error
 
     # Platform Support
 
@@ -2961,138 +3318,87 @@ impl<'nvml> Device<'nvml> {
     // Checked against local
     // Tested
     #[cfg(target_os = "linux")]
-    #[doc(alias = "nvmlDeviceGetTopologyCommonAncestor")]
-    pub fn topology_common_ancestor(
-        &self,
-        other_device: Device,
-    ) -> Result<TopologyLevel, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetTopologyCommonAncestor.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetPersistenceMode")]
+    pub fn is_in_persistent_mode(&self) -> Result<bool, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPersistenceMode)?;
 
         unsafe {
-            let mut level: nvmlGpuTopologyLevel_t = mem::zeroed();
-
-            nvml_try(sym(self.device, other_device.device, &mut level))?;
+            let mut state: nvmlEnableState_t = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut state))?;
 
-            TopologyLevel::try_from(level)
+            bool_from_state(state)
         }
     }
 
     /**
-    Gets the set of GPUs that are nearest to this `Device` at a specific interconnectivity level.
-
-    # Errors
+    Gets the default power management limit for this `Device`, in milliwatts.
 
-    * `InvalidArg`, if this `Device` is invalid or `level` is invalid (shouldn't occur?)
-    * `NotSupported`, if this `Device` or the OS does not support this feature
-    * `Unknown`, an error has occurred in the underlying topology discovery
+    This is the limit that this `Device` boots with.
 
-    # Platform Support
+    # Errors
 
-    Only supports Linux.
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler or newer fully supported devices.
     */
     // Checked against local
     // Tested
-    #[cfg(target_os = "linux")]
-    #[doc(alias = "nvmlDeviceGetTopologyNearestGpus")]
-    pub fn topology_nearest_gpus(
-        &self,
-        level: TopologyLevel,
-    ) -> Result<Vec<Device<'nvml>>, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetTopologyNearestGpus.as_ref())?;
-
-        unsafe {
-            let mut count = match self.top_nearest_gpus_count(&level)? {
-                0 => return Ok(vec![]),
-                value => value,
-            };
-            let mut gpus: Vec<nvmlDevice_t> = vec![mem::zeroed(); count as usize];
-
-            nvml_try(sym(
-                self.device,
-                level.as_c(),
-                &mut count,
-                gpus.as_mut_ptr(),
-            ))?;
-
-            Ok(gpus
-                .into_iter()
-                .map(|d| Device::new(d, self.nvml))
-                .collect())
-        }
-    }
-
-    // Helper for the above function. Returns # of GPUs in the set.
-    #[cfg(target_os = "linux")]
-    fn top_nearest_gpus_count(&self, level: &TopologyLevel) -> Result<c_uint, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetTopologyNearestGpus.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetPowerManagementDefaultLimit")]
+    pub fn power_management_limit_default(&self) -> Result<Milliwatts, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPowerManagementDefaultLimit)?;
 
         unsafe {
-            let mut count: c_uint = 0;
-
-            nvml_try(sym(
-                self.device,
-                level.as_c(),
-                &mut count,
-                // Passing null (I assume?)
-                // indicates that we want the
-                // GPU count
-                ptr::null_mut(),
-            ))?;
+            let mut limit: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut limit))?;
 
-            Ok(count)
+            Ok(Milliwatts(limit))
         }
     }
 
     /**
-    Gets the total ECC error counts for this `Device`.
+    Gets the power management limit associated with this `Device`.
 
-    Only applicable to devices with ECC. The total error count is the sum of errors across
-    each of the separate memory systems, i.e. the total set of errors across the entire device.
+    The power limit defines the upper boundary for the card's power draw. If the card's
+    total power draw reaches this limit, the power management algorithm kicks in.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid or either enum is invalid (shouldn't occur?)
+    * `InvalidArg`, if this `Device` is invalid
     * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Fermi and newer fully supported devices. Requires `InfoRom::ECC` version 1.0
-    or higher. Requires ECC mode to be enabled.
+    Supports Fermi or newer fully supported devices.
+
+    This reading is only supported if power management mode is supported. See
+    `.is_power_management_algo_active()`. Yes, it's deprecated, but that's what
+    NVIDIA's docs said to see.
     */
     // Checked against local
-    // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceGetTotalEccErrors")]
-    pub fn total_ecc_errors(
-        &self,
-        error_type: MemoryError,
-        counter_type: EccCounter,
-    ) -> Result<u64, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetTotalEccErrors.as_ref())?;
+    // Tested
+    #[doc(alias = "nvmlDeviceGetPowerManagementLimit")]
+    pub fn power_management_limit(&self) -> Result<Milliwatts, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPowerManagementLimit)?;
 
         unsafe {
-            let mut count: c_ulonglong = mem::zeroed();
-
-            nvml_try(sym(
-                self.device,
-                error_type.as_c(),
-                counter_type.as_c(),
-                &mut count,
-            ))?;
+            let mut limit: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut limit))?;
 
-            Ok(count)
+            Ok(Milliwatts(limit))
         }
     }
 
     /**
-    Gets the globally unique immutable UUID associated with this `Device` as a 5 part
-    hexadecimal string.
-
-    This UUID augments the immutable, board serial identifier. It is a globally unique
-    identifier and is the _only_ available identifier for pre-Fermi-architecture products.
-    It does NOT correspond to any identifier printed on the board.
+    Gets information about possible power management limit values for this `Device`, in milliwatts.
 
     # Errors
 
@@ -3100,773 +3406,3220 @@ impl<'nvml> Device<'nvml> {
     * `InvalidArg`, if this `Device` is invalid
     * `NotSupported`, if this `Device` does not support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
     * `Unknown`, on any unexpected error
 
-    # Examples
-
-    The UUID can be used to compare two `Device`s and find out if they represent
-    the same physical device:
+    # Device Support
 
-    ```no_run
-    # use nvml_wrapper::Nvml;
-    # use nvml_wrapper::error::*;
-    # fn main() -> Result<(), NvmlError> {
-    # let nvml = Nvml::init()?;
-    # let device1 = nvml.device_by_index(0)?;
-    # let device2 = nvml.device_by_index(1)?;
-    if device1.uuid()? == device2.uuid()? {
-        println!("`device1` represents the same physical device that `device2` does.");
-    }
-    # Ok(())
-    # }
-    ```
+    Supports Kepler or newer fully supported devices.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetUUID")]
-    pub fn uuid(&self) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetUUID.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetPowerManagementLimitConstraints")]
+    pub fn power_management_limit_constraints(
+        &self,
+    ) -> Result<PowerManagementConstraints, NvmlError> {
+        let (sym, function) =
+            nvml_fn!(self.nvml.lib, nvmlDeviceGetPowerManagementLimitConstraints)?;
 
         unsafe {
-            let mut uuid_vec = vec![0; NVML_DEVICE_UUID_V2_BUFFER_SIZE as usize];
+            let mut min_limit: c_uint = mem::zeroed();
+            let mut max_limit: c_uint = mem::zeroed();
 
-            nvml_try(sym(
-                self.device,
-                uuid_vec.as_mut_ptr(),
-                NVML_DEVICE_UUID_V2_BUFFER_SIZE,
-            ))?;
+            self.try_for(function, || {
+                sym(self.device, &mut min_limit, &mut max_limit)
+            })?;
 
-            let uuid_raw = CStr::from_ptr(uuid_vec.as_ptr());
-            Ok(uuid_raw.to_str()?.into())
+            Ok(PowerManagementConstraints {
+                min_limit: Milliwatts(min_limit),
+                max_limit: Milliwatts(max_limit),
+            })
         }
     }
 
-    /**
-    Gets the current utilization rates for this `Device`'s major subsystems.
+    /// Not documenting this because it's deprecated. Read NVIDIA's docs if you
+    /// must use it.
+    // Tested
+    #[deprecated(note = "NVIDIA states that \"this API has been deprecated.\"")]
+    #[doc(alias = "nvmlDeviceGetPowerManagementMode")]
+    pub fn is_power_management_algo_active(&self) -> Result<bool, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPowerManagementMode)?;
 
-    Note: During driver initialization when ECC is enabled, one can see high GPU
-    and memory utilization readings. This is caused by the ECC memory scrubbing
-    mechanism that is performed during driver initialization.
+        unsafe {
+            let mut state: nvmlEnableState_t = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut state))?;
+
+            bool_from_state(state)
+        }
+    }
+
+    /// Not documenting this because it's deprecated. Read NVIDIA's docs if you
+    /// must use it.
+    // Tested
+    #[deprecated(note = "use `.performance_state()`.")]
+    #[doc(alias = "nvmlDeviceGetPowerState")]
+    pub fn power_state(&self) -> Result<PerformanceState, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPowerState)?;
+
+        unsafe {
+            let mut state: nvmlPstates_t = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut state))?;
+
+            PerformanceState::try_from(state)
+        }
+    }
+
+    /**
+    Gets the power usage for this GPU and its associated circuitry (memory) in milliwatts.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
+    * `NotSupported`, if this `Device` does not support power readings
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
     Supports Fermi and newer fully supported devices.
+
+    This reading is accurate to within +/- 5% of current power draw on Fermi and Kepler GPUs.
+    It is only supported if power management mode is supported. See `.is_power_management_algo_active()`.
+    Yes, that is deprecated, but that's what NVIDIA's docs say to see.
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceGetUtilizationRates")]
-    pub fn utilization_rates(&self) -> Result<Utilization, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetUtilizationRates.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetPowerUsage")]
+    pub fn power_usage(&self) -> Result<Milliwatts, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPowerUsage)?;
 
         unsafe {
-            let mut utilization: nvmlUtilization_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut utilization))?;
+            let mut usage: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut usage))?;
 
-            Ok(utilization.into())
+            Ok(Milliwatts(usage))
         }
     }
 
     /**
-    Gets the VBIOS version of this `Device`.
-
-    The VBIOS version may change from time to time.
+    Gets this device's total energy consumption in millijoules (mJ) since the last
+    driver reload.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if this `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
+    * `NotSupported`, if this `Device` does not support energy readings
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    * `Utf8Error`, if the string obtained from the C function is not valid UTF-8
     * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Pascal and newer fully supported devices.
     */
-    // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetVbiosVersion")]
-    pub fn vbios_version(&self) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetVbiosVersion.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetTotalEnergyConsumption")]
+    pub fn total_energy_consumption(&self) -> Result<u64, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetTotalEnergyConsumption)?;
 
         unsafe {
-            let mut version_vec = vec![0; NVML_DEVICE_VBIOS_VERSION_BUFFER_SIZE as usize];
-
-            nvml_try(sym(
-                self.device,
-                version_vec.as_mut_ptr(),
-                NVML_DEVICE_VBIOS_VERSION_BUFFER_SIZE,
-            ))?;
+            let mut total: c_ulonglong = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut total))?;
 
-            let version_raw = CStr::from_ptr(version_vec.as_ptr());
-            Ok(version_raw.to_str()?.into())
+            Ok(total)
         }
     }
 
     /**
-    Gets the duration of time during which this `Device` was throttled (lower than the
-    requested clocks) due to power or thermal constraints.
-
-    This is important to users who are trying to understand if their GPUs throttle at any
-    point while running applications. The difference in violation times at two different
-    reference times gives the indication of a GPU throttling event.
+    Gets the list of retired pages filtered by `cause`, including pages pending retirement.
 
-    Violation for thermal capping is not supported at this time.
+    **I cannot verify that this method will work because the call within is not supported
+    on my dev machine**. Please **verify for yourself** that it works before you use it.
+    If you are able to test it on your machine, please let me know if it works; if it
+    doesn't, I would love a PR.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if this `Device` is invalid or `perf_policy` is invalid (shouldn't occur?)
-    * `NotSupported`, if this query is not supported by this `Device`
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler or newer fully supported devices.
+    Supports Kepler and newer fully supported devices.
     */
     // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetViolationStatus")]
-    pub fn violation_status(
-        &self,
-        perf_policy: PerformancePolicy,
-    ) -> Result<ViolationTime, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetViolationStatus.as_ref())?;
+    // Tested on machines other than my own
+    #[doc(alias = "nvmlDeviceGetRetiredPages_v2")]
+    pub fn retired_pages(&self, cause: RetirementCause) -> Result<Vec<RetiredPage>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetRetiredPages_v2)?;
+
         unsafe {
-            let mut viol_time: nvmlViolationTime_t = mem::zeroed();
+            let mut count = match self.retired_pages_count(&cause)? {
+                0 => return Ok(vec![]),
+                value => value,
+            };
+            let mut addresses: Vec<c_ulonglong> = vec![mem::zeroed(); count as usize];
+            let mut timestamps: Vec<c_ulonglong> = vec![mem::zeroed(); count as usize];
 
-            nvml_try(sym(self.device, perf_policy.as_c(), &mut viol_time))?;
+            self.try_for_args(function, &[cause.as_c() as u64], || {
+                sym(
+                    self.device,
+                    cause.as_c(),
+                    &mut count,
+                    addresses.as_mut_ptr(),
+                    timestamps.as_mut_ptr(),
+                )
+            })
+            .map_err(|e| e.with_required_size(count))?;
 
-            Ok(viol_time.into())
+            Ok(addresses
+                .into_iter()
+                .zip(timestamps.into_iter())
+                .map(|(address, timestamp)| RetiredPage { address, timestamp })
+                .collect())
         }
     }
 
     /**
-    Gets the interrupt number for this [`Device`].
+    Gets the number of retired pages for each cause, including pages pending
+    retirement.
+
+    Cheaper than `.retired_pages_by_cause()` when the addresses aren't needed.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotSupported`, if this query is not supported by this `Device`
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    */
-    #[doc(alias = "nvmlDeviceGetIrqNum")]
-    pub fn irq_num(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetIrqNum.as_ref())?;
-
-        let irq_num = unsafe {
-            let mut irq_num: c_uint = mem::zeroed();
-
-            nvml_try(sym(self.device, &mut irq_num))?;
+    * `Unknown`, on any unexpected error
 
-            irq_num
-        };
+    # Device Support
 
-        Ok(irq_num)
+    Supports Kepler and newer fully supported devices.
+    */
+    #[doc(alias = "nvmlDeviceGetRetiredPages")]
+    pub fn retired_page_counts(&self) -> Result<RetiredPageCounts, NvmlError> {
+        Ok(RetiredPageCounts {
+            single_bit_ecc: self
+                .retired_pages_count(&RetirementCause::MultipleSingleBitEccErrors)?,
+            double_bit_ecc: self.retired_pages_count(&RetirementCause::DoubleBitEccError)?,
+        })
     }
 
     /**
-    Gets the core count for this [`Device`].
+    Gets the retired pages for each cause, including pages pending retirement.
 
-    The cores represented in the count here are commonly referred to as
-    "CUDA cores".
+    Equivalent to calling `.retired_pages()` with each `RetirementCause`.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotSupported`, if this query is not supported by this `Device`
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't support this feature
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    */
-    #[doc(alias = "nvmlDeviceGetNumGpuCores")]
-    pub fn num_cores(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetNumGpuCores.as_ref())?;
-
-        unsafe {
-            let mut count: c_uint = mem::zeroed();
+    * `Unknown`, on any unexpected error
 
-            nvml_try(sym(self.device, &mut count))?;
+    # Device Support
 
-            Ok(count)
-        }
+    Supports Kepler and newer fully supported devices.
+    */
+    #[doc(alias = "nvmlDeviceGetRetiredPages_v2")]
+    pub fn retired_pages_by_cause(&self) -> Result<RetiredPagesByCause, NvmlError> {
+        Ok(RetiredPagesByCause {
+            single_bit_ecc: self.retired_pages(RetirementCause::MultipleSingleBitEccErrors)?,
+            double_bit_ecc: self.retired_pages(RetirementCause::DoubleBitEccError)?,
+        })
+    }
+
+    // Helper for the above functions. Returns # of samples that can be queried.
+    fn retired_pages_count(&self, cause: &RetirementCause) -> Result<c_uint, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetRetiredPages)?;
+
+        unsafe {
+            let mut count: c_uint = 0;
+
+            self.try_for_args(function, &[cause.as_c() as u64], || {
+                sym(
+                    self.device,
+                    cause.as_c(),
+                    &mut count,
+                    // All NVIDIA says is that this
+                    // can't be null.
+                    &mut mem::zeroed(),
+                )
+            })?;
+
+            Ok(count)
+        }
     }
 
     /**
-    Gets the power source of this [`Device`].
+    Gets whether there are pages pending retirement (they need a reboot to fully retire).
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested on machines other than my own
+    #[doc(alias = "nvmlDeviceGetRetiredPagesPendingStatus")]
+    pub fn are_pages_pending_retired(&self) -> Result<bool, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetRetiredPagesPendingStatus)?;
+
+        unsafe {
+            let mut state: nvmlEnableState_t = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut state))?;
+
+            bool_from_state(state)
+        }
+    }
+
+    /**
+    Gets recent samples for this `Device`.
+
+    `last_seen_timestamp` represents the CPU timestamp in μs. Passing in `None`
+    will fetch all samples maintained in the underlying buffer; you can
+    alternatively pass in a timestamp retrieved from the date of the previous
+    query in order to obtain more recent samples.
+
+    The advantage of using this method for samples in contrast to polling via
+    existing methods is to get higher frequency data at a lower polling cost.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
     * `NotSupported`, if this query is not supported by this `Device`
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `NotFound`, if sample entries are not found
+    * `UnexpectedVariant`, check that error's docs for more info
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+
+    # Examples
+
+    ```
+    # use nvml_wrapper::Nvml;
+    # use nvml_wrapper::error::*;
+    # fn main() -> Result<(), NvmlError> {
+    # match test() {
+    # Err(NvmlError::NotFound) => Ok(()),
+    # other => other,
+    # }
+    # }
+    # fn test() -> Result<(), NvmlError> {
+    # let nvml = Nvml::init()?;
+    # let device = nvml.device_by_index(0)?;
+    use nvml_wrapper::enum_wrappers::device::Sampling;
+
+    // Passing `None` indicates that we want all `Power` samples in the sample buffer
+    let power_samples = device.samples(Sampling::Power, None)?;
+
+    // Take the first sample from the vector, if it exists...
+    if let Some(sample) = power_samples.get(0) {
+        // ...and now we can get all `ProcessorClock` samples that exist with a later
+        // timestamp than the `Power` sample.
+        let newer_clock_samples = device.samples(Sampling::ProcessorClock, sample.timestamp)?;
+    }
+    # Ok(())
+    # }
+    ```
     */
-    #[doc(alias = "nvmlDeviceGetPowerSource")]
-    pub fn power_source(&self) -> Result<PowerSource, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPowerSource.as_ref())?;
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetSamples")]
+    pub fn samples<T>(
+        &self,
+        sample_type: Sampling,
+        last_seen_timestamp: T,
+    ) -> Result<Vec<Sample>, NvmlError>
+    where
+        T: Into<Option<u64>>,
+    {
+        let mut samples = vec![];
+        self.samples_into(sample_type, last_seen_timestamp, &mut samples)?;
 
-        let power_source_c = unsafe {
-            let mut power_source: nvmlPowerSource_t = mem::zeroed();
+        Ok(samples)
+    }
+
+    /**
+    Like `.samples()`, but writes the samples into the given `Vec` (clearing it
+    first) instead of allocating a new one.
 
-            nvml_try(sym(self.device, &mut power_source))?;
+    No heap allocations are made if `samples` has enough capacity and there
+    are no more than a few dozen samples, making this suitable for polling in
+    a loop.
 
-            power_source
-        };
+    # Errors
 
-        PowerSource::try_from(power_source_c)
+    See `.samples()`.
+    */
+    #[doc(alias = "nvmlDeviceGetSamples")]
+    pub fn samples_into<T>(
+        &self,
+        sample_type: Sampling,
+        last_seen_timestamp: T,
+        samples: &mut Vec<Sample>,
+    ) -> Result<(), NvmlError>
+    where
+        T: Into<Option<u64>>,
+    {
+        samples.clear();
+
+        let timestamp = last_seen_timestamp.into().unwrap_or(0);
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetSamples)?;
+
+        unsafe {
+            let mut val_type: nvmlValueType_t = mem::zeroed();
+            let mut count = match self.samples_count(&sample_type, timestamp)? {
+                0 => return Ok(()),
+                value => value,
+            };
+
+            with_buffer(count as usize, |buffer: &mut [nvmlSample_t]| {
+                self.try_for_args(function, &[sample_type.as_c() as u64], || {
+                    sym(
+                        self.device,
+                        sample_type.as_c(),
+                        timestamp,
+                        &mut val_type,
+                        &mut count,
+                        buffer.as_mut_ptr(),
+                    )
+                })
+                .map_err(|e| e.with_required_size(count))?;
+
+                let val_type_rust = SampleValueType::try_from(val_type)?;
+                samples.extend(
+                    buffer[..count as usize]
+                        .iter()
+                        .map(|s| Sample::from_tag_and_struct(&val_type_rust, *s)),
+                );
+                Ok(())
+            })
+        }
+    }
+
+    // Helper for the above function. Returns # of samples that can be queried.
+    fn samples_count(&self, sample_type: &Sampling, timestamp: u64) -> Result<c_uint, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetSamples)?;
+
+        unsafe {
+            let mut val_type: nvmlValueType_t = mem::zeroed();
+            let mut count: c_uint = mem::zeroed();
+
+            self.try_for_args(function, &[sample_type.as_c() as u64], || {
+                sym(
+                    self.device,
+                    sample_type.as_c(),
+                    timestamp,
+                    &mut val_type,
+                    &mut count,
+                    // Indicates that we want the count
+                    ptr::null_mut(),
+                )
+            })?;
+
+            Ok(count)
+        }
     }
 
     /**
-    Gets the memory bus width of this [`Device`].
+    Gets the processor and memory clock changes recorded in this `Device`'s
+    sample buffers, as a single timeline ordered by timestamp.
 
-    The returned value is in bits (i.e. 320 for a 320-bit bus width).
+    Useful for finding out after the fact why clocks dipped at a certain time;
+    compare the timestamps with those of `EventTypes::PSTATE_CHANGE` and
+    `EventTypes::CLOCK_CHANGE` events or with `.current_clocks_event_reasons()`
+    polled at the time.
+
+    Pass the timestamp of the last transition seen (or of the last call) as
+    `last_seen_timestamp` to only get newer transitions, or `None` to get all
+    transitions within the buffers. A change is only detected between two
+    samples both newer than `last_seen_timestamp`.
+
+    NVML doesn't keep samples of the performance state itself; register for
+    `EventTypes::PSTATE_CHANGE` events to track those.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
     * `NotSupported`, if this query is not supported by this `Device`
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, check that error's docs for more info
+    * `IncorrectValueType`, if clock samples are not reported as `u32` values
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
     */
-    #[doc(alias = "nvmlDeviceGetMemoryBusWidth")]
-    pub fn memory_bus_width(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetMemoryBusWidth.as_ref())?;
+    // Tested
+    pub fn clock_transitions<T>(
+        &self,
+        last_seen_timestamp: T,
+    ) -> Result<Vec<ClockTransition>, NvmlError>
+    where
+        T: Into<Option<u64>>,
+    {
+        let last_seen_timestamp = last_seen_timestamp.into();
+        let mut transitions = vec![];
+
+        for clock in [Sampling::ProcessorClock, Sampling::MemoryClock] {
+            // No samples since `last_seen_timestamp`
+            let samples = match self.samples(clock.clone(), last_seen_timestamp) {
+                Err(NvmlError::NotFound) => continue,
+                other => other?,
+            };
 
-        let memory_bus_width = unsafe {
-            let mut memory_bus_width: c_uint = mem::zeroed();
+            transitions.extend(clock_transitions(clock, samples)?);
+        }
+
+        transitions.sort_by_key(|t| t.timestamp);
+        Ok(transitions)
+    }
+
+    /**
+    Get values for the given slice of `FieldId`s.
+
+    NVIDIA's docs say that if any of the `FieldId`s are populated by the same driver
+    call, the samples for those IDs will be populated by a single call instead of
+    a call per ID. It would appear, then, that this is essentially a "batch-request"
+    API path for better performance.
+
+    There are too many field ID constants defined in the header to reasonably
+    wrap them with an enum in this crate. Instead, `FieldId` has associated
+    constants for the most commonly used fields (ECC counts, temperatures,
+    NvLink throughput, etc.), and I've re-exported all of the defined ID
+    constants at `nvml_wrapper::sys_exports::field_id::*`; stick those
+    constants in `FieldId`s for use with this function.
+
+    Each returned sample carries its own NVML status, so fields this `Device`
+    doesn't support don't fail the whole call.
+
+    # Examples
+
+    ```no_run
+    # use nvml_wrapper::Nvml;
+    # use nvml_wrapper::error::*;
+    # fn main() -> Result<(), NvmlError> {
+    use nvml_wrapper::structs::device::FieldId;
+
+    let nvml = Nvml::init()?;
+    let device = nvml.device_by_index(0)?;
+
+    let samples = device.field_values_for(&[
+        FieldId::MEMORY_TEMP,
+        FieldId::ECC_DBE_VOL_TOTAL,
+        FieldId::NVLINK_THROUGHPUT_DATA_TX,
+    ])?;
+
+    for sample in samples {
+        let sample = sample?;
+
+        match sample.value {
+            Ok(value) => println!("{:?} at {}: {:?}", sample.field, sample.timestamp, value),
+            Err(e) => println!("{:?} unavailable: {}", sample.field, e),
+        }
+    }
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    ## Outer `Result`
+
+    * `InvalidArg`, if `id_slice` has a length of zero
+
+    ## Inner `Result`
+
+    * `UnexpectedVariant`, check that error's docs for more info
+
+    # Device Support
+
+    Device support varies per `FieldId` that you pass in.
+    */
+    #[doc(alias = "nvmlDeviceGetFieldValues")]
+    pub fn field_values_for(
+        &self,
+        id_slice: &[FieldId],
+    ) -> Result<Vec<Result<FieldValueSample, NvmlError>>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetFieldValues)?;
+
+        unsafe {
+            let values_count = id_slice.len();
+            let mut field_values: Vec<nvmlFieldValue_t> = Vec::with_capacity(values_count);
+
+            for id in id_slice.iter() {
+                let mut raw: nvmlFieldValue_t = mem::zeroed();
+                raw.fieldId = id.0;
+
+                field_values.push(raw);
+            }
+
+            self.try_for(function, || {
+                sym(self.device, values_count as i32, field_values.as_mut_ptr())
+            })?;
+
+            Ok(field_values
+                .into_iter()
+                .map(FieldValueSample::try_from)
+                .collect())
+        }
+    }
+
+    /**
+    Gets the metrics most commonly polled by exporters and monitoring agents.
+
+    This is not a single call into NVML. The values that NVML exposes through a
+    field ID (memory temperature and total energy consumption) are fetched
+    together in one `nvmlDeviceGetFieldValues` call without allocating. The
+    power usage, GPU temperature, utilization and three clocks have no field ID
+    in the NVML version these bindings target and are fetched with one call
+    each, for seven calls in total.
+
+    Values that are fetched through field IDs are `None` if this `Device`
+    does not report them.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support one of the non-optional values
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Fermi and newer fully supported devices. See `.power_usage()`,
+    `.temperature()`, `.utilization_rates()`, and `.clock_info()` for details.
+    */
+    pub fn hot_metrics(&self) -> Result<HotMetrics, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetFieldValues)?;
+
+        let mut fields: [nvmlFieldValue_t; 2] = unsafe { mem::zeroed() };
+        fields[0].fieldId = field_id::NVML_FI_DEV_MEMORY_TEMP;
+        fields[1].fieldId = field_id::NVML_FI_DEV_TOTAL_ENERGY_CONSUMPTION;
+
+        unsafe {
+            self.try_for(function, || {
+                sym(self.device, fields.len() as i32, fields.as_mut_ptr())
+            })?;
+        }
+
+        let value = |field: nvmlFieldValue_t| {
+            FieldValueSample::try_from(field)
+                .and_then(|sample| sample.value?.as_u64())
+                .ok()
+        };
+
+        Ok(HotMetrics {
+            power_usage: self.power_usage()?,
+            total_energy_consumption: value(fields[1]),
+            gpu_temperature: self.temperature(TemperatureSensor::Gpu)?,
+            memory_temperature: value(fields[0])
+                .and_then(|v| u32::try_from(v).ok())
+                .map(Celsius),
+            utilization: self.utilization_rates()?,
+            graphics_clock: self.clock_info(Clock::Graphics)?,
+            sm_clock: self.clock_info(Clock::SM)?,
+            memory_clock: self.clock_info(Clock::Memory)?,
+        })
+    }
+
+    /**
+    Gets the data throughput of this `Device` summed across all of its NvLinks,
+    averaged since the previous call to this method for the same GPU.
+
+    The `Nvml` instance this `Device` came from keeps the counters read by the
+    previous call, so this returns `None` the first time it's called for a GPU
+    (and after the counters are reset, e.g. by a driver reload). Call it at a
+    fixed interval and the rates cover exactly that interval.
+
+    Use the `NvLink` struct for per-link counters.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not report NvLink throughput
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports devices with NvLink whose driver reports the
+    `NVML_FI_DEV_NVLINK_THROUGHPUT_DATA_*` field values.
+    */
+    pub fn nvlink_throughput(&self) -> Result<Option<NvLinkThroughput>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetFieldValues)?;
+
+        let mut fields: [nvmlFieldValue_t; 2] = unsafe { mem::zeroed() };
+        fields[0].fieldId = field_id::NVML_FI_DEV_NVLINK_THROUGHPUT_DATA_RX;
+        fields[1].fieldId = field_id::NVML_FI_DEV_NVLINK_THROUGHPUT_DATA_TX;
+        // Sum across all links
+        fields[0].scopeId = c_uint::MAX;
+        fields[1].scopeId = c_uint::MAX;
+
+        unsafe {
+            self.try_for(function, || {
+                sym(self.device, fields.len() as i32, fields.as_mut_ptr())
+            })?;
+        }
+
+        let kib = |field: nvmlFieldValue_t| FieldValueSample::try_from(field)?.value?.as_u64();
+
+        let counters = NvLinkCounters {
+            timestamp: fields[0].timestamp,
+            rx_kib: kib(fields[0])?,
+            tx_kib: kib(fields[1])?,
+        };
+
+        let previous = self
+            .nvml
+            .nvlink_counters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(self.device as usize, counters);
+
+        Ok(previous.and_then(|previous| counters.throughput_since(&previous)))
+    }
+
+    /**
+    Gets the globally unique board serial number associated with this `Device`'s board
+    as an alphanumeric string.
+
+    This serial number matches the serial number tag that is physically attached to the board.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports all products with an infoROM.
+    */
+    // Checked against local
+    // Tested on machines other than my own
+    #[doc(alias = "nvmlDeviceGetSerial")]
+    pub fn serial(&self) -> Result<String, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetSerial)?;
+
+        unsafe {
+            with_string_buffer(NVML_DEVICE_SERIAL_BUFFER_SIZE, |buffer, len| {
+                self.try_for(function, || sym(self.device, buffer, len))
+            })
+        }
+    }
+
+    /**
+    Gets the board part number for this `Device`.
+
+    The board part number is programmed into the board's infoROM.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotSupported`, if the necessary VBIOS fields have not been filled
+    * `GpuLost`, if the target GPU has fellen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
+    * `Unknown`, on any unexpected error
+    */
+    // Checked against local
+    // Tested on machines other than my own
+    #[doc(alias = "nvmlDeviceGetBoardPartNumber")]
+    pub fn board_part_number(&self) -> Result<String, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetBoardPartNumber)?;
+
+        unsafe {
+            with_string_buffer(NVML_DEVICE_PART_NUMBER_BUFFER_SIZE, |buffer, len| {
+                self.try_for(function, || sym(self.device, buffer, len))
+            })
+        }
+    }
+
+    /**
+    Gets the reasons the clocks of this `Device` are currently being limited.
+
+    Note that multiple reasons can be affecting clocks at once.
+
+    Any bits that don't correspond to flags present in this version of the
+    wrapper (e.g. ones added by a newer driver) are kept in the returned
+    bitmask and can be read with `ClocksEventReasons.unknown_bits()`.
+
+    Older drivers call these "clocks throttle reasons"; on drivers that don't
+    export `nvmlDeviceGetCurrentClocksEventReasons` yet, the equivalent
+    `nvmlDeviceGetCurrentClocksThrottleReasons` is called instead.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports all _fully supported_ devices.
+    */
+    // Checked against local.
+    // Tested
+    #[doc(alias = "nvmlDeviceGetCurrentClocksEventReasons")]
+    #[doc(alias = "nvmlDeviceGetCurrentClocksThrottleReasons")]
+    pub fn current_clocks_event_reasons(&self) -> Result<ClocksEventReasons, NvmlError> {
+        Ok(ClocksEventReasons::from_bits_retain(
+            self.current_clocks_event_reasons_raw()?,
+        ))
+    }
+
+    /**
+    Gets the reasons the clocks of this `Device` are currently being limited,
+    erroring if any bits correspond to non-present flags.
+
+    Note that multiple reasons can be affecting clocks at once.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `IncorrectBits`, if NVML returns any bits that do not correspond to flags in
+    `ClocksEventReasons`
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports all _fully supported_ devices.
+    */
+    // Checked against local.
+    // Tested
+    #[deprecated(
+        note = "use `Device.current_clocks_event_reasons()`, which keeps unknown bits, and check `.unknown_bits()`"
+    )]
+    pub fn current_clocks_event_reasons_strict(&self) -> Result<ClocksEventReasons, NvmlError> {
+        let reasons = self.current_clocks_event_reasons_raw()?;
+
+        ClocksEventReasons::from_bits(reasons).ok_or(NvmlError::IncorrectBits(Bits::U64(reasons)))
+    }
+
+    // Helper for the above methods.
+    fn current_clocks_event_reasons_raw(&self) -> Result<c_ulonglong, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetCurrentClocksEventReasons)?;
+
+        unsafe {
+            let mut reasons: c_ulonglong = mem::zeroed();
+
+            self.try_for(function, || {
+                self.nvml
+                    .legacy
+                    .call(function, sym, |sym| sym(self.device, &mut reasons))
+            })?;
+
+            Ok(reasons)
+        }
+    }
+
+    /**
+    Gets a bitmask of the supported clocks event reasons.
+
+    These reasons can be returned by `.current_clocks_event_reasons()`.
+
+    Any bits that don't correspond to flags present in this version of the
+    wrapper (e.g. ones added by a newer driver) are kept in the returned
+    bitmask and can be read with `ClocksEventReasons.unknown_bits()`.
+
+    Older drivers call these "clocks throttle reasons"; on drivers that don't
+    export `nvmlDeviceGetSupportedClocksEventReasons` yet, the equivalent
+    `nvmlDeviceGetSupportedClocksThrottleReasons` is called instead.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports all _fully supported_ devices.
+
+    # Environment Support
+
+    This method is not supported on virtual machines running vGPUs.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetSupportedClocksEventReasons")]
+    #[doc(alias = "nvmlDeviceGetSupportedClocksThrottleReasons")]
+    pub fn supported_clocks_event_reasons(&self) -> Result<ClocksEventReasons, NvmlError> {
+        Ok(ClocksEventReasons::from_bits_retain(
+            self.supported_clocks_event_reasons_raw()?,
+        ))
+    }
+
+    /**
+    Gets a bitmask of the supported clocks event reasons, erroring if any bits
+    correspond to non-present flags.
+
+    These reasons can be returned by `.current_clocks_event_reasons()`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `IncorrectBits`, if NVML returns any bits that do not correspond to flags in
+    `ClocksEventReasons`
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports all _fully supported_ devices.
+
+    # Environment Support
+
+    This method is not supported on virtual machines running vGPUs.
+    */
+    // Checked against local
+    // Tested
+    #[deprecated(
+        note = "use `Device.supported_clocks_event_reasons()`, which keeps unknown bits, and check `.unknown_bits()`"
+    )]
+    pub fn supported_clocks_event_reasons_strict(&self) -> Result<ClocksEventReasons, NvmlError> {
+        let reasons = self.supported_clocks_event_reasons_raw()?;
+
+        ClocksEventReasons::from_bits(reasons).ok_or(NvmlError::IncorrectBits(Bits::U64(reasons)))
+    }
+
+    // Helper for the above methods.
+    fn supported_clocks_event_reasons_raw(&self) -> Result<c_ulonglong, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetSupportedClocksEventReasons)?;
+
+        unsafe {
+            let mut reasons: c_ulonglong = mem::zeroed();
+
+            self.try_for(function, || {
+                self.nvml
+                    .legacy
+                    .call(function, sym, |sym| sym(self.device, &mut reasons))
+            })?;
+
+            Ok(reasons)
+        }
+    }
+
+    /**
+    Gets both the supported and the currently active clocks event reasons of
+    this `Device`.
+
+    The returned `ClocksEventReasonsStatus` tells which supported reasons are
+    not currently active, which is useful for only setting up alert rules for
+    reasons this `Device` can actually report.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports all _fully supported_ devices.
+
+    # Environment Support
+
+    This method is not supported on virtual machines running vGPUs.
+    */
+    // Tested
+    #[doc(alias = "nvmlDeviceGetSupportedClocksEventReasons")]
+    #[doc(alias = "nvmlDeviceGetCurrentClocksEventReasons")]
+    pub fn clocks_event_reasons_status(&self) -> Result<ClocksEventReasonsStatus, NvmlError> {
+        Ok(ClocksEventReasonsStatus {
+            supported: self.supported_clocks_event_reasons()?,
+            active: self.current_clocks_event_reasons()?,
+        })
+    }
+
+    /// Renamed to `.current_clocks_event_reasons()`.
+    #[deprecated(note = "use `Device.current_clocks_event_reasons()`")]
+    pub fn current_throttle_reasons(&self) -> Result<ClocksEventReasons, NvmlError> {
+        self.current_clocks_event_reasons()
+    }
+
+    /// Renamed to `.current_clocks_event_reasons_strict()`, which is deprecated
+    /// in turn.
+    #[deprecated(
+        note = "use `Device.current_clocks_event_reasons()`, which keeps unknown bits, and check `.unknown_bits()`"
+    )]
+    #[allow(deprecated)]
+    pub fn current_throttle_reasons_strict(&self) -> Result<ClocksEventReasons, NvmlError> {
+        self.current_clocks_event_reasons_strict()
+    }
+
+    /// Renamed to `.supported_clocks_event_reasons()`.
+    #[deprecated(note = "use `Device.supported_clocks_event_reasons()`")]
+    pub fn supported_throttle_reasons(&self) -> Result<ClocksEventReasons, NvmlError> {
+        self.supported_clocks_event_reasons()
+    }
+
+    /// Renamed to `.supported_clocks_event_reasons_strict()`, which is deprecated
+    /// in turn.
+    #[deprecated(
+        note = "use `Device.supported_clocks_event_reasons()`, which keeps unknown bits, and check `.unknown_bits()`"
+    )]
+    #[allow(deprecated)]
+    pub fn supported_throttle_reasons_strict(&self) -> Result<ClocksEventReasons, NvmlError> {
+        self.supported_clocks_event_reasons_strict()
+    }
+
+    /**
+    Gets a `Vec` of possible graphics clocks that can be used as an arg for
+    `set_applications_clocks()`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotFound`, if the specified `for_mem_clock` is not a supported frequency
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetSupportedGraphicsClocks")]
+    pub fn supported_graphics_clocks(
+        &self,
+        for_mem_clock: Megahertz,
+    ) -> Result<Vec<Megahertz>, NvmlError> {
+        match self.supported_graphics_clocks_manual(for_mem_clock, 128) {
+            Err(NvmlError::InsufficientSize(Some(s))) =>
+            // `s` is the required size for the call; make the call a second time
+            {
+                self.supported_graphics_clocks_manual(for_mem_clock, s)
+            }
+            value => value,
+        }
+    }
+
+    // Removes code duplication in the above function.
+    fn supported_graphics_clocks_manual(
+        &self,
+        for_mem_clock: Megahertz,
+        size: usize,
+    ) -> Result<Vec<Megahertz>, NvmlError> {
+        let mut items: Vec<c_uint> = vec![0; size];
+        let mut count = size as c_uint;
+
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetSupportedGraphicsClocks)?;
+
+        unsafe {
+            match sym(self.device, for_mem_clock.0, &mut count, items.as_mut_ptr()) {
+                // `count` is now the size that is required. Return it in the error.
+                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => {
+                    return Err(NvmlError::InsufficientSize(Some(count as usize)))
+                }
+                value => nvml_try_for(function, value)?,
+            }
+        }
+
+        items.truncate(count as usize);
+        Ok(items.into_iter().map(Megahertz).collect())
+    }
+
+    /**
+    Gets a `Vec` of possible memory clocks that can be used as an arg for
+    `set_applications_clocks()`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetSupportedMemoryClocks")]
+    pub fn supported_memory_clocks(&self) -> Result<Vec<Megahertz>, NvmlError> {
+        match self.supported_memory_clocks_manual(16) {
+            Err(NvmlError::InsufficientSize(Some(s))) => {
+                // `s` is the required size for the call; make the call a second time
+                self.supported_memory_clocks_manual(s)
+            }
+            value => value,
+        }
+    }
+
+    // Removes code duplication in the above function.
+    fn supported_memory_clocks_manual(&self, size: usize) -> Result<Vec<Megahertz>, NvmlError> {
+        let mut items: Vec<c_uint> = vec![0; size];
+        let mut count = size as c_uint;
+
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetSupportedMemoryClocks)?;
+
+        unsafe {
+            match sym(self.device, &mut count, items.as_mut_ptr()) {
+                // `count` is now the size that is required. Return it in the error.
+                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => {
+                    return Err(NvmlError::InsufficientSize(Some(count as usize)))
+                }
+                value => nvml_try_for(function, value)?,
+            }
+        }
+
+        items.truncate(count as usize);
+        Ok(items.into_iter().map(Megahertz).collect())
+    }
+
+    /**
+    Gets the current temperature readings for the given sensor, in °C.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid or `sensor` is invalid (shouldn't occur?)
+    * `NotSupported`, if this `Device` does not have the specified sensor
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetTemperature")]
+    pub fn temperature(&self, sensor: TemperatureSensor) -> Result<Celsius, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetTemperature)?;
+
+        unsafe {
+            let mut temp: c_uint = mem::zeroed();
+
+            self.try_for_args(function, &[sensor.as_c() as u64], || {
+                sym(self.device, sensor.as_c(), &mut temp)
+            })?;
+
+            Ok(Celsius(temp))
+        }
+    }
+
+    /**
+    Gets the temperature threshold for this `Device` and the specified `threshold_type`, in °C.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid or `threshold_type` is invalid (shouldn't occur?)
+    * `NotSupported`, if this `Device` does not have a temperature sensor or is unsupported
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetTemperatureThreshold")]
+    pub fn temperature_threshold(
+        &self,
+        threshold_type: TemperatureThreshold,
+    ) -> Result<Celsius, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetTemperatureThreshold)?;
+
+        unsafe {
+            let mut temp: c_uint = mem::zeroed();
+
+            self.try_for_args(function, &[threshold_type.as_c() as u64], || {
+                sym(self.device, threshold_type.as_c(), &mut temp)
+            })?;
+
+            Ok(Celsius(temp))
+        }
+    }
+
+    /**
+    Gets how far the GPU die temperature is below the given threshold, in °C.
+
+    Negative if the temperature is past the threshold. Useful for reporting how
+    close the GPU is to throttling (`Slowdown`, `GpuMax`, `AcousticCurrent`) or
+    shutting down (`Shutdown`).
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid or `threshold_type` is `MemoryMax`,
+      which applies to the memory temperature
+    * `NotSupported`, if this `Device` does not have a temperature sensor or is unsupported
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+    */
+    pub fn temperature_headroom(
+        &self,
+        threshold_type: TemperatureThreshold,
+    ) -> Result<i64, NvmlError> {
+        if threshold_type == TemperatureThreshold::MemoryMax {
+            return Err(NvmlError::InvalidArg);
+        }
+
+        let threshold = self.temperature_threshold(threshold_type)?;
+        let temperature = self.temperature(TemperatureSensor::Gpu)?;
+
+        Ok(i64::from(threshold.0) - i64::from(temperature.0))
+    }
+
+    /**
+    Gets the common ancestor for two devices.
+
+    # Errors
+
+    * `InvalidArg`, if either `Device` is invalid
+    * `NotSupported`, if this `Device` or the OS does not support this feature
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, an error has occurred in the underlying topology discovery
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested
+    #[cfg(target_os = "linux")]
+    #[doc(alias = "nvmlDeviceGetTopologyCommonAncestor")]
+    pub fn topology_common_ancestor(
+        &self,
+        other_device: Device,
+    ) -> Result<TopologyLevel, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetTopologyCommonAncestor)?;
+
+        unsafe {
+            let mut level: nvmlGpuTopologyLevel_t = mem::zeroed();
+
+            self.try_for_args(function, &[other_device.device as u64], || {
+                sym(self.device, other_device.device, &mut level)
+            })?;
+
+            TopologyLevel::try_from(level)
+        }
+    }
+
+    /**
+    Gets the set of GPUs that are nearest to this `Device` at a specific interconnectivity level.
+
+    # Errors
+
+    * `InvalidArg`, if this `Device` is invalid or `level` is invalid (shouldn't occur?)
+    * `NotSupported`, if this `Device` or the OS does not support this feature
+    * `Unknown`, an error has occurred in the underlying topology discovery
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested
+    #[cfg(target_os = "linux")]
+    #[doc(alias = "nvmlDeviceGetTopologyNearestGpus")]
+    pub fn topology_nearest_gpus(
+        &self,
+        level: TopologyLevel,
+    ) -> Result<Vec<Device<'nvml>>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetTopologyNearestGpus)?;
+
+        unsafe {
+            let mut count = match self.top_nearest_gpus_count(&level)? {
+                0 => return Ok(vec![]),
+                value => value,
+            };
+            let mut gpus: Vec<nvmlDevice_t> = vec![mem::zeroed(); count as usize];
+
+            self.try_for_args(function, &[level.as_c() as u64], || {
+                sym(self.device, level.as_c(), &mut count, gpus.as_mut_ptr())
+            })
+            .map_err(|e| e.with_required_size(count))?;
+
+            Ok(gpus
+                .into_iter()
+                .map(|d| Device::new(d, self.nvml))
+                .collect())
+        }
+    }
+
+    // Helper for the above function. Returns # of GPUs in the set.
+    #[cfg(target_os = "linux")]
+    fn top_nearest_gpus_count(&self, level: &TopologyLevel) -> Result<c_uint, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetTopologyNearestGpus)?;
+
+        unsafe {
+            let mut count: c_uint = 0;
+
+            self.try_for_args(function, &[level.as_c() as u64], || {
+                sym(
+                    self.device,
+                    level.as_c(),
+                    &mut count,
+                    // Passing null (I assume?)
+                    // indicates that we want the
+                    // GPU count
+                    ptr::null_mut(),
+                )
+            })?;
+
+            Ok(count)
+        }
+    }
+
+    /**
+    Gets the total ECC error counts for this `Device`.
+
+    Only applicable to devices with ECC. The total error count is the sum of errors across
+    each of the separate memory systems, i.e. the total set of errors across the entire device.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid or either enum is invalid (shouldn't occur?)
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Fermi and newer fully supported devices. Requires `InfoRom::ECC` version 1.0
+    or higher. Requires ECC mode to be enabled.
+    */
+    // Checked against local
+    // Tested on machines other than my own
+    #[doc(alias = "nvmlDeviceGetTotalEccErrors")]
+    pub fn total_ecc_errors(
+        &self,
+        error_type: MemoryError,
+        counter_type: EccCounter,
+    ) -> Result<u64, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetTotalEccErrors)?;
+
+        unsafe {
+            let mut count: c_ulonglong = mem::zeroed();
+
+            self.try_for_args(
+                function,
+                &[error_type.as_c() as u64, counter_type.as_c() as u64],
+                || {
+                    sym(
+                        self.device,
+                        error_type.as_c(),
+                        counter_type.as_c(),
+                        &mut count,
+                    )
+                },
+            )?;
+
+            Ok(count)
+        }
+    }
+
+    /**
+    Gets the globally unique immutable UUID associated with this `Device` as a 5 part
+    hexadecimal string.
+
+    This UUID augments the immutable, board serial identifier. It is a globally unique
+    identifier and is the _only_ available identifier for pre-Fermi-architecture products.
+    It does NOT correspond to any identifier printed on the board.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
+    * `Unknown`, on any unexpected error
+
+    # Examples
+
+    The UUID can be used to compare two `Device`s and find out if they represent
+    the same physical device:
+
+    ```no_run
+    # use nvml_wrapper::Nvml;
+    # use nvml_wrapper::error::*;
+    # fn main() -> Result<(), NvmlError> {
+    # let nvml = Nvml::init()?;
+    # let device1 = nvml.device_by_index(0)?;
+    # let device2 = nvml.device_by_index(1)?;
+    if device1.uuid()? == device2.uuid()? {
+        println!("`device1` represents the same physical device that `device2` does.");
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetUUID")]
+    pub fn uuid(&self) -> Result<String, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetUUID)?;
+
+        unsafe {
+            with_string_buffer(NVML_DEVICE_UUID_V2_BUFFER_SIZE, |buffer, len| {
+                self.try_for(function, || sym(self.device, buffer, len))
+            })
+        }
+    }
+
+    /**
+    Gets the globally unique immutable UUID associated with this `Device`,
+    parsed into a `DeviceUuid`.
+
+    Unlike the strings returned from `.uuid()`, `DeviceUuid`s compare equal
+    regardless of the case NVML happened to use, and for MIG devices tell
+    apart the `MIG-GPU-<uuid>/<gi>/<ci>` and `MIG-<uuid>` spellings. See the
+    `uuid` module.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid Utf8
+    * `InvalidUuid`, if the string obtained from the C function is not a
+      device UUID
+    * `Unknown`, on any unexpected error
+    */
+    // Tested
+    #[doc(alias = "nvmlDeviceGetUUID")]
+    pub fn device_uuid(&self) -> Result<DeviceUuid, NvmlError> {
+        Ok(self.uuid()?.parse()?)
+    }
+
+    /**
+    Gets the current utilization rates for this `Device`'s major subsystems.
+
+    Note: During driver initialization when ECC is enabled, one can see high GPU
+    and memory utilization readings. This is caused by the ECC memory scrubbing
+    mechanism that is performed during driver initialization.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Fermi and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetUtilizationRates")]
+    pub fn utilization_rates(&self) -> Result<Utilization, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetUtilizationRates)?;
+
+        unsafe {
+            let mut utilization: nvmlUtilization_t = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut utilization))?;
+
+            Ok(utilization.into())
+        }
+    }
+
+    /**
+    Gets the VBIOS version of this `Device`.
+
+    The VBIOS version may change from time to time.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the string obtained from the C function is not valid UTF-8
+    * `Unknown`, on any unexpected error
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetVbiosVersion")]
+    pub fn vbios_version(&self) -> Result<String, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetVbiosVersion)?;
+
+        unsafe {
+            with_string_buffer(NVML_DEVICE_VBIOS_VERSION_BUFFER_SIZE, |buffer, len| {
+                self.try_for(function, || sym(self.device, buffer, len))
+            })
+        }
+    }
+
+    /**
+    Gets the properties of the physical GPU backing this `Device` as an
+    opaque ASCII string.
+
+    vGPU hosts export this string so that the vGPU live migration toolchain can
+    check whether a vGPU can be migrated to this GPU.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if vGPU is not supported by the system
+    * `Utf8Error`, if the string obtained from the C function is not valid UTF-8
+    * `Unknown`, on any unexpected error
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    #[doc(alias = "nvmlDeviceGetPgpuMetadataString")]
+    pub fn pgpu_metadata_string(&self) -> Result<String, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPgpuMetadataString)?;
+
+        unsafe {
+            let mut size: c_uint = 0;
+
+            // Passing null with a size of 0 gets us the required size
+            match sym(self.device, ptr::null_mut(), &mut size) {
+                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => {}
+                // If success, the string is empty; otherwise, return error
+                other => return nvml_try_for(function, other).map(|_| String::new()),
+            }
+
+            // One extra byte guarantees that the string is null-terminated
+            let mut metadata_vec = vec![0; size as usize + 1];
+
+            self.try_for(function, || {
+                sym(self.device, metadata_vec.as_mut_ptr(), &mut size)
+            })
+            .map_err(|e| e.with_required_size(size))?;
+
+            let metadata_raw = CStr::from_ptr(metadata_vec.as_ptr());
+            Ok(metadata_raw.to_str()?.into())
+        }
+    }
+
+    /**
+    Gets the IDs of the vGPU types this `Device` supports.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if vGPU is not supported by this `Device`
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetSupportedVgpus")]
+    pub fn supported_vgpu_types(&self) -> Result<Vec<u32>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetSupportedVgpus)?;
+
+        self.vgpu_type_ids(function, |count, ids| unsafe {
+            sym(self.device, count, ids)
+        })
+    }
+
+    /**
+    Gets the IDs of the vGPU types that can currently be created on this
+    `Device`.
+
+    This may be a subset of `.supported_vgpu_types()`, depending on the vGPUs
+    already running; for example, outside of heterogeneous mode only the type
+    already running can be created.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if vGPU is not supported by this `Device`
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetCreatableVgpus")]
+    pub fn creatable_vgpu_types(&self) -> Result<Vec<u32>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetCreatableVgpus)?;
+
+        self.vgpu_type_ids(function, |count, ids| unsafe {
+            sym(self.device, count, ids)
+        })
+    }
+
+    /**
+    Gets the IDs of the vGPU instances currently active on this `Device`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if vGPU is not supported by this `Device`
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetActiveVgpus")]
+    pub fn active_vgpus(&self) -> Result<Vec<u32>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetActiveVgpus)?;
+
+        // Instance IDs and type IDs are both `c_uint`s
+        self.vgpu_type_ids(function, |count, ids| unsafe {
+            sym(self.device, count, ids)
+        })
+    }
+
+    // Removes code duplication in the above functions.
+    fn vgpu_type_ids<F>(&self, function: &'static str, call: F) -> Result<Vec<u32>, NvmlError>
+    where
+        F: Fn(*mut c_uint, *mut nvmlVgpuTypeId_t) -> nvmlReturn_t,
+    {
+        let mut count: c_uint = 0;
+
+        // Passing a count of 0 gets us the required size
+        match call(&mut count, ptr::null_mut()) {
+            nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => {}
+            // If success, there are no vGPU types; otherwise, return error
+            other => return nvml_try_for(function, other).map(|_| vec![]),
+        }
+
+        let mut ids: Vec<nvmlVgpuTypeId_t> = vec![0; count as usize];
+
+        self.try_for(function, || call(&mut count, ids.as_mut_ptr()))
+            .map_err(|e| e.with_required_size(count))?;
+
+        ids.truncate(count as usize);
+        Ok(ids)
+    }
+
+    /**
+    Gets the maximum number of vGPUs of the given type that can be created on
+    this `Device`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` or `vgpu_type` is invalid
+    * `NotSupported`, if vGPU is not supported by this `Device`
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlVgpuTypeGetMaxInstances")]
+    pub fn vgpu_type_max_instances(&self, vgpu_type: u32) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlVgpuTypeGetMaxInstances)?;
+
+        unsafe {
+            let mut count: c_uint = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, vgpu_type, &mut count))?;
+
+            Ok(count)
+        }
+    }
+
+    /**
+    Checks whether or not this `Device` is in vGPU heterogeneous mode, in which
+    it can host time-sliced vGPUs of different types (with differing
+    framebuffer sizes) at the same time.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't support heterogeneous mode
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetVgpuHeterogeneousMode")]
+    pub fn is_vgpu_heterogeneous_mode_enabled(&self) -> Result<bool, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetVgpuHeterogeneousMode)?;
+
+        unsafe {
+            let mut mode = nvmlVgpuHeterogeneousMode_t {
+                version: v1_struct_version::<nvmlVgpuHeterogeneousMode_t>(),
+                mode: 0,
+            };
+
+            self.try_for(function, || sym(self.device, &mut mode))?;
+
+            bool_from_state(mode.mode)
+        }
+    }
+
+    /**
+    Enables or disables vGPU heterogeneous mode on this `Device`.
+
+    The mode can only be changed while no vGPUs are running on this `Device`.
+
+    Requires root/admin permissions.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `InUse`, if vGPUs are running on this `Device`
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `NotSupported`, if MIG mode is enabled or this `Device` doesn't support
+      heterogeneous mode
+    * `Unknown`, on any unexpected error
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceSetVgpuHeterogeneousMode")]
+    pub fn set_vgpu_heterogeneous_mode(&mut self, enabled: bool) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetVgpuHeterogeneousMode)?;
+
+        let mode = nvmlVgpuHeterogeneousMode_t {
+            version: v1_struct_version::<nvmlVgpuHeterogeneousMode_t>(),
+            mode: state_from_bool(enabled),
+        };
+
+        unsafe { nvml_try_for(function, sym(self.device, &mode)) }
+    }
+
+    /**
+    Gets the placements vGPUs of the given type can occupy on this `Device`.
+
+    Placements are returned whether or not this `Device` is in heterogeneous
+    mode; see `.vgpu_type_creatable_placements()` for the ones that are free
+    right now.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` or `vgpu_type` is invalid
+    * `NotSupported`, if this `Device` doesn't support vGPU placements
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetVgpuTypeSupportedPlacements")]
+    pub fn vgpu_type_supported_placements(
+        &self,
+        vgpu_type: u32,
+    ) -> Result<VgpuPlacements, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetVgpuTypeSupportedPlacements)?;
+
+        self.vgpu_type_placements(function, vgpu_type, |list| unsafe {
+            sym(self.device, vgpu_type, list)
+        })
+    }
+
+    /**
+    Gets the placements a new vGPU of the given type could occupy on this
+    `Device` right now, given the vGPUs already running on it.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` or `vgpu_type` is invalid
+    * `NotSupported`, if this `Device` isn't in heterogeneous mode (see
+      `.set_vgpu_heterogeneous_mode()`) or doesn't support `vgpu_type`
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetVgpuTypeCreatablePlacements")]
+    pub fn vgpu_type_creatable_placements(
+        &self,
+        vgpu_type: u32,
+    ) -> Result<VgpuPlacements, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetVgpuTypeCreatablePlacements)?;
+
+        self.vgpu_type_placements(function, vgpu_type, |list| unsafe {
+            sym(self.device, vgpu_type, list)
+        })
+    }
+
+    // Removes code duplication in the above functions.
+    fn vgpu_type_placements<F>(
+        &self,
+        function: &'static str,
+        vgpu_type: u32,
+        call: F,
+    ) -> Result<VgpuPlacements, NvmlError>
+    where
+        F: FnOnce(*mut nvmlVgpuPlacementList_t) -> nvmlReturn_t,
+    {
+        // There are never more placements than instances of the type
+        let max = self.vgpu_type_max_instances(vgpu_type)?;
+        let mut ids: Vec<c_uint> = vec![0; max as usize];
+
+        let mut list = nvmlVgpuPlacementList_t {
+            version: v1_struct_version::<nvmlVgpuPlacementList_t>(),
+            placementSize: 0,
+            count: max,
+            placementIds: ids.as_mut_ptr(),
+        };
+
+        self.try_for(function, || call(&mut list))?;
+
+        ids.truncate(list.count.min(max) as usize);
+
+        Ok(VgpuPlacements {
+            placement_size: list.placementSize,
+            placement_ids: ids,
+        })
+    }
+
+    /**
+    Gets the vGPU software license state of this `Device`.
+
+    Meant for use inside guest VMs: an agent can check whether the guest is
+    licensed and when its license expires, and warn before an unlicensed guest
+    loses performance at the end of the licensing grace period.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Utf8Error`, if a string obtained from the C function is not valid UTF-8
+    * `Unknown`, on any unexpected error
+
+    # Environment Support
+
+    Only meaningful in vGPU guests.
+    */
+    // Checked against local
+    #[doc(alias = "nvmlDeviceGetGridLicensableFeatures_v4")]
+    pub fn licensable_features(&self) -> Result<LicensableFeatures, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetGridLicensableFeatures_v4)?;
+
+        unsafe {
+            let mut features: nvmlGridLicensableFeatures_t = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut features))?;
+
+            LicensableFeatures::try_from(features)
+        }
+    }
+
+    /**
+    Gets the duration of time during which this `Device` was throttled (lower than the
+    requested clocks) due to power or thermal constraints.
+
+    This is important to users who are trying to understand if their GPUs throttle at any
+    point while running applications. The difference in violation times at two different
+    reference times gives the indication of a GPU throttling event.
+
+    Violation for thermal capping is not supported at this time.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid or `perf_policy` is invalid (shouldn't occur?)
+    * `NotSupported`, if this query is not supported by this `Device`
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+
+    # Device Support
+
+    Supports Kepler or newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetViolationStatus")]
+    pub fn violation_status(
+        &self,
+        perf_policy: PerformancePolicy,
+    ) -> Result<ViolationTime, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetViolationStatus)?;
+        unsafe {
+            let mut viol_time: nvmlViolationTime_t = mem::zeroed();
+
+            self.try_for_args(function, &[perf_policy.as_c() as u64], || {
+                sym(self.device, perf_policy.as_c(), &mut viol_time)
+            })?;
+
+            Ok(viol_time.into())
+        }
+    }
+
+    /**
+    Gets the interrupt number for this [`Device`].
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotSupported`, if this query is not supported by this `Device`
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    */
+    #[doc(alias = "nvmlDeviceGetIrqNum")]
+    pub fn irq_num(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetIrqNum)?;
+
+        let irq_num = unsafe {
+            let mut irq_num: c_uint = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut irq_num))?;
+
+            irq_num
+        };
+
+        Ok(irq_num)
+    }
+
+    /**
+    Gets the core count for this [`Device`].
+
+    The cores represented in the count here are commonly referred to as
+    "CUDA cores".
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotSupported`, if this query is not supported by this `Device`
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    */
+    #[doc(alias = "nvmlDeviceGetNumGpuCores")]
+    pub fn num_cores(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetNumGpuCores)?;
+
+        unsafe {
+            let mut count: c_uint = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut count))?;
+
+            Ok(count)
+        }
+    }
+
+    /**
+    Gets the power source of this [`Device`].
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotSupported`, if this query is not supported by this `Device`
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    */
+    #[doc(alias = "nvmlDeviceGetPowerSource")]
+    pub fn power_source(&self) -> Result<PowerSource, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPowerSource)?;
+
+        let power_source_c = unsafe {
+            let mut power_source: nvmlPowerSource_t = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut power_source))?;
+
+            power_source
+        };
+
+        PowerSource::try_from(power_source_c)
+    }
+
+    /**
+    Gets the memory bus width of this [`Device`].
+
+    The returned value is in bits (i.e. 320 for a 320-bit bus width).
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotSupported`, if this query is not supported by this `Device`
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    */
+    #[doc(alias = "nvmlDeviceGetMemoryBusWidth")]
+    pub fn memory_bus_width(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetMemoryBusWidth)?;
+
+        let memory_bus_width = unsafe {
+            let mut memory_bus_width: c_uint = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut memory_bus_width))?;
+
+            memory_bus_width
+        };
+
+        Ok(memory_bus_width)
+    }
+
+    /**
+    Gets the max PCIe link speed for this [`Device`].
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotSupported`, if this query is not supported by this `Device`
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    */
+    #[doc(alias = "nvmlDeviceGetPcieLinkMaxSpeed")]
+    pub fn pcie_link_max_speed(&self) -> Result<PcieLinkMaxSpeed, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetPcieLinkMaxSpeed)?;
+
+        let pcie_link_max_speed_c = unsafe {
+            let mut pcie_link_max_speed: c_uint = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut pcie_link_max_speed))?;
+
+            pcie_link_max_speed
+        };
+
+        PcieLinkMaxSpeed::try_from(pcie_link_max_speed_c)
+    }
+
+    /**
+    Gets the type of bus by which this [`Device`] is connected.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    */
+    #[doc(alias = "nvmlDeviceGetBusType")]
+    pub fn bus_type(&self) -> Result<BusType, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetBusType)?;
+
+        let bus_type_c = unsafe {
+            let mut bus_type: nvmlBusType_t = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut bus_type))?;
+
+            bus_type
+        };
+
+        BusType::try_from(bus_type_c)
+    }
+
+    /**
+    Gets the workload power profiles supported by this `Device`, along with
+    their priorities and the profiles each one conflicts with.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Blackwell and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceWorkloadPowerProfileGetProfilesInfo")]
+    pub fn workload_power_profiles(&self) -> Result<Vec<WorkloadPowerProfileInfo>, NvmlError> {
+        let (sym, function) =
+            nvml_fn!(self.nvml.lib, nvmlDeviceWorkloadPowerProfileGetProfilesInfo)?;
+
+        let info = unsafe {
+            let mut info: nvmlWorkloadPowerProfileProfilesInfo_t = mem::zeroed();
+            info.version = v1_struct_version::<nvmlWorkloadPowerProfileProfilesInfo_t>();
+
+            self.try_for(function, || sym(self.device, &mut info))?;
+
+            info
+        };
+
+        (0..NVML_WORKLOAD_POWER_MAX_PROFILES)
+            .filter(|&id| mask_bit(&info.perfProfilesMask, id))
+            .map(|id| WorkloadPowerProfileInfo::try_from(info.perfProfile[id as usize]))
+            .collect()
+    }
+
+    /**
+    Gets the workload power profiles supported by, requested for, and enforced
+    on this `Device`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Blackwell and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceWorkloadPowerProfileGetCurrentProfiles")]
+    pub fn current_workload_power_profiles(&self) -> Result<CurrentPowerProfiles, NvmlError> {
+        let (sym, function) = nvml_fn!(
+            self.nvml.lib,
+            nvmlDeviceWorkloadPowerProfileGetCurrentProfiles
+        )?;
+
+        unsafe {
+            let mut profiles: nvmlWorkloadPowerProfileCurrentProfiles_t = mem::zeroed();
+            profiles.version = v1_struct_version::<nvmlWorkloadPowerProfileCurrentProfiles_t>();
+
+            self.try_for(function, || sym(self.device, &mut profiles))?;
+
+            CurrentPowerProfiles::try_from(profiles)
+        }
+    }
+
+    /**
+    Gets information about this `Device`'s registration with the NVSwitch
+    fabric: the cluster and clique (partition) it belongs to, and how far along
+    registration with the fabric manager is.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    If registration completed with an error, that error is returned.
+
+    # Device Support
+
+    Supports Hopper and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetGpuFabricInfo")]
+    pub fn fabric_info(&self) -> Result<FabricInfo, NvmlError> {
+        FabricInfo::try_from(self.raw_fabric_info()?)
+    }
+
+    /**
+    Gets the other devices in the same NVSwitch cluster and clique (partition)
+    as this `Device`.
+
+    These are the devices this `Device` can reach over NVLink through the
+    fabric. Devices whose registration with the fabric manager hasn't
+    completed, or completed with an error, are left out.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `NoData`, if this `Device`'s registration with the fabric manager hasn't
+      completed yet
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    If this `Device`'s registration completed with an error, that error is
+    returned. Errors from `Nvml.devices()` are returned as well.
+
+    # Device Support
+
+    Supports Hopper and newer fully supported devices.
+    */
+    // Tested
+    pub fn fabric_peers(&self) -> Result<Vec<Device<'nvml>>, NvmlError> {
+        let info = self.fabric_info()?;
+
+        match info.state {
+            FabricState::Completed => {}
+            FabricState::NotSupported => {
+                return Err(NvmlError::NotSupported(Some(
+                    UnsupportedContext::for_function("nvmlDeviceGetGpuFabricInfo"),
+                )))
+            }
+            FabricState::NotStarted | FabricState::InProgress => return Err(NvmlError::NoData),
+        }
+
+        let mut peers = vec![];
+
+        for device in self.nvml.devices()? {
+            let device = device?;
+
+            if device == *self {
+                continue;
+            }
+
+            let peer = match device.raw_fabric_info() {
+                Ok(peer) => peer,
+                Err(NvmlError::NotSupported(_)) => continue,
+                Err(e) => return Err(e),
+            };
+
+            if u32::from(peer.state) == NVML_GPU_FABRIC_STATE_COMPLETED
+                && peer.status == nvmlReturn_enum_NVML_SUCCESS
+                && peer.clusterUuid == info.cluster_uuid.0
+                && peer.cliqueId == info.clique_id
+            {
+                peers.push(device);
+            }
+        }
+
+        Ok(peers)
+    }
+
+    // Helper for the above functions. Returns the struct as-is so that peers
+    // whose registration failed can be skipped.
+    fn raw_fabric_info(&self) -> Result<nvmlGpuFabricInfo_t, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetGpuFabricInfo)?;
+
+        unsafe {
+            let mut info: nvmlGpuFabricInfo_t = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut info))?;
+
+            Ok(info)
+        }
+    }
+
+    /**
+    Gets the values that identify this `Device` and its firmware in one go.
+
+    This is the set of values inventory tooling typically collects: name, UUID,
+    serial, PCI info, minor number, architecture, brand, VBIOS version and
+    infoROM image version. None of them change while the driver is loaded, so
+    the result is cached by the `Nvml` instance this `Device` came from and
+    later calls for the same GPU (however it was looked up) make no NVML calls.
+
+    Values that this `Device` doesn't support querying are `None` rather than
+    causing an error; see `DeviceIdentity` for which ones may be missing.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` doesn't support one of the required values
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Utf8Error`, if a string obtained from NVML is not valid Utf8
+    * `Unknown`, on any unexpected error
+    */
+    pub fn identity(&self) -> Result<DeviceIdentity, NvmlError> {
+        let key = self.device as usize;
+
+        if let Some(identity) = self.cached_identities().get(&key) {
+            return Ok(identity.clone());
+        }
+
+        #[cfg(target_os = "linux")]
+        let minor_number = self.minor_number().optional()?;
+        #[cfg(not(target_os = "linux"))]
+        let minor_number = None;
+
+        let identity = DeviceIdentity {
+            name: self.name()?,
+            uuid: self.uuid()?,
+            serial: self.serial().optional()?,
+            pci_info: self.pci_info()?,
+            minor_number,
+            architecture: self.architecture()?,
+            brand: self.brand()?,
+            vbios_version: self.vbios_version()?,
+            info_rom_image_version: self.info_rom_image_version().optional()?,
+        };
+
+        self.cached_identities().insert(key, identity.clone());
+
+        Ok(identity)
+    }
+
+    // Like `nvml_try_for()`, but doesn't make `call` while a previous
+    // `NotSupported` result for `function` is cached.
+    fn try_for<F>(&self, function: &'static str, call: F) -> Result<(), NvmlError>
+    where
+        F: FnOnce() -> nvmlReturn_t,
+    {
+        self.try_for_args(function, &[], call)
+    }
+
+    // As above, for functions whose support depends on their input `args`
+    // (e.g. the clock type).
+    fn try_for_args<F>(
+        &self,
+        function: &'static str,
+        args: &[u64],
+        call: F,
+    ) -> Result<(), NvmlError>
+    where
+        F: FnOnce() -> nvmlReturn_t,
+    {
+        let cache = &self.nvml.not_supported;
+        let now = Instant::now();
+
+        let wsl2 = self.nvml.wsl2 && wsl::is_unavailable(function);
+        let not_supported = || {
+            NvmlError::NotSupported(Some(UnsupportedContext {
+                wsl2,
+                ..UnsupportedContext::for_function(function)
+            }))
+        };
+
+        if cache.contains(self.device as usize, function, args, now) {
+            return Err(not_supported());
+        }
+
+        let result = match nvml_try_for(function, call()) {
+            // Other errors are left alone; they may well be genuine
+            Err(NvmlError::NotSupported(_)) if wsl2 => Err(not_supported()),
+            other => other,
+        };
+
+        if let Err(NvmlError::NotSupported(_)) = result {
+            cache.insert(self.device as usize, function, args, now);
+        }
+
+        result
+    }
+
+    fn cached_identities(&self) -> MutexGuard<'_, HashMap<usize, DeviceIdentity>> {
+        // The map is never left in an inconsistent state, so poisoning is moot
+        self.nvml
+            .identities
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /**
+    Gets the architecture of this [`Device`].
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    */
+    #[doc(alias = "nvmlDeviceGetArchitecture")]
+    pub fn architecture(&self) -> Result<DeviceArchitecture, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetArchitecture)?;
+
+        let architecture_c = unsafe {
+            let mut architecture: nvmlDeviceArchitecture_t = mem::zeroed();
+
+            self.try_for(function, || sym(self.device, &mut architecture))?;
+
+            architecture
+        };
+
+        DeviceArchitecture::try_from(architecture_c)
+    }
+
+    /**
+    Checks if this `Device` and the passed-in device are on the same physical board.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if either `Device` is invalid
+    * `NotSupported`, if this check is not supported by this `Device`
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceOnSameBoard")]
+    pub fn is_on_same_board_as(&self, other_device: &Device) -> Result<bool, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceOnSameBoard)?;
+
+        unsafe {
+            let mut bool_int: c_int = mem::zeroed();
+
+            self.try_for_args(function, &[other_device.handle() as u64], || {
+                sym(self.device, other_device.handle(), &mut bool_int)
+            })?;
+
+            #[allow(clippy::match_like_matches_macro)]
+            Ok(match bool_int {
+                0 => false,
+                _ => true,
+            })
+        }
+    }
+
+    /**
+    Resets the application clock to the default value.
+
+    This is the applications clock that will be used after a system reboot or a driver
+    reload. The default value is a constant, but the current value be changed with
+    `.set_applications_clocks()`.
+
+    On Pascal and newer hardware, if clocks were previously locked with
+    `.set_applications_clocks()`, this call will unlock clocks. This returns clocks
+    to their default behavior of automatically boosting above base clocks as
+    thermal limits allow.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Fermi and newer non-GeForce fully supported devices and Maxwell or newer
+    GeForce devices.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceResetApplicationsClocks")]
+    pub fn reset_applications_clocks(&mut self) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceResetApplicationsClocks)?;
+
+        unsafe { nvml_try_for(function, sym(self.device)) }
+    }
+
+    /**
+    Try to set the current state of auto boosted clocks on this `Device`.
+
+    Auto boosted clocks are enabled by default on some hardware, allowing the GPU to run
+    as fast as thermals will allow it to. Auto boosted clocks should be disabled if fixed
+    clock rates are desired.
+
+    On Pascal and newer hardware, auto boosted clocks are controlled through application
+    clocks. Use `.set_applications_clocks()` and `.reset_applications_clocks()` to control
+    auto boost behavior.
+
+    Non-root users may use this API by default, but access can be restricted by root using
+    `.set_api_restriction()`.
+
+    Note: persistence mode is required to modify the curent auto boost settings and
+    therefore must be enabled.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support auto boosted clocks
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    Not sure why nothing is said about `NoPermission`.
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceSetAutoBoostedClocksEnabled")]
+    pub fn set_auto_boosted_clocks(&mut self, enabled: bool) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetAutoBoostedClocksEnabled)?;
+
+        unsafe { nvml_try_for(function, sym(self.device, state_from_bool(enabled))) }
+    }
+
+    /**
+    Sets the ideal affinity for the calling thread and `Device` based on the guidelines given in
+    `.cpu_affinity()`.
+
+    Currently supports up to 64 processors.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[cfg(target_os = "linux")]
+    #[doc(alias = "nvmlDeviceSetCpuAffinity")]
+    pub fn set_cpu_affinity(&mut self) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetCpuAffinity)?;
+
+        unsafe { nvml_try_for(function, sym(self.device)) }
+    }
+
+    /**
+    Pins the calling thread to the CPUs closest to this `Device`.
+
+    Combines `.cpu_affinity()` with a direct call to `sched_setaffinity`. Unlike
+    `.set_cpu_affinity()`, this does not require a mutable `Device`, making it
+    convenient for placing latency-sensitive I/O threads on the socket local to
+    a GPU. Only the first 1024 CPUs, as many as a glibc `cpu_set_t` holds, are
+    considered.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `SetAffinityFailed`, if the `sched_setaffinity` call fails
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler or newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    #[cfg(target_os = "linux")]
+    pub fn set_current_thread_affinity(&self) -> Result<(), NvmlError> {
+        extern "C" {
+            // From the C library; a `pid` of 0 refers to the calling thread
+            fn sched_setaffinity(pid: c_int, cpusetsize: usize, mask: *const c_ulong) -> c_int;
+        }
+
+        // The number of CPUs a glibc `cpu_set_t` can describe
+        const CPU_SETSIZE: usize = 1024;
+
+        let mask = self.cpu_affinity(CPU_SETSIZE / (8 * mem::size_of::<c_ulong>()))?;
+
+        unsafe {
+            let words = mask.words();
+
+            match sched_setaffinity(0, mem::size_of_val(words), words.as_ptr()) {
+                0 => Ok(()),
+                _ => Err(NvmlError::SetAffinityFailed(io::Error::last_os_error())),
+            }
+        }
+    }
+
+    /**
+    Try to set the default state of auto boosted clocks on this `Device`.
+
+    This is the default state that auto boosted clocks will return to when no compute
+    processes (e.g. CUDA application with an active context) are running.
+
+    Requires root/admin permissions.
+
+    Auto boosted clocks are enabled by default on some hardware, allowing the GPU to run
+    as fast as thermals will allow it to. Auto boosted clocks should be disabled if fixed
+    clock rates are desired.
+
+    On Pascal and newer hardware, auto boosted clocks are controlled through application
+    clocks. Use `.set_applications_clocks()` and `.reset_applications_clocks()` to control
+    auto boost behavior.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NoPermission`, if the calling user does not have permission to change the default state
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support auto boosted clocks
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Kepler or newer non-GeForce fully supported devices and Maxwell or newer
+    GeForce devices.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceSetDefaultAutoBoostedClocksEnabled")]
+    pub fn set_auto_boosted_clocks_default(&mut self, enabled: bool) -> Result<(), NvmlError> {
+        let (sym, function) =
+            nvml_fn!(self.nvml.lib, nvmlDeviceSetDefaultAutoBoostedClocksEnabled)?;
+
+        unsafe {
+            // Passing 0 because NVIDIA says flags are not supported yet
+            nvml_try_for(function, sym(self.device, state_from_bool(enabled), 0))
+        }
+    }
+
+    /**
+    Reads the infoROM from this `Device`'s flash and verifies the checksum.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `CorruptedInfoROM`, if this `Device`'s infoROM is corrupted
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
 
-            nvml_try(sym(self.device, &mut memory_bus_width))?;
+    Not sure why `InvalidArg` is not mentioned.
 
-            memory_bus_width
-        };
+    # Device Support
 
-        Ok(memory_bus_width)
+    Supports all devices with an infoROM.
+    */
+    // Checked against local
+    // Tested on machines other than my own
+    #[doc(alias = "nvmlDeviceValidateInforom")]
+    pub fn validate_info_rom(&self) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceValidateInforom)?;
+
+        unsafe { nvml_try_for(function, sym(self.device)) }
     }
 
+    // Wrappers for things from Accounting Statistics now
+
     /**
-    Gets the max PCIe link speed for this [`Device`].
+    Clears accounting information about all processes that have already terminated.
+
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotSupported`, if this query is not supported by this `Device`
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
-    */
-    #[doc(alias = "nvmlDeviceGetPcieLinkMaxSpeed")]
-    pub fn pcie_link_max_speed(&self) -> Result<PcieLinkMaxSpeed, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetPcieLinkMaxSpeed.as_ref())?;
-
-        let pcie_link_max_speed_c = unsafe {
-            let mut pcie_link_max_speed: c_uint = mem::zeroed();
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `Unknown`, on any unexpected error
 
-            nvml_try(sym(self.device, &mut pcie_link_max_speed))?;
+    # Device Support
 
-            pcie_link_max_speed
-        };
+    Supports Kepler and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceClearAccountingPids")]
+    pub fn clear_accounting_pids(&mut self) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceClearAccountingPids)?;
 
-        PcieLinkMaxSpeed::try_from(pcie_link_max_speed_c)
+        unsafe { nvml_try_for(function, sym(self.device)) }
     }
 
     /**
-    Gets the type of bus by which this [`Device`] is connected.
+    Gets the number of processes that the circular buffer with accounting PIDs can hold
+    (in number of elements).
+
+    This is the max number of processes that accounting information will be stored for
+    before the oldest process information will get overwritten by information
+    about new processes.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    */
-    #[doc(alias = "nvmlDeviceGetBusType")]
-    pub fn bus_type(&self) -> Result<BusType, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetBusType.as_ref())?;
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature or accounting mode
+    is disabled
+    * `Unknown`, on any unexpected error
 
-        let bus_type_c = unsafe {
-            let mut bus_type: nvmlBusType_t = mem::zeroed();
+    # Device Support
 
-            nvml_try(sym(self.device, &mut bus_type))?;
+    Supports Kepler and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetAccountingBufferSize")]
+    pub fn accounting_buffer_size(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetAccountingBufferSize)?;
 
-            bus_type
-        };
+        unsafe {
+            let mut count: c_uint = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut count))?;
 
-        BusType::try_from(bus_type_c)
+            Ok(count)
+        }
     }
 
     /**
-    Gets the architecture of this [`Device`].
+    Gets whether or not per-process accounting mode is enabled.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    */
-    #[doc(alias = "nvmlDeviceGetArchitecture")]
-    pub fn architecture(&self) -> Result<DeviceArchitecture, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetArchitecture.as_ref())?;
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
 
-        let architecture_c = unsafe {
-            let mut architecture: nvmlDeviceArchitecture_t = mem::zeroed();
+    # Device Support
 
-            nvml_try(sym(self.device, &mut architecture))?;
+    Supports Kepler and newer fully supported devices.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlDeviceGetAccountingMode")]
+    pub fn is_accounting_enabled(&self) -> Result<bool, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetAccountingMode)?;
 
-            architecture
-        };
+        unsafe {
+            let mut state: nvmlEnableState_t = mem::zeroed();
+            self.try_for(function, || sym(self.device, &mut state))?;
 
-        DeviceArchitecture::try_from(architecture_c)
+            bool_from_state(state)
+        }
     }
 
     /**
-    Checks if this `Device` and the passed-in device are on the same physical board.
+    Gets the list of processes that can be queried for accounting stats.
+
+    The list of processes returned can be in running or terminated state. Note that
+    in the case of a PID collision some processes might not be accessible before
+    the circular buffer is full.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if either `Device` is invalid
-    * `NotSupported`, if this check is not supported by this `Device`
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `InvalidArg`, if the `Device` is invalid
+    * `NotSupported`, if this `Device` does not support this feature or accounting
+    mode is disabled
     * `Unknown`, on any unexpected error
     */
     // Checked against local
     // Tested
-    #[doc(alias = "nvmlDeviceOnSameBoard")]
-    pub fn is_on_same_board_as(&self, other_device: &Device) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceOnSameBoard.as_ref())?;
+    #[doc(alias = "nvmlDeviceGetAccountingPids")]
+    pub fn accounting_pids(&self) -> Result<Vec<u32>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetAccountingPids)?;
 
         unsafe {
-            let mut bool_int: c_int = mem::zeroed();
+            let mut count = match self.accounting_pids_count()? {
+                0 => return Ok(vec![]),
+                value => value,
+            };
+            let mut pids: Vec<c_uint> = vec![mem::zeroed(); count as usize];
 
-            nvml_try(sym(self.device, other_device.handle(), &mut bool_int))?;
+            self.try_for(function, || sym(self.device, &mut count, pids.as_mut_ptr()))
+                .map_err(|e| e.with_required_size(count))?;
 
-            #[allow(clippy::match_like_matches_macro)]
-            Ok(match bool_int {
-                0 => false,
-                _ => true,
-            })
+            Ok(pids)
+        }
+    }
+
+    // Helper function for the above.
+    fn accounting_pids_count(&self) -> Result<c_uint, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetAccountingPids)?;
+
+        unsafe {
+            // Indicates that we want the count
+            let mut count: c_uint = 0;
+
+            // Null also indicates that we want the count
+            match sym(self.device, &mut count, ptr::null_mut()) {
+                // List is empty
+                nvmlReturn_enum_NVML_SUCCESS => Ok(0),
+                // Count is set to pids count
+                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => Ok(count),
+                // We know this is an error
+                other => self.try_for(function, || other).map(|_| 0),
+            }
         }
     }
 
     /**
-    Resets the application clock to the default value.
+    Gets a process's accounting stats.
 
-    This is the applications clock that will be used after a system reboot or a driver
-    reload. The default value is a constant, but the current value be changed with
-    `.set_applications_clocks()`.
+    Accounting stats capture GPU utilization and other statistics across the lifetime
+    of a process. Accounting stats can be queried during the lifetime of the process
+    and after its termination. The `time` field in `AccountingStats` is reported as
+    zero during the lifetime of the process and updated to the actual running time
+    after its termination.
 
-    On Pascal and newer hardware, if clocks were previously locked with
-    `.set_applications_clocks()`, this call will unlock clocks. This returns clocks
-    to their default behavior of automatically boosting above base clocks as
-    thermal limits allow.
+    Accounting stats are kept in a circular buffer; newly created processes overwrite
+    information regarding old processes.
+
+    Note:
+    * Accounting mode needs to be on. See `.is_accounting_enabled()`.
+    * Only compute and graphics applications stats can be queried. Monitoring
+    applications can't be queried since they don't contribute to GPU utilization.
+    * If a PID collision occurs, the stats of the latest process (the one that
+    terminated last) will be reported.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if the `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `NotFound`, if the process stats were not found
+    * `NotSupported`, if this `Device` does not support this feature or accounting
+    mode is disabled
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Fermi and newer non-GeForce fully supported devices and Maxwell or newer
-    GeForce devices.
+    Suports Kepler and newer fully supported devices.
+
+    # Warning
+
+    On Kepler devices, per-process stats are accurate _only if_ there's one process
+    running on this `Device`.
     */
     // Checked against local
-    // Tested (no-run)
-    #[doc(alias = "nvmlDeviceResetApplicationsClocks")]
-    pub fn reset_applications_clocks(&mut self) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceResetApplicationsClocks.as_ref())?;
+    // Tested (for error)
+    #[doc(alias = "nvmlDeviceGetAccountingStats")]
+    pub fn accounting_stats_for(&self, process_id: u32) -> Result<AccountingStats, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetAccountingStats)?;
 
-        unsafe { nvml_try(sym(self.device)) }
-    }
+        unsafe {
+            let mut stats: nvmlAccountingStats_t = mem::zeroed();
 
-    /**
-    Try to set the current state of auto boosted clocks on this `Device`.
+            self.try_for(function, || sym(self.device, process_id, &mut stats))?;
 
-    Auto boosted clocks are enabled by default on some hardware, allowing the GPU to run
-    as fast as thermals will allow it to. Auto boosted clocks should be disabled if fixed
-    clock rates are desired.
+            Ok(stats.into())
+        }
+    }
 
-    On Pascal and newer hardware, auto boosted clocks are controlled through application
-    clocks. Use `.set_applications_clocks()` and `.reset_applications_clocks()` to control
-    auto boost behavior.
+    /**
+    Enables or disables per-process accounting.
 
-    Non-root users may use this API by default, but access can be restricted by root using
-    `.set_api_restriction()`.
+    Requires root/admin permissions.
 
-    Note: persistence mode is required to modify the curent auto boost settings and
-    therefore must be enabled.
+    Note:
+    * This setting is not persistent and will default to disabled after the driver
+    unloads. Enable persistence mode to be sure the setting doesn't switch off
+    to disabled.
+    * Enabling accounting mode has no negative impact on GPU performance.
+    * Disabling accounting clears accounting information for all PIDs
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if the `Device` is invalid
-    * `NotSupported`, if this `Device` does not support auto boosted clocks
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `NotSupported`, if this `Device` does not support this feature
+    * `NoPermission`, if the user doesn't have permission to perform this operation
     * `Unknown`, on any unexpected error
 
-    Not sure why nothing is said about `NoPermission`.
-
     # Device Support
 
     Supports Kepler and newer fully supported devices.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetAutoBoostedClocksEnabled")]
-    pub fn set_auto_boosted_clocks(&mut self, enabled: bool) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetAutoBoostedClocksEnabled.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetAccountingMode")]
+    pub fn set_accounting(&mut self, enabled: bool) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetAccountingMode)?;
 
-        unsafe { nvml_try(sym(self.device, state_from_bool(enabled))) }
+        unsafe { nvml_try_for(function, sym(self.device, state_from_bool(enabled))) }
     }
 
+    // Device commands starting here
+
     /**
-    Sets the ideal affinity for the calling thread and `Device` based on the guidelines given in
-    `.cpu_affinity()`.
+    Clears the ECC error and other memory error counts for this `Device`.
 
-    Currently supports up to 64 processors.
+    Sets all of the specified ECC counters to 0, including both detailed and total counts.
+    This operation takes effect immediately.
+
+    Requires root/admin permissions and ECC mode to be enabled.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid
+    * `InvalidArg`, if the `Device` is invalid or `counter_type` is invalid (shouldn't occur?)
     * `NotSupported`, if this `Device` does not support this feature
+    * `NoPermission`, if the user doesn't have permission to perform this operation
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
-
-    # Platform Support
-
-    Only supports Linux.
+    Supports Kepler and newer fully supported devices. Only applicable to devices with
+    ECC. Requires `InfoRom::ECC` version 2.0 or higher to clear aggregate
+    location-based ECC counts. Requires `InfoRom::ECC` version 1.0 or higher to
+    clear all other ECC counts.
     */
     // Checked against local
     // Tested (no-run)
-    #[cfg(target_os = "linux")]
-    #[doc(alias = "nvmlDeviceSetCpuAffinity")]
-    pub fn set_cpu_affinity(&mut self) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetCpuAffinity.as_ref())?;
-
-        unsafe { nvml_try(sym(self.device)) }
-    }
-
-    /**
-    Try to set the default state of auto boosted clocks on this `Device`.
-
-    This is the default state that auto boosted clocks will return to when no compute
-    processes (e.g. CUDA application with an active context) are running.
+    #[doc(alias = "nvmlDeviceClearEccErrorCounts")]
+    pub fn clear_ecc_error_counts(&mut self, counter_type: EccCounter) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceClearEccErrorCounts)?;
 
-    Requires root/admin permissions.
+        unsafe { nvml_try_for(function, sym(self.device, counter_type.as_c())) }
+    }
 
-    Auto boosted clocks are enabled by default on some hardware, allowing the GPU to run
-    as fast as thermals will allow it to. Auto boosted clocks should be disabled if fixed
-    clock rates are desired.
+    /**
+    Changes the root/admin restrictions on certain APIs.
 
-    On Pascal and newer hardware, auto boosted clocks are controlled through application
-    clocks. Use `.set_applications_clocks()` and `.reset_applications_clocks()` to control
-    auto boost behavior.
+    This method can be used by a root/admin user to give non root/admin users access
+    to certain otherwise-restricted APIs. The new setting lasts for the lifetime of
+    the NVIDIA driver; it is not persistent. See `.is_api_restricted()` to query
+    current settings.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NoPermission`, if the calling user does not have permission to change the default state
-    * `InvalidArg`, if the `Device` is invalid
-    * `NotSupported`, if this `Device` does not support auto boosted clocks
+    * `InvalidArg`, if the `Device` is invalid or `api_type` is invalid (shouldn't occur?)
+    * `NotSupported`, if this `Device` does not support changing API restrictions or
+    this `Device` does not support the feature that API restrictions are being set for
+    (e.g. enabling/disabling auto boosted clocks is not supported by this `Device`).
+    * `NoPermission`, if the user doesn't have permission to perform this operation
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler or newer non-GeForce fully supported devices and Maxwell or newer
-    GeForce devices.
+    Supports Kepler and newer fully supported devices.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetDefaultAutoBoostedClocksEnabled")]
-    pub fn set_auto_boosted_clocks_default(&mut self, enabled: bool) -> Result<(), NvmlError> {
-        let sym = nvml_sym(
-            self.nvml
-                .lib
-                .nvmlDeviceSetDefaultAutoBoostedClocksEnabled
-                .as_ref(),
-        )?;
+    #[doc(alias = "nvmlDeviceSetAPIRestriction")]
+    pub fn set_api_restricted(&mut self, api_type: Api, restricted: bool) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetAPIRestriction)?;
 
         unsafe {
-            // Passing 0 because NVIDIA says flags are not supported yet
-            nvml_try(sym(self.device, state_from_bool(enabled), 0))
+            nvml_try_for(
+                function,
+                sym(self.device, api_type.as_c(), state_from_bool(restricted)),
+            )
         }
     }
 
     /**
-    Reads the infoROM from this `Device`'s flash and verifies the checksum.
+    Sets clocks that applications will lock to.
+
+    Sets the clocks that compute and graphics applications will be running at. e.g.
+    CUDA driver requests these clocks during context creation which means this
+    property defines clocks at which CUDA applications will be running unless some
+    overspec event occurs (e.g. over power, over thermal or external HW brake).
+
+    Can be used as a setting to request constant performance. Requires root/admin
+    permissions.
+
+    On Pascal and newer hardware, this will automatically disable automatic boosting
+    of clocks. On K80 and newer Kepler and Maxwell GPUs, users desiring fixed performance
+    should also call `.set_auto_boosted_clocks(false)` to prevent clocks from automatically
+    boosting above the clock value being set here.
+
+    You can determine valid `mem_clock` and `graphics_clock` arg values via
+    [`Self::supported_memory_clocks()`] and [`Self::supported_graphics_clocks()`].
+
+    Note that after a system reboot or driver reload applications clocks go back
+    to their default value.
+
+    See also [`Self::set_mem_locked_clocks()`].
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `CorruptedInfoROM`, if this `Device`'s infoROM is corrupted
+    * `InvalidArg`, if the `Device` is invalid or the clocks are not a valid combo
     * `NotSupported`, if this `Device` does not support this feature
+    * `NoPermission`, if the user doesn't have permission to perform this operation
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
-    Not sure why `InvalidArg` is not mentioned.
-
     # Device Support
 
-    Supports all devices with an infoROM.
+    Supports Kepler and newer non-GeForce fully supported devices and Maxwell or newer
+    GeForce devices.
     */
     // Checked against local
-    // Tested on machines other than my own
-    #[doc(alias = "nvmlDeviceValidateInforom")]
-    pub fn validate_info_rom(&self) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceValidateInforom.as_ref())?;
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceSetApplicationsClocks")]
+    pub fn set_applications_clocks(
+        &mut self,
+        mem_clock: Megahertz,
+        graphics_clock: Megahertz,
+    ) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetApplicationsClocks)?;
 
-        unsafe { nvml_try(sym(self.device)) }
+        unsafe { nvml_try_for(function, sym(self.device, mem_clock.0, graphics_clock.0)) }
     }
 
-    // Wrappers for things from Accounting Statistics now
-
     /**
-    Clears accounting information about all processes that have already terminated.
+    Sets the compute mode for this `Device`.
+
+    The compute mode determines whether a GPU can be used for compute operations
+    and whether it can be shared across contexts.
+
+    This operation takes effect immediately. Under Linux it is not persistent
+    across reboots and always resets to `Default`. Under Windows it is
+    persistent.
+
+    Under Windows, compute mode may only be set to `Default` when running in WDDM
+    (physical display connected).
 
     Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid
+    * `InvalidArg`, if the `Device` is invalid or `mode` is invalid (shouldn't occur?)
     * `NotSupported`, if this `Device` does not support this feature
     * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
-
-    # Device Support
-
-    Supports Kepler and newer fully supported devices.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceClearAccountingPids")]
-    pub fn clear_accounting_pids(&mut self) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceClearAccountingPids.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetComputeMode")]
+    pub fn set_compute_mode(&mut self, mode: ComputeMode) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetComputeMode)?;
 
-        unsafe { nvml_try(sym(self.device)) }
+        unsafe { nvml_try_for(function, sym(self.device, mode.as_c())) }
     }
 
     /**
-    Gets the number of processes that the circular buffer with accounting PIDs can hold
-    (in number of elements).
+    Sets the driver model for this `Device`.
 
-    This is the max number of processes that accounting information will be stored for
-    before the oldest process information will get overwritten by information
-    about new processes.
+    This operation takes effect after the next reboot. The model may only be
+    set to WDDM when running in DEFAULT compute mode. Changing the model to
+    WDDM is not supported when the GPU doesn't support graphics acceleration
+    or will not support it after a reboot.
+
+    On Windows platforms the device driver can run in either WDDM or WDM (TCC)
+    mode. If a physical display is attached to a device it must run in WDDM mode.
+
+    It is possible to force the change to WDM (TCC) while the display is still
+    attached with a `Behavior` of `FORCE`. This should only be done if the host
+    is subsequently powered down and the display is detached from this `Device`
+    before the next reboot.
+
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature or accounting mode
-    is disabled
+    * `InvalidArg`, if the `Device` is invalid or `model` is invalid (shouldn't occur?)
+    * `NotSupported`, if this `Device` does not support this feature
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
-    */
-    // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetAccountingBufferSize")]
-    pub fn accounting_buffer_size(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetAccountingBufferSize.as_ref())?;
-
-        unsafe {
-            let mut count: c_uint = mem::zeroed();
-            nvml_try(sym(self.device, &mut count))?;
+    Supports Fermi and newer fully supported devices.
 
-            Ok(count)
-        }
-    }
+    # Platform Support
 
-    /**
-    Gets whether or not per-process accounting mode is enabled.
+    Only supports Windows.
 
-    # Errors
+    # Examples
 
-    * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
-    * `UnexpectedVariant`, for which you can read the docs for
-    * `Unknown`, on any unexpected error
+    ```no_run
+    # use nvml_wrapper::Nvml;
+    # use nvml_wrapper::error::*;
+    # fn test() -> Result<(), NvmlError> {
+    # let nvml = Nvml::init()?;
+    # let mut device = nvml.device_by_index(0)?;
+    use nvml_wrapper::bitmasks::Behavior;
+    use nvml_wrapper::enum_wrappers::device::DriverModel;
 
-    # Device Support
+    device.set_driver_model(DriverModel::WDM, Behavior::DEFAULT)?;
 
-    Supports Kepler and newer fully supported devices.
+    // Force the change to WDM (TCC)
+    device.set_driver_model(DriverModel::WDM, Behavior::FORCE)?;
+    # Ok(())
+    # }
+    ```
     */
     // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetAccountingMode")]
-    pub fn is_accounting_enabled(&self) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetAccountingMode.as_ref())?;
-
-        unsafe {
-            let mut state: nvmlEnableState_t = mem::zeroed();
-            nvml_try(sym(self.device, &mut state))?;
+    // Tested (no-run)
+    #[cfg(target_os = "windows")]
+    #[doc(alias = "nvmlDeviceSetDriverModel")]
+    pub fn set_driver_model(
+        &mut self,
+        model: DriverModel,
+        flags: Behavior,
+    ) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetDriverModel)?;
 
-            bool_from_state(state)
-        }
+        unsafe { nvml_try_for(function, sym(self.device, model.as_c(), flags.bits())) }
     }
 
     /**
-    Gets the list of processes that can be queried for accounting stats.
+    Lock this `Device`'s clocks to a specific frequency range.
 
-    The list of processes returned can be in running or terminated state. Note that
-    in the case of a PID collision some processes might not be accessible before
-    the circular buffer is full.
+    This setting supercedes application clock values and takes effect regardless
+    of whether or not any CUDA apps are running. It can be used to request constant
+    performance.
+
+    After a system reboot or a driver reload the clocks go back to their default
+    values.
+
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature or accounting
-    mode is disabled
+    * `InvalidArg`, if the provided minimum and maximum clocks are not a valid combo
+    * `NotSupported`, if this `Device` does not support this feature
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
-    */
-    // Checked against local
-    // Tested
-    #[doc(alias = "nvmlDeviceGetAccountingPids")]
-    pub fn accounting_pids(&self) -> Result<Vec<u32>, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetAccountingPids.as_ref())?;
-
-        unsafe {
-            let mut count = match self.accounting_pids_count()? {
-                0 => return Ok(vec![]),
-                value => value,
-            };
-            let mut pids: Vec<c_uint> = vec![mem::zeroed(); count as usize];
 
-            nvml_try(sym(self.device, &mut count, pids.as_mut_ptr()))?;
-
-            Ok(pids)
-        }
-    }
+    # Device Support
 
-    // Helper function for the above.
-    fn accounting_pids_count(&self) -> Result<c_uint, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetAccountingPids.as_ref())?;
+    Supports Volta and newer fully supported devices.
+    */
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceSetGpuLockedClocks")]
+    pub fn set_gpu_locked_clocks(
+        &mut self,
+        setting: GpuLockedClocksSetting,
+    ) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetGpuLockedClocks)?;
 
-        unsafe {
-            // Indicates that we want the count
-            let mut count: c_uint = 0;
+        let (min_clock_mhz, max_clock_mhz) = setting.into_min_and_max_clocks();
 
-            // Null also indicates that we want the count
-            match sym(self.device, &mut count, ptr::null_mut()) {
-                // List is empty
-                nvmlReturn_enum_NVML_SUCCESS => Ok(0),
-                // Count is set to pids count
-                nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => Ok(count),
-                // We know this is an error
-                other => nvml_try(other).map(|_| 0),
-            }
-        }
+        unsafe { nvml_try_for(function, sym(self.device, min_clock_mhz, max_clock_mhz)) }
     }
 
     /**
-    Gets a process's accounting stats.
-
-    Accounting stats capture GPU utilization and other statistics across the lifetime
-    of a process. Accounting stats can be queried during the lifetime of the process
-    and after its termination. The `time` field in `AccountingStats` is reported as
-    zero during the lifetime of the process and updated to the actual running time
-    after its termination.
-
-    Accounting stats are kept in a circular buffer; newly created processes overwrite
-    information regarding old processes.
+    Reset this [`Device`]'s clocks to their default values.
 
-    Note:
-    * Accounting mode needs to be on. See `.is_accounting_enabled()`.
-    * Only compute and graphics applications stats can be queried. Monitoring
-    applications can't be queried since they don't contribute to GPU utilization.
-    * If a PID collision occurs, the stats of the latest process (the one that
-    terminated last) will be reported.
+    This resets to the same values that would be used after a reboot or driver
+    reload (defaults to idle clocks but can be configured via
+    [`Self::set_applications_clocks()`]).
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid
-    * `NotFound`, if the process stats were not found
-    * `NotSupported`, if this `Device` does not support this feature or accounting
-    mode is disabled
+    * `NotSupported`, if this `Device` does not support this feature
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Suports Kepler and newer fully supported devices.
+    Supports Volta and newer fully supported devices.
+    */
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceResetGpuLockedClocks")]
+    pub fn reset_gpu_locked_clocks(&mut self) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceResetGpuLockedClocks)?;
 
-    # Warning
+        unsafe { nvml_try_for(function, sym(self.device)) }
+    }
 
-    On Kepler devices, per-process stats are accurate _only if_ there's one process
-    running on this `Device`.
-    */
-    // Checked against local
-    // Tested (for error)
-    #[doc(alias = "nvmlDeviceGetAccountingStats")]
-    pub fn accounting_stats_for(&self, process_id: u32) -> Result<AccountingStats, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetAccountingStats.as_ref())?;
+    /**
+    Lock this [`Device`]'s memory clocks to a specific frequency range.
 
-        unsafe {
-            let mut stats: nvmlAccountingStats_t = mem::zeroed();
+    This setting supercedes application clock values and takes effect regardless
+    of whether or not any CUDA apps are running. It can be used to request
+    constant performance. See also [`Self::set_applications_clocks()`].
+
+    After a system reboot or a driver reload the clocks go back to their default
+    values. See also [`Self::reset_mem_locked_clocks()`].
 
-            nvml_try(sym(self.device, process_id, &mut stats))?;
+    You can use [`Self::supported_memory_clocks()`] to determine valid
+    frequency combinations to pass into this call.
 
-            Ok(stats.into())
-        }
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+    */
+    // Tested (no-run)
+    #[doc(alias = "nvmlDeviceSetMemoryLockedClocks")]
+    pub fn set_mem_locked_clocks(
+        &mut self,
+        min_clock_mhz: u32,
+        max_clock_mhz: u32,
+    ) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetMemoryLockedClocks)?;
+
+        unsafe { nvml_try_for(function, sym(self.device, min_clock_mhz, max_clock_mhz)) }
     }
 
     /**
-    Enables or disables per-process accounting.
-
-    Requires root/admin permissions.
+    Reset this [`Device`]'s memory clocks to their default values.
 
-    Note:
-    * This setting is not persistent and will default to disabled after the driver
-    unloads. Enable persistence mode to be sure the setting doesn't switch off
-    to disabled.
-    * Enabling accounting mode has no negative impact on GPU performance.
-    * Disabling accounting clears accounting information for all PIDs
+    This resets to the same values that would be used after a reboot or driver
+    reload (defaults to idle clocks but can be configured via
+    [`Self::set_applications_clocks()`]).
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid
     * `NotSupported`, if this `Device` does not support this feature
-    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
+    Supports Ampere and newer fully supported devices.
     */
-    // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetAccountingMode")]
-    pub fn set_accounting(&mut self, enabled: bool) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetAccountingMode.as_ref())?;
+    #[doc(alias = "nvmlDeviceResetMemoryLockedClocks")]
+    pub fn reset_mem_locked_clocks(&mut self) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceResetMemoryLockedClocks)?;
 
-        unsafe { nvml_try(sym(self.device, state_from_bool(enabled))) }
+        unsafe { nvml_try_for(function, sym(self.device)) }
     }
 
-    // Device commands starting here
-
     /**
-    Clears the ECC error and other memory error counts for this `Device`.
-
-    Sets all of the specified ECC counters to 0, including both detailed and total counts.
-    This operation takes effect immediately.
+    Sets the offset applied to the voltage/frequency curve of this `Device`'s
+    GPU clock (GPCCLK), in MHz. See `.gpc_clk_vf_offset()`.
 
-    Requires root/admin permissions and ECC mode to be enabled.
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid or `counter_type` is invalid (shouldn't occur?)
+    * `InvalidArg`, if the `Device` is invalid or `offset` is out of range
     * `NotSupported`, if this `Device` does not support this feature
     * `NoPermission`, if the user doesn't have permission to perform this operation
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
@@ -3874,86 +6627,112 @@ impl<'nvml> Device<'nvml> {
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices. Only applicable to devices with
-    ECC. Requires `InfoRom::ECC` version 2.0 or higher to clear aggregate
-    location-based ECC counts. Requires `InfoRom::ECC` version 1.0 or higher to
-    clear all other ECC counts.
+    NVIDIA doesn't document which devices support this.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceClearEccErrorCounts")]
-    pub fn clear_ecc_error_counts(&mut self, counter_type: EccCounter) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceClearEccErrorCounts.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetGpcClkVfOffset")]
+    pub fn set_gpc_clk_vf_offset(&mut self, offset: i32) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetGpcClkVfOffset)?;
 
-        unsafe { nvml_try(sym(self.device, counter_type.as_c())) }
+        unsafe { nvml_try_for(function, sym(self.device, offset)) }
     }
 
     /**
-    Changes the root/admin restrictions on certain APIs.
+    Sets the offset applied to the voltage/frequency curve of this `Device`'s
+    memory clock (MEMCLK), in MHz. See `.mem_clk_vf_offset()`.
 
-    This method can be used by a root/admin user to give non root/admin users access
-    to certain otherwise-restricted APIs. The new setting lasts for the lifetime of
-    the NVIDIA driver; it is not persistent. See `.is_api_restricted()` to query
-    current settings.
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid or `api_type` is invalid (shouldn't occur?)
-    * `NotSupported`, if this `Device` does not support changing API restrictions or
-    this `Device` does not support the feature that API restrictions are being set for
-    (e.g. enabling/disabling auto boosted clocks is not supported by this `Device`).
+    * `InvalidArg`, if the `Device` is invalid or `offset` is out of range
+    * `NotSupported`, if this `Device` does not support this feature
     * `NoPermission`, if the user doesn't have permission to perform this operation
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
+    NVIDIA doesn't document which devices support this.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetAPIRestriction")]
-    pub fn set_api_restricted(&mut self, api_type: Api, restricted: bool) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetAPIRestriction.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetMemClkVfOffset")]
+    pub fn set_mem_clk_vf_offset(&mut self, offset: i32) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetMemClkVfOffset)?;
 
-        unsafe {
-            nvml_try(sym(
-                self.device,
-                api_type.as_c(),
-                state_from_bool(restricted),
-            ))
-        }
+        unsafe { nvml_try_for(function, sym(self.device, offset)) }
     }
 
     /**
-    Sets clocks that applications will lock to.
+    Applies the settings in the given `ClockProfile` to this `Device`, leaving
+    those that are `None` or `LockedClocks::Unchanged` as they are.
 
-    Sets the clocks that compute and graphics applications will be running at. e.g.
-    CUDA driver requests these clocks during context creation which means this
-    property defines clocks at which CUDA applications will be running unless some
-    overspec event occurs (e.g. over power, over thermal or external HW brake).
+    Settings are applied in the order of the `ClockProfile` fields, stopping at
+    the first one that fails; settings applied before it stay in effect.
 
-    Can be used as a setting to request constant performance. Requires root/admin
-    permissions.
+    Requires root/admin permissions.
 
-    On Pascal and newer hardware, this will automatically disable automatic boosting
-    of clocks. On K80 and newer Kepler and Maxwell GPUs, users desiring fixed performance
-    should also call `.set_auto_boosted_clocks(false)` to prevent clocks from automatically
-    boosting above the clock value being set here.
+    # Errors
 
-    You can determine valid `mem_clock` and `graphics_clock` arg values via
-    [`Self::supported_memory_clocks()`] and [`Self::supported_graphics_clocks()`].
+    Any error returned by `.set_applications_clocks()`,
+    `.set_auto_boosted_clocks()`, `.set_gpu_locked_clocks()`,
+    `.reset_gpu_locked_clocks()`, `.set_mem_locked_clocks()`,
+    `.reset_mem_locked_clocks()`, `.set_gpc_clk_vf_offset()` or
+    `.set_mem_clk_vf_offset()`.
 
-    Note that after a system reboot or driver reload applications clocks go back
-    to their default value.
+    # Device Support
 
-    See also [`Self::set_mem_locked_clocks()`].
+    Depends on the settings in the profile; see the methods above.
+    */
+    // Tested (no-run)
+    pub fn apply_clock_profile(&mut self, profile: &ClockProfile) -> Result<(), NvmlError> {
+        if let Some(ref clocks) = profile.applications_clocks {
+            self.set_applications_clocks(clocks.memory, clocks.graphics)?;
+        }
+
+        if let Some(enabled) = profile.auto_boosted_clocks {
+            self.set_auto_boosted_clocks(enabled)?;
+        }
+
+        match profile.gpu_locked_clocks {
+            LockedClocks::Unchanged => {}
+            LockedClocks::Reset => self.reset_gpu_locked_clocks()?,
+            LockedClocks::Locked(ref setting) => self.set_gpu_locked_clocks(setting.clone())?,
+        }
+
+        match profile.mem_locked_clocks {
+            LockedClocks::Unchanged => {}
+            LockedClocks::Reset => self.reset_mem_locked_clocks()?,
+            LockedClocks::Locked(ref clocks) => {
+                self.set_mem_locked_clocks(clocks.min_clock_mhz, clocks.max_clock_mhz)?
+            }
+        }
+
+        if let Some(offset) = profile.gpc_clk_vf_offset {
+            self.set_gpc_clk_vf_offset(offset)?;
+        }
+
+        if let Some(offset) = profile.mem_clk_vf_offset {
+            self.set_mem_clk_vf_offset(offset)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+    Set whether or not ECC mode is enabled for this `Device`.
+
+    Requires root/admin permissions. Only applicable to devices with ECC.
+
+    This operation takes effect after the next reboot.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid or the clocks are not a valid combo
+    * `InvalidArg`, if the `Device` is invalid
     * `NotSupported`, if this `Device` does not support this feature
     * `NoPermission`, if the user doesn't have permission to perform this operation
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
@@ -3961,377 +6740,407 @@ impl<'nvml> Device<'nvml> {
 
     # Device Support
 
-    Supports Kepler and newer non-GeForce fully supported devices and Maxwell or newer
-    GeForce devices.
+    Supports Kepler and newer fully supported devices. Requires `InfoRom::ECC` version
+    1.0 or higher.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetApplicationsClocks")]
-    pub fn set_applications_clocks(
-        &mut self,
-        mem_clock: u32,
-        graphics_clock: u32,
-    ) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetApplicationsClocks.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetEccMode")]
+    pub fn set_ecc(&mut self, enabled: bool) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetEccMode)?;
 
-        unsafe { nvml_try(sym(self.device, mem_clock, graphics_clock)) }
+        unsafe { nvml_try_for(function, sym(self.device, state_from_bool(enabled))) }
     }
 
     /**
-    Sets the compute mode for this `Device`.
-
-    The compute mode determines whether a GPU can be used for compute operations
-    and whether it can be shared across contexts.
-
-    This operation takes effect immediately. Under Linux it is not persistent
-    across reboots and always resets to `Default`. Under Windows it is
-    persistent.
+    Sets the GPU operation mode for this `Device`.
 
-    Under Windows, compute mode may only be set to `Default` when running in WDDM
-    (physical display connected).
+    Requires root/admin permissions. Changing GOMs requires a reboot, a requirement
+    that may be removed in the future.
 
-    Requires root/admin permissions.
+    Compute only GOMs don't support graphics acceleration. Under Windows switching
+    to these GOMs when the pending driver model is WDDM (physical display attached)
+    is not supported.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
     * `InvalidArg`, if the `Device` is invalid or `mode` is invalid (shouldn't occur?)
-    * `NotSupported`, if this `Device` does not support this feature
+    * `NotSupported`, if this `Device` does not support GOMs or a specific mode
     * `NoPermission`, if the user doesn't have permission to perform this operation
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports GK110 M-class and X-class Tesla products from the Kepler family. Modes
+    `LowDP` and `AllOn` are supported on fully supported GeForce products. Not
+    supported on Quadro and Tesla C-class products.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetComputeMode")]
-    pub fn set_compute_mode(&mut self, mode: ComputeMode) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetComputeMode.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetGpuOperationMode")]
+    pub fn set_gpu_op_mode(&mut self, mode: OperationMode) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetGpuOperationMode)?;
 
-        unsafe { nvml_try(sym(self.device, mode.as_c())) }
+        unsafe { nvml_try_for(function, sym(self.device, mode.as_c())) }
     }
 
     /**
-    Sets the driver model for this `Device`.
-
-    This operation takes effect after the next reboot. The model may only be
-    set to WDDM when running in DEFAULT compute mode. Changing the model to
-    WDDM is not supported when the GPU doesn't support graphics acceleration
-    or will not support it after a reboot.
+    Sets the persistence mode for this `Device`.
 
-    On Windows platforms the device driver can run in either WDDM or WDM (TCC)
-    mode. If a physical display is attached to a device it must run in WDDM mode.
+    The persistence mode determines whether the GPU driver software is torn down
+    after the last client exits.
 
-    It is possible to force the change to WDM (TCC) while the display is still
-    attached with a `Behavior` of `FORCE`. This should only be done if the host
-    is subsequently powered down and the display is detached from this `Device`
-    before the next reboot.
+    This operation takes effect immediately and requires root/admin permissions.
+    It is not persistent across reboots; after each reboot it will default to
+    disabled.
 
-    Requires root/admin permissions.
+    Note that after disabling persistence on a device that has its own NUMA
+    memory, this `Device` handle will no longer be valid, and to continue to
+    interact with the physical device that it represents you will need to
+    obtain a new `Device` using the methods available on the `Nvml` struct.
+    This limitation is currently only applicable to devices that have a
+    coherent NVLink connection to system memory.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid or `model` is invalid (shouldn't occur?)
+    * `InvalidArg`, if the `Device` is invalid
     * `NotSupported`, if this `Device` does not support this feature
     * `NoPermission`, if the user doesn't have permission to perform this operation
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
-    # Device Support
-
-    Supports Fermi and newer fully supported devices.
-
     # Platform Support
 
-    Only supports Windows.
-
-    # Examples
-
-    ```no_run
-    # use nvml_wrapper::Nvml;
-    # use nvml_wrapper::error::*;
-    # fn test() -> Result<(), NvmlError> {
-    # let nvml = Nvml::init()?;
-    # let mut device = nvml.device_by_index(0)?;
-    use nvml_wrapper::bitmasks::Behavior;
-    use nvml_wrapper::enum_wrappers::device::DriverModel;
-
-    device.set_driver_model(DriverModel::WDM, Behavior::DEFAULT)?;
-
-    // Force the change to WDM (TCC)
-    device.set_driver_model(DriverModel::WDM, Behavior::FORCE)?;
-    # Ok(())
-    # }
-    ```
+    Only supports Linux.
     */
     // Checked against local
     // Tested (no-run)
-    #[cfg(target_os = "windows")]
-    #[doc(alias = "nvmlDeviceSetDriverModel")]
-    pub fn set_driver_model(
-        &mut self,
-        model: DriverModel,
-        flags: Behavior,
-    ) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetDriverModel.as_ref())?;
+    #[cfg(target_os = "linux")]
+    #[doc(alias = "nvmlDeviceSetPersistenceMode")]
+    pub fn set_persistent(&mut self, enabled: bool) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetPersistenceMode)?;
 
-        unsafe { nvml_try(sym(self.device, model.as_c(), flags.bits())) }
+        unsafe { nvml_try_for(function, sym(self.device, state_from_bool(enabled))) }
     }
 
-    /**
-    Lock this `Device`'s clocks to a specific frequency range.
-
-    This setting supercedes application clock values and takes effect regardless
-    of whether or not any CUDA apps are running. It can be used to request constant
-    performance.
-
-    After a system reboot or a driver reload the clocks go back to their default
-    values.
+    /**
+    Sets the power limit for this `Device`, in milliwatts.
 
-    Requires root/admin permissions.
+    This limit is not persistent across reboots or driver unloads. Enable
+    persistent mode to prevent the driver from unloading when no application
+    is using this `Device`.
+
+    Requires root/admin permissions. See `.power_management_limit_constraints()`
+    to check the allowed range of values.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the provided minimum and maximum clocks are not a valid combo
+    * `InvalidArg`, if the `Device` is invalid or `limit` is out of range
     * `NotSupported`, if this `Device` does not support this feature
-    * `NoPermission`, if the user doesn't have permission to perform this operation
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
+    For some reason NVIDIA does not mention `NoPermission`.
+
     # Device Support
 
-    Supports Volta and newer fully supported devices.
+    Supports Kepler and newer fully supported devices.
     */
+    // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetGpuLockedClocks")]
-    pub fn set_gpu_locked_clocks(
-        &mut self,
-        setting: GpuLockedClocksSetting,
-    ) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetGpuLockedClocks.as_ref())?;
-
-        let (min_clock_mhz, max_clock_mhz) = setting.into_min_and_max_clocks();
+    #[doc(alias = "nvmlDeviceSetPowerManagementLimit")]
+    pub fn set_power_management_limit(&mut self, limit: Milliwatts) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetPowerManagementLimit)?;
 
-        unsafe { nvml_try(sym(self.device, min_clock_mhz, max_clock_mhz)) }
+        unsafe { nvml_try_for(function, sym(self.device, limit.0)) }
     }
 
     /**
-    Reset this [`Device`]'s clocks to their default values.
+    Sets the power limit for the given part of this `Device`, in milliwatts.
 
-    This resets to the same values that would be used after a reboot or driver
-    reload (defaults to idle clocks but can be configured via
-    [`Self::set_applications_clocks()`]).
+    This is the newer variant of `.set_power_management_limit()`; with
+    `PowerScope::Gpu` the two behave the same. `PowerScope::Module` caps the
+    whole board, which matters on modules that share a power budget between
+    the GPU and other components (such as Grace Hopper).
+
+    As with `.set_power_management_limit()`, this limit is not persistent
+    across reboots or driver unloads, and root/admin permissions are required.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotSupported`, if this `Device` does not support this feature
+    * `InvalidArg`, if the `Device` is invalid or `limit` is out of range
+    * `NotSupported`, if this `Device` does not support this feature or the
+      given scope
     * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Volta and newer fully supported devices.
+    Supports Kepler and newer fully supported devices. `PowerScope::Module`
+    is only supported on modules that report a module power limit.
     */
+    // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceResetGpuLockedClocks")]
-    pub fn reset_gpu_locked_clocks(&mut self) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceResetGpuLockedClocks.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetPowerManagementLimit_v2")]
+    pub fn set_power_management_limit_scoped(
+        &mut self,
+        scope: PowerScope,
+        limit: Milliwatts,
+    ) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetPowerManagementLimit_v2)?;
 
-        unsafe { nvml_try(sym(self.device)) }
+        unsafe {
+            let mut value = nvmlPowerValue_v2_t {
+                version: v2_struct_version::<nvmlPowerValue_v2_t>(),
+                powerScope: scope.as_c(),
+                powerValueMw: limit.0,
+            };
+
+            nvml_try_for(function, sym(self.device, &mut value))
+        }
     }
 
     /**
-    Lock this [`Device`]'s memory clocks to a specific frequency range.
+    Sets the temperature threshold of the given type for this `Device`, in °C.
 
-    This setting supercedes application clock values and takes effect regardless
-    of whether or not any CUDA apps are running. It can be used to request
-    constant performance. See also [`Self::set_applications_clocks()`].
+    Mainly useful for `TemperatureThreshold::AcousticCurrent`, the temperature
+    the GPU is throttled at to keep its fans quiet. The value is checked against
+    the `AcousticMin` and `AcousticMax` thresholds before calling NVML, if the
+    device reports them; see `.temperature_threshold()`. Other thresholds are
+    fixed on most devices, and NVML rejects changes to them.
 
-    After a system reboot or a driver reload the clocks go back to their default
-    values. See also [`Self::reset_mem_locked_clocks()`].
+    Requires root/admin permissions.
 
-    You can use [`Self::supported_memory_clocks()`] to determine valid
-    frequency combinations to pass into this call.
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the `Device` is invalid, `threshold_type` can't be set or
+      `value` is outside of the acoustic range
+    * `NotSupported`, if this `Device` does not support this feature
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Ampere and newer fully supported devices.
+    Supports Maxwell and newer fully supported devices.
     */
+    // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetMemoryLockedClocks")]
-    pub fn set_mem_locked_clocks(
+    #[doc(alias = "nvmlDeviceSetTemperatureThreshold")]
+    pub fn set_temperature_threshold(
         &mut self,
-        min_clock_mhz: u32,
-        max_clock_mhz: u32,
+        threshold_type: TemperatureThreshold,
+        value: Celsius,
     ) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetMemoryLockedClocks.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetTemperatureThreshold)?;
+
+        if threshold_type == TemperatureThreshold::AcousticCurrent {
+            let min = self
+                .temperature_threshold(TemperatureThreshold::AcousticMin)
+                .optional()?;
+            let max = self
+                .temperature_threshold(TemperatureThreshold::AcousticMax)
+                .optional()?;
+
+            if min.map_or(false, |min| value < min) || max.map_or(false, |max| value > max) {
+                return Err(NvmlError::InvalidArg);
+            }
+        }
 
-        unsafe { nvml_try(sym(self.device, min_clock_mhz, max_clock_mhz)) }
+        let mut temp = c_int::try_from(value.0).map_err(|_| NvmlError::InvalidArg)?;
+
+        unsafe { nvml_try_for(function, sym(self.device, threshold_type.as_c(), &mut temp)) }
     }
 
     /**
-    Reset this [`Device`]'s memory clocks to their default values.
+    Sets the speed of the specified fan, as a percentage of its maximum speed.
 
-    This resets to the same values that would be used after a reboot or driver
-    reload (defaults to idle clocks but can be configured via
-    [`Self::set_applications_clocks()`]).
+    **Warning**: this switches the fan to manual control. The driver no longer
+    adjusts its speed as the GPU heats up, so it's up to you to monitor the
+    temperature and adjust the speed accordingly; set it too low and you can
+    damage the GPU. Use `.set_default_fan_speed()` to hand control back to the
+    driver.
+
+    You can determine valid fan indices using [`Self::num_fans()`].
+
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `NotSupported`, if this `Device` does not support this feature
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `InvalidArg`, if the `Device` is invalid, `fan_idx` is invalid or `speed`
+      is over 100 or outside of the range the fan accepts
+    * `NotSupported`, if this `Device` does not have a fan or is older than
+      Maxwell
+    * `NoPermission`, if the user doesn't have permission to perform this operation
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Ampere and newer fully supported devices.
+    Supports Maxwell and newer CUDA-capable discrete products with fans.
     */
+    // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceResetMemoryLockedClocks")]
-    pub fn reset_mem_locked_clocks(&mut self) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceResetMemoryLockedClocks.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetFanSpeed_v2")]
+    pub fn set_fan_speed(&mut self, fan_idx: u32, speed: u32) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetFanSpeed_v2)?;
+
+        if speed > 100 {
+            return Err(NvmlError::InvalidArg);
+        }
 
-        unsafe { nvml_try(sym(self.device)) }
+        unsafe { nvml_try_for(function, sym(self.device, fan_idx, speed)) }
     }
 
     /**
-    Set whether or not ECC mode is enabled for this `Device`.
+    Restores the default fan control policy of the specified fan, undoing
+    `.set_fan_speed()`.
 
-    Requires root/admin permissions. Only applicable to devices with ECC.
+    You can determine valid fan indices using [`Self::num_fans()`].
 
-    This operation takes effect after the next reboot.
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid
-    * `NotSupported`, if this `Device` does not support this feature
+    * `InvalidArg`, if the `Device` is invalid or `fan_idx` is invalid
+    * `NotSupported`, if this `Device` does not have a fan
     * `NoPermission`, if the user doesn't have permission to perform this operation
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports Kepler and newer fully supported devices. Requires `InfoRom::ECC` version
-    1.0 or higher.
+    Supports all CUDA-capable discrete products with fans.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetEccMode")]
-    pub fn set_ecc(&mut self, enabled: bool) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetEccMode.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetDefaultFanSpeed_v2")]
+    pub fn set_default_fan_speed(&mut self, fan_idx: u32) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetDefaultFanSpeed_v2)?;
 
-        unsafe { nvml_try(sym(self.device, state_from_bool(enabled))) }
+        unsafe { nvml_try_for(function, sym(self.device, fan_idx)) }
     }
 
     /**
-    Sets the GPU operation mode for this `Device`.
+    Sets the control policy of the specified fan.
 
-    Requires root/admin permissions. Changing GOMs requires a reboot, a requirement
-    that may be removed in the future.
+    Setting `FanControlPolicy::Manual` keeps the fan at its current speed until
+    `.set_fan_speed()` is called; the same warning applies. Setting
+    `FanControlPolicy::TemperatureContinuousSw` hands control back to the
+    driver.
 
-    Compute only GOMs don't support graphics acceleration. Under Windows switching
-    to these GOMs when the pending driver model is WDDM (physical display attached)
-    is not supported.
+    You can determine valid fan indices using [`Self::num_fans()`].
+
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid or `mode` is invalid (shouldn't occur?)
-    * `NotSupported`, if this `Device` does not support GOMs or a specific mode
+    * `InvalidArg`, if the `Device` is invalid or `fan_idx` is invalid
+    * `NotSupported`, if this `Device` does not have a fan or is older than
+      Maxwell
     * `NoPermission`, if the user doesn't have permission to perform this operation
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
     # Device Support
 
-    Supports GK110 M-class and X-class Tesla products from the Kepler family. Modes
-    `LowDP` and `AllOn` are supported on fully supported GeForce products. Not
-    supported on Quadro and Tesla C-class products.
+    Supports Maxwell or newer fully supported devices with fans.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetGpuOperationMode")]
-    pub fn set_gpu_op_mode(&mut self, mode: OperationMode) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetGpuOperationMode.as_ref())?;
+    #[doc(alias = "nvmlDeviceSetFanControlPolicy")]
+    pub fn set_fan_control_policy(
+        &mut self,
+        fan_idx: u32,
+        policy: FanControlPolicy,
+    ) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceSetFanControlPolicy)?;
 
-        unsafe { nvml_try(sym(self.device, mode.as_c())) }
+        unsafe { nvml_try_for(function, sym(self.device, fan_idx, policy.as_c())) }
     }
 
     /**
-    Sets the persistence mode for this `Device`.
-
-    The persistence mode determines whether the GPU driver software is torn down
-    after the last client exits.
+    Requests the given workload power profiles for this `Device`, in addition
+    to any that are already requested.
 
-    This operation takes effect immediately and requires root/admin permissions.
-    It is not persistent across reboots; after each reboot it will default to
-    disabled.
+    When requested profiles conflict, the one with the lowest priority value
+    is enforced. See `.current_workload_power_profiles()` to check which
+    profiles ended up being enforced.
 
-    Note that after disabling persistence on a device that has its own NUMA
-    memory, this `Device` handle will no longer be valid, and to continue to
-    interact with the physical device that it represents you will need to
-    obtain a new `Device` using the methods available on the `Nvml` struct.
-    This limitation is currently only applicable to devices that have a
-    coherent NVLink connection to system memory.
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid
+    * `InvalidArg`, if the `Device` is invalid or a profile is not supported
     * `NotSupported`, if this `Device` does not support this feature
     * `NoPermission`, if the user doesn't have permission to perform this operation
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
     * `Unknown`, on any unexpected error
 
-    # Platform Support
+    # Device Support
 
-    Only supports Linux.
+    Supports Blackwell and newer fully supported devices.
     */
     // Checked against local
     // Tested (no-run)
-    #[cfg(target_os = "linux")]
-    #[doc(alias = "nvmlDeviceSetPersistenceMode")]
-    pub fn set_persistent(&mut self, enabled: bool) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetPersistenceMode.as_ref())?;
+    #[doc(alias = "nvmlDeviceWorkloadPowerProfileSetRequestedProfiles")]
+    pub fn request_workload_power_profiles(
+        &mut self,
+        profiles: &[PowerProfile],
+    ) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(
+            self.nvml.lib,
+            nvmlDeviceWorkloadPowerProfileSetRequestedProfiles
+        )?;
+
+        unsafe {
+            let mut requested = requested_power_profiles(profiles);
 
-        unsafe { nvml_try(sym(self.device, state_from_bool(enabled))) }
+            nvml_try_for(function, sym(self.device, &mut requested))
+        }
     }
 
     /**
-    Sets the power limit for this `Device`, in milliwatts.
-
-    This limit is not persistent across reboots or driver unloads. Enable
-    persistent mode to prevent the driver from unloading when no application
-    is using this `Device`.
+    Withdraws earlier requests for the given workload power profiles on this
+    `Device`.
 
-    Requires root/admin permissions. See `.power_management_limit_constraints()`
-    to check the allowed range of values.
+    Requires root/admin permissions.
 
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if the `Device` is invalid or `limit` is out of range
+    * `InvalidArg`, if the `Device` is invalid or a profile is not supported
     * `NotSupported`, if this `Device` does not support this feature
-    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `NoPermission`, if the user doesn't have permission to perform this operation
     * `Unknown`, on any unexpected error
 
-    For some reason NVIDIA does not mention `NoPermission`.
-
     # Device Support
 
-    Supports Kepler and newer fully supported devices.
+    Supports Blackwell and newer fully supported devices.
     */
     // Checked against local
     // Tested (no-run)
-    #[doc(alias = "nvmlDeviceSetPowerManagementLimit")]
-    pub fn set_power_management_limit(&mut self, limit: u32) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceSetPowerManagementLimit.as_ref())?;
+    #[doc(alias = "nvmlDeviceWorkloadPowerProfileClearRequestedProfiles")]
+    pub fn clear_workload_power_profile_requests(
+        &mut self,
+        profiles: &[PowerProfile],
+    ) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(
+            self.nvml.lib,
+            nvmlDeviceWorkloadPowerProfileClearRequestedProfiles
+        )?;
+
+        unsafe {
+            let mut requested = requested_power_profiles(profiles);
 
-        unsafe { nvml_try(sym(self.device, limit)) }
+            nvml_try_for(function, sym(self.device, &mut requested))
+        }
     }
 
     // Event handling methods
@@ -4412,34 +7221,55 @@ impl<'nvml> Device<'nvml> {
         events: EventTypes,
         set: EventSet<'nvml>,
     ) -> Result<EventSet<'nvml>, NvmlErrorWithSource> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceRegisterEvents.as_ref())?;
+        match self.register_events_or_return(events, set) {
+            Ok(set) => Ok(set),
+            Err((e, None)) => Err(e),
+            Err((e, Some(set))) => {
+                // TODO: return set here so you can use it again?
+                if let Err(e) = set.release_events() {
+                    return Err(NvmlErrorWithSource {
+                        error: NvmlError::SetReleaseFailed,
+                        source: Some(e),
+                    });
+                }
+
+                Err(e)
+            }
+        }
+    }
+
+    // Like `.register_events()`, but hands `set` back along with the error
+    // unless NVML left it in an undefined state.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn register_events_or_return(
+        &self,
+        events: EventTypes,
+        set: EventSet<'nvml>,
+    ) -> Result<EventSet<'nvml>, (NvmlErrorWithSource, Option<EventSet<'nvml>>)> {
+        let (sym, function) = match nvml_fn!(self.nvml.lib, nvmlDeviceRegisterEvents) {
+            Ok(f) => f,
+            Err(e) => return Err((e.into(), Some(set))),
+        };
 
         unsafe {
-            match nvml_try(sym(self.device, events.bits(), set.handle())) {
+            match nvml_try_for(function, sym(self.device, events.bits(), set.handle())) {
                 Ok(()) => Ok(set),
                 Err(NvmlError::Unknown) => {
                     // NVIDIA says that if an Unknown error is returned, `set` will
                     // be in an undefined state and should be freed.
                     if let Err(e) = set.release_events() {
-                        return Err(NvmlErrorWithSource {
-                            error: NvmlError::SetReleaseFailed,
-                            source: Some(e),
-                        });
-                    }
-
-                    Err(NvmlError::Unknown.into())
-                }
-                Err(e) => {
-                    // TODO: return set here so you can use it again?
-                    if let Err(e) = set.release_events() {
-                        return Err(NvmlErrorWithSource {
-                            error: NvmlError::SetReleaseFailed,
-                            source: Some(e),
-                        });
+                        return Err((
+                            NvmlErrorWithSource {
+                                error: NvmlError::SetReleaseFailed,
+                                source: Some(e),
+                            },
+                            None,
+                        ));
                     }
 
-                    Err(e.into())
+                    Err((NvmlError::Unknown.into(), None))
                 }
+                Err(e) => Err((e.into(), Some(set))),
             }
         }
     }
@@ -4447,9 +7277,9 @@ impl<'nvml> Device<'nvml> {
     /**
     Gets the `EventTypes` that this `Device` supports.
 
-    The returned bitmask is created via the `EventTypes::from_bits_truncate`
-    method, meaning that any bits that don't correspond to flags present in this
-    version of the wrapper will be dropped.
+    Any bits that don't correspond to flags present in this version of the
+    wrapper (e.g. ones added by a newer driver) are kept in the returned
+    bitmask and can be read with `EventTypes.unknown_bits()`.
 
     # Errors
 
@@ -4493,7 +7323,7 @@ impl<'nvml> Device<'nvml> {
     #[cfg(target_os = "linux")]
     #[doc(alias = "nvmlDeviceGetSupportedEventTypes")]
     pub fn supported_event_types(&self) -> Result<EventTypes, NvmlError> {
-        Ok(EventTypes::from_bits_truncate(
+        Ok(EventTypes::from_bits_retain(
             self.supported_event_types_raw()?,
         ))
     }
@@ -4520,6 +7350,9 @@ impl<'nvml> Device<'nvml> {
     */
     // Tested
     #[cfg(target_os = "linux")]
+    #[deprecated(
+        note = "use `Device.supported_event_types()`, which keeps unknown bits, and check `.unknown_bits()`"
+    )]
     pub fn supported_event_types_strict(&self) -> Result<EventTypes, NvmlError> {
         let ev_types = self.supported_event_types_raw()?;
 
@@ -4529,11 +7362,11 @@ impl<'nvml> Device<'nvml> {
     // Helper for the above methods.
     #[cfg(target_os = "linux")]
     fn supported_event_types_raw(&self) -> Result<c_ulonglong, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceGetSupportedEventTypes.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceGetSupportedEventTypes)?;
 
         unsafe {
             let mut ev_types: c_ulonglong = mem::zeroed();
-            nvml_try(sym(self.device, &mut ev_types))?;
+            self.try_for(function, || sym(self.device, &mut ev_types))?;
 
             Ok(ev_types)
         }
@@ -4611,9 +7444,14 @@ impl<'nvml> Device<'nvml> {
             self.pci_info()?
         };
 
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceModifyDrainState.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceModifyDrainState)?;
 
-        unsafe { nvml_try(sym(&mut pci_info.try_into()?, state_from_bool(enabled))) }
+        unsafe {
+            nvml_try_for(
+                function,
+                sym(&mut pci_info.try_into()?, state_from_bool(enabled)),
+            )
+        }
     }
 
     /**
@@ -4678,12 +7516,12 @@ impl<'nvml> Device<'nvml> {
             self.pci_info()?
         };
 
-        let sym = nvml_sym(self.nvml.lib.nvmlDeviceQueryDrainState.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlDeviceQueryDrainState)?;
 
         unsafe {
             let mut state: nvmlEnableState_t = mem::zeroed();
 
-            nvml_try(sym(&mut pci_info.try_into()?, &mut state))?;
+            nvml_try_for(function, sym(&mut pci_info.try_into()?, &mut state))?;
 
             bool_from_state(state)
         }
@@ -4824,8 +7662,8 @@ impl<'nvml> Device<'nvml> {
             }
         };
 
-        let sym = match nvml_sym(self.nvml.lib.nvmlDeviceRemoveGpu_v2.as_ref()) {
-            Ok(sym) => sym,
+        let (sym, function) = match nvml_fn!(self.nvml.lib, nvmlDeviceRemoveGpu_v2) {
+            Ok(found) => found,
             Err(error) => {
                 return (
                     Err(NvmlErrorWithSource {
@@ -4837,42 +7675,286 @@ impl<'nvml> Device<'nvml> {
             }
         };
 
-        unsafe {
-            match nvml_try(sym(&mut raw_pci_info, gpu_state.as_c(), link_state.as_c())) {
-                // `Device` removed; call was successful, no `Device` to return
-                Ok(()) => (Ok(()), None),
-                // `Device` has not been removed; unsuccessful call, return `Device`
-                Err(e) => (Err(e.into()), Some(self)),
-            }
+        unsafe {
+            match nvml_try_for(
+                function,
+                sym(&mut raw_pci_info, gpu_state.as_c(), link_state.as_c()),
+            ) {
+                // `Device` removed; call was successful, no `Device` to return
+                Ok(()) => (Ok(()), None),
+                // `Device` has not been removed; unsuccessful call, return `Device`
+                Err(e) => (Err(e.into()), Some(self)),
+            }
+        }
+    }
+
+    // NvLink
+
+    /**
+    Obtain a struct that represents an NvLink.
+
+    NVIDIA does not provide any information as to how to obtain a valid NvLink
+    value, so you're on your own there.
+    */
+    pub fn link_wrapper_for(&self, link: u32) -> NvLink {
+        NvLink { device: self, link }
+    }
+}
+
+// The NvLink counters read by a `Device.nvlink_throughput()` call.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NvLinkCounters {
+    // μs (Unix time)
+    timestamp: i64,
+    rx_kib: u64,
+    tx_kib: u64,
+}
+
+impl NvLinkCounters {
+    // `None` if the counters were reset in between or no time has passed.
+    fn throughput_since(&self, previous: &Self) -> Option<NvLinkThroughput> {
+        let micros = u64::try_from(self.timestamp.checked_sub(previous.timestamp)?)
+            .ok()
+            .filter(|&micros| micros > 0)?;
+        let rate = |kib: u64| (kib as u128 * 1024 * 1_000_000 / micros as u128) as u64;
+
+        Some(NvLinkThroughput {
+            rx: rate(self.rx_kib.checked_sub(previous.rx_kib)?),
+            tx: rate(self.tx_kib.checked_sub(previous.tx_kib)?),
+            interval: Duration::from_micros(micros),
+        })
+    }
+}
+
+// `NotSupported` results remembered per device handle, NVML function and
+// input arguments; see `NvmlBuilder.cache_not_supported()`.
+#[derive(Debug, Default)]
+pub(crate) struct NotSupportedCache {
+    // `None` if caching is disabled
+    period: Option<Duration>,
+    // `None` for entries that never expire, as their period is too long to
+    // be represented as an `Instant`
+    expiries: Mutex<HashMap<NotSupportedKey, Option<Instant>>>,
+}
+
+type NotSupportedKey = (usize, &'static str, Vec<u64>);
+
+impl NotSupportedCache {
+    pub(crate) fn new(period: Option<Duration>) -> Self {
+        Self {
+            period,
+            expiries: Mutex::default(),
+        }
+    }
+
+    fn contains(&self, handle: usize, function: &'static str, args: &[u64], now: Instant) -> bool {
+        if self.period.is_none() {
+            return false;
+        }
+
+        let key = (handle, function, args.to_vec());
+        let mut expiries = self.expiries();
+
+        match expiries.get(&key) {
+            Some(None) => true,
+            Some(&Some(expiry)) if now < expiry => true,
+            Some(_) => {
+                expiries.remove(&key);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&self, handle: usize, function: &'static str, args: &[u64], now: Instant) {
+        if let Some(period) = self.period {
+            self.expiries()
+                .insert((handle, function, args.to_vec()), now.checked_add(period));
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        self.expiries().clear();
+    }
+
+    fn expiries(&self) -> MutexGuard<'_, HashMap<NotSupportedKey, Option<Instant>>> {
+        // The map is never left in an inconsistent state, so poisoning is moot
+        self.expiries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// The number of elements that `with_buffer` will place on the stack.
+const STACK_BUFFER_LEN: usize = 64;
+
+// Calls `f` with a zeroed buffer of `len` elements, placing it on the stack if
+// it is small enough in order to avoid a heap allocation.
+unsafe fn with_buffer<T: Copy, R>(len: usize, f: impl FnOnce(&mut [T]) -> R) -> R {
+    if len <= STACK_BUFFER_LEN {
+        let mut buffer: [T; STACK_BUFFER_LEN] = mem::zeroed();
+        f(&mut buffer[..len])
+    } else {
+        let mut buffer: Vec<T> = vec![mem::zeroed(); len];
+        f(&mut buffer)
+    }
+}
+
+// The largest buffer `with_string_buffer` will grow to.
+const MAX_STRING_BUFFER_LEN: c_uint = 4096;
+
+// Calls `f` with a buffer of `len` bytes and its length, doubling the buffer for
+// as long as `f` fails with `InsufficientSize` or fills it without terminating
+// the string, and reads the string `f` wrote with surrounding whitespace
+// trimmed.
+//
+// NVML doesn't report the length strings need, but the buffer size constants
+// in the header can fall behind what newer drivers return, and some drivers
+// silently truncate instead of failing.
+pub(crate) unsafe fn with_string_buffer<F>(mut len: c_uint, mut f: F) -> Result<String, NvmlError>
+where
+    F: FnMut(*mut c_char, c_uint) -> Result<(), NvmlError>,
+{
+    loop {
+        let mut buffer: Vec<c_char> = vec![0; len as usize];
+
+        match f(buffer.as_mut_ptr(), len) {
+            Ok(()) if !buffer.contains(&0) && len < MAX_STRING_BUFFER_LEN => len *= 2,
+            Ok(()) => {
+                // Don't rely on NVML to terminate the string
+                if let Some(last) = buffer.last_mut() {
+                    *last = 0;
+                }
+
+                let string = CStr::from_ptr(buffer.as_ptr()).to_str()?;
+                return Ok(string.trim().into());
+            }
+            Err(NvmlError::InsufficientSize(_)) if len < MAX_STRING_BUFFER_LEN => len *= 2,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Compares consecutive samples of the given clock.
+fn clock_transitions(
+    clock: Sampling,
+    mut samples: Vec<Sample>,
+) -> Result<Vec<ClockTransition>, NvmlError> {
+    samples.sort_by_key(|s| s.timestamp);
+
+    let mut transitions = vec![];
+    let mut previous: Option<u32> = None;
+
+    for sample in samples {
+        let speed = sample.value.as_u32()?;
+
+        match previous {
+            Some(from) if from != speed => transitions.push(ClockTransition {
+                timestamp: sample.timestamp,
+                clock: clock.clone(),
+                from,
+                to: speed,
+            }),
+            _ => {}
+        }
+
+        previous = Some(speed);
+    }
+
+    Ok(transitions)
+}
+
+// Versions the first version of a struct the way `NVML_STRUCT_VERSION` does in
+// the header.
+fn v1_struct_version<T>() -> c_uint {
+    mem::size_of::<T>() as c_uint | (1 << 24)
+}
+
+fn v2_struct_version<T>() -> c_uint {
+    mem::size_of::<T>() as c_uint | (2 << 24)
+}
+
+// Like `.optional()`, but also treats functions missing from older drivers as
+// unsupported.
+fn supported<T>(result: Result<T, NvmlError>) -> Result<Option<T>, NvmlError> {
+    match result {
+        Err(NvmlError::FailedToLoadSymbol(_)) | Err(NvmlError::FunctionNotFound) => Ok(None),
+        other => other.optional(),
+    }
+}
+
+fn is_privileged() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        extern "C" {
+            // From the C library
+            fn geteuid() -> c_uint;
         }
+
+        unsafe { geteuid() == 0 }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+// In MIG mode, only privileged callers get per-process usage through the parent
+// device's handle; outside of it there's nothing more to say than `Unavailable`.
+fn resolve_unavailable_memory(processes: &mut [ProcessInfo], mig_enabled: bool, privileged: bool) {
+    if !mig_enabled {
+        return;
     }
 
-    // NvLink
+    let resolved = if privileged {
+        UsedGpuMemory::UnknownUnderMig
+    } else {
+        UsedGpuMemory::NoPermission
+    };
 
-    /**
-    Obtain a struct that represents an NvLink.
+    for process in processes
+        .iter_mut()
+        .filter(|p| p.used_gpu_memory == UsedGpuMemory::Unavailable)
+    {
+        process.used_gpu_memory = resolved.clone();
+    }
+}
 
-    NVIDIA does not provide any information as to how to obtain a valid NvLink
-    value, so you're on your own there.
-    */
-    pub fn link_wrapper_for(&self, link: u32) -> NvLink {
-        NvLink { device: self, link }
+fn requested_power_profiles(
+    profiles: &[PowerProfile],
+) -> nvmlWorkloadPowerProfileRequestedProfiles_t {
+    nvmlWorkloadPowerProfileRequestedProfiles_t {
+        version: v1_struct_version::<nvmlWorkloadPowerProfileRequestedProfiles_t>(),
+        requestedProfilesMask: power_profiles_to_mask(profiles),
     }
 }
 
 #[cfg(test)]
 #[deny(unused_mut)]
 mod test {
+    use super::{resolve_unavailable_memory, Device, NotSupportedCache, NvLinkCounters};
     #[cfg(target_os = "linux")]
     use crate::bitmasks::event::*;
     #[cfg(target_os = "windows")]
     use crate::bitmasks::Behavior;
     use crate::enum_wrappers::device::*;
-    use crate::enums::device::GpuLockedClocksSetting;
+    use crate::enums::device::LockedClocks;
+    use crate::enums::device::{
+        FanControlPolicy, GpuInstanceProfile, GpuLockedClocksSetting, PowerScope, SampleValue,
+        UsedGpuMemory,
+    };
     use crate::error::*;
-    use crate::structs::device::FieldId;
+    use crate::struct_wrappers::device::{ProcessInfo, Sample};
+    use crate::structs::device::{ClockProfile, ClockTransition, FieldId};
+    use crate::structs::nv_link::NvLinkThroughput;
     use crate::sys_exports::field_id::*;
     use crate::test_utils::*;
+    use crate::units::{Celsius, Megahertz, Milliwatts};
+    use crate::Nvml;
+    use std::collections::HashSet;
+    use std::os::raw::c_char;
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
 
     // This modifies device state, so we don't want to actually run the test
     #[allow(dead_code)]
@@ -4894,6 +7976,12 @@ mod test {
         })
     }
 
+    #[test]
+    fn can_set() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.can_set(Api::ApplicationClocks))
+    }
+
     #[test]
     #[ignore = "my machine does not support this call"]
     fn applications_clock() {
@@ -4936,6 +8024,87 @@ mod test {
         test_with_device(3, &nvml, |device| device.brand())
     }
 
+    #[test]
+    fn model() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.model())
+    }
+
+    #[test]
+    fn snapshot() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.snapshot())
+    }
+
+    #[test]
+    fn max_mig_device_count() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.max_mig_device_count())
+    }
+
+    #[test]
+    fn mig_devices() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            let mig_devices = device.mig_devices()?;
+            Ok(mig_devices.iter().map(|m| m.index()).collect::<Vec<_>>())
+        })
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn engine_counts() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.engine_counts())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn gpu_instance_profile_info() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device.gpu_instance_profile_info(GpuInstanceProfile::Slice1)
+        })
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn gpu_instances() {
+        let nvml = nvml();
+        let device = device(&nvml);
+        test(3, || {
+            let profile = device.gpu_instance_profile_info(GpuInstanceProfile::Slice1)?;
+            let instances = device.gpu_instances(&profile)?;
+
+            instances
+                .iter()
+                .map(|instance| instance.info())
+                .collect::<Result<Vec<_>, _>>()
+        })
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn create_gpu_instance() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        let profile = device
+            .gpu_instance_profile_info(GpuInstanceProfile::Slice1)
+            .expect("profile info");
+        let instance = device
+            .create_gpu_instance(&profile)
+            .expect("created GPU instance");
+        let placement = instance.info().expect("instance info").placement;
+
+        instance.destroy().expect("destroyed GPU instance");
+        device
+            .create_gpu_instance_with_placement(&profile, &placement)
+            .expect("recreated GPU instance")
+            .destroy()
+            .expect("destroyed GPU instance");
+    }
+
     #[test]
     #[ignore = "my machine does not support this call"]
     fn bridge_chip_info() {
@@ -4996,6 +8165,17 @@ mod test {
         test_with_device(3, &nvml, |device| device.running_compute_processes())
     }
 
+    #[test]
+    fn running_compute_processes_into() {
+        let nvml = nvml();
+
+        test_with_device(3, &nvml, |device| {
+            let mut processes = Vec::with_capacity(8);
+            device.running_compute_processes_into(&mut processes)?;
+            Ok(processes)
+        })
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn cpu_affinity() {
@@ -5050,6 +8230,12 @@ mod test {
         test_with_device(3, &nvml, |device| device.is_display_connected())
     }
 
+    #[test]
+    fn display_state() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.display_state())
+    }
+
     #[cfg(target_os = "windows")]
     #[test]
     fn driver_model() {
@@ -5064,6 +8250,18 @@ mod test {
         test_with_device(3, &nvml, |device| device.is_ecc_enabled())
     }
 
+    #[test]
+    fn is_mig_enabled() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.is_mig_enabled())
+    }
+
+    #[test]
+    fn pending_changes() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.pending_changes())
+    }
+
     #[test]
     fn encoder_utilization() {
         let nvml = nvml();
@@ -5078,6 +8276,13 @@ mod test {
         })
     }
 
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn encoder_capacity_av1() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.encoder_capacity(EncoderType::AV1))
+    }
+
     #[test]
     fn encoder_stats() {
         let nvml = nvml();
@@ -5114,12 +8319,136 @@ mod test {
         test_with_device(3, &nvml, |device| device.fan_speed(0))
     }
 
+    #[test]
+    fn fan_speed_rpm() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.fan_speed_rpm(0))
+    }
+
+    #[test]
+    fn fan_speeds() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.fan_speeds())
+    }
+
     #[test]
     fn num_fans() {
         let nvml = nvml();
         test_with_device(3, &nvml, |device| device.num_fans())
     }
 
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn supported_vgpu_types() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.supported_vgpu_types())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn active_vgpus() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.active_vgpus())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn creatable_vgpu_types() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.creatable_vgpu_types())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn vgpu_type_placements() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device
+                .supported_vgpu_types()?
+                .into_iter()
+                .map(|vgpu_type| {
+                    device.vgpu_type_max_instances(vgpu_type)?;
+                    device.vgpu_type_supported_placements(vgpu_type)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn is_vgpu_heterogeneous_mode_enabled() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device.is_vgpu_heterogeneous_mode_enabled()
+        })
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn set_vgpu_heterogeneous_mode() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        device
+            .set_vgpu_heterogeneous_mode(true)
+            .expect("enabled heterogeneous mode");
+        device
+            .supported_vgpu_types()
+            .expect("supported vGPU types")
+            .into_iter()
+            .for_each(|vgpu_type| {
+                device
+                    .vgpu_type_creatable_placements(vgpu_type)
+                    .expect("creatable placements");
+            });
+    }
+
+    #[test]
+    fn gpc_clk_vf_offset() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.gpc_clk_vf_offset())
+    }
+
+    #[test]
+    fn mem_clk_vf_offset() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.mem_clk_vf_offset())
+    }
+
+    #[test]
+    fn clock_profile() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            let profile = device.clock_profile()?;
+            assert_eq!(profile.gpu_locked_clocks, LockedClocks::Reset);
+            assert_eq!(profile.mem_locked_clocks, LockedClocks::Reset);
+
+            Ok(profile)
+        })
+    }
+
+    #[test]
+    fn target_fan_speed() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.target_fan_speed(0))
+    }
+
+    #[test]
+    fn fan_speed_constraints() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.fan_speed_constraints())
+    }
+
+    #[test]
+    fn fan_control_policy() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            (0..device.num_fans()?)
+                .map(|fan| device.fan_control_policy(fan))
+                .collect::<Result<Vec<_>, _>>()
+        })
+    }
+
     #[test]
     #[ignore = "my machine does not support this call"]
     fn gpu_operation_mode() {
@@ -5257,6 +8586,18 @@ mod test {
         })
     }
 
+    #[test]
+    fn performance_modes() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.performance_modes())
+    }
+
+    #[test]
+    fn current_clock_freqs() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.current_clock_freqs())
+    }
+
     #[test]
     fn performance_state() {
         let nvml = nvml();
@@ -5291,52 +8632,237 @@ mod test {
     }
 
     #[test]
-    fn is_power_management_algo_active() {
-        let nvml = nvml();
+    fn is_power_management_algo_active() {
+        let nvml = nvml();
+
+        #[allow(deprecated)]
+        test_with_device(3, &nvml, |device| device.is_power_management_algo_active())
+    }
+
+    #[test]
+    fn power_state() {
+        let nvml = nvml();
+
+        #[allow(deprecated)]
+        test_with_device(3, &nvml, |device| device.power_state())
+    }
+
+    #[test]
+    fn power_usage() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.power_usage())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn retired_pages() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device.retired_pages(RetirementCause::MultipleSingleBitEccErrors)?;
+            device.retired_pages(RetirementCause::DoubleBitEccError)
+        })
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn retired_page_counts() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.retired_page_counts())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn retired_pages_by_cause() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            let pages = device.retired_pages_by_cause()?;
+            assert_eq!(
+                pages.counts().total() as usize,
+                pages.single_bit_ecc.len() + pages.double_bit_ecc.len()
+            );
+            Ok(pages)
+        })
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn are_pages_pending_retired() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.are_pages_pending_retired())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn samples() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device.samples(Sampling::ProcessorClock, None)?;
+            Ok(())
+        })
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn samples_into() {
+        let nvml = nvml();
+
+        test_with_device(3, &nvml, |device| {
+            let mut samples = vec![];
+            device.samples_into(Sampling::ProcessorClock, None, &mut samples)?;
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn string_buffer_grows() {
+        let mut lens = vec![];
+        let string = unsafe {
+            super::with_string_buffer(16, |buffer, len| {
+                lens.push(len);
+
+                if len < 64 {
+                    return Err(NvmlError::InsufficientSize(None));
+                }
 
-        #[allow(deprecated)]
-        test_with_device(3, &nvml, |device| device.is_power_management_algo_active())
+                for (i, &b) in b"GPU-1234\0".iter().enumerate() {
+                    *buffer.add(i) = b as c_char;
+                }
+                Ok(())
+            })
+        };
+
+        assert_eq!(string, Ok("GPU-1234".to_string()));
+        assert_eq!(lens, vec![16, 32, 64]);
+
+        let error =
+            unsafe { super::with_string_buffer(16, |_, _| Err(NvmlError::InsufficientSize(None))) };
+        assert_eq!(error, Err(NvmlError::InsufficientSize(None)));
     }
 
     #[test]
-    fn power_state() {
-        let nvml = nvml();
+    fn string_buffer_sanitizes() {
+        // Fills the buffer with as much of the name as fits, without a NUL,
+        // like a driver silently truncating a long name
+        let name = b"  NVIDIA RTX 6000 Ada Generation\0 trailing";
+        let mut lens = vec![];
+        let string = unsafe {
+            super::with_string_buffer(16, |buffer, len| {
+                lens.push(len);
+
+                for (i, &b) in name.iter().take(len as usize).enumerate() {
+                    *buffer.add(i) = b as c_char;
+                }
+                Ok(())
+            })
+        };
 
-        #[allow(deprecated)]
-        test_with_device(3, &nvml, |device| device.power_state())
+        assert_eq!(string, Ok("NVIDIA RTX 6000 Ada Generation".to_string()));
+        assert_eq!(lens, vec![16, 32, 64]);
     }
 
     #[test]
-    fn power_usage() {
-        let nvml = nvml();
-        test_with_device(3, &nvml, |device| device.power_usage())
+    fn not_supported_cache() {
+        let now = Instant::now();
+        let cache = NotSupportedCache::new(Some(Duration::from_secs(10)));
+
+        cache.insert(1, "nvmlDeviceGetClockInfo", &[0], now);
+
+        assert!(cache.contains(1, "nvmlDeviceGetClockInfo", &[0], now));
+        // Other devices, functions and arguments are unaffected
+        assert!(!cache.contains(2, "nvmlDeviceGetClockInfo", &[0], now));
+        assert!(!cache.contains(1, "nvmlDeviceGetMaxClockInfo", &[0], now));
+        assert!(!cache.contains(1, "nvmlDeviceGetClockInfo", &[1], now));
+
+        let later = now + Duration::from_secs(10);
+        assert!(!cache.contains(1, "nvmlDeviceGetClockInfo", &[0], later));
+
+        cache.insert(1, "nvmlDeviceGetClockInfo", &[0], now);
+        cache.clear();
+        assert!(!cache.contains(1, "nvmlDeviceGetClockInfo", &[0], now));
+
+        let disabled = NotSupportedCache::default();
+        disabled.insert(1, "nvmlDeviceGetClockInfo", &[0], now);
+        assert!(!disabled.contains(1, "nvmlDeviceGetClockInfo", &[0], now));
+
+        // Periods too long to add to an `Instant` never expire
+        let forever = NotSupportedCache::new(Some(Duration::MAX));
+        forever.insert(1, "nvmlDeviceGetClockInfo", &[0], now);
+        assert!(forever.contains(1, "nvmlDeviceGetClockInfo", &[0], later));
     }
 
     #[test]
-    #[ignore = "my machine does not support this call"]
-    fn retired_pages() {
-        let nvml = nvml();
+    fn cache_not_supported() {
+        let nvml = Nvml::builder()
+            .cache_not_supported(Duration::from_secs(60))
+            .init()
+            .expect("init");
+
         test_with_device(3, &nvml, |device| {
-            device.retired_pages(RetirementCause::MultipleSingleBitEccErrors)?;
-            device.retired_pages(RetirementCause::DoubleBitEccError)
+            // Unsupported calls fail the same way when answered from the cache
+            let first = device.fan_speed(0).map_err(|e| e.to_string());
+            let second = device.fan_speed(0).map_err(|e| e.to_string());
+            assert_eq!(first, second);
+
+            nvml.clear_not_supported_cache();
+            device.fan_speed(0).optional()
         })
     }
 
+    #[test]
+    fn clock_transitions_from_samples() {
+        let sample = |timestamp, mhz| Sample {
+            timestamp,
+            value: SampleValue::U32(mhz),
+        };
+        // Out of order, as NVML's ring buffer may return them
+        let samples = vec![
+            sample(30, 1400),
+            sample(10, 1800),
+            sample(20, 1800),
+            sample(40, 1800),
+        ];
+
+        let transitions = super::clock_transitions(Sampling::ProcessorClock, samples).unwrap();
+
+        assert_eq!(
+            transitions,
+            vec![
+                ClockTransition {
+                    timestamp: 30,
+                    clock: Sampling::ProcessorClock,
+                    from: 1800,
+                    to: 1400,
+                },
+                ClockTransition {
+                    timestamp: 40,
+                    clock: Sampling::ProcessorClock,
+                    from: 1400,
+                    to: 1800,
+                },
+            ]
+        );
+
+        let wrong_type = vec![
+            sample(10, 1800),
+            Sample {
+                timestamp: 20,
+                value: SampleValue::F64(1.0),
+            },
+        ];
+        assert!(super::clock_transitions(Sampling::MemoryClock, wrong_type).is_err());
+    }
+
     #[test]
     #[ignore = "my machine does not support this call"]
-    fn are_pages_pending_retired() {
+    fn clock_transitions() {
         let nvml = nvml();
-        test_with_device(3, &nvml, |device| device.are_pages_pending_retired())
+        test_with_device(3, &nvml, |device| device.clock_transitions(None))
     }
 
     #[test]
-    #[ignore = "my machine does not support this call"]
-    fn samples() {
+    fn hot_metrics() {
         let nvml = nvml();
-        test_with_device(3, &nvml, |device| {
-            device.samples(Sampling::ProcessorClock, None)?;
-            Ok(())
-        })
+        test_with_device(3, &nvml, |device| device.hot_metrics())
     }
 
     #[test]
@@ -5386,6 +8912,23 @@ mod test {
         })
     }
 
+    #[test]
+    fn field_values_for_named() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device.field_values_for(&[
+                FieldId::ECC_CURRENT,
+                FieldId::ECC_DBE_VOL_TOTAL,
+                FieldId::RETIRED_PENDING,
+                FieldId::REMAPPED_UNC,
+                FieldId::MEMORY_TEMP,
+                FieldId::PCIE_REPLAY_COUNTER,
+                FieldId::NVLINK_LINK_COUNT,
+                FieldId::NVLINK_THROUGHPUT_DATA_TX,
+            ])
+        })
+    }
+
     // Passing an empty slice should return an `InvalidArg` error
     #[should_panic(expected = "InvalidArg")]
     #[test]
@@ -5408,27 +8951,73 @@ mod test {
         test_with_device(3, &nvml, |device| device.board_part_number())
     }
 
+    #[test]
+    fn current_clocks_event_reasons() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.current_clocks_event_reasons())
+    }
+
+    #[test]
+    fn current_clocks_event_reasons_strict() {
+        let nvml = nvml();
+
+        #[allow(deprecated)]
+        test_with_device(3, &nvml, |device| {
+            device.current_clocks_event_reasons_strict()
+        })
+    }
+
+    #[test]
+    fn supported_clocks_event_reasons() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.supported_clocks_event_reasons())
+    }
+
+    #[test]
+    fn clocks_event_reasons_status() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.clocks_event_reasons_status())
+    }
+
+    #[test]
+    fn supported_clocks_event_reasons_strict() {
+        let nvml = nvml();
+
+        #[allow(deprecated)]
+        test_with_device(3, &nvml, |device| {
+            device.supported_clocks_event_reasons_strict()
+        })
+    }
+
     #[test]
     fn current_throttle_reasons() {
         let nvml = nvml();
+
+        #[allow(deprecated)]
         test_with_device(3, &nvml, |device| device.current_throttle_reasons())
     }
 
     #[test]
     fn current_throttle_reasons_strict() {
         let nvml = nvml();
+
+        #[allow(deprecated)]
         test_with_device(3, &nvml, |device| device.current_throttle_reasons_strict())
     }
 
     #[test]
     fn supported_throttle_reasons() {
         let nvml = nvml();
+
+        #[allow(deprecated)]
         test_with_device(3, &nvml, |device| device.supported_throttle_reasons())
     }
 
     #[test]
     fn supported_throttle_reasons_strict() {
         let nvml = nvml();
+
+        #[allow(deprecated)]
         test_with_device(3, &nvml, |device| {
             device.supported_throttle_reasons_strict()
         })
@@ -5440,7 +9029,7 @@ mod test {
         let nvml = nvml();
         #[allow(unused_variables)]
         test_with_device(3, &nvml, |device| {
-            let supported = device.supported_graphics_clocks(810)?;
+            let supported = device.supported_graphics_clocks(Megahertz(810))?;
             Ok(())
         })
     }
@@ -5476,6 +9065,18 @@ mod test {
         })
     }
 
+    #[test]
+    fn temperature_headroom() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            assert_eq!(
+                device.temperature_headroom(TemperatureThreshold::MemoryMax),
+                Err(NvmlError::InvalidArg)
+            );
+            device.temperature_headroom(TemperatureThreshold::Slowdown)
+        })
+    }
+
     // I do not have 2 devices
     #[ignore = "my machine does not support this call"]
     #[cfg(target_os = "linux")]
@@ -5513,12 +9114,42 @@ mod test {
         test_with_device(3, &nvml, |device| device.uuid())
     }
 
+    #[test]
+    fn raw_round_trip() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            let raw = unsafe { Device::from_raw(device.as_raw(), &nvml) };
+            assert_eq!(&raw, device);
+            raw.uuid()
+        })
+    }
+
+    #[test]
+    fn device_uuid() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.device_uuid())
+    }
+
     #[test]
     fn utilization_rates() {
         let nvml = nvml();
         test_with_device(3, &nvml, |device| device.utilization_rates())
     }
 
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn pgpu_metadata_string() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.pgpu_metadata_string())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn licensable_features() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.licensable_features())
+    }
+
     #[test]
     fn vbios_version() {
         let nvml = nvml();
@@ -5569,6 +9200,82 @@ mod test {
         test_with_device(3, &nvml, |device| device.bus_type())
     }
 
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn workload_power_profiles() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.workload_power_profiles())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn current_workload_power_profiles() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.current_workload_power_profiles())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn fabric_info() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.fabric_info())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn fabric_peers() {
+        let nvml = nvml();
+        let device = device(&nvml);
+        test(3, || device.fabric_peers())
+    }
+
+    #[test]
+    fn nvlink_throughput() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device.nvlink_throughput()?;
+            thread::sleep(Duration::from_millis(10));
+            device.nvlink_throughput()
+        })
+    }
+
+    #[test]
+    fn nvlink_throughput_since() {
+        let previous = NvLinkCounters {
+            timestamp: 1_000_000,
+            rx_kib: 100,
+            tx_kib: 50,
+        };
+        let counters = NvLinkCounters {
+            timestamp: 3_000_000,
+            rx_kib: 300,
+            tx_kib: 50,
+        };
+
+        assert_eq!(
+            counters.throughput_since(&previous),
+            Some(NvLinkThroughput {
+                rx: 100 * 1024,
+                tx: 0,
+                interval: Duration::from_secs(2),
+            })
+        );
+        assert_eq!(previous.throughput_since(&counters), None);
+        assert_eq!(counters.throughput_since(&counters), None);
+    }
+
+    #[test]
+    fn device_identity() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            let identity = device.identity()?;
+            let again = nvml.device_by_uuid(identity.uuid.as_str())?.identity()?;
+
+            assert_eq!(identity, again);
+            Ok(identity)
+        })
+    }
+
     #[test]
     fn architecture() {
         let nvml = nvml();
@@ -5614,6 +9321,13 @@ mod test {
         device.set_cpu_affinity().expect("ideal affinity set")
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn set_current_thread_affinity() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| device.set_current_thread_affinity())
+    }
+
     // This modifies device state, so we don't want to actually run the test
     #[allow(dead_code)]
     fn set_auto_boosted_clocks_default() {
@@ -5711,7 +9425,9 @@ mod test {
         let nvml = nvml();
         let mut device = device(&nvml);
 
-        device.set_applications_clocks(32, 32).expect("set to true")
+        device
+            .set_applications_clocks(Megahertz(32), Megahertz(32))
+            .expect("set to true")
     }
 
     // This modifies device state, so we don't want to actually run the test
@@ -5817,10 +9533,112 @@ mod test {
         let mut device = device(&nvml);
 
         device
-            .set_power_management_limit(250000)
+            .set_power_management_limit(Milliwatts(250000))
             .expect("set to true")
     }
 
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn set_gpc_clk_vf_offset() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        device.set_gpc_clk_vf_offset(0).expect("set offset")
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn set_mem_clk_vf_offset() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        device.set_mem_clk_vf_offset(0).expect("set offset")
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn apply_clock_profile() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        let saved = device.clock_profile().expect("clock profile");
+        let preset = ClockProfile {
+            auto_boosted_clocks: Some(false),
+            ..ClockProfile::default()
+        };
+
+        device.apply_clock_profile(&preset).expect("applied preset");
+        device.apply_clock_profile(&saved).expect("restored")
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn set_power_management_limit_scoped() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        device
+            .set_power_management_limit_scoped(PowerScope::Gpu, Milliwatts(250000))
+            .expect("set GPU limit")
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn set_temperature_threshold() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        device
+            .set_temperature_threshold(TemperatureThreshold::AcousticCurrent, Celsius(80))
+            .expect("set acoustic threshold")
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn set_fan_speed() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        device.set_fan_speed(0, 80).expect("set fan speed");
+        device.set_default_fan_speed(0).expect("restored default")
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn set_fan_control_policy() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        device
+            .set_fan_control_policy(0, FanControlPolicy::Manual)
+            .expect("set manual policy");
+        device
+            .set_fan_control_policy(0, FanControlPolicy::TemperatureContinuousSw)
+            .expect("restored default policy")
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn request_workload_power_profiles() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        device
+            .request_workload_power_profiles(&[PowerProfile::MaxQ])
+            .expect("requested");
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn clear_workload_power_profile_requests() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        device
+            .clear_workload_power_profile_requests(&[PowerProfile::MaxQ])
+            .expect("cleared");
+    }
+
     #[cfg(target_os = "linux")]
     #[allow(unused_variables)]
     #[test]
@@ -5852,6 +9670,8 @@ mod test {
     #[test]
     fn supported_event_types_strict() {
         let nvml = nvml();
+
+        #[allow(deprecated)]
         test_with_device(3, &nvml, |device| device.supported_event_types_strict())
     }
 
@@ -5861,4 +9681,62 @@ mod test {
         let nvml = nvml();
         test_with_device(3, &nvml, |device| device.is_drain_enabled(None))
     }
+
+    #[test]
+    // Hashing only looks at the handle, see the `Hash` impl
+    #[allow(clippy::mutable_key_type)]
+    fn identity() {
+        let nvml = nvml();
+        let device = device(&nvml);
+
+        let by_uuid = nvml
+            .device_by_uuid(device.uuid().expect("uuid"))
+            .expect("device");
+        let by_bus_id = nvml
+            .device_by_pci_bus_id(device.pci_info().expect("pci info").bus_id)
+            .expect("device");
+
+        assert_eq!(device, by_uuid);
+        assert_eq!(device, by_bus_id);
+
+        let unique: HashSet<_> = vec![device, by_uuid, by_bus_id].into_iter().collect();
+        assert_eq!(unique.len(), 1);
+    }
+
+    #[test]
+    fn index_cached() {
+        let nvml = nvml();
+        let device = nvml.device_by_index(0).expect("device");
+        let by_uuid = nvml
+            .device_by_uuid(device.uuid().expect("uuid"))
+            .expect("device");
+
+        assert_eq!(device.index, Some(0));
+        assert_eq!(by_uuid.index, None);
+        assert_eq!(by_uuid.index().expect("index"), 0);
+    }
+
+    #[test]
+    fn unavailable_memory_resolved() {
+        let process = |used_gpu_memory| ProcessInfo {
+            pid: 100,
+            used_gpu_memory,
+            gpu_instance_id: None,
+            compute_instance_id: None,
+        };
+        let resolve = |mig_enabled, privileged| {
+            let mut processes = vec![
+                process(UsedGpuMemory::Used(1024)),
+                process(UsedGpuMemory::Unavailable),
+            ];
+            resolve_unavailable_memory(&mut processes, mig_enabled, privileged);
+
+            assert_eq!(processes[0].used_gpu_memory, UsedGpuMemory::Used(1024));
+            processes[1].used_gpu_memory.clone()
+        };
+
+        assert_eq!(resolve(false, true), UsedGpuMemory::Unavailable);
+        assert_eq!(resolve(true, false), UsedGpuMemory::NoPermission);
+        assert_eq!(resolve(true, true), UsedGpuMemory::UnknownUnderMig);
+    }
 }
@@ -384,6 +384,16 @@ pub enum TemperatureThreshold {
     /// GPU temperature at which the GPU can be throttled below the base clock.
     #[wrap(c_variant = "NVML_TEMPERATURE_THRESHOLD_GPU_MAX")]
     GpuMax,
+    /// Minimum GPU temperature that can be set as the acoustic threshold.
+    #[wrap(c_variant = "NVML_TEMPERATURE_THRESHOLD_ACOUSTIC_MIN")]
+    AcousticMin,
+    /// Current GPU temperature above which the GPU is throttled to keep fan
+    /// noise down.
+    #[wrap(c_variant = "NVML_TEMPERATURE_THRESHOLD_ACOUSTIC_CURR")]
+    AcousticCurrent,
+    /// Maximum GPU temperature that can be set as the acoustic threshold.
+    #[wrap(c_variant = "NVML_TEMPERATURE_THRESHOLD_ACOUSTIC_MAX")]
+    AcousticMax,
 }
 
 /// Level relationships within a system between two GPUs.
@@ -538,6 +548,9 @@ pub enum EncoderType {
     H264,
     #[wrap(c_variant = "NVML_ENCODER_QUERY_HEVC")]
     HEVC,
+    /// Only supported by Ada and newer devices.
+    #[wrap(c_variant = "NVML_ENCODER_QUERY_AV1")]
+    AV1,
 }
 
 /// The type of a frame buffer capture session
@@ -593,3 +606,210 @@ pub enum ClockLimitId {
     #[wrap(c_variant = "NVML_CLOCK_LIMIT_ID_UNLIMITED")]
     Unlimited,
 }
+
+/// Workload power profiles for use with
+/// [`crate::Device::request_workload_power_profiles()`].
+#[derive(EnumWrapper, Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[wrap(c_enum = "nvmlPowerProfileType_t")]
+pub enum PowerProfile {
+    /// Maximum performance.
+    #[wrap(c_variant = "NVML_POWER_PROFILE_MAX_P")]
+    MaxP,
+    /// Maximum performance per watt.
+    #[wrap(c_variant = "NVML_POWER_PROFILE_MAX_Q")]
+    MaxQ,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_COMPUTE")]
+    Compute,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_MEMORY_BOUND")]
+    MemoryBound,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_NETWORK")]
+    Network,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_BALANCED")]
+    Balanced,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_LLM_INFERENCE")]
+    LlmInference,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_LLM_TRAINING")]
+    LlmTraining,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_RBM")]
+    Rbm,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_DCPCIE")]
+    DcPcie,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_HMMA_SPARSE")]
+    HmmaSparse,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_HMMA_DENSE")]
+    HmmaDense,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_SYNC_BALANCED")]
+    SyncBalanced,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_HPC")]
+    Hpc,
+    #[wrap(c_variant = "NVML_POWER_PROFILE_MIG")]
+    Mig,
+}
+
+/// vGPU software features that can be licensed, as reported by
+/// `Device.licensable_features()`.
+// Checked against local
+#[derive(EnumWrapper, Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[wrap(c_enum = "nvmlGridLicenseFeatureCode_t")]
+pub enum LicenseFeatureCode {
+    #[wrap(c_variant = "NVML_GRID_LICENSE_FEATURE_CODE_UNKNOWN")]
+    Unknown,
+    /// Virtual GPU.
+    #[wrap(c_variant = "NVML_GRID_LICENSE_FEATURE_CODE_VGPU")]
+    Vgpu,
+    /// NVIDIA RTX, formerly known as Quadro Virtual Workstation.
+    #[wrap(c_variant = "NVML_GRID_LICENSE_FEATURE_CODE_NVIDIA_RTX")]
+    NvidiaRtx,
+    #[wrap(c_variant = "NVML_GRID_LICENSE_FEATURE_CODE_GAMING")]
+    Gaming,
+    #[wrap(c_variant = "NVML_GRID_LICENSE_FEATURE_CODE_COMPUTE")]
+    Compute,
+}
+
+into_c! {
+    Api => nvmlRestrictedAPI_enum,
+    Clock => nvmlClockType_enum,
+    ClockId => nvmlClockId_enum,
+    Brand => nvmlBrandType_enum,
+    BridgeChip => nvmlBridgeChipType_enum,
+    MemoryError => nvmlMemoryErrorType_enum,
+    EccCounter => nvmlEccCounterType_enum,
+    MemoryLocation => nvmlMemoryLocation_enum,
+    OperationMode => nvmlGom_enum,
+    InfoRom => nvmlInforomObject_enum,
+    PcieUtilCounter => nvmlPcieUtilCounter_enum,
+    PerformanceState => nvmlPStates_enum,
+    RetirementCause => nvmlPageRetirementCause_enum,
+    Sampling => nvmlSamplingType_enum,
+    TemperatureSensor => nvmlTemperatureSensors_enum,
+    TemperatureThreshold => nvmlTemperatureThresholds_enum,
+    TopologyLevel => nvmlGpuLevel_enum,
+    PerformancePolicy => nvmlPerfPolicyType_enum,
+    ComputeMode => nvmlComputeMode_enum,
+    P2pStatus => nvmlGpuP2PStatus_enum,
+    P2pCapabilitiesIndex => nvmlGpuP2PCapsIndex_enum,
+    SampleValueType => nvmlValueType_enum,
+    EncoderType => nvmlEncoderQueryType_enum,
+    FbcSessionType => nvmlFBCSessionType_enum,
+    DetachGpuState => nvmlDetachGpuState_enum,
+    PcieLinkState => nvmlPcieLinkState_enum,
+    ClockLimitId => nvmlClockLimitId_enum,
+    PowerProfile => nvmlPowerProfileType_t,
+    LicenseFeatureCode => nvmlGridLicenseFeatureCode_t,
+}
+
+#[cfg(target_os = "windows")]
+into_c! {
+    DriverModel => nvmlDriverModel_enum,
+}
+
+names! {
+    Brand {
+        Unknown => "Unknown",
+        Quadro => "Quadro",
+        Tesla => "Tesla",
+        NVS => "NVS",
+        GRID => "GRID",
+        GeForce => "GeForce",
+        Titan => "Titan",
+    }
+    Clock {
+        Graphics => "Graphics",
+        SM => "SM",
+        Memory => "Memory",
+        Video => "Video",
+    }
+    MemoryError {
+        Corrected => "Corrected",
+        Uncorrected => "Uncorrected",
+    }
+    EccCounter {
+        Volatile => "Volatile",
+        Aggregate => "Aggregate",
+    }
+    MemoryLocation {
+        L1Cache => "L1 Cache",
+        L2Cache => "L2 Cache",
+        Device => "Device Memory",
+        RegisterFile => "Register File",
+        Texture => "Texture Memory",
+        Shared => "Texture Shared",
+        Cbu => "CBU",
+        SRAM => "SRAM",
+    }
+    OperationMode {
+        AllOn => "All On",
+        Compute => "Compute",
+        LowDP => "Low Double Precision",
+    }
+    PerformanceState {
+        Zero => "P0",
+        One => "P1",
+        Two => "P2",
+        Three => "P3",
+        Four => "P4",
+        Five => "P5",
+        Six => "P6",
+        Seven => "P7",
+        Eight => "P8",
+        Nine => "P9",
+        Ten => "P10",
+        Eleven => "P11",
+        Twelve => "P12",
+        Thirteen => "P13",
+        Fourteen => "P14",
+        Fifteen => "P15",
+        Unknown => "Unknown",
+    }
+    TemperatureSensor {
+        Gpu => "GPU",
+    }
+    TemperatureThreshold {
+        Shutdown => "Shutdown",
+        Slowdown => "Slowdown",
+        MemoryMax => "Memory Max",
+        GpuMax => "GPU Max",
+        AcousticMin => "Acoustic Min",
+        AcousticCurrent => "Acoustic Current",
+        AcousticMax => "Acoustic Max",
+    }
+    TopologyLevel {
+        Internal => "Internal",
+        Single => "Single",
+        Multiple => "Multiple",
+        HostBridge => "Host Bridge",
+        Node => "Node",
+        System => "System",
+    }
+    PerformancePolicy {
+        Power => "Power",
+        Thermal => "Thermal",
+        SyncBoost => "Sync Boost",
+        BoardLimit => "Board Limit",
+        LowUtilization => "Low Utilization",
+        Reliability => "Reliability",
+        TotalAppClocks => "Total App Clocks",
+        TotalBaseClocks => "Total Base Clocks",
+    }
+    ComputeMode {
+        Default => "Default",
+        ExclusiveThread => "Exclusive_Thread",
+        Prohibited => "Prohibited",
+        ExclusiveProcess => "Exclusive_Process",
+    }
+    EncoderType {
+        H264 => "H264",
+        HEVC => "HEVC",
+        AV1 => "AV1",
+    }
+}
+
+#[cfg(target_os = "windows")]
+names! {
+    DriverModel {
+        WDDM => "WDDM",
+        WDM => "TCC",
+    }
+}
@@ -1,10 +1,112 @@
+/*!
+Rust enums wrapping the C enums used by NVML.
+
+Every enum here can be converted from its C counterpart with `TryFrom`, which
+fails with `NvmlError::UnexpectedVariant` for values this version of the
+wrapper doesn't know about, and back with `From` (or the `as_c()` method).
+This lets code that calls into `nvml_wrapper_sys` directly reuse the safe
+types:
+
+```
+# use nvml_wrapper::error::NvmlError;
+use nvml_wrapper::enum_wrappers::device::Brand;
+use nvml_wrapper_sys::bindings::{nvmlBrandType_enum, nvmlBrandType_enum_NVML_BRAND_TESLA};
+use std::convert::TryFrom;
+
+let brand = Brand::try_from(nvmlBrandType_enum_NVML_BRAND_TESLA)?;
+assert_eq!(brand, Brand::Tesla);
+assert_eq!(nvmlBrandType_enum::from(brand), nvmlBrandType_enum_NVML_BRAND_TESLA);
+
+assert!(matches!(Brand::try_from(9999), Err(NvmlError::UnexpectedVariant(9999))));
+# Ok::<(), NvmlError>(())
+```
+
+The enums that tools commonly print also implement `Display`, using the names
+`nvidia-smi` shows, and `FromStr`, which accepts those names regardless of case
+and of whether words are separated by spaces, `_` or `-`:
+
+```
+use nvml_wrapper::enum_wrappers::device::{ComputeMode, PerformanceState};
+
+assert_eq!(PerformanceState::Two.to_string(), "P2");
+assert_eq!("exclusive process".parse(), Ok(ComputeMode::ExclusiveProcess));
+assert!("shared".parse::<ComputeMode>().is_err());
+```
+*/
+
 use crate::error::NvmlError;
 use crate::ffi::bindings::*;
+use thiserror::Error;
+
+// Implements `From<$wrapper> for $c_enum` in terms of `$wrapper::as_c()`.
+macro_rules! into_c {
+    ($($wrapper:ident => $c_enum:ident),+ $(,)?) => {
+        $(
+            impl From<$wrapper> for $c_enum {
+                fn from(wrapper: $wrapper) -> Self {
+                    wrapper.as_c()
+                }
+            }
+        )+
+    };
+}
+
+// Implements `Display` with the given names and `FromStr` accepting them (see
+// the module-level docs).
+macro_rules! names {
+    ($($wrapper:ident { $($variant:ident => $name:literal),+ $(,)? })+) => {
+        $(
+            impl std::fmt::Display for $wrapper {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(match self {
+                        $($wrapper::$variant => $name,)+
+                    })
+                }
+            }
+
+            impl std::str::FromStr for $wrapper {
+                type Err = crate::enum_wrappers::ParseEnumError;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    $(
+                        if crate::enum_wrappers::same_name(s, $name) {
+                            return Ok($wrapper::$variant);
+                        }
+                    )+
+
+                    Err(crate::enum_wrappers::ParseEnumError {
+                        kind: stringify!($wrapper),
+                        value: s.into(),
+                    })
+                }
+            }
+        )+
+    };
+}
 
 pub mod device;
 pub mod nv_link;
 pub mod unit;
 
+/// Returned when parsing one of the enums in this module from a string fails.
+#[derive(Error, Debug, Clone, Eq, PartialEq, Hash)]
+#[error("invalid {kind}: {value:?}")]
+pub struct ParseEnumError {
+    /// The name of the enum that was being parsed.
+    pub kind: &'static str,
+    pub value: String,
+}
+
+// Compares names ignoring case and the separator used between words.
+fn same_name(input: &str, name: &str) -> bool {
+    let normalize = |c: char| match c {
+        ' ' | '-' => '_',
+        c => c.to_ascii_lowercase(),
+    };
+
+    input.chars().map(normalize).eq(name.chars().map(normalize))
+}
+
 pub fn bool_from_state(state: nvmlEnableState_t) -> Result<bool, NvmlError> {
     match state {
         nvmlEnableState_enum_NVML_FEATURE_DISABLED => Ok(false),
@@ -20,3 +122,71 @@ pub fn state_from_bool(enabled: bool) -> nvmlEnableState_t {
         nvmlEnableState_enum_NVML_FEATURE_DISABLED
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::device::{Brand, ComputeMode, EncoderType, OperationMode, PerformanceState};
+    use super::unit::LedColor;
+    use super::ParseEnumError;
+    use crate::enums::nv_link::Counter;
+    use crate::error::NvmlError;
+    use crate::ffi::bindings::*;
+    use std::convert::TryFrom;
+    use std::os::raw::c_uint;
+
+    #[test]
+    fn names() {
+        assert_eq!(PerformanceState::Zero.to_string(), "P0");
+        assert_eq!(LedColor::Amber.to_string(), "Amber");
+        assert_eq!(EncoderType::HEVC.to_string(), "HEVC");
+
+        assert_eq!("p15".parse(), Ok(PerformanceState::Fifteen));
+        assert_eq!(
+            "Exclusive_Process".parse(),
+            Ok(ComputeMode::ExclusiveProcess)
+        );
+        assert_eq!(
+            "exclusive-process".parse(),
+            Ok(ComputeMode::ExclusiveProcess)
+        );
+        assert_eq!("LOW DOUBLE PRECISION".parse(), Ok(OperationMode::LowDP));
+        assert_eq!(
+            "P16".parse::<PerformanceState>(),
+            Err(ParseEnumError {
+                kind: "PerformanceState",
+                value: "P16".into()
+            })
+        );
+
+        for brand in &[Brand::GeForce, Brand::NVS, Brand::Unknown] {
+            assert_eq!(brand.to_string().parse(), Ok(brand.clone()));
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        for state in &[PerformanceState::Zero, PerformanceState::Unknown] {
+            let c = nvmlPStates_enum::from(state.clone());
+            assert_eq!(PerformanceState::try_from(c).unwrap(), *state);
+        }
+
+        assert_eq!(
+            nvmlEncoderQueryType_enum::from(EncoderType::HEVC),
+            nvmlEncoderQueryType_enum_NVML_ENCODER_QUERY_HEVC
+        );
+        assert_eq!(
+            LedColor::try_from(nvmlLedColor_enum_NVML_LED_COLOR_AMBER).unwrap(),
+            LedColor::Amber
+        );
+        assert!(matches!(
+            EncoderType::try_from(42),
+            Err(NvmlError::UnexpectedVariant(42))
+        ));
+
+        assert_eq!(c_uint::from(Counter::One), 1);
+        assert!(matches!(
+            Counter::try_from(2),
+            Err(NvmlError::UnexpectedVariant(2))
+        ));
+    }
+}
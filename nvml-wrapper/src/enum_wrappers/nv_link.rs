@@ -63,3 +63,9 @@ pub enum ErrorCounter {
     #[wrap(c_variant = "NVML_NVLINK_ERROR_DL_CRC_DATA")]
     DlCrcData,
 }
+
+into_c! {
+    UtilizationCountUnit => nvmlNvLinkUtilizationCountUnits_enum,
+    Capability => nvmlNvLinkCapability_enum,
+    ErrorCounter => nvmlNvLinkErrorCounter_enum,
+}
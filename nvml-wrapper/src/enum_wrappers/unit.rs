@@ -29,3 +29,19 @@ pub enum LedColor {
     #[wrap(c_variant = "NVML_LED_COLOR_AMBER")]
     Amber,
 }
+
+into_c! {
+    FanState => nvmlFanState_enum,
+    LedColor => nvmlLedColor_enum,
+}
+
+names! {
+    FanState {
+        Normal => "Normal",
+        Failed => "Failed",
+    }
+    LedColor {
+        Green => "Green",
+        Amber => "Amber",
+    }
+}
@@ -1,6 +1,8 @@
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::os::raw::c_uint;
+#[cfg(test)]
+use std::os::raw::c_ulong;
 
 use crate::enum_wrappers::device::{ClockLimitId, SampleValueType};
 use crate::error::NvmlError;
@@ -34,6 +36,13 @@ pub enum UsedGpuMemory {
     /// Under WDDM, `NVML_VALUE_NOT_AVAILABLE` is always reported because
     /// Windows KMD manages all the memory, not the NVIDIA driver.
     Unavailable,
+    /// The `Device` is in MIG mode and the caller lacks the privileges needed
+    /// to see per-process usage through the parent device's handle.
+    NoPermission,
+    /// The `Device` is in MIG mode and NVML didn't attribute any usage to the
+    /// process through the parent device's handle. Query the `MigDevice` the
+    /// process runs on instead.
+    UnknownUnderMig,
     /// Memory used in bytes.
     Used(u64),
 }
@@ -49,7 +58,13 @@ impl From<u64> for UsedGpuMemory {
     }
 }
 
-/// Represents different types of sample values.
+/**
+Represents different types of sample values.
+
+Used for samples, field values and any other value NVML returns alongside a
+type tag. The variant is picked based on that tag; use the `as_*()` methods to
+read the value as a specific type without matching on the variant.
+*/
 // Checked against local
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -61,6 +76,9 @@ pub enum SampleValue {
 }
 
 impl SampleValue {
+    /// Read the member of `union` that `tag` says is valid.
+    // `.into()` is only a no-op where `unsigned long` is 64 bits wide
+    #[allow(clippy::useless_conversion)]
     pub fn from_tag_and_union(tag: &SampleValueType, union: nvmlValue_t) -> Self {
         use self::SampleValueType::*;
 
@@ -68,14 +86,79 @@ impl SampleValue {
             match *tag {
                 Double => SampleValue::F64(union.dVal),
                 UnsignedInt => SampleValue::U32(union.uiVal),
-                // Methodology: NVML supports 32-bit Linux. UL is u32 on that platform.
-                // NVML wouldn't return anything larger
-                UnsignedLong => SampleValue::U32(union.ulVal as u32),
+                // `unsigned long` is 32 bits wide on some platforms and 64 on
+                // others, so widen it to fit either
+                UnsignedLong => SampleValue::U64(union.ulVal.into()),
                 UnsignedLongLong => SampleValue::U64(union.ullVal),
                 SignedLongLong => SampleValue::I64(union.sllVal),
             }
         }
     }
+
+    /**
+    Get this value as an `f64`.
+
+    # Errors
+
+    * `IncorrectValueType`, if this is not an `F64` value
+    */
+    pub fn as_f64(&self) -> Result<f64, NvmlError> {
+        match *self {
+            SampleValue::F64(v) => Ok(v),
+            _ => Err(self.incorrect_type("f64")),
+        }
+    }
+
+    /**
+    Get this value as a `u32`.
+
+    # Errors
+
+    * `IncorrectValueType`, if this is not a `U32` value
+    */
+    pub fn as_u32(&self) -> Result<u32, NvmlError> {
+        match *self {
+            SampleValue::U32(v) => Ok(v),
+            _ => Err(self.incorrect_type("u32")),
+        }
+    }
+
+    /**
+    Get this value as a `u64`, widening `U32` values.
+
+    # Errors
+
+    * `IncorrectValueType`, if this is not a `U32` or `U64` value
+    */
+    pub fn as_u64(&self) -> Result<u64, NvmlError> {
+        match *self {
+            SampleValue::U32(v) => Ok(v.into()),
+            SampleValue::U64(v) => Ok(v),
+            _ => Err(self.incorrect_type("u64")),
+        }
+    }
+
+    /**
+    Get this value as an `i64`, widening `U32` values.
+
+    # Errors
+
+    * `IncorrectValueType`, if this is not a `U32` or `I64` value
+    */
+    pub fn as_i64(&self) -> Result<i64, NvmlError> {
+        match *self {
+            SampleValue::U32(v) => Ok(v.into()),
+            SampleValue::I64(v) => Ok(v),
+            _ => Err(self.incorrect_type("i64")),
+        }
+    }
+
+    fn incorrect_type(&self, requested: &'static str) -> NvmlError {
+        NvmlError::IncorrectValueType {
+            value: self.clone(),
+            requested,
+        }
+    }
 }
 
 /// Represents different types of sample values.
@@ -116,6 +199,26 @@ impl GpuLockedClocksSetting {
     }
 }
 
+/// A locked clocks setting stored in a `ClockProfile`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LockedClocks<T> {
+    /// Leave the locked clocks as they are.
+    Unchanged,
+    /// Remove any locked clocks, letting the driver manage the clocks again.
+    Reset,
+    /// Lock the clocks to the given setting.
+    Locked(T),
+}
+
+// Deriving would require `T: Default`
+#[allow(clippy::derivable_impls)]
+impl<T> Default for LockedClocks<T> {
+    fn default() -> Self {
+        LockedClocks::Unchanged
+    }
+}
+
 /// Returned by [`crate::Device::bus_type()`].
 // TODO: technically this is an "enum wrapper" but the type on the C side isn't
 // an enum
@@ -166,6 +269,12 @@ impl TryFrom<nvmlBusType_t> for BusType {
     }
 }
 
+impl From<BusType> for nvmlBusType_t {
+    fn from(wrapper: BusType) -> Self {
+        wrapper.as_c()
+    }
+}
+
 /// Returned by [`crate::Device::power_source()`].
 // TODO: technically this is an "enum wrapper" but the type on the C side isn't
 // an enum
@@ -200,6 +309,12 @@ impl TryFrom<nvmlPowerSource_t> for PowerSource {
     }
 }
 
+impl From<PowerSource> for nvmlPowerSource_t {
+    fn from(wrapper: PowerSource) -> Self {
+        wrapper.as_c()
+    }
+}
+
 /// Returned by [`crate::Device::architecture()`].
 ///
 /// This is the simplified chip architecture of the device.
@@ -256,6 +371,12 @@ impl TryFrom<nvmlDeviceArchitecture_t> for DeviceArchitecture {
     }
 }
 
+impl From<DeviceArchitecture> for nvmlDeviceArchitecture_t {
+    fn from(wrapper: DeviceArchitecture) -> Self {
+        wrapper.as_c()
+    }
+}
+
 impl Display for DeviceArchitecture {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -319,3 +440,432 @@ impl TryFrom<c_uint> for PcieLinkMaxSpeed {
         }
     }
 }
+
+impl From<PcieLinkMaxSpeed> for c_uint {
+    fn from(wrapper: PcieLinkMaxSpeed) -> Self {
+        wrapper.as_c()
+    }
+}
+
+/**
+Whether a monitor is attached to a `Device` and whether a display is
+initialized on it, as returned by `Device.display_state()`.
+
+The two are independent: a display can be initialized without a monitor
+(e.g. an X server on a headless server GPU), and a monitor can be attached to
+a `Device` that no display has been initialized on.
+*/
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DisplayState {
+    /// No monitor is attached and no display is initialized.
+    Headless,
+    /// A display is initialized, but no monitor is attached.
+    ActiveWithoutMonitor,
+    /// A monitor is attached, but no display is initialized.
+    ConnectedInactive,
+    /// A monitor is attached and a display is initialized.
+    ConnectedActive,
+}
+
+impl DisplayState {
+    /// Combine the results of `Device.is_display_connected()` and
+    /// `Device.is_display_active()`.
+    pub fn new(connected: bool, active: bool) -> Self {
+        match (connected, active) {
+            (false, false) => Self::Headless,
+            (false, true) => Self::ActiveWithoutMonitor,
+            (true, false) => Self::ConnectedInactive,
+            (true, true) => Self::ConnectedActive,
+        }
+    }
+
+    /// Whether or not a monitor is physically attached.
+    pub fn is_connected(&self) -> bool {
+        matches!(self, Self::ConnectedInactive | Self::ConnectedActive)
+    }
+
+    /// Whether or not a display is initialized.
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::ActiveWithoutMonitor | Self::ConnectedActive)
+    }
+}
+
+/// When the license for a vGPU software feature expires, as reported in a
+/// `LicensableFeature`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LicenseExpiry {
+    /// Expiry information is not available.
+    NotAvailable,
+    /// The reported expiry is invalid.
+    Invalid,
+    /// The license expires at the given date and time.
+    Valid {
+        year: u32,
+        month: u16,
+        day: u16,
+        hour: u16,
+        min: u16,
+        sec: u16,
+    },
+    /// The feature does not expire, e.g. because it isn't licensed.
+    NotApplicable,
+    /// The license is permanent.
+    Permanent,
+}
+
+impl TryFrom<nvmlGridLicenseExpiry_t> for LicenseExpiry {
+    type Error = NvmlError;
+
+    fn try_from(data: nvmlGridLicenseExpiry_t) -> Result<Self, Self::Error> {
+        match u32::from(data.status) {
+            NVML_GRID_LICENSE_EXPIRY_NOT_AVAILABLE => Ok(Self::NotAvailable),
+            NVML_GRID_LICENSE_EXPIRY_INVALID => Ok(Self::Invalid),
+            NVML_GRID_LICENSE_EXPIRY_VALID => Ok(Self::Valid {
+                year: data.year,
+                month: data.month,
+                day: data.day,
+                hour: data.hour,
+                min: data.min,
+                sec: data.sec,
+            }),
+            NVML_GRID_LICENSE_EXPIRY_NOT_APPLICABLE => Ok(Self::NotApplicable),
+            NVML_GRID_LICENSE_EXPIRY_PERMANENT => Ok(Self::Permanent),
+            status => Err(NvmlError::UnexpectedVariant(status)),
+        }
+    }
+}
+
+/// The state of a GPU's registration with the NVSwitch fabric, as reported in
+/// a `FabricInfo`.
+// TODO: technically this is an "enum wrapper" but the type on the C side isn't
+// an enum
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FabricState {
+    /// The GPU does not support fabric registration.
+    NotSupported,
+    /// Registration has not started yet.
+    NotStarted,
+    /// Registration is underway.
+    InProgress,
+    /// Registration has finished, successfully or not.
+    Completed,
+}
+
+impl FabricState {
+    /// Returns the C constant equivalent for the given Rust enum variant.
+    pub fn as_c(&self) -> nvmlGpuFabricState_t {
+        // The constants are all well below `u8::MAX`
+        (match *self {
+            Self::NotSupported => NVML_GPU_FABRIC_STATE_NOT_SUPPORTED,
+            Self::NotStarted => NVML_GPU_FABRIC_STATE_NOT_STARTED,
+            Self::InProgress => NVML_GPU_FABRIC_STATE_IN_PROGRESS,
+            Self::Completed => NVML_GPU_FABRIC_STATE_COMPLETED,
+        }) as nvmlGpuFabricState_t
+    }
+}
+
+impl TryFrom<nvmlGpuFabricState_t> for FabricState {
+    type Error = NvmlError;
+
+    fn try_from(data: nvmlGpuFabricState_t) -> Result<Self, Self::Error> {
+        match u32::from(data) {
+            NVML_GPU_FABRIC_STATE_NOT_SUPPORTED => Ok(Self::NotSupported),
+            NVML_GPU_FABRIC_STATE_NOT_STARTED => Ok(Self::NotStarted),
+            NVML_GPU_FABRIC_STATE_IN_PROGRESS => Ok(Self::InProgress),
+            NVML_GPU_FABRIC_STATE_COMPLETED => Ok(Self::Completed),
+            state => Err(NvmlError::UnexpectedVariant(state)),
+        }
+    }
+}
+
+impl From<FabricState> for nvmlGpuFabricState_t {
+    fn from(wrapper: FabricState) -> Self {
+        wrapper.as_c()
+    }
+}
+
+/// Who controls the speed of a fan, as returned from
+/// `Device.fan_control_policy()`.
+// TODO: technically this is an "enum wrapper" but the type on the C side isn't
+// an enum
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FanControlPolicy {
+    /// The driver adjusts the fan speed with the temperature. This is the
+    /// default.
+    TemperatureContinuousSw,
+    /// The fan speed is set by the user, e.g. via `Device.set_fan_speed()`.
+    Manual,
+}
+
+impl FanControlPolicy {
+    /// Returns the C constant equivalent for the given Rust enum variant.
+    pub fn as_c(&self) -> nvmlFanControlPolicy_t {
+        match *self {
+            Self::TemperatureContinuousSw => NVML_FAN_POLICY_TEMPERATURE_CONTINOUS_SW,
+            Self::Manual => NVML_FAN_POLICY_MANUAL,
+        }
+    }
+}
+
+impl TryFrom<nvmlFanControlPolicy_t> for FanControlPolicy {
+    type Error = NvmlError;
+
+    fn try_from(data: nvmlFanControlPolicy_t) -> Result<Self, Self::Error> {
+        match data {
+            NVML_FAN_POLICY_TEMPERATURE_CONTINOUS_SW => Ok(Self::TemperatureContinuousSw),
+            NVML_FAN_POLICY_MANUAL => Ok(Self::Manual),
+            _ => Err(NvmlError::UnexpectedVariant(data)),
+        }
+    }
+}
+
+impl From<FanControlPolicy> for nvmlFanControlPolicy_t {
+    fn from(wrapper: FanControlPolicy) -> Self {
+        wrapper.as_c()
+    }
+}
+
+/// The part of a board a power limit applies to, as passed to
+/// `Device.set_power_management_limit_scoped()`.
+// TODO: technically this is an "enum wrapper" but the type on the C side isn't
+// an enum
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PowerScope {
+    /// Only the GPU itself.
+    Gpu,
+    /// The whole module, e.g. a Grace Hopper superchip including its CPU.
+    Module,
+    /// Only the GPU's memory.
+    Memory,
+}
+
+impl PowerScope {
+    /// Returns the C constant equivalent for the given Rust enum variant.
+    pub fn as_c(&self) -> nvmlPowerScopeType_t {
+        // The constants are all well below `u8::MAX`
+        (match *self {
+            Self::Gpu => NVML_POWER_SCOPE_GPU,
+            Self::Module => NVML_POWER_SCOPE_MODULE,
+            Self::Memory => NVML_POWER_SCOPE_MEMORY,
+        }) as nvmlPowerScopeType_t
+    }
+}
+
+impl TryFrom<nvmlPowerScopeType_t> for PowerScope {
+    type Error = NvmlError;
+
+    fn try_from(data: nvmlPowerScopeType_t) -> Result<Self, Self::Error> {
+        match u32::from(data) {
+            NVML_POWER_SCOPE_GPU => Ok(Self::Gpu),
+            NVML_POWER_SCOPE_MODULE => Ok(Self::Module),
+            NVML_POWER_SCOPE_MEMORY => Ok(Self::Memory),
+            scope => Err(NvmlError::UnexpectedVariant(scope)),
+        }
+    }
+}
+
+impl From<PowerScope> for nvmlPowerScopeType_t {
+    fn from(wrapper: PowerScope) -> Self {
+        wrapper.as_c()
+    }
+}
+
+/// The size of a MIG GPU instance, in slices of the GPU, as passed to
+/// `Device.gpu_instance_profile_info()`.
+// TODO: technically this is an "enum wrapper" but the type on the C side isn't
+// an enum
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GpuInstanceProfile {
+    Slice1,
+    Slice2,
+    Slice3,
+    Slice4,
+    Slice7,
+    Slice8,
+    Slice6,
+    /// A single slice with more media engines than `Slice1`.
+    Slice1Rev1,
+}
+
+impl GpuInstanceProfile {
+    /// Returns the C constant equivalent for the given Rust enum variant.
+    pub fn as_c(&self) -> c_uint {
+        match *self {
+            Self::Slice1 => NVML_GPU_INSTANCE_PROFILE_1_SLICE,
+            Self::Slice2 => NVML_GPU_INSTANCE_PROFILE_2_SLICE,
+            Self::Slice3 => NVML_GPU_INSTANCE_PROFILE_3_SLICE,
+            Self::Slice4 => NVML_GPU_INSTANCE_PROFILE_4_SLICE,
+            Self::Slice7 => NVML_GPU_INSTANCE_PROFILE_7_SLICE,
+            Self::Slice8 => NVML_GPU_INSTANCE_PROFILE_8_SLICE,
+            Self::Slice6 => NVML_GPU_INSTANCE_PROFILE_6_SLICE,
+            Self::Slice1Rev1 => NVML_GPU_INSTANCE_PROFILE_1_SLICE_REV1,
+        }
+    }
+}
+
+impl TryFrom<c_uint> for GpuInstanceProfile {
+    type Error = NvmlError;
+
+    fn try_from(data: c_uint) -> Result<Self, Self::Error> {
+        match data {
+            NVML_GPU_INSTANCE_PROFILE_1_SLICE => Ok(Self::Slice1),
+            NVML_GPU_INSTANCE_PROFILE_2_SLICE => Ok(Self::Slice2),
+            NVML_GPU_INSTANCE_PROFILE_3_SLICE => Ok(Self::Slice3),
+            NVML_GPU_INSTANCE_PROFILE_4_SLICE => Ok(Self::Slice4),
+            NVML_GPU_INSTANCE_PROFILE_7_SLICE => Ok(Self::Slice7),
+            NVML_GPU_INSTANCE_PROFILE_8_SLICE => Ok(Self::Slice8),
+            NVML_GPU_INSTANCE_PROFILE_6_SLICE => Ok(Self::Slice6),
+            NVML_GPU_INSTANCE_PROFILE_1_SLICE_REV1 => Ok(Self::Slice1Rev1),
+            _ => Err(NvmlError::UnexpectedVariant(data)),
+        }
+    }
+}
+
+impl From<GpuInstanceProfile> for c_uint {
+    fn from(wrapper: GpuInstanceProfile) -> Self {
+        wrapper.as_c()
+    }
+}
+
+/// The size of a MIG compute instance, in slices of its GPU instance, as
+/// passed to `GpuInstance.compute_instance_profile_info()`.
+// TODO: technically this is an "enum wrapper" but the type on the C side isn't
+// an enum
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ComputeInstanceProfile {
+    Slice1,
+    Slice2,
+    Slice3,
+    Slice4,
+    Slice7,
+    Slice8,
+    Slice6,
+}
+
+impl ComputeInstanceProfile {
+    /// Returns the C constant equivalent for the given Rust enum variant.
+    pub fn as_c(&self) -> c_uint {
+        match *self {
+            Self::Slice1 => NVML_COMPUTE_INSTANCE_PROFILE_1_SLICE,
+            Self::Slice2 => NVML_COMPUTE_INSTANCE_PROFILE_2_SLICE,
+            Self::Slice3 => NVML_COMPUTE_INSTANCE_PROFILE_3_SLICE,
+            Self::Slice4 => NVML_COMPUTE_INSTANCE_PROFILE_4_SLICE,
+            Self::Slice7 => NVML_COMPUTE_INSTANCE_PROFILE_7_SLICE,
+            Self::Slice8 => NVML_COMPUTE_INSTANCE_PROFILE_8_SLICE,
+            Self::Slice6 => NVML_COMPUTE_INSTANCE_PROFILE_6_SLICE,
+        }
+    }
+}
+
+impl TryFrom<c_uint> for ComputeInstanceProfile {
+    type Error = NvmlError;
+
+    fn try_from(data: c_uint) -> Result<Self, Self::Error> {
+        match data {
+            NVML_COMPUTE_INSTANCE_PROFILE_1_SLICE => Ok(Self::Slice1),
+            NVML_COMPUTE_INSTANCE_PROFILE_2_SLICE => Ok(Self::Slice2),
+            NVML_COMPUTE_INSTANCE_PROFILE_3_SLICE => Ok(Self::Slice3),
+            NVML_COMPUTE_INSTANCE_PROFILE_4_SLICE => Ok(Self::Slice4),
+            NVML_COMPUTE_INSTANCE_PROFILE_7_SLICE => Ok(Self::Slice7),
+            NVML_COMPUTE_INSTANCE_PROFILE_8_SLICE => Ok(Self::Slice8),
+            NVML_COMPUTE_INSTANCE_PROFILE_6_SLICE => Ok(Self::Slice6),
+            _ => Err(NvmlError::UnexpectedVariant(data)),
+        }
+    }
+}
+
+impl From<ComputeInstanceProfile> for c_uint {
+    fn from(wrapper: ComputeInstanceProfile) -> Self {
+        wrapper.as_c()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sample_value_accessors() {
+        assert_eq!(SampleValue::U32(7).as_u32().unwrap(), 7);
+        assert_eq!(SampleValue::U32(7).as_u64().unwrap(), 7);
+        assert_eq!(SampleValue::U32(7).as_i64().unwrap(), 7);
+        assert_eq!(SampleValue::U64(u64::MAX).as_u64().unwrap(), u64::MAX);
+        assert_eq!(SampleValue::I64(-1).as_i64().unwrap(), -1);
+        assert_eq!(SampleValue::F64(0.5).as_f64().unwrap(), 0.5);
+
+        assert!(matches!(
+            SampleValue::U64(u64::MAX).as_u32(),
+            Err(NvmlError::IncorrectValueType {
+                value: SampleValue::U64(_),
+                requested: "u32"
+            })
+        ));
+        assert!(SampleValue::I64(-1).as_u64().is_err());
+        assert!(SampleValue::F64(1.0).as_u64().is_err());
+        assert!(SampleValue::U32(1).as_f64().is_err());
+    }
+
+    #[test]
+    fn fabric_state_round_trip() {
+        for state in [
+            FabricState::NotSupported,
+            FabricState::NotStarted,
+            FabricState::InProgress,
+            FabricState::Completed,
+        ] {
+            assert_eq!(FabricState::try_from(state.as_c()).unwrap(), state);
+        }
+
+        assert!(matches!(
+            FabricState::try_from(4),
+            Err(NvmlError::UnexpectedVariant(4))
+        ));
+    }
+
+    #[test]
+    fn fan_control_policy_round_trip() {
+        for policy in [
+            FanControlPolicy::TemperatureContinuousSw,
+            FanControlPolicy::Manual,
+        ] {
+            assert_eq!(FanControlPolicy::try_from(policy.as_c()).unwrap(), policy);
+        }
+
+        assert!(matches!(
+            FanControlPolicy::try_from(2),
+            Err(NvmlError::UnexpectedVariant(2))
+        ));
+    }
+
+    #[test]
+    fn power_scope_round_trip() {
+        for scope in [PowerScope::Gpu, PowerScope::Module, PowerScope::Memory] {
+            assert_eq!(PowerScope::try_from(scope.as_c()).unwrap(), scope);
+        }
+
+        assert!(matches!(
+            PowerScope::try_from(3),
+            Err(NvmlError::UnexpectedVariant(3))
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::useless_conversion)]
+    fn unsigned_long_is_not_truncated() {
+        let union = nvmlValue_t {
+            ulVal: c_ulong::MAX,
+        };
+
+        assert_eq!(
+            SampleValue::from_tag_and_union(&SampleValueType::UnsignedLong, union),
+            SampleValue::U64(c_ulong::MAX.into())
+        );
+    }
+}
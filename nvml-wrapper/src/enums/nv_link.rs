@@ -1,5 +1,7 @@
+use crate::error::NvmlError;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, os::raw::c_uint};
 
 /// Used to specify the counter in `NvLink.set_utilization_control_for()`
 ///
@@ -11,3 +13,21 @@ pub enum Counter {
     Zero = 0,
     One = 1,
 }
+
+impl TryFrom<c_uint> for Counter {
+    type Error = NvmlError;
+
+    fn try_from(data: c_uint) -> Result<Self, Self::Error> {
+        match data {
+            0 => Ok(Self::Zero),
+            1 => Ok(Self::One),
+            _ => Err(NvmlError::UnexpectedVariant(data)),
+        }
+    }
+}
+
+impl From<Counter> for c_uint {
+    fn from(wrapper: Counter) -> Self {
+        wrapper as c_uint
+    }
+}
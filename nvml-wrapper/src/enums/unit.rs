@@ -2,7 +2,7 @@ use crate::error::NvmlError;
 use crate::ffi::bindings::*;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::{convert::TryFrom, ffi::CStr};
+use std::{convert::TryFrom, ffi::CStr, os::raw::c_uint};
 
 /// LED states for an S-class unit.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -49,3 +49,22 @@ pub enum TemperatureReading {
     Exhaust = 1,
     Board = 2,
 }
+
+impl TryFrom<c_uint> for TemperatureReading {
+    type Error = NvmlError;
+
+    fn try_from(data: c_uint) -> Result<Self, Self::Error> {
+        match data {
+            0 => Ok(Self::Intake),
+            1 => Ok(Self::Exhaust),
+            2 => Ok(Self::Board),
+            _ => Err(NvmlError::UnexpectedVariant(data)),
+        }
+    }
+}
+
+impl From<TemperatureReading> for c_uint {
+    fn from(wrapper: TemperatureReading) -> Self {
+        wrapper as c_uint
+    }
+}
@@ -1,6 +1,16 @@
+use crate::device::with_string_buffer;
+use crate::enum_wrappers::device::Api;
+use crate::enums::device::{DeviceArchitecture, SampleValue};
 use crate::ffi::bindings::*;
+use crate::permissions;
+use crate::support;
+use crate::uuid::ParseUuidError;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use static_assertions::assert_impl_all;
+use std::{ffi::OsStr, fmt, io, os::raw::c_uint, path::PathBuf};
+#[cfg(target_os = "linux")]
+use std::{fs, path::Path};
 use thiserror::Error;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -11,7 +21,7 @@ pub enum Bits {
 }
 
 /// An `NvmlError` with an optionally present source error for chaining errors
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Eq, PartialEq)]
 #[error("{error}")]
 pub struct NvmlErrorWithSource {
     pub error: NvmlError,
@@ -68,9 +78,40 @@ pub enum NvmlError {
     #[error("unexpected enum variant value: {0}")]
     UnexpectedVariant(u32),
 
+    /**
+    A UUID obtained from NVML could not be parsed.
+
+    This error is specific to this Rust wrapper. See the `uuid` module.
+    */
+    #[error(transparent)]
+    InvalidUuid(#[from] ParseUuidError),
+
+    /**
+    A `SampleValue` was read as a type that it does not hold.
+
+    This error is specific to this Rust wrapper. `requested` is the name of the
+    Rust type the value was requested as.
+    */
+    #[error("sample value {value:?} cannot be read as `{requested}`")]
+    IncorrectValueType {
+        value: SampleValue,
+        requested: &'static str,
+    },
+
     #[error("a call to `EventSet.release_events()` failed")]
     SetReleaseFailed,
 
+    /**
+    A call to `sched_setaffinity` made by `Device.set_current_thread_affinity()`
+    failed.
+
+    This error is specific to this Rust wrapper. The single field contains the
+    error reported by the operating system, e.g. `EINVAL` if none of the CPUs
+    closest to the device are in the calling thread's cpuset.
+    */
+    #[error("a call to `sched_setaffinity` failed: {0}")]
+    SetAffinityFailed(std::io::Error),
+
     #[error("a call to `Device.pci_info()` failed")]
     GetPciInfoFailed,
 
@@ -83,11 +124,39 @@ pub enum NvmlError {
     #[error("a supplied argument was invalid")]
     InvalidArg,
 
-    #[error("the requested operation is not available on the target device")]
-    NotSupported,
+    /**
+    The index passed to `Nvml.device_by_index()` was out of range.
+
+    NVML reports this as an invalid argument; the wrapper adds the number of
+    devices present at the time, which helps diagnose e.g. a misconfigured
+    `CUDA_VISIBLE_DEVICES`.
+    */
+    #[error("device index {index} requested, only {count} devices present")]
+    DeviceIndexOutOfRange { index: u32, count: u32 },
+
+    /**
+    The requested operation is not available on the target device.
+
+    The single field contains context about the call that failed when it is
+    known; see `UnsupportedContext`.
+    */
+    #[error(
+        "the requested operation is not available on the target device{}",
+        .0.as_ref().map(|c| format!(" ({})", c)).unwrap_or_default()
+    )]
+    NotSupported(Option<UnsupportedContext>),
 
-    #[error("the current user does not have permission to perform this operation")]
-    NoPermission,
+    /**
+    The current user does not have permission to perform the operation.
+
+    The single field contains context about the call that failed when it is
+    known, including the privilege it requires; see `PermissionContext`.
+    */
+    #[error(
+        "the current user does not have permission to perform this operation{}",
+        .0.as_ref().map(|c| format!(" ({})", c)).unwrap_or_default()
+    )]
+    NoPermission(Option<PermissionContext>),
 
     #[error("NVML was already initialized")]
     #[deprecated = "deprecated in NVML (multiple initializations now allowed via refcounting)"]
@@ -116,8 +185,18 @@ pub enum NvmlError {
     #[error("device's external power cables are not properly attached")]
     InsufficientPower,
 
-    #[error("NVIDIA driver is not loaded")]
-    DriverNotLoaded,
+    /**
+    The NVIDIA driver is not loaded.
+
+    The single field contains `InitDiagnostics` describing the NVML library and
+    kernel module when the error comes from initialization or one of the first
+    calls made after it (`Nvml.device_count()`, `Nvml.device_by_index()`).
+    */
+    #[error(
+        "NVIDIA driver is not loaded{}",
+        .0.as_ref().map(|d| format!(" ({})", d)).unwrap_or_default()
+    )]
+    DriverNotLoaded(Option<Box<InitDiagnostics>>),
 
     #[error("the provided timeout was reached")]
     Timeout,
@@ -143,8 +222,16 @@ pub enum NvmlError {
     #[error("device control has been blocked by the operating system/cgroups")]
     OperatingSystem,
 
-    #[error("RM detects a driver/library version mismatch")]
-    LibRmVersionMismatch,
+    /**
+    The NVML library does not match the version of the loaded kernel module.
+
+    The single field contains `InitDiagnostics`, as for `DriverNotLoaded`.
+    */
+    #[error(
+        "RM detects a driver/library version mismatch{}",
+        .0.as_ref().map(|d| format!(" ({})", d)).unwrap_or_default()
+    )]
+    LibRmVersionMismatch(Option<Box<InitDiagnostics>>),
 
     #[error("operation cannot be performed because the GPU is currently in use")]
     InUse,
@@ -163,6 +250,593 @@ pub enum NvmlError {
 
     #[error("an internal driver error occured")]
     Unknown,
+
+    /**
+    NVML returned an error code that this wrapper doesn't know about, e.g. one
+    introduced in a newer driver.
+
+    The single field contains the code as returned by NVML.
+    */
+    #[error("NVML returned an unrecognized error code: {0}")]
+    UnrecognizedCode(nvmlReturn_t),
+}
+
+assert_impl_all!(NvmlError: Send, Sync);
+assert_impl_all!(NvmlErrorWithSource: Send, Sync);
+
+/**
+Errors compare equal if they are the same variant holding equal data, so
+that they can be asserted against in tests.
+
+The context carried by `NotSupported` and `NoPermission` is ignored, so that
+`NotSupported(Some(_)) == NotSupported(None)`; it only describes the call that
+failed. `LibloadingError`s are compared by their messages, as
+`libloading::Error` itself can't be compared, and `SetAffinityFailed` errors
+by their kinds and OS error codes. `SampleValue::F64` values in
+`IncorrectValueType` are compared bit for bit so that every error is equal to
+itself.
+*/
+impl PartialEq for NvmlError {
+    // Matching on `self` alone makes adding a variant without deciding how it
+    // compares a compile error
+    #[allow(deprecated)]
+    fn eq(&self, other: &Self) -> bool {
+        use NvmlError::*;
+
+        match self {
+            Utf8Error(a) => matches!(other, Utf8Error(b) if a == b),
+            NulError(a) => matches!(other, NulError(b) if a == b),
+            LibloadingError(a) => {
+                matches!(other, LibloadingError(b) if a.to_string() == b.to_string())
+            }
+            FailedToLoadSymbol(a) => matches!(other, FailedToLoadSymbol(b) if a == b),
+            StringTooLong {
+                max_len: a_max,
+                actual_len: a_actual,
+            } => matches!(
+                other,
+                StringTooLong {
+                    max_len: b_max,
+                    actual_len: b_actual,
+                } if a_max == b_max && a_actual == b_actual
+            ),
+            IncorrectBits(a) => matches!(other, IncorrectBits(b) if a == b),
+            UnexpectedVariant(a) => matches!(other, UnexpectedVariant(b) if a == b),
+            InvalidUuid(a) => matches!(other, InvalidUuid(b) if a == b),
+            IncorrectValueType {
+                value: a_value,
+                requested: a_requested,
+            } => match other {
+                IncorrectValueType {
+                    value: b_value,
+                    requested: b_requested,
+                } => {
+                    let same_value = match (a_value, b_value) {
+                        (SampleValue::F64(a), SampleValue::F64(b)) => a.to_bits() == b.to_bits(),
+                        (a, b) => a == b,
+                    };
+
+                    same_value && a_requested == b_requested
+                }
+                _ => false,
+            },
+            SetReleaseFailed => matches!(other, SetReleaseFailed),
+            SetAffinityFailed(a) => matches!(
+                other,
+                SetAffinityFailed(b) if a.kind() == b.kind() && a.raw_os_error() == b.raw_os_error()
+            ),
+            GetPciInfoFailed => matches!(other, GetPciInfoFailed),
+            PciInfoToCFailed => matches!(other, PciInfoToCFailed),
+            Uninitialized => matches!(other, Uninitialized),
+            InvalidArg => matches!(other, InvalidArg),
+            DeviceIndexOutOfRange {
+                index: a_index,
+                count: a_count,
+            } => matches!(
+                other,
+                DeviceIndexOutOfRange {
+                    index: b_index,
+                    count: b_count,
+                } if a_index == b_index && a_count == b_count
+            ),
+            NotSupported(_) => matches!(other, NotSupported(_)),
+            NoPermission(_) => matches!(other, NoPermission(_)),
+            AlreadyInitialized => matches!(other, AlreadyInitialized),
+            NotFound => matches!(other, NotFound),
+            InsufficientSize(a) => matches!(other, InsufficientSize(b) if a == b),
+            InsufficientPower => matches!(other, InsufficientPower),
+            DriverNotLoaded(a) => matches!(other, DriverNotLoaded(b) if a == b),
+            Timeout => matches!(other, Timeout),
+            IrqIssue => matches!(other, IrqIssue),
+            LibraryNotFound => matches!(other, LibraryNotFound),
+            FunctionNotFound => matches!(other, FunctionNotFound),
+            CorruptedInfoROM => matches!(other, CorruptedInfoROM),
+            GpuLost => matches!(other, GpuLost),
+            ResetRequired => matches!(other, ResetRequired),
+            OperatingSystem => matches!(other, OperatingSystem),
+            LibRmVersionMismatch(a) => matches!(other, LibRmVersionMismatch(b) if a == b),
+            InUse => matches!(other, InUse),
+            InsufficientMemory => matches!(other, InsufficientMemory),
+            NoData => matches!(other, NoData),
+            VgpuEccNotSupported => matches!(other, VgpuEccNotSupported),
+            Unknown => matches!(other, Unknown),
+            UnrecognizedCode(a) => matches!(other, UnrecognizedCode(b) if a == b),
+        }
+    }
+}
+
+impl Eq for NvmlError {}
+
+/**
+Context attached to an `NvmlError::NotSupported` error.
+
+Describes which NVML function reported the lack of support and, where known,
+which devices do support it.
+
+Only implements `Serialize`, as its fields borrow from the wrapper's own
+tables.
+*/
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct UnsupportedContext {
+    /// The name of the NVML function that was called, e.g.
+    /// `nvmlDeviceGetFanSpeed_v2`.
+    pub function: &'static str,
+    /// NVIDIA's description of the devices that support `function`, if known.
+    pub device_support: Option<&'static str>,
+    /// The oldest architecture that supports `function`, if it is known and
+    /// support depends on it.
+    pub min_architecture: Option<DeviceArchitecture>,
+    /// Whether or not the wrapper is running under WSL2 and `function` is
+    /// known to be unavailable there, regardless of the device.
+    pub wsl2: bool,
+}
+
+impl UnsupportedContext {
+    /// Create context for the given NVML function, filling in what is known
+    /// about which devices support it.
+    pub fn for_function(function: &'static str) -> Self {
+        let (device_support, min_architecture) = match support::lookup(function) {
+            Some((text, arch)) => (Some(text), arch),
+            None => (None, None),
+        };
+
+        Self {
+            function,
+            device_support,
+            min_architecture,
+            wsl2: false,
+        }
+    }
+}
+
+impl fmt::Display for UnsupportedContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}`", self.function)?;
+
+        if self.wsl2 {
+            return write!(f, ": not available under WSL2");
+        }
+
+        if let Some(support) = self.device_support {
+            write!(f, ": {}", support)?;
+        }
+
+        Ok(())
+    }
+}
+
+/**
+Context attached to an `NvmlError::NoPermission` error.
+
+Describes which NVML function was denied and, for functions that are known to
+require it, the privilege needed to call it.
+
+Only implements `Serialize`, as its fields borrow from the wrapper's own
+tables.
+*/
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PermissionContext {
+    /// The name of the NVML function that was called, e.g.
+    /// `nvmlDeviceSetPowerManagementLimit`.
+    pub function: &'static str,
+    /// The privilege `function` requires, if it is known to require one.
+    ///
+    /// `None` for functions that any user may call. These usually fail with
+    /// `NoPermission` because the user can't access the device nodes
+    /// (`/dev/nvidia*` on Linux).
+    pub required: Option<RequiredPrivilege>,
+}
+
+impl PermissionContext {
+    /// Create context for the given NVML function, filling in the privilege
+    /// it is known to require.
+    pub fn for_function(function: &'static str) -> Self {
+        Self {
+            function,
+            required: permissions::lookup(function),
+        }
+    }
+}
+
+impl fmt::Display for PermissionContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}`", self.function)?;
+
+        if let Some(ref required) = self.required {
+            write!(f, ": {}", required)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The privilege an NVML function requires. See `PermissionContext`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RequiredPrivilege {
+    /**
+    Must be called as root on Linux (or with the `CAP_SYS_ADMIN` capability,
+    which containers don't have by default) or as an administrator on
+    Windows.
+    */
+    Root,
+    /**
+    Like `Root`, but only while the given API is restricted. Root can lift the
+    restriction for all users with `Device.set_api_restricted()` and query it
+    with `Device.is_api_restricted()`.
+
+    `Api::ApplicationClocks` is restricted by default; `Api::AutoBoostedClocks`
+    is not.
+    */
+    RootWhileRestricted(Api),
+}
+
+impl fmt::Display for RequiredPrivilege {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let root = "requires root or CAP_SYS_ADMIN (administrator on Windows)";
+
+        match self {
+            RequiredPrivilege::Root => write!(f, "{}", root),
+            RequiredPrivilege::RootWhileRestricted(api) => write!(
+                f,
+                "{} unless the `{:?}` API restriction has been lifted",
+                root, api
+            ),
+        }
+    }
+}
+
+/**
+Context attached to `NvmlError::DriverNotLoaded` and
+`NvmlError::LibRmVersionMismatch` errors.
+
+Both errors usually mean the driver install is broken: the kernel module
+isn't loaded, or the NVML library on the library path belongs to another
+driver version than the loaded kernel module (e.g. after a driver upgrade
+without a reboot). Comparing `library_version` with `kernel_module_version`
+tells these cases apart.
+*/
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InitDiagnostics {
+    /// The path of the NVML library that was loaded. On Linux, this is the
+    /// file the dynamic linker resolved it to; elsewhere, the name it was
+    /// opened by.
+    pub library_path: Option<PathBuf>,
+    /// The version of the NVML library, e.g. `12.535.104.05`, if it could be
+    /// obtained.
+    pub library_version: Option<String>,
+    /// Whether the `nvidia` kernel module is loaded. Linux only; `None`
+    /// elsewhere.
+    pub kernel_module_loaded: Option<bool>,
+    /// The version of the loaded `nvidia` kernel module, e.g. `535.104.05`.
+    /// Linux only; `None` elsewhere.
+    pub kernel_module_version: Option<String>,
+}
+
+impl InitDiagnostics {
+    // Collects what can be found out about the library opened by the given
+    // name and the kernel module. Doesn't require NVML to be initialized.
+    pub(crate) fn collect(lib: &NvmlLib, opened_as: &OsStr) -> Self {
+        let library_path = loaded_library_path().unwrap_or_else(|| opened_as.into());
+
+        let library_version = nvml_version(lib).or_else(|| {
+            // The driver installs the library as `libnvidia-ml.so.<version>`
+            let name = library_path.file_name()?.to_str()?;
+            let version = name.strip_prefix("libnvidia-ml.so.")?;
+            version.contains('.').then(|| version.to_string())
+        });
+
+        let (kernel_module_loaded, kernel_module_version) = kernel_module();
+
+        Self {
+            library_path: Some(library_path),
+            library_version,
+            kernel_module_loaded,
+            kernel_module_version,
+        }
+    }
+}
+
+impl fmt::Display for InitDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = vec![];
+
+        if let Some(ref path) = self.library_path {
+            parts.push(format!("library `{}`", path.display()));
+        }
+
+        if let Some(ref version) = self.library_version {
+            parts.push(format!("library version {}", version));
+        }
+
+        match (self.kernel_module_loaded, &self.kernel_module_version) {
+            (_, Some(version)) => parts.push(format!("kernel module version {}", version)),
+            (Some(false), None) => parts.push("kernel module not loaded".into()),
+            _ => {}
+        }
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+// Attaches `InitDiagnostics` to the errors they help diagnose.
+pub(crate) fn with_init_diagnostics(
+    error: NvmlError,
+    lib: &NvmlLib,
+    opened_as: &OsStr,
+) -> NvmlError {
+    let diagnostics = || Some(Box::new(InitDiagnostics::collect(lib, opened_as)));
+
+    match error {
+        NvmlError::DriverNotLoaded(None) => NvmlError::DriverNotLoaded(diagnostics()),
+        NvmlError::LibRmVersionMismatch(None) => NvmlError::LibRmVersionMismatch(diagnostics()),
+        other => other,
+    }
+}
+
+// Some drivers report their version before `nvmlInit()` has been called.
+fn nvml_version(lib: &NvmlLib) -> Option<String> {
+    let sym = lib.nvmlSystemGetNVMLVersion.as_ref().ok()?;
+
+    unsafe {
+        with_string_buffer(NVML_SYSTEM_NVML_VERSION_BUFFER_SIZE, |buffer, len| {
+            nvml_try(sym(buffer, len))
+        })
+        .ok()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn loaded_library_path() -> Option<PathBuf> {
+    let maps = fs::read_to_string("/proc/self/maps").ok()?;
+
+    maps.lines()
+        .filter_map(|line| line.split_whitespace().nth(5))
+        .find(|path| path.contains("libnvidia-ml"))
+        .map(PathBuf::from)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn loaded_library_path() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn kernel_module() -> (Option<bool>, Option<String>) {
+    let loaded = Path::new("/sys/module/nvidia").exists();
+    let version = fs::read_to_string("/sys/module/nvidia/version")
+        .ok()
+        .map(|v| v.trim().to_string());
+
+    (Some(loaded), version)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn kernel_module() -> (Option<bool>, Option<String>) {
+    (None, None)
+}
+
+/**
+Machine-usable advice on how to recover from an `NvmlError`.
+
+Obtain one via `NvmlError.recovery_hint()`.
+*/
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecoveryHint {
+    /// The condition is likely transient; retrying the same call later may
+    /// succeed.
+    Retry,
+    /// NVML (or the driver) needs to be (re)initialized before calls can
+    /// succeed. Drop the `Nvml` instance and initialize a new one, possibly
+    /// after the driver has been (re)loaded.
+    Reinit,
+    /// The GPU is in a state that requires a reset (or a reboot) before it can
+    /// be used again.
+    ResetGpu,
+    /// The calling process lacks the privileges required for the operation.
+    CheckPermissions,
+    /// The operation is not supported by this GPU, driver, or library version.
+    /// Retrying will never succeed.
+    Unsupported,
+    /// The error stems from invalid input, a bug, or broken hardware or
+    /// software. Retrying will not help without outside intervention.
+    Fatal,
+}
+
+impl NvmlError {
+    /**
+    Get advice on how to recover from this error.
+
+    The mapping is a best-effort interpretation of NVIDIA's documentation for
+    each return code and is intended to give long-running processes a single,
+    consistent place to decide between retrying, re-initializing, and giving up.
+    */
+    #[allow(deprecated)]
+    pub fn recovery_hint(&self) -> RecoveryHint {
+        use NvmlError::*;
+
+        match self {
+            Timeout | InUse | InsufficientMemory | NoData => RecoveryHint::Retry,
+            Uninitialized
+            | DriverNotLoaded(_)
+            | LibRmVersionMismatch(_)
+            | LibraryNotFound
+            | LibloadingError(_) => RecoveryHint::Reinit,
+            GpuLost | ResetRequired | IrqIssue => RecoveryHint::ResetGpu,
+            NoPermission(_) => RecoveryHint::CheckPermissions,
+            SetAffinityFailed(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                RecoveryHint::CheckPermissions
+            }
+            NotSupported(_) | VgpuEccNotSupported | FunctionNotFound | FailedToLoadSymbol(_) => {
+                RecoveryHint::Unsupported
+            }
+            Utf8Error(_)
+            | NulError(_)
+            | StringTooLong { .. }
+            | IncorrectBits(_)
+            | UnexpectedVariant(_)
+            | InvalidUuid(_)
+            | IncorrectValueType { .. }
+            | SetReleaseFailed
+            | SetAffinityFailed(_)
+            | GetPciInfoFailed
+            | PciInfoToCFailed
+            | InvalidArg
+            | DeviceIndexOutOfRange { .. }
+            | AlreadyInitialized
+            | NotFound
+            | InsufficientSize(_)
+            | InsufficientPower
+            | CorruptedInfoROM
+            | OperatingSystem
+            | Unknown
+            | UnrecognizedCode(_) => RecoveryHint::Fatal,
+        }
+    }
+
+    // Fills in the size NVML wrote into an in/out count parameter alongside
+    // `NVML_ERROR_INSUFFICIENT_SIZE`.
+    pub(crate) fn with_required_size(self, size: c_uint) -> Self {
+        match self {
+            NvmlError::InsufficientSize(None) => NvmlError::InsufficientSize(Some(size as usize)),
+            other => other,
+        }
+    }
+
+    /**
+    Get the `nvmlReturn_t` code NVML returned to cause this error, e.g. for
+    inclusion in bug reports.
+
+    Returns `None` for errors that originate in this wrapper rather than in
+    NVML, such as `Utf8Error` or `UnexpectedVariant`.
+    */
+    #[allow(deprecated)]
+    pub fn raw_code(&self) -> Option<nvmlReturn_t> {
+        use NvmlError::*;
+
+        let code = match self {
+            Uninitialized => nvmlReturn_enum_NVML_ERROR_UNINITIALIZED,
+            InvalidArg | DeviceIndexOutOfRange { .. } => {
+                nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT
+            }
+            NotSupported(_) => nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED,
+            NoPermission(_) => nvmlReturn_enum_NVML_ERROR_NO_PERMISSION,
+            AlreadyInitialized => nvmlReturn_enum_NVML_ERROR_ALREADY_INITIALIZED,
+            NotFound => nvmlReturn_enum_NVML_ERROR_NOT_FOUND,
+            InsufficientSize(_) => nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE,
+            InsufficientPower => nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_POWER,
+            DriverNotLoaded(_) => nvmlReturn_enum_NVML_ERROR_DRIVER_NOT_LOADED,
+            Timeout => nvmlReturn_enum_NVML_ERROR_TIMEOUT,
+            IrqIssue => nvmlReturn_enum_NVML_ERROR_IRQ_ISSUE,
+            LibraryNotFound => nvmlReturn_enum_NVML_ERROR_LIBRARY_NOT_FOUND,
+            FunctionNotFound => nvmlReturn_enum_NVML_ERROR_FUNCTION_NOT_FOUND,
+            CorruptedInfoROM => nvmlReturn_enum_NVML_ERROR_CORRUPTED_INFOROM,
+            GpuLost => nvmlReturn_enum_NVML_ERROR_GPU_IS_LOST,
+            ResetRequired => nvmlReturn_enum_NVML_ERROR_RESET_REQUIRED,
+            OperatingSystem => nvmlReturn_enum_NVML_ERROR_OPERATING_SYSTEM,
+            LibRmVersionMismatch(_) => nvmlReturn_enum_NVML_ERROR_LIB_RM_VERSION_MISMATCH,
+            InUse => nvmlReturn_enum_NVML_ERROR_IN_USE,
+            InsufficientMemory => nvmlReturn_enum_NVML_ERROR_MEMORY,
+            NoData => nvmlReturn_enum_NVML_ERROR_NO_DATA,
+            VgpuEccNotSupported => nvmlReturn_enum_NVML_ERROR_VGPU_ECC_NOT_SUPPORTED,
+            Unknown => nvmlReturn_enum_NVML_ERROR_UNKNOWN,
+            UnrecognizedCode(code) => *code,
+            Utf8Error(_)
+            | NulError(_)
+            | LibloadingError(_)
+            | FailedToLoadSymbol(_)
+            | StringTooLong { .. }
+            | IncorrectBits(_)
+            | UnexpectedVariant(_)
+            | InvalidUuid(_)
+            | IncorrectValueType { .. }
+            | SetReleaseFailed
+            | SetAffinityFailed(_)
+            | GetPciInfoFailed
+            | PciInfoToCFailed => return None,
+        };
+
+        Some(code)
+    }
+}
+
+impl NvmlErrorWithSource {
+    /// Get advice on how to recover from the contained `error`.
+    ///
+    /// See `NvmlError.recovery_hint()`.
+    pub fn recovery_hint(&self) -> RecoveryHint {
+        self.error.recovery_hint()
+    }
+
+    /// Get the `nvmlReturn_t` code behind the contained `error`.
+    ///
+    /// See `NvmlError.raw_code()`.
+    pub fn raw_code(&self) -> Option<nvmlReturn_t> {
+        self.error.raw_code()
+    }
+}
+
+/**
+Extension methods for results returned by this wrapper.
+
+Import with `use nvml_wrapper::error::*;`.
+*/
+pub trait NvmlResultExt<T> {
+    /**
+    Treat errors that mean "there is no value" as `Ok(None)`.
+
+    `NotSupported` and `NoData` are converted; any other error is returned
+    as-is. This replaces the common pattern of matching on those two variants when querying
+    values that only some devices report:
+
+    ```no_run
+    # use nvml_wrapper::Nvml;
+    # use nvml_wrapper::error::*;
+    # fn main() -> Result<(), NvmlError> {
+    # let nvml = Nvml::init()?;
+    # let device = nvml.device_by_index(0)?;
+    if let Some(speed) = device.fan_speed(0).optional()? {
+        println!("fan 0 is at {}%", speed);
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    fn optional(self) -> Result<Option<T>, NvmlError>;
+}
+
+impl<T> NvmlResultExt<T> for Result<T, NvmlError> {
+    fn optional(self) -> Result<Option<T>, NvmlError> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(NvmlError::NotSupported(_)) | Err(NvmlError::NoData) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 /// Converts an `nvmlReturn_t` type into a `Result<(), NvmlError>`.
@@ -174,13 +848,13 @@ pub fn nvml_try(code: nvmlReturn_t) -> Result<(), NvmlError> {
         nvmlReturn_enum_NVML_SUCCESS => Ok(()),
         nvmlReturn_enum_NVML_ERROR_UNINITIALIZED => Err(Uninitialized),
         nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT => Err(InvalidArg),
-        nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED => Err(NotSupported),
-        nvmlReturn_enum_NVML_ERROR_NO_PERMISSION => Err(NoPermission),
+        nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED => Err(NotSupported(None)),
+        nvmlReturn_enum_NVML_ERROR_NO_PERMISSION => Err(NoPermission(None)),
         nvmlReturn_enum_NVML_ERROR_ALREADY_INITIALIZED => Err(AlreadyInitialized),
         nvmlReturn_enum_NVML_ERROR_NOT_FOUND => Err(NotFound),
         nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => Err(InsufficientSize(None)),
         nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_POWER => Err(InsufficientPower),
-        nvmlReturn_enum_NVML_ERROR_DRIVER_NOT_LOADED => Err(DriverNotLoaded),
+        nvmlReturn_enum_NVML_ERROR_DRIVER_NOT_LOADED => Err(DriverNotLoaded(None)),
         nvmlReturn_enum_NVML_ERROR_TIMEOUT => Err(Timeout),
         nvmlReturn_enum_NVML_ERROR_IRQ_ISSUE => Err(IrqIssue),
         nvmlReturn_enum_NVML_ERROR_LIBRARY_NOT_FOUND => Err(LibraryNotFound),
@@ -189,17 +863,362 @@ pub fn nvml_try(code: nvmlReturn_t) -> Result<(), NvmlError> {
         nvmlReturn_enum_NVML_ERROR_GPU_IS_LOST => Err(GpuLost),
         nvmlReturn_enum_NVML_ERROR_RESET_REQUIRED => Err(ResetRequired),
         nvmlReturn_enum_NVML_ERROR_OPERATING_SYSTEM => Err(OperatingSystem),
-        nvmlReturn_enum_NVML_ERROR_LIB_RM_VERSION_MISMATCH => Err(LibRmVersionMismatch),
+        nvmlReturn_enum_NVML_ERROR_LIB_RM_VERSION_MISMATCH => Err(LibRmVersionMismatch(None)),
         nvmlReturn_enum_NVML_ERROR_IN_USE => Err(InUse),
         nvmlReturn_enum_NVML_ERROR_MEMORY => Err(InsufficientMemory),
         nvmlReturn_enum_NVML_ERROR_NO_DATA => Err(NoData),
         nvmlReturn_enum_NVML_ERROR_VGPU_ECC_NOT_SUPPORTED => Err(VgpuEccNotSupported),
         nvmlReturn_enum_NVML_ERROR_UNKNOWN => Err(Unknown),
-        _ => Err(UnexpectedVariant(code)),
+        _ => Err(UnrecognizedCode(code)),
     }
 }
 
+/**
+Converts an `nvmlReturn_t` type returned by the named NVML `function` into a
+`Result<(), NvmlError>`.
+
+Identical to `nvml_try()` except that `NotSupported` and `NoPermission` errors
+carry an `UnsupportedContext` or `PermissionContext` describing `function`.
+*/
+pub fn nvml_try_for(function: &'static str, code: nvmlReturn_t) -> Result<(), NvmlError> {
+    nvml_try(code).map_err(|e| match e {
+        NvmlError::NotSupported(None) => {
+            NvmlError::NotSupported(Some(UnsupportedContext::for_function(function)))
+        }
+        NvmlError::NoPermission(None) => {
+            NvmlError::NoPermission(Some(PermissionContext::for_function(function)))
+        }
+        other => other,
+    })
+}
+
 /// Helper to map a `&libloading::Error` into an `NvmlError`
 pub fn nvml_sym<'a, T>(sym: Result<&'a T, &libloading::Error>) -> Result<&'a T, NvmlError> {
     sym.map_err(|e| NvmlError::FailedToLoadSymbol(e.to_string()))
 }
+
+// Like `nvml_sym()`, looking up the NVML function `$name` in `$lib` and
+// evaluating to the function along with its name. Pass the name on to
+// `nvml_try_for()` so that errors always name the function that was called.
+macro_rules! nvml_fn {
+    ($lib:expr, $name:ident) => {
+        crate::error::nvml_sym($lib.$name.as_ref()).map(|sym| (sym, stringify!($name)))
+    };
+}
+
+pub(crate) use nvml_fn;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn eq() {
+        assert_eq!(NvmlError::GpuLost, NvmlError::GpuLost);
+        assert_ne!(NvmlError::GpuLost, NvmlError::Timeout);
+        assert_eq!(
+            NvmlError::InsufficientSize(Some(4)),
+            NvmlError::InsufficientSize(Some(4))
+        );
+        assert_ne!(
+            NvmlError::InsufficientSize(Some(4)),
+            NvmlError::InsufficientSize(None)
+        );
+        // Context is ignored
+        assert_eq!(
+            nvml_try_for(
+                "nvmlDeviceGetFanSpeed",
+                nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED
+            ),
+            Err(NvmlError::NotSupported(None))
+        );
+        assert_eq!(
+            nvml_try_for(
+                "nvmlDeviceSetPowerManagementLimit",
+                nvmlReturn_enum_NVML_ERROR_NO_PERMISSION
+            ),
+            Err(NvmlError::NoPermission(None))
+        );
+        assert_ne!(NvmlError::NotSupported(None), NvmlError::NoPermission(None));
+        assert_eq!(
+            nvml_try(nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED),
+            Err(NvmlError::NotSupported(None))
+        );
+
+        let nan = || NvmlError::IncorrectValueType {
+            value: SampleValue::F64(f64::NAN),
+            requested: "u32",
+        };
+        assert_eq!(nan(), nan());
+
+        assert_eq!(
+            NvmlErrorWithSource::from(NvmlError::NoData),
+            NvmlErrorWithSource {
+                error: NvmlError::NoData,
+                source: None,
+            }
+        );
+    }
+
+    #[test]
+    fn raw_code() {
+        let codes = (nvmlReturn_enum_NVML_ERROR_UNINITIALIZED
+            ..=nvmlReturn_enum_NVML_ERROR_VGPU_ECC_NOT_SUPPORTED)
+            .chain(vec![nvmlReturn_enum_NVML_ERROR_UNKNOWN, 12345]);
+
+        for code in codes {
+            assert_eq!(nvml_try(code).unwrap_err().raw_code(), Some(code));
+        }
+
+        assert_eq!(nvml_try(12345), Err(NvmlError::UnrecognizedCode(12345)));
+        assert_eq!(NvmlError::UnexpectedVariant(3).raw_code(), None);
+        assert_eq!(
+            NvmlError::DeviceIndexOutOfRange { index: 4, count: 2 }.raw_code(),
+            Some(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT)
+        );
+        assert_eq!(
+            NvmlErrorWithSource::from(NvmlError::GpuLost).raw_code(),
+            Some(nvmlReturn_enum_NVML_ERROR_GPU_IS_LOST)
+        );
+    }
+
+    #[test]
+    fn unsupported_under_wsl2() {
+        let context = UnsupportedContext {
+            wsl2: true,
+            ..UnsupportedContext::for_function("nvmlDeviceGetProcessUtilization")
+        };
+
+        assert_eq!(
+            context.to_string(),
+            "`nvmlDeviceGetProcessUtilization`: not available under WSL2"
+        );
+        assert_eq!(
+            Err::<u32, _>(NvmlError::NotSupported(Some(context))).optional(),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn device_index_out_of_range() {
+        let error = NvmlError::DeviceIndexOutOfRange { index: 4, count: 2 };
+
+        assert_eq!(
+            error.to_string(),
+            "device index 4 requested, only 2 devices present"
+        );
+        assert_ne!(
+            error,
+            NvmlError::DeviceIndexOutOfRange { index: 4, count: 3 }
+        );
+        assert_eq!(error.recovery_hint(), RecoveryHint::Fatal);
+    }
+
+    #[test]
+    fn set_affinity_failed() {
+        let error = NvmlError::SetAffinityFailed(io::Error::from_raw_os_error(22));
+
+        assert_eq!(
+            error,
+            NvmlError::SetAffinityFailed(io::Error::from_raw_os_error(22))
+        );
+        assert_ne!(
+            error,
+            NvmlError::SetAffinityFailed(io::Error::from_raw_os_error(1))
+        );
+        assert_eq!(error.raw_code(), None);
+        assert_eq!(error.recovery_hint(), RecoveryHint::Fatal);
+        assert_eq!(
+            NvmlError::SetAffinityFailed(io::ErrorKind::PermissionDenied.into()).recovery_hint(),
+            RecoveryHint::CheckPermissions
+        );
+    }
+
+    #[test]
+    fn init_diagnostics() {
+        let diagnostics = InitDiagnostics {
+            library_path: Some("/usr/lib/libnvidia-ml.so.535.104.05".into()),
+            library_version: Some("12.535.104.05".into()),
+            kernel_module_loaded: Some(true),
+            kernel_module_version: Some("530.41.03".into()),
+        };
+        let error = NvmlError::LibRmVersionMismatch(Some(Box::new(diagnostics.clone())));
+
+        assert_eq!(
+            error.to_string(),
+            "RM detects a driver/library version mismatch (library \
+            `/usr/lib/libnvidia-ml.so.535.104.05`, library version 12.535.104.05, kernel \
+            module version 530.41.03)"
+        );
+        assert_ne!(error, NvmlError::LibRmVersionMismatch(None));
+        assert_eq!(error.recovery_hint(), RecoveryHint::Reinit);
+
+        let not_loaded = InitDiagnostics {
+            library_version: None,
+            kernel_module_loaded: Some(false),
+            kernel_module_version: None,
+            ..diagnostics
+        };
+        assert_eq!(
+            NvmlError::DriverNotLoaded(Some(Box::new(not_loaded))).to_string(),
+            "NVIDIA driver is not loaded (library `/usr/lib/libnvidia-ml.so.535.104.05`, \
+            kernel module not loaded)"
+        );
+        assert_eq!(
+            NvmlError::DriverNotLoaded(None).to_string(),
+            "NVIDIA driver is not loaded"
+        );
+    }
+
+    #[test]
+    fn with_required_size() {
+        assert_eq!(
+            NvmlError::InsufficientSize(None).with_required_size(8),
+            NvmlError::InsufficientSize(Some(8))
+        );
+        assert_eq!(
+            NvmlError::InsufficientSize(Some(4)).with_required_size(8),
+            NvmlError::InsufficientSize(Some(4))
+        );
+        assert_eq!(NvmlError::NoData.with_required_size(8), NvmlError::NoData);
+    }
+
+    #[test]
+    fn recovery_hint() {
+        assert_eq!(NvmlError::Timeout.recovery_hint(), RecoveryHint::Retry);
+        assert_eq!(
+            NvmlError::DriverNotLoaded(None).recovery_hint(),
+            RecoveryHint::Reinit
+        );
+        assert_eq!(NvmlError::GpuLost.recovery_hint(), RecoveryHint::ResetGpu);
+        assert_eq!(
+            NvmlError::NoPermission(None).recovery_hint(),
+            RecoveryHint::CheckPermissions
+        );
+        assert_eq!(
+            NvmlError::NotSupported(None).recovery_hint(),
+            RecoveryHint::Unsupported
+        );
+        assert_eq!(NvmlError::InvalidArg.recovery_hint(), RecoveryHint::Fatal);
+    }
+
+    #[test]
+    fn recovery_hint_table() {
+        use RecoveryHint::*;
+
+        for &(code, hint) in &[
+            (nvmlReturn_enum_NVML_ERROR_UNINITIALIZED, Reinit),
+            (nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT, Fatal),
+            (nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED, Unsupported),
+            (nvmlReturn_enum_NVML_ERROR_NO_PERMISSION, CheckPermissions),
+            (nvmlReturn_enum_NVML_ERROR_ALREADY_INITIALIZED, Fatal),
+            (nvmlReturn_enum_NVML_ERROR_NOT_FOUND, Fatal),
+            (nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE, Fatal),
+            (nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_POWER, Fatal),
+            (nvmlReturn_enum_NVML_ERROR_DRIVER_NOT_LOADED, Reinit),
+            (nvmlReturn_enum_NVML_ERROR_TIMEOUT, Retry),
+            (nvmlReturn_enum_NVML_ERROR_IRQ_ISSUE, ResetGpu),
+            (nvmlReturn_enum_NVML_ERROR_LIBRARY_NOT_FOUND, Reinit),
+            (nvmlReturn_enum_NVML_ERROR_FUNCTION_NOT_FOUND, Unsupported),
+            (nvmlReturn_enum_NVML_ERROR_CORRUPTED_INFOROM, Fatal),
+            (nvmlReturn_enum_NVML_ERROR_GPU_IS_LOST, ResetGpu),
+            (nvmlReturn_enum_NVML_ERROR_RESET_REQUIRED, ResetGpu),
+            (nvmlReturn_enum_NVML_ERROR_OPERATING_SYSTEM, Fatal),
+            (nvmlReturn_enum_NVML_ERROR_LIB_RM_VERSION_MISMATCH, Reinit),
+            (nvmlReturn_enum_NVML_ERROR_IN_USE, Retry),
+            (nvmlReturn_enum_NVML_ERROR_MEMORY, Retry),
+            (nvmlReturn_enum_NVML_ERROR_NO_DATA, Retry),
+            (
+                nvmlReturn_enum_NVML_ERROR_VGPU_ECC_NOT_SUPPORTED,
+                Unsupported,
+            ),
+            (nvmlReturn_enum_NVML_ERROR_UNKNOWN, Fatal),
+            (999, Fatal),
+        ] {
+            let error = nvml_try(code).unwrap_err();
+            assert_eq!(error.recovery_hint(), hint, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn not_supported_context() {
+        let error = nvml_try_for(
+            "nvmlDeviceGetFBCStats",
+            nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED,
+        )
+        .unwrap_err();
+
+        match error {
+            NvmlError::NotSupported(Some(ref context)) => {
+                assert_eq!(context.function, "nvmlDeviceGetFBCStats");
+                assert_eq!(context.min_architecture, Some(DeviceArchitecture::Maxwell));
+            }
+            ref other => panic!("unexpected error: {:?}", other),
+        }
+
+        assert!(error.to_string().contains("Maxwell or newer"));
+        assert!(matches!(
+            nvml_try_for("nvmlDeviceGetFBCStats", nvmlReturn_enum_NVML_ERROR_TIMEOUT),
+            Err(NvmlError::Timeout)
+        ));
+    }
+
+    #[test]
+    fn no_permission_context() {
+        let error = nvml_try_for(
+            "nvmlDeviceSetApplicationsClocks",
+            nvmlReturn_enum_NVML_ERROR_NO_PERMISSION,
+        )
+        .unwrap_err();
+
+        // Errors compare equal regardless of context
+        match &error {
+            NvmlError::NoPermission(context) => assert_eq!(
+                context,
+                &Some(PermissionContext {
+                    function: "nvmlDeviceSetApplicationsClocks",
+                    required: Some(RequiredPrivilege::RootWhileRestricted(
+                        Api::ApplicationClocks
+                    )),
+                })
+            ),
+            _ => panic!("expected NoPermission, got {:?}", error),
+        }
+        assert!(error
+            .to_string()
+            .contains("unless the `ApplicationClocks` API restriction has been lifted"));
+
+        let error = nvml_try_for(
+            "nvmlDeviceGetPowerUsage",
+            nvmlReturn_enum_NVML_ERROR_NO_PERMISSION,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "the current user does not have permission to perform this operation \
+             (`nvmlDeviceGetPowerUsage`)"
+        );
+        assert_eq!(error, NvmlError::NoPermission(None));
+        assert_eq!(
+            error.raw_code(),
+            Some(nvmlReturn_enum_NVML_ERROR_NO_PERMISSION)
+        );
+    }
+
+    #[test]
+    fn optional() {
+        assert_eq!(Ok::<_, NvmlError>(3).optional().unwrap(), Some(3));
+        assert_eq!(
+            Err::<u32, _>(NvmlError::NotSupported(None))
+                .optional()
+                .unwrap(),
+            None
+        );
+        assert_eq!(Err::<u32, _>(NvmlError::NoData).optional().unwrap(), None);
+        assert!(matches!(
+            Err::<u32, _>(NvmlError::GpuLost).optional(),
+            Err(NvmlError::GpuLost)
+        ));
+    }
+}
@@ -1,8 +1,8 @@
-use crate::error::{nvml_sym, nvml_try, NvmlError};
+use crate::error::{nvml_fn, nvml_try_for, NvmlError};
 use crate::ffi::bindings::*;
 use crate::Nvml;
 
-use std::mem;
+use std::{mem, time::Duration};
 
 use crate::struct_wrappers::event::EventData;
 
@@ -19,6 +19,7 @@ the `Nvml` instance it was obtained from is dropped:
 ```compile_fail
 use nvml_wrapper::Nvml;
 # use nvml_wrapper::error::*;
+use std::time::Duration;
 
 # fn main() -> Result<(), NvmlError> {
 let nvml = Nvml::init()?;
@@ -27,7 +28,7 @@ let event_set = nvml.create_event_set()?;
 drop(nvml);
 
 // This won't compile
-event_set.wait(5)?;
+event_set.wait(Duration::from_millis(5))?;
 # Ok(())
 # }
 ```
@@ -72,10 +73,10 @@ impl<'nvml> EventSet<'nvml> {
     // Checked against local
     #[doc(alias = "nvmlEventSetFree")]
     pub fn release_events(self) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlEventSetFree.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlEventSetFree)?;
 
         unsafe {
-            nvml_try(sym(self.set))?;
+            nvml_try_for(function, sym(self.set))?;
         }
 
         mem::forget(self);
@@ -83,7 +84,10 @@ impl<'nvml> EventSet<'nvml> {
     }
 
     /**
-    Waits on events for the given timeout (in ms) and delivers one when it arrives.
+    Waits on events for the given timeout and delivers one when it arrives.
+
+    NVML takes the timeout in whole milliseconds; `timeout` is truncated to
+    milliseconds and saturates at `u32::MAX` of them.
 
     See the `high_level::event_loop` module for an abstracted version of this.
 
@@ -111,12 +115,19 @@ impl<'nvml> EventSet<'nvml> {
     */
     // Checked against local
     #[doc(alias = "nvmlEventSetWait_v2")]
-    pub fn wait(&self, timeout_ms: u32) -> Result<EventData<'nvml>, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlEventSetWait_v2.as_ref())?;
+    pub fn wait(&self, timeout: Duration) -> Result<EventData<'nvml>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlEventSetWait_v2)?;
+
+        let timeout_ms = timeout.as_millis().min(u32::MAX as u128) as u32;
 
         unsafe {
             let mut data: nvmlEventData_t = mem::zeroed();
-            nvml_try(sym(self.set, &mut data, timeout_ms))?;
+            nvml_try_for(
+                function,
+                self.nvml
+                    .legacy
+                    .call(function, sym, |sym| sym(self.set, &mut data, timeout_ms)),
+            )?;
 
             Ok(EventData::new(data, self.nvml))
         }
@@ -135,6 +146,47 @@ impl<'nvml> EventSet<'nvml> {
     pub unsafe fn handle(&self) -> nvmlEventSet_t {
         self.set
     }
+
+    /**
+    Take ownership of an event set handle obtained from other NVML FFI code.
+
+    The returned `EventSet` frees the set when dropped.
+
+    # Safety
+
+    The handle must be valid and obtained from the same NVML library `nvml`
+    has loaded, and nothing else may free it.
+    */
+    pub unsafe fn from_raw(set: nvmlEventSet_t, nvml: &'nvml Nvml) -> Self {
+        Self::new(set, nvml)
+    }
+
+    /**
+    Get the raw event set handle, to pass to other NVML FFI code.
+
+    This `EventSet` keeps ownership of the set; see `.into_raw()` to give it up.
+
+    # Safety
+
+    This is unsafe to prevent it from being used without care. The handle is
+    freed when this `EventSet` drops, so it must not be used (or passed to
+    `EventSet::from_raw()`) past that point.
+    */
+    pub unsafe fn as_raw(&self) -> nvmlEventSet_t {
+        self.set
+    }
+
+    /**
+    Give up ownership of the raw event set handle.
+
+    The set is not freed; it is up to the caller to free it with
+    `nvmlEventSetFree` or to hand it back to `EventSet::from_raw()`.
+    */
+    pub fn into_raw(self) -> nvmlEventSet_t {
+        let set = self.set;
+        mem::forget(self);
+        set
+    }
 }
 
 /// This `Drop` implementation ignores errors! Use the `.release_events()`
@@ -153,6 +205,17 @@ impl<'nvml> Drop for EventSet<'nvml> {
 mod test {
     use crate::bitmasks::event::*;
     use crate::test_utils::*;
+    use crate::EventSet;
+
+    #[test]
+    fn raw_round_trip() {
+        let nvml = nvml();
+        test(3, || {
+            let set = nvml.create_event_set()?;
+            let set = unsafe { EventSet::from_raw(set.into_raw(), &nvml) };
+            set.release_events()
+        })
+    }
 
     #[test]
     fn release_events() {
@@ -176,6 +239,7 @@ mod test {
     #[test]
     fn wait() {
         use crate::error::NvmlError;
+        use std::time::Duration;
 
         let nvml = nvml();
         let device = device(&nvml);
@@ -189,7 +253,7 @@ mod test {
             )
             .expect("registration");
 
-        let data = match set.wait(10_000) {
+        let data = match set.wait(Duration::from_secs(10)) {
             Err(NvmlError::Timeout) => return (),
             Ok(d) => d,
             _ => panic!("An error other than `Timeout` occurred"),
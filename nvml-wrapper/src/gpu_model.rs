@@ -0,0 +1,259 @@
+/*!
+Structured GPU models parsed from marketing names.
+
+Device names such as `NVIDIA H100 80GB HBM3` or `Tesla V100-SXM2-16GB` mix the
+model with its memory size, memory type and form factor, and their spelling
+has changed between driver versions. Grouping by the raw name therefore
+splits identical GPUs into several groups. `GpuModel` pulls the pieces apart:
+
+```
+use nvml_wrapper::enum_wrappers::device::Brand;
+use nvml_wrapper::enums::device::DeviceArchitecture;
+use nvml_wrapper::gpu_model::{FormFactor, GpuFamily, GpuModel};
+
+let model = GpuModel::new("Tesla V100-SXM2-16GB", &Brand::Tesla, DeviceArchitecture::Volta);
+
+assert_eq!(model.family, GpuFamily::DataCenter);
+assert_eq!(model.model, "V100");
+assert_eq!(model.memory_gb, Some(16));
+assert_eq!(model.form_factor, Some(FormFactor::Sxm));
+```
+
+`Device.model()` queries the inputs and parses them in one go.
+
+Parsing is best-effort: unrecognized words are kept as part of `model`.
+*/
+
+use crate::enum_wrappers::device::Brand;
+use crate::enums::device::DeviceArchitecture;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The product line a GPU belongs to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GpuFamily {
+    /// Consumer GPUs (GeForce).
+    GeForce,
+    /// TITAN GPUs.
+    Titan,
+    /// Workstation GPUs (Quadro and NVIDIA RTX).
+    Professional,
+    /// Data center GPUs (formerly Tesla).
+    DataCenter,
+    /// Multi-display GPUs (NVS).
+    Nvs,
+    /// GPUs targeted at virtualization (GRID).
+    Virtual,
+    Unknown,
+}
+
+/// The physical form of a GPU.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FormFactor {
+    /// A PCIe add-in card.
+    Pcie,
+    /// An SXM module on a baseboard.
+    Sxm,
+    /// A pair of PCIe cards bridged with NVLink, sold as one product.
+    Nvl,
+    /// A laptop GPU.
+    Laptop,
+}
+
+/// A GPU model parsed from its marketing name. See the module-level docs.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GpuModel {
+    pub family: GpuFamily,
+    /// The model without vendor, product line, memory or form factor, e.g.
+    /// `H100` or `RTX 3080 Ti`.
+    pub model: String,
+    /// The memory size in the name, in GB.
+    pub memory_gb: Option<u32>,
+    /// The form factor in the name.
+    pub form_factor: Option<FormFactor>,
+    pub architecture: DeviceArchitecture,
+}
+
+impl GpuModel {
+    /// Parse the given name, as returned by `Device.name()`, using `brand` and
+    /// `architecture` to fill in what the name doesn't say.
+    pub fn new(name: &str, brand: &Brand, architecture: DeviceArchitecture) -> Self {
+        let mut family = family_from_brand(brand);
+        let mut memory_gb = None;
+        let mut form_factor = None;
+        let mut words: Vec<&str> = vec![];
+
+        // "Max-Q" is the only hyphenated word; other hyphens separate words
+        let name = name
+            .replace("with Max-Q Design", "Max-Q")
+            .replace("Max-Q", "MaxQ");
+
+        for word in name.split(|c: char| c.is_whitespace() || c == '-') {
+            let upper = word.to_ascii_uppercase();
+
+            match upper.as_str() {
+                "" | "NVIDIA" => {}
+                "GEFORCE" | "QUADRO" | "TESLA" => {
+                    if family == GpuFamily::Unknown {
+                        family = family_from_series(&upper);
+                    }
+                }
+                "PCIE" => form_factor = Some(FormFactor::Pcie),
+                "NVL" => form_factor = Some(FormFactor::Nvl),
+                "LAPTOP" | "MOBILE" | "MAXQ" => form_factor = Some(FormFactor::Laptop),
+                // Only follows "Laptop"
+                "GPU" if form_factor == Some(FormFactor::Laptop) => {}
+                _ if upper.starts_with("SXM") => form_factor = Some(FormFactor::Sxm),
+                _ if upper.starts_with("HBM") || upper.starts_with("GDDR") => {}
+                _ => match memory(&upper) {
+                    Some(gb) => memory_gb = Some(gb),
+                    None => words.push(word),
+                },
+            }
+        }
+
+        if family == GpuFamily::Unknown
+            && words
+                .first()
+                .map_or(false, |w| w.eq_ignore_ascii_case("TITAN"))
+        {
+            family = GpuFamily::Titan;
+        }
+
+        Self {
+            family,
+            model: words.join(" "),
+            memory_gb,
+            form_factor,
+            architecture,
+        }
+    }
+}
+
+fn family_from_brand(brand: &Brand) -> GpuFamily {
+    match brand {
+        Brand::GeForce => GpuFamily::GeForce,
+        Brand::Titan => GpuFamily::Titan,
+        Brand::Quadro => GpuFamily::Professional,
+        Brand::Tesla => GpuFamily::DataCenter,
+        Brand::NVS => GpuFamily::Nvs,
+        Brand::GRID => GpuFamily::Virtual,
+        Brand::Unknown => GpuFamily::Unknown,
+    }
+}
+
+fn family_from_series(series: &str) -> GpuFamily {
+    match series {
+        "GEFORCE" => GpuFamily::GeForce,
+        "QUADRO" => GpuFamily::Professional,
+        _ => GpuFamily::DataCenter,
+    }
+}
+
+// Parses words such as "80GB".
+fn memory(word: &str) -> Option<u32> {
+    word.strip_suffix("GB")?.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(name: &str, brand: Brand) -> (GpuFamily, String, Option<u32>, Option<FormFactor>) {
+        let model = GpuModel::new(name, &brand, DeviceArchitecture::Unknown);
+        (
+            model.family,
+            model.model,
+            model.memory_gb,
+            model.form_factor,
+        )
+    }
+
+    #[test]
+    fn names() {
+        use self::FormFactor::*;
+        use self::GpuFamily::*;
+
+        let cases = vec![
+            (
+                "NVIDIA H100 80GB HBM3",
+                Brand::Unknown,
+                (Unknown, "H100", Some(80), None),
+            ),
+            (
+                "NVIDIA H100 PCIe",
+                Brand::Unknown,
+                (Unknown, "H100", None, Some(Pcie)),
+            ),
+            (
+                "NVIDIA H100 NVL",
+                Brand::Unknown,
+                (Unknown, "H100", None, Some(Nvl)),
+            ),
+            (
+                "NVIDIA A100-SXM4-40GB",
+                Brand::Unknown,
+                (Unknown, "A100", Some(40), Some(Sxm)),
+            ),
+            (
+                "Tesla V100-SXM2-16GB",
+                Brand::Unknown,
+                (DataCenter, "V100", Some(16), Some(Sxm)),
+            ),
+            (
+                "NVIDIA GeForce RTX 3080",
+                Brand::GeForce,
+                (GeForce, "RTX 3080", None, None),
+            ),
+            (
+                "NVIDIA GeForce RTX 3080 Ti Laptop GPU",
+                Brand::GeForce,
+                (GeForce, "RTX 3080 Ti", None, Some(Laptop)),
+            ),
+            (
+                "GeForce GTX 1080 with Max-Q Design",
+                Brand::GeForce,
+                (GeForce, "GTX 1080", None, Some(Laptop)),
+            ),
+            (
+                "Quadro RTX 8000",
+                Brand::Unknown,
+                (Professional, "RTX 8000", None, None),
+            ),
+            (
+                "NVIDIA RTX A6000",
+                Brand::Quadro,
+                (Professional, "RTX A6000", None, None),
+            ),
+            (
+                "NVIDIA TITAN RTX",
+                Brand::Unknown,
+                (Titan, "TITAN RTX", None, None),
+            ),
+        ];
+
+        for (name, brand, (family, model, memory_gb, form_factor)) in cases {
+            assert_eq!(
+                parse(name, brand),
+                (family, model.to_string(), memory_gb, form_factor),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn brand_and_architecture() {
+        let model = GpuModel::new(
+            "NVIDIA A100 80GB PCIe",
+            &Brand::Tesla,
+            DeviceArchitecture::Ampere,
+        );
+
+        assert_eq!(model.family, GpuFamily::DataCenter);
+        assert_eq!(model.architecture, DeviceArchitecture::Ampere);
+    }
+}
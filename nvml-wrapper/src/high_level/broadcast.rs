@@ -0,0 +1,523 @@
+/*!
+Poll devices once and share the results with any number of subscribers.
+
+When several parts of the same process (a logger, an alerter, a UI) each poll
+NVML for the same metrics, the calls are made once per part. A `Broadcast`
+instead runs a `DeviceQuery` against every device on a single sampling thread
+and hands each `Sample` to every `Subscription`:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::{Broadcast, DeviceQuery};
+use std::{thread, time::Duration};
+
+let nvml = Nvml::init()?;
+let broadcast = Broadcast::new(DeviceQuery::builder().all().build());
+
+let logger = broadcast.subscribe();
+thread::spawn(move || {
+    while let Some(sample) = logger.recv() {
+        for device in &sample.devices {
+            println!("{}: {:?}", device.uuid, device.metrics);
+        }
+    }
+});
+
+let alerter = broadcast.subscribe();
+thread::spawn(move || {
+    while let Some(sample) = alerter.recv() {
+        // ...
+    }
+});
+
+// Returns once both threads have exited and dropped their subscriptions
+broadcast.run(&nvml, Duration::from_secs(1))?;
+# Ok(())
+# }
+```
+
+Subscriptions behave like a watch channel: each one only holds on to the
+latest sample, so a slow subscriber skips samples rather than building up a
+backlog or slowing down the others.
+
+Consumers that already select over channels can subscribe through one instead:
+with the `broadcast-crossbeam` feature, `Broadcast.subscribe_crossbeam()`
+returns a `crossbeam_channel::Receiver`, and with the `broadcast-tokio`
+feature, `Broadcast.subscribe_tokio()` returns a `tokio::sync::watch::Receiver`
+that can be awaited from async code.
+*/
+
+use crate::error::NvmlError;
+use crate::high_level::query::{DeviceQuery, QueryResult};
+use crate::{Device, Nvml};
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, Weak},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The results of a single poll of every device.
+#[derive(Debug)]
+pub struct Sample {
+    /// When the devices were polled, in microseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// One entry per device, ordered by index.
+    pub devices: Vec<DeviceSample>,
+}
+
+/// The result of running a `DeviceQuery` against a single device.
+#[derive(Debug)]
+pub struct DeviceSample {
+    pub index: u32,
+    pub uuid: String,
+    pub metrics: Result<QueryResult, NvmlError>,
+}
+
+/// Shares polled metrics with subscribers. See the module-level docs.
+#[derive(Debug)]
+pub struct Broadcast {
+    query: DeviceQuery,
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+/// Receives the samples published by a `Broadcast`.
+///
+/// Create one with `Broadcast.subscribe()`.
+#[derive(Debug)]
+pub struct Subscription {
+    slot: Arc<Slot>,
+}
+
+// Where a `Broadcast` publishes samples to.
+#[derive(Debug)]
+enum Subscriber {
+    Slot(Weak<Slot>),
+    // Holds on to a receiver to discard samples that weren't received in time
+    #[cfg(feature = "broadcast-crossbeam")]
+    Crossbeam(
+        crossbeam_channel::Sender<Arc<Sample>>,
+        crossbeam_channel::Receiver<Arc<Sample>>,
+    ),
+    #[cfg(feature = "broadcast-tokio")]
+    Tokio(tokio::sync::watch::Sender<Option<Arc<Sample>>>),
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+    state: Mutex<SlotState>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct SlotState {
+    latest: Option<Arc<Sample>>,
+    closed: bool,
+}
+
+impl Broadcast {
+    /// Create a `Broadcast` that publishes the results of the given query.
+    pub fn new(query: DeviceQuery) -> Self {
+        Self {
+            query,
+            subscribers: Mutex::new(vec![]),
+        }
+    }
+
+    /// Subscribe to samples published from now on.
+    pub fn subscribe(&self) -> Subscription {
+        let slot = Arc::new(Slot::default());
+        self.subscribers()
+            .push(Subscriber::Slot(Arc::downgrade(&slot)));
+
+        Subscription { slot }
+    }
+
+    /**
+    Subscribe to samples published from now on through a crossbeam channel.
+
+    Like a `Subscription`, the channel only holds on to the latest sample: a
+    sample that hasn't been received by the time the next one is published is
+    replaced by it. The channel is disconnected once the `Broadcast` is
+    dropped.
+
+    Replacing samples requires the `Broadcast` to hold a receiver of its own, so
+    it can't tell when the returned receiver is dropped. Crossbeam subscriptions
+    therefore count towards `.subscriber_count()` and keep `.run()` going for as
+    long as the `Broadcast` exists.
+
+    This method is only available with the `broadcast-crossbeam` feature
+    enabled.
+    */
+    #[cfg(feature = "broadcast-crossbeam")]
+    pub fn subscribe_crossbeam(&self) -> crossbeam_channel::Receiver<Arc<Sample>> {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        self.subscribers()
+            .push(Subscriber::Crossbeam(sender, receiver.clone()));
+
+        receiver
+    }
+
+    /**
+    Subscribe to samples published from now on through a tokio watch channel.
+
+    The receiver holds `None` until the first sample is published. Its
+    `.changed()` returns an error once the `Broadcast` is dropped.
+
+    This method is only available with the `broadcast-tokio` feature enabled.
+    */
+    #[cfg(feature = "broadcast-tokio")]
+    pub fn subscribe_tokio(&self) -> tokio::sync::watch::Receiver<Option<Arc<Sample>>> {
+        let (sender, receiver) = tokio::sync::watch::channel(None);
+        self.subscribers().push(Subscriber::Tokio(sender));
+
+        receiver
+    }
+
+    /**
+    The number of subscriptions that have not been dropped yet.
+
+    Crossbeam subscriptions are always counted; see `.subscribe_crossbeam()`.
+    */
+    pub fn subscriber_count(&self) -> usize {
+        let mut subscribers = self.subscribers();
+        subscribers.retain(Subscriber::is_alive);
+        subscribers.len()
+    }
+
+    /**
+    Poll every device once and publish the results to all subscribers.
+
+    Returns the number of subscribers the sample was published to.
+
+    # Errors
+
+    Errors from enumerating the devices are returned; see `Nvml.device_count()`,
+    `Nvml.device_by_index()` and `Device.uuid()` for details. Errors from
+    running the query against a device are published in its `DeviceSample`.
+    */
+    pub fn sample(&self, nvml: &Nvml) -> Result<usize, NvmlError> {
+        let devices = devices(nvml)?;
+
+        Ok(self.publish(self.poll(&devices)))
+    }
+
+    /**
+    Publish a sample to all subscribers every `interval` until none are left.
+
+    Devices are enumerated once up front. Subscribe before calling this, as it
+    returns as soon as a sample finds no subscribers.
+
+    # Errors
+
+    Errors from enumerating the devices are returned; see `.sample()`.
+    */
+    pub fn run(&self, nvml: &Nvml, interval: Duration) -> Result<(), NvmlError> {
+        let devices = devices(nvml)?;
+        let mut next_poll = Instant::now();
+
+        loop {
+            if self.publish(self.poll(&devices)) == 0 {
+                return Ok(());
+            }
+
+            // Don't try to catch up on polls missed because of slow calls
+            let now = Instant::now();
+            next_poll = next_poll.max(now) + interval;
+            thread::sleep(next_poll - now);
+        }
+    }
+
+    fn poll(&self, devices: &[(u32, String, Device<'_>)]) -> Sample {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+
+        Sample {
+            timestamp,
+            devices: devices
+                .iter()
+                .map(|(index, uuid, device)| DeviceSample {
+                    index: *index,
+                    uuid: uuid.clone(),
+                    metrics: self.query.run(device),
+                })
+                .collect(),
+        }
+    }
+
+    // Hands the sample to every live subscriber, forgetting dropped ones.
+    fn publish(&self, sample: Sample) -> usize {
+        let sample = Arc::new(sample);
+        let mut subscribers = self.subscribers();
+
+        subscribers.retain(|subscriber| subscriber.send(&sample));
+
+        subscribers.len()
+    }
+
+    fn subscribers(&self) -> MutexGuard<'_, Vec<Subscriber>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for Broadcast {
+    // Wakes up subscribers blocked in `recv()` so they can see that no more
+    // samples are coming. Channel subscribers are closed by dropping their
+    // senders.
+    fn drop(&mut self) {
+        for subscriber in self.subscribers().iter() {
+            subscriber.close();
+        }
+    }
+}
+
+impl Subscriber {
+    // Returns whether the subscriber is still around to receive samples.
+    fn send(&self, sample: &Arc<Sample>) -> bool {
+        match self {
+            Subscriber::Slot(weak) => match weak.upgrade() {
+                Some(slot) => {
+                    slot.state().latest = Some(sample.clone());
+                    slot.changed.notify_all();
+                    true
+                }
+                None => false,
+            },
+            #[cfg(feature = "broadcast-crossbeam")]
+            Subscriber::Crossbeam(sender, receiver) => {
+                // Discard the previous sample if it's still waiting. The
+                // subscriber may receive it first, in which case there's room
+                // anyway.
+                let _ = receiver.try_recv();
+                let _ = sender.try_send(sample.clone());
+                true
+            }
+            #[cfg(feature = "broadcast-tokio")]
+            Subscriber::Tokio(sender) => sender.send(Some(sample.clone())).is_ok(),
+        }
+    }
+
+    fn close(&self) {
+        match self {
+            Subscriber::Slot(weak) => {
+                if let Some(slot) = weak.upgrade() {
+                    slot.state().closed = true;
+                    slot.changed.notify_all();
+                }
+            }
+            #[cfg(feature = "broadcast-crossbeam")]
+            Subscriber::Crossbeam(..) => {}
+            #[cfg(feature = "broadcast-tokio")]
+            Subscriber::Tokio(_) => {}
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        match self {
+            Subscriber::Slot(weak) => weak.strong_count() > 0,
+            #[cfg(feature = "broadcast-crossbeam")]
+            Subscriber::Crossbeam(..) => true,
+            #[cfg(feature = "broadcast-tokio")]
+            Subscriber::Tokio(sender) => !sender.is_closed(),
+        }
+    }
+}
+
+impl Subscription {
+    /**
+    Block until a sample is published that hasn't been received yet, and
+    return it.
+
+    Returns `None` once the `Broadcast` has been dropped and the last sample
+    it published has been received.
+    */
+    pub fn recv(&self) -> Option<Arc<Sample>> {
+        let mut state = self.slot.state();
+
+        loop {
+            if let Some(sample) = state.latest.take() {
+                return Some(sample);
+            }
+
+            if state.closed {
+                return None;
+            }
+
+            state = self
+                .slot
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Like `.recv()`, but gives up and returns `None` after `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Arc<Sample>> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.slot.state();
+
+        loop {
+            if let Some(sample) = state.latest.take() {
+                return Some(sample);
+            }
+
+            let now = Instant::now();
+            if state.closed || now >= deadline {
+                return None;
+            }
+
+            state = self
+                .slot
+                .changed
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Get the latest sample if one has been published that hasn't been
+    /// received yet, without blocking.
+    pub fn try_recv(&self) -> Option<Arc<Sample>> {
+        self.slot.state().latest.take()
+    }
+}
+
+impl Slot {
+    fn state(&self) -> MutexGuard<'_, SlotState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn devices(nvml: &Nvml) -> Result<Vec<(u32, String, Device<'_>)>, NvmlError> {
+    (0..nvml.device_count()?)
+        .map(|index| {
+            let device = nvml.device_by_index(index)?;
+            Ok((index, device.uuid()?, device))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn sample(timestamp: u64) -> Sample {
+        Sample {
+            timestamp,
+            devices: vec![],
+        }
+    }
+
+    #[test]
+    fn latest_sample_wins() {
+        let broadcast = Broadcast::new(DeviceQuery::default());
+        let first = broadcast.subscribe();
+        let second = broadcast.subscribe();
+
+        assert_eq!(broadcast.publish(sample(1)), 2);
+        assert_eq!(broadcast.publish(sample(2)), 2);
+
+        assert_eq!(first.recv().unwrap().timestamp, 2);
+        assert!(first.try_recv().is_none());
+        assert_eq!(second.try_recv().unwrap().timestamp, 2);
+    }
+
+    #[test]
+    fn dropped_subscribers() {
+        let broadcast = Broadcast::new(DeviceQuery::default());
+        let kept = broadcast.subscribe();
+        drop(broadcast.subscribe());
+
+        assert_eq!(broadcast.subscriber_count(), 1);
+        assert_eq!(broadcast.publish(sample(1)), 1);
+
+        drop(kept);
+        assert_eq!(broadcast.publish(sample(2)), 0);
+    }
+
+    #[test]
+    fn closed_after_drop() {
+        let broadcast = Broadcast::new(DeviceQuery::default());
+        let subscription = broadcast.subscribe();
+
+        broadcast.publish(sample(1));
+        drop(broadcast);
+
+        assert_eq!(subscription.recv().unwrap().timestamp, 1);
+        assert!(subscription.recv().is_none());
+        assert!(subscription.recv_timeout(Duration::from_secs(10)).is_none());
+    }
+
+    #[test]
+    fn recv_across_threads() {
+        let broadcast = Arc::new(Broadcast::new(DeviceQuery::default()));
+        let subscription = broadcast.subscribe();
+
+        let publisher = broadcast.clone();
+        let handle = thread::spawn(move || publisher.publish(sample(1)));
+
+        assert_eq!(subscription.recv().unwrap().timestamp, 1);
+        assert_eq!(handle.join().unwrap(), 1);
+    }
+
+    #[cfg(feature = "broadcast-crossbeam")]
+    #[test]
+    fn crossbeam_subscriber() {
+        let broadcast = Broadcast::new(DeviceQuery::default());
+        let receiver = broadcast.subscribe_crossbeam();
+
+        assert_eq!(broadcast.publish(sample(1)), 1);
+        assert_eq!(broadcast.publish(sample(2)), 1);
+        assert_eq!(receiver.try_recv().unwrap().timestamp, 2);
+        assert!(receiver.try_recv().is_err());
+
+        assert_eq!(broadcast.publish(sample(3)), 1);
+        assert_eq!(receiver.recv().unwrap().timestamp, 3);
+
+        // Dropped receivers can't be told apart from slow ones
+        drop(receiver);
+        assert_eq!(broadcast.subscriber_count(), 1);
+
+        let receiver = broadcast.subscribe_crossbeam();
+        drop(broadcast);
+        assert!(receiver.recv().is_err());
+    }
+
+    #[cfg(feature = "broadcast-tokio")]
+    #[test]
+    fn tokio_subscriber() {
+        let broadcast = Broadcast::new(DeviceQuery::default());
+        let receiver = broadcast.subscribe_tokio();
+        assert!(receiver.borrow().is_none());
+
+        assert_eq!(broadcast.publish(sample(1)), 1);
+        assert_eq!(broadcast.publish(sample(2)), 1);
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(receiver.borrow().as_ref().unwrap().timestamp, 2);
+
+        drop(receiver);
+        assert_eq!(broadcast.subscriber_count(), 0);
+
+        let receiver = broadcast.subscribe_tokio();
+        drop(broadcast);
+        assert!(receiver.has_changed().is_err());
+    }
+
+    #[test]
+    fn broadcast_sample() {
+        let nvml = nvml();
+        let broadcast = Broadcast::new(DeviceQuery::builder().all().build());
+        let subscription = broadcast.subscribe();
+
+        test(3, || {
+            broadcast.sample(&nvml)?;
+            Ok(subscription.try_recv().is_some())
+        })
+    }
+}
@@ -0,0 +1,551 @@
+/*!
+Cross-check NVML's view of a device against the kernel's.
+
+Driver bugs occasionally cause NVML to report values that disagree with what
+the kernel driver exposes through `/proc/driver/nvidia` and sysfs. A
+`CrossCheckReport` reads the same values through both paths and records every
+disagreement, which is useful as a diagnostic step when NVML readings look
+suspicious:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::CrossCheckReport;
+
+let nvml = Nvml::init()?;
+let device = nvml.device_by_index(0)?;
+let report = CrossCheckReport::run(&device)?;
+
+for discrepancy in &report.discrepancies {
+    eprintln!(
+        "{:?}: NVML says {:?}, {} says {:?}",
+        discrepancy.value,
+        discrepancy.nvml,
+        discrepancy.source.display(),
+        discrepancy.kernel
+    );
+}
+# Ok(())
+# }
+```
+
+Only values that the kernel exposes can be checked. See `CheckedValue` for
+what is compared.
+
+The PCIe link of an idle GPU is frequently downtrained to save power, so the
+current link generation and width can legitimately change in between the two
+reads. Re-run the check under load before drawing conclusions from a mismatch
+in those values.
+
+The kernel driver doesn't expose memory usage or persistence mode directly, so
+those are compared against the closest thing it does expose:
+
+* Memory usage is compared against the VRAM that DRM clients of the device
+  hold according to `/proc/<pid>/fdinfo`, where the driver reports it. That
+  only covers processes whose file descriptors can be read and doesn't include
+  memory reserved by the driver itself, so it is only a discrepancy if the
+  kernel's figure exceeds NVML's.
+* Persistence mode is compared against whether `nvidia-persistenced` is
+  running. Persistence mode enabled through the deprecated legacy mechanism,
+  or disabled for a single GPU while the daemon runs, is a legitimate mismatch.
+*/
+
+use crate::error::NvmlError;
+use crate::struct_wrappers::device::PciBusId;
+use crate::Device;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const PROC: &str = "/proc";
+const PROC_GPUS: &str = "/proc/driver/nvidia/gpus";
+const SYS_PCI_DEVICES: &str = "/sys/bus/pci/devices";
+const PERSISTENCED_PID: &str = "/var/run/nvidia-persistenced/nvidia-persistenced.pid";
+
+/// A value compared by a `CrossCheckReport`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CheckedValue {
+    /// `Device.uuid()` against the `GPU UUID` in `/proc/driver/nvidia`.
+    Uuid,
+    /// `Device.vbios_version()` against the `Video BIOS` in `/proc/driver/nvidia`.
+    VbiosVersion,
+    /// `Device.minor_number()` against the `Device Minor` in `/proc/driver/nvidia`.
+    MinorNumber,
+    /// `Device.current_pcie_link_gen()` against sysfs `current_link_speed`.
+    CurrentPcieLinkGen,
+    /// `Device.current_pcie_link_width()` against sysfs `current_link_width`.
+    CurrentPcieLinkWidth,
+    /// `Device.max_pcie_link_gen()` against sysfs `max_link_speed`.
+    MaxPcieLinkGen,
+    /// `Device.max_pcie_link_width()` against sysfs `max_link_width`.
+    MaxPcieLinkWidth,
+    /// `Device.memory_info()` against the VRAM held by DRM clients of the
+    /// device in `/proc/<pid>/fdinfo`. See the module-level docs.
+    MemoryUsed,
+    /// `Device.is_in_persistent_mode()` against whether `nvidia-persistenced`
+    /// is running. See the module-level docs.
+    PersistenceMode,
+}
+
+/// A value that NVML and the kernel driver disagree on.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Discrepancy {
+    pub value: CheckedValue,
+    /// The value as reported by NVML.
+    pub nvml: String,
+    /// The value as reported by the kernel driver.
+    pub kernel: String,
+    /// The file the kernel driver's value was read from, or `/proc` for
+    /// values gathered from every process.
+    pub source: PathBuf,
+}
+
+/// The result of cross-checking a single device.
+///
+/// Create one with `CrossCheckReport::run()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CrossCheckReport {
+    /// The PCI bus ID of the device in the kernel's format, e.g. `0000:01:00.0`.
+    pub bus_id: String,
+    /// Every value NVML and the kernel driver disagree on.
+    pub discrepancies: Vec<Discrepancy>,
+    /// Values that could not be compared because either NVML does not
+    /// support them on this device or the kernel driver does not expose them.
+    pub skipped: Vec<CheckedValue>,
+}
+
+impl CrossCheckReport {
+    /**
+    Cross-check the given `Device`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    Values that NVML reports as `NotSupported` are recorded in `skipped`.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    pub fn run(device: &Device) -> Result<Self, NvmlError> {
+        let pci_info = device.pci_info()?;
+        // GPUs are always function 0
+        let bus_id = PciBusId {
+            domain: pci_info.domain,
+            bus: pci_info.bus,
+            device: pci_info.device,
+            function: 0,
+        }
+        .to_sysfs();
+
+        let mut report = Self {
+            bus_id,
+            discrepancies: vec![],
+            skipped: vec![],
+        };
+
+        let information = Path::new(PROC_GPUS)
+            .join(&report.bus_id)
+            .join("information");
+        let info = fs::read_to_string(&information).unwrap_or_default();
+        let info_field = |name: &str| proc_field(&info, name).map(String::from);
+
+        report.check(
+            CheckedValue::Uuid,
+            device.uuid(),
+            info_field("GPU UUID"),
+            &information,
+        )?;
+        report.check(
+            CheckedValue::VbiosVersion,
+            device.vbios_version(),
+            info_field("Video BIOS"),
+            &information,
+        )?;
+        report.check(
+            CheckedValue::MinorNumber,
+            device.minor_number().map(|n| n.to_string()),
+            info_field("Device Minor"),
+            &information,
+        )?;
+
+        let sysfs = Path::new(SYS_PCI_DEVICES).join(&report.bus_id);
+
+        report.check_sysfs(
+            CheckedValue::CurrentPcieLinkGen,
+            device.current_pcie_link_gen(),
+            sysfs.join("current_link_speed"),
+            link_speed_to_gen,
+        )?;
+        report.check_sysfs(
+            CheckedValue::CurrentPcieLinkWidth,
+            device.current_pcie_link_width(),
+            sysfs.join("current_link_width"),
+            |s| s.parse().ok(),
+        )?;
+        report.check_sysfs(
+            CheckedValue::MaxPcieLinkGen,
+            device.max_pcie_link_gen(),
+            sysfs.join("max_link_speed"),
+            link_speed_to_gen,
+        )?;
+        report.check_sysfs(
+            CheckedValue::MaxPcieLinkWidth,
+            device.max_pcie_link_width(),
+            sysfs.join("max_link_width"),
+            |s| s.parse().ok(),
+        )?;
+
+        report.check_memory_used(
+            device.memory_info().map(|m| m.used),
+            drm_vram_used(Path::new(PROC), &report.bus_id),
+        )?;
+
+        let persistenced = Path::new(PERSISTENCED_PID);
+        report.check(
+            CheckedValue::PersistenceMode,
+            device.is_in_persistent_mode().map(enabled),
+            process_running(Path::new(PROC), persistenced).map(enabled),
+            persistenced,
+        )?;
+
+        Ok(report)
+    }
+
+    /// Whether or not NVML and the kernel driver agreed on every value that
+    /// could be compared.
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    fn check(
+        &mut self,
+        value: CheckedValue,
+        nvml: Result<String, NvmlError>,
+        kernel: Option<String>,
+        source: &Path,
+    ) -> Result<(), NvmlError> {
+        let nvml = match nvml {
+            Ok(nvml) => nvml,
+            Err(NvmlError::NotSupported(_)) => {
+                self.skipped.push(value);
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let kernel = match kernel {
+            Some(kernel) => kernel,
+            None => {
+                self.skipped.push(value);
+                return Ok(());
+            }
+        };
+
+        if !nvml.trim().eq_ignore_ascii_case(kernel.trim()) {
+            self.discrepancies.push(Discrepancy {
+                value,
+                nvml,
+                kernel,
+                source: source.to_path_buf(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn check_sysfs(
+        &mut self,
+        value: CheckedValue,
+        nvml: Result<u32, NvmlError>,
+        path: PathBuf,
+        parse: impl Fn(&str) -> Option<u32>,
+    ) -> Result<(), NvmlError> {
+        let kernel = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| parse(s.trim()))
+            .map(|n| n.to_string());
+
+        self.check(value, nvml.map(|n| n.to_string()), kernel, &path)
+    }
+
+    // Memory used can only be compared one way; see the module-level docs.
+    fn check_memory_used(
+        &mut self,
+        nvml: Result<u64, NvmlError>,
+        kernel: Option<u64>,
+    ) -> Result<(), NvmlError> {
+        let value = CheckedValue::MemoryUsed;
+
+        let nvml = match nvml {
+            Ok(nvml) => nvml,
+            Err(NvmlError::NotSupported(_)) => {
+                self.skipped.push(value);
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        match kernel {
+            Some(kernel) if kernel > nvml => self.discrepancies.push(Discrepancy {
+                value,
+                nvml: nvml.to_string(),
+                kernel: kernel.to_string(),
+                source: PathBuf::from(PROC),
+            }),
+            Some(_) => {}
+            None => self.skipped.push(value),
+        }
+
+        Ok(())
+    }
+}
+
+fn enabled(enabled: bool) -> String {
+    if enabled { "Enabled" } else { "Disabled" }.into()
+}
+
+// Whether the process in the given PID file is running; `false` if there is
+// no PID file.
+fn process_running(proc: &Path, pid_file: &Path) -> Option<bool> {
+    let pid: u32 = match fs::read_to_string(pid_file) {
+        Ok(pid) => pid.trim().parse().ok()?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Some(false),
+        Err(_) => return None,
+    };
+
+    Some(proc.join(pid.to_string()).exists())
+}
+
+// Sums the VRAM held by every DRM client of the device with the given bus ID
+// whose fdinfo can be read, or `None` if there are none or the driver doesn't
+// report memory.
+fn drm_vram_used(proc: &Path, bus_id: &str) -> Option<u64> {
+    // Several file descriptors can refer to the same client
+    let mut clients = HashMap::new();
+
+    for process in fs::read_dir(proc).ok()?.flatten() {
+        let fdinfo = match fs::read_dir(process.path().join("fdinfo")) {
+            Ok(fdinfo) => fdinfo,
+            Err(_) => continue,
+        };
+
+        for fd in fdinfo.flatten() {
+            let contents = match fs::read_to_string(fd.path()) {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+
+            if let Some((client, vram)) = fdinfo_vram(&contents, bus_id) {
+                let client = client.unwrap_or_else(|| fd.path().display().to_string());
+                clients.insert(client, vram);
+            }
+        }
+    }
+
+    if clients.is_empty() {
+        None
+    } else {
+        Some(clients.values().sum())
+    }
+}
+
+// Gets the DRM client ID and the VRAM it holds in bytes from the contents of
+// an fdinfo file, if it belongs to a DRM client of the device with the given
+// bus ID and reports memory.
+fn fdinfo_vram(contents: &str, bus_id: &str) -> Option<(Option<String>, u64)> {
+    let fields: Vec<(&str, &str)> = contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, ':');
+            Some((parts.next()?.trim(), parts.next()?.trim()))
+        })
+        .collect();
+    let field = |name: &str| fields.iter().find(|(k, _)| *k == name).map(|(_, v)| *v);
+
+    if !field("drm-pdev")?.eq_ignore_ascii_case(bus_id) {
+        return None;
+    }
+
+    // `drm-memory-` is the deprecated name for `drm-resident-`
+    let vram = |prefix: &str| {
+        fields
+            .iter()
+            .filter(|(k, _)| k.starts_with(prefix))
+            .map(|(_, v)| parse_drm_size(v))
+            .collect::<Option<Vec<_>>>()
+            .filter(|sizes| !sizes.is_empty())
+            .map(|sizes| sizes.iter().sum())
+    };
+    let bytes = vram("drm-resident-vram").or_else(|| vram("drm-memory-vram"))?;
+
+    Some((field("drm-client-id").map(String::from), bytes))
+}
+
+// Parses a DRM fdinfo memory size, e.g. `1024 KiB`.
+fn parse_drm_size(size: &str) -> Option<u64> {
+    let mut parts = size.split_whitespace();
+    let value: u64 = parts.next()?.parse().ok()?;
+
+    let unit = match parts.next() {
+        None => 1,
+        Some("KiB") => 1 << 10,
+        Some("MiB") => 1 << 20,
+        Some("GiB") => 1 << 30,
+        Some(_) => return None,
+    };
+
+    value.checked_mul(unit)
+}
+
+// Gets the value of a `Name: value` line from a `/proc/driver/nvidia` file.
+fn proc_field<'a>(contents: &'a str, name: &str) -> Option<&'a str> {
+    contents.lines().find_map(|line| {
+        let mut parts = line.splitn(2, ':');
+
+        match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) if key.trim() == name => Some(value.trim()),
+            _ => None,
+        }
+    })
+}
+
+// Maps a sysfs link speed (e.g. `8.0 GT/s PCIe` or `8 GT/s`) to the PCIe
+// generation that introduced it.
+fn link_speed_to_gen(speed: &str) -> Option<u32> {
+    let rate = speed.split_whitespace().next()?;
+
+    match rate {
+        "2.5" => Some(1),
+        "5" | "5.0" => Some(2),
+        "8" | "8.0" => Some(3),
+        "16" | "16.0" => Some(4),
+        "32" | "32.0" => Some(5),
+        "64" | "64.0" => Some(6),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn parse_proc_field() {
+        let information = "Model: \t\t NVIDIA GeForce RTX 3080\n\
+                           IRQ:   \t\t 142\n\
+                           GPU UUID: \t GPU-0c5a3d6e-1111-2222-3333-444455556666\n\
+                           Video BIOS: \t 94.02.42.00.a9\n\
+                           Bus Location: \t 0000:01:00.0\n\
+                           Device Minor: \t 0\n";
+
+        assert_eq!(
+            proc_field(information, "GPU UUID"),
+            Some("GPU-0c5a3d6e-1111-2222-3333-444455556666")
+        );
+        assert_eq!(
+            proc_field(information, "Bus Location"),
+            Some("0000:01:00.0")
+        );
+        assert_eq!(proc_field(information, "Device Minor"), Some("0"));
+        assert_eq!(proc_field(information, "Excluded"), None);
+    }
+
+    #[test]
+    fn parse_link_speed() {
+        assert_eq!(link_speed_to_gen("2.5 GT/s PCIe"), Some(1));
+        assert_eq!(link_speed_to_gen("8.0 GT/s PCIe"), Some(3));
+        assert_eq!(link_speed_to_gen("16 GT/s"), Some(4));
+        assert_eq!(link_speed_to_gen("Unknown"), None);
+        assert_eq!(link_speed_to_gen(""), None);
+    }
+
+    #[test]
+    fn parse_fdinfo() {
+        let fdinfo = "pos:\t0\n\
+                      flags:\t02100002\n\
+                      drm-driver:\tnvidia-drm\n\
+                      drm-pdev:\t0000:01:00.0\n\
+                      drm-client-id:\t7\n\
+                      drm-total-vram0:\t4096 KiB\n\
+                      drm-resident-vram0:\t2 MiB\n\
+                      drm-resident-vram1:\t512\n\
+                      drm-memory-vram:\t1 GiB\n";
+
+        assert_eq!(
+            fdinfo_vram(fdinfo, "0000:01:00.0"),
+            Some((Some("7".into()), (2 << 20) + 512))
+        );
+        assert_eq!(fdinfo_vram(fdinfo, "0000:02:00.0"), None);
+        assert_eq!(
+            fdinfo_vram(
+                "drm-pdev: 0000:01:00.0\ndrm-memory-vram: 1 KiB\n",
+                "0000:01:00.0"
+            ),
+            Some((None, 1024))
+        );
+        // Not a DRM client, or no memory reported
+        assert_eq!(fdinfo_vram("pos: 0\nflags: 0\n", "0000:01:00.0"), None);
+        assert_eq!(
+            fdinfo_vram("drm-pdev: 0000:01:00.0\ndrm-client-id: 7\n", "0000:01:00.0"),
+            None
+        );
+        assert_eq!(parse_drm_size("3 lots"), None);
+    }
+
+    #[test]
+    fn persistenced_pid_file() {
+        let dir =
+            std::env::temp_dir().join(format!("nvml-wrapper-persistenced-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let pid_file = dir.join("nvidia-persistenced.pid");
+        let proc = Path::new(PROC);
+
+        assert_eq!(process_running(proc, &pid_file), Some(false));
+
+        fs::write(&pid_file, format!("{}\n", std::process::id())).unwrap();
+        assert_eq!(process_running(proc, &pid_file), Some(true));
+
+        fs::write(&pid_file, "not a pid").unwrap();
+        assert_eq!(process_running(proc, &pid_file), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn memory_used_is_checked_one_way() {
+        let mut report = CrossCheckReport {
+            bus_id: "0000:01:00.0".into(),
+            discrepancies: vec![],
+            skipped: vec![],
+        };
+
+        report.check_memory_used(Ok(2048), Some(1024)).unwrap();
+        assert!(report.is_consistent());
+
+        report.check_memory_used(Ok(1024), Some(2048)).unwrap();
+        assert_eq!(report.discrepancies[0].kernel, "2048");
+
+        report.check_memory_used(Ok(1024), None).unwrap();
+        assert_eq!(report.skipped, [CheckedValue::MemoryUsed]);
+    }
+
+    #[test]
+    fn run() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, CrossCheckReport::run)
+    }
+}
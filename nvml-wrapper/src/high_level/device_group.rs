@@ -0,0 +1,428 @@
+/*!
+Operate on an explicit set of devices at once.
+
+Fleet tooling usually acts on groups of GPUs (all GPUs of a node, the GPUs
+assigned to a job, ...) rather than on single devices. A `DeviceGroup` runs
+the same operation against each of its devices and always reports one result
+per device as a `PartialFailure`, so that a failure on one device neither hides
+the results for the others nor needs handling of its own:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::{DeviceGroup, DeviceQuery};
+use nvml_wrapper::units::Milliwatts;
+
+let nvml = Nvml::init()?;
+let mut group = DeviceGroup::by_indices(&nvml, &[0, 1])?;
+
+let outcome = group.set_power_management_limit(Milliwatts(250_000));
+for (device, error) in &outcome.failed {
+    eprintln!("failed to set the power limit of device {}: {}", device, error);
+}
+
+let query = DeviceQuery::builder().all().build();
+let snapshots = group.snapshots(&query).into_result()?;
+
+let health = group.health();
+println!("{} of {} devices healthy", health.healthy_count(), group.len());
+# Ok(())
+# }
+```
+*/
+
+#[cfg(target_os = "linux")]
+use crate::bitmasks::event::EventTypes;
+#[cfg(target_os = "linux")]
+use crate::error::NvmlErrorWithSource;
+use crate::error::{NvmlError, NvmlResultExt};
+use crate::high_level::query::{DeviceQuery, DeviceSnapshot};
+use crate::units::Milliwatts;
+#[cfg(target_os = "linux")]
+use crate::EventSet;
+use crate::{Device, Nvml};
+use std::{iter::FromIterator, thread};
+
+/**
+The outcome of a bulk operation on a `DeviceGroup`: the values for the devices
+the operation succeeded on and the errors for those it failed on.
+
+Devices are identified by their position in `DeviceGroup.devices()`. Both
+lists are in group order, and every device appears in exactly one of them.
+*/
+#[derive(Debug, PartialEq)]
+pub struct PartialFailure<T> {
+    pub succeeded: Vec<(usize, T)>,
+    pub failed: Vec<(usize, NvmlError)>,
+}
+
+impl<T> PartialFailure<T> {
+    /// Whether or not the operation succeeded on every device.
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Whether or not the operation failed on every device (or there were no
+    /// devices).
+    pub fn is_total_failure(&self) -> bool {
+        self.succeeded.is_empty()
+    }
+
+    /// The values for every device, or the error for the first device the
+    /// operation failed on.
+    pub fn into_result(mut self) -> Result<Vec<T>, NvmlError> {
+        if self.failed.is_empty() {
+            Ok(self.succeeded.into_iter().map(|(_, value)| value).collect())
+        } else {
+            Err(self.failed.swap_remove(0).1)
+        }
+    }
+}
+
+impl<T> Default for PartialFailure<T> {
+    fn default() -> Self {
+        Self {
+            succeeded: vec![],
+            failed: vec![],
+        }
+    }
+}
+
+/// Collects per-device results given in group order.
+impl<T> FromIterator<Result<T, NvmlError>> for PartialFailure<T> {
+    fn from_iter<I: IntoIterator<Item = Result<T, NvmlError>>>(iter: I) -> Self {
+        let mut outcome = Self::default();
+
+        for (i, result) in iter.into_iter().enumerate() {
+            match result {
+                Ok(value) => outcome.succeeded.push((i, value)),
+                Err(e) => outcome.failed.push((i, e)),
+            }
+        }
+
+        outcome
+    }
+}
+
+/// The health of a single device, as reported by `DeviceGroup.health()`.
+#[derive(Debug, Eq, PartialEq)]
+pub enum DeviceHealth {
+    Healthy,
+    /// The device works, but has memory pages pending retirement that will
+    /// only be retired on the next reset or reboot.
+    RetirementPending,
+    /// The device requires a reset before it can be used again.
+    ResetRequired,
+    /// The device has fallen off the bus or is otherwise inaccessible.
+    Lost,
+    /// The device couldn't be checked because of another error.
+    Error(NvmlError),
+}
+
+/// The health of every device in a `DeviceGroup`, in group order.
+#[derive(Debug, Eq, PartialEq)]
+pub struct GroupHealth {
+    pub devices: Vec<DeviceHealth>,
+}
+
+impl GroupHealth {
+    /// Whether or not every device is `DeviceHealth::Healthy`.
+    pub fn is_healthy(&self) -> bool {
+        self.devices.iter().all(|h| *h == DeviceHealth::Healthy)
+    }
+
+    /// The number of `DeviceHealth::Healthy` devices.
+    pub fn healthy_count(&self) -> usize {
+        self.devices
+            .iter()
+            .filter(|h| **h == DeviceHealth::Healthy)
+            .count()
+    }
+
+    /// The positions in the group of the devices that aren't healthy, along
+    /// with their health.
+    pub fn unhealthy(&self) -> impl Iterator<Item = (usize, &DeviceHealth)> + '_ {
+        self.devices
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| **h != DeviceHealth::Healthy)
+    }
+}
+
+/// An explicit set of devices that operations are run against in bulk. See the
+/// module-level docs.
+#[derive(Debug)]
+pub struct DeviceGroup<'nvml> {
+    devices: Vec<Device<'nvml>>,
+}
+
+impl<'nvml> DeviceGroup<'nvml> {
+    /// Create a group of the given devices.
+    pub fn new(devices: Vec<Device<'nvml>>) -> Self {
+        Self { devices }
+    }
+
+    /**
+    Create a group of every device currently visible to NVML.
+
+    # Errors
+
+    See `Nvml.device_count()` and `Nvml.device_by_index()`.
+    */
+    pub fn all(nvml: &'nvml Nvml) -> Result<Self, NvmlError> {
+        let indices: Vec<u32> = (0..nvml.device_count()?).collect();
+        Self::by_indices(nvml, &indices)
+    }
+
+    /**
+    Create a group of the devices at the given indices.
+
+    # Errors
+
+    The first error encountered is returned. See `Nvml.device_by_index()`.
+    */
+    pub fn by_indices(nvml: &'nvml Nvml, indices: &[u32]) -> Result<Self, NvmlError> {
+        indices
+            .iter()
+            .map(|&index| nvml.device_by_index(index))
+            .collect::<Result<_, _>>()
+            .map(Self::new)
+    }
+
+    /**
+    Create a group of the devices with the given UUIDs.
+
+    # Errors
+
+    The first error encountered is returned. See `Nvml.device_by_uuid()`.
+    */
+    pub fn by_uuids<S: AsRef<str>>(nvml: &'nvml Nvml, uuids: &[S]) -> Result<Self, NvmlError> {
+        uuids
+            .iter()
+            .map(|uuid| nvml.device_by_uuid(uuid.as_ref()))
+            .collect::<Result<_, _>>()
+            .map(Self::new)
+    }
+
+    /// The devices in this group.
+    pub fn devices(&self) -> &[Device<'nvml>] {
+        &self.devices
+    }
+
+    /// The number of devices in this group.
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// Whether or not this group is empty.
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /**
+    Set the power management limit of every device.
+
+    Every device is attempted, even if setting the limit fails for some. See
+    `Device.set_power_management_limit()` for the possible errors and
+    requirements.
+    */
+    pub fn set_power_management_limit(&mut self, limit: Milliwatts) -> PartialFailure<()> {
+        self.devices
+            .iter_mut()
+            .map(|device| device.set_power_management_limit(limit))
+            .collect()
+    }
+
+    /**
+    Run the given query against every device concurrently, one thread per
+    device.
+
+    NVML calls for different devices don't wait on each other, so this takes
+    about as long as querying the slowest device rather than all of them in
+    turn. The `index` of each snapshot is the device's NVML index.
+
+    See `DeviceQuery.run()` for the possible errors.
+    */
+    pub fn snapshots(&self, query: &DeviceQuery) -> PartialFailure<DeviceSnapshot> {
+        thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .devices
+                .iter()
+                .map(|device| scope.spawn(move || query.snapshot(device)))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| match handle.join() {
+                    Ok(result) => result,
+                    Err(panic) => std::panic::resume_unwind(panic),
+                })
+                .collect()
+        })
+    }
+
+    /**
+    Register every device for the given event types in one new `EventSet`.
+
+    Each device is only registered for the subset of `events` it supports;
+    devices supporting none of them are skipped. Use `EventData.device` to
+    tell which device an event came from.
+
+    # Errors
+
+    The first error encountered is returned and the set is released. See
+    `Nvml.create_event_set()`, `Device.supported_event_types()` and
+    `Device.register_events()`.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    #[cfg(target_os = "linux")]
+    pub fn register_events(
+        &self,
+        nvml: &'nvml Nvml,
+        events: EventTypes,
+    ) -> Result<EventSet<'nvml>, NvmlErrorWithSource> {
+        let mut set = nvml.create_event_set()?;
+
+        for device in &self.devices {
+            let supported = match device.supported_event_types() {
+                Ok(supported) => supported,
+                Err(e) => {
+                    set.release_events()?;
+                    return Err(e.into());
+                }
+            };
+
+            let events = events & supported;
+            if !events.is_empty() {
+                set = device.register_events(events, set)?;
+            }
+        }
+
+        Ok(set)
+    }
+
+    /**
+    Check the health of every device.
+
+    A device is checked by making a call that fails if it has fallen off the
+    bus or requires a reset, then asking whether any of its memory pages are
+    pending retirement. Devices that don't support page retirement are
+    considered healthy in that regard.
+    */
+    pub fn health(&self) -> GroupHealth {
+        GroupHealth {
+            devices: self.devices.iter().map(device_health).collect(),
+        }
+    }
+}
+
+fn device_health(device: &Device) -> DeviceHealth {
+    let check = || -> Result<DeviceHealth, NvmlError> {
+        device.performance_state().optional()?;
+
+        if device.are_pages_pending_retired().optional()? == Some(true) {
+            return Ok(DeviceHealth::RetirementPending);
+        }
+
+        Ok(DeviceHealth::Healthy)
+    };
+
+    match check() {
+        Ok(health) => health,
+        Err(NvmlError::GpuLost) => DeviceHealth::Lost,
+        Err(NvmlError::ResetRequired) => DeviceHealth::ResetRequired,
+        Err(e) => DeviceHealth::Error(e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn partial_failure() {
+        let outcome: PartialFailure<u32> = vec![Ok(1), Err(NvmlError::GpuLost), Ok(3)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(outcome.succeeded, vec![(0, 1), (2, 3)]);
+        assert_eq!(outcome.failed, vec![(1, NvmlError::GpuLost)]);
+        assert!(!outcome.is_ok() && !outcome.is_total_failure());
+        assert_eq!(outcome.into_result(), Err(NvmlError::GpuLost));
+
+        let outcome: PartialFailure<u32> = vec![Ok(1), Ok(2)].into_iter().collect();
+        assert!(outcome.is_ok());
+        assert_eq!(outcome.into_result(), Ok(vec![1, 2]));
+
+        let outcome: PartialFailure<u32> = vec![Err(NvmlError::Timeout)].into_iter().collect();
+        assert!(outcome.is_total_failure());
+    }
+
+    #[test]
+    fn group_health() {
+        let health = GroupHealth {
+            devices: vec![
+                DeviceHealth::Healthy,
+                DeviceHealth::Lost,
+                DeviceHealth::Healthy,
+            ],
+        };
+
+        assert!(!health.is_healthy());
+        assert_eq!(health.healthy_count(), 2);
+        assert_eq!(
+            health.unhealthy().collect::<Vec<_>>(),
+            vec![(1, &DeviceHealth::Lost)]
+        );
+    }
+
+    #[test]
+    fn snapshots() {
+        let nvml = nvml();
+        let group = DeviceGroup::all(&nvml).expect("group");
+        let query = DeviceQuery::builder().all().build();
+
+        test(3, || group.snapshots(&query).into_result())
+    }
+
+    #[test]
+    fn health() {
+        let nvml = nvml();
+        let group = DeviceGroup::all(&nvml).expect("group");
+
+        test(3, || Ok(group.health()))
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn register_events() {
+        let nvml = nvml();
+        let group = DeviceGroup::all(&nvml).expect("group");
+
+        let set = group
+            .register_events(&nvml, EventTypes::CRITICAL_XID_ERROR)
+            .expect("register");
+        set.release_events().expect("release");
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn set_power_management_limit() {
+        let nvml = nvml();
+        let mut group = DeviceGroup::all(&nvml).expect("group");
+
+        let limits: Vec<Milliwatts> = group
+            .devices()
+            .iter()
+            .map(|d| d.power_management_limit().expect("limit"))
+            .collect();
+        let min = *limits.iter().min().expect("devices");
+
+        assert!(group.set_power_management_limit(min).is_ok());
+    }
+}
@@ -0,0 +1,263 @@
+/*!
+Encoder latency and FPS percentiles across sessions.
+
+`Device.encoder_sessions()` reports a moving average latency and FPS per
+session at the time of the call. Streaming dashboards need the distribution
+over a window instead (e.g. p95 encode latency across every stream over the
+last minute). An `EncoderLatencyTracker` polls the sessions, keeps the
+observations within the window and summarizes them as percentiles:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::encoder_latency::EncoderLatencyTracker;
+use std::{thread, time::Duration};
+
+let nvml = Nvml::init()?;
+let device = nvml.device_by_index(0)?;
+let mut tracker = EncoderLatencyTracker::new(Duration::from_secs(60));
+
+loop {
+    tracker.poll(&device)?;
+
+    if let Some(summary) = tracker.summary() {
+        println!(
+            "p95 latency {} μs, p50 FPS {} across {} sessions",
+            summary.latency.p95, summary.fps.p50, summary.sessions
+        );
+    }
+
+    thread::sleep(Duration::from_secs(1));
+}
+# }
+```
+
+Each poll contributes one observation per active session. Sessions that
+haven't encoded a frame yet (reporting 0 FPS) are skipped.
+*/
+
+use crate::error::NvmlError;
+use crate::struct_wrappers::device::EncoderSessionInfo;
+use crate::Device;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// Percentiles of a set of observations, using the nearest-rank method.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Percentiles {
+    pub min: u32,
+    pub p50: u32,
+    pub p90: u32,
+    pub p95: u32,
+    pub p99: u32,
+    pub max: u32,
+}
+
+/// Returned from `EncoderLatencyTracker.summary()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EncoderSummary {
+    /// The number of observations the percentiles are taken over.
+    pub samples: usize,
+    /// The number of distinct sessions observed.
+    pub sessions: usize,
+    /// Encode latency in μs.
+    pub latency: Percentiles,
+    /// Encoded frames per second.
+    pub fps: Percentiles,
+}
+
+/// Accumulates encoder session observations over a window of time. See the
+/// module-level docs.
+#[derive(Debug, Clone)]
+pub struct EncoderLatencyTracker {
+    window: Duration,
+    observations: VecDeque<Observation>,
+}
+
+#[derive(Debug, Clone)]
+struct Observation {
+    at: Instant,
+    session_id: u32,
+    latency: u32,
+    fps: u32,
+}
+
+impl EncoderLatencyTracker {
+    /// Create a tracker that keeps observations for `window`.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            observations: VecDeque::new(),
+        }
+    }
+
+    /**
+    Fetch the active encoder sessions of the given device and record them.
+
+    Returns the number of sessions recorded.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the device is invalid
+    * `NotSupported`, if the device does not support this feature
+    * `GpuLost`, if the device has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+    */
+    pub fn poll(&mut self, device: &Device) -> Result<usize, NvmlError> {
+        Ok(self.record(&device.encoder_sessions()?))
+    }
+
+    /// Record one observation for each of the given sessions, dropping
+    /// observations older than the window.
+    ///
+    /// Returns the number of sessions recorded. `.poll()` calls this for you.
+    pub fn record(&mut self, sessions: &[EncoderSessionInfo]) -> usize {
+        self.record_at(sessions, Instant::now())
+    }
+
+    fn record_at(&mut self, sessions: &[EncoderSessionInfo], now: Instant) -> usize {
+        while let Some(oldest) = self.observations.front() {
+            if now.duration_since(oldest.at) <= self.window {
+                break;
+            }
+
+            self.observations.pop_front();
+        }
+
+        let before = self.observations.len();
+
+        self.observations
+            .extend(
+                sessions
+                    .iter()
+                    .filter(|s| s.average_fps > 0)
+                    .map(|s| Observation {
+                        at: now,
+                        session_id: s.session_id,
+                        latency: s.average_latency,
+                        fps: s.average_fps,
+                    }),
+            );
+
+        self.observations.len() - before
+    }
+
+    /// Summarize the observations within the window.
+    ///
+    /// `None` if there are none.
+    pub fn summary(&self) -> Option<EncoderSummary> {
+        let latency = percentiles(self.observations.iter().map(|o| o.latency).collect())?;
+        let fps = percentiles(self.observations.iter().map(|o| o.fps).collect())?;
+
+        let sessions: BTreeSet<u32> = self.observations.iter().map(|o| o.session_id).collect();
+
+        Some(EncoderSummary {
+            samples: self.observations.len(),
+            sessions: sessions.len(),
+            latency,
+            fps,
+        })
+    }
+
+    /// Forget every observation.
+    pub fn reset(&mut self) {
+        self.observations.clear();
+    }
+}
+
+fn percentiles(mut values: Vec<u32>) -> Option<Percentiles> {
+    values.sort_unstable();
+
+    // Nearest-rank: the smallest value that at least `p` percent of the
+    // values are less than or equal to
+    let rank = |p: usize| values[((p * values.len() + 99) / 100).max(1) - 1];
+
+    Some(Percentiles {
+        min: *values.first()?,
+        p50: rank(50),
+        p90: rank(90),
+        p95: rank(95),
+        p99: rank(99),
+        max: *values.last()?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::enum_wrappers::device::EncoderType;
+    use crate::test_utils::*;
+
+    fn session(session_id: u32, average_latency: u32, average_fps: u32) -> EncoderSessionInfo {
+        EncoderSessionInfo {
+            session_id,
+            pid: 1,
+            vgpu_instance: None,
+            codec_type: EncoderType::H264,
+            hres: 1920,
+            vres: 1080,
+            average_fps,
+            average_latency,
+        }
+    }
+
+    #[test]
+    fn nearest_rank() {
+        let p = percentiles((1..=100).collect()).unwrap();
+        assert_eq!(
+            (p.min, p.p50, p.p90, p.p95, p.p99, p.max),
+            (1, 50, 90, 95, 99, 100)
+        );
+
+        let p = percentiles(vec![7]).unwrap();
+        assert_eq!((p.min, p.p50, p.p99, p.max), (7, 7, 7, 7));
+
+        assert_eq!(percentiles(vec![]), None);
+    }
+
+    #[test]
+    fn window() {
+        let start = Instant::now();
+        let mut tracker = EncoderLatencyTracker::new(Duration::from_secs(10));
+        assert_eq!(tracker.summary(), None);
+
+        // Idle sessions are skipped
+        let added = tracker.record_at(&[session(1, 900, 60), session(2, 0, 0)], start);
+        assert_eq!(added, 1);
+
+        let later = start + Duration::from_secs(5);
+        tracker.record_at(&[session(1, 1000, 60), session(3, 5000, 30)], later);
+
+        let summary = tracker.summary().unwrap();
+        assert_eq!((summary.samples, summary.sessions), (3, 2));
+        assert_eq!(summary.latency.p95, 5000);
+        assert_eq!(summary.latency.p50, 1000);
+        assert_eq!(summary.fps.min, 30);
+
+        // The first observation falls out of the window
+        tracker.record_at(&[], start + Duration::from_secs(11));
+        assert_eq!(tracker.summary().unwrap().samples, 2);
+
+        tracker.reset();
+        assert_eq!(tracker.summary(), None);
+    }
+
+    #[test]
+    fn poll() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            let mut tracker = EncoderLatencyTracker::new(Duration::from_secs(60));
+            tracker.poll(device)?;
+            Ok(tracker.summary())
+        })
+    }
+}
@@ -71,6 +71,7 @@ use crate::EventSet;
 use crate::Nvml;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 // TODO: Tests
 
@@ -194,7 +195,7 @@ impl<'nvml> EventLoop<'nvml> {
                 break;
             };
 
-            match self.set.wait(1) {
+            match self.set.wait(Duration::from_millis(1)) {
                 Ok(data) => {
                     callback(Ok(data.into()), &mut state);
                 }
@@ -0,0 +1,421 @@
+/*!
+Advisory per-GPU locks for tools that change device state.
+
+NVML doesn't arbitrate between processes: if two daemons tune the same GPU's
+clocks, power limit or fans, every call succeeds and the last writer wins,
+usually in a loop that never settles. Any number of processes may safely
+*read* from a GPU at the same time; it's only setters that need coordinating.
+
+A `GpuLock` is a cooperative way of doing so. Tools that change a GPU's state
+take the lock for that GPU (keyed by its UUID, so it survives re-enumeration)
+before doing so and hold it for as long as they intend to keep the state
+applied:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use nvml_wrapper::high_level::gpu_lock::{GpuLock, GpuLockError};
+use nvml_wrapper::units::Milliwatts;
+
+let nvml = Nvml::init()?;
+let mut device = nvml.device_by_index(0)?;
+
+let _lock = match GpuLock::acquire(&device, "fan curve daemon") {
+    Ok(lock) => lock,
+    Err(GpuLockError::Held(holder)) => {
+        eprintln!("GPU is already being tuned by {}", holder);
+        return Ok(());
+    }
+    Err(e) => return Err(e.into()),
+};
+
+device.set_power_management_limit(Milliwatts(250_000))?;
+// ... keep `_lock` alive while the limit should stay in place
+# Ok(())
+# }
+```
+
+Locks are `flock(2)` locks on files in `DEFAULT_LOCK_DIR` (or a directory of
+your choosing); they are released when the `GpuLock` is dropped or the process
+exits, however it exits, so there are no stale locks to clean up. The lock
+files themselves are left in place.
+
+The lock is purely advisory. It only helps against processes that take it too,
+and says nothing about tools that change state without using this crate (such
+as `nvidia-smi`). When the lock is held, `GpuLock::holder()` reports the holder's
+PID, its stated purpose and whether it has NVML loaded, which helps tell another
+NVML-based controller apart from an unrelated process.
+
+This module is only available on Linux with the `gpu-lock` feature enabled.
+*/
+
+use crate::error::NvmlError;
+use crate::Device;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    os::raw::c_int,
+    os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+extern "C" {
+    // From the C library
+    fn flock(fd: c_int, operation: c_int) -> c_int;
+}
+
+const LOCK_SH: c_int = 1;
+const LOCK_EX: c_int = 2;
+const LOCK_NB: c_int = 4;
+const LOCK_UN: c_int = 8;
+
+/// The directory lock files are kept in unless another one is given.
+pub const DEFAULT_LOCK_DIR: &str = "/run/lock";
+
+/// Errors that can occur while taking or inspecting a lock.
+#[derive(Error, Debug)]
+pub enum GpuLockError {
+    #[error("an I/O error occurred: {0}")]
+    Io(#[from] io::Error),
+    #[error("an NVML error occurred: {0}")]
+    Nvml(#[from] NvmlError),
+    /// Another process (or another `GpuLock` in this process) holds the lock.
+    #[error("the GPU is locked by {0}")]
+    Held(LockHolder),
+    /// The UUID contains a character that can't be part of a file name, so no
+    /// lock file can be named after it.
+    #[error("no lock file can be named after UUID {0:?}")]
+    InvalidUuid(String),
+}
+
+/// The process holding a lock, as reported by `GpuLock::holder()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LockHolder {
+    /// `None` if the holder hasn't written its details yet.
+    pub pid: Option<u32>,
+    /// The purpose given to `GpuLock::acquire()`.
+    pub purpose: String,
+    /// The name of the holding process, if it could be read from `/proc`.
+    pub process_name: Option<String>,
+    /// Whether or not the holding process has the NVML library loaded, i.e. is
+    /// likely another NVML-based controller.
+    ///
+    /// `None` if that could not be determined, e.g. because the process
+    /// belongs to another user.
+    pub uses_nvml: Option<bool>,
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.pid {
+            Some(pid) => write!(f, "PID {}", pid)?,
+            None => f.write_str("an unknown process")?,
+        }
+
+        if let Some(ref name) = self.process_name {
+            write!(f, " ({})", name)?;
+        }
+
+        if !self.purpose.is_empty() {
+            write!(f, " for \"{}\"", self.purpose)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An advisory lock on a single GPU, released on drop. See the module-level
+/// docs.
+#[derive(Debug)]
+pub struct GpuLock {
+    file: File,
+    path: PathBuf,
+    uuid: String,
+}
+
+impl GpuLock {
+    /**
+    Take the lock for the given device in `DEFAULT_LOCK_DIR`, without waiting.
+
+    `purpose` is reported to other processes that find the lock held.
+
+    # Errors
+
+    * `Held`, if the lock is already held
+    * `Io`, if the lock file could not be opened or written
+    * `Nvml`, if the device's UUID could not be obtained
+    */
+    pub fn acquire(device: &Device, purpose: &str) -> Result<Self, GpuLockError> {
+        Self::acquire_in(DEFAULT_LOCK_DIR, &device.uuid()?, purpose)
+    }
+
+    /**
+    Take the lock for the GPU with the given UUID in `dir`, without waiting.
+
+    All cooperating processes must use the same directory.
+
+    # Errors
+
+    * `Held`, if the lock is already held
+    * `InvalidUuid`, if `uuid` contains a `/` or a NUL byte
+    * `Io`, if the lock file could not be opened or written
+    */
+    pub fn acquire_in<P: AsRef<Path>>(
+        dir: P,
+        uuid: &str,
+        purpose: &str,
+    ) -> Result<Self, GpuLockError> {
+        let path = lock_path(dir.as_ref(), uuid)?;
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            // The current holder's details must survive until we have the lock
+            .truncate(false)
+            .mode(0o644)
+            .open(&path)?;
+
+        if !try_flock(&file, LOCK_EX)? {
+            return Err(GpuLockError::Held(read_holder(&mut file)?));
+        }
+
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(file, "{}\n{}\n", std::process::id(), escape(purpose))?;
+        file.flush()?;
+
+        Ok(Self {
+            file,
+            path,
+            uuid: uuid.into(),
+        })
+    }
+
+    /**
+    Find out who holds the lock for the given device in `DEFAULT_LOCK_DIR`.
+
+    Returns `None` if the lock is free.
+
+    # Errors
+
+    * `Io`, if the lock file exists but could not be read
+    * `Nvml`, if the device's UUID could not be obtained
+    */
+    pub fn holder(device: &Device) -> Result<Option<LockHolder>, GpuLockError> {
+        Self::holder_in(DEFAULT_LOCK_DIR, &device.uuid()?)
+    }
+
+    /**
+    Find out who holds the lock for the GPU with the given UUID in `dir`.
+
+    Returns `None` if the lock is free.
+
+    # Errors
+
+    * `InvalidUuid`, if `uuid` contains a `/` or a NUL byte
+    * `Io`, if the lock file exists but could not be read
+    */
+    pub fn holder_in<P: AsRef<Path>>(
+        dir: P,
+        uuid: &str,
+    ) -> Result<Option<LockHolder>, GpuLockError> {
+        let mut file = match File::open(lock_path(dir.as_ref(), uuid)?) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        // A shared lock can only be taken if no one holds the exclusive one
+        if try_flock(&file, LOCK_SH)? {
+            unsafe { flock(file.as_raw_fd(), LOCK_UN) };
+            return Ok(None);
+        }
+
+        Ok(Some(read_holder(&mut file)?))
+    }
+
+    /// The UUID of the locked GPU.
+    pub fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    /// The path of the lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for GpuLock {
+    fn drop(&mut self) {
+        // Clear our details before closing the file releases the lock; the
+        // file itself stays so that it is never replaced while locked
+        let _ = self.file.set_len(0);
+    }
+}
+
+// The UUID is only ever part of the file name, so it can't escape `dir`.
+fn lock_path(dir: &Path, uuid: &str) -> Result<PathBuf, GpuLockError> {
+    if uuid.contains(&['/', '\0'][..]) {
+        return Err(GpuLockError::InvalidUuid(uuid.into()));
+    }
+
+    Ok(dir.join(format!("nvml-gpu-{}.lock", uuid)))
+}
+
+// The purpose is stored on a single line of the lock file.
+fn escape(purpose: &str) -> String {
+    purpose.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut purpose = String::with_capacity(line.len());
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                purpose.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                purpose.push('\\');
+                chars.next();
+            }
+            (c, _) => purpose.push(c),
+        }
+    }
+
+    purpose
+}
+
+// Returns `false` if the lock is held elsewhere.
+fn try_flock(file: &File, operation: c_int) -> io::Result<bool> {
+    if unsafe { flock(file.as_raw_fd(), operation | LOCK_NB) } == 0 {
+        return Ok(true);
+    }
+
+    let error = io::Error::last_os_error();
+    match error.kind() {
+        io::ErrorKind::WouldBlock => Ok(false),
+        _ => Err(error),
+    }
+}
+
+fn read_holder(file: &mut File) -> io::Result<LockHolder> {
+    let mut contents = String::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(&mut contents)?;
+
+    let mut lines = contents.lines();
+    let pid = lines.next().and_then(|pid| pid.parse().ok());
+    let purpose = unescape(lines.next().unwrap_or_default());
+
+    Ok(LockHolder {
+        pid,
+        purpose,
+        process_name: pid.and_then(process_name),
+        uses_nvml: pid.and_then(uses_nvml),
+    })
+}
+
+fn process_name(pid: u32) -> Option<String> {
+    let name = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    Some(name.trim_end().to_string())
+}
+
+fn uses_nvml(pid: u32) -> Option<bool> {
+    let maps = fs::read_to_string(format!("/proc/{}/maps", pid)).ok()?;
+    Some(maps.contains("libnvidia-ml.so"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    fn lock_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("nvml-wrapper-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).expect("create lock dir");
+        dir
+    }
+
+    #[test]
+    fn exclusive() {
+        let dir = lock_dir("exclusive");
+        let uuid = "GPU-0000";
+
+        let lock = GpuLock::acquire_in(&dir, uuid, "tuning").expect("acquire");
+        assert_eq!(lock.uuid(), uuid);
+
+        let holder = match GpuLock::acquire_in(&dir, uuid, "other") {
+            Err(GpuLockError::Held(holder)) => holder,
+            other => panic!("expected `Held`, got {:?}", other),
+        };
+        assert_eq!(holder.pid, Some(std::process::id()));
+        assert_eq!(holder.purpose, "tuning");
+        assert!(holder.process_name.is_some());
+
+        // Other GPUs are unaffected
+        GpuLock::acquire_in(&dir, "GPU-1111", "other").expect("acquire other GPU");
+
+        drop(lock);
+        GpuLock::acquire_in(&dir, uuid, "other").expect("acquire after drop");
+
+        fs::remove_dir_all(&dir).expect("remove lock dir");
+    }
+
+    #[test]
+    fn holder() {
+        let dir = lock_dir("holder");
+        let uuid = "GPU-0000";
+
+        assert_eq!(GpuLock::holder_in(&dir, uuid).unwrap(), None);
+
+        let lock = GpuLock::acquire_in(&dir, uuid, "fan curve").expect("acquire");
+        let holder = GpuLock::holder_in(&dir, uuid).unwrap().expect("holder");
+        assert_eq!(holder.purpose, "fan curve");
+        assert!(holder.to_string().contains("for \"fan curve\""));
+
+        drop(lock);
+        assert_eq!(GpuLock::holder_in(&dir, uuid).unwrap(), None);
+
+        fs::remove_dir_all(&dir).expect("remove lock dir");
+    }
+
+    #[test]
+    fn multiline_purpose() {
+        let dir = lock_dir("multiline");
+        let uuid = "GPU-0000";
+        let purpose = "fan curve\n12345\nC:\\not\\n a newline";
+
+        let _lock = GpuLock::acquire_in(&dir, uuid, purpose).expect("acquire");
+        let holder = GpuLock::holder_in(&dir, uuid).unwrap().expect("holder");
+        assert_eq!(holder.pid, Some(std::process::id()));
+        assert_eq!(holder.purpose, purpose);
+
+        fs::remove_dir_all(&dir).expect("remove lock dir");
+    }
+
+    #[test]
+    fn invalid_uuid() {
+        let dir = lock_dir("invalid");
+
+        for uuid in &["../../tmp/x", "GPU-0000/1/0", "GPU\0"] {
+            assert!(matches!(
+                GpuLock::acquire_in(&dir, uuid, "tuning"),
+                Err(GpuLockError::InvalidUuid(_))
+            ));
+            assert!(matches!(
+                GpuLock::holder_in(&dir, uuid),
+                Err(GpuLockError::InvalidUuid(_))
+            ));
+        }
+
+        fs::remove_dir_all(&dir).expect("remove lock dir");
+    }
+}
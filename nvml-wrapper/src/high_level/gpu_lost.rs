@@ -0,0 +1,312 @@
+/*!
+Detect GPUs falling off the bus.
+
+A GPU that falls off the bus (Xid 79) makes every further call for it fail
+with `GpuLost`, but nothing announces the transition by itself. A
+`GpuLostDetector` watches for it by combining critical Xid error events with
+a liveness probe of every device on each poll, and reports each lost GPU
+exactly once, along with the last snapshot taken while it was still reachable:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlErrorWithSource> {
+use nvml_wrapper::high_level::{DeviceQuery, GpuLostDetector};
+use std::time::Duration;
+
+let nvml = Nvml::init()?;
+let query = DeviceQuery::builder().all().build();
+let mut detector = GpuLostDetector::new(&nvml, query)?;
+
+loop {
+    for lost in detector.poll(Duration::from_secs(5))? {
+        println!(
+            "GPU {} ({}) fell off the bus at {:?}",
+            lost.index, lost.uuid, lost.detected_at
+        );
+    }
+}
+# }
+```
+
+The functionality in this module is only available on Linux platforms; NVML does
+not support events on any other platform.
+*/
+
+use crate::bitmasks::event::EventTypes;
+use crate::enums::event::XidError;
+use crate::error::{NvmlError, NvmlErrorWithSource, NvmlResultExt};
+use crate::high_level::query::{DeviceQuery, DeviceSnapshot, QueryResult};
+use crate::{Device, EventSet, Nvml};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// A device that was found to have fallen off the bus, returned from
+/// `GpuLostDetector.poll()`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LostDevice {
+    /// The index the device had when the detector was created.
+    pub index: u32,
+    pub uuid: String,
+    /// When the detector noticed that the device was lost.
+    pub detected_at: SystemTime,
+    /// The last critical Xid error reported for the device, if any.
+    pub xid: Option<XidError>,
+    /// The last snapshot taken while the device was still reachable.
+    ///
+    /// `None` if the device was lost before a snapshot could be taken or the
+    /// query failed every time.
+    pub last_snapshot: Option<DeviceSnapshot>,
+    /// When `last_snapshot` was taken.
+    pub last_seen: Option<SystemTime>,
+}
+
+/// Watches devices for falling off the bus. See the module-level docs.
+#[derive(Debug)]
+pub struct GpuLostDetector<'nvml> {
+    query: DeviceQuery,
+    devices: Vec<(Device<'nvml>, Health)>,
+    // `None` if no device supports critical Xid error events
+    events: Option<EventSet<'nvml>>,
+}
+
+// What is known about a device between polls.
+#[derive(Debug, Clone, PartialEq)]
+struct Health {
+    index: u32,
+    uuid: String,
+    name: String,
+    pci_bus_id: String,
+    xid: Option<XidError>,
+    last_snapshot: Option<DeviceSnapshot>,
+    last_seen: Option<SystemTime>,
+    lost: bool,
+}
+
+impl<'nvml> GpuLostDetector<'nvml> {
+    /**
+    Start watching every device currently visible to NVML.
+
+    `query` is run against every device on each poll; its results are kept as
+    the devices' last-known snapshots. An empty query still probes devices.
+
+    Devices that don't support critical Xid error events are only probed.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `GpuLost`, if a GPU has already fallen off the bus
+    * `Unknown`, on any unexpected error
+
+    Errors from `Nvml.create_event_set()` and `Device.register_events()` are
+    returned as well.
+    */
+    pub fn new(nvml: &'nvml Nvml, query: DeviceQuery) -> Result<Self, NvmlErrorWithSource> {
+        let mut devices = vec![];
+        let mut events = None;
+
+        for index in 0..nvml.device_count()? {
+            let device = nvml.device_by_index(index)?;
+
+            let supported = device.supported_event_types().optional()?;
+            if supported.map_or(false, |s| s.contains(EventTypes::CRITICAL_XID_ERROR)) {
+                let set = match events.take() {
+                    Some(set) => set,
+                    None => nvml.create_event_set()?,
+                };
+                events = Some(device.register_events(EventTypes::CRITICAL_XID_ERROR, set)?);
+            }
+
+            let health = Health::new(
+                index,
+                device.uuid()?,
+                device.name()?,
+                device.pci_info()?.bus_id,
+            );
+            devices.push((device, health));
+        }
+
+        Ok(Self {
+            query,
+            devices,
+            events,
+        })
+    }
+
+    /**
+    Wait up to `timeout` for critical Xid errors, then probe every device that
+    hasn't been reported lost yet.
+
+    Returns the devices that were found to be lost during this poll. Every
+    device is reported at most once.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `Unknown`, on any unexpected error
+
+    Errors from a single device's probe other than `GpuLost` don't mark it as
+    lost and are not returned.
+    */
+    pub fn poll(&mut self, timeout: Duration) -> Result<Vec<LostDevice>, NvmlError> {
+        self.wait_for_xid_errors(timeout)?;
+
+        let query = &self.query;
+        let mut lost = vec![];
+
+        for (device, health) in self.devices.iter_mut().filter(|(_, h)| !h.lost) {
+            if let Some(device) = health.observe(probe(query, device), SystemTime::now()) {
+                lost.push(device);
+            }
+        }
+
+        Ok(lost)
+    }
+
+    /// The UUIDs of the devices that have been reported lost so far.
+    pub fn lost_uuids(&self) -> Vec<&str> {
+        self.devices
+            .iter()
+            .filter(|(_, h)| h.lost)
+            .map(|(_, h)| h.uuid.as_str())
+            .collect()
+    }
+
+    // Records the Xid of every critical Xid error event that arrives within
+    // `timeout`, or just sleeps if there is no event set.
+    fn wait_for_xid_errors(&mut self, mut timeout: Duration) -> Result<(), NvmlError> {
+        let set = match self.events {
+            Some(ref set) => set,
+            None => {
+                thread::sleep(timeout);
+                return Ok(());
+            }
+        };
+
+        loop {
+            let data = match set.wait(timeout) {
+                Ok(data) => data,
+                Err(NvmlError::Timeout) => return Ok(()),
+                // Reported by the probe for the device in question
+                Err(NvmlError::GpuLost) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            let device = &data.device;
+            if let Some((_, health)) = self.devices.iter_mut().find(|(d, _)| d == device) {
+                if data.event_data.is_some() {
+                    health.xid = data.event_data;
+                }
+            }
+
+            // Drain any other pending events without waiting
+            timeout = Duration::from_secs(0);
+        }
+    }
+}
+
+impl Health {
+    fn new(index: u32, uuid: String, name: String, pci_bus_id: String) -> Self {
+        Self {
+            index,
+            uuid,
+            name,
+            pci_bus_id,
+            xid: None,
+            last_snapshot: None,
+            last_seen: None,
+            lost: false,
+        }
+    }
+
+    // Updates the state with the result of a probe, returning the report if
+    // the device turned out to be lost.
+    fn observe(
+        &mut self,
+        probe: Result<QueryResult, NvmlError>,
+        now: SystemTime,
+    ) -> Option<LostDevice> {
+        match probe {
+            Ok(metrics) => {
+                self.last_snapshot = Some(DeviceSnapshot {
+                    index: self.index,
+                    uuid: self.uuid.clone(),
+                    name: self.name.clone(),
+                    pci_bus_id: self.pci_bus_id.clone(),
+                    metrics,
+                });
+                self.last_seen = Some(now);
+                None
+            }
+            Err(NvmlError::GpuLost) => {
+                self.lost = true;
+
+                Some(LostDevice {
+                    index: self.index,
+                    uuid: self.uuid.clone(),
+                    detected_at: now,
+                    xid: self.xid.clone(),
+                    last_snapshot: self.last_snapshot.clone(),
+                    last_seen: self.last_seen,
+                })
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+// Makes at least one call that fails with `GpuLost` for a lost device, even if
+// the query is empty.
+fn probe(query: &DeviceQuery, device: &Device) -> Result<QueryResult, NvmlError> {
+    device.performance_state().optional()?;
+    query.run(device)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn reports_lost_once() {
+        let mut health = Health::new(
+            5,
+            "GPU-a".into(),
+            "Test GPU".into(),
+            "00000000:05:00.0".into(),
+        );
+        let seen = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+        let lost_at = seen + Duration::from_secs(1);
+
+        assert_eq!(health.observe(Ok(QueryResult::default()), seen), None);
+        assert_eq!(health.observe(Err(NvmlError::Timeout), seen), None);
+
+        health.xid = Some(XidError::Value(79));
+        let lost = health.observe(Err(NvmlError::GpuLost), lost_at).unwrap();
+
+        assert_eq!(lost.index, 5);
+        assert_eq!(lost.detected_at, lost_at);
+        assert_eq!(lost.xid, Some(XidError::Value(79)));
+        assert_eq!(lost.last_seen, Some(seen));
+        assert_eq!(lost.last_snapshot.unwrap().name, "Test GPU");
+        assert!(health.lost);
+    }
+
+    #[test]
+    fn poll() {
+        let nvml = nvml();
+        let mut detector = GpuLostDetector::new(&nvml, DeviceQuery::default()).expect("detector");
+
+        for _ in 0..3 {
+            let lost = detector.poll(Duration::from_millis(10)).expect("poll");
+            assert!(lost.is_empty());
+        }
+
+        assert!(detector.lost_uuids().is_empty());
+    }
+}
@@ -0,0 +1,369 @@
+/*!
+Serve device metrics over HTTP.
+
+Runs a `DeviceQuery` against every device whenever a request comes in and
+serves the results at two endpoints:
+
+* `/metrics`, in the Prometheus text exposition format (or OpenMetrics, with
+  `ExporterConfig.openmetrics()` enabled)
+* `/devices.json`, as a JSON array of `DeviceSnapshot`s
+
+A device that can't be queried (one that has fallen off the bus, say) is left
+out of the response rather than failing it, so that the remaining devices keep
+being reported. The response only fails if no device could be queried.
+
+Getting a working endpoint takes a few lines inside a tokio runtime:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# async fn run() -> Result<(), Box<dyn std::error::Error>> {
+use nvml_wrapper::high_level::{http_exporter, DeviceQuery};
+use std::sync::Arc;
+
+let nvml = Arc::new(Nvml::init()?);
+let query = DeviceQuery::builder().all().build();
+let config = http_exporter::ExporterConfig::default();
+
+http_exporter::serve(nvml, query, config, ([0, 0, 0, 0], 9400).into()).await?;
+# Ok(())
+# }
+```
+
+To add the endpoints to an existing hyper (or axum) server instead, call
+`handle()` from your own service.
+
+The metrics at `/metrics` are rendered by the `prometheus` module, and an
+`ExporterConfig` controls their names and labels; see that module for details.
+`serve_with_events()` and `handle_with_events()` also export the critical
+events recorded in a `CriticalEvents`, which can be shared with the code
+watching for them.
+
+`handle()` makes its NVML calls synchronously. `serve()` and
+`serve_with_events()` run them on tokio's blocking thread pool instead, so that
+a slow query doesn't stall the runtime's other tasks.
+
+This module is only available with the `http-exporter` feature enabled.
+*/
+
+use crate::error::NvmlError;
+use crate::high_level::prometheus;
+use crate::high_level::query::{DeviceQuery, DeviceSnapshot};
+use crate::Nvml;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use std::{
+    convert::Infallible,
+    future,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+use tokio::task;
+
+pub use crate::high_level::prometheus::{
+    CriticalEvent, CriticalEvents, ExportedMetric, ExporterConfig, Label,
+};
+
+/**
+Handle a request to one of the endpoints described in the module-level docs.
+
+Requests for other paths get a `404 Not Found`, requests with methods other
+than `GET` and `HEAD` get a `405 Method Not Allowed`, and NVML errors are
+reported as a `500 Internal Server Error` if no device could be queried.
+*/
+pub fn handle<B>(
+    nvml: &Nvml,
+    query: &DeviceQuery,
+    config: &ExporterConfig,
+    request: &Request<B>,
+) -> Response<Body> {
+    respond(
+        config,
+        request,
+        || collect(nvml, query),
+        |snapshots| prometheus::render(config, snapshots),
+    )
+}
+
+/// Like `handle()`, adding the given critical events to `/metrics` as described
+/// by `prometheus::render_with_events()`.
+pub fn handle_with_events<B>(
+    nvml: &Nvml,
+    query: &DeviceQuery,
+    config: &ExporterConfig,
+    events: &CriticalEvents,
+    request: &Request<B>,
+) -> Response<Body> {
+    respond(
+        config,
+        request,
+        || collect(nvml, query),
+        |snapshots| prometheus::render_with_events(config, snapshots, events),
+    )
+}
+
+/**
+Serve the endpoints described in the module-level docs on the given address
+until an error occurs.
+
+Must be polled within a tokio runtime.
+
+# Errors
+
+* `hyper::Error`, if binding to the address fails or the server errors
+*/
+pub async fn serve(
+    nvml: Arc<Nvml>,
+    query: DeviceQuery,
+    config: ExporterConfig,
+    addr: SocketAddr,
+) -> Result<(), hyper::Error> {
+    let make_service = make_service_fn(move |_| {
+        let nvml = nvml.clone();
+        let query = query.clone();
+        let config = config.clone();
+
+        future::ready(Ok::<_, Infallible>(service_fn(move |request| {
+            let nvml = nvml.clone();
+            let query = query.clone();
+            let config = config.clone();
+
+            blocking(move || handle(&nvml, &query, &config, &request))
+        })))
+    });
+
+    Server::try_bind(&addr)?.serve(make_service).await
+}
+
+/**
+Like `serve()`, adding the critical events recorded in `events` to `/metrics`
+as described by `prometheus::render_with_events()`.
+
+Record events into `events` from wherever they are watched for; the lock is
+only held while rendering a response, not while querying devices.
+
+# Errors
+
+* `hyper::Error`, if binding to the address fails or the server errors
+*/
+pub async fn serve_with_events(
+    nvml: Arc<Nvml>,
+    query: DeviceQuery,
+    config: ExporterConfig,
+    events: Arc<Mutex<CriticalEvents>>,
+    addr: SocketAddr,
+) -> Result<(), hyper::Error> {
+    let make_service = make_service_fn(move |_| {
+        let nvml = nvml.clone();
+        let query = query.clone();
+        let config = config.clone();
+        let events = events.clone();
+
+        future::ready(Ok::<_, Infallible>(service_fn(move |request| {
+            let nvml = nvml.clone();
+            let query = query.clone();
+            let config = config.clone();
+            let events = events.clone();
+
+            blocking(move || {
+                respond(
+                    &config,
+                    &request,
+                    || collect(&nvml, &query),
+                    |snapshots| {
+                        // A panic elsewhere doesn't leave the counts inconsistent
+                        let events = events.lock().unwrap_or_else(|e| e.into_inner());
+                        prometheus::render_with_events(&config, snapshots, &events)
+                    },
+                )
+            })
+        })))
+    });
+
+    Server::try_bind(&addr)?.serve(make_service).await
+}
+
+// Runs `f` on the blocking thread pool, answering with a `500 Internal Server
+// Error` if it panics.
+async fn blocking<F>(f: F) -> Result<Response<Body>, Infallible>
+where
+    F: FnOnce() -> Response<Body> + Send + 'static,
+{
+    Ok(task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR)))
+}
+
+// Snapshots every device that can be queried.
+fn collect(nvml: &Nvml, query: &DeviceQuery) -> Result<Vec<DeviceSnapshot>, NvmlError> {
+    let snapshots = nvml
+        .devices()?
+        .map(|device| device.and_then(|device| query.snapshot(&device)));
+
+    skip_failed(snapshots)
+}
+
+// Leaves out the devices that failed, returning the last error only if every
+// device did.
+fn skip_failed<I>(results: I) -> Result<Vec<DeviceSnapshot>, NvmlError>
+where
+    I: IntoIterator<Item = Result<DeviceSnapshot, NvmlError>>,
+{
+    let mut snapshots = vec![];
+    let mut error = None;
+
+    for result in results {
+        match result {
+            Ok(snapshot) => snapshots.push(snapshot),
+            Err(e) => error = Some(e),
+        }
+    }
+
+    match error {
+        Some(e) if snapshots.is_empty() => Err(e),
+        _ => Ok(snapshots),
+    }
+}
+
+// Split out from `handle()` so that routing can be tested without NVML.
+fn respond<B, C, R>(
+    config: &ExporterConfig,
+    request: &Request<B>,
+    collect: C,
+    render: R,
+) -> Response<Body>
+where
+    C: FnOnce() -> Result<Vec<DeviceSnapshot>, NvmlError>,
+    R: FnOnce(&[DeviceSnapshot]) -> String,
+{
+    let content_type = match request.uri().path() {
+        "/metrics" => config.content_type(),
+        "/devices.json" => "application/json",
+        _ => return status(StatusCode::NOT_FOUND),
+    };
+
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return status(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    let snapshots = match collect() {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            let mut response = Response::new(Body::from(e.to_string()));
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            return response;
+        }
+    };
+
+    let body = if content_type == config.content_type() {
+        render(&snapshots).into_bytes()
+    } else {
+        match serde_json::to_vec(&snapshots) {
+            Ok(json) => json,
+            Err(_) => return status(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    };
+
+    let mut response = Response::new(Body::from(body));
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::enums::event::XidError;
+    use crate::high_level::query::QueryResult;
+    use crate::struct_wrappers::device::Utilization;
+
+    fn snapshot() -> DeviceSnapshot {
+        DeviceSnapshot {
+            index: 0,
+            uuid: "GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5".into(),
+            name: "NVIDIA Test GPU".into(),
+            pci_bus_id: "00000000:01:00.0".into(),
+            metrics: QueryResult {
+                utilization: Some(Utilization { gpu: 25, memory: 5 }),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn request(method: Method, path: &str) -> Request<()> {
+        Request::builder()
+            .method(method)
+            .uri(path)
+            .body(())
+            .unwrap()
+    }
+
+    #[test]
+    fn routing() {
+        let config = ExporterConfig::default();
+        let ok = || Ok(vec![snapshot()]);
+        let render = |snapshots: &[DeviceSnapshot]| prometheus::render(&config, snapshots);
+
+        let metrics = respond(&config, &request(Method::GET, "/metrics"), ok, render);
+        assert_eq!(metrics.status(), StatusCode::OK);
+        assert_eq!(metrics.headers()[CONTENT_TYPE], prometheus::CONTENT_TYPE);
+
+        let json = respond(&config, &request(Method::GET, "/devices.json"), ok, render);
+        assert_eq!(json.status(), StatusCode::OK);
+        assert_eq!(json.headers()[CONTENT_TYPE], "application/json");
+
+        let not_found = respond(&config, &request(Method::GET, "/"), ok, render);
+        assert_eq!(not_found.status(), StatusCode::NOT_FOUND);
+
+        let not_allowed = respond(&config, &request(Method::POST, "/metrics"), ok, render);
+        assert_eq!(not_allowed.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let failed = respond(
+            &config,
+            &request(Method::GET, "/metrics"),
+            || Err(NvmlError::GpuLost),
+            render,
+        );
+        assert_eq!(failed.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn failed_devices_are_skipped() {
+        let snapshots = skip_failed(vec![Err(NvmlError::GpuLost), Ok(snapshot())]).unwrap();
+        assert_eq!(snapshots, vec![snapshot()]);
+
+        assert_eq!(
+            skip_failed(vec![Err(NvmlError::Unknown), Err(NvmlError::GpuLost)]),
+            Err(NvmlError::GpuLost)
+        );
+        assert_eq!(skip_failed(vec![]), Ok(vec![]));
+    }
+
+    #[test]
+    fn critical_events() {
+        let mut config = ExporterConfig::default();
+        config.openmetrics(true);
+
+        let mut events = CriticalEvents::new();
+        events.record(&snapshot().uuid, CriticalEvent::Xid(XidError::Value(79)));
+
+        let metrics = respond(
+            &config,
+            &request(Method::GET, "/metrics"),
+            || Ok(vec![snapshot()]),
+            |snapshots| prometheus::render_with_events(&config, snapshots, &events),
+        );
+        assert_eq!(metrics.status(), StatusCode::OK);
+        assert_eq!(
+            metrics.headers()[CONTENT_TYPE],
+            prometheus::OPENMETRICS_CONTENT_TYPE
+        );
+    }
+}
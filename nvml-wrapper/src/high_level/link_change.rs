@@ -0,0 +1,284 @@
+/*!
+PCIe link state changes, detected by polling.
+
+NVML has no event types for PCIe link changes or AER errors, so they can't be
+waited on through an `EventSet`. A `LinkChangeWatcher` instead compares each
+reading of a device's link generation and width with the previous one, and
+reports increases of the PCIe replay counter (link-level errors corrected by
+retransmission, the closest NVML gets to correctable AER errors):
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::link_change::{LinkChangeWatcher, LinkEvent};
+use std::{thread, time::Duration};
+
+let nvml = Nvml::init()?;
+let device = nvml.device_by_index(0)?;
+
+let mut watcher = LinkChangeWatcher::for_device(&device)?;
+
+loop {
+    for event in watcher.poll(&device)? {
+        match event {
+            LinkEvent::Changed { from, to } if to.width < from.width => {
+                println!("link width dropped from x{} to x{}", from.width, to.width)
+            }
+            LinkEvent::Changed { .. } => {}
+            LinkEvent::Replays { new, total } => {
+                println!("{} new PCIe replays ({} total)", new, total)
+            }
+        }
+    }
+
+    thread::sleep(Duration::from_secs(1));
+}
+# }
+```
+
+Note that most GPUs lower their link generation while idle to save power, so
+generation changes alone are usually not a problem. A link that stays below
+`LinkChangeWatcher.max()` under load, or one that loses width, is.
+*/
+
+use crate::error::{NvmlError, NvmlResultExt};
+use crate::Device;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The generation and width a PCIe link is trained to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LinkState {
+    pub gen: u32,
+    /// The number of lanes.
+    pub width: u32,
+}
+
+impl LinkState {
+    /**
+    Read the current link state of the given device.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the device is invalid
+    * `NotSupported`, if PCIe link information is not available
+    * `GpuLost`, if the device has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    pub fn current(device: &Device) -> Result<Self, NvmlError> {
+        Ok(Self {
+            gen: device.current_pcie_link_gen()?,
+            width: device.current_pcie_link_width()?,
+        })
+    }
+
+    /**
+    Read the best link state possible with the given device and system.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the device is invalid
+    * `NotSupported`, if PCIe link information is not available
+    * `GpuLost`, if the device has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    pub fn max(device: &Device) -> Result<Self, NvmlError> {
+        Ok(Self {
+            gen: device.max_pcie_link_gen()?,
+            width: device.max_pcie_link_width()?,
+        })
+    }
+
+    /// Whether or not either the generation or the width is below `max`'s.
+    pub fn is_below(&self, max: &LinkState) -> bool {
+        self.gen < max.gen || self.width < max.width
+    }
+}
+
+/// A change detected by a `LinkChangeWatcher`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LinkEvent {
+    /// The link was retrained to a different generation or width.
+    Changed { from: LinkState, to: LinkState },
+    /// The PCIe replay counter increased by `new`, to `total`.
+    Replays { new: u32, total: u32 },
+}
+
+/// Tracks the PCIe link of a device. See the module-level docs.
+#[derive(Debug, Clone, Default)]
+pub struct LinkChangeWatcher {
+    max: Option<LinkState>,
+    last: Option<LinkState>,
+    replays: Option<u32>,
+}
+
+impl LinkChangeWatcher {
+    /// Create a watcher that doesn't know the device's best link state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    Create a watcher with the best link state of the given device, if it
+    reports one.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the device is invalid
+    * `GpuLost`, if the device has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    pub fn for_device(device: &Device) -> Result<Self, NvmlError> {
+        Ok(Self {
+            max: LinkState::max(device).optional()?,
+            ..Self::default()
+        })
+    }
+
+    /// The best link state possible with the device, if known.
+    pub fn max(&self) -> Option<LinkState> {
+        self.max
+    }
+
+    /// The link state of the last reading, if any.
+    pub fn last(&self) -> Option<LinkState> {
+        self.last
+    }
+
+    /// Whether or not the last reading is below the best link state possible.
+    ///
+    /// `false` if either is unknown.
+    pub fn is_degraded(&self) -> bool {
+        match (self.last, self.max) {
+            (Some(last), Some(max)) => last.is_below(&max),
+            _ => false,
+        }
+    }
+
+    /**
+    Track a new reading of the link state and, if the device reports it, the
+    PCIe replay counter.
+
+    The first reading only sets the baseline. A replay counter that went down
+    (e.g. because the driver was reloaded) is taken as the new baseline as well.
+    */
+    pub fn update(&mut self, state: LinkState, replays: Option<u32>) -> Vec<LinkEvent> {
+        let mut events = vec![];
+
+        if let Some(from) = self.last.replace(state) {
+            if from != state {
+                events.push(LinkEvent::Changed { from, to: state });
+            }
+        }
+
+        if let (Some(previous), Some(total)) = (self.replays, replays) {
+            if total > previous {
+                events.push(LinkEvent::Replays {
+                    new: total - previous,
+                    total,
+                });
+            }
+        }
+
+        if replays.is_some() {
+            self.replays = replays;
+        }
+
+        events
+    }
+
+    /**
+    Read the link state and replay counter of the given device and track them,
+    as `.update()` does.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the device is invalid
+    * `NotSupported`, if PCIe link information is not available
+    * `GpuLost`, if the device has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    pub fn poll(&mut self, device: &Device) -> Result<Vec<LinkEvent>, NvmlError> {
+        let state = LinkState::current(device)?;
+        let replays = device.pcie_replay_counter().optional()?;
+
+        Ok(self.update(state, replays))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn state(gen: u32, width: u32) -> LinkState {
+        LinkState { gen, width }
+    }
+
+    #[test]
+    fn changes() {
+        let mut watcher = LinkChangeWatcher::new();
+
+        assert_eq!(watcher.update(state(4, 16), None), []);
+        assert_eq!(watcher.update(state(4, 16), None), []);
+        assert_eq!(
+            watcher.update(state(1, 16), None),
+            [LinkEvent::Changed {
+                from: state(4, 16),
+                to: state(1, 16)
+            }]
+        );
+        assert_eq!(watcher.last(), Some(state(1, 16)));
+        assert!(!watcher.is_degraded());
+
+        watcher.max = Some(state(4, 16));
+        assert!(watcher.is_degraded());
+        assert_eq!(watcher.update(state(4, 8), None).len(), 1);
+        assert!(watcher.is_degraded());
+        assert_eq!(watcher.update(state(4, 16), None).len(), 1);
+        assert!(!watcher.is_degraded());
+    }
+
+    #[test]
+    fn replays() {
+        let mut watcher = LinkChangeWatcher::new();
+        let link = state(4, 16);
+
+        assert_eq!(watcher.update(link, Some(3)), []);
+        assert_eq!(watcher.update(link, Some(3)), []);
+        assert_eq!(
+            watcher.update(link, Some(5)),
+            [LinkEvent::Replays { new: 2, total: 5 }]
+        );
+
+        // Missing readings keep the baseline
+        assert_eq!(watcher.update(link, None), []);
+        assert_eq!(
+            watcher.update(link, Some(6)),
+            [LinkEvent::Replays { new: 1, total: 6 }]
+        );
+
+        // A counter that went down becomes the new baseline
+        assert_eq!(watcher.update(link, Some(0)), []);
+        assert_eq!(
+            watcher.update(link, Some(1)),
+            [LinkEvent::Replays { new: 1, total: 1 }]
+        );
+    }
+
+    #[test]
+    fn for_device() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            let mut watcher = LinkChangeWatcher::for_device(device)?;
+            watcher.poll(device)
+        })
+    }
+}
@@ -0,0 +1,799 @@
+/*!
+Declarative MIG layouts that are applied as a whole or not at all.
+
+Partitioning a GPU by hand means creating and destroying GPU instances and
+their compute instances one at a time; if one call fails half way through, the
+GPU is left in a layout nobody asked for. A `MigConfig` instead describes the
+layout each GPU should end up with, as a number of GPU instances per profile,
+and `MigConfig::apply()` works out and performs the changes needed to get
+there:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use nvml_wrapper::enums::device::GpuInstanceProfile;
+use nvml_wrapper::high_level::mig_config::{MigConfig, MigLayout};
+
+let nvml = Nvml::init()?;
+
+let changes = MigConfig::new()
+    .device(
+        0,
+        MigLayout::new()
+            .instances(GpuInstanceProfile::Slice3, 1)
+            .instances(GpuInstanceProfile::Slice2, 2),
+    )
+    .apply(&nvml)?;
+
+for change in changes {
+    println!("{:?}", change);
+}
+# Ok(())
+# }
+```
+
+GPU instances that already match the layout are left alone, so applying the
+same config twice changes nothing the second time. Surplus instances are
+destroyed (highest ID first) before missing ones are created (largest profile
+first), which leaves the most room for the new instances. Every new GPU
+instance gets a single compute instance spanning all of it; GPU instances that
+are kept are not touched, whatever their compute instances look like.
+
+If any step fails, the steps that already succeeded are undone in reverse
+order: created instances are destroyed and destroyed ones are recreated at
+their original placement, along with their compute instances. The error says
+which device the failing step was for and whether undoing everything worked.
+
+GPUs that aren't part of the config are never touched. MIG mode must already
+be enabled on the ones that are (see `Device.is_mig_enabled()`), and changing
+MIG layouts requires root/admin permissions.
+*/
+
+use crate::enums::device::{ComputeInstanceProfile, GpuInstanceProfile};
+use crate::error::{NvmlError, UnsupportedContext};
+use crate::mig_device::{ComputeInstance, GpuInstance};
+use crate::struct_wrappers::device::InstancePlacement;
+use crate::{Device, Nvml};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+pub(crate) const GPU_INSTANCE_PROFILES: [GpuInstanceProfile; 8] = [
+    GpuInstanceProfile::Slice1,
+    GpuInstanceProfile::Slice1Rev1,
+    GpuInstanceProfile::Slice2,
+    GpuInstanceProfile::Slice3,
+    GpuInstanceProfile::Slice4,
+    GpuInstanceProfile::Slice6,
+    GpuInstanceProfile::Slice7,
+    GpuInstanceProfile::Slice8,
+];
+
+const COMPUTE_INSTANCE_PROFILES: [ComputeInstanceProfile; 7] = [
+    ComputeInstanceProfile::Slice1,
+    ComputeInstanceProfile::Slice2,
+    ComputeInstanceProfile::Slice3,
+    ComputeInstanceProfile::Slice4,
+    ComputeInstanceProfile::Slice6,
+    ComputeInstanceProfile::Slice7,
+    ComputeInstanceProfile::Slice8,
+];
+
+/// The GPU instances one GPU should have, as a number of instances per
+/// profile.
+///
+/// Profiles that aren't mentioned should have no instances.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MigLayout {
+    instances: BTreeMap<GpuInstanceProfile, u32>,
+}
+
+impl MigLayout {
+    /// A layout without any GPU instances.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of GPU instances of the given profile, replacing any
+    /// count set for it before.
+    pub fn instances(mut self, profile: GpuInstanceProfile, count: u32) -> Self {
+        if count == 0 {
+            self.instances.remove(&profile);
+        } else {
+            self.instances.insert(profile, count);
+        }
+
+        self
+    }
+
+    /// The number of GPU instances of the given profile in this layout.
+    pub fn count(&self, profile: GpuInstanceProfile) -> u32 {
+        self.instances.get(&profile).copied().unwrap_or(0)
+    }
+}
+
+/// The MIG layouts of a set of GPUs, keyed by device index.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MigConfig {
+    layouts: BTreeMap<u32, MigLayout>,
+}
+
+impl MigConfig {
+    /// A config that doesn't cover any GPU.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the layout of the GPU at the given index, replacing any layout set
+    /// for it before.
+    pub fn device(mut self, index: u32, layout: MigLayout) -> Self {
+        self.layouts.insert(index, layout);
+        self
+    }
+
+    /// The layout of the GPU at the given index, if this config covers it.
+    pub fn layout(&self, index: u32) -> Option<&MigLayout> {
+        self.layouts.get(&index)
+    }
+
+    /**
+    Changes the GPU instances of every GPU in this config to match its layout,
+    undoing all changes if one fails.
+
+    Returns the changes that were made, in the order they were made; empty if
+    every GPU already matched.
+
+    # Errors
+
+    A `MigConfigError` describing the step that failed and any errors
+    encountered while undoing the steps before it. Common causes:
+
+    * `NotSupported`, if MIG mode isn't enabled on a GPU or a layout uses a
+      profile the GPU doesn't support
+    * `NoPermission`, if the user doesn't have permission to change MIG layouts
+    * `InUse`, if a GPU instance that should be destroyed is in use
+    * `UnrecognizedCode`, with `NVML_ERROR_INSUFFICIENT_RESOURCES`, if a layout
+      doesn't fit on its GPU
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    pub fn apply(&self, nvml: &Nvml) -> Result<Vec<MigChange>, MigConfigError> {
+        self.apply_with(&mut NvmlBackend { nvml })
+    }
+
+    fn apply_with<B: Backend>(&self, backend: &mut B) -> Result<Vec<MigChange>, MigConfigError> {
+        let mut journal: Vec<Step> = vec![];
+
+        for (&device, layout) in &self.layouts {
+            if let Err(error) = apply_layout(backend, device, layout, &mut journal) {
+                let rollback_errors = journal
+                    .iter()
+                    .rev()
+                    .filter_map(|step| step.undo(backend).err())
+                    .collect();
+
+                return Err(MigConfigError {
+                    device,
+                    error,
+                    rollback_errors,
+                });
+            }
+        }
+
+        Ok(journal.iter().map(Step::change).collect())
+    }
+}
+
+/// A change made by `MigConfig::apply()`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MigChange {
+    /// A GPU instance was created, along with a compute instance spanning it.
+    Created {
+        device: u32,
+        profile: GpuInstanceProfile,
+        gpu_instance_id: u32,
+    },
+    /// A GPU instance was destroyed, along with its compute instances.
+    Destroyed {
+        device: u32,
+        profile: GpuInstanceProfile,
+        gpu_instance_id: u32,
+    },
+}
+
+/// Returned from `MigConfig::apply()` when a step fails.
+#[derive(Error, Debug, PartialEq)]
+#[error(
+    "applying the MIG layout of device {device} failed: {error}{}",
+    rollback_summary(.rollback_errors)
+)]
+pub struct MigConfigError {
+    /// The index of the device the failing step was for.
+    pub device: u32,
+    /// The error the failing step returned.
+    #[source]
+    pub error: NvmlError,
+    /// Errors encountered while undoing the steps that succeeded before the
+    /// failing one.
+    ///
+    /// Empty if every GPU was returned to the layout it had before
+    /// `MigConfig::apply()` was called.
+    pub rollback_errors: Vec<NvmlError>,
+}
+
+fn rollback_summary(errors: &[NvmlError]) -> String {
+    match errors.first() {
+        None => String::new(),
+        Some(first) => format!(
+            " (rolling back failed {} time(s), first with: {})",
+            errors.len(),
+            first
+        ),
+    }
+}
+
+/// A GPU instance as found on a GPU, with enough detail to recreate it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct ExistingInstance {
+    id: u32,
+    profile: GpuInstanceProfile,
+    placement: InstancePlacement,
+    compute_profiles: Vec<ComputeInstanceProfile>,
+}
+
+#[derive(Debug)]
+enum Step {
+    Created {
+        device: u32,
+        profile: GpuInstanceProfile,
+        id: u32,
+    },
+    Destroyed {
+        device: u32,
+        instance: ExistingInstance,
+    },
+}
+
+impl Step {
+    fn change(&self) -> MigChange {
+        match *self {
+            Step::Created {
+                device,
+                profile,
+                id,
+            } => MigChange::Created {
+                device,
+                profile,
+                gpu_instance_id: id,
+            },
+            Step::Destroyed {
+                device,
+                ref instance,
+            } => MigChange::Destroyed {
+                device,
+                profile: instance.profile,
+                gpu_instance_id: instance.id,
+            },
+        }
+    }
+
+    fn undo<B: Backend>(&self, backend: &mut B) -> Result<(), NvmlError> {
+        match *self {
+            Step::Created { device, id, .. } => backend.destroy(device, id),
+            Step::Destroyed {
+                device,
+                ref instance,
+            } => backend.restore(device, instance),
+        }
+    }
+}
+
+/// The operations `MigConfig::apply()` needs, so that the diffing and rollback
+/// logic can be tested without a MIG-capable GPU.
+trait Backend {
+    fn instances(&mut self, device: u32) -> Result<Vec<ExistingInstance>, NvmlError>;
+
+    /// Creates a GPU instance with a compute instance spanning it and returns
+    /// the GPU instance's ID.
+    fn create(&mut self, device: u32, profile: GpuInstanceProfile) -> Result<u32, NvmlError>;
+
+    /// Destroys a GPU instance along with its compute instances, leaving it
+    /// untouched on failure.
+    fn destroy(&mut self, device: u32, id: u32) -> Result<(), NvmlError>;
+
+    /// Recreates a destroyed GPU instance along with its compute instances,
+    /// leaving nothing behind on failure.
+    fn restore(&mut self, device: u32, instance: &ExistingInstance) -> Result<(), NvmlError>;
+}
+
+fn apply_layout<B: Backend>(
+    backend: &mut B,
+    device: u32,
+    layout: &MigLayout,
+    journal: &mut Vec<Step>,
+) -> Result<(), NvmlError> {
+    let mut existing = backend.instances(device)?;
+    existing.sort_by_key(|instance| instance.id);
+
+    for &profile in GPU_INSTANCE_PROFILES.iter() {
+        let wanted = layout.count(profile) as usize;
+        let mut present: Vec<&ExistingInstance> = existing
+            .iter()
+            .filter(|instance| instance.profile == profile)
+            .collect();
+
+        while present.len() > wanted {
+            let instance = present.pop().expect("more present than wanted");
+            backend.destroy(device, instance.id)?;
+
+            journal.push(Step::Destroyed {
+                device,
+                instance: instance.clone(),
+            });
+        }
+    }
+
+    let mut missing: Vec<(GpuInstanceProfile, u32)> = GPU_INSTANCE_PROFILES
+        .iter()
+        .map(|&profile| {
+            let present = existing
+                .iter()
+                .filter(|instance| instance.profile == profile)
+                .count() as u32;
+
+            (profile, layout.count(profile).saturating_sub(present))
+        })
+        .filter(|&(_, count)| count > 0)
+        .collect();
+
+    missing.sort_by_key(|&(profile, _)| std::cmp::Reverse(slice_count(profile)));
+
+    for (profile, count) in missing {
+        for _ in 0..count {
+            let id = backend.create(device, profile)?;
+            journal.push(Step::Created {
+                device,
+                profile,
+                id,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn slice_count(profile: GpuInstanceProfile) -> u32 {
+    match profile {
+        GpuInstanceProfile::Slice1 | GpuInstanceProfile::Slice1Rev1 => 1,
+        GpuInstanceProfile::Slice2 => 2,
+        GpuInstanceProfile::Slice3 => 3,
+        GpuInstanceProfile::Slice4 => 4,
+        GpuInstanceProfile::Slice6 => 6,
+        GpuInstanceProfile::Slice7 => 7,
+        GpuInstanceProfile::Slice8 => 8,
+    }
+}
+
+/// The compute instance profile that spans a whole GPU instance of the given
+/// profile.
+fn spanning_compute_profile(profile: GpuInstanceProfile) -> ComputeInstanceProfile {
+    match slice_count(profile) {
+        1 => ComputeInstanceProfile::Slice1,
+        2 => ComputeInstanceProfile::Slice2,
+        3 => ComputeInstanceProfile::Slice3,
+        4 => ComputeInstanceProfile::Slice4,
+        6 => ComputeInstanceProfile::Slice6,
+        7 => ComputeInstanceProfile::Slice7,
+        _ => ComputeInstanceProfile::Slice8,
+    }
+}
+
+/// Treats errors NVML returns for profiles a GPU doesn't offer as there being
+/// nothing of that profile.
+fn unless_unsupported<T>(result: Result<T, NvmlError>) -> Result<Option<T>, NvmlError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(NvmlError::NotSupported(_)) | Err(NvmlError::InvalidArg) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+struct NvmlBackend<'nvml> {
+    nvml: &'nvml Nvml,
+}
+
+impl<'nvml> NvmlBackend<'nvml> {
+    fn mig_device(&self, index: u32) -> Result<Device<'nvml>, NvmlError> {
+        let device = self.nvml.device_by_index(index)?;
+
+        if !device.is_mig_enabled()?.currently_enabled {
+            return Err(NvmlError::NotSupported(Some(
+                UnsupportedContext::for_function("nvmlDeviceGetGpuInstanceProfileInfo"),
+            )));
+        }
+
+        Ok(device)
+    }
+}
+
+fn compute_instances<'nvml>(
+    instance: &GpuInstance<'nvml>,
+) -> Result<Vec<(ComputeInstanceProfile, ComputeInstance<'nvml>)>, NvmlError> {
+    let mut compute_instances = vec![];
+
+    for &profile in COMPUTE_INSTANCE_PROFILES.iter() {
+        let info = match unless_unsupported(instance.compute_instance_profile_info(profile))? {
+            Some(info) => info,
+            None => continue,
+        };
+
+        for compute_instance in instance.compute_instances(&info)? {
+            compute_instances.push((profile, compute_instance));
+        }
+    }
+
+    Ok(compute_instances)
+}
+
+impl<'nvml> Backend for NvmlBackend<'nvml> {
+    fn instances(&mut self, device: u32) -> Result<Vec<ExistingInstance>, NvmlError> {
+        let device = self.mig_device(device)?;
+        let mut instances = vec![];
+
+        for &profile in GPU_INSTANCE_PROFILES.iter() {
+            let info = match unless_unsupported(device.gpu_instance_profile_info(profile))? {
+                Some(info) => info,
+                None => continue,
+            };
+
+            for instance in device.gpu_instances(&info)? {
+                let instance_info = instance.info()?;
+
+                instances.push(ExistingInstance {
+                    id: instance_info.id,
+                    profile,
+                    placement: instance_info.placement,
+                    compute_profiles: compute_instances(&instance)?
+                        .into_iter()
+                        .map(|(profile, _)| profile)
+                        .collect(),
+                });
+            }
+        }
+
+        Ok(instances)
+    }
+
+    fn create(&mut self, device: u32, profile: GpuInstanceProfile) -> Result<u32, NvmlError> {
+        let mut device = self.mig_device(device)?;
+        let info = device.gpu_instance_profile_info(profile)?;
+        let mut instance = device.create_gpu_instance(&info)?;
+
+        let spanning = instance
+            .compute_instance_profile_info(spanning_compute_profile(profile))
+            .and_then(|info| instance.create_compute_instance(&info));
+
+        match spanning.and_then(|_| instance.info()) {
+            Ok(info) => Ok(info.id),
+            Err(e) => {
+                // Don't leave an unusable GPU instance behind; the original
+                // error is the interesting one
+                if let Ok(compute_instances) = compute_instances(&instance) {
+                    for (_, compute_instance) in compute_instances {
+                        let _ = compute_instance.destroy();
+                    }
+                }
+                let _ = instance.destroy();
+
+                Err(e)
+            }
+        }
+    }
+
+    fn destroy(&mut self, device: u32, id: u32) -> Result<(), NvmlError> {
+        let device = self.mig_device(device)?;
+        let instance = device.gpu_instance_by_id(id)?;
+        let mut destroyed = vec![];
+
+        // Undo a half-finished teardown before returning the error; the
+        // caller's journal only records the GPU instance as a whole
+        let result = compute_instances(&instance).and_then(|compute_instances| {
+            for (profile, compute_instance) in compute_instances {
+                compute_instance.destroy()?;
+                destroyed.push(profile);
+            }
+
+            instance.destroy()
+        });
+
+        if result.is_err() && !destroyed.is_empty() {
+            if let Ok(mut instance) = device.gpu_instance_by_id(id) {
+                for &profile in destroyed.iter().rev() {
+                    let _ = instance
+                        .compute_instance_profile_info(profile)
+                        .and_then(|info| instance.create_compute_instance(&info));
+                }
+            }
+        }
+
+        result
+    }
+
+    fn restore(&mut self, device: u32, existing: &ExistingInstance) -> Result<(), NvmlError> {
+        let mut device = self.mig_device(device)?;
+        let info = device.gpu_instance_profile_info(existing.profile)?;
+        let mut instance = device.create_gpu_instance_with_placement(&info, &existing.placement)?;
+        let mut created = vec![];
+
+        for &profile in &existing.compute_profiles {
+            let compute_instance = instance
+                .compute_instance_profile_info(profile)
+                .and_then(|info| instance.create_compute_instance(&info));
+
+            match compute_instance {
+                Ok(compute_instance) => created.push(compute_instance),
+                Err(e) => {
+                    // Don't leave a partly restored GPU instance behind; the
+                    // original error is the interesting one
+                    for compute_instance in created.into_iter().rev() {
+                        let _ = compute_instance.destroy();
+                    }
+                    let _ = instance.destroy();
+
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeBackend {
+        devices: BTreeMap<u32, Vec<ExistingInstance>>,
+        next_id: u32,
+        /// Fail the `create()` call with this many calls before it.
+        fail_create_after: Option<usize>,
+        creates: usize,
+    }
+
+    impl FakeBackend {
+        fn with(mut self, device: u32, id: u32, profile: GpuInstanceProfile) -> Self {
+            self.devices
+                .entry(device)
+                .or_default()
+                .push(ExistingInstance {
+                    id,
+                    profile,
+                    placement: InstancePlacement {
+                        start: id,
+                        size: slice_count(profile),
+                    },
+                    compute_profiles: vec![spanning_compute_profile(profile)],
+                });
+            self.next_id = self.next_id.max(id + 1);
+            self
+        }
+
+        fn layout(&self, device: u32) -> Vec<(u32, GpuInstanceProfile)> {
+            let mut layout: Vec<_> = self.devices[&device]
+                .iter()
+                .map(|instance| (instance.id, instance.profile))
+                .collect();
+
+            layout.sort();
+            layout
+        }
+    }
+
+    impl Backend for FakeBackend {
+        fn instances(&mut self, device: u32) -> Result<Vec<ExistingInstance>, NvmlError> {
+            self.devices
+                .get(&device)
+                .cloned()
+                .ok_or(NvmlError::InvalidArg)
+        }
+
+        fn create(&mut self, device: u32, profile: GpuInstanceProfile) -> Result<u32, NvmlError> {
+            if self.fail_create_after == Some(self.creates) {
+                return Err(NvmlError::UnrecognizedCode(23));
+            }
+
+            self.creates += 1;
+            self.next_id += 1;
+
+            let id = self.next_id;
+            self.devices
+                .get_mut(&device)
+                .ok_or(NvmlError::InvalidArg)?
+                .push(ExistingInstance {
+                    id,
+                    profile,
+                    placement: InstancePlacement {
+                        start: id,
+                        size: slice_count(profile),
+                    },
+                    compute_profiles: vec![spanning_compute_profile(profile)],
+                });
+
+            Ok(id)
+        }
+
+        fn destroy(&mut self, device: u32, id: u32) -> Result<(), NvmlError> {
+            let instances = self.devices.get_mut(&device).ok_or(NvmlError::InvalidArg)?;
+            let before = instances.len();
+            instances.retain(|instance| instance.id != id);
+
+            if instances.len() == before {
+                return Err(NvmlError::NotFound);
+            }
+
+            Ok(())
+        }
+
+        fn restore(&mut self, device: u32, instance: &ExistingInstance) -> Result<(), NvmlError> {
+            self.devices
+                .get_mut(&device)
+                .ok_or(NvmlError::InvalidArg)?
+                .push(instance.clone());
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn layout_counts() {
+        let layout = MigLayout::new()
+            .instances(GpuInstanceProfile::Slice2, 3)
+            .instances(GpuInstanceProfile::Slice1, 1)
+            .instances(GpuInstanceProfile::Slice1, 0);
+
+        assert_eq!(layout.count(GpuInstanceProfile::Slice2), 3);
+        assert_eq!(layout.count(GpuInstanceProfile::Slice1), 0);
+        assert_eq!(
+            layout,
+            MigLayout::new().instances(GpuInstanceProfile::Slice2, 3)
+        );
+    }
+
+    #[test]
+    fn apply_diffs_against_current_instances() {
+        let mut backend = FakeBackend::default()
+            .with(0, 1, GpuInstanceProfile::Slice1)
+            .with(0, 2, GpuInstanceProfile::Slice1)
+            .with(0, 3, GpuInstanceProfile::Slice3)
+            .with(1, 1, GpuInstanceProfile::Slice7);
+
+        let config = MigConfig::new().device(
+            0,
+            MigLayout::new()
+                .instances(GpuInstanceProfile::Slice1, 1)
+                .instances(GpuInstanceProfile::Slice2, 1)
+                .instances(GpuInstanceProfile::Slice4, 1),
+        );
+
+        let changes = config.apply_with(&mut backend).expect("applied");
+
+        assert_eq!(
+            changes,
+            vec![
+                MigChange::Destroyed {
+                    device: 0,
+                    profile: GpuInstanceProfile::Slice1,
+                    gpu_instance_id: 2,
+                },
+                MigChange::Destroyed {
+                    device: 0,
+                    profile: GpuInstanceProfile::Slice3,
+                    gpu_instance_id: 3,
+                },
+                MigChange::Created {
+                    device: 0,
+                    profile: GpuInstanceProfile::Slice4,
+                    gpu_instance_id: 5,
+                },
+                MigChange::Created {
+                    device: 0,
+                    profile: GpuInstanceProfile::Slice2,
+                    gpu_instance_id: 6,
+                },
+            ]
+        );
+        assert_eq!(
+            backend.layout(0),
+            vec![
+                (1, GpuInstanceProfile::Slice1),
+                (5, GpuInstanceProfile::Slice4),
+                (6, GpuInstanceProfile::Slice2),
+            ]
+        );
+        // Not part of the config
+        assert_eq!(backend.layout(1), vec![(1, GpuInstanceProfile::Slice7)]);
+
+        assert_eq!(config.apply_with(&mut backend), Ok(vec![]));
+    }
+
+    #[test]
+    fn apply_rolls_back_on_failure() {
+        let mut backend = FakeBackend::default()
+            .with(0, 1, GpuInstanceProfile::Slice7)
+            .with(1, 1, GpuInstanceProfile::Slice3);
+        backend.fail_create_after = Some(2);
+
+        let config = MigConfig::new()
+            .device(0, MigLayout::new().instances(GpuInstanceProfile::Slice2, 2))
+            .device(1, MigLayout::new().instances(GpuInstanceProfile::Slice1, 1));
+
+        let error = config.apply_with(&mut backend).unwrap_err();
+
+        assert_eq!(
+            error,
+            MigConfigError {
+                device: 1,
+                error: NvmlError::UnrecognizedCode(23),
+                rollback_errors: vec![],
+            }
+        );
+        assert_eq!(backend.layout(0), vec![(1, GpuInstanceProfile::Slice7)]);
+        assert_eq!(backend.layout(1), vec![(1, GpuInstanceProfile::Slice3)]);
+        assert_eq!(
+            backend.devices[&0][0].placement,
+            InstancePlacement { start: 1, size: 7 }
+        );
+    }
+
+    #[test]
+    fn rollback_errors_are_reported() {
+        let mut backend = FakeBackend::default().with(0, 1, GpuInstanceProfile::Slice1);
+        backend.fail_create_after = Some(1);
+
+        let config =
+            MigConfig::new().device(0, MigLayout::new().instances(GpuInstanceProfile::Slice1, 3));
+
+        // The instance created first vanishes before the rollback gets to it
+        let error = {
+            let mut backend = RemoveOnFailure(&mut backend);
+            config.apply_with(&mut backend).unwrap_err()
+        };
+
+        assert_eq!(error.rollback_errors, vec![NvmlError::NotFound]);
+        assert!(error.to_string().contains("rolling back failed 1 time(s)"));
+    }
+
+    struct RemoveOnFailure<'a>(&'a mut FakeBackend);
+
+    impl<'a> Backend for RemoveOnFailure<'a> {
+        fn instances(&mut self, device: u32) -> Result<Vec<ExistingInstance>, NvmlError> {
+            self.0.instances(device)
+        }
+
+        fn create(&mut self, device: u32, profile: GpuInstanceProfile) -> Result<u32, NvmlError> {
+            let result = self.0.create(device, profile);
+
+            if result.is_err() {
+                self.0.devices.get_mut(&device).unwrap().clear();
+            }
+
+            result
+        }
+
+        fn destroy(&mut self, device: u32, id: u32) -> Result<(), NvmlError> {
+            self.0.destroy(device, id)
+        }
+
+        fn restore(&mut self, device: u32, instance: &ExistingInstance) -> Result<(), NvmlError> {
+            self.0.restore(device, instance)
+        }
+    }
+}
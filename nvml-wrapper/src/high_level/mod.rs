@@ -1,4 +1,60 @@
+pub mod broadcast;
+#[cfg(target_os = "linux")]
+pub mod cross_check;
+pub mod device_group;
+pub mod encoder_latency;
 #[cfg(target_os = "linux")]
 pub mod event_loop;
+#[cfg(all(feature = "gpu-lock", target_os = "linux"))]
+pub mod gpu_lock;
+#[cfg(target_os = "linux")]
+pub mod gpu_lost;
+#[cfg(feature = "http-exporter")]
+pub mod http_exporter;
+pub mod link_change;
+#[cfg(target_os = "linux")]
+pub mod mig_config;
+pub mod monitor;
+pub mod process_utilization;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+pub mod query;
+pub mod registry;
+#[cfg(target_os = "linux")]
+pub mod reset_readiness;
+pub mod sampler;
+#[cfg(all(feature = "shm", target_os = "linux"))]
+pub mod shm;
+#[cfg(feature = "cli")]
+pub mod table;
+pub mod temperature_alert;
+pub mod top;
+#[cfg(target_os = "linux")]
+pub mod topology;
+pub mod watch;
+pub use self::broadcast::{Broadcast, Subscription};
+#[cfg(target_os = "linux")]
+pub use self::cross_check::CrossCheckReport;
+pub use self::device_group::{DeviceGroup, DeviceHealth, GroupHealth, PartialFailure};
+pub use self::encoder_latency::EncoderLatencyTracker;
 #[cfg(target_os = "linux")]
 pub use self::event_loop::{Event, EventLoop, EventLoopProvider};
+#[cfg(target_os = "linux")]
+pub use self::gpu_lost::{GpuLostDetector, LostDevice};
+pub use self::link_change::LinkChangeWatcher;
+#[cfg(target_os = "linux")]
+pub use self::mig_config::{MigChange, MigConfig, MigConfigError, MigLayout};
+pub use self::monitor::{Cycle, MonitorLoop};
+pub use self::process_utilization::{ProcessUtilization, ProcessUtilizationTracker};
+pub use self::query::{DeviceQuery, DeviceQueryBuilder, DeviceSnapshot, QueryResult};
+pub use self::registry::{DeviceRegistry, RegistryEvent};
+#[cfg(target_os = "linux")]
+pub use self::reset_readiness::ResetReadiness;
+pub use self::sampler::Sampler;
+pub use self::temperature_alert::TemperatureAlerts;
+pub use self::top::{Top, TopView};
+#[cfg(target_os = "linux")]
+pub use self::topology::TopologyGraph;
+#[cfg(feature = "watch-stream")]
+pub use self::watch::WatchStream;
+pub use self::watch::{MetricUpdate, MetricValue, Watch};
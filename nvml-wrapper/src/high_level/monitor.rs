@@ -0,0 +1,266 @@
+/*!
+A long-running poll loop for monitoring daemons.
+
+Every daemon that watches GPUs ends up with the same loop: poll each device on
+a fixed interval, keep going when one device misbehaves, report what went
+wrong, and tell the service manager the loop is still alive. A `MonitorLoop`
+is that loop:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::{DeviceQuery, MonitorLoop};
+use std::{ops::ControlFlow, time::Duration};
+
+let nvml = Nvml::init()?;
+let query = DeviceQuery::builder().utilization().temperature().build();
+
+MonitorLoop::new(query)
+    .interval(Duration::from_secs(5))
+    .run(&nvml, |cycle| {
+        for device in &cycle.devices {
+            match device.outcome {
+                Ok(ref snapshot) => println!("{}: {:?}", snapshot.uuid, snapshot.metrics),
+                Err(ref e) => eprintln!("device {}: {}", device.index, e),
+            }
+        }
+
+        // e.g. `sd_notify(0, "WATCHDOG=1")`
+        ControlFlow::Continue(())
+    })?;
+# Ok(())
+# }
+```
+
+The hook runs once per cycle, after every device has been polled, so it is a
+natural place to ping a systemd watchdog: if an NVML call hangs, the pings stop
+and the service manager can restart the daemon. Pick an interval well below the
+watchdog timeout.
+
+A device whose query fails or panics is reported in its `DeviceCycle` and
+polled again next cycle; it never takes down the loop or the other devices.
+*/
+
+use crate::error::NvmlError;
+use crate::high_level::query::{DeviceQuery, DeviceSnapshot};
+use crate::{Device, Nvml};
+use std::{
+    any::Any,
+    ops::ControlFlow,
+    panic::{self, AssertUnwindSafe},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+/// The default interval between cycles.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Polls every device on a fixed interval. See the module-level docs.
+#[derive(Debug, Clone)]
+pub struct MonitorLoop {
+    query: DeviceQuery,
+    interval: Duration,
+}
+
+/// Why polling a single device failed during a cycle.
+#[derive(Error, Debug)]
+pub enum MonitorError {
+    #[error("an NVML error occurred: {0}")]
+    Nvml(#[from] NvmlError),
+    /// Polling the device panicked. Contains the panic message, if it had one.
+    #[error("polling the device panicked: {0}")]
+    Panicked(String),
+}
+
+/// The results of a single cycle, handed to the hook passed to
+/// `MonitorLoop.run()`.
+#[derive(Debug)]
+pub struct Cycle {
+    /// The number of cycles run before this one.
+    pub number: u64,
+    /// When the cycle started, in microseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// How long polling every device took.
+    pub duration: Duration,
+    /// One entry per device, ordered by index.
+    pub devices: Vec<DeviceCycle>,
+}
+
+/// The result of polling a single device during a cycle.
+#[derive(Debug)]
+pub struct DeviceCycle {
+    pub index: u32,
+    pub outcome: Result<DeviceSnapshot, MonitorError>,
+}
+
+impl MonitorLoop {
+    /// Create a loop that runs the given query every `DEFAULT_INTERVAL`.
+    pub fn new(query: DeviceQuery) -> Self {
+        Self {
+            query,
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+
+    /// Set the interval between the starts of consecutive cycles.
+    ///
+    /// A cycle that takes longer than the interval is followed by the next one
+    /// immediately; missed cycles are not caught up on.
+    pub fn interval(&mut self, interval: Duration) -> &mut Self {
+        self.interval = interval;
+        self
+    }
+
+    /**
+    Poll every device once per interval and pass each `Cycle` to `on_cycle`,
+    until it returns `ControlFlow::Break`.
+
+    Devices are enumerated once up front.
+
+    # Errors
+
+    Errors from enumerating the devices are returned; see `Nvml.devices()`.
+    Errors from polling a device are reported in its `DeviceCycle`.
+    */
+    pub fn run<F>(&self, nvml: &Nvml, mut on_cycle: F) -> Result<(), NvmlError>
+    where
+        F: FnMut(&Cycle) -> ControlFlow<()>,
+    {
+        let devices = nvml.devices()?.collect::<Result<Vec<_>, _>>()?;
+        let mut next_cycle = Instant::now();
+
+        for number in 0.. {
+            let cycle = self.cycle(&devices, number);
+
+            if on_cycle(&cycle).is_break() {
+                break;
+            }
+
+            let now = Instant::now();
+            next_cycle = next_cycle.max(now) + self.interval;
+            thread::sleep(next_cycle - now);
+        }
+
+        Ok(())
+    }
+
+    fn cycle(&self, devices: &[Device], number: u64) -> Cycle {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+        let start = Instant::now();
+
+        let devices = devices
+            .iter()
+            .zip(0..)
+            .map(|(device, index)| DeviceCycle {
+                index,
+                outcome: isolate(|| self.query.snapshot(device)),
+            })
+            .collect();
+
+        Cycle {
+            number,
+            timestamp,
+            duration: start.elapsed(),
+            devices,
+        }
+    }
+}
+
+impl Cycle {
+    /// The devices that could not be polled this cycle.
+    pub fn failures(&self) -> impl Iterator<Item = &DeviceCycle> {
+        self.devices.iter().filter(|d| d.outcome.is_err())
+    }
+
+    /// Whether every device was polled successfully.
+    pub fn is_healthy(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+// Runs `f`, turning a panic into an error.
+fn isolate<T, F>(f: F) -> Result<T, MonitorError>
+where
+    F: FnOnce() -> Result<T, NvmlError>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => Ok(result?),
+        Err(payload) => Err(MonitorError::Panicked(panic_message(payload))),
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn isolates_panics() {
+        let result: Result<u32, _> = isolate(|| panic!("bad device {}", 3));
+        match result {
+            Err(MonitorError::Panicked(message)) => assert_eq!(message, "bad device 3"),
+            other => panic!("expected `Panicked`, got {:?}", other),
+        }
+
+        let result: Result<u32, _> = isolate(|| Err(NvmlError::GpuLost));
+        assert!(matches!(
+            result,
+            Err(MonitorError::Nvml(NvmlError::GpuLost))
+        ));
+
+        assert_eq!(isolate(|| Ok(5)).unwrap(), 5);
+    }
+
+    #[test]
+    fn failures() {
+        let cycle = Cycle {
+            number: 0,
+            timestamp: 0,
+            duration: Duration::default(),
+            devices: vec![DeviceCycle {
+                index: 1,
+                outcome: Err(MonitorError::Nvml(NvmlError::GpuLost)),
+            }],
+        };
+
+        assert!(!cycle.is_healthy());
+        assert_eq!(cycle.failures().map(|d| d.index).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn run() {
+        let nvml = nvml();
+        let mut monitor = MonitorLoop::new(DeviceQuery::builder().all().build());
+        monitor.interval(Duration::from_millis(10));
+
+        test(3, || {
+            let mut cycles = 0;
+
+            monitor.run(&nvml, |cycle| {
+                cycles = cycle.number + 1;
+
+                match cycles {
+                    3 => ControlFlow::Break(()),
+                    _ => ControlFlow::Continue(()),
+                }
+            })?;
+
+            Ok(cycles)
+        })
+    }
+}
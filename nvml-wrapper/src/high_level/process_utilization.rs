@@ -0,0 +1,293 @@
+/*!
+Average per-process utilization over a window of time.
+
+`Device.process_utilization_stats()` returns the raw samples the driver has
+buffered, and leaves the bookkeeping needed to turn them into something useful
+to the caller: the `last_seen_timestamp` to pass next, the `NotFound` error
+returned when there are no new samples, and the samples some drivers return
+again despite being older than `last_seen_timestamp`. A
+`ProcessUtilizationTracker` does this once and averages what it has seen per
+process:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use std::time::Duration;
+
+let nvml = Nvml::init()?;
+let device = nvml.device_by_index(0)?;
+
+let window = Duration::from_secs(10);
+for process in device.average_process_utilization(window, Duration::from_secs(1))? {
+    println!(
+        "{}: {:.1}% SM, {:.1}% memory over {} samples",
+        process.pid, process.sm_util, process.mem_util, process.samples
+    );
+}
+# Ok(())
+# }
+```
+
+NVML only reports a sample for a process if it had non-zero utilization at
+the time, so the averages are over the samples a process appears in, not over
+the whole window. Use `samples` and the timestamps to judge how active a
+process was.
+
+The driver only buffers a limited number of samples; poll at least once every
+second or so to avoid missing any.
+*/
+
+use crate::error::NvmlError;
+use crate::struct_wrappers::device::ProcessUtilizationSample;
+use crate::Device;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The average utilization of a single process, returned from
+/// `ProcessUtilizationTracker.averages()`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProcessUtilization {
+    pub pid: u32,
+    /// The number of samples the averages are taken over.
+    pub samples: u32,
+    /// Average SM (3D / compute) utilization in percent
+    pub sm_util: f64,
+    /// Average frame buffer memory utilization in percent
+    pub mem_util: f64,
+    /// Average encoder utilization in percent
+    pub enc_util: f64,
+    /// Average decoder utilization in percent
+    pub dec_util: f64,
+    /// CPU timestamp of the first sample in μs
+    pub first_timestamp: u64,
+    /// CPU timestamp of the last sample in μs
+    pub last_timestamp: u64,
+}
+
+/// Accumulates process utilization samples across polls. See the
+/// module-level docs.
+#[derive(Debug, Clone)]
+pub struct ProcessUtilizationTracker {
+    last_seen_timestamp: u64,
+    totals: BTreeMap<u32, Totals>,
+    // Reused between polls
+    buffer: Vec<ProcessUtilizationSample>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Totals {
+    samples: u32,
+    sm_util: u64,
+    mem_util: u64,
+    enc_util: u64,
+    dec_util: u64,
+    first_timestamp: u64,
+    last_timestamp: u64,
+}
+
+impl ProcessUtilizationTracker {
+    /// Create a tracker that ignores samples taken before now.
+    pub fn new() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_micros() as u64);
+
+        Self::since(now)
+    }
+
+    /// Create a tracker that ignores samples taken at or before the given CPU
+    /// timestamp in μs.
+    ///
+    /// Passing `0` includes every sample the driver still has buffered.
+    pub fn since(timestamp: u64) -> Self {
+        Self {
+            last_seen_timestamp: timestamp,
+            totals: BTreeMap::new(),
+            buffer: vec![],
+        }
+    }
+
+    /**
+    Fetch the samples taken on the given device since the previous poll and
+    add them to the totals.
+
+    Returns the number of new samples. No new samples is not an error.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the device is invalid
+    * `NotSupported`, if the device does not support this feature
+    * `GpuLost`, if the device has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    pub fn poll(&mut self, device: &Device) -> Result<usize, NvmlError> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+
+        let result =
+            match device.process_utilization_stats_into(self.last_seen_timestamp, &mut buffer) {
+                Ok(()) => Ok(self.record(&buffer)),
+                // No samples newer than `last_seen_timestamp`
+                Err(NvmlError::NotFound) => Ok(0),
+                Err(e) => Err(e),
+            };
+
+        self.buffer = buffer;
+        result
+    }
+
+    /// Add the given samples to the totals, skipping any that aren't newer
+    /// than the ones seen so far.
+    ///
+    /// Returns the number of samples added. `.poll()` calls this for you.
+    pub fn record(&mut self, samples: &[ProcessUtilizationSample]) -> usize {
+        // Every process sampled at once shares a timestamp, so compare against
+        // the newest timestamp seen before this batch
+        let last_seen = self.last_seen_timestamp;
+        let mut added = 0;
+
+        for sample in samples.iter().filter(|s| s.timestamp > last_seen) {
+            let totals = self.totals.entry(sample.pid).or_insert_with(|| Totals {
+                first_timestamp: sample.timestamp,
+                ..Totals::default()
+            });
+
+            totals.samples += 1;
+            totals.sm_util += sample.sm_util as u64;
+            totals.mem_util += sample.mem_util as u64;
+            totals.enc_util += sample.enc_util as u64;
+            totals.dec_util += sample.dec_util as u64;
+            totals.first_timestamp = totals.first_timestamp.min(sample.timestamp);
+            totals.last_timestamp = totals.last_timestamp.max(sample.timestamp);
+
+            self.last_seen_timestamp = self.last_seen_timestamp.max(sample.timestamp);
+            added += 1;
+        }
+
+        added
+    }
+
+    /// The timestamp to pass to `Device.process_utilization_stats()` to get
+    /// samples that haven't been recorded yet.
+    pub fn last_seen_timestamp(&self) -> u64 {
+        self.last_seen_timestamp
+    }
+
+    /// The average utilization of every process seen so far, ordered by PID.
+    pub fn averages(&self) -> Vec<ProcessUtilization> {
+        self.totals
+            .iter()
+            .map(|(&pid, totals)| {
+                let average = |total: u64| total as f64 / totals.samples as f64;
+
+                ProcessUtilization {
+                    pid,
+                    samples: totals.samples,
+                    sm_util: average(totals.sm_util),
+                    mem_util: average(totals.mem_util),
+                    enc_util: average(totals.enc_util),
+                    dec_util: average(totals.dec_util),
+                    first_timestamp: totals.first_timestamp,
+                    last_timestamp: totals.last_timestamp,
+                }
+            })
+            .collect()
+    }
+
+    /// Forget the totals, keeping track of which samples have been seen.
+    pub fn reset(&mut self) {
+        self.totals.clear();
+    }
+}
+
+impl Default for ProcessUtilizationTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Backs `Device.average_process_utilization()`.
+pub(crate) fn average_over(
+    device: &Device,
+    window: Duration,
+    interval: Duration,
+) -> Result<Vec<ProcessUtilization>, NvmlError> {
+    let mut tracker = ProcessUtilizationTracker::new();
+    let start = Instant::now();
+
+    loop {
+        let remaining = window.saturating_sub(start.elapsed());
+        thread::sleep(remaining.min(interval));
+        tracker.poll(device)?;
+
+        if remaining <= interval {
+            return Ok(tracker.averages());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn sample(pid: u32, timestamp: u64, sm_util: u32) -> ProcessUtilizationSample {
+        ProcessUtilizationSample {
+            pid,
+            timestamp,
+            sm_util,
+            mem_util: sm_util / 2,
+            enc_util: 0,
+            dec_util: 0,
+        }
+    }
+
+    #[test]
+    fn averages_new_samples() {
+        let mut tracker = ProcessUtilizationTracker::since(100);
+
+        // The first one is from before the tracker was created
+        let added = tracker.record(&[sample(1, 50, 90), sample(1, 200, 40), sample(2, 200, 10)]);
+        assert_eq!(added, 2);
+        assert_eq!(tracker.last_seen_timestamp(), 200);
+
+        // Repeats of samples that were already recorded are skipped
+        let added = tracker.record(&[sample(1, 200, 40), sample(1, 300, 60)]);
+        assert_eq!(added, 1);
+
+        let averages = tracker.averages();
+        assert_eq!(averages.len(), 2);
+
+        assert_eq!(averages[0].pid, 1);
+        assert_eq!(averages[0].samples, 2);
+        assert_eq!(averages[0].sm_util, 50.0);
+        assert_eq!(averages[0].mem_util, 25.0);
+        assert_eq!(averages[0].first_timestamp, 200);
+        assert_eq!(averages[0].last_timestamp, 300);
+
+        assert_eq!(averages[1].pid, 2);
+        assert_eq!(averages[1].samples, 1);
+        assert_eq!(averages[1].sm_util, 10.0);
+
+        tracker.reset();
+        assert!(tracker.averages().is_empty());
+        assert_eq!(tracker.record(&[sample(1, 300, 60)]), 0);
+    }
+
+    #[test]
+    fn poll() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            let mut tracker = ProcessUtilizationTracker::since(0);
+            tracker.poll(device)?;
+            Ok(tracker.averages())
+        })
+    }
+}
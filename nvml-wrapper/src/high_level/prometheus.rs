@@ -0,0 +1,1020 @@
+/*!
+Render device metrics in the Prometheus text exposition format.
+
+Maps the results of a `DeviceQuery` to gauge and counter families with the
+base units and naming conventions Prometheus recommends, labeled with the
+device each value belongs to:
+
+```
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+# let nvml = Nvml::init()?;
+use nvml_wrapper::high_level::prometheus::{self, ExporterConfig};
+use nvml_wrapper::high_level::DeviceQuery;
+
+let query = DeviceQuery::builder().all().build();
+let snapshots = query.run_all(&nvml)?;
+
+// e.g. write to a node_exporter textfile collector directory, or serve it
+let text = prometheus::render(&ExporterConfig::default(), &snapshots);
+# Ok(())
+# }
+```
+
+Only the metrics a query selects are rendered; a family is left out entirely
+if no device reports it. Fan speeds are labeled with the `fan` index and
+per-process memory with the `pid`, on top of the device labels.
+
+An `ExporterConfig` controls the metric name prefix, which labels identify a
+device and which metrics are exported. It can also switch to the metric names,
+units and labels used by NVIDIA's [DCGM exporter][dcgm-exporter], so that
+existing dashboards and alerts keep working:
+
+```
+use nvml_wrapper::high_level::prometheus::{ExportedMetric, ExporterConfig, Label};
+
+let mut config = ExporterConfig::default();
+config
+    .prefix("gpu_")
+    .labels(&[Label::Uuid, Label::PciBusId])
+    .disable(ExportedMetric::MemoryFree);
+```
+
+# Critical events
+
+Alerts on GPU metrics are easier to act on when they point at what happened.
+Record critical Xid errors and thermal violations in a `CriticalEvents` as they
+arrive (from an `EventLoop` and `TemperatureAlerts`, say) and render with
+`render_with_events()` to add `xid_errors_total` and `thermal_violations_total`
+counters for every device:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+# let nvml = Nvml::init()?;
+# let device = nvml.device_by_index(0)?;
+# let snapshots = vec![];
+use nvml_wrapper::enums::event::XidError;
+use nvml_wrapper::high_level::prometheus::{self, CriticalEvent, CriticalEvents, ExporterConfig};
+
+let mut events = CriticalEvents::new();
+events.record(&device.uuid()?, CriticalEvent::Xid(XidError::Value(79)));
+
+let mut config = ExporterConfig::default();
+config.openmetrics(true);
+
+let text = prometheus::render_with_events(&config, &snapshots, &events);
+# Ok(())
+# }
+```
+
+With `ExporterConfig.openmetrics()` enabled, output is in the [OpenMetrics]
+format and each of those counters carries the most recent event for its device
+as an exemplar (e.g. `# {xid="79"} 1 1700000000.000`), which Grafana shows
+alongside the series. The Prometheus text format has no exemplars, so they are
+left out otherwise.
+
+To serve the rendered text over HTTP, see the `http_exporter` module.
+
+This module is only available with the `prometheus` feature enabled.
+
+[dcgm-exporter]: https://github.com/NVIDIA/dcgm-exporter
+[OpenMetrics]: https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md
+*/
+
+use crate::enum_wrappers::device::TemperatureThreshold;
+use crate::enums::device::UsedGpuMemory;
+use crate::enums::event::XidError;
+use crate::high_level::query::{DeviceSnapshot, QueryResult};
+use crate::units::Celsius;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Content type of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Content type of the OpenMetrics text format, used with
+/// `ExporterConfig.openmetrics()` enabled.
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// A metric that can be exported, for use with `ExporterConfig.disable()`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ExportedMetric {
+    GpuUtilization,
+    MemoryUtilization,
+    MemoryUsed,
+    MemoryFree,
+    MemoryTotal,
+    GpuTemperature,
+    MemoryTemperature,
+    PowerUsage,
+    EnforcedPowerLimit,
+    EnergyConsumption,
+    FanSpeed,
+    EccErrorsCorrected,
+    EccErrorsUncorrected,
+    ProcessMemoryUsed,
+    /// Only rendered by `render_with_events()`.
+    XidErrors,
+    /// Only rendered by `render_with_events()`.
+    ThermalViolations,
+}
+
+/// A label identifying the device a metric belongs to.
+///
+/// Only physical GPUs are exported, so there is no label for MIG slices.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Label {
+    /// The device's NVML index, as `gpu`.
+    Index,
+    /// The device's UUID, as `uuid` (`UUID` in DCGM compatibility mode).
+    Uuid,
+    /// The device's name, as `name` (`modelName` in DCGM compatibility mode).
+    Name,
+    /// The device's PCI bus ID, as `pci_bus_id`.
+    PciBusId,
+}
+
+/**
+Configures the metrics rendered by `render()`.
+
+The default configuration exports every metric, prefixed with `nvml_` and
+labeled with the `gpu` index, `uuid` and `name` of its device.
+*/
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExporterConfig {
+    prefix: String,
+    labels: Vec<Label>,
+    disabled: Vec<ExportedMetric>,
+    dcgm_compatible: bool,
+    openmetrics: bool,
+}
+
+impl Default for ExporterConfig {
+    fn default() -> Self {
+        Self {
+            prefix: "nvml_".into(),
+            labels: vec![Label::Index, Label::Uuid, Label::Name],
+            disabled: vec![],
+            dcgm_compatible: false,
+            openmetrics: false,
+        }
+    }
+}
+
+impl ExporterConfig {
+    /// Set the prefix of every metric name. Ignored in DCGM compatibility mode.
+    pub fn prefix(&mut self, prefix: &str) -> &mut Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Set the labels attached to every metric, in the given order.
+    pub fn labels(&mut self, labels: &[Label]) -> &mut Self {
+        self.labels = labels.to_vec();
+        self
+    }
+
+    /// Leave the given metric out.
+    pub fn disable(&mut self, metric: ExportedMetric) -> &mut Self {
+        if !self.disabled.contains(&metric) {
+            self.disabled.push(metric);
+        }
+        self
+    }
+
+    /// Export the given metric again after a call to `.disable()`.
+    pub fn enable(&mut self, metric: ExportedMetric) -> &mut Self {
+        self.disabled.retain(|m| *m != metric);
+        self
+    }
+
+    /**
+    Use the metric names, units and label names of NVIDIA's DCGM exporter, e.g.
+    `DCGM_FI_DEV_GPU_UTIL` in percent instead of `nvml_gpu_utilization_ratio`.
+
+    Metrics the DCGM exporter has no equivalent for (fan speeds and
+    per-process memory) are left out in this mode.
+
+    Disabled by default.
+    */
+    pub fn dcgm_compatible(&mut self, enabled: bool) -> &mut Self {
+        self.dcgm_compatible = enabled;
+        self
+    }
+
+    /**
+    Render in the OpenMetrics text format instead of the Prometheus one, which
+    allows critical events to be attached to metrics as exemplars (see the
+    module-level docs).
+
+    Counter families are named without their `_total` suffix in this mode, as
+    OpenMetrics requires, and the samples of DCGM counters get one.
+
+    Disabled by default.
+    */
+    pub fn openmetrics(&mut self, enabled: bool) -> &mut Self {
+        self.openmetrics = enabled;
+        self
+    }
+
+    /// The content type of the text rendered with this configuration.
+    pub fn content_type(&self) -> &'static str {
+        if self.openmetrics {
+            OPENMETRICS_CONTENT_TYPE
+        } else {
+            CONTENT_TYPE
+        }
+    }
+
+    // The name of a family and of its samples.
+    fn names(&self, name: &str, kind: &str) -> (String, String) {
+        if self.openmetrics && kind == "counter" {
+            let family = name.trim_end_matches("_total");
+            (family.to_string(), format!("{}_total", family))
+        } else {
+            (name.to_string(), name.to_string())
+        }
+    }
+
+    fn label_name(&self, label: Label) -> &'static str {
+        match (label, self.dcgm_compatible) {
+            (Label::Index, _) => "gpu",
+            (Label::Uuid, false) => "uuid",
+            (Label::Uuid, true) => "UUID",
+            (Label::Name, false) => "name",
+            (Label::Name, true) => "modelName",
+            (Label::PciBusId, _) => "pci_bus_id",
+        }
+    }
+}
+
+/// A critical event to attach to the exported metrics. See the module-level
+/// docs.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum CriticalEvent {
+    /// A critical Xid error, e.g. from `Event::CriticalXidError`.
+    Xid(XidError),
+    /// The GPU reached a temperature threshold, e.g. from `Alert::Raised`.
+    ThermalViolation {
+        threshold: TemperatureThreshold,
+        temperature: Celsius,
+    },
+}
+
+/// Counts the critical events of each device and remembers the most recent
+/// ones, for `render_with_events()`.
+#[derive(Debug, Clone, Default)]
+pub struct CriticalEvents {
+    // Keyed by UUID
+    devices: HashMap<String, DeviceEvents>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct DeviceEvents {
+    xid_errors: u64,
+    thermal_violations: u64,
+    last_xid: Option<(CriticalEvent, SystemTime)>,
+    last_thermal: Option<(CriticalEvent, SystemTime)>,
+}
+
+impl CriticalEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an event that just happened on the device with the given UUID.
+    pub fn record(&mut self, uuid: &str, event: CriticalEvent) {
+        self.record_at(uuid, event, SystemTime::now())
+    }
+
+    /// Record an event that happened at `time` on the device with the given
+    /// UUID.
+    pub fn record_at(&mut self, uuid: &str, event: CriticalEvent, time: SystemTime) {
+        let device = self.devices.entry(uuid.to_string()).or_default();
+
+        let (count, last) = match event {
+            CriticalEvent::Xid(_) => (&mut device.xid_errors, &mut device.last_xid),
+            CriticalEvent::ThermalViolation { .. } => {
+                (&mut device.thermal_violations, &mut device.last_thermal)
+            }
+        };
+
+        *count += 1;
+
+        // Events can be recorded out of order
+        if last.as_ref().map_or(true, |&(_, last)| last <= time) {
+            *last = Some((event, time));
+        }
+    }
+
+    /// Forget every recorded event.
+    pub fn clear(&mut self) {
+        self.devices.clear();
+    }
+}
+
+/**
+Render the given snapshots in the Prometheus text exposition format.
+
+Metrics are named and labeled as described by `config`. Metrics that weren't
+queried or aren't reported by a device are left out.
+*/
+pub fn render(config: &ExporterConfig, snapshots: &[DeviceSnapshot]) -> String {
+    render_inner(config, snapshots, None)
+}
+
+/**
+Render the given snapshots like `render()`, adding counters of the critical
+events recorded for each device.
+
+With `ExporterConfig.openmetrics()` enabled, the most recent event of each
+device is attached to its counter as an exemplar. In DCGM compatibility mode,
+the value of the last Xid error is exported as `DCGM_FI_DEV_XID_ERRORS`
+instead, and thermal violations are left out.
+*/
+pub fn render_with_events(
+    config: &ExporterConfig,
+    snapshots: &[DeviceSnapshot],
+    events: &CriticalEvents,
+) -> String {
+    render_inner(config, snapshots, Some(events))
+}
+
+fn render_inner(
+    config: &ExporterConfig,
+    snapshots: &[DeviceSnapshot],
+    events: Option<&CriticalEvents>,
+) -> String {
+    let mut out = String::new();
+
+    for metric in METRICS
+        .iter()
+        .filter(|m| !config.disabled.contains(&m.metric))
+    {
+        let (name, help, value) = if config.dcgm_compatible {
+            match metric.dcgm {
+                Some(ref dcgm) => (dcgm.name.to_string(), dcgm.help, Value::Single(dcgm.value)),
+                None => continue,
+            }
+        } else {
+            let name = format!("{}{}", config.prefix, metric.name);
+            (name, metric.help, metric.value)
+        };
+
+        let samples: Vec<_> = snapshots
+            .iter()
+            .flat_map(|s| value.samples(&s.metrics).into_iter().map(move |v| (s, v)))
+            .collect();
+
+        if samples.is_empty() {
+            continue;
+        }
+
+        let (family, name) = config.names(&name, metric.kind);
+
+        // Writing to a `String` can't fail
+        let _ = writeln!(out, "# HELP {} {}", family, help);
+        let _ = writeln!(out, "# TYPE {} {}", family, metric.kind);
+
+        for (snapshot, (item, sample)) in samples {
+            let mut labels = render_labels(config, snapshot);
+
+            if let (Value::PerItem { label, .. }, Some(item)) = (value, item) {
+                labels.push(format!("{}=\"{}\"", label, item));
+            }
+
+            let _ = writeln!(out, "{}{{{}}} {}", name, labels.join(","), sample);
+        }
+    }
+
+    if let Some(events) = events {
+        render_events(config, snapshots, events, &mut out);
+    }
+
+    if config.openmetrics {
+        out.push_str("# EOF\n");
+    }
+
+    out
+}
+
+// Every device in `snapshots` gets a sample, so that alerts on `increase()`
+// see the first event.
+fn render_events(
+    config: &ExporterConfig,
+    snapshots: &[DeviceSnapshot],
+    events: &CriticalEvents,
+    out: &mut String,
+) {
+    let none = DeviceEvents::default();
+    let device = |s: &DeviceSnapshot| events.devices.get(&s.uuid).unwrap_or(&none);
+
+    if config.dcgm_compatible {
+        if config.disabled.contains(&ExportedMetric::XidErrors) || snapshots.is_empty() {
+            return;
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP DCGM_FI_DEV_XID_ERRORS Value of the last XID error encountered."
+        );
+        let _ = writeln!(out, "# TYPE DCGM_FI_DEV_XID_ERRORS gauge");
+
+        for snapshot in snapshots {
+            let xid = match device(snapshot).last_xid {
+                Some((CriticalEvent::Xid(XidError::Value(xid)), _)) => xid,
+                _ => 0,
+            };
+
+            let labels = render_labels(config, snapshot).join(",");
+            let _ = writeln!(out, "DCGM_FI_DEV_XID_ERRORS{{{}}} {}", labels, xid);
+        }
+
+        return;
+    }
+
+    for family in EVENT_FAMILIES {
+        if config.disabled.contains(&family.metric) || snapshots.is_empty() {
+            continue;
+        }
+
+        let (name, sample_name) =
+            config.names(&format!("{}{}", config.prefix, family.name), "counter");
+        let _ = writeln!(out, "# HELP {} {}", name, family.help);
+        let _ = writeln!(out, "# TYPE {} counter", name);
+
+        for snapshot in snapshots {
+            let device = device(snapshot);
+            let count = (family.count)(device);
+            let last = (family.last)(device);
+            let labels = render_labels(config, snapshot).join(",");
+            let _ = write!(out, "{}{{{}}} {}", sample_name, labels, count);
+
+            if let (true, Some((event, time))) = (config.openmetrics, last) {
+                let _ = write!(
+                    out,
+                    " # {{{}}} 1 {}",
+                    exemplar_labels(event),
+                    timestamp(*time)
+                );
+            }
+
+            out.push('\n');
+        }
+    }
+}
+
+struct EventFamily {
+    metric: ExportedMetric,
+    name: &'static str,
+    help: &'static str,
+    count: fn(&DeviceEvents) -> u64,
+    last: fn(&DeviceEvents) -> Option<&(CriticalEvent, SystemTime)>,
+}
+
+const EVENT_FAMILIES: &[EventFamily] = &[
+    EventFamily {
+        metric: ExportedMetric::XidErrors,
+        name: "xid_errors_total",
+        help: "Critical Xid errors recorded.",
+        count: |d| d.xid_errors,
+        last: |d| d.last_xid.as_ref(),
+    },
+    EventFamily {
+        metric: ExportedMetric::ThermalViolations,
+        name: "thermal_violations_total",
+        help: "Temperature thresholds reached.",
+        count: |d| d.thermal_violations,
+        last: |d| d.last_thermal.as_ref(),
+    },
+];
+
+fn exemplar_labels(event: &CriticalEvent) -> String {
+    match event {
+        CriticalEvent::Xid(XidError::Value(xid)) => format!("xid=\"{}\"", xid),
+        CriticalEvent::Xid(XidError::Unknown) => "xid=\"unknown\"".into(),
+        CriticalEvent::ThermalViolation {
+            threshold,
+            temperature,
+        } => format!(
+            "threshold=\"{}\",temperature_celsius=\"{}\"",
+            threshold, temperature.0
+        ),
+    }
+}
+
+// Seconds since the epoch, with millisecond precision.
+fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!(
+        "{}.{:03}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_millis()
+    )
+}
+
+fn render_labels(config: &ExporterConfig, snapshot: &DeviceSnapshot) -> Vec<String> {
+    config
+        .labels
+        .iter()
+        .map(|&label| {
+            let value = match label {
+                Label::Index => snapshot.index.to_string(),
+                Label::Uuid => escape_label(&snapshot.uuid),
+                Label::Name => escape_label(&snapshot.name),
+                Label::PciBusId => escape_label(&snapshot.pci_bus_id),
+            };
+
+            format!("{}=\"{}\"", config.label_name(label), value)
+        })
+        .collect()
+}
+
+// Escapes a value for use inside a quoted Prometheus label value.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+struct Metric {
+    metric: ExportedMetric,
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    value: Value,
+    dcgm: Option<DcgmMetric>,
+}
+
+// How the values of a metric are extracted from a device's results.
+#[derive(Clone, Copy)]
+enum Value {
+    // At most one value per device.
+    Single(fn(&QueryResult) -> Option<f64>),
+    // Any number of values per device, told apart by an extra label.
+    PerItem {
+        label: &'static str,
+        values: fn(&QueryResult) -> Vec<(u32, f64)>,
+    },
+}
+
+impl Value {
+    fn samples(&self, result: &QueryResult) -> Vec<(Option<u32>, f64)> {
+        match *self {
+            Value::Single(value) => value(result).map(|v| (None, v)).into_iter().collect(),
+            Value::PerItem { values, .. } => values(result)
+                .into_iter()
+                .map(|(item, v)| (Some(item), v))
+                .collect(),
+        }
+    }
+}
+
+// The DCGM exporter's equivalent of a metric.
+struct DcgmMetric {
+    name: &'static str,
+    help: &'static str,
+    // In DCGM's units
+    value: fn(&QueryResult) -> Option<f64>,
+}
+
+const MIB: u64 = 1024 * 1024;
+
+// Values are converted to the base units Prometheus recommends.
+const METRICS: &[Metric] = &[
+    Metric {
+        metric: ExportedMetric::GpuUtilization,
+        name: "gpu_utilization_ratio",
+        help: "Fraction of the last sample period during which kernels were executing.",
+        kind: "gauge",
+        value: Value::Single(|r| r.utilization.as_ref().map(|u| u.gpu as f64 / 100.0)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_GPU_UTIL",
+            help: "GPU utilization (in %).",
+            value: |r| r.utilization.as_ref().map(|u| u.gpu as f64),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::MemoryUtilization,
+        name: "memory_utilization_ratio",
+        help: "Fraction of the last sample period during which memory was read or written.",
+        kind: "gauge",
+        value: Value::Single(|r| r.utilization.as_ref().map(|u| u.memory as f64 / 100.0)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_MEM_COPY_UTIL",
+            help: "Memory utilization (in %).",
+            value: |r| r.utilization.as_ref().map(|u| u.memory as f64),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::MemoryUsed,
+        name: "memory_used_bytes",
+        help: "Allocated FB memory.",
+        kind: "gauge",
+        value: Value::Single(|r| r.memory.as_ref().map(|m| m.used as f64)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_FB_USED",
+            help: "Framebuffer memory used (in MiB).",
+            value: |r| r.memory.as_ref().map(|m| (m.used / MIB) as f64),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::MemoryFree,
+        name: "memory_free_bytes",
+        help: "Unallocated FB memory.",
+        kind: "gauge",
+        value: Value::Single(|r| r.memory.as_ref().map(|m| m.free as f64)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_FB_FREE",
+            help: "Framebuffer memory free (in MiB).",
+            value: |r| r.memory.as_ref().map(|m| (m.free / MIB) as f64),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::MemoryTotal,
+        name: "memory_total_bytes",
+        help: "Total installed FB memory.",
+        kind: "gauge",
+        value: Value::Single(|r| r.memory.as_ref().map(|m| m.total as f64)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_FB_TOTAL",
+            help: "Total framebuffer memory (in MiB).",
+            value: |r| r.memory.as_ref().map(|m| (m.total / MIB) as f64),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::GpuTemperature,
+        name: "gpu_temperature_celsius",
+        help: "GPU die temperature.",
+        kind: "gauge",
+        value: Value::Single(|r| r.temperature.as_ref().map(|t| t.gpu.into())),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_GPU_TEMP",
+            help: "GPU temperature (in C).",
+            value: |r| r.temperature.as_ref().map(|t| t.gpu.into()),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::MemoryTemperature,
+        name: "memory_temperature_celsius",
+        help: "Memory temperature.",
+        kind: "gauge",
+        value: Value::Single(|r| r.temperature.as_ref()?.memory.map(f64::from)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_MEMORY_TEMP",
+            help: "Memory temperature (in C).",
+            value: |r| r.temperature.as_ref()?.memory.map(f64::from),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::PowerUsage,
+        name: "power_usage_watts",
+        help: "Power usage of the GPU and its associated circuitry.",
+        kind: "gauge",
+        value: Value::Single(|r| r.power.as_ref().map(|p| p.usage.as_watts().0)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_POWER_USAGE",
+            help: "Power draw (in W).",
+            value: |r| r.power.as_ref().map(|p| p.usage.as_watts().0),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::EnforcedPowerLimit,
+        name: "enforced_power_limit_watts",
+        help: "Power limit enforced by the driver.",
+        kind: "gauge",
+        value: Value::Single(|r| r.power.as_ref().map(|p| p.enforced_limit.as_watts().0)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_ENFORCED_POWER_LIMIT",
+            help: "Effective power limit that the driver enforces (in W).",
+            value: |r| r.power.as_ref().map(|p| p.enforced_limit.as_watts().0),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::EnergyConsumption,
+        name: "energy_consumption_joules_total",
+        help: "Energy consumed since the driver was last reloaded.",
+        kind: "counter",
+        value: Value::Single(|r| {
+            r.power
+                .as_ref()?
+                .total_energy_consumption
+                .map(|e| e as f64 / 1000.0)
+        }),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_TOTAL_ENERGY_CONSUMPTION",
+            help: "Total energy consumption since boot (in mJ).",
+            value: |r| r.power.as_ref()?.total_energy_consumption.map(|e| e as f64),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::FanSpeed,
+        name: "fan_speed_ratio",
+        help: "Intended fan speed as a fraction of its maximum.",
+        kind: "gauge",
+        value: Value::PerItem {
+            label: "fan",
+            values: |r| {
+                r.fan_speeds
+                    .iter()
+                    .flatten()
+                    .zip(0..)
+                    .map(|(&speed, fan)| (fan, speed as f64 / 100.0))
+                    .collect()
+            },
+        },
+        dcgm: None,
+    },
+    Metric {
+        metric: ExportedMetric::EccErrorsCorrected,
+        name: "ecc_errors_corrected_total",
+        help: "Single bit ECC errors corrected since the driver was last reloaded.",
+        kind: "counter",
+        value: Value::Single(|r| r.ecc_errors.as_ref().map(|e| e.corrected as f64)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_ECC_SBE_VOL_TOTAL",
+            help: "Total number of single-bit volatile ECC errors.",
+            value: |r| r.ecc_errors.as_ref().map(|e| e.corrected as f64),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::EccErrorsUncorrected,
+        name: "ecc_errors_uncorrected_total",
+        help: "Double bit ECC errors detected since the driver was last reloaded.",
+        kind: "counter",
+        value: Value::Single(|r| r.ecc_errors.as_ref().map(|e| e.uncorrected as f64)),
+        dcgm: Some(DcgmMetric {
+            name: "DCGM_FI_DEV_ECC_DBE_VOL_TOTAL",
+            help: "Total number of double-bit volatile ECC errors.",
+            value: |r| r.ecc_errors.as_ref().map(|e| e.uncorrected as f64),
+        }),
+    },
+    Metric {
+        metric: ExportedMetric::ProcessMemoryUsed,
+        name: "process_memory_used_bytes",
+        help: "FB memory used by a process.",
+        kind: "gauge",
+        value: Value::PerItem {
+            label: "pid",
+            values: process_memory,
+        },
+        dcgm: None,
+    },
+];
+
+// A process can be both a compute and a graphics process, in which case it is
+// listed twice with the same usage.
+fn process_memory(result: &QueryResult) -> Vec<(u32, f64)> {
+    let mut used = BTreeMap::new();
+
+    for process in result
+        .compute_processes
+        .iter()
+        .chain(&result.graphics_processes)
+        .flatten()
+    {
+        if let UsedGpuMemory::Used(bytes) = process.used_gpu_memory {
+            used.entry(process.pid).or_insert(bytes as f64);
+        }
+    }
+
+    used.into_iter().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::high_level::query::{EccErrorCounts, PowerReadings, TemperatureReadings};
+    use crate::struct_wrappers::device::{MemoryInfo, ProcessInfo, Utilization};
+    use crate::units::{Celsius, Milliwatts};
+    use std::time::Duration;
+
+    fn process(pid: u32, used_gpu_memory: UsedGpuMemory) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            used_gpu_memory,
+            gpu_instance_id: None,
+            compute_instance_id: None,
+        }
+    }
+
+    fn snapshot() -> DeviceSnapshot {
+        DeviceSnapshot {
+            index: 0,
+            uuid: "GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5".into(),
+            name: "NVIDIA \"Test\" GPU".into(),
+            pci_bus_id: "00000000:01:00.0".into(),
+            metrics: QueryResult {
+                utilization: Some(Utilization { gpu: 25, memory: 5 }),
+                memory: Some(MemoryInfo {
+                    free: 1024 * MIB,
+                    total: 4096 * MIB,
+                    used: 3072 * MIB,
+                }),
+                temperature: Some(TemperatureReadings {
+                    gpu: Celsius(40),
+                    memory: None,
+                }),
+                power: Some(PowerReadings {
+                    usage: Milliwatts(150_500),
+                    enforced_limit: Milliwatts(300_000),
+                    total_energy_consumption: None,
+                }),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn prometheus() {
+        let text = render(&ExporterConfig::default(), &[snapshot()]);
+        let labels = r#"{gpu="0",uuid="GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5",name="NVIDIA \"Test\" GPU"}"#;
+
+        assert!(text.contains("# TYPE nvml_gpu_utilization_ratio gauge\n"));
+        assert!(text.contains(&format!("nvml_gpu_utilization_ratio{} 0.25\n", labels)));
+        assert!(text.contains(&format!("nvml_power_usage_watts{} 150.5\n", labels)));
+        assert!(text.contains(&format!("nvml_gpu_temperature_celsius{} 40\n", labels)));
+
+        // Not queried or not reported
+        assert!(!text.contains("nvml_memory_temperature_celsius"));
+        assert!(!text.contains("nvml_energy_consumption_joules_total"));
+        assert!(!text.contains("nvml_fan_speed_ratio"));
+    }
+
+    #[test]
+    fn prometheus_config() {
+        let mut config = ExporterConfig::default();
+        config
+            .prefix("gpu_")
+            .labels(&[Label::PciBusId, Label::Index])
+            .disable(ExportedMetric::MemoryFree)
+            .disable(ExportedMetric::PowerUsage)
+            .enable(ExportedMetric::PowerUsage);
+
+        let text = render(&config, &[snapshot()]);
+        let labels = r#"{pci_bus_id="00000000:01:00.0",gpu="0"}"#;
+
+        assert!(text.contains(&format!("gpu_gpu_utilization_ratio{} 0.25\n", labels)));
+        assert!(text.contains(&format!("gpu_power_usage_watts{} 150.5\n", labels)));
+        assert!(!text.contains("memory_free_bytes"));
+        assert!(!text.contains("nvml_"));
+    }
+
+    #[test]
+    fn prometheus_dcgm_compatible() {
+        let mut snapshot = snapshot();
+        snapshot.metrics.fan_speeds = Some(vec![40]);
+
+        let mut config = ExporterConfig::default();
+        config.prefix("ignored_").dcgm_compatible(true);
+
+        let text = render(&config, &[snapshot]);
+        let labels = r#"{gpu="0",UUID="GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5",modelName="NVIDIA \"Test\" GPU"}"#;
+
+        assert!(text.contains("# TYPE DCGM_FI_DEV_GPU_UTIL gauge\n"));
+        assert!(text.contains(&format!("DCGM_FI_DEV_GPU_UTIL{} 25\n", labels)));
+        assert!(text.contains(&format!("DCGM_FI_DEV_FB_USED{} 3072\n", labels)));
+        assert!(text.contains(&format!("DCGM_FI_DEV_POWER_USAGE{} 150.5\n", labels)));
+        assert!(!text.contains("ignored_"));
+        assert!(!text.contains("fan"));
+    }
+
+    #[test]
+    fn per_item_metrics() {
+        let mut snapshot = snapshot();
+        snapshot.metrics.fan_speeds = Some(vec![40, 55]);
+        snapshot.metrics.ecc_errors = Some(EccErrorCounts {
+            corrected: 3,
+            uncorrected: 0,
+        });
+        snapshot.metrics.compute_processes = Some(vec![
+            process(200, UsedGpuMemory::Used(512 * MIB)),
+            process(100, UsedGpuMemory::Unavailable),
+        ]);
+        snapshot.metrics.graphics_processes =
+            Some(vec![process(200, UsedGpuMemory::Used(512 * MIB))]);
+
+        let mut config = ExporterConfig::default();
+        config.labels(&[Label::Index]);
+
+        let text = render(&config, &[snapshot]);
+
+        assert!(text.contains("# TYPE nvml_fan_speed_ratio gauge\n"));
+        assert!(text.contains("nvml_fan_speed_ratio{gpu=\"0\",fan=\"0\"} 0.4\n"));
+        assert!(text.contains("nvml_fan_speed_ratio{gpu=\"0\",fan=\"1\"} 0.55\n"));
+
+        assert!(text.contains("# TYPE nvml_ecc_errors_corrected_total counter\n"));
+        assert!(text.contains("nvml_ecc_errors_corrected_total{gpu=\"0\"} 3\n"));
+        assert!(text.contains("nvml_ecc_errors_uncorrected_total{gpu=\"0\"} 0\n"));
+
+        // Listed once despite being both a compute and a graphics process, and
+        // processes with unavailable usage are left out
+        assert_eq!(
+            text.matches("nvml_process_memory_used_bytes{").count(),
+            1,
+            "{}",
+            text
+        );
+        assert!(text.contains("nvml_process_memory_used_bytes{gpu=\"0\",pid=\"200\"} 536870912\n"));
+    }
+
+    fn events() -> CriticalEvents {
+        let uuid = snapshot().uuid;
+        let at = |secs| UNIX_EPOCH + Duration::from_millis(secs);
+
+        let mut events = CriticalEvents::new();
+        events.record_at(
+            &uuid,
+            CriticalEvent::Xid(XidError::Value(79)),
+            at(1_700_000_000_500),
+        );
+        events.record_at(
+            &uuid,
+            CriticalEvent::Xid(XidError::Value(48)),
+            at(1_600_000_000_000),
+        );
+        events.record_at(
+            &uuid,
+            CriticalEvent::ThermalViolation {
+                threshold: TemperatureThreshold::Slowdown,
+                temperature: Celsius(91),
+            },
+            at(1_700_000_001_000),
+        );
+        events.record(
+            "GPU-not-in-snapshots",
+            CriticalEvent::Xid(XidError::Unknown),
+        );
+        events
+    }
+
+    #[test]
+    fn critical_events() {
+        let mut other = snapshot();
+        other.index = 1;
+        other.uuid = "GPU-00000000-0000-0000-0000-000000000000".into();
+
+        let mut config = ExporterConfig::default();
+        config.labels(&[Label::Index]);
+
+        let text = render_with_events(&config, &[snapshot(), other], &events());
+
+        assert!(text.contains("# TYPE nvml_xid_errors_total counter\n"));
+        assert!(text.contains("nvml_xid_errors_total{gpu=\"0\"} 2\n"));
+        assert!(text.contains("nvml_xid_errors_total{gpu=\"1\"} 0\n"));
+        assert!(text.contains("nvml_thermal_violations_total{gpu=\"0\"} 1\n"));
+
+        // No exemplars in the Prometheus format
+        assert!(!text.contains(" # {"));
+        assert!(!text.contains("# EOF"));
+        assert_eq!(config.content_type(), CONTENT_TYPE);
+    }
+
+    #[test]
+    fn critical_events_openmetrics() {
+        let mut config = ExporterConfig::default();
+        config
+            .labels(&[Label::Index])
+            .openmetrics(true)
+            .disable(ExportedMetric::ThermalViolations);
+
+        let text = render_with_events(&config, &[snapshot()], &events());
+
+        // The most recent Xid, not the last one recorded
+        assert!(text.contains("# TYPE nvml_xid_errors counter\n"));
+        assert!(
+            text.contains("nvml_xid_errors_total{gpu=\"0\"} 2 # {xid=\"79\"} 1 1700000000.500\n")
+        );
+        assert!(!text.contains("thermal"));
+        assert!(text.ends_with("# EOF\n"));
+        assert_eq!(config.content_type(), OPENMETRICS_CONTENT_TYPE);
+
+        config.enable(ExportedMetric::ThermalViolations);
+        let text = render_with_events(&config, &[snapshot()], &events());
+
+        assert!(text.contains(concat!(
+            "nvml_thermal_violations_total{gpu=\"0\"} 1 ",
+            "# {threshold=\"Slowdown\",temperature_celsius=\"91\"} 1 1700000001.000\n"
+        )));
+    }
+
+    #[test]
+    fn critical_events_dcgm_compatible() {
+        let mut config = ExporterConfig::default();
+        config.labels(&[Label::Index]).dcgm_compatible(true);
+
+        let text = render_with_events(&config, &[snapshot()], &events());
+
+        assert!(text.contains("# TYPE DCGM_FI_DEV_XID_ERRORS gauge\n"));
+        assert!(text.contains("DCGM_FI_DEV_XID_ERRORS{gpu=\"0\"} 79\n"));
+        assert!(!text.contains("thermal"));
+    }
+}
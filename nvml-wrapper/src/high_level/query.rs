@@ -0,0 +1,473 @@
+/*!
+Fetch exactly the metrics you need from a `Device`.
+
+Build a `DeviceQuery` once, selecting the metric groups you care about, and
+then run it against as many `Device`s as you'd like, as often as you'd like:
+
+```
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+# let nvml = Nvml::init()?;
+# let device = nvml.device_by_index(0)?;
+use nvml_wrapper::high_level::DeviceQuery;
+
+let query = DeviceQuery::builder()
+    .utilization()
+    .memory()
+    .temperature()
+    .power()
+    .build();
+
+let result = query.run(&device)?;
+
+if let Some(memory) = result.memory {
+    println!("{} of {} bytes used", memory.used, memory.total);
+}
+# Ok(())
+# }
+```
+
+Running a query is not a single call into NVML. Each selected metric group is
+fetched through the `Device` methods named on its `DeviceQueryBuilder` method,
+with one call per value. Only the supplementary values that NVML exposes
+through a field ID (memory temperature, total energy consumption and ECC error
+counts) are gathered together, in one `nvmlDeviceGetFieldValues` call per run.
+*/
+
+use crate::bitmasks::device::ClocksEventReasons;
+use crate::enum_wrappers::device::{Clock, PerformanceState, TemperatureSensor};
+use crate::enums::device::SampleValue;
+use crate::error::{NvmlError, NvmlResultExt};
+use crate::ffi::bindings::field_id::*;
+use crate::struct_wrappers::device::{FieldValueSample, MemoryInfo, ProcessInfo, Utilization};
+use crate::structs::device::FieldId;
+use crate::units::{Celsius, Megahertz, Milliwatts};
+use crate::{Device, Nvml};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// A reusable description of which metrics to fetch from a `Device`.
+///
+/// Create one with `DeviceQuery::builder()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceQuery {
+    utilization: bool,
+    memory: bool,
+    temperature: bool,
+    power: bool,
+    clocks: bool,
+    fans: bool,
+    performance_state: bool,
+    clocks_event_reasons: bool,
+    ecc_errors: bool,
+    processes: bool,
+}
+
+impl DeviceQuery {
+    /// Create a `DeviceQueryBuilder` with no metrics selected.
+    pub fn builder() -> DeviceQueryBuilder {
+        DeviceQueryBuilder::default()
+    }
+
+    /// Whether or not this query does not select any metrics.
+    pub fn is_empty(&self) -> bool {
+        !(self.utilization
+            || self.memory
+            || self.temperature
+            || self.power
+            || self.clocks
+            || self.fans
+            || self.performance_state
+            || self.clocks_event_reasons
+            || self.ecc_errors
+            || self.processes)
+    }
+
+    /**
+    Run this query against the given `Device`.
+
+    Fields of the returned `QueryResult` that correspond to metric groups that
+    were not selected, or that this `Device` does not support, are `None`.
+
+    # Errors
+
+    The first error returned by a call for a selected metric group is returned,
+    except for `NotSupported` and `NoData`, which leave the group's field `None`
+    (see `NvmlResultExt.optional()`). See the docs for the individual `Device`
+    methods listed on each `DeviceQueryBuilder` method for details.
+
+    Values obtained through the field values call are considered
+    supplementary; failures there result in the corresponding field being
+    `None` rather than an error.
+    */
+    pub fn run(&self, device: &Device) -> Result<QueryResult, NvmlError> {
+        let mut result = QueryResult::default();
+        let mut batch = FieldBatch::default();
+
+        if self.temperature {
+            batch.push(NVML_FI_DEV_MEMORY_TEMP);
+        }
+
+        if self.power {
+            batch.push(NVML_FI_DEV_TOTAL_ENERGY_CONSUMPTION);
+        }
+
+        if self.ecc_errors {
+            batch.push(NVML_FI_DEV_ECC_SBE_VOL_TOTAL);
+            batch.push(NVML_FI_DEV_ECC_DBE_VOL_TOTAL);
+        }
+
+        let samples = batch.fetch(device);
+
+        if self.utilization {
+            result.utilization = device.utilization_rates().optional()?;
+        }
+
+        if self.memory {
+            result.memory = device.memory_info().optional()?;
+        }
+
+        if self.temperature {
+            let gpu = device.temperature(TemperatureSensor::Gpu).optional()?;
+
+            result.temperature = gpu.map(|gpu| TemperatureReadings {
+                gpu,
+                memory: find_value(&samples, NVML_FI_DEV_MEMORY_TEMP)
+                    .and_then(|v| u32::try_from(v).ok())
+                    .map(Celsius),
+            });
+        }
+
+        if self.power {
+            let usage = device.power_usage().optional()?;
+            let enforced_limit = device.enforced_power_limit().optional()?;
+
+            if let (Some(usage), Some(enforced_limit)) = (usage, enforced_limit) {
+                result.power = Some(PowerReadings {
+                    usage,
+                    enforced_limit,
+                    total_energy_consumption: find_value(
+                        &samples,
+                        NVML_FI_DEV_TOTAL_ENERGY_CONSUMPTION,
+                    ),
+                });
+            }
+        }
+
+        if self.clocks {
+            let graphics = device.clock_info(Clock::Graphics).optional()?;
+            let sm = device.clock_info(Clock::SM).optional()?;
+            let memory = device.clock_info(Clock::Memory).optional()?;
+
+            if let (Some(graphics), Some(sm), Some(memory)) = (graphics, sm, memory) {
+                result.clocks = Some(ClockReadings {
+                    graphics,
+                    sm,
+                    memory,
+                });
+            }
+        }
+
+        if self.fans {
+            result.fan_speeds = device.fan_speeds().optional()?;
+        }
+
+        if self.performance_state {
+            result.performance_state = device.performance_state().optional()?;
+        }
+
+        if self.clocks_event_reasons {
+            result.clocks_event_reasons = device.current_clocks_event_reasons().optional()?;
+        }
+
+        if self.ecc_errors {
+            let corrected = find_value(&samples, NVML_FI_DEV_ECC_SBE_VOL_TOTAL);
+            let uncorrected = find_value(&samples, NVML_FI_DEV_ECC_DBE_VOL_TOTAL);
+
+            if let (Some(corrected), Some(uncorrected)) = (corrected, uncorrected) {
+                result.ecc_errors = Some(EccErrorCounts {
+                    corrected,
+                    uncorrected,
+                });
+            }
+        }
+
+        if self.processes {
+            result.compute_processes = device.running_compute_processes().optional()?;
+            result.graphics_processes = device.running_graphics_processes().optional()?;
+        }
+
+        Ok(result)
+    }
+
+    // Runs this query against the given device, along with the calls needed
+    // to identify it.
+    pub(crate) fn snapshot(&self, device: &Device) -> Result<DeviceSnapshot, NvmlError> {
+        Ok(DeviceSnapshot {
+            index: device.index()?,
+            uuid: device.uuid()?,
+            name: device.name()?,
+            pci_bus_id: device.pci_info()?.bus_id,
+            metrics: self.run(device)?,
+        })
+    }
+
+    /**
+    Run this query against every device.
+
+    # Errors
+
+    The first error encountered is returned. See `Nvml.device_count()`,
+    `Nvml.device_by_index()`, `Device.uuid()`, `Device.name()` and
+    `.run()` for details.
+    */
+    pub fn run_all(&self, nvml: &Nvml) -> Result<Vec<DeviceSnapshot>, NvmlError> {
+        nvml.devices()?
+            .map(|device| self.snapshot(&device?))
+            .collect()
+    }
+}
+
+/**
+Builder for `DeviceQuery`.
+
+Each method selects a group of metrics to be fetched when the built
+`DeviceQuery` is run.
+*/
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct DeviceQueryBuilder {
+    query: DeviceQuery,
+}
+
+impl DeviceQueryBuilder {
+    /// Fetch GPU and memory utilization via `Device.utilization_rates()`.
+    pub fn utilization(&mut self) -> &mut Self {
+        self.query.utilization = true;
+        self
+    }
+
+    /// Fetch memory allocation info via `Device.memory_info()`.
+    pub fn memory(&mut self) -> &mut Self {
+        self.query.memory = true;
+        self
+    }
+
+    /// Fetch the GPU die temperature via `Device.temperature()` and, where
+    /// supported, the memory temperature.
+    pub fn temperature(&mut self) -> &mut Self {
+        self.query.temperature = true;
+        self
+    }
+
+    /// Fetch power usage and the enforced power limit via `Device.power_usage()`
+    /// and `Device.enforced_power_limit()` and, where supported, the total
+    /// energy consumption.
+    pub fn power(&mut self) -> &mut Self {
+        self.query.power = true;
+        self
+    }
+
+    /// Fetch the graphics, SM and memory clocks via `Device.clock_info()`.
+    pub fn clocks(&mut self) -> &mut Self {
+        self.query.clocks = true;
+        self
+    }
+
+    /// Fetch the speed of every fan via `Device.num_fans()` and
+    /// `Device.fan_speed()`.
+    pub fn fans(&mut self) -> &mut Self {
+        self.query.fans = true;
+        self
+    }
+
+    /// Fetch the performance state via `Device.performance_state()`.
+    pub fn performance_state(&mut self) -> &mut Self {
+        self.query.performance_state = true;
+        self
+    }
+
+    /// Fetch the reasons clocks are being held back (throttled) via
+    /// `Device.current_clocks_event_reasons()`.
+    pub fn clocks_event_reasons(&mut self) -> &mut Self {
+        self.query.clocks_event_reasons = true;
+        self
+    }
+
+    /// Fetch the volatile corrected and uncorrected ECC error counts, where
+    /// the device has ECC enabled.
+    pub fn ecc_errors(&mut self) -> &mut Self {
+        self.query.ecc_errors = true;
+        self
+    }
+
+    /// Fetch running processes via `Device.running_compute_processes()` and
+    /// `Device.running_graphics_processes()`.
+    pub fn processes(&mut self) -> &mut Self {
+        self.query.processes = true;
+        self
+    }
+
+    /// Select every metric group.
+    pub fn all(&mut self) -> &mut Self {
+        self.utilization()
+            .memory()
+            .temperature()
+            .power()
+            .clocks()
+            .fans()
+            .performance_state()
+            .clocks_event_reasons()
+            .ecc_errors()
+            .processes()
+    }
+
+    /// Build the `DeviceQuery`.
+    pub fn build(&self) -> DeviceQuery {
+        self.query.clone()
+    }
+}
+
+/// Returned from `DeviceQuery.run()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QueryResult {
+    pub utilization: Option<Utilization>,
+    pub memory: Option<MemoryInfo>,
+    pub temperature: Option<TemperatureReadings>,
+    pub power: Option<PowerReadings>,
+    pub clocks: Option<ClockReadings>,
+    /// The speed of every fan in percent, ordered by fan index.
+    pub fan_speeds: Option<Vec<u32>>,
+    pub performance_state: Option<PerformanceState>,
+    pub clocks_event_reasons: Option<ClocksEventReasons>,
+    pub ecc_errors: Option<EccErrorCounts>,
+    pub compute_processes: Option<Vec<ProcessInfo>>,
+    pub graphics_processes: Option<Vec<ProcessInfo>>,
+}
+
+/// Returned from `DeviceQuery.run_all()` and `Device.snapshot()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceSnapshot {
+    pub index: u32,
+    pub uuid: String,
+    pub name: String,
+    /// The PCI bus ID, as in `PciInfo.bus_id`.
+    pub pci_bus_id: String,
+    pub metrics: QueryResult,
+}
+
+/// Temperature readings.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TemperatureReadings {
+    /// The GPU die temperature.
+    pub gpu: Celsius,
+    /// The memory temperature, if this `Device` reports it.
+    pub memory: Option<Celsius>,
+}
+
+/// Power readings.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PowerReadings {
+    pub usage: Milliwatts,
+    /// The power limit enforced by the driver.
+    pub enforced_limit: Milliwatts,
+    /// Energy consumed since the last driver reload in millijoules, if this
+    /// `Device` reports it.
+    pub total_energy_consumption: Option<u64>,
+}
+
+/// Current clock speeds.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockReadings {
+    pub graphics: Megahertz,
+    pub sm: Megahertz,
+    pub memory: Megahertz,
+}
+
+/// ECC error counts since the last driver reload, summed over every memory
+/// location.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EccErrorCounts {
+    /// Single bit errors, which were corrected.
+    pub corrected: u64,
+    /// Double bit errors, which could not be corrected.
+    pub uncorrected: u64,
+}
+
+// Collects field IDs so they can be fetched in one call.
+#[derive(Debug, Default)]
+struct FieldBatch {
+    ids: Vec<FieldId>,
+}
+
+impl FieldBatch {
+    fn push(&mut self, id: u32) {
+        self.ids.push(FieldId(id));
+    }
+
+    fn fetch(&self, device: &Device) -> Vec<FieldValueSample> {
+        if self.ids.is_empty() {
+            return vec![];
+        }
+
+        match device.field_values_for(&self.ids) {
+            Ok(samples) => samples.into_iter().filter_map(Result::ok).collect(),
+            Err(_) => vec![],
+        }
+    }
+}
+
+// Finds the value for the given field, widened to a `u64`.
+fn find_value(samples: &[FieldValueSample], id: u32) -> Option<u64> {
+    let sample = samples.iter().find(|s| s.field.0 == id)?;
+
+    match sample.value {
+        Ok(SampleValue::U32(v)) => Some(v as u64),
+        Ok(SampleValue::U64(v)) => Some(v),
+        Ok(SampleValue::I64(v)) => u64::try_from(v).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn builder_selects_metrics() {
+        let query = DeviceQuery::builder().memory().power().build();
+
+        assert!(query.memory);
+        assert!(query.power);
+        assert!(!query.utilization);
+        assert!(!query.temperature);
+        assert!(!query.processes);
+        assert!(DeviceQuery::builder().build().is_empty());
+        assert!(!DeviceQuery::builder().fans().build().is_empty());
+    }
+
+    #[test]
+    fn run() {
+        let nvml = nvml();
+        let query = DeviceQuery::builder().all().build();
+
+        test_with_device(3, &nvml, |device| query.run(device))
+    }
+
+    #[test]
+    fn run_all() {
+        let nvml = nvml();
+        let query = DeviceQuery::builder().all().build();
+
+        test(3, || query.run_all(&nvml))
+    }
+}
@@ -0,0 +1,494 @@
+/*!
+Track devices by UUID across hot-plug, removal, and drain-state changes.
+
+Device indices are not stable: when a GPU is drained and removed (see
+`Device.set_drain()` and `Device.remove()`) or a new one is discovered (see
+`Nvml.discover_gpus()`), the remaining devices may be renumbered and any
+`Device` obtained earlier may refer to a different GPU or none at all.
+
+A `DeviceRegistry` keys devices by UUID instead. Call `.refresh()`
+periodically, or whenever something suggests that the set of devices has
+changed (e.g. a `GpuLost` error or an `XidCriticalError` event), to re-scan
+the system and receive a `RegistryEvent` for every change:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::{DeviceRegistry, RegistryEvent};
+
+let nvml = Nvml::init()?;
+let mut registry = DeviceRegistry::new(&nvml)?;
+
+for event in registry.refresh()? {
+    match event {
+        RegistryEvent::Added { uuid, .. } => {
+            // Freshly-obtained handle for the new device
+            let device = registry.device(&uuid).unwrap();
+            println!("added {}", device.name()?);
+        }
+        RegistryEvent::Removed { uuid } => println!("removed {}", uuid),
+        _ => {}
+    }
+}
+# Ok(())
+# }
+```
+
+NVML has no event for a device being attached or detached, so
+`DeviceRegistry.wait()` watches for the closest signals instead: on Linux it
+waits for critical Xid error events (a GPU falling off the bus is reported as
+Xid 79), and on every platform it checks whether the number of devices has
+changed (e.g. after `Nvml.discover_gpus()` or a hot-plug). It only re-scans
+when one of these suggests that something changed, which makes it cheap
+enough to run in a loop:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+# use nvml_wrapper::high_level::DeviceRegistry;
+use std::time::Duration;
+
+let nvml = Nvml::init()?;
+let mut registry = DeviceRegistry::new(&nvml)?;
+
+loop {
+    for event in registry.wait(Duration::from_secs(5))? {
+        println!("{:?}", event);
+    }
+}
+# }
+```
+
+Changes that neither raise an event nor alter the device count, such as the
+drain state, are only picked up by `.refresh()`.
+
+A device that fails to be scanned with an error other than `GpuLost` is not
+treated as removed; the error is kept in `DeviceRegistry.failures()` until a
+later scan succeeds.
+*/
+
+#[cfg(target_os = "linux")]
+use crate::bitmasks::event::EventTypes;
+use crate::error::NvmlError;
+use crate::{Device, EventSet, Nvml};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "linux")]
+use std::collections::VecDeque;
+use std::{thread, time::Duration};
+
+/// A change observed by `DeviceRegistry.refresh()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RegistryEvent {
+    /// A device that was not previously known appeared.
+    Added { uuid: String, index: u32 },
+    /// A previously known device is no longer visible.
+    Removed { uuid: String },
+    /// A previously known device is now found at a different index.
+    IndexChanged {
+        uuid: String,
+        old_index: u32,
+        new_index: u32,
+    },
+    /// The drain state of a previously known device changed.
+    ///
+    /// Drain state is only tracked on Linux.
+    DrainStateChanged { uuid: String, drained: bool },
+}
+
+/// Tracks the devices visible to NVML by UUID.
+///
+/// See the module-level docs for details.
+#[derive(Debug)]
+pub struct DeviceRegistry<'nvml> {
+    nvml: &'nvml Nvml,
+    entries: Vec<Entry<'nvml>>,
+    failures: Vec<(u32, NvmlError)>,
+    // The device count at the last scan
+    count: u32,
+    // `None` if no device supports critical Xid error events
+    events: Option<EventSet<'nvml>>,
+}
+
+#[derive(Debug)]
+struct Entry<'nvml> {
+    device: Device<'nvml>,
+    state: DeviceState,
+}
+
+// What is compared between scans.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct DeviceState {
+    uuid: String,
+    index: u32,
+    drained: Option<bool>,
+}
+
+impl<'nvml> DeviceRegistry<'nvml> {
+    /**
+    Create a registry containing the devices currently visible to NVML.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `Unknown`, on any unexpected error
+
+    See `.refresh()` for how individual devices failing is handled.
+    */
+    pub fn new(nvml: &'nvml Nvml) -> Result<Self, NvmlError> {
+        let scan = scan(nvml)?;
+
+        Ok(Self {
+            nvml,
+            events: xid_events(nvml, &scan.entries),
+            entries: scan.entries,
+            failures: scan.failures,
+            count: scan.count,
+        })
+    }
+
+    /**
+    Re-scan the system, returning the changes since the last scan.
+
+    All handles held by this registry are refreshed. Devices that fail with
+    `GpuLost` while being scanned are treated as removed. Devices that fail
+    with any other error are recorded in `.failures()` instead, and a
+    previously known device at the index of such a failure is kept as it was
+    rather than reported as removed.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `Unknown`, on any unexpected error
+    */
+    pub fn refresh(&mut self) -> Result<Vec<RegistryEvent>, NvmlError> {
+        let mut scan = scan(self.nvml)?;
+        let old: Vec<_> = self.entries.iter().map(|e| e.state.clone()).collect();
+
+        let failed: Vec<u32> = scan.failures.iter().map(|&(index, _)| index).collect();
+        for entry in self.entries.drain(..) {
+            let missing = !scan
+                .entries
+                .iter()
+                .any(|e| e.state.uuid == entry.state.uuid);
+
+            if missing && failed.contains(&entry.state.index) {
+                scan.entries.push(entry);
+            }
+        }
+        scan.entries.sort_by_key(|e| e.state.index);
+
+        let new = scan.entries.iter().map(|e| &e.state);
+        let events = diff(old.iter(), new);
+
+        // Newly-added devices need registering, and lost ones can't be
+        // unregistered
+        if !events.is_empty() {
+            self.events = None;
+            self.events = xid_events(self.nvml, &scan.entries);
+        }
+
+        self.entries = scan.entries;
+        self.failures = scan.failures;
+        self.count = scan.count;
+
+        Ok(events)
+    }
+
+    /**
+    Wait up to `timeout` for a sign that devices were attached or detached,
+    then re-scan if there was one. See the module-level docs.
+
+    Returns the changes since the last scan, which are empty if there was no
+    sign of a change.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `Unknown`, on any unexpected error
+    */
+    pub fn wait(&mut self, timeout: Duration) -> Result<Vec<RegistryEvent>, NvmlError> {
+        let xid_error = self.wait_for_xid_errors(timeout)?;
+
+        if xid_error || self.nvml.device_count()? != self.count {
+            self.refresh()
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// The devices that could not be scanned during the last scan, by index,
+    /// along with the error that occurred.
+    pub fn failures(&self) -> &[(u32, NvmlError)] {
+        &self.failures
+    }
+
+    /// Get the handle for the device with the given UUID, if it was visible
+    /// during the last scan.
+    pub fn device(&self, uuid: &str) -> Option<&Device<'nvml>> {
+        self.entries
+            .iter()
+            .find(|e| e.state.uuid == uuid)
+            .map(|e| &e.device)
+    }
+
+    /// The UUIDs of the devices visible during the last scan, ordered by index.
+    pub fn uuids(&self) -> Vec<&str> {
+        self.entries.iter().map(|e| e.state.uuid.as_str()).collect()
+    }
+
+    /// The number of devices visible during the last scan.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether or not no devices were visible during the last scan.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<'nvml> DeviceRegistry<'nvml> {
+    // Waits up to `timeout` for critical Xid error events, returning whether
+    // there were any, or just sleeps if there is no event set.
+    #[cfg(target_os = "linux")]
+    fn wait_for_xid_errors(&mut self, mut timeout: Duration) -> Result<bool, NvmlError> {
+        let set = match self.events {
+            Some(ref set) => set,
+            None => {
+                thread::sleep(timeout);
+                return Ok(false);
+            }
+        };
+
+        let mut received = false;
+
+        loop {
+            match set.wait(timeout) {
+                Ok(_) => received = true,
+                Err(NvmlError::Timeout) => return Ok(received),
+                // Picked up by the scan
+                Err(NvmlError::GpuLost) => return Ok(true),
+                Err(e) => return Err(e),
+            }
+
+            // Drain any other pending events without waiting
+            timeout = Duration::from_secs(0);
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn wait_for_xid_errors(&mut self, timeout: Duration) -> Result<bool, NvmlError> {
+        thread::sleep(timeout);
+        Ok(false)
+    }
+}
+
+// The result of scanning every device.
+struct Scan<'nvml> {
+    entries: Vec<Entry<'nvml>>,
+    failures: Vec<(u32, NvmlError)>,
+    count: u32,
+}
+
+fn scan(nvml: &Nvml) -> Result<Scan<'_>, NvmlError> {
+    let count = nvml.device_count()?;
+    let mut entries = vec![];
+    let mut failures = vec![];
+
+    for index in 0..count {
+        let entry = nvml.device_by_index(index).and_then(|device| {
+            Ok(Entry {
+                state: DeviceState {
+                    uuid: device.uuid()?,
+                    index,
+                    drained: drain_state(&device),
+                },
+                device,
+            })
+        });
+
+        match entry {
+            Ok(entry) => entries.push(entry),
+            Err(NvmlError::GpuLost) => {}
+            Err(e) => failures.push((index, e)),
+        }
+    }
+
+    Ok(Scan {
+        entries,
+        failures,
+        count,
+    })
+}
+
+// Registers every device that supports them for critical Xid error events,
+// skipping devices that fail. Falls back to `None`, i.e. only watching the
+// device count, if no device could be registered.
+#[cfg(target_os = "linux")]
+fn xid_events<'nvml>(nvml: &'nvml Nvml, entries: &[Entry<'nvml>]) -> Option<EventSet<'nvml>> {
+    let mut pending = entries
+        .iter()
+        .map(|entry| &entry.device)
+        .filter(|device| {
+            device.supported_event_types().map_or(false, |supported| {
+                supported.contains(EventTypes::CRITICAL_XID_ERROR)
+            })
+        })
+        .collect::<VecDeque<_>>();
+    let mut registered = vec![];
+    let mut events = None;
+
+    while let Some(device) = pending.pop_front() {
+        let set = match events.take() {
+            Some(set) => set,
+            None => nvml.create_event_set().ok()?,
+        };
+
+        match device.register_events_or_return(EventTypes::CRITICAL_XID_ERROR, set) {
+            Ok(set) => {
+                events = Some(set);
+                registered.push(device);
+            }
+            Err((_, Some(set))) => events = Some(set),
+            // The set was freed along with the devices registered so far, so
+            // register those again. Each time this happens, one device is
+            // dropped, so this terminates.
+            Err((_, None)) => pending.extend(registered.drain(..)),
+        }
+    }
+
+    events
+}
+
+#[cfg(not(target_os = "linux"))]
+fn xid_events<'nvml>(_nvml: &'nvml Nvml, _entries: &[Entry<'nvml>]) -> Option<EventSet<'nvml>> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn drain_state(device: &Device) -> Option<bool> {
+    device.is_drain_enabled(None).ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn drain_state(_device: &Device) -> Option<bool> {
+    None
+}
+
+fn diff<'a>(
+    old: impl Iterator<Item = &'a DeviceState> + Clone,
+    new: impl Iterator<Item = &'a DeviceState> + Clone,
+) -> Vec<RegistryEvent> {
+    let mut events = vec![];
+
+    for o in old.clone() {
+        if !new.clone().any(|n| n.uuid == o.uuid) {
+            events.push(RegistryEvent::Removed {
+                uuid: o.uuid.clone(),
+            });
+        }
+    }
+
+    for n in new {
+        let o = match old.clone().find(|o| o.uuid == n.uuid) {
+            Some(o) => o,
+            None => {
+                events.push(RegistryEvent::Added {
+                    uuid: n.uuid.clone(),
+                    index: n.index,
+                });
+                continue;
+            }
+        };
+
+        if o.index != n.index {
+            events.push(RegistryEvent::IndexChanged {
+                uuid: n.uuid.clone(),
+                old_index: o.index,
+                new_index: n.index,
+            });
+        }
+
+        if let (Some(was), Some(drained)) = (o.drained, n.drained) {
+            if was != drained {
+                events.push(RegistryEvent::DrainStateChanged {
+                    uuid: n.uuid.clone(),
+                    drained,
+                });
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn state(uuid: &str, index: u32, drained: Option<bool>) -> DeviceState {
+        DeviceState {
+            uuid: uuid.into(),
+            index,
+            drained,
+        }
+    }
+
+    #[test]
+    fn diff_reports_changes() {
+        let old = [
+            state("GPU-a", 0, Some(false)),
+            state("GPU-b", 1, Some(false)),
+            state("GPU-c", 2, None),
+        ];
+        let new = [
+            state("GPU-a", 0, Some(true)),
+            state("GPU-c", 1, Some(true)),
+            state("GPU-d", 2, None),
+        ];
+
+        assert_eq!(
+            diff(old.iter(), new.iter()),
+            vec![
+                RegistryEvent::Removed {
+                    uuid: "GPU-b".into()
+                },
+                RegistryEvent::DrainStateChanged {
+                    uuid: "GPU-a".into(),
+                    drained: true
+                },
+                RegistryEvent::IndexChanged {
+                    uuid: "GPU-c".into(),
+                    old_index: 2,
+                    new_index: 1
+                },
+                RegistryEvent::Added {
+                    uuid: "GPU-d".into(),
+                    index: 2
+                },
+            ]
+        );
+        assert!(diff(new.iter(), new.iter()).is_empty());
+    }
+
+    #[test]
+    fn refresh() {
+        let nvml = nvml();
+
+        test(3, || DeviceRegistry::new(&nvml)?.refresh())
+    }
+
+    #[test]
+    fn wait() {
+        let nvml = nvml();
+
+        test(3, || {
+            DeviceRegistry::new(&nvml)?.wait(Duration::from_millis(10))
+        })
+    }
+}
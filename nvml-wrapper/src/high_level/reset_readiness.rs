@@ -0,0 +1,258 @@
+/*!
+Check whether a GPU can be reset or drained right now.
+
+Resetting a GPU (or removing it after enabling drain state) fails with `InUse`
+as long as anything is attached to it, and NVML only reports the first reason
+it runs into. A `ResetReadiness` check instead looks at everything that is
+known to block a reset up front and lists all of it, so maintenance tooling
+can clear every blocker before trying:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::reset_readiness::{ResetBlocker, ResetReadiness};
+
+let nvml = Nvml::init()?;
+let device = nvml.device_by_index(0)?;
+let readiness = ResetReadiness::check(&device)?;
+
+for blocker in &readiness.blockers {
+    match blocker {
+        ResetBlocker::RunningProcesses(processes) => {
+            let pids: Vec<_> = processes.iter().map(|p| p.pid).collect();
+            eprintln!("processes still running: {:?}", pids);
+        }
+        other => eprintln!("blocked by {:?}", other),
+    }
+}
+# Ok(())
+# }
+```
+
+The result is a snapshot; a process can start right after the check unless the
+device is in drain state, which is why not being in drain state is itself
+reported as a blocker.
+
+MIG mode on its own does not block a reset; a pending MIG mode change is in
+fact applied by resetting the GPU. Only GPU instances that still exist do.
+*/
+
+use crate::error::{NvmlError, NvmlResultExt};
+use crate::high_level::mig_config::GPU_INSTANCE_PROFILES;
+use crate::struct_wrappers::device::ProcessInfo;
+use crate::Device;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A condition examined by a `ResetReadiness` check.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ResetCheck {
+    /// `Device.running_compute_processes()` and
+    /// `Device.running_graphics_processes()`.
+    Processes,
+    /// `Device.is_in_persistent_mode()`.
+    PersistenceMode,
+    /// `Device.is_drain_enabled()`.
+    DrainState,
+    /// `Device.gpu_instances()`, for devices with MIG mode enabled.
+    GpuInstances,
+    /// `Device.active_vgpus()`.
+    Vgpus,
+}
+
+/// Something that prevents a device from being reset right now.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ResetBlocker {
+    /// Processes are running on the device. Each process is listed once even
+    /// if it is both a compute and a graphics process.
+    RunningProcesses(Vec<ProcessInfo>),
+    /// Persistence mode is enabled, which counts as an attachment to the
+    /// device. Disable it with `Device.set_persistent()`.
+    PersistenceMode,
+    /// The device is not in drain state, so new processes can still start on
+    /// it before the reset. Enable it with `Device.set_drain()` (which requires
+    /// persistence mode to be disabled first).
+    NotDraining,
+    /// MIG GPU instances exist on the device, listed by ID. Destroy them with
+    /// `GpuInstance.destroy()`.
+    GpuInstances(Vec<u32>),
+    /// vGPU instances are active on the device, listed by ID.
+    ActiveVgpus(Vec<u32>),
+}
+
+/// The result of checking whether a single device can be reset.
+///
+/// Create one with `ResetReadiness::check()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ResetReadiness {
+    /// Every condition found that blocks a reset.
+    pub blockers: Vec<ResetBlocker>,
+    /// Conditions that could not be examined because this device does not
+    /// support querying them.
+    pub skipped: Vec<ResetCheck>,
+}
+
+impl ResetReadiness {
+    /**
+    Check whether the given `Device` can be reset right now.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `Device` is invalid
+    * `NoPermission`, if MIG mode is enabled and the user doesn't have
+      permission to list GPU instances
+    * `GpuLost`, if this `Device` has fallen off the bus or is otherwise inaccessible
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Unknown`, on any unexpected error
+
+    Conditions that NVML reports as `NotSupported` are recorded in `skipped`.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    pub fn check(device: &Device) -> Result<Self, NvmlError> {
+        let mut readiness = Self {
+            blockers: vec![],
+            skipped: vec![],
+        };
+
+        let compute = device.running_compute_processes().optional()?;
+        let graphics = device.running_graphics_processes().optional()?;
+
+        if compute.is_none() && graphics.is_none() {
+            readiness.skipped.push(ResetCheck::Processes);
+        }
+
+        let processes = merge_processes(compute.unwrap_or_default(), graphics.unwrap_or_default());
+        if !processes.is_empty() {
+            readiness
+                .blockers
+                .push(ResetBlocker::RunningProcesses(processes));
+        }
+
+        readiness.flag(
+            ResetCheck::PersistenceMode,
+            device.is_in_persistent_mode(),
+            |&enabled| enabled,
+            ResetBlocker::PersistenceMode,
+        )?;
+        readiness.flag(
+            ResetCheck::DrainState,
+            device.is_drain_enabled(None),
+            |&enabled| !enabled,
+            ResetBlocker::NotDraining,
+        )?;
+        match gpu_instance_ids(device)? {
+            Some(ids) if !ids.is_empty() => {
+                readiness.blockers.push(ResetBlocker::GpuInstances(ids))
+            }
+            Some(_) => {}
+            None => readiness.skipped.push(ResetCheck::GpuInstances),
+        }
+
+        match device.active_vgpus().optional()? {
+            Some(ids) if !ids.is_empty() => readiness.blockers.push(ResetBlocker::ActiveVgpus(ids)),
+            Some(_) => {}
+            None => readiness.skipped.push(ResetCheck::Vgpus),
+        }
+
+        Ok(readiness)
+    }
+
+    /// Whether or not nothing was found that blocks a reset.
+    ///
+    /// Conditions in `skipped` are not taken into account.
+    pub fn is_ready(&self) -> bool {
+        self.blockers.is_empty()
+    }
+
+    fn flag<T>(
+        &mut self,
+        check: ResetCheck,
+        value: Result<T, NvmlError>,
+        blocks: impl Fn(&T) -> bool,
+        blocker: ResetBlocker,
+    ) -> Result<(), NvmlError> {
+        match value.optional()? {
+            Some(value) if blocks(&value) => self.blockers.push(blocker),
+            Some(_) => {}
+            None => self.skipped.push(check),
+        }
+
+        Ok(())
+    }
+}
+
+// The IDs of every GPU instance on the device, or `None` if MIG isn't supported.
+fn gpu_instance_ids(device: &Device) -> Result<Option<Vec<u32>>, NvmlError> {
+    let mode = match device.is_mig_enabled().optional()? {
+        Some(mode) => mode,
+        None => return Ok(None),
+    };
+
+    let mut ids = vec![];
+    if !mode.currently_enabled {
+        return Ok(Some(ids));
+    }
+
+    for &profile in GPU_INSTANCE_PROFILES.iter() {
+        // Not every device supports every profile
+        let info = match device.gpu_instance_profile_info(profile).optional()? {
+            Some(info) => info,
+            None => continue,
+        };
+
+        for instance in device.gpu_instances(&info)? {
+            ids.push(instance.info()?.id);
+        }
+    }
+
+    Ok(Some(ids))
+}
+
+// Combines compute and graphics processes, listing processes that are both
+// only once.
+fn merge_processes(mut compute: Vec<ProcessInfo>, graphics: Vec<ProcessInfo>) -> Vec<ProcessInfo> {
+    for process in graphics {
+        if !compute.iter().any(|p| p.pid == process.pid) {
+            compute.push(process);
+        }
+    }
+
+    compute
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::enums::device::UsedGpuMemory;
+    use crate::test_utils::*;
+
+    fn process(pid: u32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            used_gpu_memory: UsedGpuMemory::Used(1024),
+            gpu_instance_id: None,
+            compute_instance_id: None,
+        }
+    }
+
+    #[test]
+    fn merge() {
+        let merged = merge_processes(vec![process(1), process(2)], vec![process(2), process(3)]);
+
+        assert_eq!(merged, vec![process(1), process(2), process(3)]);
+    }
+
+    #[test]
+    fn check() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, ResetReadiness::check)
+    }
+}
@@ -0,0 +1,649 @@
+/*!
+Sample metrics in the background and keep a rolling window of them.
+
+Dashboards want "utilization over the last five minutes" rather than the
+instantaneous value, which means polling in the background and keeping the
+results around. A `Sampler` polls every device on its own thread and stores
+timestamped samples of each selected `Metric` in a bounded ring buffer per
+device, which can be read or aggregated at any time:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::sampler::{Metric, Sampler};
+use std::{sync::Arc, thread, time::Duration};
+
+let nvml = Arc::new(Nvml::init()?);
+
+// Keep five minutes' worth of samples
+let sampler = Sampler::builder()
+    .metric(Metric::GpuUtilization)
+    .metric(Metric::PowerUsage)
+    .interval(Duration::from_secs(1))
+    .capacity(300)
+    .start(nvml)?;
+
+thread::sleep(Duration::from_secs(10));
+
+if let Some(stats) = sampler.stats(0, Metric::GpuUtilization) {
+    println!("{}% avg, {}% max over {} samples", stats.avg, stats.max, stats.samples);
+}
+# Ok(())
+# }
+```
+
+Timestamps are taken from a monotonic clock (anchored to the system clock
+when sampling starts), so adjustments to the system clock don't show up as
+gaps or overlaps between samples. Each device's sample is timestamped at the
+point within its NVML calls given by `SamplerBuilder.latency_compensation()`
+rather than when the polling round started, so slow calls for one device don't
+skew the timestamps of the next. How regularly samples were actually taken is
+available from `Sampler.period_stats()`; rates computed from consecutive
+samples are only as accurate as those periods.
+
+Sampling stops when the `Sampler` is dropped.
+*/
+
+use crate::error::NvmlError;
+use crate::high_level::query::{DeviceQuery, DeviceQueryBuilder, QueryResult};
+use crate::Nvml;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{mpsc, Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The default interval between samples.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+/// The default number of samples kept per device and metric.
+pub const DEFAULT_CAPACITY: usize = 60;
+/// The default point within a device's NVML calls at which its samples are
+/// timestamped: halfway through.
+pub const DEFAULT_LATENCY_COMPENSATION: f64 = 0.5;
+
+/// A metric a `Sampler` can sample.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Metric {
+    /// GPU utilization in percent.
+    GpuUtilization,
+    /// Memory utilization in percent.
+    MemoryUtilization,
+    /// Allocated FB memory in bytes.
+    MemoryUsed,
+    /// The GPU die temperature in °C.
+    GpuTemperature,
+    /// Power usage in milliwatts.
+    PowerUsage,
+    /// The graphics clock in MHz.
+    GraphicsClock,
+    /// The SM clock in MHz.
+    SmClock,
+    /// The memory clock in MHz.
+    MemoryClock,
+}
+
+/// A single timestamped value of a metric.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimedValue {
+    /// When the value was sampled, in microseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub value: u64,
+}
+
+/// Aggregates of the samples of a metric, returned from `Sampler.stats()`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Stats {
+    pub min: u64,
+    pub max: u64,
+    pub avg: f64,
+    /// The number of samples the aggregates are taken over.
+    pub samples: usize,
+    /// The timestamp of the oldest sample, in microseconds since the Unix epoch.
+    pub first_timestamp: u64,
+    /// The timestamp of the newest sample, in microseconds since the Unix epoch.
+    pub last_timestamp: u64,
+}
+
+/// Statistics of the actual time between consecutive samples of a metric,
+/// returned from `Sampler.period_stats()`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PeriodStats {
+    pub min: Duration,
+    pub max: Duration,
+    pub avg: Duration,
+    /// The standard deviation of the periods.
+    pub jitter: Duration,
+    /// The number of periods the statistics are taken over, one less than the
+    /// number of samples.
+    pub periods: usize,
+}
+
+/// Builder for a `Sampler`.
+///
+/// Create one with `Sampler::builder()`.
+#[derive(Debug, Clone)]
+pub struct SamplerBuilder {
+    metrics: Vec<Metric>,
+    interval: Duration,
+    capacity: usize,
+    latency_compensation: f64,
+}
+
+/// Samples metrics on a background thread. See the module-level docs.
+#[derive(Debug)]
+pub struct Sampler {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<State>,
+    stopped: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    buffers: HashMap<(u32, Metric), VecDeque<TimedValue>>,
+    last_errors: HashMap<u32, String>,
+    stop: bool,
+}
+
+// Converts `Instant`s to microseconds since the Unix epoch, anchored to the
+// system clock when created.
+#[derive(Debug, Clone, Copy)]
+struct MonotonicClock {
+    start: Instant,
+    unix_start: u64,
+}
+
+impl SamplerBuilder {
+    /// Sample the given metric. Can be called several times.
+    pub fn metric(&mut self, metric: Metric) -> &mut Self {
+        if !self.metrics.contains(&metric) {
+            self.metrics.push(metric);
+        }
+
+        self
+    }
+
+    /// Set the interval between samples. Defaults to `DEFAULT_INTERVAL`.
+    pub fn interval(&mut self, interval: Duration) -> &mut Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the number of samples kept per device and metric, after which the
+    /// oldest are discarded. Defaults to `DEFAULT_CAPACITY`.
+    pub fn capacity(&mut self, capacity: usize) -> &mut Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /**
+    Set the point within a device's NVML calls at which its samples are
+    timestamped, from 0.0 (when the first call starts) to 1.0 (when the last
+    one returns). Defaults to `DEFAULT_LATENCY_COMPENSATION`.
+
+    NVML doesn't say when during a call a value was read. Calibrate this by
+    comparing `Sampler.period_stats()` under load for a few values; the one
+    with the least jitter matches the driver best. Values outside the range
+    are clamped.
+    */
+    pub fn latency_compensation(&mut self, fraction: f64) -> &mut Self {
+        self.latency_compensation = if fraction.is_nan() {
+            DEFAULT_LATENCY_COMPENSATION
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+        self
+    }
+
+    /**
+    Start sampling every device on a new thread.
+
+    Devices are enumerated once, before this returns.
+
+    # Errors
+
+    Errors from enumerating the devices are returned; see `Nvml.devices()`.
+    Errors from sampling a device are available from `Sampler.last_error()`.
+    */
+    pub fn start(&self, nvml: Arc<Nvml>) -> Result<Sampler, NvmlError> {
+        let shared = Arc::new(Shared::default());
+        let (ready, started) = mpsc::channel();
+
+        let config = self.clone();
+        let thread_shared = shared.clone();
+        let thread = thread::spawn(move || config.run(&nvml, &thread_shared, ready));
+
+        match started.recv() {
+            Ok(Ok(())) => Ok(Sampler {
+                shared,
+                thread: Some(thread),
+            }),
+            Ok(Err(e)) => Err(e),
+            // The thread panicked before enumerating the devices
+            Err(_) => Err(NvmlError::Unknown),
+        }
+    }
+
+    fn run(&self, nvml: &Nvml, shared: &Shared, ready: mpsc::Sender<Result<(), NvmlError>>) {
+        let devices = match nvml
+            .devices()
+            .and_then(|d| d.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(devices) => devices,
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            }
+        };
+        let _ = ready.send(Ok(()));
+
+        let query = self.query();
+        let clock = MonotonicClock::new();
+        let mut next_sample = Instant::now();
+
+        loop {
+            for (device, index) in devices.iter().zip(0..) {
+                let before = Instant::now();
+                let result = query.run(device);
+                let timestamp = clock.timestamp(self.read_at(before, Instant::now()));
+
+                self.record(&mut shared.state(), index, timestamp, result);
+            }
+
+            // Don't try to catch up on samples missed because of slow calls
+            let now = Instant::now();
+            next_sample = next_sample.max(now) + self.interval;
+
+            if shared.wait_for_stop(next_sample - now) {
+                return;
+            }
+        }
+    }
+
+    // When values read by calls made between `before` and `after` are taken to
+    // have been read.
+    fn read_at(&self, before: Instant, after: Instant) -> Instant {
+        before + (after - before).mul_f64(self.latency_compensation)
+    }
+
+    fn query(&self) -> DeviceQuery {
+        let mut builder = DeviceQuery::builder();
+
+        for metric in &self.metrics {
+            metric.select(&mut builder);
+        }
+
+        builder.build()
+    }
+
+    fn record(
+        &self,
+        state: &mut State,
+        index: u32,
+        timestamp: u64,
+        result: Result<QueryResult, NvmlError>,
+    ) {
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                state.last_errors.insert(index, e.to_string());
+                return;
+            }
+        };
+
+        state.last_errors.remove(&index);
+
+        for &metric in &self.metrics {
+            let value = match metric.value(&result) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            let buffer = state.buffers.entry((index, metric)).or_default();
+            if buffer.len() == self.capacity {
+                buffer.pop_front();
+            }
+
+            buffer.push_back(TimedValue { timestamp, value });
+        }
+    }
+}
+
+impl Sampler {
+    /// Create a builder with no metrics selected, `DEFAULT_INTERVAL`,
+    /// `DEFAULT_CAPACITY` and `DEFAULT_LATENCY_COMPENSATION`.
+    pub fn builder() -> SamplerBuilder {
+        SamplerBuilder {
+            metrics: vec![],
+            interval: DEFAULT_INTERVAL,
+            capacity: DEFAULT_CAPACITY,
+            latency_compensation: DEFAULT_LATENCY_COMPENSATION,
+        }
+    }
+
+    /// The samples of `metric` currently kept for the device at `index`,
+    /// oldest first.
+    ///
+    /// Empty if the metric wasn't selected, isn't supported by the device, or
+    /// hasn't been sampled yet.
+    pub fn samples(&self, index: u32, metric: Metric) -> Vec<TimedValue> {
+        self.shared
+            .state()
+            .buffers
+            .get(&(index, metric))
+            .map(|buffer| buffer.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// The latest sample of `metric` for the device at `index`.
+    pub fn latest(&self, index: u32, metric: Metric) -> Option<TimedValue> {
+        self.shared
+            .state()
+            .buffers
+            .get(&(index, metric))
+            .and_then(|buffer| buffer.back().copied())
+    }
+
+    /// Min, max and average of the samples of `metric` currently kept for the
+    /// device at `index`.
+    ///
+    /// `None` if there are no samples; see `.samples()`.
+    pub fn stats(&self, index: u32, metric: Metric) -> Option<Stats> {
+        stats(self.shared.state().buffers.get(&(index, metric))?)
+    }
+
+    /// Min, max, average and standard deviation of the time between
+    /// consecutive samples of `metric` currently kept for the device at
+    /// `index`.
+    ///
+    /// Compare these to the configured interval to see how much sampling
+    /// jitters. `None` if there are fewer than two samples; see `.samples()`.
+    pub fn period_stats(&self, index: u32, metric: Metric) -> Option<PeriodStats> {
+        period_stats(self.shared.state().buffers.get(&(index, metric))?)
+    }
+
+    /// The error from the latest sample of the device at `index`, if it failed.
+    pub fn last_error(&self, index: u32) -> Option<String> {
+        self.shared.state().last_errors.get(&index).cloned()
+    }
+
+    /// Stop sampling and wait for the background thread to exit.
+    ///
+    /// Dropping the `Sampler` does the same.
+    pub fn stop(mut self) {
+        self.stop_thread();
+    }
+
+    fn stop_thread(&mut self) {
+        self.shared.state().stop = true;
+        self.shared.stopped.notify_all();
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Returns `true` if sampling should stop.
+    fn wait_for_stop(&self, timeout: Duration) -> bool {
+        let state = self.state();
+
+        self.stopped
+            .wait_timeout_while(state, timeout, |state| !state.stop)
+            .unwrap_or_else(PoisonError::into_inner)
+            .0
+            .stop
+    }
+}
+
+impl MonotonicClock {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            unix_start: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_micros() as u64)
+                .unwrap_or(0),
+        }
+    }
+
+    fn timestamp(&self, instant: Instant) -> u64 {
+        self.unix_start + instant.saturating_duration_since(self.start).as_micros() as u64
+    }
+}
+
+impl Metric {
+    fn select(self, builder: &mut DeviceQueryBuilder) {
+        match self {
+            Metric::GpuUtilization | Metric::MemoryUtilization => builder.utilization(),
+            Metric::MemoryUsed => builder.memory(),
+            Metric::GpuTemperature => builder.temperature(),
+            Metric::PowerUsage => builder.power(),
+            Metric::GraphicsClock | Metric::SmClock | Metric::MemoryClock => builder.clocks(),
+        };
+    }
+
+    fn value(self, result: &QueryResult) -> Option<u64> {
+        match self {
+            Metric::GpuUtilization => result.utilization.as_ref().map(|u| u.gpu.into()),
+            Metric::MemoryUtilization => result.utilization.as_ref().map(|u| u.memory.into()),
+            Metric::MemoryUsed => result.memory.as_ref().map(|m| m.used),
+            Metric::GpuTemperature => result.temperature.as_ref().map(|t| t.gpu.0.into()),
+            Metric::PowerUsage => result.power.as_ref().map(|p| p.usage.0.into()),
+            Metric::GraphicsClock => result.clocks.as_ref().map(|c| c.graphics.0.into()),
+            Metric::SmClock => result.clocks.as_ref().map(|c| c.sm.0.into()),
+            Metric::MemoryClock => result.clocks.as_ref().map(|c| c.memory.0.into()),
+        }
+    }
+}
+
+fn stats(buffer: &VecDeque<TimedValue>) -> Option<Stats> {
+    let first = buffer.front()?;
+    let last = buffer.back()?;
+    let sum: u128 = buffer.iter().map(|s| s.value as u128).sum();
+
+    Some(Stats {
+        min: buffer.iter().map(|s| s.value).min()?,
+        max: buffer.iter().map(|s| s.value).max()?,
+        avg: sum as f64 / buffer.len() as f64,
+        samples: buffer.len(),
+        first_timestamp: first.timestamp,
+        last_timestamp: last.timestamp,
+    })
+}
+
+fn period_stats(buffer: &VecDeque<TimedValue>) -> Option<PeriodStats> {
+    let periods: Vec<u64> = buffer
+        .iter()
+        .zip(buffer.iter().skip(1))
+        .map(|(a, b)| b.timestamp.saturating_sub(a.timestamp))
+        .collect();
+
+    let count = periods.len() as f64;
+    let avg = periods.iter().sum::<u64>() as f64 / count;
+    let variance = periods
+        .iter()
+        .map(|&p| (p as f64 - avg).powi(2))
+        .sum::<f64>()
+        / count;
+    let micros = |us: f64| Duration::from_nanos((us * 1000.0).round() as u64);
+
+    Some(PeriodStats {
+        min: Duration::from_micros(*periods.iter().min()?),
+        max: Duration::from_micros(*periods.iter().max()?),
+        avg: micros(avg),
+        jitter: micros(variance.sqrt()),
+        periods: periods.len(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::high_level::query::PowerReadings;
+    use crate::struct_wrappers::device::Utilization;
+    use crate::test_utils::*;
+    use crate::units::Milliwatts;
+
+    fn result(gpu: u32) -> Result<QueryResult, NvmlError> {
+        Ok(QueryResult {
+            utilization: Some(Utilization { gpu, memory: 0 }),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let mut builder = Sampler::builder();
+        builder
+            .metric(Metric::GpuUtilization)
+            .metric(Metric::PowerUsage)
+            .capacity(3);
+
+        let mut state = State::default();
+        for (timestamp, gpu) in [(1, 10), (2, 50), (3, 30), (4, 90)] {
+            builder.record(&mut state, 0, timestamp, result(gpu));
+        }
+
+        let buffer = &state.buffers[&(0, Metric::GpuUtilization)];
+        assert_eq!(
+            buffer.iter().map(|s| s.value).collect::<Vec<_>>(),
+            [50, 30, 90]
+        );
+
+        let stats = stats(buffer).unwrap();
+        assert_eq!((stats.min, stats.max, stats.samples), (30, 90, 3));
+        assert!((stats.avg - 170.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!((stats.first_timestamp, stats.last_timestamp), (2, 4));
+
+        // Not reported, so nothing is stored
+        assert!(!state.buffers.contains_key(&(0, Metric::PowerUsage)));
+    }
+
+    #[test]
+    fn period_stats() {
+        let mut builder = Sampler::builder();
+        builder.metric(Metric::GpuUtilization);
+
+        let mut state = State::default();
+        builder.record(&mut state, 0, 1_000, result(10));
+
+        let buffer = &state.buffers[&(0, Metric::GpuUtilization)];
+        assert_eq!(super::period_stats(buffer), None);
+
+        for timestamp in [2_000, 3_200, 4_000] {
+            builder.record(&mut state, 0, timestamp, result(10));
+        }
+
+        let buffer = &state.buffers[&(0, Metric::GpuUtilization)];
+        assert_eq!(
+            super::period_stats(buffer).unwrap(),
+            PeriodStats {
+                min: Duration::from_micros(800),
+                max: Duration::from_micros(1_200),
+                avg: Duration::from_micros(1_000),
+                // sqrt((0 + 200^2 + 200^2) / 3)
+                jitter: Duration::from_nanos(163_299),
+                periods: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn latency_compensation() {
+        let mut builder = Sampler::builder();
+        let before = Instant::now();
+        let after = before + Duration::from_millis(10);
+
+        assert_eq!(
+            builder.read_at(before, after),
+            before + Duration::from_millis(5)
+        );
+
+        builder.latency_compensation(2.0);
+        assert_eq!(builder.read_at(before, after), after);
+
+        builder.latency_compensation(0.0);
+        assert_eq!(builder.read_at(before, after), before);
+
+        let clock = MonotonicClock::new();
+        assert_eq!(
+            clock.timestamp(clock.start + Duration::from_millis(3)),
+            clock.unix_start + 3_000
+        );
+        assert_eq!(
+            clock.timestamp(clock.start - Duration::from_millis(3)),
+            clock.unix_start
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut builder = Sampler::builder();
+        builder.metric(Metric::GpuUtilization);
+
+        let mut state = State::default();
+        builder.record(&mut state, 1, 1, Err(NvmlError::GpuLost));
+        assert!(state.last_errors.contains_key(&1));
+
+        builder.record(&mut state, 1, 2, result(5));
+        assert!(state.last_errors.is_empty());
+    }
+
+    #[test]
+    fn query() {
+        let mut builder = Sampler::builder();
+        builder.metric(Metric::PowerUsage);
+        assert!(!builder.query().is_empty());
+
+        let power = PowerReadings {
+            usage: Milliwatts(100_000),
+            enforced_limit: Milliwatts(200_000),
+            total_energy_consumption: None,
+        };
+        let result = QueryResult {
+            power: Some(power),
+            ..Default::default()
+        };
+        assert_eq!(Metric::PowerUsage.value(&result), Some(100_000));
+        assert_eq!(Metric::SmClock.value(&result), None);
+    }
+
+    #[test]
+    fn start() {
+        let nvml = Arc::new(nvml());
+
+        test(3, || {
+            let sampler = Sampler::builder()
+                .metric(Metric::GpuUtilization)
+                .interval(Duration::from_millis(10))
+                .start(nvml.clone())?;
+
+            thread::sleep(Duration::from_millis(50));
+            Ok(sampler.samples(0, Metric::GpuUtilization))
+        })
+    }
+}
@@ -0,0 +1,646 @@
+/*!
+Publish per-device metrics to other processes through shared memory.
+
+Having many processes each initialize NVML and poll the same devices is
+wasteful, and some of them may not have the privileges to do so at all. With
+this module a single collector process polls NVML and publishes the latest
+metrics for each device into a memory-mapped file, typically one under
+`/dev/shm`. Any number of reader processes can map the same file and read the
+metrics without touching NVML.
+
+Each device occupies a fixed slot (its index) in the region, guarded by a
+sequence lock: readers never block the publisher and retry if they observe a
+slot while it is being written.
+
+```no_run
+# use nvml_wrapper::Nvml;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use nvml_wrapper::high_level::shm::{ShmPublisher, ShmReader};
+
+// In the collector process
+let nvml = Nvml::init()?;
+let mut publisher = ShmPublisher::create("/dev/shm/nvml-metrics", nvml.device_count()?)?;
+
+for index in 0..nvml.device_count()? {
+    publisher.publish_device(&nvml.device_by_index(index)?)?;
+}
+
+// In any reader process
+let reader = ShmReader::open("/dev/shm/nvml-metrics")?;
+
+for snapshot in reader.read_all() {
+    println!("{}: {}", snapshot.uuid, snapshot.metrics.power_usage);
+}
+# Ok(())
+# }
+```
+
+This module is only available on Linux with the `shm` feature enabled.
+*/
+
+use crate::error::NvmlError;
+use crate::ffi::bindings::NVML_DEVICE_UUID_V2_BUFFER_SIZE;
+use crate::struct_wrappers::device::{HotMetrics, Utilization};
+use crate::units::{Celsius, Megahertz, Milliwatts};
+use crate::Device;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use static_assertions::const_assert_eq;
+use std::{
+    fs::{self, File, OpenOptions},
+    hint, io, mem,
+    os::raw::{c_int, c_long, c_void},
+    os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+    path::{Path, PathBuf},
+    process, ptr,
+    sync::atomic::{fence, AtomicU32, AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+extern "C" {
+    // From the C library
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: c_long,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_SHARED: c_int = 1;
+
+const MAGIC: [u8; 8] = *b"NVMWSHM\0";
+
+// How many times a read is retried while a slot is being written before giving
+// up, e.g. because the publisher died half way through writing it
+const MAX_READ_RETRIES: u32 = 10_000;
+
+/**
+Version of the region layout.
+
+Bumped whenever the layout changes. Readers refuse to open regions with a
+different version.
+*/
+pub const LAYOUT_VERSION: u32 = 1;
+
+const UUID_LEN: usize = NVML_DEVICE_UUID_V2_BUFFER_SIZE as usize;
+
+// Bits of `SlotData.flags`
+const HAS_TOTAL_ENERGY_CONSUMPTION: u32 = 1 << 0;
+const HAS_MEMORY_TEMPERATURE: u32 = 1 << 1;
+
+#[repr(C)]
+struct Header {
+    magic: [u8; 8],
+    version: u32,
+    capacity: u32,
+    // One past the highest slot index published to so far
+    len: AtomicU32,
+    _reserved: u32,
+}
+
+#[repr(C)]
+struct Slot {
+    // Odd while the slot is being written, zero if it was never written
+    seq: AtomicU64,
+    data: SlotData,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SlotData {
+    timestamp: u64,
+    total_energy_consumption: u64,
+    index: u32,
+    flags: u32,
+    power_usage: u32,
+    gpu_temperature: u32,
+    memory_temperature: u32,
+    gpu_utilization: u32,
+    memory_utilization: u32,
+    graphics_clock: u32,
+    sm_clock: u32,
+    memory_clock: u32,
+    uuid: [u8; UUID_LEN],
+}
+
+// Slots directly follow the header and must stay aligned
+const_assert_eq!(mem::size_of::<Header>() % mem::align_of::<Slot>(), 0);
+
+/// Errors that can occur while publishing or reading metrics.
+#[derive(Error, Debug)]
+pub enum ShmError {
+    #[error("an I/O error occurred: {0}")]
+    Io(#[from] io::Error),
+    #[error("an NVML error occurred: {0}")]
+    Nvml(#[from] NvmlError),
+    /// The file is not a region created by `ShmPublisher`, or was created by
+    /// an incompatible version of this crate.
+    #[error("the file is not a compatible shared metrics region")]
+    InvalidRegion,
+    #[error("device index {index} does not fit in a region of capacity {capacity}")]
+    IndexOutOfRange { index: u32, capacity: u32 },
+}
+
+/// The metrics published for a single device.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SharedSnapshot {
+    /// The index of the device; also the slot it is published to.
+    pub index: u32,
+    /// The UUID of the device.
+    pub uuid: String,
+    /// When the metrics were published, in microseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub metrics: HotMetrics,
+}
+
+impl SlotData {
+    fn new(snapshot: &SharedSnapshot) -> Self {
+        let metrics = &snapshot.metrics;
+        let mut flags = 0;
+
+        if metrics.total_energy_consumption.is_some() {
+            flags |= HAS_TOTAL_ENERGY_CONSUMPTION;
+        }
+        if metrics.memory_temperature.is_some() {
+            flags |= HAS_MEMORY_TEMPERATURE;
+        }
+
+        // Always leave room for a nul terminator
+        let mut uuid = [0; UUID_LEN];
+        let uuid_len = snapshot.uuid.len().min(UUID_LEN - 1);
+        uuid[..uuid_len].copy_from_slice(&snapshot.uuid.as_bytes()[..uuid_len]);
+
+        Self {
+            timestamp: snapshot.timestamp,
+            total_energy_consumption: metrics.total_energy_consumption.unwrap_or(0),
+            index: snapshot.index,
+            flags,
+            power_usage: metrics.power_usage.0,
+            gpu_temperature: metrics.gpu_temperature.0,
+            memory_temperature: metrics.memory_temperature.map_or(0, |t| t.0),
+            gpu_utilization: metrics.utilization.gpu,
+            memory_utilization: metrics.utilization.memory,
+            graphics_clock: metrics.graphics_clock.0,
+            sm_clock: metrics.sm_clock.0,
+            memory_clock: metrics.memory_clock.0,
+            uuid,
+        }
+    }
+
+    fn into_snapshot(self) -> SharedSnapshot {
+        let uuid_len = self.uuid.iter().position(|&b| b == 0).unwrap_or(UUID_LEN);
+
+        SharedSnapshot {
+            index: self.index,
+            uuid: String::from_utf8_lossy(&self.uuid[..uuid_len]).into_owned(),
+            timestamp: self.timestamp,
+            metrics: HotMetrics {
+                power_usage: Milliwatts(self.power_usage),
+                total_energy_consumption: Some(self.total_energy_consumption)
+                    .filter(|_| self.flags & HAS_TOTAL_ENERGY_CONSUMPTION != 0),
+                gpu_temperature: Celsius(self.gpu_temperature),
+                memory_temperature: Some(Celsius(self.memory_temperature))
+                    .filter(|_| self.flags & HAS_MEMORY_TEMPERATURE != 0),
+                utilization: Utilization {
+                    gpu: self.gpu_utilization,
+                    memory: self.memory_utilization,
+                },
+                graphics_clock: Megahertz(self.graphics_clock),
+                sm_clock: Megahertz(self.sm_clock),
+                memory_clock: Megahertz(self.memory_clock),
+            },
+        }
+    }
+}
+
+// A path next to `path` that no other publisher uses.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", process::id()));
+
+    path.with_file_name(name)
+}
+
+fn region_len(capacity: u32) -> usize {
+    mem::size_of::<Header>() + capacity as usize * mem::size_of::<Slot>()
+}
+
+// A shared memory mapping of an entire file.
+#[derive(Debug)]
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+}
+
+// The mapping is only ever accessed through atomics and the seqlock protocol
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn new(file: &File, len: usize, writable: bool) -> io::Result<Self> {
+        let prot = if writable {
+            PROT_READ | PROT_WRITE
+        } else {
+            PROT_READ
+        };
+
+        let ptr = unsafe { mmap(ptr::null_mut(), len, prot, MAP_SHARED, file.as_raw_fd(), 0) };
+
+        // `MAP_FAILED`
+        if ptr as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            ptr: ptr as *mut u8,
+            len,
+        })
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { &*(self.ptr as *const Header) }
+    }
+
+    // `index` must be less than the region's capacity.
+    fn slot(&self, index: u32) -> *mut Slot {
+        unsafe {
+            self.ptr
+                .add(mem::size_of::<Header>() + index as usize * mem::size_of::<Slot>())
+                as *mut Slot
+        }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        unsafe {
+            munmap(self.ptr as *mut c_void, self.len);
+        }
+    }
+}
+
+/**
+Publishes metrics into a shared memory region.
+
+There must only be one publisher per region at a time; creating a publisher
+for a path that another publisher is using will corrupt the region for both.
+*/
+#[derive(Debug)]
+pub struct ShmPublisher {
+    map: Mapping,
+    capacity: u32,
+}
+
+impl ShmPublisher {
+    /**
+    Create the file at the given path (replacing any existing one) and map it
+    as a region with room for `capacity` devices.
+
+    The region is set up in a temporary file in the same directory that is
+    then renamed into place, so readers that still have a previous region at
+    this path mapped are unaffected; they keep reading the old region until
+    they open the path again.
+
+    The file is created readable by everyone so that unprivileged processes
+    can read from it.
+
+    # Errors
+
+    * `Io`, if the file could not be created, resized, mapped, or renamed into
+      place
+    */
+    pub fn create<P: AsRef<Path>>(path: P, capacity: u32) -> Result<Self, ShmError> {
+        let path = path.as_ref();
+        let temp_path = temp_path(path);
+
+        let result = Self::create_at(&temp_path, capacity)
+            .and_then(|publisher| Ok(fs::rename(&temp_path, path).map(|_| publisher)?));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+
+        result
+    }
+
+    fn create_at(path: &Path, capacity: u32) -> Result<Self, ShmError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o644)
+            .open(path)?;
+
+        let len = region_len(capacity);
+        // The file is zero-filled, which leaves every slot unpublished
+        file.set_len(len as u64)?;
+
+        let map = Mapping::new(&file, len, true)?;
+
+        unsafe {
+            let header = map.ptr as *mut Header;
+
+            (*header).version = LAYOUT_VERSION;
+            (*header).capacity = capacity;
+            // Readers check the magic first; make sure they see the rest
+            fence(Ordering::Release);
+            (*header).magic = MAGIC;
+        }
+
+        Ok(Self { map, capacity })
+    }
+
+    /// The number of devices this region has room for.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /**
+    Publish the given snapshot to the slot for its device index.
+
+    UUIDs longer than NVML's maximum UUID length are truncated.
+
+    # Errors
+
+    * `IndexOutOfRange`, if the snapshot's index does not fit in this region
+    */
+    pub fn publish(&mut self, snapshot: &SharedSnapshot) -> Result<(), ShmError> {
+        if snapshot.index >= self.capacity {
+            return Err(ShmError::IndexOutOfRange {
+                index: snapshot.index,
+                capacity: self.capacity,
+            });
+        }
+
+        let data = SlotData::new(snapshot);
+
+        unsafe {
+            let slot = self.map.slot(snapshot.index);
+            let seq = &(*slot).seq;
+            let before = seq.load(Ordering::Relaxed);
+
+            seq.store(before + 1, Ordering::Relaxed);
+            fence(Ordering::Release);
+            ptr::write_volatile(&mut (*slot).data, data);
+            seq.store(before + 2, Ordering::Release);
+        }
+
+        self.map
+            .header()
+            .len
+            .fetch_max(snapshot.index + 1, Ordering::Release);
+
+        Ok(())
+    }
+
+    /**
+    Gather `.hot_metrics()` for the given `Device` and publish them to the
+    slot for its index, timestamped with the current time.
+
+    # Errors
+
+    * `Nvml`, if any of the NVML calls fail
+    * `IndexOutOfRange`, if the device's index does not fit in this region
+    */
+    pub fn publish_device(&mut self, device: &Device) -> Result<(), ShmError> {
+        let metrics = device.hot_metrics()?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+
+        self.publish(&SharedSnapshot {
+            index: device.index()?,
+            uuid: device.uuid()?,
+            timestamp,
+            metrics,
+        })
+    }
+}
+
+/// Reads metrics from a shared memory region created by a `ShmPublisher`.
+#[derive(Debug)]
+pub struct ShmReader {
+    map: Mapping,
+    capacity: u32,
+}
+
+impl ShmReader {
+    /**
+    Map the region at the given path for reading.
+
+    # Errors
+
+    * `Io`, if the file could not be opened or mapped
+    * `InvalidRegion`, if the file is not a region created by a compatible
+      `ShmPublisher`
+    */
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ShmError> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+
+        if len < mem::size_of::<Header>() {
+            return Err(ShmError::InvalidRegion);
+        }
+
+        let map = Mapping::new(&file, len, false)?;
+        let header = map.header();
+
+        if header.magic != MAGIC || header.version != LAYOUT_VERSION {
+            return Err(ShmError::InvalidRegion);
+        }
+        fence(Ordering::Acquire);
+
+        let capacity = header.capacity;
+        if len < region_len(capacity) {
+            return Err(ShmError::InvalidRegion);
+        }
+
+        Ok(Self { map, capacity })
+    }
+
+    /// The number of devices this region has room for.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /**
+    Read the latest snapshot for the device with the given index, if one has
+    been published.
+
+    Also returns `None` if the slot stays mid-write for too long, which
+    happens if the publisher died while writing it.
+    */
+    pub fn read(&self, index: u32) -> Option<SharedSnapshot> {
+        if index >= self.capacity {
+            return None;
+        }
+
+        unsafe {
+            let slot = self.map.slot(index);
+            let seq = &(*slot).seq;
+
+            for _ in 0..MAX_READ_RETRIES {
+                let before = seq.load(Ordering::Acquire);
+
+                if before == 0 {
+                    return None;
+                } else if before % 2 == 1 {
+                    hint::spin_loop();
+                    continue;
+                }
+
+                let data = ptr::read_volatile(&(*slot).data);
+                fence(Ordering::Acquire);
+
+                if seq.load(Ordering::Relaxed) == before {
+                    return Some(data.into_snapshot());
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Read the latest snapshots for every device that has been published,
+    /// ordered by index.
+    pub fn read_all(&self) -> Vec<SharedSnapshot> {
+        let len = self
+            .map
+            .header()
+            .len
+            .load(Ordering::Acquire)
+            .min(self.capacity);
+
+        (0..len).filter_map(|index| self.read(index)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use std::{env, fs, process};
+
+    fn path(name: &str) -> std::path::PathBuf {
+        env::temp_dir().join(format!("nvml-wrapper-{}-{}", name, process::id()))
+    }
+
+    fn snapshot(index: u32) -> SharedSnapshot {
+        SharedSnapshot {
+            index,
+            uuid: format!("GPU-{}", index),
+            timestamp: 1_600_000_000_000_000,
+            metrics: HotMetrics {
+                power_usage: Milliwatts(250_000),
+                total_energy_consumption: Some(1_000_000),
+                gpu_temperature: Celsius(60),
+                memory_temperature: None,
+                utilization: Utilization {
+                    gpu: 99,
+                    memory: 40,
+                },
+                graphics_clock: Megahertz(1800),
+                sm_clock: Megahertz(1800),
+                memory_clock: Megahertz(9000),
+            },
+        }
+    }
+
+    #[test]
+    fn publish_and_read() {
+        let path = path("publish_and_read");
+        let mut publisher = ShmPublisher::create(&path, 4).unwrap();
+        let reader = ShmReader::open(&path).unwrap();
+
+        assert_eq!(reader.capacity(), 4);
+        assert!(reader.read_all().is_empty());
+
+        publisher.publish(&snapshot(2)).unwrap();
+        publisher.publish(&snapshot(0)).unwrap();
+        assert!(matches!(
+            publisher.publish(&snapshot(4)),
+            Err(ShmError::IndexOutOfRange { .. })
+        ));
+
+        assert_eq!(reader.read(2), Some(snapshot(2)));
+        assert_eq!(reader.read(1), None);
+        assert_eq!(reader.read(4), None);
+        assert_eq!(reader.read_all(), vec![snapshot(0), snapshot(2)]);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn recreate_while_mapped() {
+        let path = path("recreate_while_mapped");
+        let mut publisher = ShmPublisher::create(&path, 4).unwrap();
+        publisher.publish(&snapshot(3)).unwrap();
+
+        let old_reader = ShmReader::open(&path).unwrap();
+
+        // A smaller region would SIGBUS readers of the old one if the file
+        // were resized in place
+        let mut publisher = ShmPublisher::create(&path, 1).unwrap();
+        publisher.publish(&snapshot(0)).unwrap();
+
+        assert_eq!(old_reader.read(3), Some(snapshot(3)));
+        assert_eq!(
+            ShmReader::open(&path).unwrap().read_all(),
+            vec![snapshot(0)]
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_abandoned_write() {
+        let path = path("read_abandoned_write");
+        let mut publisher = ShmPublisher::create(&path, 1).unwrap();
+        publisher.publish(&snapshot(0)).unwrap();
+
+        // As if the publisher died half way through the next write
+        unsafe { (*publisher.map.slot(0)).seq.fetch_add(1, Ordering::Relaxed) };
+
+        let reader = ShmReader::open(&path).unwrap();
+        assert_eq!(reader.read(0), None);
+        assert!(reader.read_all().is_empty());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn open_invalid_region() {
+        let path = path("open_invalid_region");
+        fs::write(&path, [0; 512]).unwrap();
+
+        assert!(matches!(
+            ShmReader::open(&path),
+            Err(ShmError::InvalidRegion)
+        ));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn publish_device() {
+        let nvml = nvml();
+        let path = path("publish_device");
+
+        test_with_device(3, &nvml, |device| {
+            let mut publisher = ShmPublisher::create(&path, device.index()? + 1).unwrap();
+            publisher.publish_device(device).unwrap();
+
+            Ok(ShmReader::open(&path).unwrap().read_all())
+        })
+    }
+}
@@ -0,0 +1,404 @@
+/*!
+Render devices as an aligned text table for the console.
+
+`Table::from_snapshots()` turns the results of `DeviceQuery.run_all()` into a
+summary with one row per device:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::{table::Table, DeviceQuery};
+
+let nvml = Nvml::init()?;
+let snapshots = DeviceQuery::builder().all().build().run_all(&nvml)?;
+
+let mut table = Table::from_snapshots(&snapshots);
+table.color(std::env::var_os("NO_COLOR").is_none());
+
+print!("{}", table);
+# Ok(())
+# }
+```
+
+which prints something like
+
+```text
+#  NAME                     GPU  MEM            MEMORY  TEMP        POWER
+0  NVIDIA GeForce RTX 3080  97%  41%  6120 / 10240 MiB  78°C  301 / 320 W
+1  NVIDIA GeForce RTX 3080   0%   0%     1 / 10240 MiB  34°C   18 / 320 W
+```
+
+`Table` can also be filled with arbitrary rows for other summaries. With
+color enabled, the header is printed in bold and cells are colored according
+to their `Level`, using ANSI escape codes. Whether the output supports those
+is up to the caller to decide.
+
+This module is only available with the `cli` feature enabled.
+*/
+
+use crate::high_level::query::{DeviceSnapshot, QueryResult};
+use std::fmt;
+
+/// How a cell is highlighted when color is enabled.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Level {
+    Normal,
+    /// Printed in yellow.
+    Warning,
+    /// Printed in red.
+    Critical,
+}
+
+/// Alignment of a cell within its column.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// A single table cell.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Cell {
+    text: String,
+    level: Level,
+    align: Align,
+}
+
+impl Cell {
+    /// A left-aligned cell at `Level::Normal`.
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            level: Level::Normal,
+            align: Align::Left,
+        }
+    }
+
+    /// Set the level this cell is highlighted at.
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Set the alignment of this cell within its column.
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// A right-aligned cell, for numbers.
+    fn number<S: Into<String>>(text: S) -> Self {
+        Self::new(text).align(Align::Right)
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+/// An aligned text table, printed through its `Display` impl.
+///
+/// Every line, including the last, ends with a newline.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+    color: bool,
+}
+
+impl Table {
+    /// Create an empty table with the given column headers and color disabled.
+    pub fn new<I, S>(headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: vec![],
+            color: false,
+        }
+    }
+
+    /**
+    Create a table summarizing the given snapshots, one row per device.
+
+    Values for metric groups that weren't queried are shown as `-`.
+    Temperatures, memory usage and power usage close to their limits are
+    marked as `Level::Warning` or `Level::Critical`.
+    */
+    pub fn from_snapshots(snapshots: &[DeviceSnapshot]) -> Self {
+        let mut table = Self::new(vec!["#", "NAME", "GPU", "MEM", "MEMORY", "TEMP", "POWER"]);
+
+        for snapshot in snapshots {
+            let mut row = vec![
+                Cell::number(snapshot.index.to_string()),
+                Cell::new(snapshot.name.as_str()),
+            ];
+            row.extend(metric_cells(&snapshot.metrics));
+            table.add_row(row);
+        }
+
+        table
+    }
+
+    /// Enable or disable color.
+    pub fn color(&mut self, enabled: bool) -> &mut Self {
+        self.color = enabled;
+        self
+    }
+
+    /// Add a row. Missing trailing cells are left empty and extra cells are
+    /// ignored.
+    pub fn add_row<I, C>(&mut self, cells: I) -> &mut Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<Cell>,
+    {
+        let mut row: Vec<Cell> = cells
+            .into_iter()
+            .map(Into::into)
+            .take(self.headers.len())
+            .collect();
+        row.resize(self.headers.len(), Cell::new(""));
+
+        self.rows.push(row);
+        self
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| {
+                self.rows
+                    .iter()
+                    .map(|row| width(&row[i].text))
+                    .chain(Some(width(header)))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    // Headers of columns holding only right-aligned cells are right-aligned
+    // as well.
+    fn column_align(&self, column: usize) -> Align {
+        if !self.rows.is_empty()
+            && self
+                .rows
+                .iter()
+                .all(|row| row[column].align == Align::Right)
+        {
+            Align::Right
+        } else {
+            Align::Left
+        }
+    }
+
+    fn style(&self, cell: &Cell) -> Option<&'static str> {
+        match cell.level {
+            _ if !self.color => None,
+            Level::Normal => None,
+            Level::Warning => Some(YELLOW),
+            Level::Critical => Some(RED),
+        }
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths = self.widths();
+        let bold = if self.color { Some(BOLD) } else { None };
+
+        let headers: Vec<Cell> = self
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| Cell::new(header.as_str()).align(self.column_align(i)))
+            .collect();
+        write_line(f, headers.iter().map(|c| (c, bold)), &widths)?;
+
+        for row in &self.rows {
+            write_line(f, row.iter().map(|c| (c, self.style(c))), &widths)?;
+        }
+
+        Ok(())
+    }
+}
+
+const BOLD: &str = "\x1b[1m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+fn write_line<'a, I>(f: &mut fmt::Formatter<'_>, cells: I, widths: &[usize]) -> fmt::Result
+where
+    I: Iterator<Item = (&'a Cell, Option<&'a str>)>,
+{
+    let mut line = String::new();
+
+    for (i, ((cell, style), &column_width)) in cells.zip(widths).enumerate() {
+        if i > 0 {
+            line.push_str("  ");
+        }
+
+        let padding = " ".repeat(column_width - width(&cell.text));
+        let (left, right) = match cell.align {
+            Align::Left => ("", padding.as_str()),
+            Align::Right => (padding.as_str(), ""),
+        };
+
+        // Escape codes only go around the text so that padding stays uncolored
+        line.push_str(left);
+        match style {
+            Some(style) => line.push_str(&format!("{}{}{}", style, cell.text, RESET)),
+            None => line.push_str(&cell.text),
+        }
+        line.push_str(right);
+    }
+
+    writeln!(f, "{}", line.trim_end())
+}
+
+// Display width, assuming no wide or combining characters.
+fn width(text: &str) -> usize {
+    text.chars().count()
+}
+
+fn metric_cells(metrics: &QueryResult) -> Vec<Cell> {
+    let missing = || Cell::number("-");
+
+    let (gpu, mem) = match metrics.utilization {
+        Some(ref u) => (
+            Cell::number(format!("{}%", u.gpu)),
+            Cell::number(format!("{}%", u.memory)),
+        ),
+        None => (missing(), missing()),
+    };
+
+    let memory = metrics.memory.as_ref().map_or_else(missing, |m| {
+        const MIB: u64 = 1024 * 1024;
+        let level = level(m.used as f64 / m.total.max(1) as f64, 0.8, 0.95);
+
+        Cell::number(format!("{} / {} MiB", m.used / MIB, m.total / MIB)).level(level)
+    });
+
+    let temperature = metrics.temperature.as_ref().map_or_else(missing, |t| {
+        Cell::number(format!("{}°C", t.gpu.0)).level(level(t.gpu.into(), 75.0, 85.0))
+    });
+
+    let power = metrics.power.as_ref().map_or_else(missing, |p| {
+        let ratio = f64::from(p.usage) / f64::from(p.enforced_limit.0.max(1));
+
+        Cell::number(format!(
+            "{} / {} W",
+            p.usage.0 / 1000,
+            p.enforced_limit.0 / 1000
+        ))
+        .level(level(ratio, 0.9, 1.0))
+    });
+
+    vec![gpu, mem, memory, temperature, power]
+}
+
+fn level(value: f64, warning: f64, critical: f64) -> Level {
+    if value >= critical {
+        Level::Critical
+    } else if value >= warning {
+        Level::Warning
+    } else {
+        Level::Normal
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::high_level::query::{PowerReadings, TemperatureReadings};
+    use crate::struct_wrappers::device::{MemoryInfo, Utilization};
+    use crate::units::{Celsius, Milliwatts};
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn snapshot(index: u32, gpu: u32, temperature: u32) -> DeviceSnapshot {
+        DeviceSnapshot {
+            index,
+            uuid: format!("GPU-{}", index),
+            name: "NVIDIA GeForce RTX 3080".into(),
+            pci_bus_id: format!("00000000:0{}:00.0", index),
+            metrics: QueryResult {
+                utilization: Some(Utilization { gpu, memory: 41 }),
+                memory: Some(MemoryInfo {
+                    free: 4120 * MIB,
+                    total: 10240 * MIB,
+                    used: 6120 * MIB,
+                }),
+                temperature: Some(TemperatureReadings {
+                    gpu: Celsius(temperature),
+                    memory: None,
+                }),
+                power: Some(PowerReadings {
+                    usage: Milliwatts(301_000),
+                    enforced_limit: Milliwatts(320_000),
+                    total_energy_consumption: None,
+                }),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn snapshots() {
+        let table = Table::from_snapshots(&[snapshot(0, 97, 78), snapshot(1, 5, 34)]);
+
+        assert_eq!(
+            table.to_string(),
+            "\
+#  NAME                     GPU  MEM            MEMORY  TEMP        POWER
+0  NVIDIA GeForce RTX 3080  97%  41%  6120 / 10240 MiB  78°C  301 / 320 W
+1  NVIDIA GeForce RTX 3080   5%  41%  6120 / 10240 MiB  34°C  301 / 320 W
+"
+        );
+    }
+
+    #[test]
+    fn missing_metrics() {
+        let mut snapshot = snapshot(0, 0, 0);
+        snapshot.metrics = QueryResult::default();
+
+        assert!(Table::from_snapshots(&[snapshot])
+            .to_string()
+            .ends_with("0  NVIDIA GeForce RTX 3080    -    -       -     -      -\n"));
+    }
+
+    #[test]
+    fn colors() {
+        let mut table = Table::new(vec!["A", "B"]);
+        table
+            .add_row(vec![Cell::new("x").level(Level::Critical), Cell::new("y")])
+            .color(true);
+
+        assert_eq!(
+            table.to_string(),
+            "\x1b[1mA\x1b[0m  \x1b[1mB\x1b[0m\n\x1b[31mx\x1b[0m  y\n"
+        );
+    }
+
+    #[test]
+    fn uneven_rows() {
+        let mut table = Table::new(vec!["A", "B"]);
+        table.add_row(vec!["long"]).add_row(vec!["1", "2", "3"]);
+
+        assert_eq!(table.to_string(), "A     B\nlong\n1     2\n");
+    }
+}
@@ -0,0 +1,290 @@
+/*!
+Temperature alerts that don't flap.
+
+Comparing each reading against a threshold raises and clears an alert every
+time the temperature wobbles across it, which in practice is constantly. A
+`TemperatureAlerts` tracker raises an alert when the GPU reaches a threshold,
+but only clears it once the temperature has dropped below the threshold minus
+`hysteresis` again:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::temperature_alert::{Alert, TemperatureAlerts};
+use std::{thread, time::Duration};
+
+let nvml = Nvml::init()?;
+let device = nvml.device_by_index(0)?;
+
+// Thresholds default to the ones the device reports
+let mut alerts = TemperatureAlerts::for_device(&device)?;
+
+loop {
+    for alert in alerts.poll(&device)? {
+        match alert {
+            Alert::Raised { threshold, temperature } => {
+                println!("{:?} threshold reached at {}", threshold, temperature)
+            }
+            Alert::Cleared { threshold, temperature } => {
+                println!("back below {:?} threshold at {}", threshold, temperature)
+            }
+        }
+    }
+
+    thread::sleep(Duration::from_secs(1));
+}
+# }
+```
+
+Readings are of the GPU die (`TemperatureSensor::Gpu`). Thresholds are keyed by
+`TemperatureThreshold`, but may be set to any temperature.
+*/
+
+use crate::enum_wrappers::device::{TemperatureSensor, TemperatureThreshold};
+use crate::error::{NvmlError, NvmlResultExt};
+use crate::units::Celsius;
+use crate::Device;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The hysteresis used unless another one is given.
+pub const DEFAULT_HYSTERESIS: Celsius = Celsius(5);
+
+/// A change in the state of a threshold, returned from
+/// `TemperatureAlerts.update()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Alert {
+    /// The temperature reached the threshold.
+    Raised {
+        threshold: TemperatureThreshold,
+        temperature: Celsius,
+    },
+    /// The temperature dropped below the threshold minus the hysteresis after
+    /// it was raised.
+    Cleared {
+        threshold: TemperatureThreshold,
+        temperature: Celsius,
+    },
+}
+
+/// Tracks GPU temperature against thresholds. See the module-level docs.
+#[derive(Debug, Clone)]
+pub struct TemperatureAlerts {
+    // Ordered by temperature
+    thresholds: Vec<Threshold>,
+    hysteresis: Celsius,
+}
+
+#[derive(Debug, Clone)]
+struct Threshold {
+    kind: TemperatureThreshold,
+    temperature: Celsius,
+    raised: bool,
+}
+
+impl TemperatureAlerts {
+    /// Create a tracker without any thresholds, using `DEFAULT_HYSTERESIS`.
+    pub fn new() -> Self {
+        Self {
+            thresholds: vec![],
+            hysteresis: DEFAULT_HYSTERESIS,
+        }
+    }
+
+    /**
+    Create a tracker with the `Slowdown`, `GpuMax` and `Shutdown` thresholds
+    of the given device, using `DEFAULT_HYSTERESIS`.
+
+    Thresholds the device doesn't report are left out.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the device is invalid
+    * `GpuLost`, if the device has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    pub fn for_device(device: &Device) -> Result<Self, NvmlError> {
+        let mut alerts = Self::new();
+
+        for kind in [
+            TemperatureThreshold::Slowdown,
+            TemperatureThreshold::GpuMax,
+            TemperatureThreshold::Shutdown,
+        ] {
+            if let Some(temperature) = device.temperature_threshold(kind.clone()).optional()? {
+                alerts.threshold(kind, temperature);
+            }
+        }
+
+        Ok(alerts)
+    }
+
+    /// Set how far below a raised threshold the temperature must drop before
+    /// the alert is cleared. `Celsius(0)` clears as soon as the temperature is
+    /// below the threshold.
+    pub fn hysteresis(&mut self, hysteresis: Celsius) -> &mut Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Raise an alert when the temperature reaches `temperature`, replacing
+    /// the temperature previously set for `kind`.
+    ///
+    /// Replacing a threshold resets its state.
+    pub fn threshold(&mut self, kind: TemperatureThreshold, temperature: Celsius) -> &mut Self {
+        self.thresholds.retain(|t| t.kind != kind);
+        self.thresholds.push(Threshold {
+            kind,
+            temperature,
+            raised: false,
+        });
+        self.thresholds.sort_by_key(|t| t.temperature);
+        self
+    }
+
+    /// The temperature set for `kind`, if any.
+    pub fn threshold_for(&self, kind: &TemperatureThreshold) -> Option<Celsius> {
+        self.thresholds
+            .iter()
+            .find(|t| t.kind == *kind)
+            .map(|t| t.temperature)
+    }
+
+    /// Track a new reading.
+    ///
+    /// Returns the alerts raised or cleared by it, in order of threshold
+    /// temperature.
+    pub fn update(&mut self, temperature: Celsius) -> Vec<Alert> {
+        let hysteresis = self.hysteresis;
+
+        self.thresholds
+            .iter_mut()
+            .filter_map(|threshold| {
+                let clear_below = threshold.temperature.saturating_sub(hysteresis);
+
+                if !threshold.raised && temperature >= threshold.temperature {
+                    threshold.raised = true;
+                    Some(Alert::Raised {
+                        threshold: threshold.kind.clone(),
+                        temperature,
+                    })
+                } else if threshold.raised && temperature < clear_below {
+                    threshold.raised = false;
+                    Some(Alert::Cleared {
+                        threshold: threshold.kind.clone(),
+                        temperature,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /**
+    Read the GPU temperature of the given device and track it, as `.update()`
+    does.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if the device is invalid
+    * `NotSupported`, if the device does not have a temperature sensor
+    * `GpuLost`, if the device has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    pub fn poll(&mut self, device: &Device) -> Result<Vec<Alert>, NvmlError> {
+        Ok(self.update(device.temperature(TemperatureSensor::Gpu)?))
+    }
+
+    /// The thresholds currently raised, in order of temperature.
+    pub fn raised(&self) -> impl Iterator<Item = &TemperatureThreshold> {
+        self.thresholds.iter().filter(|t| t.raised).map(|t| &t.kind)
+    }
+}
+
+impl Default for TemperatureAlerts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn raised(threshold: TemperatureThreshold, temperature: u32) -> Alert {
+        Alert::Raised {
+            threshold,
+            temperature: Celsius(temperature),
+        }
+    }
+
+    fn cleared(threshold: TemperatureThreshold, temperature: u32) -> Alert {
+        Alert::Cleared {
+            threshold,
+            temperature: Celsius(temperature),
+        }
+    }
+
+    #[test]
+    fn hysteresis() {
+        use TemperatureThreshold::*;
+
+        let mut alerts = TemperatureAlerts::new();
+        alerts
+            .threshold(Shutdown, Celsius(95))
+            .threshold(Slowdown, Celsius(85));
+
+        assert_eq!(alerts.update(Celsius(80)), []);
+        assert_eq!(alerts.update(Celsius(85)), [raised(Slowdown, 85)]);
+
+        // Wobbling around the threshold doesn't flap
+        assert_eq!(alerts.update(Celsius(83)), []);
+        assert_eq!(alerts.update(Celsius(86)), []);
+        assert_eq!(alerts.update(Celsius(81)), []);
+
+        assert_eq!(alerts.update(Celsius(96)), [raised(Shutdown, 96)]);
+        assert_eq!(alerts.raised().collect::<Vec<_>>(), [&Slowdown, &Shutdown]);
+
+        assert_eq!(
+            alerts.update(Celsius(70)),
+            [cleared(Slowdown, 70), cleared(Shutdown, 70)]
+        );
+        assert_eq!(alerts.raised().count(), 0);
+    }
+
+    #[test]
+    fn configure() {
+        use TemperatureThreshold::*;
+
+        let mut alerts = TemperatureAlerts::new();
+        alerts
+            .hysteresis(Celsius(0))
+            .threshold(Slowdown, Celsius(85));
+
+        assert_eq!(alerts.update(Celsius(85)), [raised(Slowdown, 85)]);
+        assert_eq!(alerts.update(Celsius(85)), []);
+        assert_eq!(alerts.update(Celsius(84)), [cleared(Slowdown, 84)]);
+        assert_eq!(alerts.update(Celsius(85)), [raised(Slowdown, 85)]);
+
+        // Replacing a threshold resets it
+        alerts.threshold(Slowdown, Celsius(90));
+        assert_eq!(alerts.threshold_for(&Slowdown), Some(Celsius(90)));
+        assert_eq!(alerts.raised().count(), 0);
+        assert_eq!(alerts.update(Celsius(89)), []);
+    }
+
+    #[test]
+    fn for_device() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            let mut alerts = TemperatureAlerts::for_device(device)?;
+            alerts.poll(device)
+        })
+    }
+}
@@ -0,0 +1,473 @@
+/*!
+The joined device and process view `nvidia-smi`- and `top`-like tools display.
+
+Every refresh, a `Top` queries each device and joins the processes running on
+it with their utilization, GPU memory usage, owner and command line, sorted and
+ready to be displayed:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::top::{SortKey, Top};
+use std::{thread, time::Duration};
+
+let nvml = Nvml::init()?;
+let mut top = Top::new();
+top.sort_by(SortKey::SmUtilization);
+
+loop {
+    let view = top.refresh(&nvml)?;
+
+    for device in &view.devices {
+        println!("{}: {:?}", device.name, device.metrics.utilization);
+    }
+
+    for process in &view.processes {
+        println!(
+            "GPU {} {:>7} {:<10} {:>5.1}% {}",
+            process.device,
+            process.pid,
+            process.user.as_deref().unwrap_or("?"),
+            process.sm_util().unwrap_or(0.0),
+            process.command.as_deref().unwrap_or("?"),
+        );
+    }
+
+    thread::sleep(Duration::from_secs(1));
+}
+# }
+```
+
+Process utilization is averaged over the samples the driver took since the
+previous refresh (see `process_utilization`), so it is `None` on the first
+refresh and for processes that were idle in between.
+
+The user and command line are read from `/proc` on Linux. Elsewhere the
+command is the process name NVML reports and the user is unknown.
+*/
+
+use crate::enums::device::UsedGpuMemory;
+use crate::error::{NvmlError, NvmlResultExt};
+use crate::high_level::process_utilization::{ProcessUtilization, ProcessUtilizationTracker};
+use crate::high_level::query::{DeviceQuery, DeviceSnapshot};
+use crate::struct_wrappers::device::ProcessInfo;
+use crate::Nvml;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::HashMap};
+
+/// How `TopView.processes` is ordered.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SortKey {
+    /// Most GPU memory used first.
+    #[default]
+    GpuMemory,
+    /// Highest average SM utilization first.
+    SmUtilization,
+    /// Lowest PID first.
+    Pid,
+    /// By device index, then by PID.
+    Device,
+}
+
+/// What a process is using a device for.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ProcessKind {
+    Compute,
+    Graphics,
+    /// Listed as both a compute and a graphics process.
+    Both,
+}
+
+/// A process running on a device, as displayed by `top`-like tools.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TopProcess {
+    /// The index of the device the process is running on.
+    pub device: u32,
+    pub pid: u32,
+    pub kind: ProcessKind,
+    pub used_gpu_memory: UsedGpuMemory,
+    /// The process's average utilization since the previous refresh, if it was
+    /// sampled.
+    pub utilization: Option<ProcessUtilization>,
+    /// The name of the user owning the process, if known.
+    pub user: Option<String>,
+    /// The process's command line, if known.
+    pub command: Option<String>,
+}
+
+impl TopProcess {
+    /// The process's average SM (3D / compute) utilization in percent, if it
+    /// was sampled.
+    pub fn sm_util(&self) -> Option<f64> {
+        self.utilization.as_ref().map(|u| u.sm_util)
+    }
+
+    /// The GPU memory used in bytes, or `0` if it isn't available.
+    pub fn used_bytes(&self) -> u64 {
+        match self.used_gpu_memory {
+            UsedGpuMemory::Used(bytes) => bytes,
+            UsedGpuMemory::Unavailable
+            | UsedGpuMemory::NoPermission
+            | UsedGpuMemory::UnknownUnderMig => 0,
+        }
+    }
+}
+
+/// Returned from `Top.refresh()`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TopView {
+    /// Every device, ordered by index.
+    pub devices: Vec<DeviceSnapshot>,
+    /// The processes running on every device, ordered as `Top.sort_by()` was
+    /// told to.
+    pub processes: Vec<TopProcess>,
+}
+
+impl TopView {
+    /// Reorder `processes` by the given key.
+    pub fn sort_by(&mut self, key: SortKey) {
+        self.processes.sort_by(|a, b| compare(key, a, b));
+    }
+}
+
+/// Produces a `TopView` per refresh. See the module-level docs.
+#[derive(Debug)]
+pub struct Top {
+    query: DeviceQuery,
+    sort: SortKey,
+    // Keyed by UUID, so they survive re-enumeration
+    trackers: HashMap<String, ProcessUtilizationTracker>,
+    users: HashMap<u32, Option<String>>,
+}
+
+impl Top {
+    /// Create a `Top` that sorts processes by GPU memory usage.
+    pub fn new() -> Self {
+        Self {
+            query: DeviceQuery::builder()
+                .utilization()
+                .memory()
+                .temperature()
+                .power()
+                .fans()
+                .processes()
+                .build(),
+            sort: SortKey::default(),
+            trackers: HashMap::new(),
+            users: HashMap::new(),
+        }
+    }
+
+    /// Set the order of `TopView.processes`.
+    pub fn sort_by(&mut self, key: SortKey) -> &mut Self {
+        self.sort = key;
+        self
+    }
+
+    /**
+    Query every device and join the processes running on it with their
+    utilization, owner and command line.
+
+    Devices or processes that don't support a metric have it set to `None`.
+
+    # Errors
+
+    The first error encountered is returned. See `DeviceQuery.run_all()` and
+    `ProcessUtilizationTracker.poll()` for details.
+    */
+    pub fn refresh(&mut self, nvml: &Nvml) -> Result<TopView, NvmlError> {
+        let mut devices = vec![];
+        let mut utilization = HashMap::new();
+
+        for device in nvml.devices()? {
+            let device = device?;
+            let snapshot = self.query.snapshot(&device)?;
+
+            let tracker = self.trackers.entry(snapshot.uuid.clone()).or_default();
+            if tracker.poll(&device).optional()?.is_some() {
+                utilization.insert(snapshot.index, tracker.averages());
+            }
+            tracker.reset();
+
+            devices.push(snapshot);
+        }
+
+        let mut processes = join(&devices, &utilization);
+
+        for process in &mut processes {
+            process.command = command(nvml, process.pid);
+            process.user = uid(process.pid).and_then(|uid| {
+                self.users
+                    .entry(uid)
+                    .or_insert_with(|| user_name(uid))
+                    .clone()
+            });
+        }
+
+        let mut view = TopView { devices, processes };
+        view.sort_by(self.sort);
+
+        Ok(view)
+    }
+}
+
+impl Default for Top {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Joins the process lists of every snapshot with the utilization averages of
+// the device they're on, keyed by device index. Owners and commands are left
+// for the caller.
+fn join(
+    devices: &[DeviceSnapshot],
+    utilization: &HashMap<u32, Vec<ProcessUtilization>>,
+) -> Vec<TopProcess> {
+    let mut processes: Vec<TopProcess> = vec![];
+
+    for device in devices {
+        let lists = [
+            (ProcessKind::Compute, &device.metrics.compute_processes),
+            (ProcessKind::Graphics, &device.metrics.graphics_processes),
+        ];
+        let first = processes.len();
+
+        for (kind, list) in lists {
+            for info in list.iter().flatten() {
+                let ProcessInfo {
+                    pid,
+                    ref used_gpu_memory,
+                    ..
+                } = *info;
+
+                match processes[first..].iter_mut().find(|p| p.pid == pid) {
+                    Some(existing) => existing.kind = ProcessKind::Both,
+                    None => processes.push(TopProcess {
+                        device: device.index,
+                        pid,
+                        kind,
+                        used_gpu_memory: used_gpu_memory.clone(),
+                        utilization: utilization
+                            .get(&device.index)
+                            .and_then(|all| all.iter().find(|u| u.pid == pid))
+                            .cloned(),
+                        user: None,
+                        command: None,
+                    }),
+                }
+            }
+        }
+    }
+
+    processes
+}
+
+fn compare(key: SortKey, a: &TopProcess, b: &TopProcess) -> Ordering {
+    let by_device = (a.device, a.pid).cmp(&(b.device, b.pid));
+
+    match key {
+        SortKey::GpuMemory => b.used_bytes().cmp(&a.used_bytes()),
+        SortKey::SmUtilization => {
+            let util = |p: &TopProcess| p.sm_util().unwrap_or(-1.0);
+            util(b).partial_cmp(&util(a)).unwrap_or(Ordering::Equal)
+        }
+        SortKey::Pid => a.pid.cmp(&b.pid),
+        SortKey::Device => Ordering::Equal,
+    }
+    .then(by_device)
+}
+
+#[cfg(target_os = "linux")]
+fn command(_nvml: &Nvml, pid: u32) -> Option<String> {
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let args: Vec<_> = cmdline
+        .split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect();
+
+    if args.is_empty() {
+        // Kernel threads and zombies have no command line
+        let name = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+        Some(format!("[{}]", name.trim_end()))
+    } else {
+        Some(args.join(" "))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn command(nvml: &Nvml, pid: u32) -> Option<String> {
+    nvml.sys_process_name(pid, 256).ok()
+}
+
+// The real user ID of the given process.
+#[cfg(target_os = "linux")]
+fn uid(pid: u32) -> Option<u32> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    parse_uid(&status)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn uid(_pid: u32) -> Option<u32> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn user_name(uid: u32) -> Option<String> {
+    let passwd = std::fs::read_to_string("/etc/passwd").ok();
+    // Fall back to the number, as `ps` does
+    Some(
+        passwd
+            .and_then(|passwd| parse_user_name(&passwd, uid))
+            .unwrap_or_else(|| uid.to_string()),
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+fn user_name(_uid: u32) -> Option<String> {
+    None
+}
+
+// Gets the real UID from the contents of `/proc/<pid>/status`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))
+        .and_then(|ids| ids.split_whitespace().next())
+        .and_then(|id| id.parse().ok())
+}
+
+// Gets the name of the given UID from the contents of `/etc/passwd`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_user_name(passwd: &str, uid: u32) -> Option<String> {
+    passwd.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        let id = fields.nth(1)?;
+
+        (id.parse::<u32>().ok() == Some(uid)).then(|| name.to_string())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::high_level::query::QueryResult;
+    use crate::test_utils::*;
+
+    fn info(pid: u32, used: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            used_gpu_memory: UsedGpuMemory::Used(used),
+            gpu_instance_id: None,
+            compute_instance_id: None,
+        }
+    }
+
+    fn device(index: u32, compute: Vec<ProcessInfo>, graphics: Vec<ProcessInfo>) -> DeviceSnapshot {
+        DeviceSnapshot {
+            index,
+            uuid: format!("GPU-{}", index),
+            name: "NVIDIA Test GPU".into(),
+            pci_bus_id: format!("00000000:0{}:00.0", index),
+            metrics: QueryResult {
+                compute_processes: Some(compute),
+                graphics_processes: Some(graphics),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn averages(pid: u32, sm_util: f64) -> ProcessUtilization {
+        ProcessUtilization {
+            pid,
+            samples: 1,
+            sm_util,
+            mem_util: 0.0,
+            enc_util: 0.0,
+            dec_util: 0.0,
+            first_timestamp: 0,
+            last_timestamp: 0,
+        }
+    }
+
+    fn view() -> TopView {
+        let devices = vec![
+            device(0, vec![info(10, 100), info(11, 500)], vec![info(10, 100)]),
+            device(1, vec![], vec![info(10, 300), info(12, 200)]),
+        ];
+        let mut utilization = HashMap::new();
+        utilization.insert(0, vec![averages(11, 80.0)]);
+        utilization.insert(1, vec![averages(10, 20.0)]);
+
+        let processes = join(&devices, &utilization);
+        TopView { devices, processes }
+    }
+
+    fn order(view: &TopView) -> Vec<(u32, u32)> {
+        view.processes.iter().map(|p| (p.device, p.pid)).collect()
+    }
+
+    #[test]
+    fn joins_processes() {
+        let view = view();
+        assert_eq!(order(&view), [(0, 10), (0, 11), (1, 10), (1, 12)]);
+
+        let kinds: Vec<_> = view.processes.iter().map(|p| p.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ProcessKind::Both,
+                ProcessKind::Compute,
+                ProcessKind::Graphics,
+                ProcessKind::Graphics
+            ]
+        );
+
+        let sm_utils: Vec<_> = view.processes.iter().map(|p| p.sm_util()).collect();
+        assert_eq!(sm_utils, [None, Some(80.0), Some(20.0), None]);
+    }
+
+    #[test]
+    fn sorts() {
+        let mut view = view();
+
+        view.sort_by(SortKey::GpuMemory);
+        assert_eq!(order(&view), [(0, 11), (1, 10), (1, 12), (0, 10)]);
+
+        view.sort_by(SortKey::SmUtilization);
+        assert_eq!(order(&view), [(0, 11), (1, 10), (0, 10), (1, 12)]);
+
+        view.sort_by(SortKey::Pid);
+        assert_eq!(order(&view), [(0, 10), (1, 10), (0, 11), (1, 12)]);
+
+        view.sort_by(SortKey::Device);
+        assert_eq!(order(&view), [(0, 10), (0, 11), (1, 10), (1, 12)]);
+    }
+
+    #[test]
+    fn parses_owners() {
+        let status = "Name:\tpython3\nUmask:\t0022\nUid:\t1000\t1000\t1000\t1000\n";
+        assert_eq!(parse_uid(status), Some(1000));
+        assert_eq!(parse_uid("Name:\tpython3\n"), None);
+
+        let passwd = "root:x:0:0:root:/root:/bin/bash\nalice:x:1000:1000::/home/alice:/bin/sh\n";
+        assert_eq!(parse_user_name(passwd, 1000), Some("alice".into()));
+        assert_eq!(parse_user_name(passwd, 0), Some("root".into()));
+        assert_eq!(parse_user_name(passwd, 42), None);
+    }
+
+    #[test]
+    fn refresh() {
+        let nvml = nvml();
+        test(3, || Top::new().refresh(&nvml))
+    }
+}
@@ -0,0 +1,406 @@
+/*!
+Build a graph of the GPUs in a system and the connections between them.
+
+A `TopologyGraph` contains every GPU visible to NVML, the PCIe relationship
+between every pair of GPUs, and every active NVLink. It can be exported to
+[DOT] for visualization or, with the `json` feature, to JSON for further
+processing:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::TopologyGraph;
+
+let nvml = Nvml::init()?;
+let graph = TopologyGraph::build(&nvml)?;
+
+// Render with e.g. `dot -Tsvg topology.dot -o topology.svg`
+std::fs::write("topology.dot", graph.to_dot()).unwrap();
+# Ok(())
+# }
+```
+
+PCIe edges are labeled using the same abbreviations as `nvidia-smi topo -m`.
+
+A GPU that can't be queried doesn't prevent the rest of the graph from being
+built; the error is recorded in `TopologyGraph.failures` instead.
+
+[DOT]: https://graphviz.org/doc/info/lang.html
+*/
+
+use crate::enum_wrappers::device::TopologyLevel;
+use crate::error::{NvmlError, NvmlResultExt};
+use crate::ffi::bindings::NVML_NVLINK_MAX_LINKS;
+use crate::{Device, Nvml};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// A graph of the GPUs in a system and the connections between them.
+///
+/// Create one with `TopologyGraph::build()`.
+#[derive(Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TopologyGraph {
+    /// Every GPU visible to NVML, ordered by index.
+    pub gpus: Vec<GpuNode>,
+    /// The closest common PCIe ancestor of every pair of GPUs.
+    pub pcie_links: Vec<PcieLink>,
+    /// Every active NVLink, as seen from the GPU it belongs to.
+    pub nvlinks: Vec<NvLinkConnection>,
+    /// The index of every GPU that could not be fully queried, along with the
+    /// error that occurred.
+    ///
+    /// A GPU whose identity can't be queried is missing from `gpus`; a GPU
+    /// whose NVLinks can't be queried is missing from `nvlinks`. A failure to
+    /// query the PCIe relationship between two GPUs is recorded against the
+    /// first of them. Not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub failures: Vec<(u32, NvmlError)>,
+}
+
+/// A GPU in a `TopologyGraph`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GpuNode {
+    /// The NVML index of this GPU.
+    pub index: u32,
+    pub name: String,
+    pub uuid: String,
+    pub bus_id: String,
+}
+
+/// The PCIe relationship between two GPUs in a `TopologyGraph`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PcieLink {
+    /// The index of the first GPU.
+    pub gpu1: u32,
+    /// The index of the second GPU.
+    pub gpu2: u32,
+    /// The closest common ancestor of the two GPUs.
+    pub common_ancestor: TopologyLevel,
+}
+
+/// An active NVLink in a `TopologyGraph`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NvLinkConnection {
+    /// The index of the GPU this link belongs to.
+    pub gpu: u32,
+    /// The link number on that GPU.
+    pub link: u32,
+    /// The PCI bus ID of the device on the other end of the link.
+    pub remote_bus_id: String,
+    /// The index of the GPU on the other end of the link, or `None` if the
+    /// remote device is not a GPU (e.g. an NVSwitch or an IBM NPU).
+    pub remote_gpu: Option<u32>,
+}
+
+impl TopologyGraph {
+    /**
+    Query NVML for the topology of the system.
+
+    PCIe relationships and NVLinks that are not supported by the system are
+    left out of the graph. Any other error for a single GPU is recorded in
+    `.failures` and the rest of the graph is still built.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `Unknown`, on any unexpected error
+
+    These are only returned if the number of GPUs can't be queried. See the
+    docs for `Device.pci_info()`, `Nvml.topology_common_ancestor()`, and
+    `NvLink.remote_pci_info()` for the errors recorded per GPU.
+    */
+    pub fn build(nvml: &Nvml) -> Result<Self, NvmlError> {
+        let mut failures = vec![];
+        let mut devices = vec![];
+        let mut gpus = vec![];
+
+        for index in 0..nvml.device_count()? {
+            let node = nvml.device_by_index(index).and_then(|device| {
+                let node = GpuNode {
+                    index,
+                    name: device.name()?,
+                    uuid: device.uuid()?,
+                    bus_id: device.pci_info()?.bus_id,
+                };
+
+                Ok((device, node))
+            });
+
+            match node {
+                Ok((device, node)) => {
+                    devices.push(device);
+                    gpus.push(node);
+                }
+                Err(e) => failures.push((index, e)),
+            }
+        }
+
+        let mut pcie_links = vec![];
+
+        for (i, (device1, gpu1)) in devices.iter().zip(&gpus).enumerate() {
+            for (device2, gpu2) in devices.iter().zip(&gpus).skip(i + 1) {
+                match nvml.topology_common_ancestor(device1, device2).optional() {
+                    Ok(Some(common_ancestor)) => pcie_links.push(PcieLink {
+                        gpu1: gpu1.index,
+                        gpu2: gpu2.index,
+                        common_ancestor,
+                    }),
+                    Ok(None) => {}
+                    Err(e) => failures.push((gpu1.index, e)),
+                }
+            }
+        }
+
+        let mut nvlinks = vec![];
+
+        for (device, gpu) in devices.iter().zip(&gpus) {
+            let links = match active_nvlinks(device) {
+                Ok(links) => links,
+                Err(e) => {
+                    failures.push((gpu.index, e));
+                    continue;
+                }
+            };
+
+            for (link, remote_bus_id) in links {
+                let remote_gpu = gpus
+                    .iter()
+                    .find(|g| g.bus_id.eq_ignore_ascii_case(&remote_bus_id))
+                    .map(|g| g.index);
+
+                nvlinks.push(NvLinkConnection {
+                    gpu: gpu.index,
+                    link,
+                    remote_bus_id,
+                    remote_gpu,
+                });
+            }
+        }
+
+        failures.sort_by_key(|&(index, _)| index);
+
+        Ok(Self {
+            gpus,
+            pcie_links,
+            nvlinks,
+            failures,
+        })
+    }
+
+    /**
+    Render this graph in the [DOT] language.
+
+    GPUs are connected by a dashed edge labeled with their PCIe relationship
+    and by a bold edge labeled `NV#` for every pair connected by `#` NVLinks.
+    Non-GPU devices on the other end of NVLinks are rendered as boxes.
+
+    [DOT]: https://graphviz.org/doc/info/lang.html
+    */
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("graph topology {\n");
+
+        for gpu in &self.gpus {
+            let label = format!("GPU{}: {}\n{}", gpu.index, gpu.name, gpu.bus_id);
+            let _ = writeln!(
+                out,
+                "    gpu{} [label={}];",
+                gpu.index,
+                quote(&label, dot_escape)
+            );
+        }
+
+        let mut remotes: Vec<&str> = self
+            .nvlinks
+            .iter()
+            .filter(|l| l.remote_gpu.is_none())
+            .map(|l| l.remote_bus_id.as_str())
+            .collect();
+        remotes.sort_unstable();
+        remotes.dedup();
+
+        for bus_id in &remotes {
+            let _ = writeln!(
+                out,
+                "    {} [shape=box, label={}];",
+                quote(bus_id, dot_escape),
+                quote(bus_id, dot_escape)
+            );
+        }
+
+        for link in &self.pcie_links {
+            let _ = writeln!(
+                out,
+                "    gpu{} -- gpu{} [label=\"{}\", style=dashed];",
+                link.gpu1,
+                link.gpu2,
+                level_abbreviation(&link.common_ancestor)
+            );
+        }
+
+        for ((from, to), count) in self.nvlink_bundles() {
+            let _ = writeln!(
+                out,
+                "    gpu{} -- {} [label=\"NV{}\", style=bold];",
+                from, to, count
+            );
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render this graph as a JSON object with `gpus`, `pcie_links`, and
+    /// `nvlinks` arrays whose elements mirror the fields of this crate's types.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        // Only strings and integers, which always serialize
+        serde_json::to_string(self).expect("topology graph failed to serialize")
+    }
+
+    // Groups NVLinks by the pair of nodes they connect, counting each
+    // GPU-to-GPU link once even though it is seen from both ends.
+    fn nvlink_bundles(&self) -> Vec<((u32, String), usize)> {
+        let mut bundles: Vec<((u32, String), usize)> = vec![];
+
+        for link in &self.nvlinks {
+            let key = match link.remote_gpu {
+                Some(remote) if remote < link.gpu => continue,
+                Some(remote) => (link.gpu, format!("gpu{}", remote)),
+                None => (link.gpu, quote(&link.remote_bus_id, dot_escape)),
+            };
+
+            match bundles.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, count)) => *count += 1,
+                None => bundles.push((key, 1)),
+            }
+        }
+
+        bundles
+    }
+}
+
+// Returns the link number and remote bus ID of every active NVLink on the
+// given device.
+fn active_nvlinks(device: &Device) -> Result<Vec<(u32, String)>, NvmlError> {
+    let mut links = vec![];
+
+    for link in 0..NVML_NVLINK_MAX_LINKS {
+        let wrapper = device.link_wrapper_for(link);
+
+        match wrapper.is_active() {
+            Ok(true) => links.push((link, wrapper.remote_pci_info()?.bus_id)),
+            Ok(false) => {}
+            // No NVLink support at all or no more links on this device
+            Err(NvmlError::NotSupported(_)) | Err(NvmlError::InvalidArg) => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(links)
+}
+
+// The abbreviations used in the legend of `nvidia-smi topo -m`.
+fn level_abbreviation(level: &TopologyLevel) -> &'static str {
+    match level {
+        TopologyLevel::Internal => "BOARD",
+        TopologyLevel::Single => "PIX",
+        TopologyLevel::Multiple => "PXB",
+        TopologyLevel::HostBridge => "PHB",
+        TopologyLevel::Node => "NODE",
+        TopologyLevel::System => "SYS",
+    }
+}
+
+fn quote(s: &str, escape: fn(char, &mut String)) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+
+    out.push('"');
+    s.chars().for_each(|c| escape(c, &mut out));
+    out.push('"');
+
+    out
+}
+
+fn dot_escape(c: char, out: &mut String) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        c => out.push(c),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn graph() -> TopologyGraph {
+        let gpu = |index: u32| GpuNode {
+            index,
+            name: "Tesla \"V100\"".into(),
+            uuid: format!("GPU-{}", index),
+            bus_id: format!("00000000:0{}:00.0", index),
+        };
+        let nvlink = |gpu, link, remote_gpu: Option<u32>| NvLinkConnection {
+            gpu,
+            link,
+            remote_bus_id: match remote_gpu {
+                Some(i) => format!("00000000:0{}:00.0", i),
+                None => "00000000:FF:00.0".into(),
+            },
+            remote_gpu,
+        };
+
+        TopologyGraph {
+            gpus: vec![gpu(0), gpu(1)],
+            pcie_links: vec![PcieLink {
+                gpu1: 0,
+                gpu2: 1,
+                common_ancestor: TopologyLevel::HostBridge,
+            }],
+            nvlinks: vec![
+                nvlink(0, 0, Some(1)),
+                nvlink(0, 1, Some(1)),
+                nvlink(1, 0, Some(0)),
+                nvlink(1, 1, Some(0)),
+                nvlink(1, 2, None),
+            ],
+            failures: vec![],
+        }
+    }
+
+    #[test]
+    fn to_dot() {
+        let dot = graph().to_dot();
+
+        assert!(dot.contains("gpu0 [label=\"GPU0: Tesla \\\"V100\\\"\\n00000000:00:00.0\"];"));
+        assert!(dot.contains("gpu0 -- gpu1 [label=\"PHB\", style=dashed];"));
+        assert!(dot.contains("gpu0 -- gpu1 [label=\"NV2\", style=bold];"));
+        assert!(dot.contains("gpu1 -- \"00000000:FF:00.0\" [label=\"NV1\", style=bold];"));
+        assert!(!dot.contains("gpu1 -- gpu0"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn to_json() {
+        let json = graph().to_json();
+
+        assert!(json.starts_with("{\"gpus\":[{\"index\":0,\"name\":\"Tesla \\\"V100\\\"\""));
+        assert!(json.contains("\"common_ancestor\":\"HostBridge\""));
+        assert!(
+            json.contains("\"link\":2,\"remote_bus_id\":\"00000000:FF:00.0\",\"remote_gpu\":null")
+        );
+    }
+
+    #[test]
+    fn build() {
+        test(3, || TopologyGraph::build(&nvml()))
+    }
+}
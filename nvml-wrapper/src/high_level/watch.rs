@@ -0,0 +1,482 @@
+/*!
+Stream metric updates for every device at a fixed interval.
+
+`Nvml.watch()` polls a `DeviceQuery` against every device once per interval
+and yields a `MetricUpdate` for every value that changed since the previous
+poll of the same device (every value is yielded on the first poll). This is
+the one-stop entry point for dashboards that redraw when something changes:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::{DeviceQuery, MetricValue};
+use std::time::Duration;
+
+let nvml = Nvml::init()?;
+let query = DeviceQuery::builder().utilization().power().build();
+
+for update in nvml.watch(query, Duration::from_secs(1))? {
+    let update = update?;
+
+    if let MetricValue::PowerUsage(mw) = update.value {
+        println!("{}: {} mW", update.uuid, mw);
+    }
+}
+# Ok(())
+# }
+```
+
+The iterator never ends on its own and blocks in between polls. Async
+applications can enable the `watch-stream` feature and use
+`Nvml.watch_stream()` instead, which polls on a dedicated thread and yields
+the same updates through a `WatchStream`, a `futures_core::Stream`:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# #[cfg(feature = "watch-stream")]
+# async fn example() -> Result<(), NvmlError> {
+use nvml_wrapper::high_level::DeviceQuery;
+use std::{sync::Arc, time::Duration};
+
+let nvml = Arc::new(Nvml::init()?);
+let query = DeviceQuery::builder().utilization().power().build();
+let mut updates = nvml.watch_stream(query, Duration::from_secs(1))?;
+
+while let Some(update) = updates.recv().await {
+    println!("{:?}", update?);
+}
+# Ok(())
+# }
+# fn main() {}
+```
+*/
+
+use crate::bitmasks::device::ClocksEventReasons;
+use crate::enum_wrappers::device::PerformanceState;
+use crate::error::NvmlError;
+use crate::high_level::query::{DeviceQuery, QueryResult};
+use crate::units::{Celsius, Megahertz, Milliwatts};
+use crate::{Device, Nvml};
+#[cfg(feature = "watch-stream")]
+use futures_core::Stream;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+#[cfg(feature = "watch-stream")]
+use std::{
+    pin::Pin,
+    sync::{mpsc, Arc},
+    task::{Context, Poll},
+};
+
+/// How many updates a `WatchStream` buffers before its polling thread waits
+/// for them to be received.
+#[cfg(feature = "watch-stream")]
+const STREAM_BUFFER: usize = 256;
+
+/// A single value reported by a `Watch`.
+///
+/// Which values are reported depends on the `DeviceQuery` being watched.
+/// Running processes (`DeviceQueryBuilder.processes()`) are not reported.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MetricValue {
+    /// GPU utilization in percent.
+    GpuUtilization(u32),
+    /// Memory utilization in percent.
+    MemoryUtilization(u32),
+    /// Allocated FB memory in bytes.
+    MemoryUsed(u64),
+    /// Unallocated FB memory in bytes.
+    MemoryFree(u64),
+    /// Total installed FB memory in bytes.
+    MemoryTotal(u64),
+    /// The GPU die temperature.
+    GpuTemperature(Celsius),
+    /// The memory temperature.
+    MemoryTemperature(Celsius),
+    PowerUsage(Milliwatts),
+    /// The power limit enforced by the driver.
+    EnforcedPowerLimit(Milliwatts),
+    /// Energy consumed since the last driver reload in millijoules.
+    TotalEnergyConsumption(u64),
+    GraphicsClock(Megahertz),
+    SmClock(Megahertz),
+    MemoryClock(Megahertz),
+    /// The speed of the fan at the given index in percent.
+    FanSpeed {
+        fan: u32,
+        speed: u32,
+    },
+    PerformanceState(PerformanceState),
+    /// The reasons clocks are being held back.
+    ClocksEventReasons(ClocksEventReasons),
+    /// Corrected ECC errors since the last driver reload.
+    CorrectedEccErrors(u64),
+    /// Uncorrected ECC errors since the last driver reload.
+    UncorrectedEccErrors(u64),
+}
+
+/// A changed value for a single device, yielded by a `Watch`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MetricUpdate {
+    /// The UUID of the device the value belongs to.
+    pub uuid: String,
+    /// When the value was polled, in microseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub value: MetricValue,
+}
+
+/// Iterator over `MetricUpdate`s for every device.
+///
+/// Create one with `Nvml.watch()`. See the module-level docs for details.
+#[derive(Debug)]
+pub struct Watch<'nvml> {
+    query: DeviceQuery,
+    interval: Duration,
+    devices: Vec<WatchedDevice<'nvml>>,
+    next_poll: Instant,
+    pending: VecDeque<Result<MetricUpdate, NvmlError>>,
+}
+
+/**
+Stream of `MetricUpdate`s for every device.
+
+Create one with `Nvml.watch_stream()`. The devices are polled on a dedicated
+thread, which exits within one interval of the stream being dropped. See the
+module-level docs for details.
+
+This type is only available with the `watch-stream` feature enabled.
+*/
+#[cfg(feature = "watch-stream")]
+#[derive(Debug)]
+pub struct WatchStream {
+    receiver: tokio::sync::mpsc::Receiver<Result<MetricUpdate, NvmlError>>,
+}
+
+#[derive(Debug)]
+struct WatchedDevice<'nvml> {
+    device: Device<'nvml>,
+    uuid: String,
+    last: Vec<MetricValue>,
+}
+
+impl<'nvml> Watch<'nvml> {
+    pub(crate) fn new(
+        nvml: &'nvml Nvml,
+        query: DeviceQuery,
+        interval: Duration,
+    ) -> Result<Self, NvmlError> {
+        let devices = (0..nvml.device_count()?)
+            .map(|index| {
+                let device = nvml.device_by_index(index)?;
+
+                Ok(WatchedDevice {
+                    uuid: device.uuid()?,
+                    device,
+                    last: vec![],
+                })
+            })
+            .collect::<Result<_, NvmlError>>()?;
+
+        Ok(Self {
+            query,
+            interval,
+            devices,
+            next_poll: Instant::now(),
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// The UUIDs of the devices being watched, ordered by index.
+    pub fn uuids(&self) -> Vec<&str> {
+        self.devices.iter().map(|d| d.uuid.as_str()).collect()
+    }
+
+    // Sleeps until the next poll is due and polls.
+    fn poll_when_due(&mut self) {
+        let now = Instant::now();
+        if self.next_poll > now {
+            thread::sleep(self.next_poll - now);
+        }
+
+        // Don't try to catch up on polls missed because of slow calls
+        self.next_poll = self.next_poll.max(now) + self.interval;
+        self.poll();
+    }
+
+    // Polls every device once, queueing changed values and errors.
+    fn poll(&mut self) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+
+        for watched in &mut self.devices {
+            let values = match self.query.run(&watched.device) {
+                Ok(result) => values(&result),
+                Err(e) => {
+                    self.pending.push_back(Err(e));
+                    continue;
+                }
+            };
+
+            for value in changes(&watched.last, &values) {
+                self.pending.push_back(Ok(MetricUpdate {
+                    uuid: watched.uuid.clone(),
+                    timestamp,
+                    value: value.clone(),
+                }));
+            }
+
+            watched.last = values;
+        }
+    }
+}
+
+impl<'nvml> Iterator for Watch<'nvml> {
+    type Item = Result<MetricUpdate, NvmlError>;
+
+    /**
+    Get the next update, blocking until the next poll if there is none queued.
+
+    Polls that fail for a device yield the error and leave the last known
+    values for that device untouched. Never returns `None` unless there are no
+    devices to watch.
+    */
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.devices.is_empty() {
+            return None;
+        }
+
+        while self.pending.is_empty() {
+            self.poll_when_due();
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(feature = "watch-stream")]
+impl WatchStream {
+    pub(crate) fn new(
+        nvml: Arc<Nvml>,
+        query: DeviceQuery,
+        interval: Duration,
+    ) -> Result<Self, NvmlError> {
+        let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_BUFFER);
+        let (started, start) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut watch = match nvml.watch(query, interval) {
+                Ok(watch) => {
+                    let _ = started.send(Ok(()));
+                    watch
+                }
+                Err(e) => {
+                    let _ = started.send(Err(e));
+                    return;
+                }
+            };
+
+            // Polls that change nothing don't send anything, so check for a
+            // dropped stream on every poll.
+            while !watch.devices.is_empty() && !sender.is_closed() {
+                watch.poll_when_due();
+
+                for update in watch.pending.drain(..) {
+                    if sender.blocking_send(update).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        start
+            .recv()
+            .expect("watch thread reports whether it started")?;
+
+        Ok(Self { receiver })
+    }
+
+    /**
+    Get the next update, waiting for the next poll if there is none buffered.
+
+    Polls that fail for a device yield the error, as with `Watch`. Never
+    returns `None` unless there are no devices to watch.
+    */
+    pub async fn recv(&mut self) -> Option<Result<MetricUpdate, NvmlError>> {
+        self.receiver.recv().await
+    }
+}
+
+#[cfg(feature = "watch-stream")]
+impl Stream for WatchStream {
+    type Item = Result<MetricUpdate, NvmlError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+// Flattens a `QueryResult` into individual values.
+fn values(result: &QueryResult) -> Vec<MetricValue> {
+    let mut values = vec![];
+
+    if let Some(ref utilization) = result.utilization {
+        values.push(MetricValue::GpuUtilization(utilization.gpu));
+        values.push(MetricValue::MemoryUtilization(utilization.memory));
+    }
+
+    if let Some(ref memory) = result.memory {
+        values.push(MetricValue::MemoryUsed(memory.used));
+        values.push(MetricValue::MemoryFree(memory.free));
+        values.push(MetricValue::MemoryTotal(memory.total));
+    }
+
+    if let Some(ref temperature) = result.temperature {
+        values.push(MetricValue::GpuTemperature(temperature.gpu));
+        values.extend(temperature.memory.map(MetricValue::MemoryTemperature));
+    }
+
+    if let Some(ref power) = result.power {
+        values.push(MetricValue::PowerUsage(power.usage));
+        values.push(MetricValue::EnforcedPowerLimit(power.enforced_limit));
+        values.extend(
+            power
+                .total_energy_consumption
+                .map(MetricValue::TotalEnergyConsumption),
+        );
+    }
+
+    if let Some(ref clocks) = result.clocks {
+        values.push(MetricValue::GraphicsClock(clocks.graphics));
+        values.push(MetricValue::SmClock(clocks.sm));
+        values.push(MetricValue::MemoryClock(clocks.memory));
+    }
+
+    if let Some(ref fan_speeds) = result.fan_speeds {
+        values.extend(
+            fan_speeds
+                .iter()
+                .zip(0..)
+                .map(|(&speed, fan)| MetricValue::FanSpeed { fan, speed }),
+        );
+    }
+
+    values.extend(
+        result
+            .performance_state
+            .clone()
+            .map(MetricValue::PerformanceState),
+    );
+    values.extend(
+        result
+            .clocks_event_reasons
+            .map(MetricValue::ClocksEventReasons),
+    );
+
+    if let Some(ref ecc_errors) = result.ecc_errors {
+        values.push(MetricValue::CorrectedEccErrors(ecc_errors.corrected));
+        values.push(MetricValue::UncorrectedEccErrors(ecc_errors.uncorrected));
+    }
+
+    values
+}
+
+// Each kind of value occurs at most once per poll, so a value is unchanged
+// exactly when an equal one was present in the previous poll.
+fn changes<'a>(
+    last: &'a [MetricValue],
+    values: &'a [MetricValue],
+) -> impl Iterator<Item = &'a MetricValue> {
+    values.iter().filter(move |v| !last.contains(v))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::high_level::query::{PowerReadings, TemperatureReadings};
+    use crate::struct_wrappers::device::Utilization;
+    use crate::test_utils::*;
+
+    #[test]
+    fn values_and_changes() {
+        let mut result = QueryResult {
+            utilization: Some(Utilization { gpu: 10, memory: 5 }),
+            temperature: Some(TemperatureReadings {
+                gpu: Celsius(40),
+                memory: None,
+            }),
+            ..Default::default()
+        };
+        let first = values(&result);
+
+        assert_eq!(
+            first,
+            vec![
+                MetricValue::GpuUtilization(10),
+                MetricValue::MemoryUtilization(5),
+                MetricValue::GpuTemperature(Celsius(40)),
+            ]
+        );
+        assert_eq!(changes(&[], &first).count(), 3);
+
+        result.utilization = Some(Utilization { gpu: 80, memory: 5 });
+        result.power = Some(PowerReadings {
+            usage: Milliwatts(150_000),
+            enforced_limit: Milliwatts(300_000),
+            total_energy_consumption: None,
+        });
+        result.fan_speeds = Some(vec![30, 45]);
+        let second = values(&result);
+
+        assert_eq!(
+            changes(&first, &second).collect::<Vec<_>>(),
+            vec![
+                &MetricValue::GpuUtilization(80),
+                &MetricValue::PowerUsage(Milliwatts(150_000)),
+                &MetricValue::EnforcedPowerLimit(Milliwatts(300_000)),
+                &MetricValue::FanSpeed { fan: 0, speed: 30 },
+                &MetricValue::FanSpeed { fan: 1, speed: 45 },
+            ]
+        );
+        assert_eq!(changes(&second, &second).count(), 0);
+    }
+
+    #[cfg(feature = "watch-stream")]
+    #[test]
+    fn watch_stream() {
+        let nvml = Arc::new(nvml());
+        let query = DeviceQuery::builder().all().build();
+
+        test(3, || {
+            let mut stream = nvml
+                .clone()
+                .watch_stream(query.clone(), Duration::from_millis(10))?;
+
+            (0..5)
+                .filter_map(|_| stream.receiver.blocking_recv())
+                .collect::<Result<Vec<_>, _>>()
+        })
+    }
+
+    #[test]
+    fn watch() {
+        let nvml = nvml();
+        let query = DeviceQuery::builder().all().build();
+
+        test(3, || {
+            nvml.watch(query.clone(), Duration::from_millis(10))?
+                .take(5)
+                .collect::<Result<Vec<_>, _>>()
+        })
+    }
+}
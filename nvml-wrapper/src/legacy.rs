@@ -0,0 +1,441 @@
+/*!
+Compatibility with legacy (R384 / R418-era) driver branches.
+
+NVIDIA versions functions whose signatures or structs change by appending a
+`_vN` suffix (and occasionally renames functions outright, such as the clocks
+throttle reason functions becoming clocks event reason functions), and this
+wrapper always calls the newest version. Older drivers don't export the newer
+versions, however, which would otherwise leave the corresponding wrapper
+methods failing with `FailedToLoadSymbol`.
+
+When NVML is loaded, each versioned function that fails to load is replaced
+with the newest ancestor exported by the driver:
+
+* Ancestors that are ABI-compatible are substituted directly.
+* Ancestors that use an older, smaller struct are called through a separate
+  code path that fills the fields missing from the older struct with `None`.
+
+Some drivers export a versioned function but return `FunctionNotFound` when
+it is called. The ABI-compatible ancestor of such a function is retried
+instead, and is called directly from then on.
+
+# Targeting a driver branch
+
+By default, the newest version of every function is preferred. The `nvml-11`
+and `nvml-12` features instead target the entry points of the NVML 11 and
+NVML 12 branches respectively: functions renamed or versioned after the
+targeted branch are replaced with their ABI-compatible ancestors even if the
+driver exports them (if more than one is enabled, the newest branch is
+targeted). This is useful for keeping one binary's behavior identical across
+old and new drivers. Functions that were added after the targeted branch are
+unaffected; they simply fail to load on drivers that don't export them.
+
+The substitutions made are reported through `Nvml.library_capabilities()`.
+*/
+
+use crate::error::{nvml_try_for, NvmlError};
+use crate::ffi::bindings::*;
+use crate::struct_wrappers::device::ProcessInfo;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::{
+    mem,
+    os::raw::c_uint,
+    ptr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/**
+The NVML branch whose entry points are preferred, as selected by the `nvml-11`
+and `nvml-12` features.
+
+`None` if neither feature is enabled, in which case the newest entry points are
+preferred.
+*/
+pub const TARGET_VERSION: Option<u32> = if cfg!(feature = "nvml-12") {
+    Some(12)
+} else if cfg!(feature = "nvml-11") {
+    Some(11)
+} else {
+    None
+};
+
+/// Why a function was replaced by one of its ancestors.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum FallbackReason {
+    /// The loaded library does not export the function.
+    Missing,
+    /// The function is newer than the branch selected by `TARGET_VERSION`.
+    Targeted,
+    /// The loaded library exports the function, but calling it returned
+    /// `FunctionNotFound`.
+    FunctionNotFound,
+}
+
+/// Describes a function that was replaced by an ancestor from an older driver
+/// branch.
+///
+/// Only implements `Serialize`, as its fields borrow from the wrapper's own
+/// tables.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SymbolFallback {
+    /// The function the wrapper normally calls, e.g. `nvmlInit_v2`.
+    pub function: &'static str,
+    /// The function that is called instead, e.g. `nvmlInit`.
+    pub substitute: &'static str,
+    /**
+    Names of wrapper struct fields that the substitute cannot provide.
+
+    These fields are always `None` in values obtained through the substitute.
+    Empty if the substitute is fully compatible.
+    */
+    pub unavailable_fields: &'static [&'static str],
+    /// Why the substitute is called instead.
+    pub reason: FallbackReason,
+}
+
+pub(crate) type RunningProcessesV1 =
+    unsafe extern "C" fn(nvmlDevice_t, *mut c_uint, *mut nvmlProcessInfo_v1_t) -> nvmlReturn_t;
+
+/// Substitutes for functions that could not be loaded and that can't be
+/// stored in `NvmlLib` because their signatures differ, along with their names.
+#[derive(Debug, Default)]
+pub(crate) struct LegacyLib {
+    pub(crate) compute_running_processes: Option<(RunningProcessesV1, &'static str)>,
+    pub(crate) graphics_running_processes: Option<(RunningProcessesV1, &'static str)>,
+    pub(crate) fallbacks: Vec<SymbolFallback>,
+    pub(crate) retries: Vec<Retry>,
+}
+
+/// An ABI-compatible ancestor to retry a function with if calling it returns
+/// `FunctionNotFound`.
+#[derive(Debug)]
+pub(crate) struct Retry {
+    function: &'static str,
+    substitute: &'static str,
+    // The ancestor's address, as raw pointers would make `Nvml` `!Send`
+    address: usize,
+    // Set once the function returned `FunctionNotFound`
+    active: AtomicBool,
+}
+
+impl LegacyLib {
+    /**
+    Call `sym`, the loaded version of `function`, via `call`, retrying with its
+    ancestor if the driver returns `FunctionNotFound`.
+
+    # Safety
+
+    `F` must be the type of the `NvmlLib` field for `function`.
+    */
+    pub(crate) unsafe fn call<F, C>(
+        &self,
+        function: &'static str,
+        sym: F,
+        mut call: C,
+    ) -> nvmlReturn_t
+    where
+        F: Copy,
+        C: FnMut(F) -> nvmlReturn_t,
+    {
+        let retry = match self.retries.iter().find(|r| r.function == function) {
+            Some(retry) => retry,
+            None => return call(sym),
+        };
+
+        if retry.active.load(Ordering::Relaxed) {
+            return call(retry.sym());
+        }
+
+        match call(sym) {
+            nvmlReturn_enum_NVML_ERROR_FUNCTION_NOT_FOUND => {
+                retry.active.store(true, Ordering::Relaxed);
+                call(retry.sym())
+            }
+            other => other,
+        }
+    }
+
+    /// The substitutions made at load time and since, through retries.
+    pub(crate) fn fallbacks(&self) -> Vec<SymbolFallback> {
+        let retries = self
+            .retries
+            .iter()
+            .filter(|r| r.active.load(Ordering::Relaxed))
+            .map(|r| SymbolFallback {
+                function: r.function,
+                substitute: r.substitute,
+                unavailable_fields: &[],
+                reason: FallbackReason::FunctionNotFound,
+            });
+
+        self.fallbacks.iter().cloned().chain(retries).collect()
+    }
+}
+
+impl Retry {
+    unsafe fn sym<F: Copy>(&self) -> F {
+        assert_eq!(mem::size_of::<F>(), mem::size_of::<usize>());
+        mem::transmute_copy(&self.address)
+    }
+}
+
+// Whether the entry points of the NVML `major` branch are preferred over
+// older ones.
+fn targets(major: u32) -> bool {
+    TARGET_VERSION.map_or(true, |target| target >= major)
+}
+
+// Replaces `$field` with the first of the given ABI-compatible ancestors that
+// can be loaded if `$field` itself failed to load, or if it is newer than the
+// targeted branch (`since` the given NVML major version). Otherwise the first
+// ancestor that can be loaded is kept for retries.
+macro_rules! compatible_fallback {
+    (
+        $lib:ident, $fallbacks:ident, $retries:ident,
+        $field:ident $(since $major:literal)? => $($ancestor:literal),+
+    ) => {
+        let targeted = true $(&& targets($major))?;
+        let reason = if $lib.$field.is_err() {
+            Some(FallbackReason::Missing)
+        } else if !targeted {
+            Some(FallbackReason::Targeted)
+        } else {
+            None
+        };
+
+        #[allow(clippy::never_loop)]
+        loop {
+            $(
+                if let Ok(sym) = $lib.__library.get(concat!($ancestor, "\0").as_bytes()) {
+                    match reason {
+                        Some(reason) => {
+                            $lib.$field = Ok(*sym);
+                            $fallbacks.push(SymbolFallback {
+                                function: stringify!($field),
+                                substitute: $ancestor,
+                                unavailable_fields: &[],
+                                reason,
+                            });
+                        }
+                        None => $retries.push(Retry {
+                            function: stringify!($field),
+                            substitute: $ancestor,
+                            address: *sym as usize,
+                            active: AtomicBool::new(false),
+                        }),
+                    }
+
+                    break;
+                }
+            )+
+
+            break;
+        }
+    };
+}
+
+/**
+Fill in functions that failed to load with their ancestors, where possible.
+
+# Safety
+
+The ancestors are assumed to have the signatures documented in the NVML
+headers of their respective driver branches.
+*/
+pub(crate) unsafe fn load(lib: &mut NvmlLib) -> LegacyLib {
+    let mut fallbacks = Vec::new();
+    let mut retries = Vec::new();
+
+    compatible_fallback!(lib, fallbacks, retries, nvmlInit_v2 => "nvmlInit");
+    compatible_fallback!(lib, fallbacks, retries, nvmlDeviceGetCount_v2 => "nvmlDeviceGetCount");
+    compatible_fallback!(
+        lib, fallbacks, retries, nvmlDeviceGetHandleByIndex_v2 => "nvmlDeviceGetHandleByIndex"
+    );
+    compatible_fallback!(
+        lib, fallbacks, retries, nvmlDeviceGetHandleByPciBusId_v2 => "nvmlDeviceGetHandleByPciBusId"
+    );
+    compatible_fallback!(lib, fallbacks, retries, nvmlDeviceGetPciInfo_v3 => "nvmlDeviceGetPciInfo_v2");
+    compatible_fallback!(
+        lib, fallbacks, retries, nvmlSystemGetCudaDriverVersion_v2 => "nvmlSystemGetCudaDriverVersion"
+    );
+    compatible_fallback!(lib, fallbacks, retries, nvmlEventSetWait_v2 since 11 => "nvmlEventSetWait");
+    compatible_fallback!(
+        lib,
+        fallbacks,
+        retries,
+        nvmlDeviceGetCurrentClocksEventReasons since 12 => "nvmlDeviceGetCurrentClocksThrottleReasons"
+    );
+    compatible_fallback!(
+        lib,
+        fallbacks,
+        retries,
+        nvmlDeviceGetSupportedClocksEventReasons since 12 => "nvmlDeviceGetSupportedClocksThrottleReasons"
+    );
+    compatible_fallback!(
+        lib,
+        fallbacks,
+        retries,
+        nvmlDeviceGetComputeRunningProcesses_v3 since 11 => "nvmlDeviceGetComputeRunningProcesses_v2"
+    );
+    compatible_fallback!(
+        lib,
+        fallbacks,
+        retries,
+        nvmlDeviceGetGraphicsRunningProcesses_v3 since 11 => "nvmlDeviceGetGraphicsRunningProcesses_v2"
+    );
+
+    let compute_running_processes = running_processes_v1(
+        lib,
+        &mut fallbacks,
+        lib.nvmlDeviceGetComputeRunningProcesses_v3.is_err(),
+        "nvmlDeviceGetComputeRunningProcesses_v3",
+        "nvmlDeviceGetComputeRunningProcesses",
+    );
+    let graphics_running_processes = running_processes_v1(
+        lib,
+        &mut fallbacks,
+        lib.nvmlDeviceGetGraphicsRunningProcesses_v3.is_err(),
+        "nvmlDeviceGetGraphicsRunningProcesses_v3",
+        "nvmlDeviceGetGraphicsRunningProcesses",
+    );
+
+    LegacyLib {
+        compute_running_processes,
+        graphics_running_processes,
+        fallbacks,
+        retries,
+    }
+}
+
+unsafe fn running_processes_v1(
+    lib: &NvmlLib,
+    fallbacks: &mut Vec<SymbolFallback>,
+    missing: bool,
+    function: &'static str,
+    substitute: &'static str,
+) -> Option<(RunningProcessesV1, &'static str)> {
+    if !missing {
+        return None;
+    }
+
+    let name = format!("{}\0", substitute);
+    let sym = lib
+        .__library
+        .get::<RunningProcessesV1>(name.as_bytes())
+        .ok()?;
+
+    fallbacks.push(SymbolFallback {
+        function,
+        substitute,
+        unavailable_fields: &["gpu_instance_id", "compute_instance_id"],
+        reason: FallbackReason::Missing,
+    });
+
+    Some((*sym, substitute))
+}
+
+/// Get the number of running processes via a `_v1` running processes function.
+pub(crate) unsafe fn running_processes_count(
+    function: &'static str,
+    sym: RunningProcessesV1,
+    device: nvmlDevice_t,
+) -> Result<u32, NvmlError> {
+    let mut count: c_uint = 0;
+
+    match sym(device, &mut count, ptr::null_mut()) {
+        nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE => Ok(count),
+        other => nvml_try_for(function, other).map(|_| 0),
+    }
+}
+
+/// Get running processes via a `_v1` running processes function.
+pub(crate) unsafe fn running_processes(
+    function: &'static str,
+    sym: RunningProcessesV1,
+    device: nvmlDevice_t,
+) -> Result<Vec<ProcessInfo>, NvmlError> {
+    let mut count = match running_processes_count(function, sym, device)? {
+        0 => return Ok(vec![]),
+        value => value,
+    };
+    // Headroom in case more processes are launched in between the calls
+    count += 5;
+    let mut processes: Vec<nvmlProcessInfo_v1_t> = vec![mem::zeroed(); count as usize];
+
+    nvml_try_for(function, sym(device, &mut count, processes.as_mut_ptr()))?;
+    processes.truncate(count as usize);
+
+    Ok(processes.into_iter().map(ProcessInfo::from).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    type GetCount = unsafe extern "C" fn(*mut c_uint) -> nvmlReturn_t;
+
+    static NEWEST_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe extern "C" fn newest(_: *mut c_uint) -> nvmlReturn_t {
+        NEWEST_CALLS.fetch_add(1, Ordering::Relaxed);
+        nvmlReturn_enum_NVML_ERROR_FUNCTION_NOT_FOUND
+    }
+
+    unsafe extern "C" fn ancestor(count: *mut c_uint) -> nvmlReturn_t {
+        *count = 2;
+        nvmlReturn_enum_NVML_SUCCESS
+    }
+
+    #[test]
+    fn retries_function_not_found() {
+        let lib = LegacyLib {
+            retries: vec![Retry {
+                function: "nvmlDeviceGetCount_v2",
+                substitute: "nvmlDeviceGetCount",
+                address: ancestor as GetCount as usize,
+                active: AtomicBool::new(false),
+            }],
+            ..LegacyLib::default()
+        };
+        assert!(lib.fallbacks().is_empty());
+
+        for _ in 0..2 {
+            let mut count = 0;
+            let result = unsafe {
+                lib.call("nvmlDeviceGetCount_v2", newest as GetCount, |sym| {
+                    sym(&mut count)
+                })
+            };
+
+            assert_eq!(result, nvmlReturn_enum_NVML_SUCCESS);
+            assert_eq!(count, 2);
+        }
+
+        // The ancestor is called directly once the retry is active
+        assert_eq!(NEWEST_CALLS.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            lib.fallbacks(),
+            vec![SymbolFallback {
+                function: "nvmlDeviceGetCount_v2",
+                substitute: "nvmlDeviceGetCount",
+                unavailable_fields: &[],
+                reason: FallbackReason::FunctionNotFound,
+            }]
+        );
+
+        // Functions without an ancestor are called as-is
+        let result = unsafe { lib.call("nvmlUnitGetCount", newest as GetCount, |sym| sym(&mut 0)) };
+        assert_eq!(result, nvmlReturn_enum_NVML_ERROR_FUNCTION_NOT_FOUND);
+    }
+
+    #[test]
+    fn targets_branches() {
+        assert!(targets(11));
+        assert_eq!(targets(12), TARGET_VERSION != Some(11));
+        assert_eq!(targets(13), TARGET_VERSION.is_none());
+    }
+}
@@ -32,7 +32,19 @@ also the underlying library for NVIDIA's nvidia-smi tool.
 [`libloading`][libloading] crate. This means the NVML library gets loaded upon
 calling `Nvml::init` and can return an error if NVML isn't present, making it
 possible to drop NVIDIA-related features in your code at runtime on systems that
-don't have relevant hardware.
+don't have relevant hardware. Nothing links against NVML at build time, so
+binaries start fine on machines without the NVIDIA driver installed:
+
+```
+use nvml_wrapper::error::NvmlError;
+use nvml_wrapper::Nvml;
+
+match Nvml::init() {
+    Ok(nvml) => { /* GPU support enabled */ }
+    Err(NvmlError::LibraryNotFound) => { /* no NVIDIA driver installed */ }
+    Err(e) => { /* the driver is installed but unusable */ }
+}
+```
 
 Successful execution of `Nvml::init` means:
 
@@ -66,6 +78,13 @@ way to avoid bumping this.
 The `serde` feature can be toggled on in order to `#[derive(Serialize, Deserialize)]`
 for every NVML data structure.
 
+The `binary` feature (which implies `serde`) adds the `binary` module, a compact
+encoding of this crate's types for shipping them between processes.
+
+The `shm` feature adds the `high_level::shm` module (Linux only), through which a
+single collector process can publish device metrics to any number of reader
+processes via shared memory.
+
 [nvml]: https://developer.nvidia.com/nvidia-management-library-nvml
 [libloading]: https://github.com/nagisa/rust_libloading
 [once_cell]: https://docs.rs/once_cell/latest/once_cell/sync/struct.Lazy.html
@@ -77,19 +96,33 @@ for every NVML data structure.
 extern crate libloading;
 extern crate nvml_wrapper_sys as ffi;
 
+#[cfg(feature = "binary")]
+pub mod binary;
 pub mod bitmasks;
+pub mod cpu_set;
 pub mod device;
 pub mod enum_wrappers;
 pub mod enums;
 pub mod error;
 pub mod event;
+pub mod gpu_model;
 pub mod high_level;
+pub mod legacy;
+pub mod mig_device;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod nv_link;
+mod permissions;
 pub mod struct_wrappers;
 pub mod structs;
+mod support;
 #[cfg(test)]
 mod test_utils;
+pub mod timing;
 pub mod unit;
+pub mod units;
+pub mod uuid;
+mod wsl;
 
 // Re-exports for convenience
 pub use crate::device::Device;
@@ -103,40 +136,92 @@ pub mod sys_exports {
     pub mod field_id {
         pub use crate::ffi::bindings::field_id::*;
     }
+
+    /// Raw handle types, for use with `Device.as_raw()`, `Device::from_raw()` and
+    /// their `Unit` and `EventSet` counterparts.
+    pub mod handles {
+        pub use crate::ffi::bindings::{nvmlDevice_t, nvmlEventSet_t, nvmlUnit_t};
+    }
 }
 
 #[cfg(target_os = "linux")]
 use std::convert::TryInto;
 #[cfg(target_os = "linux")]
 use std::ptr;
+#[cfg(feature = "watch-stream")]
+use std::sync::Arc;
 use std::{
+    collections::HashMap,
     convert::TryFrom,
-    ffi::{CStr, CString, OsStr},
+    ffi::{CStr, CString, OsStr, OsString},
+    iter::FusedIterator,
     mem::{self, ManuallyDrop},
+    ops::Range,
     os::raw::{c_int, c_uint},
+    sync::Mutex,
+    time::Duration,
 };
 
+#[cfg(feature = "serde")]
+use serde::Serialize;
 use static_assertions::assert_impl_all;
 
 #[cfg(target_os = "linux")]
 use crate::enum_wrappers::device::TopologyLevel;
 
-use crate::error::{nvml_sym, nvml_try, NvmlError};
+use crate::device::{with_string_buffer, NotSupportedCache, NvLinkCounters};
+use crate::error::{nvml_fn, nvml_try_for, with_init_diagnostics, NvmlError, NvmlResultExt};
 use crate::ffi::bindings::*;
 
-use crate::struct_wrappers::ExcludedDeviceInfo;
+use crate::struct_wrappers::{ExcludedDeviceInfo, VgpuVersionRange, VgpuVersions};
 
 #[cfg(target_os = "linux")]
 use crate::struct_wrappers::device::PciInfo;
 use crate::struct_wrappers::unit::HwbcEntry;
+use crate::structs::device::DeviceIdentity;
 
 use crate::bitmasks::InitFlags;
+use crate::high_level::watch::Watch;
+#[cfg(feature = "watch-stream")]
+use crate::high_level::watch::WatchStream;
+use crate::high_level::DeviceQuery;
+use crate::legacy::{FallbackReason, LegacyLib, SymbolFallback};
 
 #[cfg(not(target_os = "linux"))]
-const LIB_PATH: &str = "nvml.dll";
+const LIB_PATHS: &[&str] = &["nvml.dll"];
 
+// The driver only installs the versioned name; the unversioned one comes with
+// the development package
 #[cfg(target_os = "linux")]
-const LIB_PATH: &str = "libnvidia-ml.so";
+const LIB_PATHS: &[&str] = &["libnvidia-ml.so.1", "libnvidia-ml.so"];
+
+// Opens the first of the given NVML libraries that can be found, returning it
+// along with the name it was opened by.
+//
+// If a library was found but failed to load (built for another architecture,
+// say), the error from loading it is returned rather than `LibraryNotFound`.
+unsafe fn load_first_lib(paths: &[&str]) -> Result<(NvmlLib, OsString), NvmlError> {
+    let mut load_error = None;
+
+    for path in paths {
+        match NvmlLib::new(path) {
+            Ok(lib) => return Ok((lib, path.into())),
+            Err(e) if !is_not_found(&e) => load_error = Some(e),
+            Err(_) => {}
+        }
+    }
+
+    Err(load_error.map_or(NvmlError::LibraryNotFound, NvmlError::LibloadingError))
+}
+
+// Whether opening a library failed because there was no such library, as
+// opposed to one that couldn't be loaded.
+fn is_not_found(error: &libloading::Error) -> bool {
+    let message = error.to_string();
+
+    // `dlopen()` and `ERROR_MOD_NOT_FOUND` respectively
+    message.contains("No such file or directory") || message.contains("os error 126")
+}
 
 /// Determines the major version of the CUDA driver given the full version.
 ///
@@ -175,6 +260,17 @@ downloaded as part of the [CUDA toolkit](https://developer.nvidia.com/cuda-downl
 */
 pub struct Nvml {
     lib: ManuallyDrop<NvmlLib>,
+    // The name the library was opened by; see `InitDiagnostics`
+    lib_path: OsString,
+    legacy: LegacyLib,
+    // Keyed by device handle; see `Device.identity()`
+    identities: Mutex<HashMap<usize, DeviceIdentity>>,
+    // Keyed by device handle; see `Device.nvlink_throughput()`
+    nvlink_counters: Mutex<HashMap<usize, NvLinkCounters>>,
+    // See `NvmlBuilder.cache_not_supported()`
+    not_supported: NotSupportedCache,
+    // See the `wsl` module
+    wsl2: bool,
 }
 
 assert_impl_all!(Nvml: Send, Sync);
@@ -198,32 +294,48 @@ impl Nvml {
     Note that this will initialize NVML but not any GPUs. This means that NVML can
     communicate with a GPU even when other GPUs in a system are bad or unstable.
 
-    By default, initialization looks for "libnvidia-ml.so" on linux and "nvml.dll"
-    on Windows. These default names should work for default installs on those
-    platforms; if further specification is required, use `Nvml::builder`.
+    By default, initialization looks for "libnvidia-ml.so.1" (falling back to
+    "libnvidia-ml.so") on linux and "nvml.dll" on Windows. These default names
+    should work for default installs on those platforms; if further specification
+    is required, use `Nvml::builder`.
 
     # Errors
 
+    * `LibraryNotFound`, if the NVML library could not be found, e.g. because
+      the NVIDIA driver is not installed
+    * `LibloadingError`, if the NVML library was found but could not be loaded
     * `DriverNotLoaded`, if the NVIDIA driver is not running
+    * `LibRmVersionMismatch`, if the NVML library doesn't match the loaded
+      kernel module
     * `NoPermission`, if NVML does not have permission to talk to the driver
     * `Unknown`, on any unexpected error
     */
     // Checked against local
     #[doc(alias = "nvmlInit_v2")]
     pub fn init() -> Result<Self, NvmlError> {
-        Self::init_internal(LIB_PATH)
+        let (lib, lib_path) = unsafe { load_first_lib(LIB_PATHS)? };
+        Self::init_internal(lib, lib_path)
     }
 
-    fn init_internal(path: impl AsRef<std::ffi::OsStr>) -> Result<Self, NvmlError> {
-        let lib = unsafe {
-            let lib = NvmlLib::new(path)?;
-            let sym = nvml_sym(lib.nvmlInit_v2.as_ref())?;
+    fn init_internal(mut lib: NvmlLib, lib_path: OsString) -> Result<Self, NvmlError> {
+        let (lib, legacy) = unsafe {
+            let legacy = legacy::load(&mut lib);
+            let (sym, function) = nvml_fn!(lib, nvmlInit_v2)?;
 
-            nvml_try(sym())?;
-            ManuallyDrop::new(lib)
+            nvml_try_for(function, legacy.call(function, sym, |sym| sym()))
+                .map_err(|e| with_init_diagnostics(e, &lib, &lib_path))?;
+            (ManuallyDrop::new(lib), legacy)
         };
 
-        Ok(Self { lib })
+        Ok(Self {
+            lib,
+            lib_path,
+            legacy,
+            identities: Mutex::default(),
+            nvlink_counters: Mutex::default(),
+            not_supported: NotSupportedCache::default(),
+            wsl2: wsl::detect(),
+        })
     }
 
     /**
@@ -233,7 +345,10 @@ impl Nvml {
 
     # Errors
 
+    * `LibraryNotFound`, if the NVML library could not be found or opened
     * `DriverNotLoaded`, if the NVIDIA driver is not running
+    * `LibRmVersionMismatch`, if the NVML library doesn't match the loaded
+      kernel module
     * `NoPermission`, if NVML does not have permission to talk to the driver
     * `Unknown`, on any unexpected error
 
@@ -255,22 +370,33 @@ impl Nvml {
     */
     #[doc(alias = "nvmlInitWithFlags")]
     pub fn init_with_flags(flags: InitFlags) -> Result<Self, NvmlError> {
-        Self::init_with_flags_internal(LIB_PATH, flags)
+        let (lib, lib_path) = unsafe { load_first_lib(LIB_PATHS)? };
+        Self::init_with_flags_internal(lib, lib_path, flags)
     }
 
     fn init_with_flags_internal(
-        path: impl AsRef<std::ffi::OsStr>,
+        mut lib: NvmlLib,
+        lib_path: OsString,
         flags: InitFlags,
     ) -> Result<Self, NvmlError> {
-        let lib = unsafe {
-            let lib = NvmlLib::new(path)?;
-            let sym = nvml_sym(lib.nvmlInitWithFlags.as_ref())?;
+        let (lib, legacy) = unsafe {
+            let legacy = legacy::load(&mut lib);
+            let (sym, function) = nvml_fn!(lib, nvmlInitWithFlags)?;
 
-            nvml_try(sym(flags.bits()))?;
-            ManuallyDrop::new(lib)
+            nvml_try_for(function, sym(flags.bits()))
+                .map_err(|e| with_init_diagnostics(e, &lib, &lib_path))?;
+            (ManuallyDrop::new(lib), legacy)
         };
 
-        Ok(Self { lib })
+        Ok(Self {
+            lib,
+            lib_path,
+            legacy,
+            identities: Mutex::default(),
+            nvlink_counters: Mutex::default(),
+            not_supported: NotSupportedCache::default(),
+            wsl2: wsl::detect(),
+        })
     }
 
     /// Create an `NvmlBuilder` for further flexibility in how NVML is initialized.
@@ -278,6 +404,70 @@ impl Nvml {
         NvmlBuilder::default()
     }
 
+    /**
+    Forget all `NotSupported` errors remembered because of
+    `NvmlBuilder.cache_not_supported()`.
+
+    Call this after changes that can affect what devices support, such as
+    toggling MIG mode or changing the driver model.
+    */
+    pub fn clear_not_supported_cache(&self) {
+        self.not_supported.clear();
+    }
+
+    /**
+    Describes how well the loaded NVML library supports this wrapper.
+
+    See the `legacy` module for details on how older driver branches are
+    handled.
+    */
+    pub fn library_capabilities(&self) -> LibraryCapabilities {
+        LibraryCapabilities {
+            fallbacks: self.legacy.fallbacks(),
+            wsl2: self.wsl2,
+        }
+    }
+
+    /**
+    Watch every device, polling the metrics selected by `query` once per
+    `interval` and yielding updates for the values that changed.
+
+    See the `high_level::watch` module for details.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `GpuLost`, if any device has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+    */
+    pub fn watch(&self, query: DeviceQuery, interval: Duration) -> Result<Watch<'_>, NvmlError> {
+        Watch::new(self, query, interval)
+    }
+
+    /**
+    Like `.watch()`, but polls on a dedicated thread and yields the updates as
+    a `Stream` for use in async code.
+
+    Blocks while the devices are enumerated. See the `high_level::watch` module
+    for details.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `GpuLost`, if any device has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    This method is only available with the `watch-stream` feature enabled.
+    */
+    #[cfg(feature = "watch-stream")]
+    pub fn watch_stream(
+        self: Arc<Self>,
+        query: DeviceQuery,
+        interval: Duration,
+    ) -> Result<WatchStream, NvmlError> {
+        WatchStream::new(self, query, interval)
+    }
+
     /**
     Use this to shutdown NVML and release allocated resources if you care about handling
     potential errors (*the `Drop` implementation ignores errors!*).
@@ -291,17 +481,27 @@ impl Nvml {
     // Checked against local
     // Tested
     #[doc(alias = "nvmlShutdown")]
-    pub fn shutdown(mut self) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlShutdown.as_ref())?;
+    pub fn shutdown(self) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.lib, nvmlShutdown)?;
 
         unsafe {
-            nvml_try(sym())?;
+            nvml_try_for(function, sym())?;
         }
 
-        // SAFETY: we `mem::forget(self)` after this, so `self.lib` won't get
-        // touched by our `Drop` impl
-        let lib = unsafe { ManuallyDrop::take(&mut self.lib) };
-        mem::forget(self);
+        // Our `Drop` impl must not run, but every field still has to be dropped
+        let mut this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped or used again, so each field is moved
+        // out exactly once
+        let lib = unsafe {
+            drop(ptr::read(&this.lib_path));
+            drop(ptr::read(&this.legacy));
+            drop(ptr::read(&this.identities));
+            drop(ptr::read(&this.nvlink_counters));
+            drop(ptr::read(&this.not_supported));
+
+            ManuallyDrop::take(&mut this.lib)
+        };
 
         Ok(lib.__library.close()?)
     }
@@ -311,6 +511,11 @@ impl Nvml {
 
     Note that this count can include devices you do not have permission to access.
 
+    Devices excluded by the driver (see `.excluded_device_count()`) are not
+    counted and can't be obtained through `.device_by_index()`. Use
+    `.device_count_including_excluded()` for the number of devices physically
+    present.
+
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
@@ -320,16 +525,37 @@ impl Nvml {
     // Tested
     #[doc(alias = "nvmlDeviceGetCount_v2")]
     pub fn device_count(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlDeviceGetCount_v2.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlDeviceGetCount_v2)?;
 
         unsafe {
             let mut count: c_uint = mem::zeroed();
-            nvml_try(sym(&mut count))?;
+            let result = self.legacy.call(function, sym, |sym| sym(&mut count));
+
+            nvml_try_for(function, result).map_err(|e| self.with_init_diagnostics(e))?;
 
             Ok(count as u32)
         }
     }
 
+    /**
+    Get the number of compute devices in the system, including devices
+    excluded by the driver.
+
+    This is the sum of `.device_count()` and `.excluded_device_count()`. Indices
+    up to `.device_count()` are still the only ones valid for
+    `.device_by_index()`; information about excluded devices is available
+    through `.excluded_device_info()`.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `Unknown`, on any unexpected error
+    */
+    // Tested
+    pub fn device_count_including_excluded(&self) -> Result<u32, NvmlError> {
+        Ok(self.device_count()? + self.excluded_device_count()?)
+    }
+
     /**
     Gets the version of the system's graphics driver and returns it as an alphanumeric
     string.
@@ -343,18 +569,12 @@ impl Nvml {
     // Tested
     #[doc(alias = "nvmlSystemGetDriverVersion")]
     pub fn sys_driver_version(&self) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlSystemGetDriverVersion.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlSystemGetDriverVersion)?;
 
         unsafe {
-            let mut version_vec = vec![0; NVML_SYSTEM_DRIVER_VERSION_BUFFER_SIZE as usize];
-
-            nvml_try(sym(
-                version_vec.as_mut_ptr(),
-                NVML_SYSTEM_DRIVER_VERSION_BUFFER_SIZE,
-            ))?;
-
-            let version_raw = CStr::from_ptr(version_vec.as_ptr());
-            Ok(version_raw.to_str()?.into())
+            with_string_buffer(NVML_SYSTEM_DRIVER_VERSION_BUFFER_SIZE, |buffer, len| {
+                nvml_try_for(function, sym(buffer, len))
+            })
         }
     }
 
@@ -370,19 +590,13 @@ impl Nvml {
     // Tested
     #[doc(alias = "nvmlSystemGetNVMLVersion")]
     pub fn sys_nvml_version(&self) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlSystemGetNVMLVersion.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlSystemGetNVMLVersion)?;
 
+        // Thanks to `Amaranth` on IRC for help with this
         unsafe {
-            let mut version_vec = vec![0; NVML_SYSTEM_NVML_VERSION_BUFFER_SIZE as usize];
-
-            nvml_try(sym(
-                version_vec.as_mut_ptr(),
-                NVML_SYSTEM_NVML_VERSION_BUFFER_SIZE,
-            ))?;
-
-            // Thanks to `Amaranth` on IRC for help with this
-            let version_raw = CStr::from_ptr(version_vec.as_ptr());
-            Ok(version_raw.to_str()?.into())
+            with_string_buffer(NVML_SYSTEM_NVML_VERSION_BUFFER_SIZE, |buffer, len| {
+                nvml_try_for(function, sym(buffer, len))
+            })
         }
     }
 
@@ -401,11 +615,14 @@ impl Nvml {
     */
     #[doc(alias = "nvmlSystemGetCudaDriverVersion_v2")]
     pub fn sys_cuda_driver_version(&self) -> Result<i32, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlSystemGetCudaDriverVersion_v2.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlSystemGetCudaDriverVersion_v2)?;
 
         unsafe {
             let mut version: c_int = mem::zeroed();
-            nvml_try(sym(&mut version))?;
+            nvml_try_for(
+                function,
+                self.legacy.call(function, sym, |sym| sym(&mut version)),
+            )?;
 
             Ok(version)
         }
@@ -430,12 +647,12 @@ impl Nvml {
     // Tested
     #[doc(alias = "nvmlSystemGetProcessName")]
     pub fn sys_process_name(&self, pid: u32, length: usize) -> Result<String, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlSystemGetProcessName.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlSystemGetProcessName)?;
 
         unsafe {
             let mut name_vec = vec![0; length];
 
-            nvml_try(sym(pid, name_vec.as_mut_ptr(), length as c_uint))?;
+            nvml_try_for(function, sym(pid, name_vec.as_mut_ptr(), length as c_uint))?;
 
             let name_raw = CStr::from_ptr(name_vec.as_ptr());
             Ok(name_raw.to_str()?.into())
@@ -449,10 +666,8 @@ impl Nvml {
     GPUs may be initialized if the target GPU is an SLI slave.
 
     You can determine valid indices by using `.device_count()`. This
-    function doesn't call that for you, but the actual C function to get
-    the device handle will return an error in the case of an invalid index.
-    This means that the `InvalidArg` error will be returned if you pass in
-    an invalid index.
+    function only calls that for you if NVML rejects the index, to report how
+    many devices are present in the `DeviceIndexOutOfRange` error.
 
     NVIDIA's docs state that "The order in which NVML enumerates devices has
     no guarantees of consistency between reboots. For that reason it is recommended
@@ -464,7 +679,8 @@ impl Nvml {
     # Errors
 
     * `Uninitialized`, if the library has not been successfully initialized
-    * `InvalidArg`, if index is invalid
+    * `DeviceIndexOutOfRange`, if `index` is not less than the device count
+    * `InvalidArg`, if index is invalid for any other reason
     * `InsufficientPower`, if any attached devices have improperly attached external power cables
     * `NoPermission`, if the user doesn't have permission to talk to this device
     * `IrqIssue`, if the NVIDIA kernel detected an interrupt issue with the attached GPUs
@@ -475,13 +691,34 @@ impl Nvml {
     // Tested
     #[doc(alias = "nvmlDeviceGetHandleByIndex_v2")]
     pub fn device_by_index(&self, index: u32) -> Result<Device, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlDeviceGetHandleByIndex_v2.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlDeviceGetHandleByIndex_v2)?;
 
         unsafe {
             let mut device: nvmlDevice_t = mem::zeroed();
-            nvml_try(sym(index, &mut device))?;
 
-            Ok(Device::new(device, self))
+            let result = self
+                .legacy
+                .call(function, sym, |sym| sym(index, &mut device));
+
+            match nvml_try_for(function, result) {
+                Ok(()) => Ok(Device::with_index(device, self, index)),
+                Err(NvmlError::InvalidArg) => Err(self.invalid_index_error(index)),
+                Err(e) => Err(self.with_init_diagnostics(e)),
+            }
+        }
+    }
+
+    // The first calls made after initialization are where a broken driver
+    // install tends to show up, so these get the same diagnostics as `init()`.
+    fn with_init_diagnostics(&self, error: NvmlError) -> NvmlError {
+        with_init_diagnostics(error, &self.lib, &self.lib_path)
+    }
+
+    // Tells an out of range index apart from an otherwise invalid one.
+    fn invalid_index_error(&self, index: u32) -> NvmlError {
+        match self.device_count() {
+            Ok(count) if index >= count => NvmlError::DeviceIndexOutOfRange { index, count },
+            _ => NvmlError::InvalidArg,
         }
     }
 
@@ -512,13 +749,17 @@ impl Nvml {
     where
         Vec<u8>: From<S>,
     {
-        let sym = nvml_sym(self.lib.nvmlDeviceGetHandleByPciBusId_v2.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlDeviceGetHandleByPciBusId_v2)?;
 
         unsafe {
             let c_string = CString::new(pci_bus_id)?;
             let mut device: nvmlDevice_t = mem::zeroed();
 
-            nvml_try(sym(c_string.as_ptr(), &mut device))?;
+            nvml_try_for(
+                function,
+                self.legacy
+                    .call(function, sym, |sym| sym(c_string.as_ptr(), &mut device)),
+            )?;
 
             Ok(Device::new(device, self))
         }
@@ -533,13 +774,13 @@ impl Nvml {
     where
         Vec<u8>: From<S>,
     {
-        let sym = nvml_sym(self.lib.nvmlDeviceGetHandleBySerial.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlDeviceGetHandleBySerial)?;
 
         unsafe {
             let c_string = CString::new(board_serial)?;
             let mut device: nvmlDevice_t = mem::zeroed();
 
-            nvml_try(sym(c_string.as_ptr(), &mut device))?;
+            nvml_try_for(function, sym(c_string.as_ptr(), &mut device))?;
 
             Ok(Device::new(device, self))
         }
@@ -572,18 +813,74 @@ impl Nvml {
     where
         Vec<u8>: From<S>,
     {
-        let sym = nvml_sym(self.lib.nvmlDeviceGetHandleByUUID.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlDeviceGetHandleByUUID)?;
 
         unsafe {
             let c_string = CString::new(uuid)?;
             let mut device: nvmlDevice_t = mem::zeroed();
 
-            nvml_try(sym(c_string.as_ptr(), &mut device))?;
+            nvml_try_for(function, sym(c_string.as_ptr(), &mut device))?;
 
             Ok(Device::new(device, self))
         }
     }
 
+    /**
+    Iterate over every device in the system, in index order.
+
+    The device count is read once, up front; each device is then obtained with
+    `.device_by_index()` as the iterator advances.
+
+    ```no_run
+    # use nvml_wrapper::Nvml;
+    # use nvml_wrapper::error::*;
+    # fn main() -> Result<(), NvmlError> {
+    let nvml = Nvml::init()?;
+
+    for device in nvml.devices()? {
+        let device = device?;
+        println!("{}: {}", device.index()?, device.name()?);
+    }
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `Unknown`, on any unexpected error
+
+    Errors from `.device_by_index()` are yielded by the iterator.
+    */
+    // Tested
+    pub fn devices(&self) -> Result<Devices<'_>, NvmlError> {
+        Ok(Devices {
+            nvml: self,
+            indices: 0..self.device_count()?,
+        })
+    }
+
+    /**
+    Gets every device in the system, keyed by UUID.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `GpuLost`, if a `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    Errors from `.device_by_index()` are returned as well.
+    */
+    // Tested
+    pub fn devices_by_uuid(&self) -> Result<HashMap<String, Device<'_>>, NvmlError> {
+        self.devices()?
+            .map(|device| {
+                let device = device?;
+                Ok((device.uuid()?, device))
+            })
+            .collect()
+    }
+
     /**
     Gets the common ancestor for two devices.
 
@@ -609,12 +906,15 @@ impl Nvml {
         device1: &Device,
         device2: &Device,
     ) -> Result<TopologyLevel, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlDeviceGetTopologyCommonAncestor.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlDeviceGetTopologyCommonAncestor)?;
 
         unsafe {
             let mut level: nvmlGpuTopologyLevel_t = mem::zeroed();
 
-            nvml_try(sym(device1.handle(), device2.handle(), &mut level))?;
+            nvml_try_for(
+                function,
+                sym(device1.handle(), device2.handle(), &mut level),
+            )?;
 
             TopologyLevel::try_from(level)
         }
@@ -644,11 +944,11 @@ impl Nvml {
     // Tested (for an error)
     #[doc(alias = "nvmlUnitGetHandleByIndex")]
     pub fn unit_by_index(&self, index: u32) -> Result<Unit, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlUnitGetHandleByIndex.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlUnitGetHandleByIndex)?;
 
         unsafe {
             let mut unit: nvmlUnit_t = mem::zeroed();
-            nvml_try(sym(index as c_uint, &mut unit))?;
+            nvml_try_for(function, sym(index as c_uint, &mut unit))?;
 
             Ok(Unit::new(unit, self))
         }
@@ -675,12 +975,15 @@ impl Nvml {
         device1: &Device,
         device2: &Device,
     ) -> Result<bool, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlDeviceOnSameBoard.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlDeviceOnSameBoard)?;
 
         unsafe {
             let mut bool_int: c_int = mem::zeroed();
 
-            nvml_try(sym(device1.handle(), device2.handle(), &mut bool_int))?;
+            nvml_try_for(
+                function,
+                sym(device1.handle(), device2.handle(), &mut bool_int),
+            )?;
 
             match bool_int {
                 0 => Ok(false),
@@ -689,6 +992,45 @@ impl Nvml {
         }
     }
 
+    /**
+    Gets the devices in the system grouped by the physical board they are on.
+
+    Useful for policies that act per board, such as power and thermal limits on
+    dual-GPU boards or SXM baseboards. Boards are ordered by the index of their
+    first device, and devices within a board by index.
+
+    Devices are grouped using `Device.is_on_same_board_as()`. Devices that
+    don't support that check are each put on a board of their own.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `GpuLost`, if a `Device` has fallen off the bus or is otherwise inaccessible
+    * `Unknown`, on any unexpected error
+
+    Errors from `.device_by_index()` are returned as well.
+    */
+    // Tested
+    pub fn boards(&self) -> Result<Vec<Board<'_>>, NvmlError> {
+        let devices = self.devices()?.collect::<Result<Vec<_>, _>>()?;
+
+        let groups = group(devices, |a, b| {
+            a.is_on_same_board_as(b)
+                .optional()
+                .map(|same| same.unwrap_or(false))
+        })?;
+
+        groups
+            .into_iter()
+            .map(|devices| {
+                Ok(Board {
+                    id: devices[0].board_id().optional()?,
+                    devices,
+                })
+            })
+            .collect()
+    }
+
     /**
     Gets the set of GPUs that have a CPU affinity with the given CPU number.
 
@@ -706,7 +1048,7 @@ impl Nvml {
     #[cfg(target_os = "linux")]
     #[doc(alias = "nvmlSystemGetTopologyGpuSet")]
     pub fn topology_gpu_set(&self, cpu_number: u32) -> Result<Vec<Device>, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlSystemGetTopologyGpuSet.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlSystemGetTopologyGpuSet)?;
 
         unsafe {
             let mut count = match self.topology_gpu_set_count(cpu_number)? {
@@ -715,7 +1057,8 @@ impl Nvml {
             };
             let mut devices: Vec<nvmlDevice_t> = vec![mem::zeroed(); count as usize];
 
-            nvml_try(sym(cpu_number, &mut count, devices.as_mut_ptr()))?;
+            nvml_try_for(function, sym(cpu_number, &mut count, devices.as_mut_ptr()))
+                .map_err(|e| e.with_required_size(count))?;
 
             Ok(devices.into_iter().map(|d| Device::new(d, self)).collect())
         }
@@ -724,14 +1067,14 @@ impl Nvml {
     // Helper function for the above.
     #[cfg(target_os = "linux")]
     fn topology_gpu_set_count(&self, cpu_number: u32) -> Result<c_uint, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlSystemGetTopologyGpuSet.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlSystemGetTopologyGpuSet)?;
 
         unsafe {
             // Indicates that we want the count
             let mut count: c_uint = 0;
 
             // Passing null doesn't indicate that we want the count, just allowed
-            nvml_try(sym(cpu_number, &mut count, ptr::null_mut()))?;
+            nvml_try_for(function, sym(cpu_number, &mut count, ptr::null_mut()))?;
 
             Ok(count)
         }
@@ -752,7 +1095,7 @@ impl Nvml {
     // Tested
     #[doc(alias = "nvmlSystemGetHicVersion")]
     pub fn hic_versions(&self) -> Result<Vec<HwbcEntry>, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlSystemGetHicVersion.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlSystemGetHicVersion)?;
 
         unsafe {
             let mut count: c_uint = match self.hic_count()? {
@@ -761,7 +1104,8 @@ impl Nvml {
             };
             let mut hics: Vec<nvmlHwbcEntry_t> = vec![mem::zeroed(); count as usize];
 
-            nvml_try(sym(&mut count, hics.as_mut_ptr()))?;
+            nvml_try_for(function, sym(&mut count, hics.as_mut_ptr()))
+                .map_err(|e| e.with_required_size(count))?;
 
             hics.into_iter().map(HwbcEntry::try_from).collect()
         }
@@ -781,7 +1125,7 @@ impl Nvml {
     // Tested as part of the above method
     #[doc(alias = "nvmlSystemGetHicVersion")]
     pub fn hic_count(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlSystemGetHicVersion.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlSystemGetHicVersion)?;
 
         unsafe {
             /*
@@ -805,7 +1149,7 @@ impl Nvml {
                     Ok(count)
                 }
                 // We know that this will be an error
-                other => nvml_try(other).map(|_| 0),
+                other => nvml_try_for(function, other).map(|_| 0),
             }
         }
     }
@@ -826,11 +1170,11 @@ impl Nvml {
     // Tested
     #[doc(alias = "nvmlUnitGetCount")]
     pub fn unit_count(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlUnitGetCount.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlUnitGetCount)?;
 
         unsafe {
             let mut count: c_uint = mem::zeroed();
-            nvml_try(sym(&mut count))?;
+            nvml_try_for(function, sym(&mut count))?;
 
             Ok(count)
         }
@@ -852,11 +1196,11 @@ impl Nvml {
     // Tested
     #[doc(alias = "nvmlEventSetCreate")]
     pub fn create_event_set(&self) -> Result<EventSet, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlEventSetCreate.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlEventSetCreate)?;
 
         unsafe {
             let mut set: nvmlEventSet_t = mem::zeroed();
-            nvml_try(sym(&mut set))?;
+            nvml_try_for(function, sym(&mut set))?;
 
             Ok(EventSet::new(set, self))
         }
@@ -907,9 +1251,9 @@ impl Nvml {
     #[cfg(target_os = "linux")]
     #[doc(alias = "nvmlDeviceDiscoverGpus")]
     pub fn discover_gpus(&self, pci_info: PciInfo) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlDeviceDiscoverGpus.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlDeviceDiscoverGpus)?;
 
-        unsafe { nvml_try(sym(&mut pci_info.try_into()?)) }
+        unsafe { nvml_try_for(function, sym(&mut pci_info.try_into()?)) }
     }
 
     /**
@@ -921,12 +1265,12 @@ impl Nvml {
     */
     #[doc(alias = "nvmlGetExcludedDeviceCount")]
     pub fn excluded_device_count(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlGetExcludedDeviceCount.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlGetExcludedDeviceCount)?;
 
         unsafe {
             let mut count: c_uint = mem::zeroed();
 
-            nvml_try(sym(&mut count))?;
+            nvml_try_for(function, sym(&mut count))?;
             Ok(count)
         }
     }
@@ -945,15 +1289,76 @@ impl Nvml {
     */
     #[doc(alias = "nvmlGetExcludedDeviceInfoByIndex")]
     pub fn excluded_device_info(&self, index: u32) -> Result<ExcludedDeviceInfo, NvmlError> {
-        let sym = nvml_sym(self.lib.nvmlGetExcludedDeviceInfoByIndex.as_ref())?;
+        let (sym, function) = nvml_fn!(self.lib, nvmlGetExcludedDeviceInfoByIndex)?;
 
         unsafe {
             let mut info: nvmlExcludedDeviceInfo_t = mem::zeroed();
 
-            nvml_try(sym(index, &mut info))?;
+            nvml_try_for(function, sym(index, &mut info))?;
             ExcludedDeviceInfo::try_from(info)
         }
     }
+
+    /**
+    Gets the range of guest vGPU versions supported by the vGPU manager on this
+    host, along with the range currently allowed.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `NotSupported`, if vGPU is not supported by the system
+    * `Unknown`, on any unexpected error
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    #[doc(alias = "nvmlGetVgpuVersion")]
+    pub fn vgpu_version(&self) -> Result<VgpuVersions, NvmlError> {
+        let (sym, function) = nvml_fn!(self.lib, nvmlGetVgpuVersion)?;
+
+        unsafe {
+            let mut supported: nvmlVgpuVersion_t = mem::zeroed();
+            let mut current: nvmlVgpuVersion_t = mem::zeroed();
+
+            nvml_try_for(function, sym(&mut supported, &mut current))?;
+
+            Ok(VgpuVersions {
+                supported: supported.into(),
+                current: current.into(),
+            })
+        }
+    }
+
+    /**
+    Restricts the range of guest vGPU versions allowed on this host.
+
+    Narrowing the range lets a host refuse guests with drivers it should not
+    run alongside, e.g. while rolling out a driver upgrade. The range must lie
+    within the supported range reported by `.vgpu_version()`, and can only be
+    changed while no VMs with vGPUs are running.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if `range.min` is greater than `range.max`
+    * `NotSupported`, if vGPU is not supported by the system or `range` is
+      outside of the supported range
+    * `InUse`, if a VM with a vGPU is running
+    * `Unknown`, on any unexpected error
+
+    # Environment Support
+
+    Only meaningful on vGPU hosts.
+    */
+    // Checked against local
+    #[doc(alias = "nvmlSetVgpuVersion")]
+    pub fn set_vgpu_version(&self, range: VgpuVersionRange) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.lib, nvmlSetVgpuVersion)?;
+
+        unsafe { nvml_try_for(function, sym(&mut range.into())) }
+    }
 }
 
 /// This `Drop` implementation ignores errors! Use the `.shutdown()` method on
@@ -971,6 +1376,119 @@ impl Drop for Nvml {
     }
 }
 
+/// A physical board and the devices on it, returned from `Nvml.boards()`.
+#[derive(Debug)]
+pub struct Board<'nvml> {
+    /// The board ID shared by the devices, if reported. See `Device.board_id()`.
+    pub id: Option<u32>,
+    /// The devices on this board, ordered by index. Never empty.
+    pub devices: Vec<Device<'nvml>>,
+}
+
+impl<'nvml> Board<'nvml> {
+    /// Whether or not this board has more than one device on it.
+    pub fn is_multi_gpu(&self) -> bool {
+        self.devices.len() > 1
+    }
+}
+
+/// An iterator over every device in the system, returned from `Nvml.devices()`.
+#[derive(Debug, Clone)]
+pub struct Devices<'nvml> {
+    nvml: &'nvml Nvml,
+    indices: Range<u32>,
+}
+
+impl<'nvml> Iterator for Devices<'nvml> {
+    type Item = Result<Device<'nvml>, NvmlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices
+            .next()
+            .map(|index| self.nvml.device_by_index(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<'nvml> DoubleEndedIterator for Devices<'nvml> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.indices
+            .next_back()
+            .map(|index| self.nvml.device_by_index(index))
+    }
+}
+
+impl<'nvml> ExactSizeIterator for Devices<'nvml> {}
+
+impl<'nvml> FusedIterator for Devices<'nvml> {}
+
+// Groups items for which `same` returns true, comparing each item against the
+// first item of every existing group. Keeps the order of first appearance.
+fn group<T, E>(
+    items: Vec<T>,
+    mut same: impl FnMut(&T, &T) -> Result<bool, E>,
+) -> Result<Vec<Vec<T>>, E> {
+    let mut groups: Vec<Vec<T>> = vec![];
+
+    'items: for item in items {
+        for group in &mut groups {
+            if same(&group[0], &item)? {
+                group.push(item);
+                continue 'items;
+            }
+        }
+
+        groups.push(vec![item]);
+    }
+
+    Ok(groups)
+}
+
+/// Returned from `Nvml.library_capabilities()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct LibraryCapabilities {
+    /// Functions that were replaced with an ancestor from an older driver
+    /// branch, either because the loaded library does not support them or
+    /// because of the targeted branch (see `legacy::TARGET_VERSION`).
+    pub fallbacks: Vec<SymbolFallback>,
+    /**
+    Whether or not NVML is running under WSL2, where some functions are
+    unavailable regardless of the device (see `.unavailable_functions()`).
+
+    When those functions fail with `NotSupported` there, `wsl2` is set in the
+    error's `UnsupportedContext`. Some driver versions fail them with `Unknown`
+    or `NoPermission` instead, which are returned as-is.
+    */
+    pub wsl2: bool,
+}
+
+impl LibraryCapabilities {
+    /// Whether or not any functions had to be replaced with an ancestor
+    /// because the loaded library does not support them, or are unavailable
+    /// because of WSL2.
+    pub fn is_degraded(&self) -> bool {
+        self.wsl2
+            || self
+                .fallbacks
+                .iter()
+                .any(|f| f.reason != FallbackReason::Targeted)
+    }
+
+    /// The NVML functions known to be unavailable in this environment, e.g.
+    /// `nvmlDeviceGetComputeRunningProcesses_v3` under WSL2.
+    pub fn unavailable_functions(&self) -> &'static [&'static str] {
+        if self.wsl2 {
+            wsl::unavailable()
+        } else {
+            &[]
+        }
+    }
+}
+
 /**
 A builder struct that provides further flexibility in how NVML is initialized.
 
@@ -998,6 +1516,7 @@ let init_result = Nvml::builder().lib_path(OsStr::new("/some/path/to/libnvidia-m
 pub struct NvmlBuilder<'a> {
     lib_path: Option<&'a OsStr>,
     flags: InitFlags,
+    not_supported_period: Option<Duration>,
 }
 
 impl<'a> NvmlBuilder<'a> {
@@ -1005,7 +1524,9 @@ impl<'a> NvmlBuilder<'a> {
     Set the path to the NVML lib file.
 
     See [`libloading`'s docs][libloading] for details about how this lib path is
-    handled.
+    handled. Failing to open it is reported as a `LibloadingError` describing why,
+    rather than the `LibraryNotFound` returned when none of the default libraries
+    can be found.
 
     [libloading]: https://docs.rs/libloading/0.6.6/libloading/struct.Library.html#method.new
     */
@@ -1020,15 +1541,46 @@ impl<'a> NvmlBuilder<'a> {
         self
     }
 
+    /**
+    Remember `NotSupported` errors returned from `Device` methods for the
+    given period.
+
+    While a `NotSupported` result is remembered, further calls to the same NVML
+    function for the same device return `NotSupported` right away instead of
+    asking the driver again. This saves time for callers that repeatedly poll
+    many metrics, most of which a given device will never support.
+
+    Results are remembered per device, NVML function and relevant input (e.g.
+    the clock type passed to `Device.clock_info()`). Setters and other calls
+    that change device state are never skipped. Use a period short enough to
+    pick up changes in support, such as those caused by toggling MIG mode, or
+    call `Nvml.clear_not_supported_cache()` after such changes. Periods too long
+    to be added to the current time, like `Duration::MAX`, never expire.
+
+    Disabled by default.
+    */
+    pub fn cache_not_supported(&mut self, period: Duration) -> &mut Self {
+        self.not_supported_period = Some(period);
+        self
+    }
+
     /// Perform initialization.
     pub fn init(&self) -> Result<Nvml, NvmlError> {
-        let lib_path = self.lib_path.unwrap_or_else(|| LIB_PATH.as_ref());
+        let (lib, lib_path) = unsafe {
+            match self.lib_path {
+                Some(path) => (NvmlLib::new(path)?, path.into()),
+                None => load_first_lib(LIB_PATHS)?,
+            }
+        };
 
-        if self.flags.is_empty() {
-            Nvml::init_internal(lib_path)
+        let mut nvml = if self.flags.is_empty() {
+            Nvml::init_internal(lib, lib_path)?
         } else {
-            Nvml::init_with_flags_internal(lib_path, self.flags)
-        }
+            Nvml::init_with_flags_internal(lib, lib_path, self.flags)?
+        };
+
+        nvml.not_supported = NotSupportedCache::new(self.not_supported_period);
+        Ok(nvml)
     }
 }
 
@@ -1039,11 +1591,28 @@ mod test {
     use crate::error::NvmlError;
     use crate::test_utils::*;
 
+    #[test]
+    fn lib_not_found() {
+        let missing = "libnvidia-ml-does-not-exist.so";
+
+        let result = unsafe { load_first_lib(&[missing, "libnvidia-ml-neither.so"]) };
+        assert!(matches!(result, Err(NvmlError::LibraryNotFound)));
+
+        // An explicitly given path reports why it couldn't be opened
+        let result = Nvml::builder().lib_path(OsStr::new(missing)).init();
+        assert!(matches!(result, Err(NvmlError::LibloadingError(_))));
+    }
+
     #[test]
     fn init_with_flags() {
         Nvml::init_with_flags(InitFlags::NO_GPUS).unwrap();
     }
 
+    #[test]
+    fn library_capabilities() {
+        test(3, || Ok(nvml().library_capabilities()))
+    }
+
     #[test]
     fn shutdown() {
         test(3, || nvml().shutdown())
@@ -1054,6 +1623,16 @@ mod test {
         test(3, || nvml().device_count())
     }
 
+    #[test]
+    fn device_count_including_excluded() {
+        let nvml = nvml();
+        test(3, || {
+            let count = nvml.device_count_including_excluded()?;
+            assert!(count >= nvml.device_count()?);
+            Ok(count)
+        })
+    }
+
     #[test]
     fn sys_driver_version() {
         test(3, || nvml().sys_driver_version())
@@ -1089,7 +1668,7 @@ mod test {
         test_with_device(3, &nvml, |device| {
             let processes = device.running_graphics_processes()?;
             match nvml.sys_process_name(processes[0].pid, 64) {
-                Err(NvmlError::NoPermission) => Ok("No permission error".into()),
+                Err(NvmlError::NoPermission(_)) => Ok("No permission error".into()),
                 v => v,
             }
         })
@@ -1101,6 +1680,43 @@ mod test {
         test(3, || nvml.device_by_index(0))
     }
 
+    #[test]
+    fn devices() {
+        let nvml = nvml();
+        test(3, || {
+            let devices = nvml.devices()?;
+            assert_eq!(devices.len() as u32, nvml.device_count()?);
+
+            devices.collect::<Result<Vec<_>, _>>()
+        })
+    }
+
+    #[test]
+    fn devices_by_uuid() {
+        let nvml = nvml();
+        test(3, || {
+            let devices = nvml.devices_by_uuid()?;
+            assert_eq!(devices.len() as u32, nvml.device_count()?);
+
+            for (uuid, device) in &devices {
+                assert_eq!(&device.uuid()?, uuid);
+            }
+
+            Ok(devices.len() as u32)
+        })
+    }
+
+    #[test]
+    fn device_by_index_out_of_range() {
+        let nvml = nvml();
+        let count = nvml.device_count().expect("count");
+
+        assert!(matches!(
+            nvml.device_by_index(count),
+            Err(NvmlError::DeviceIndexOutOfRange { index, count: c }) if index == count && c == count
+        ));
+    }
+
     #[test]
     fn device_by_pci_bus_id() {
         let nvml = nvml();
@@ -1167,6 +1783,41 @@ mod test {
     }
 
     #[cfg(target_os = "linux")]
+    #[test]
+    fn boards() {
+        let nvml = nvml();
+        test(3, || {
+            let boards = nvml.boards()?;
+            let count: usize = boards.iter().map(|b| b.devices.len()).sum();
+            assert_eq!(count, nvml.device_count()? as usize);
+            Ok(boards)
+        })
+    }
+
+    #[test]
+    fn group_by_first_item() {
+        let groups = group(vec![1, 2, 11, 3, 12, 21], |a, b| {
+            Ok::<_, ()>(a / 10 == b / 10)
+        });
+
+        assert_eq!(groups, Ok(vec![vec![1, 2, 3], vec![11, 12], vec![21]]));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn load_first_lib_errors() {
+        let missing = unsafe { load_first_lib(&["/nonexistent/libnvidia-ml.so.1"]) };
+        assert_eq!(missing.err(), Some(NvmlError::LibraryNotFound));
+
+        // Exists, but isn't a shared library
+        let path = std::env::temp_dir().join("nvml-wrapper-not-a-library.so");
+        std::fs::write(&path, b"not a library").unwrap();
+        let path = path.to_str().unwrap();
+
+        let invalid = unsafe { load_first_lib(&["/nonexistent/libnvidia-ml.so.1", path]) };
+        assert!(matches!(invalid, Err(NvmlError::LibloadingError(_))));
+    }
+
     #[test]
     fn topology_gpu_set() {
         let nvml = nvml();
@@ -1200,7 +1851,7 @@ mod test {
 
             // We don't test with admin perms and therefore expect an error
             match nvml.discover_gpus(pci_info) {
-                Err(NvmlError::NoPermission) => panic!("NoPermission"),
+                Err(NvmlError::NoPermission(_)) => panic!("NoPermission"),
                 other => other,
             }
         })
@@ -1220,4 +1871,21 @@ mod test {
             test(3, || nvml.excluded_device_info(0))
         }
     }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn vgpu_version() {
+        let nvml = nvml();
+        test(3, || nvml.vgpu_version())
+    }
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn set_vgpu_version() {
+        let nvml = nvml();
+        let versions = nvml.vgpu_version().expect("vgpu versions");
+
+        nvml.set_vgpu_version(versions.current)
+            .expect("set to the current range");
+    }
 }
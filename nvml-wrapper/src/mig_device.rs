@@ -0,0 +1,655 @@
+/*!
+MIG (Multi-Instance GPU) devices.
+
+When MIG mode is enabled (see `Device.is_mig_enabled()`), a GPU is split into
+slices that each look like a smaller GPU to the processes running on them.
+NVML exposes every slice as a handle of its own, which `MigDevice` wraps:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+let nvml = Nvml::init()?;
+let device = nvml.device_by_index(0)?;
+
+for mig_device in device.mig_devices()? {
+    let snapshot = mig_device.snapshot()?;
+
+    if let Some(memory) = snapshot.memory {
+        println!("{}: {} of {} bytes used", snapshot.uuid, memory.used, memory.total);
+    }
+}
+# Ok(())
+# }
+```
+
+Only a subset of the `Device` methods accept MIG device handles; most others
+return `NotSupported` (or `InvalidArg`) for them. `MigDevice.device()` gives
+access to the ones that do.
+
+The slices themselves are managed as GPU instances (see
+`Device.create_gpu_instance()`), each of which is further split into one or
+more compute instances (see `GpuInstance.create_compute_instance()`); every
+compute instance shows up as a MIG device. `high_level::mig_config` builds a
+declarative layout on top of these.
+*/
+
+use crate::enums::device::ComputeInstanceProfile;
+use crate::error::{nvml_fn, nvml_try_for, NvmlError, NvmlResultExt};
+use crate::ffi::bindings::*;
+use crate::struct_wrappers::device::{
+    ComputeInstanceInfo, ComputeInstanceProfileInfo, DeviceAttributes, GpuInstanceInfo, MemoryInfo,
+    ProcessInfo, Utilization,
+};
+use crate::{Device, Nvml};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{mem, os::raw::c_uint};
+
+use static_assertions::assert_impl_all;
+
+/**
+Struct that represents a MIG device (a slice of a GPU in MIG mode).
+
+Obtain this via `Device.mig_device_by_index()` or `Device.mig_devices()`. Its
+lifetime is tied to the `Nvml` instance it was obtained from, just like a
+`Device`'s.
+*/
+#[derive(Debug)]
+pub struct MigDevice<'nvml> {
+    device: Device<'nvml>,
+    index: u32,
+}
+
+assert_impl_all!(MigDevice: Send, Sync);
+
+/**
+Struct that represents a MIG GPU instance, a slice of a GPU with its own
+memory and engines.
+
+Obtain this via `Device.gpu_instances()`, `Device.gpu_instance_by_id()` or
+`Device.create_gpu_instance()`. Its lifetime is tied to the `Nvml` instance it
+was obtained from, just like a `Device`'s.
+*/
+#[derive(Debug)]
+pub struct GpuInstance<'nvml> {
+    instance: nvmlGpuInstance_t,
+    nvml: &'nvml Nvml,
+}
+
+unsafe impl<'nvml> Send for GpuInstance<'nvml> {}
+unsafe impl<'nvml> Sync for GpuInstance<'nvml> {}
+
+assert_impl_all!(GpuInstance: Send, Sync);
+
+/**
+Struct that represents a MIG compute instance, a share of a GPU instance's
+multiprocessors that shows up as a MIG device.
+
+Obtain this via `GpuInstance.compute_instances()` or
+`GpuInstance.create_compute_instance()`. Its lifetime is tied to the `Nvml`
+instance it was obtained from, just like a `Device`'s.
+*/
+#[derive(Debug)]
+pub struct ComputeInstance<'nvml> {
+    instance: nvmlComputeInstance_t,
+    nvml: &'nvml Nvml,
+}
+
+unsafe impl<'nvml> Send for ComputeInstance<'nvml> {}
+unsafe impl<'nvml> Sync for ComputeInstance<'nvml> {}
+
+assert_impl_all!(ComputeInstance: Send, Sync);
+
+/// Returned from `MigDevice.snapshot()`.
+///
+/// Metrics the MIG device doesn't support are `None`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MigDeviceSnapshot {
+    /// The index of the MIG device within its parent GPU.
+    pub index: u32,
+    pub uuid: String,
+    pub gpu_instance_id: u32,
+    pub compute_instance_id: u32,
+    pub attributes: Option<DeviceAttributes>,
+    pub memory: Option<MemoryInfo>,
+    /// Not reported by current drivers, which only report utilization for
+    /// full GPUs.
+    pub utilization: Option<Utilization>,
+    pub compute_processes: Option<Vec<ProcessInfo>>,
+    pub graphics_processes: Option<Vec<ProcessInfo>>,
+}
+
+impl<'nvml> MigDevice<'nvml> {
+    pub(crate) fn new(device: Device<'nvml>, index: u32) -> Self {
+        Self { device, index }
+    }
+
+    /// Obtain the MIG device handle as a `Device`, for use with the `Device`
+    /// methods that support MIG devices (such as `Device.memory_info()`).
+    pub fn device(&self) -> &Device<'nvml> {
+        &self.device
+    }
+
+    /// The index of this MIG device within its parent GPU, as passed to
+    /// `Device.mig_device_by_index()`.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /**
+    Gets the ID of the GPU instance this MIG device belongs to.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `MigDevice` is invalid
+    * `NotSupported`, if this `MigDevice` is not a MIG device
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+    */
+    // Tested
+    #[doc(alias = "nvmlDeviceGetGpuInstanceId")]
+    pub fn gpu_instance_id(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.device.nvml().lib, nvmlDeviceGetGpuInstanceId)?;
+
+        unsafe {
+            let mut id: c_uint = mem::zeroed();
+
+            nvml_try_for(function, sym(self.device.handle(), &mut id))?;
+
+            Ok(id)
+        }
+    }
+
+    /**
+    Gets the ID of the compute instance this MIG device belongs to.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `MigDevice` is invalid
+    * `NotSupported`, if this `MigDevice` is not a MIG device
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+    */
+    // Tested
+    #[doc(alias = "nvmlDeviceGetComputeInstanceId")]
+    pub fn compute_instance_id(&self) -> Result<u32, NvmlError> {
+        let (sym, function) = nvml_fn!(self.device.nvml().lib, nvmlDeviceGetComputeInstanceId)?;
+
+        unsafe {
+            let mut id: c_uint = mem::zeroed();
+
+            nvml_try_for(function, sym(self.device.handle(), &mut id))?;
+
+            Ok(id)
+        }
+    }
+
+    /**
+    Gets the resources (multiprocessors, engines, memory) of this MIG device.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `MigDevice` is invalid
+    * `NotSupported`, if this `MigDevice` does not support this feature
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+    */
+    // Tested
+    #[doc(alias = "nvmlDeviceGetAttributes_v2")]
+    pub fn attributes(&self) -> Result<DeviceAttributes, NvmlError> {
+        self.device.attributes()
+    }
+
+    /**
+    Gets a snapshot of this MIG device: its identity, attributes, memory usage,
+    utilization and running processes.
+
+    Metrics this MIG device doesn't support are left `None` in the returned
+    `MigDeviceSnapshot` rather than failing the whole snapshot.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `MigDevice` is invalid
+    * `NotSupported`, if this `MigDevice` is not a MIG device
+    * `GpuLost`, if the parent GPU has fallen off the bus or is otherwise inaccessible
+    * `Utf8Error`, if the UUID obtained from the C library is not valid UTF-8
+    * `Unknown`, on any unexpected error
+    */
+    // Tested
+    pub fn snapshot(&self) -> Result<MigDeviceSnapshot, NvmlError> {
+        Ok(MigDeviceSnapshot {
+            index: self.index,
+            uuid: self.device.uuid()?,
+            gpu_instance_id: self.gpu_instance_id()?,
+            compute_instance_id: self.compute_instance_id()?,
+            attributes: self.attributes().optional()?,
+            memory: self.device.memory_info().optional()?,
+            utilization: self.device.utilization_rates().optional()?,
+            compute_processes: self.device.running_compute_processes().optional()?,
+            graphics_processes: self.device.running_graphics_processes().optional()?,
+        })
+    }
+}
+
+impl<'nvml> GpuInstance<'nvml> {
+    pub(crate) fn new(instance: nvmlGpuInstance_t, nvml: &'nvml Nvml) -> Self {
+        Self { instance, nvml }
+    }
+
+    /// Get the raw GPU instance handle contained in this struct.
+    ///
+    /// Sometimes necessary for C interop.
+    ///
+    /// # Safety
+    ///
+    /// This is unsafe to prevent it from being used without care. In
+    /// particular, you must avoid creating a new `GpuInstance` from this
+    /// handle and allowing both this `GpuInstance` and the newly created one
+    /// to drop (which would result in a double-free).
+    pub unsafe fn handle(&self) -> nvmlGpuInstance_t {
+        self.instance
+    }
+
+    /**
+    Gets the ID, profile and placement of this GPU instance.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `GpuInstance` is invalid
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlGpuInstanceGetInfo")]
+    pub fn info(&self) -> Result<GpuInstanceInfo, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlGpuInstanceGetInfo)?;
+
+        unsafe {
+            let mut info: nvmlGpuInstanceInfo_t = mem::zeroed();
+            nvml_try_for(function, sym(self.instance, &mut info))?;
+
+            Ok(info.into())
+        }
+    }
+
+    /**
+    Gets the resources of the given compute instance profile within this GPU
+    instance.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `GpuInstance` is invalid
+    * `NotSupported`, if `profile` isn't supported
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlGpuInstanceGetComputeInstanceProfileInfo")]
+    pub fn compute_instance_profile_info(
+        &self,
+        profile: ComputeInstanceProfile,
+    ) -> Result<ComputeInstanceProfileInfo, NvmlError> {
+        let (sym, function) =
+            nvml_fn!(self.nvml.lib, nvmlGpuInstanceGetComputeInstanceProfileInfo)?;
+
+        unsafe {
+            let mut info: nvmlComputeInstanceProfileInfo_t = mem::zeroed();
+
+            nvml_try_for(
+                function,
+                sym(
+                    self.instance,
+                    profile.as_c(),
+                    NVML_COMPUTE_INSTANCE_ENGINE_PROFILE_SHARED,
+                    &mut info,
+                ),
+            )?;
+
+            Ok(info.into())
+        }
+    }
+
+    /**
+    Gets the compute instances of the given profile in this GPU instance.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `GpuInstance` or `profile` is invalid
+    * `NotSupported`, if `profile` isn't supported
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux. Requires root/admin permissions.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlGpuInstanceGetComputeInstances")]
+    pub fn compute_instances(
+        &self,
+        profile: &ComputeInstanceProfileInfo,
+    ) -> Result<Vec<ComputeInstance<'nvml>>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlGpuInstanceGetComputeInstances)?;
+
+        unsafe {
+            let mut count: c_uint = profile.instance_count;
+            let mut instances: Vec<nvmlComputeInstance_t> =
+                vec![mem::zeroed(); profile.instance_count as usize];
+
+            nvml_try_for(
+                function,
+                sym(
+                    self.instance,
+                    profile.id,
+                    instances.as_mut_ptr(),
+                    &mut count,
+                ),
+            )?;
+
+            instances.truncate(count as usize);
+
+            Ok(instances
+                .into_iter()
+                .map(|instance| ComputeInstance::new(instance, self.nvml))
+                .collect())
+        }
+    }
+
+    /**
+    Creates a compute instance of the given profile in this GPU instance.
+
+    Requires root/admin permissions.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `GpuInstance` or `profile` is invalid
+    * `NotSupported`, if `profile` isn't supported
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `UnrecognizedCode`, with `NVML_ERROR_INSUFFICIENT_RESOURCES`, if there is no
+      room left for the compute instance
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlGpuInstanceCreateComputeInstance")]
+    pub fn create_compute_instance(
+        &mut self,
+        profile: &ComputeInstanceProfileInfo,
+    ) -> Result<ComputeInstance<'nvml>, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlGpuInstanceCreateComputeInstance)?;
+
+        unsafe {
+            let mut instance: nvmlComputeInstance_t = mem::zeroed();
+
+            nvml_try_for(function, sym(self.instance, profile.id, &mut instance))?;
+
+            Ok(ComputeInstance::new(instance, self.nvml))
+        }
+    }
+
+    /**
+    Destroys this GPU instance.
+
+    Its compute instances must be destroyed first.
+
+    Requires root/admin permissions.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `GpuInstance` is invalid
+    * `NotSupported`, if MIG mode is not enabled on the GPU
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `InUse`, if the GPU instance still has compute instances or processes
+      running on it
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlGpuInstanceDestroy")]
+    pub fn destroy(self) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlGpuInstanceDestroy)?;
+
+        unsafe { nvml_try_for(function, sym(self.instance)) }
+    }
+}
+
+impl<'nvml> ComputeInstance<'nvml> {
+    pub(crate) fn new(instance: nvmlComputeInstance_t, nvml: &'nvml Nvml) -> Self {
+        Self { instance, nvml }
+    }
+
+    /// Get the raw compute instance handle contained in this struct.
+    ///
+    /// Sometimes necessary for C interop.
+    ///
+    /// # Safety
+    ///
+    /// This is unsafe to prevent it from being used without care. In
+    /// particular, you must avoid creating a new `ComputeInstance` from this
+    /// handle and allowing both this `ComputeInstance` and the newly created
+    /// one to drop (which would result in a double-free).
+    pub unsafe fn handle(&self) -> nvmlComputeInstance_t {
+        self.instance
+    }
+
+    /**
+    Gets the ID, profile and placement of this compute instance.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `ComputeInstance` is invalid
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested
+    #[doc(alias = "nvmlComputeInstanceGetInfo_v2")]
+    pub fn info(&self) -> Result<ComputeInstanceInfo, NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlComputeInstanceGetInfo_v2)?;
+
+        unsafe {
+            let mut info: nvmlComputeInstanceInfo_t = mem::zeroed();
+            nvml_try_for(function, sym(self.instance, &mut info))?;
+
+            Ok(info.into())
+        }
+    }
+
+    /**
+    Destroys this compute instance.
+
+    Requires root/admin permissions.
+
+    # Errors
+
+    * `Uninitialized`, if the library has not been successfully initialized
+    * `InvalidArg`, if this `ComputeInstance` is invalid
+    * `NoPermission`, if the user doesn't have permission to perform this operation
+    * `InUse`, if processes are running on the compute instance
+    * `Unknown`, on any unexpected error
+
+    # Device Support
+
+    Supports Ampere and newer fully supported devices.
+
+    # Platform Support
+
+    Only supports Linux.
+    */
+    // Checked against local
+    // Tested (no-run)
+    #[doc(alias = "nvmlComputeInstanceDestroy")]
+    pub fn destroy(self) -> Result<(), NvmlError> {
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlComputeInstanceDestroy)?;
+
+        unsafe { nvml_try_for(function, sym(self.instance)) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::enums::device::{ComputeInstanceProfile, GpuInstanceProfile};
+    use crate::test_utils::*;
+
+    #[test]
+    #[ignore = "my machine does not support this call"]
+    fn compute_instances() {
+        let nvml = nvml();
+        let device = device(&nvml);
+        test(3, || {
+            let profile = device.gpu_instance_profile_info(GpuInstanceProfile::Slice1)?;
+            let mut infos = vec![];
+
+            for instance in device.gpu_instances(&profile)? {
+                let profile =
+                    instance.compute_instance_profile_info(ComputeInstanceProfile::Slice1)?;
+
+                for compute_instance in instance.compute_instances(&profile)? {
+                    infos.push(compute_instance.info()?);
+                }
+            }
+
+            Ok(infos)
+        })
+    }
+
+    // This modifies device state, so we don't want to actually run the test
+    #[allow(dead_code)]
+    fn create_compute_instance() {
+        let nvml = nvml();
+        let mut device = device(&nvml);
+
+        let profile = device
+            .gpu_instance_profile_info(GpuInstanceProfile::Slice1)
+            .expect("profile info");
+        let mut instance = device
+            .create_gpu_instance(&profile)
+            .expect("created GPU instance");
+        let profile = instance
+            .compute_instance_profile_info(ComputeInstanceProfile::Slice1)
+            .expect("compute profile info");
+
+        instance
+            .create_compute_instance(&profile)
+            .expect("created compute instance")
+            .destroy()
+            .expect("destroyed compute instance");
+        instance.destroy().expect("destroyed GPU instance");
+    }
+
+    #[test]
+    fn gpu_instance_id() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device
+                .mig_devices()?
+                .iter()
+                .map(|mig_device| mig_device.gpu_instance_id())
+                .collect::<Result<Vec<_>, _>>()
+        })
+    }
+
+    #[test]
+    fn compute_instance_id() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device
+                .mig_devices()?
+                .iter()
+                .map(|mig_device| mig_device.compute_instance_id())
+                .collect::<Result<Vec<_>, _>>()
+        })
+    }
+
+    #[test]
+    fn attributes() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device
+                .mig_devices()?
+                .iter()
+                .map(|mig_device| mig_device.attributes())
+                .collect::<Result<Vec<_>, _>>()
+        })
+    }
+
+    #[test]
+    fn snapshot() {
+        let nvml = nvml();
+        test_with_device(3, &nvml, |device| {
+            device
+                .mig_devices()?
+                .iter()
+                .map(|mig_device| mig_device.snapshot())
+                .collect::<Result<Vec<_>, _>>()
+        })
+    }
+}
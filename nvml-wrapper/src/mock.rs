@@ -0,0 +1,1387 @@
+/*!
+A fake NVML for testing code that uses this crate on machines without a GPU.
+
+A `MockNvml` stands in for the NVML library itself: the wrapper calls into fake
+NVML functions that answer from virtual devices described with `MockDevice`,
+so everything built on top of `Nvml` and `Device` (including the `high_level`
+module) runs unchanged:
+
+```
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
+use nvml_wrapper::mock::{MockDevice, MockNvml};
+use nvml_wrapper::units::Celsius;
+
+let mock = MockNvml::builder()
+    .device(MockDevice::new("NVIDIA Mock GPU").temperature([Celsius(40), Celsius(60), Celsius(85)]))
+    .init()?;
+
+// Derefs to `Nvml`
+let device = mock.device_by_index(0)?;
+
+// Scripted values are returned in order, repeating the last one
+assert_eq!(device.temperature(TemperatureSensor::Gpu)?, Celsius(40));
+assert_eq!(device.temperature(TemperatureSensor::Gpu)?, Celsius(60));
+assert_eq!(device.temperature(TemperatureSensor::Gpu)?, Celsius(85));
+assert_eq!(device.temperature(TemperatureSensor::Gpu)?, Celsius(85));
+
+// Errors can be injected per NVML function
+mock.update(0, |device| {
+    device.fail("nvmlDeviceGetTemperature", NvmlError::GpuLost);
+});
+assert_eq!(
+    device.temperature(TemperatureSensor::Gpu),
+    Err(NvmlError::GpuLost)
+);
+# Ok(())
+# }
+```
+
+# Fault scenarios
+
+Faults can also be scheduled to happen some time after the mock is
+initialized, so that alerting built on this crate can be soak-tested against
+a GPU falling off the bus, a burst of ECC errors or a thermal runaway:
+
+```
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::enum_wrappers::device::{EccCounter, MemoryError, TemperatureSensor};
+use nvml_wrapper::mock::{MockDevice, MockFault, MockNvml};
+use nvml_wrapper::units::Celsius;
+use std::time::Duration;
+
+let mock = MockNvml::builder()
+    .device(
+        MockDevice::new("NVIDIA Mock GPU")
+            .temperature([Celsius(60)])
+            .ecc_errors(0, 0)
+            .fault_at(Duration::from_secs(10), MockFault::EccErrors { corrected: 0, uncorrected: 5 })
+            .fault_at(Duration::from_secs(20), MockFault::ThermalRunaway { per_second: 2 })
+            .fault_at(Duration::from_secs(30), MockFault::GpuLost),
+    )
+    .init()?;
+let device = mock.device_by_index(0)?;
+
+// Skip ahead instead of waiting
+mock.advance(Duration::from_secs(25));
+assert_eq!(device.total_ecc_errors(MemoryError::Uncorrected, EccCounter::Volatile)?, 5);
+assert_eq!(device.temperature(TemperatureSensor::Gpu)?, Celsius(70));
+
+mock.advance(Duration::from_secs(5));
+assert_eq!(device.temperature(TemperatureSensor::Gpu), Err(NvmlError::GpuLost));
+# Ok(())
+# }
+```
+
+Time passes in real time from initialization, plus whatever was skipped with
+`MockNvml.advance()`. Faults take effect on the first call for the device
+after they are due.
+
+# Coverage
+
+The following are faked: initialization and shutdown, device enumeration
+(`Nvml.device_count()`, `.device_by_index()`, `.device_by_uuid()`), and the
+`Device` methods `.index()`, `.name()`, `.uuid()`, `.pci_info()`,
+`.utilization_rates()`, `.memory_info()`, `.temperature()` (of the GPU die),
+`.power_usage()`, `.enforced_power_limit()`, `.total_energy_consumption()`,
+`.num_fans()`, `.fan_speed()`, `.fan_speed_rpm()`, `.clock_info()`, `.performance_state()`,
+`.current_clocks_event_reasons()`, `.total_ecc_errors()`,
+`.running_compute_processes()` and `.running_graphics_processes()`. This
+covers every metric group of a
+`high_level::DeviceQuery`, except for the values it gets through field values.
+Values that weren't scripted for a device are reported as `NotSupported`.
+Every other method fails with `FailedToLoadSymbol`, as it would against a
+library that doesn't have the function.
+
+NVML functions can't tell which library instance they belong to, so the
+virtual devices are shared by the whole process. Only one `MockNvml` exists at
+a time; `MockNvmlBuilder.init()` blocks until any other one is dropped, which
+keeps tests running in parallel from seeing each other's devices.
+
+This module is only available with the `mock` feature enabled.
+*/
+
+use crate::bitmasks::device::ClocksEventReasons;
+use crate::enum_wrappers::device::{Clock, MemoryError, PerformanceState, TemperatureSensor};
+use crate::enums::device::UsedGpuMemory;
+use crate::error::NvmlError;
+use crate::ffi::bindings::*;
+use crate::struct_wrappers::device::{MemoryInfo, PciBusId, ProcessInfo, Utilization};
+use crate::units::{Celsius, Megahertz, Milliwatts};
+use crate::Nvml;
+use std::{
+    collections::HashMap,
+    ffi::CStr,
+    mem,
+    ops::Deref,
+    os::raw::{c_char, c_uint, c_ulonglong},
+    ptr,
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Held by the `MockNvml` in existence, if any.
+static EXCLUSIVE: Mutex<()> = Mutex::new(());
+// The virtual devices the fake NVML functions answer from.
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+struct State {
+    devices: Vec<MockDevice>,
+    init_error: Option<nvmlReturn_t>,
+    // `nvmlInit()` calls not yet matched by an `nvmlShutdown()`
+    init_count: u32,
+    started: Instant,
+    // Time skipped with `MockNvml.advance()`
+    skipped: Duration,
+}
+
+impl State {
+    // The time since the mock was initialized, as seen by scheduled faults.
+    fn elapsed(&self) -> Duration {
+        self.started.elapsed() + self.skipped
+    }
+}
+
+/**
+A virtual device served by a `MockNvml`.
+
+Metric setters take the values to return from consecutive calls, in order;
+once they run out, the last one keeps being returned.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct MockDevice {
+    name: String,
+    uuid: Option<String>,
+    pci_bus_id: Option<String>,
+    utilization: Script<Utilization>,
+    memory: Script<MemoryInfo>,
+    temperature: Script<u32>,
+    power_usage: Script<u32>,
+    enforced_power_limit: Script<u32>,
+    total_energy_consumption: Script<u64>,
+    fan_speeds: Vec<Script<u32>>,
+    fan_speeds_rpm: Vec<Script<u32>>,
+    clocks: HashMap<nvmlClockType_t, Script<u32>>,
+    performance_state: Script<PerformanceState>,
+    clocks_event_reasons: Script<ClocksEventReasons>,
+    compute_processes: Script<Vec<ProcessInfo>>,
+    graphics_processes: Script<Vec<ProcessInfo>>,
+    // Corrected and uncorrected counts, if ECC is scripted
+    ecc_errors: Option<(u64, u64)>,
+    errors: HashMap<String, nvmlReturn_t>,
+    delays: HashMap<String, Duration>,
+    // Scheduled faults not yet in effect, in the order they were added
+    faults: Vec<(Duration, Fault)>,
+    lost: bool,
+    // When a thermal runaway started, and how fast the temperature rises
+    runaway: Option<(Duration, u32)>,
+}
+
+/**
+A fault to schedule with `MockDevice.fault_at()`.
+
+Faults stay in effect once they happen, unless undone by a later `Recover`.
+*/
+#[derive(Debug)]
+pub enum MockFault {
+    /// The device falls off the bus: every call for it fails with `GpuLost`.
+    GpuLost,
+    /// Calls to the named NVML function fail with the given error, e.g.
+    /// `NotSupported` or `Timeout`. See `MockDevice.fail()`.
+    Fail { function: String, error: NvmlError },
+    /// Calls to the named NVML function take at least the given time to
+    /// return, as they do when a driver is struggling.
+    Delay { function: String, delay: Duration },
+    /// Calls to the named NVML function stop failing and being delayed.
+    Recover { function: String },
+    /// A burst of ECC errors is added to the counts reported by
+    /// `Device.total_ecc_errors()`, which start at zero unless set with
+    /// `MockDevice.ecc_errors()`.
+    EccErrors { corrected: u64, uncorrected: u64 },
+    /// The scripted GPU temperature rises by the given number of degrees
+    /// Celsius every second from then on.
+    ThermalRunaway { per_second: u32 },
+}
+
+// `MockFault` with errors converted to return codes, so that `MockDevice`s can
+// be cloned.
+#[derive(Debug, Clone)]
+enum Fault {
+    GpuLost,
+    Fail(String, nvmlReturn_t),
+    Delay(String, Duration),
+    Recover(String),
+    EccErrors(u64, u64),
+    ThermalRunaway(u32),
+}
+
+impl From<MockFault> for Fault {
+    fn from(fault: MockFault) -> Self {
+        match fault {
+            MockFault::GpuLost => Fault::GpuLost,
+            MockFault::Fail { function, error } => Fault::Fail(function, raw_code(&error)),
+            MockFault::Delay { function, delay } => Fault::Delay(function, delay),
+            MockFault::Recover { function } => Fault::Recover(function),
+            MockFault::EccErrors {
+                corrected,
+                uncorrected,
+            } => Fault::EccErrors(corrected, uncorrected),
+            MockFault::ThermalRunaway { per_second } => Fault::ThermalRunaway(per_second),
+        }
+    }
+}
+
+// Errors that don't correspond to an NVML return code are reported as `Unknown`.
+fn raw_code(error: &NvmlError) -> nvmlReturn_t {
+    error
+        .raw_code()
+        .unwrap_or(nvmlReturn_enum_NVML_ERROR_UNKNOWN)
+}
+
+// Values returned from consecutive calls, repeating the last one.
+#[derive(Debug, Clone)]
+struct Script<T> {
+    values: Vec<T>,
+    next: usize,
+}
+
+impl<T> Default for Script<T> {
+    fn default() -> Self {
+        Self {
+            values: vec![],
+            next: 0,
+        }
+    }
+}
+
+impl<T: Clone> Script<T> {
+    fn new<I: IntoIterator<Item = T>>(values: I) -> Self {
+        Self {
+            values: values.into_iter().collect(),
+            next: 0,
+        }
+    }
+
+    // `None` if no values were scripted.
+    fn next(&mut self) -> Option<T> {
+        let value = self.current().cloned();
+        self.advance();
+        value
+    }
+
+    fn current(&self) -> Option<&T> {
+        let last = self.values.len().checked_sub(1)?;
+        self.values.get(self.next.min(last))
+    }
+
+    fn advance(&mut self) {
+        self.next += 1;
+    }
+}
+
+// Scripts the values for the given fan, adding unscripted fans up to it.
+fn script_fan<I: IntoIterator<Item = u32>>(fans: &mut Vec<Script<u32>>, fan: u32, values: I) {
+    let fan = fan as usize;
+
+    if fans.len() <= fan {
+        fans.resize_with(fan + 1, Script::default);
+    }
+
+    fans[fan] = Script::new(values);
+}
+
+impl MockDevice {
+    /**
+    Create a device with the given name and nothing else scripted.
+
+    Unless set, the UUID is `GPU-00000000-0000-0000-0000-00000000000<index>`
+    and the PCI bus ID `00000000:<index + 1>:00.0`, in hex.
+    */
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the UUID, e.g. `GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5`.
+    pub fn uuid(&mut self, uuid: &str) -> &mut Self {
+        self.uuid = Some(uuid.into());
+        self
+    }
+
+    /// Set the PCI bus ID, e.g. `00000000:01:00.0`.
+    pub fn pci_bus_id(&mut self, bus_id: &str) -> &mut Self {
+        self.pci_bus_id = Some(bus_id.into());
+        self
+    }
+
+    /// Script the values returned from `Device.utilization_rates()`.
+    pub fn utilization<I: IntoIterator<Item = Utilization>>(&mut self, values: I) -> &mut Self {
+        self.utilization = Script::new(values);
+        self
+    }
+
+    /// Script the values returned from `Device.memory_info()`.
+    pub fn memory<I: IntoIterator<Item = MemoryInfo>>(&mut self, values: I) -> &mut Self {
+        self.memory = Script::new(values);
+        self
+    }
+
+    /// Script the GPU die temperatures returned from `Device.temperature()`.
+    pub fn temperature<I: IntoIterator<Item = Celsius>>(&mut self, values: I) -> &mut Self {
+        self.temperature = Script::new(values.into_iter().map(u32::from));
+        self
+    }
+
+    /// Script the values returned from `Device.power_usage()`.
+    pub fn power_usage<I: IntoIterator<Item = Milliwatts>>(&mut self, values: I) -> &mut Self {
+        self.power_usage = Script::new(values.into_iter().map(u32::from));
+        self
+    }
+
+    /// Script the values returned from `Device.enforced_power_limit()`.
+    pub fn enforced_power_limit<I: IntoIterator<Item = Milliwatts>>(
+        &mut self,
+        values: I,
+    ) -> &mut Self {
+        self.enforced_power_limit = Script::new(values.into_iter().map(u32::from));
+        self
+    }
+
+    /// Script the values returned from `Device.total_energy_consumption()`,
+    /// in millijoules.
+    pub fn total_energy_consumption<I: IntoIterator<Item = u64>>(
+        &mut self,
+        values: I,
+    ) -> &mut Self {
+        self.total_energy_consumption = Script::new(values);
+        self
+    }
+
+    /// Script the values returned from `Device.fan_speed()` for the given fan,
+    /// in percent.
+    ///
+    /// `Device.num_fans()` reports one more than the highest fan scripted.
+    pub fn fan_speed<I: IntoIterator<Item = u32>>(&mut self, fan: u32, values: I) -> &mut Self {
+        script_fan(&mut self.fan_speeds, fan, values);
+        self
+    }
+
+    /// Script the values returned from `Device.fan_speed_rpm()` for the given
+    /// fan.
+    ///
+    /// `Device.num_fans()` reports one more than the highest fan scripted.
+    pub fn fan_speed_rpm<I: IntoIterator<Item = u32>>(&mut self, fan: u32, values: I) -> &mut Self {
+        script_fan(&mut self.fan_speeds_rpm, fan, values);
+        self
+    }
+
+    /// Script the values returned from `Device.clock_info()` for the given
+    /// clock.
+    pub fn clock<I: IntoIterator<Item = Megahertz>>(
+        &mut self,
+        clock: Clock,
+        values: I,
+    ) -> &mut Self {
+        self.clocks
+            .insert(clock.as_c(), Script::new(values.into_iter().map(u32::from)));
+        self
+    }
+
+    /// Script the values returned from `Device.performance_state()`.
+    pub fn performance_state<I: IntoIterator<Item = PerformanceState>>(
+        &mut self,
+        values: I,
+    ) -> &mut Self {
+        self.performance_state = Script::new(values);
+        self
+    }
+
+    /// Script the values returned from `Device.current_clocks_event_reasons()`.
+    pub fn clocks_event_reasons<I: IntoIterator<Item = ClocksEventReasons>>(
+        &mut self,
+        values: I,
+    ) -> &mut Self {
+        self.clocks_event_reasons = Script::new(values);
+        self
+    }
+
+    /// Script the processes returned from `Device.running_compute_processes()`.
+    pub fn compute_processes<I: IntoIterator<Item = Vec<ProcessInfo>>>(
+        &mut self,
+        values: I,
+    ) -> &mut Self {
+        self.compute_processes = Script::new(values);
+        self
+    }
+
+    /// Script the processes returned from `Device.running_graphics_processes()`.
+    pub fn graphics_processes<I: IntoIterator<Item = Vec<ProcessInfo>>>(
+        &mut self,
+        values: I,
+    ) -> &mut Self {
+        self.graphics_processes = Script::new(values);
+        self
+    }
+
+    /**
+    Make every call to the named NVML function for this device fail with the
+    given error, e.g. `fail("nvmlDeviceGetPowerUsage", NvmlError::GpuLost)`.
+
+    The function name is the one listed in the `#[doc(alias)]` of the `Device`
+    method. Errors that don't correspond to an NVML return code are reported
+    as `Unknown`.
+    */
+    pub fn fail(&mut self, function: &str, error: NvmlError) -> &mut Self {
+        self.errors.insert(function.into(), raw_code(&error));
+        self
+    }
+
+    /// Stop failing calls to the named NVML function after `.fail()`.
+    pub fn succeed(&mut self, function: &str) -> &mut Self {
+        self.errors.remove(function);
+        self
+    }
+
+    /// Set the corrected and uncorrected ECC error counts reported by
+    /// `Device.total_ecc_errors()`, for every counter type.
+    pub fn ecc_errors(&mut self, corrected: u64, uncorrected: u64) -> &mut Self {
+        self.ecc_errors = Some((corrected, uncorrected));
+        self
+    }
+
+    /**
+    Schedule a fault to happen the given time after the mock is initialized.
+    See the module-level docs.
+
+    Faults scheduled for the same time happen in the order they were added.
+    */
+    pub fn fault_at(&mut self, after: Duration, fault: MockFault) -> &mut Self {
+        let position = self.faults.partition_point(|&(at, _)| at <= after);
+        self.faults.insert(position, (after, fault.into()));
+        self
+    }
+
+    // Puts the faults due by `now` into effect.
+    fn apply_faults(&mut self, now: Duration) {
+        let due = self.faults.partition_point(|&(at, _)| at <= now);
+
+        for (at, fault) in self.faults.drain(..due).collect::<Vec<_>>() {
+            match fault {
+                Fault::GpuLost => self.lost = true,
+                Fault::Fail(function, code) => {
+                    self.errors.insert(function, code);
+                }
+                Fault::Delay(function, delay) => {
+                    self.delays.insert(function, delay);
+                }
+                Fault::Recover(function) => {
+                    self.errors.remove(&function);
+                    self.delays.remove(&function);
+                }
+                Fault::EccErrors(corrected, uncorrected) => {
+                    let counts = self.ecc_errors.get_or_insert((0, 0));
+                    counts.0 += corrected;
+                    counts.1 += uncorrected;
+                }
+                Fault::ThermalRunaway(per_second) => self.runaway = Some((at, per_second)),
+            }
+        }
+    }
+
+    // The next scripted GPU temperature, raised by any thermal runaway.
+    fn next_temperature(&mut self, now: Duration) -> Option<u32> {
+        let temperature = self.temperature.next()?;
+
+        Some(match self.runaway {
+            Some((since, per_second)) => {
+                let rise = now.saturating_sub(since).as_secs() * per_second as u64;
+                temperature.saturating_add(rise.min(u32::MAX as u64) as u32)
+            }
+            None => temperature,
+        })
+    }
+}
+
+/// Builder for `MockNvml`, obtained via `MockNvml::builder()`.
+#[derive(Debug, Clone, Default)]
+pub struct MockNvmlBuilder {
+    devices: Vec<MockDevice>,
+    init_error: Option<nvmlReturn_t>,
+}
+
+impl MockNvmlBuilder {
+    /// Add a virtual device. Devices are indexed in the order they're added.
+    pub fn device(&mut self, device: &MockDevice) -> &mut Self {
+        self.devices.push(device.clone());
+        self
+    }
+
+    /// Make initialization fail with the given error, e.g. to test handling
+    /// of `DriverNotLoaded`.
+    pub fn init_error(&mut self, error: NvmlError) -> &mut Self {
+        self.init_error = Some(raw_code(&error));
+        self
+    }
+
+    /**
+    Initialize the mock, blocking until any other `MockNvml` is dropped.
+
+    # Errors
+
+    * The error set with `.init_error()`, if any
+    * `LibloadingError`, if a handle to the current process couldn't be
+      obtained to stand in for the NVML library
+    */
+    pub fn init(&self) -> Result<MockNvml, NvmlError> {
+        let exclusive = EXCLUSIVE.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut devices = self.devices.clone();
+        for (index, device) in devices.iter_mut().enumerate() {
+            device
+                .uuid
+                .get_or_insert_with(|| format!("GPU-00000000-0000-0000-0000-{:012x}", index));
+            device
+                .pci_bus_id
+                .get_or_insert_with(|| format!("00000000:{:02X}:00.0", index + 1));
+        }
+
+        *state() = Some(State {
+            devices,
+            init_error: self.init_error,
+            init_count: 0,
+            started: Instant::now(),
+            skipped: Duration::ZERO,
+        });
+
+        let result = unsafe { fake_lib() }.and_then(|lib| Nvml::init_internal(lib, "mock".into()));
+
+        match result {
+            Ok(nvml) => Ok(MockNvml {
+                nvml: Arc::new(nvml),
+                _exclusive: exclusive,
+            }),
+            Err(e) => {
+                *state() = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+/**
+An initialized fake NVML. See the module-level docs.
+
+Derefs to `Nvml`. The virtual devices are removed when this is dropped; an
+`Nvml` obtained from `.shared()` that outlives it reports `Uninitialized`.
+*/
+#[derive(Debug)]
+pub struct MockNvml {
+    nvml: Arc<Nvml>,
+    _exclusive: MutexGuard<'static, ()>,
+}
+
+impl MockNvml {
+    /// Create a `MockNvmlBuilder` without any devices.
+    pub fn builder() -> MockNvmlBuilder {
+        MockNvmlBuilder::default()
+    }
+
+    /// The `Nvml` instance as an `Arc`, for APIs that need to share it with
+    /// other threads (e.g. `high_level::sampler`).
+    pub fn shared(&self) -> Arc<Nvml> {
+        self.nvml.clone()
+    }
+
+    /// Change the virtual device at the given index, e.g. to script new
+    /// values or inject errors while the code under test is running.
+    ///
+    /// # Panics
+    ///
+    /// If there is no device at `index`.
+    pub fn update<F: FnOnce(&mut MockDevice)>(&self, index: u32, f: F) {
+        let mut state = state();
+        let device = state
+            .as_mut()
+            .and_then(|s| s.devices.get_mut(index as usize))
+            .unwrap_or_else(|| panic!("no mock device at index {}", index));
+
+        f(device)
+    }
+
+    /// Skip ahead by the given time, putting faults scheduled with
+    /// `MockDevice.fault_at()` into effect without waiting for them.
+    pub fn advance(&self, time: Duration) {
+        if let Some(state) = state().as_mut() {
+            state.skipped += time;
+        }
+    }
+}
+
+impl Deref for MockNvml {
+    type Target = Nvml;
+
+    fn deref(&self) -> &Nvml {
+        &self.nvml
+    }
+}
+
+impl Drop for MockNvml {
+    fn drop(&mut self) {
+        *state() = None;
+    }
+}
+
+fn state() -> MutexGuard<'static, Option<State>> {
+    STATE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Creates a library without any NVML functions and fills in the fake ones.
+unsafe fn fake_lib() -> Result<NvmlLib, NvmlError> {
+    #[cfg(unix)]
+    let library = libloading::os::unix::Library::this();
+    #[cfg(windows)]
+    let library = libloading::os::windows::Library::this()?;
+
+    let mut lib = NvmlLib::from_library(library)?;
+
+    lib.nvmlInit_v2 = Ok(init);
+    lib.nvmlShutdown = Ok(shutdown);
+    lib.nvmlDeviceGetCount_v2 = Ok(device_count);
+    lib.nvmlDeviceGetHandleByIndex_v2 = Ok(handle_by_index);
+    lib.nvmlDeviceGetHandleByUUID = Ok(handle_by_uuid);
+    lib.nvmlDeviceGetIndex = Ok(index);
+    lib.nvmlDeviceGetName = Ok(name);
+    lib.nvmlDeviceGetUUID = Ok(uuid);
+    lib.nvmlDeviceGetPciInfo_v3 = Ok(pci_info);
+    lib.nvmlDeviceGetUtilizationRates = Ok(utilization_rates);
+    lib.nvmlDeviceGetMemoryInfo = Ok(memory_info);
+    lib.nvmlDeviceGetTemperature = Ok(temperature);
+    lib.nvmlDeviceGetPowerUsage = Ok(power_usage);
+    lib.nvmlDeviceGetEnforcedPowerLimit = Ok(enforced_power_limit);
+    lib.nvmlDeviceGetTotalEnergyConsumption = Ok(total_energy_consumption);
+    lib.nvmlDeviceGetNumFans = Ok(num_fans);
+    lib.nvmlDeviceGetFanSpeed_v2 = Ok(fan_speed);
+    lib.nvmlDeviceGetFanSpeedRPM = Ok(fan_speed_rpm);
+    lib.nvmlDeviceGetClockInfo = Ok(clock_info);
+    lib.nvmlDeviceGetPerformanceState = Ok(performance_state);
+    lib.nvmlDeviceGetCurrentClocksEventReasons = Ok(clocks_event_reasons);
+    lib.nvmlDeviceGetTotalEccErrors = Ok(total_ecc_errors);
+    lib.nvmlDeviceGetComputeRunningProcesses_v3 = Ok(compute_processes);
+    lib.nvmlDeviceGetGraphicsRunningProcesses_v3 = Ok(graphics_processes);
+
+    Ok(lib)
+}
+
+// The fake NVML functions. Device handles are the device's index plus one, so
+// that no handle is null.
+
+fn handle(index: usize) -> nvmlDevice_t {
+    (index + 1) as nvmlDevice_t
+}
+
+// Runs `f` against the state if NVML is initialized.
+fn with_state<F>(f: F) -> nvmlReturn_t
+where
+    F: FnOnce(&mut State) -> Result<(), nvmlReturn_t>,
+{
+    match state().as_mut() {
+        Some(state) if state.init_count > 0 => match f(state) {
+            Ok(()) => nvmlReturn_enum_NVML_SUCCESS,
+            Err(code) => code,
+        },
+        _ => nvmlReturn_enum_NVML_ERROR_UNINITIALIZED,
+    }
+}
+
+// Runs `f` against the device behind `handle` and the time since the mock was
+// initialized, unless the device is lost or an error was injected for
+// `function`.
+fn with_device<F>(function: &str, handle: nvmlDevice_t, f: F) -> nvmlReturn_t
+where
+    F: FnOnce(usize, &mut MockDevice, Duration) -> Result<(), nvmlReturn_t>,
+{
+    let mut delay = None;
+
+    let code = with_state(|state| {
+        let now = state.elapsed();
+        let index = (handle as usize)
+            .checked_sub(1)
+            .filter(|&i| i < state.devices.len())
+            .ok_or(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT)?;
+        let device = &mut state.devices[index];
+
+        device.apply_faults(now);
+        delay = device.delays.get(function).copied();
+
+        if device.lost {
+            return Err(nvmlReturn_enum_NVML_ERROR_GPU_IS_LOST);
+        }
+
+        match device.errors.get(function) {
+            Some(&code) => Err(code),
+            None => f(index, device, now),
+        }
+    });
+
+    // Outside of the lock, so that other devices aren't held up
+    if let Some(delay) = delay {
+        thread::sleep(delay);
+    }
+
+    code
+}
+
+// Writes a value through `out`, reporting `NotSupported` if it wasn't scripted.
+unsafe fn write<T>(out: *mut T, value: Option<T>) -> Result<(), nvmlReturn_t> {
+    if out.is_null() {
+        return Err(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT);
+    }
+
+    *out = value.ok_or(nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED)?;
+    Ok(())
+}
+
+unsafe fn write_str(buffer: *mut c_char, length: c_uint, s: &str) -> Result<(), nvmlReturn_t> {
+    if buffer.is_null() {
+        return Err(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT);
+    }
+
+    if s.len() >= length as usize {
+        return Err(nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE);
+    }
+
+    ptr::copy_nonoverlapping(s.as_ptr() as *const c_char, buffer, s.len());
+    *buffer.add(s.len()) = 0;
+    Ok(())
+}
+
+unsafe extern "C" fn init() -> nvmlReturn_t {
+    match state().as_mut() {
+        Some(State {
+            init_error: Some(code),
+            ..
+        }) => *code,
+        Some(state) => {
+            state.init_count += 1;
+            nvmlReturn_enum_NVML_SUCCESS
+        }
+        None => nvmlReturn_enum_NVML_ERROR_DRIVER_NOT_LOADED,
+    }
+}
+
+unsafe extern "C" fn shutdown() -> nvmlReturn_t {
+    with_state(|state| {
+        state.init_count -= 1;
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn device_count(count: *mut c_uint) -> nvmlReturn_t {
+    with_state(|state| write(count, Some(state.devices.len() as c_uint)))
+}
+
+unsafe extern "C" fn handle_by_index(index: c_uint, device: *mut nvmlDevice_t) -> nvmlReturn_t {
+    with_state(|state| {
+        if index as usize >= state.devices.len() {
+            return Err(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT);
+        }
+
+        write(device, Some(handle(index as usize)))
+    })
+}
+
+unsafe extern "C" fn handle_by_uuid(
+    uuid: *const c_char,
+    device: *mut nvmlDevice_t,
+) -> nvmlReturn_t {
+    with_state(|state| {
+        if uuid.is_null() {
+            return Err(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT);
+        }
+
+        let uuid = CStr::from_ptr(uuid).to_str().ok();
+        let index = state
+            .devices
+            .iter()
+            .position(|d| d.uuid.as_deref() == uuid)
+            .ok_or(nvmlReturn_enum_NVML_ERROR_NOT_FOUND)?;
+
+        write(device, Some(handle(index)))
+    })
+}
+
+unsafe extern "C" fn index(device: nvmlDevice_t, out: *mut c_uint) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetIndex", device, |index, _, _| {
+        write(out, Some(index as c_uint))
+    })
+}
+
+unsafe extern "C" fn name(device: nvmlDevice_t, name: *mut c_char, length: c_uint) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetName", device, |_, d, _| {
+        write_str(name, length, &d.name)
+    })
+}
+
+unsafe extern "C" fn uuid(device: nvmlDevice_t, uuid: *mut c_char, length: c_uint) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetUUID", device, |_, d, _| {
+        write_str(uuid, length, d.uuid.as_deref().unwrap_or_default())
+    })
+}
+
+unsafe extern "C" fn pci_info(device: nvmlDevice_t, pci: *mut nvmlPciInfo_t) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetPciInfo_v3", device, |_, d, _| {
+        if pci.is_null() {
+            return Err(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT);
+        }
+
+        let bus_id = d.pci_bus_id.as_deref().unwrap_or_default();
+        let parsed = bus_id.parse::<PciBusId>().unwrap_or_default();
+
+        let mut info: nvmlPciInfo_t = mem::zeroed();
+        info.domain = parsed.domain;
+        info.bus = parsed.bus;
+        info.device = parsed.device;
+        write_str(info.busId.as_mut_ptr(), info.busId.len() as c_uint, bus_id)?;
+
+        *pci = info;
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn utilization_rates(
+    device: nvmlDevice_t,
+    utilization: *mut nvmlUtilization_t,
+) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetUtilizationRates", device, |_, d, _| {
+        let value = d.utilization.next().map(|u| nvmlUtilization_t {
+            gpu: u.gpu,
+            memory: u.memory,
+        });
+
+        write(utilization, value)
+    })
+}
+
+unsafe extern "C" fn memory_info(device: nvmlDevice_t, memory: *mut nvmlMemory_t) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetMemoryInfo", device, |_, d, _| {
+        let value = d.memory.next().map(|m| nvmlMemory_t {
+            total: m.total,
+            free: m.free,
+            used: m.used,
+        });
+
+        write(memory, value)
+    })
+}
+
+unsafe extern "C" fn temperature(
+    device: nvmlDevice_t,
+    sensor: nvmlTemperatureSensors_t,
+    temp: *mut c_uint,
+) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetTemperature", device, |_, d, now| {
+        if sensor != TemperatureSensor::Gpu.as_c() {
+            return Err(nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED);
+        }
+
+        write(temp, d.next_temperature(now))
+    })
+}
+
+unsafe extern "C" fn power_usage(device: nvmlDevice_t, power: *mut c_uint) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetPowerUsage", device, |_, d, _| {
+        write(power, d.power_usage.next())
+    })
+}
+
+unsafe extern "C" fn enforced_power_limit(
+    device: nvmlDevice_t,
+    limit: *mut c_uint,
+) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetEnforcedPowerLimit", device, |_, d, _| {
+        write(limit, d.enforced_power_limit.next())
+    })
+}
+
+unsafe extern "C" fn total_energy_consumption(
+    device: nvmlDevice_t,
+    energy: *mut c_ulonglong,
+) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetTotalEnergyConsumption", device, |_, d, _| {
+        write(energy, d.total_energy_consumption.next())
+    })
+}
+
+unsafe extern "C" fn num_fans(device: nvmlDevice_t, count: *mut c_uint) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetNumFans", device, |_, d, _| {
+        let fans = d.fan_speeds.len().max(d.fan_speeds_rpm.len());
+        let count_value = Some(fans as c_uint).filter(|&c| c > 0);
+        write(count, count_value)
+    })
+}
+
+unsafe extern "C" fn fan_speed(
+    device: nvmlDevice_t,
+    fan: c_uint,
+    speed: *mut c_uint,
+) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetFanSpeed_v2", device, |_, d, _| {
+        let script = d
+            .fan_speeds
+            .get_mut(fan as usize)
+            .ok_or(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT)?;
+
+        write(speed, script.next())
+    })
+}
+
+unsafe extern "C" fn fan_speed_rpm(
+    device: nvmlDevice_t,
+    info: *mut nvmlFanSpeedInfo_t,
+) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetFanSpeedRPM", device, |_, d, _| {
+        let info = info
+            .as_mut()
+            .ok_or(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT)?;
+        let script = d
+            .fan_speeds_rpm
+            .get_mut(info.fan as usize)
+            .ok_or(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT)?;
+
+        write(&mut info.speed, script.next())
+    })
+}
+
+unsafe extern "C" fn clock_info(
+    device: nvmlDevice_t,
+    clock_type: nvmlClockType_t,
+    clock: *mut c_uint,
+) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetClockInfo", device, |_, d, _| {
+        write(clock, d.clocks.get_mut(&clock_type).and_then(Script::next))
+    })
+}
+
+unsafe extern "C" fn performance_state(
+    device: nvmlDevice_t,
+    state: *mut nvmlPstates_t,
+) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetPerformanceState", device, |_, d, _| {
+        write(state, d.performance_state.next().map(|p| p.as_c()))
+    })
+}
+
+unsafe extern "C" fn clocks_event_reasons(
+    device: nvmlDevice_t,
+    reasons: *mut c_ulonglong,
+) -> nvmlReturn_t {
+    with_device(
+        "nvmlDeviceGetCurrentClocksEventReasons",
+        device,
+        |_, d, _| write(reasons, d.clocks_event_reasons.next().map(|r| r.bits())),
+    )
+}
+
+unsafe extern "C" fn compute_processes(
+    device: nvmlDevice_t,
+    count: *mut c_uint,
+    infos: *mut nvmlProcessInfo_t,
+) -> nvmlReturn_t {
+    with_device(
+        "nvmlDeviceGetComputeRunningProcesses_v3",
+        device,
+        |_, d, _| write_processes(&mut d.compute_processes, count, infos),
+    )
+}
+
+unsafe extern "C" fn graphics_processes(
+    device: nvmlDevice_t,
+    count: *mut c_uint,
+    infos: *mut nvmlProcessInfo_t,
+) -> nvmlReturn_t {
+    with_device(
+        "nvmlDeviceGetGraphicsRunningProcesses_v3",
+        device,
+        |_, d, _| write_processes(&mut d.graphics_processes, count, infos),
+    )
+}
+
+unsafe extern "C" fn total_ecc_errors(
+    device: nvmlDevice_t,
+    error_type: nvmlMemoryErrorType_t,
+    _counter_type: nvmlEccCounterType_t,
+    count: *mut c_ulonglong,
+) -> nvmlReturn_t {
+    with_device("nvmlDeviceGetTotalEccErrors", device, |_, d, _| {
+        let value = d.ecc_errors.map(|(corrected, uncorrected)| {
+            if error_type == MemoryError::Uncorrected.as_c() {
+                uncorrected
+            } else {
+                corrected
+            }
+        });
+
+        write(count, value)
+    })
+}
+
+// Like NVML, reports the number of processes through `count` when the buffer is
+// too small. Only moves on to the next scripted value once it has been read.
+unsafe fn write_processes(
+    script: &mut Script<Vec<ProcessInfo>>,
+    count: *mut c_uint,
+    infos: *mut nvmlProcessInfo_t,
+) -> Result<(), nvmlReturn_t> {
+    let processes = script
+        .current()
+        .ok_or(nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED)?;
+
+    if count.is_null() {
+        return Err(nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT);
+    }
+
+    let capacity = *count as usize;
+    *count = processes.len() as c_uint;
+
+    if capacity < processes.len() {
+        return Err(nvmlReturn_enum_NVML_ERROR_INSUFFICIENT_SIZE);
+    }
+
+    for (i, process) in processes.iter().enumerate() {
+        *infos.add(i) = nvmlProcessInfo_t {
+            pid: process.pid,
+            usedGpuMemory: match process.used_gpu_memory {
+                UsedGpuMemory::Used(bytes) => bytes,
+                UsedGpuMemory::Unavailable
+                | UsedGpuMemory::NoPermission
+                | UsedGpuMemory::UnknownUnderMig => NVML_VALUE_NOT_AVAILABLE as u64,
+            },
+            gpuInstanceId: process.gpu_instance_id.unwrap_or(u32::MAX),
+            computeInstanceId: process.compute_instance_id.unwrap_or(u32::MAX),
+        };
+    }
+
+    script.advance();
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::enum_wrappers::device::EccCounter;
+    #[cfg(target_os = "linux")]
+    use crate::high_level::TopologyGraph;
+    use crate::high_level::{DeviceQuery, DeviceRegistry, RegistryEvent};
+
+    fn mock() -> MockNvml {
+        MockNvml::builder()
+            .device(
+                MockDevice::new("NVIDIA Mock GPU")
+                    .utilization([Utilization { gpu: 25, memory: 5 }])
+                    .memory([MemoryInfo {
+                        free: 1024,
+                        total: 4096,
+                        used: 3072,
+                    }])
+                    .temperature([Celsius(40), Celsius(50)])
+                    .power_usage([Milliwatts(150_000)])
+                    .enforced_power_limit([Milliwatts(300_000)])
+                    .fan_speed(1, [60])
+                    .clock(Clock::Graphics, [Megahertz(1500)])
+                    .clock(Clock::SM, [Megahertz(1500)])
+                    .clock(Clock::Memory, [Megahertz(5000)])
+                    .compute_processes([vec![ProcessInfo {
+                        pid: 100,
+                        used_gpu_memory: UsedGpuMemory::Used(512),
+                        gpu_instance_id: None,
+                        compute_instance_id: None,
+                    }]]),
+            )
+            .device(
+                MockDevice::new("NVIDIA Other GPU")
+                    .uuid("GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5")
+                    .pci_bus_id("00000000:0A:00.0"),
+            )
+            .init()
+            .unwrap()
+    }
+
+    #[test]
+    fn devices() {
+        let mock = mock();
+        assert_eq!(mock.device_count().unwrap(), 2);
+
+        let device = mock.device_by_index(0).unwrap();
+        assert_eq!(device.name().unwrap(), "NVIDIA Mock GPU");
+        assert_eq!(
+            device.uuid().unwrap(),
+            "GPU-00000000-0000-0000-0000-000000000000"
+        );
+        assert_eq!(device.pci_info().unwrap().bus_id, "00000000:01:00.0");
+
+        let other = mock
+            .device_by_uuid("GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5")
+            .unwrap();
+        assert_eq!(other.index().unwrap(), 1);
+        assert_eq!(other.pci_info().unwrap().bus, 0x0a);
+
+        assert!(matches!(
+            mock.device_by_index(2),
+            Err(NvmlError::DeviceIndexOutOfRange { index: 2, count: 2 })
+        ));
+        assert!(matches!(
+            mock.device_by_uuid("GPU-nope"),
+            Err(NvmlError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn scripts() {
+        let mock = mock();
+        let device = mock.device_by_index(0).unwrap();
+
+        let temperatures: Vec<_> = (0..3)
+            .map(|_| device.temperature(TemperatureSensor::Gpu).unwrap())
+            .collect();
+        assert_eq!(temperatures, [Celsius(40), Celsius(50), Celsius(50)]);
+
+        assert_eq!(device.num_fans().unwrap(), 2);
+        assert_eq!(device.fan_speed(1).unwrap(), 60);
+        assert!(matches!(
+            device.fan_speed(0),
+            Err(NvmlError::NotSupported(_))
+        ));
+        assert!(matches!(
+            device.performance_state(),
+            Err(NvmlError::NotSupported(_))
+        ));
+
+        // Not faked at all
+        assert!(matches!(
+            device.serial(),
+            Err(NvmlError::FailedToLoadSymbol(_))
+        ));
+    }
+
+    #[test]
+    fn fans() {
+        let mock = MockNvml::builder()
+            .device(
+                MockDevice::new("NVIDIA Mock GPU")
+                    .fan_speed(0, [40])
+                    .fan_speed(1, [45])
+                    .fan_speed(2, [50])
+                    .fan_speed_rpm(2, [1800]),
+            )
+            .init()
+            .unwrap();
+        let device = mock.device_by_index(0).unwrap();
+
+        assert_eq!(device.fan_speeds().unwrap(), [40, 45, 50]);
+        assert_eq!(device.fan_speed_rpm(2).unwrap(), 1800);
+        assert!(matches!(
+            device.fan_speed_rpm(0),
+            Err(NvmlError::NotSupported(_))
+        ));
+        assert_eq!(device.fan_speed_rpm(3), Err(NvmlError::InvalidArg));
+    }
+
+    #[test]
+    fn errors() {
+        let mock = mock();
+        let device = mock.device_by_index(0).unwrap();
+
+        mock.update(0, |device| {
+            device.fail("nvmlDeviceGetPowerUsage", NvmlError::GpuLost);
+        });
+        assert_eq!(device.power_usage(), Err(NvmlError::GpuLost));
+
+        mock.update(0, |device| {
+            device.succeed("nvmlDeviceGetPowerUsage");
+        });
+        assert_eq!(device.power_usage(), Ok(Milliwatts(150_000)));
+    }
+
+    #[test]
+    fn faults() {
+        let mock = MockNvml::builder()
+            .device(
+                MockDevice::new("NVIDIA Mock GPU")
+                    .temperature([Celsius(60)])
+                    .power_usage([Milliwatts(150_000)])
+                    .fault_at(
+                        Duration::from_secs(5),
+                        MockFault::Fail {
+                            function: "nvmlDeviceGetPowerUsage".into(),
+                            error: NvmlError::Timeout,
+                        },
+                    )
+                    .fault_at(
+                        Duration::from_secs(10),
+                        MockFault::Recover {
+                            function: "nvmlDeviceGetPowerUsage".into(),
+                        },
+                    )
+                    .fault_at(
+                        Duration::from_secs(10),
+                        MockFault::EccErrors {
+                            corrected: 2,
+                            uncorrected: 1,
+                        },
+                    )
+                    .fault_at(
+                        Duration::from_secs(20),
+                        MockFault::ThermalRunaway { per_second: 3 },
+                    )
+                    .fault_at(Duration::from_secs(60), MockFault::GpuLost),
+            )
+            .init()
+            .unwrap();
+        let device = mock.device_by_index(0).unwrap();
+
+        assert_eq!(device.power_usage(), Ok(Milliwatts(150_000)));
+        assert!(matches!(
+            device.total_ecc_errors(MemoryError::Corrected, EccCounter::Volatile),
+            Err(NvmlError::NotSupported(_))
+        ));
+
+        mock.advance(Duration::from_secs(5));
+        assert_eq!(device.power_usage(), Err(NvmlError::Timeout));
+
+        mock.advance(Duration::from_secs(5));
+        assert_eq!(device.power_usage(), Ok(Milliwatts(150_000)));
+        assert_eq!(
+            device.total_ecc_errors(MemoryError::Corrected, EccCounter::Volatile),
+            Ok(2)
+        );
+        assert_eq!(
+            device.total_ecc_errors(MemoryError::Uncorrected, EccCounter::Aggregate),
+            Ok(1)
+        );
+        assert_eq!(device.temperature(TemperatureSensor::Gpu), Ok(Celsius(60)));
+
+        mock.advance(Duration::from_secs(20));
+        assert_eq!(device.temperature(TemperatureSensor::Gpu), Ok(Celsius(90)));
+
+        mock.advance(Duration::from_secs(30));
+        assert_eq!(device.name(), Err(NvmlError::GpuLost));
+        assert_eq!(device.power_usage(), Err(NvmlError::GpuLost));
+    }
+
+    #[test]
+    fn delay() {
+        let mock = MockNvml::builder()
+            .device(MockDevice::new("NVIDIA Mock GPU").power_usage([Milliwatts(150_000)]))
+            .device(
+                MockDevice::new("NVIDIA Other GPU")
+                    .uuid("GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5")
+                    .power_usage([Milliwatts(150_000)]),
+            )
+            .init()
+            .unwrap();
+        mock.update(0, |device| {
+            device.fault_at(
+                Duration::ZERO,
+                MockFault::Delay {
+                    function: "nvmlDeviceGetPowerUsage".into(),
+                    delay: Duration::from_millis(50),
+                },
+            );
+        });
+        let device = mock.device_by_index(0).unwrap();
+        let other = mock.device_by_index(1).unwrap();
+
+        let start = Instant::now();
+        assert_eq!(device.power_usage(), Ok(Milliwatts(150_000)));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        let start = Instant::now();
+        assert_eq!(other.power_usage(), Ok(Milliwatts(150_000)));
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn registry() {
+        let mock = mock();
+        let mut registry = DeviceRegistry::new(&mock).unwrap();
+        assert_eq!(registry.len(), 2);
+
+        mock.update(1, |device| {
+            device.fail("nvmlDeviceGetUUID", NvmlError::Unknown);
+        });
+        assert!(registry.refresh().unwrap().is_empty());
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.failures(), [(1, NvmlError::Unknown)]);
+
+        mock.update(1, |device| {
+            device.succeed("nvmlDeviceGetUUID");
+            device.fault_at(Duration::ZERO, MockFault::GpuLost);
+        });
+        assert_eq!(
+            registry.refresh().unwrap(),
+            [RegistryEvent::Removed {
+                uuid: "GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5".into()
+            }]
+        );
+        assert!(registry.failures().is_empty());
+
+        // The device count didn't change
+        assert!(registry.wait(Duration::ZERO).unwrap().is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn topology() {
+        let mock = mock();
+        mock.update(1, |device| {
+            device.fail("nvmlDeviceGetUUID", NvmlError::Unknown);
+        });
+
+        let graph = TopologyGraph::build(&mock).unwrap();
+
+        assert_eq!(graph.gpus.len(), 1);
+        assert_eq!(graph.gpus[0].name, "NVIDIA Mock GPU");
+        assert!(graph.failures.contains(&(1, NvmlError::Unknown)));
+    }
+
+    #[test]
+    fn init_error() {
+        let result = MockNvml::builder()
+            .init_error(NvmlError::DriverNotLoaded(None))
+            .init();
+
+        assert!(matches!(result, Err(NvmlError::DriverNotLoaded(Some(_)))));
+
+        // The failed mock doesn't block the next one
+        assert_eq!(mock().device_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn query() {
+        let mock = mock();
+        let snapshots = DeviceQuery::builder().all().build().run_all(&mock).unwrap();
+
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].metrics.fan_speeds, None);
+        assert_eq!(
+            snapshots[0].metrics.clocks.as_ref().unwrap().memory,
+            Megahertz(5000)
+        );
+        assert_eq!(
+            snapshots[0].metrics.power.as_ref().unwrap().enforced_limit,
+            Milliwatts(300_000)
+        );
+        assert_eq!(
+            snapshots[0].metrics.compute_processes.as_ref().unwrap()[0].pid,
+            100
+        );
+        assert_eq!(snapshots[0].metrics.graphics_processes, None);
+        assert_eq!(snapshots[1].metrics.utilization, None);
+        assert_eq!(snapshots[1].pci_bus_id, "00000000:0A:00.0");
+    }
+}
@@ -0,0 +1,91 @@
+/*!
+Which NVML functions require elevated privileges.
+
+Taken from the docs of the corresponding methods in this crate (which are in
+turn taken from NVIDIA's docs) and used to give context to
+`NvmlError::NoPermission` errors.
+*/
+
+use crate::enum_wrappers::device::Api;
+use crate::error::RequiredPrivilege::{self, *};
+
+// (function, required privilege), sorted by function.
+static PERMISSIONS: &[(&str, RequiredPrivilege)] = &[
+    ("nvmlComputeInstanceDestroy", Root),
+    ("nvmlDeviceClearAccountingPids", Root),
+    ("nvmlDeviceClearEccErrorCounts", Root),
+    ("nvmlDeviceCreateGpuInstance", Root),
+    ("nvmlDeviceCreateGpuInstanceWithPlacement", Root),
+    ("nvmlDeviceDiscoverGpus", Root),
+    ("nvmlDeviceGetGpuInstanceById", Root),
+    ("nvmlDeviceGetGpuInstances", Root),
+    ("nvmlDeviceModifyDrainState", Root),
+    ("nvmlDeviceRemoveGpu_v2", Root),
+    (
+        "nvmlDeviceResetApplicationsClocks",
+        RootWhileRestricted(Api::ApplicationClocks),
+    ),
+    ("nvmlDeviceResetGpuLockedClocks", Root),
+    ("nvmlDeviceResetMemoryLockedClocks", Root),
+    ("nvmlDeviceSetAPIRestriction", Root),
+    ("nvmlDeviceSetAccountingMode", Root),
+    (
+        "nvmlDeviceSetApplicationsClocks",
+        RootWhileRestricted(Api::ApplicationClocks),
+    ),
+    (
+        "nvmlDeviceSetAutoBoostedClocksEnabled",
+        RootWhileRestricted(Api::AutoBoostedClocks),
+    ),
+    ("nvmlDeviceSetComputeMode", Root),
+    ("nvmlDeviceSetDefaultAutoBoostedClocksEnabled", Root),
+    ("nvmlDeviceSetDefaultFanSpeed_v2", Root),
+    ("nvmlDeviceSetDriverModel", Root),
+    ("nvmlDeviceSetEccMode", Root),
+    ("nvmlDeviceSetFanControlPolicy", Root),
+    ("nvmlDeviceSetFanSpeed_v2", Root),
+    ("nvmlDeviceSetGpcClkVfOffset", Root),
+    ("nvmlDeviceSetGpuLockedClocks", Root),
+    ("nvmlDeviceSetGpuOperationMode", Root),
+    ("nvmlDeviceSetMemClkVfOffset", Root),
+    ("nvmlDeviceSetMemoryLockedClocks", Root),
+    ("nvmlDeviceSetPersistenceMode", Root),
+    ("nvmlDeviceSetPowerManagementLimit", Root),
+    ("nvmlDeviceSetPowerManagementLimit_v2", Root),
+    ("nvmlDeviceSetTemperatureThreshold", Root),
+    ("nvmlDeviceSetVgpuHeterogeneousMode", Root),
+    ("nvmlDeviceWorkloadPowerProfileClearRequestedProfiles", Root),
+    ("nvmlDeviceWorkloadPowerProfileSetRequestedProfiles", Root),
+    ("nvmlGpuInstanceCreateComputeInstance", Root),
+    ("nvmlGpuInstanceDestroy", Root),
+    ("nvmlGpuInstanceGetComputeInstances", Root),
+    ("nvmlUnitSetLedState", Root),
+];
+
+/// Look up the privilege required to call the given NVML function.
+pub(crate) fn lookup(function: &str) -> Option<RequiredPrivilege> {
+    PERMISSIONS
+        .binary_search_by_key(&function, |(f, _)| f)
+        .ok()
+        .map(|i| PERMISSIONS[i].1.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(PERMISSIONS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn lookup_known_function() {
+        assert_eq!(lookup("nvmlDeviceSetPowerManagementLimit"), Some(Root));
+        assert_eq!(
+            lookup("nvmlDeviceSetApplicationsClocks"),
+            Some(RootWhileRestricted(Api::ApplicationClocks))
+        );
+        assert_eq!(lookup("nvmlDeviceGetPowerUsage"), None);
+    }
+}
@@ -1,19 +1,29 @@
-use crate::bitmasks::device::FbcFlags;
-use crate::enum_wrappers::device::{BridgeChip, EncoderType, FbcSessionType, SampleValueType};
-use crate::enums::device::{FirmwareVersion, SampleValue, UsedGpuMemory};
-use crate::error::{nvml_try, Bits, NvmlError};
+use crate::bitmasks::device::{ClocksEventReasons, FbcFlags};
+use crate::enum_wrappers::device::{
+    BridgeChip, EncoderType, FbcSessionType, LicenseFeatureCode, PerformanceState, PowerProfile,
+    SampleValueType,
+};
+use crate::enums::device::{
+    FabricState, FirmwareVersion, LicenseExpiry, SampleValue, UsedGpuMemory,
+};
+use crate::error::{nvml_try, NvmlError};
 use crate::ffi::bindings::*;
 use crate::structs::device::FieldId;
+use crate::units::{Celsius, Megahertz, Milliwatts};
+use crate::uuid::Uuid;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
     ffi::{CStr, CString},
+    fmt,
+    str::FromStr,
 };
 use std::{
     convert::{TryFrom, TryInto},
     os::raw::c_char,
 };
+use thiserror::Error;
 
 /// PCI information about a GPU device.
 // Checked against local
@@ -131,6 +141,119 @@ impl TryInto<nvmlPciInfo_t> for PciInfo {
     }
 }
 
+/// Formats as the bus ID followed by the vendor and device IDs, as `lspci -nn`
+/// does, e.g. `00000000:01:00.0 [10de:2206]`.
+impl fmt::Display for PciInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{:04x}:{:04x}]",
+            self.bus_id,
+            self.pci_device_id & 0xffff,
+            self.pci_device_id >> 16
+        )
+    }
+}
+
+impl PciInfo {
+    /**
+    Parse `bus_id` into its parts, including the function number that the
+    other fields leave out.
+
+    # Errors
+
+    * `ParsePciBusIdError`, if `bus_id` is not a valid PCI bus ID. This should
+      only be able to occur if the user modifies `bus_id` in some fashion.
+    */
+    pub fn parsed_bus_id(&self) -> Result<PciBusId, ParsePciBusIdError> {
+        self.bus_id.parse()
+    }
+}
+
+/// Returned when parsing a `PciBusId` fails.
+#[derive(Error, Debug, Clone, Eq, PartialEq, Hash)]
+#[error("invalid PCI bus ID: {0:?}")]
+pub struct ParsePciBusIdError(pub String);
+
+/**
+A PCI bus ID, i.e. the `<domain>:<bus>:<device>.<function>` address of a device.
+
+Formats the way NVML does, e.g. `00000000:3B:00.0`, which `Nvml.device_by_pci_bus_id()`
+accepts. Parses case-insensitively from that format as well as from the 4
+digit domain of sysfs (`0000:3b:00.0`) and the domainless format of `lspci`
+(`3b:00.0`, in domain 0).
+*/
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PciBusId {
+    /// 0 to 0xffffffff.
+    pub domain: u32,
+    /// 0 to 0xff.
+    pub bus: u32,
+    /// 0 to 31.
+    pub device: u32,
+    /// 0 to 7.
+    pub function: u32,
+}
+
+impl PciBusId {
+    /// Format the way sysfs does, e.g. `0000:3b:00.0`, as used in the paths
+    /// under `/sys/bus/pci/devices`.
+    pub fn to_sysfs(&self) -> String {
+        format!(
+            "{:04x}:{:02x}:{:02x}.{:x}",
+            self.domain, self.bus, self.device, self.function
+        )
+    }
+}
+
+impl FromStr for PciBusId {
+    type Err = ParsePciBusIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParsePciBusIdError(s.into());
+        let hex = |part: &str, max_len: usize, max: u32| {
+            // `from_str_radix()` accepts a leading sign
+            if part.is_empty()
+                || part.len() > max_len
+                || !part.bytes().all(|b| b.is_ascii_hexdigit())
+            {
+                return Err(err());
+            }
+
+            u32::from_str_radix(part, 16)
+                .ok()
+                .filter(|&value| value <= max)
+                .ok_or_else(err)
+        };
+
+        let (address, function) = s.rsplit_once('.').ok_or_else(err)?;
+        let parts: Vec<&str> = address.split(':').collect();
+        let (domain, bus, device) = match parts[..] {
+            [domain, bus, device] => (hex(domain, 8, u32::MAX)?, bus, device),
+            [bus, device] => (0, bus, device),
+            _ => return Err(err()),
+        };
+
+        Ok(Self {
+            domain,
+            bus: hex(bus, 2, 0xff)?,
+            device: hex(device, 2, 31)?,
+            function: hex(function, 1, 7)?,
+        })
+    }
+}
+
+impl fmt::Display for PciBusId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:08X}:{:02X}:{:02X}.{:X}",
+            self.domain, self.bus, self.device, self.function
+        )
+    }
+}
+
 /// BAR1 memory allocation information for a device (in bytes)
 // Checked against local
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -261,6 +384,19 @@ impl From<nvmlProcessInfo_t> for ProcessInfo {
     }
 }
 
+/// Used by legacy drivers that don't support MIG; the instance IDs are always
+/// `None`.
+impl From<nvmlProcessInfo_v1_t> for ProcessInfo {
+    fn from(struct_: nvmlProcessInfo_v1_t) -> Self {
+        Self {
+            pid: struct_.pid,
+            used_gpu_memory: UsedGpuMemory::from(struct_.usedGpuMemory),
+            gpu_instance_id: None,
+            compute_instance_id: None,
+        }
+    }
+}
+
 /// Detailed ECC error counts for a device.
 // Checked against local
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -283,6 +419,61 @@ impl From<nvmlEccErrorCounts_t> for EccErrorCounts {
     }
 }
 
+/// Frequently-polled metrics for a device, returned from `Device.hot_metrics()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HotMetrics {
+    pub power_usage: Milliwatts,
+    /// Energy consumed since the driver was last reloaded in millijoules, if
+    /// the device reports it.
+    pub total_energy_consumption: Option<u64>,
+    /// The GPU die temperature.
+    pub gpu_temperature: Celsius,
+    /// The memory temperature, if the device reports it.
+    pub memory_temperature: Option<Celsius>,
+    /// GPU and memory utilization.
+    pub utilization: Utilization,
+    pub graphics_clock: Megahertz,
+    pub sm_clock: Megahertz,
+    pub memory_clock: Megahertz,
+}
+
+/**
+The supported and currently active clocks event reasons of a device, returned
+from `Device.clocks_event_reasons_status()`.
+
+Unknown bits (see `ClocksEventReasons.unknown_bits()`) are kept in both sets
+and carried through the methods below.
+*/
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClocksEventReasonsStatus {
+    /// The reasons the device can report.
+    pub supported: ClocksEventReasons,
+    /// The reasons currently limiting the device's clocks.
+    pub active: ClocksEventReasons,
+}
+
+impl ClocksEventReasonsStatus {
+    /// The reasons the device supports that are not currently active.
+    pub fn inactive(&self) -> ClocksEventReasons {
+        self.supported - self.active
+    }
+
+    /// The active reasons that the device does not claim to support.
+    ///
+    /// Empty unless the driver's reports are inconsistent.
+    pub fn unexpected(&self) -> ClocksEventReasons {
+        self.active - self.supported
+    }
+
+    /// Whether or not the device can report all of the given `reasons`, i.e.
+    /// whether an alert rule on them can ever fire.
+    pub fn can_report(&self, reasons: ClocksEventReasons) -> bool {
+        self.supported.contains(reasons)
+    }
+}
+
 /// Memory allocation information for a device (in bytes).
 // Checked against local
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -332,6 +523,40 @@ impl From<nvmlUtilization_t> for Utilization {
     }
 }
 
+/// The resources of a MIG device, returned from `MigDevice.attributes()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceAttributes {
+    pub multiprocessor_count: u32,
+    pub shared_copy_engine_count: u32,
+    pub shared_decoder_count: u32,
+    pub shared_encoder_count: u32,
+    pub shared_jpeg_count: u32,
+    pub shared_ofa_count: u32,
+    /// The number of GPU slices of the GPU instance.
+    pub gpu_instance_slice_count: u32,
+    /// The number of GPU slices of the compute instance.
+    pub compute_instance_slice_count: u32,
+    /// Memory size in MB.
+    pub memory_size_mb: u64,
+}
+
+impl From<nvmlDeviceAttributes_t> for DeviceAttributes {
+    fn from(struct_: nvmlDeviceAttributes_t) -> Self {
+        Self {
+            multiprocessor_count: struct_.multiprocessorCount,
+            shared_copy_engine_count: struct_.sharedCopyEngineCount,
+            shared_decoder_count: struct_.sharedDecoderCount,
+            shared_encoder_count: struct_.sharedEncoderCount,
+            shared_jpeg_count: struct_.sharedJpegCount,
+            shared_ofa_count: struct_.sharedOfaCount,
+            gpu_instance_slice_count: struct_.gpuInstanceSliceCount,
+            compute_instance_slice_count: struct_.computeInstanceSliceCount,
+            memory_size_mb: struct_.memorySizeMB,
+        }
+    }
+}
+
 /// Performance policy violation status data.
 // Checked against local
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -631,8 +856,10 @@ impl TryFrom<nvmlFBCSessionInfo_t> for FbcSessionInfo {
     # Errors
 
     * `UnexpectedVariant`, for which you can read the docs for
-    * `IncorrectBits`, if the `sessionFlags` from the given struct do match the
-    wrapper definition
+
+    Any bits in `sessionFlags` that don't correspond to flags present in this
+    version of the wrapper are kept and can be read with
+    `FbcFlags.unknown_bits()`.
     */
     fn try_from(value: nvmlFBCSessionInfo_t) -> Result<Self, Self::Error> {
         Ok(Self {
@@ -644,8 +871,7 @@ impl TryFrom<nvmlFBCSessionInfo_t> for FbcSessionInfo {
             },
             display_ordinal: value.displayOrdinal,
             session_type: FbcSessionType::try_from(value.sessionType)?,
-            session_flags: FbcFlags::from_bits(value.sessionFlags)
-                .ok_or(NvmlError::IncorrectBits(Bits::U32(value.sessionFlags)))?,
+            session_flags: FbcFlags::from_bits_retain(value.sessionFlags),
             hres_max: value.hMaxResolution,
             vres_max: value.vMaxResolution,
             hres: value.hResolution,
@@ -656,14 +882,539 @@ impl TryFrom<nvmlFBCSessionInfo_t> for FbcSessionInfo {
     }
 }
 
+/// A workload power profile supported by a device, returned from
+/// `Device.workload_power_profiles()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WorkloadPowerProfileInfo {
+    pub profile: PowerProfile,
+    /// Lower values take precedence when conflicting profiles are requested.
+    pub priority: u32,
+    /// The profiles that conflict with this one.
+    pub conflicting: Vec<PowerProfile>,
+}
+
+impl TryFrom<nvmlWorkloadPowerProfileInfo_t> for WorkloadPowerProfileInfo {
+    type Error = NvmlError;
+
+    /**
+    Construct `WorkloadPowerProfileInfo` from the corresponding C struct.
+
+    # Errors
+
+    * `UnexpectedVariant`, for which you can read the docs for
+    */
+    fn try_from(value: nvmlWorkloadPowerProfileInfo_t) -> Result<Self, Self::Error> {
+        Ok(Self {
+            profile: PowerProfile::try_from(value.profileId)?,
+            priority: value.priority,
+            conflicting: power_profiles_from_mask(&value.conflictingMask)?,
+        })
+    }
+}
+
+/// The workload power profiles of a device, returned from
+/// `Device.current_workload_power_profiles()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CurrentPowerProfiles {
+    /// The profiles supported by the device.
+    pub supported: Vec<PowerProfile>,
+    /// The profiles that have been requested.
+    pub requested: Vec<PowerProfile>,
+    /// The profiles in effect after resolving conflicts between the requested
+    /// profiles.
+    pub enforced: Vec<PowerProfile>,
+}
+
+impl TryFrom<nvmlWorkloadPowerProfileCurrentProfiles_t> for CurrentPowerProfiles {
+    type Error = NvmlError;
+
+    /**
+    Construct `CurrentPowerProfiles` from the corresponding C struct.
+
+    # Errors
+
+    * `UnexpectedVariant`, for which you can read the docs for
+    */
+    fn try_from(value: nvmlWorkloadPowerProfileCurrentProfiles_t) -> Result<Self, Self::Error> {
+        Ok(Self {
+            supported: power_profiles_from_mask(&value.perfProfilesMask)?,
+            requested: power_profiles_from_mask(&value.requestedProfilesMask)?,
+            enforced: power_profiles_from_mask(&value.enforcedProfilesMask)?,
+        })
+    }
+}
+
+/// A GPU's registration with the NVSwitch fabric, returned from
+/// `Device.fabric_info()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FabricInfo {
+    /// The UUID of the cluster the GPU belongs to.
+    pub cluster_uuid: Uuid,
+    /// The ID of the fabric clique (partition) the GPU belongs to.
+    ///
+    /// GPUs in the same cluster and clique can communicate with each other
+    /// over NVLink.
+    pub clique_id: u32,
+    /// How far along registration with the fabric manager is.
+    ///
+    /// `cluster_uuid` and `clique_id` are only meaningful once this is
+    /// `FabricState::Completed`.
+    pub state: FabricState,
+}
+
+impl TryFrom<nvmlGpuFabricInfo_t> for FabricInfo {
+    type Error = NvmlError;
+
+    /**
+    Construct `FabricInfo` from the corresponding C struct.
+
+    # Errors
+
+    * `UnexpectedVariant`, for which you can read the docs for
+    * any error that registration with the fabric completed with
+    */
+    fn try_from(value: nvmlGpuFabricInfo_t) -> Result<Self, Self::Error> {
+        let state = FabricState::try_from(value.state)?;
+
+        if state == FabricState::Completed {
+            nvml_try(value.status)?;
+        }
+
+        Ok(Self {
+            cluster_uuid: Uuid(value.clusterUuid),
+            clique_id: value.cliqueId,
+            state,
+        })
+    }
+}
+
+/// Where a MIG GPU instance sits on its GPU, or a compute instance within its
+/// GPU instance, in slices.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InstancePlacement {
+    /// The index of the first slice.
+    pub start: u32,
+    /// The number of slices.
+    pub size: u32,
+}
+
+impl From<nvmlGpuInstancePlacement_t> for InstancePlacement {
+    fn from(struct_: nvmlGpuInstancePlacement_t) -> Self {
+        Self {
+            start: struct_.start,
+            size: struct_.size,
+        }
+    }
+}
+
+impl From<nvmlComputeInstancePlacement_t> for InstancePlacement {
+    fn from(struct_: nvmlComputeInstancePlacement_t) -> Self {
+        Self {
+            start: struct_.start,
+            size: struct_.size,
+        }
+    }
+}
+
+impl From<InstancePlacement> for nvmlGpuInstancePlacement_t {
+    fn from(placement: InstancePlacement) -> Self {
+        Self {
+            start: placement.start,
+            size: placement.size,
+        }
+    }
+}
+
+/// The resources of a MIG GPU instance profile on a given GPU, returned from
+/// `Device.gpu_instance_profile_info()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GpuInstanceProfileInfo {
+    /// The ID NVML knows the profile by on this GPU, which is passed to the
+    /// functions that create or list GPU instances of this profile.
+    pub id: u32,
+    pub is_p2p_supported: bool,
+    pub slice_count: u32,
+    /// The maximum number of GPU instances of this profile.
+    pub instance_count: u32,
+    pub multiprocessor_count: u32,
+    pub copy_engine_count: u32,
+    pub decoder_count: u32,
+    pub encoder_count: u32,
+    pub jpeg_count: u32,
+    pub ofa_count: u32,
+    /// The memory of a GPU instance of this profile, in MiB.
+    pub memory_size_mb: u64,
+}
+
+impl From<nvmlGpuInstanceProfileInfo_t> for GpuInstanceProfileInfo {
+    fn from(struct_: nvmlGpuInstanceProfileInfo_t) -> Self {
+        Self {
+            id: struct_.id,
+            is_p2p_supported: struct_.isP2pSupported != 0,
+            slice_count: struct_.sliceCount,
+            instance_count: struct_.instanceCount,
+            multiprocessor_count: struct_.multiprocessorCount,
+            copy_engine_count: struct_.copyEngineCount,
+            decoder_count: struct_.decoderCount,
+            encoder_count: struct_.encoderCount,
+            jpeg_count: struct_.jpegCount,
+            ofa_count: struct_.ofaCount,
+            memory_size_mb: struct_.memorySizeMB,
+        }
+    }
+}
+
+/// The resources of a MIG compute instance profile within a given GPU
+/// instance, returned from `GpuInstance.compute_instance_profile_info()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComputeInstanceProfileInfo {
+    /// The ID NVML knows the profile by within the GPU instance, which is
+    /// passed to the functions that create or list compute instances of this
+    /// profile.
+    pub id: u32,
+    pub slice_count: u32,
+    /// The maximum number of compute instances of this profile.
+    pub instance_count: u32,
+    pub multiprocessor_count: u32,
+    pub shared_copy_engine_count: u32,
+    pub shared_decoder_count: u32,
+    pub shared_encoder_count: u32,
+    pub shared_jpeg_count: u32,
+    pub shared_ofa_count: u32,
+}
+
+impl From<nvmlComputeInstanceProfileInfo_t> for ComputeInstanceProfileInfo {
+    fn from(struct_: nvmlComputeInstanceProfileInfo_t) -> Self {
+        Self {
+            id: struct_.id,
+            slice_count: struct_.sliceCount,
+            instance_count: struct_.instanceCount,
+            multiprocessor_count: struct_.multiprocessorCount,
+            shared_copy_engine_count: struct_.sharedCopyEngineCount,
+            shared_decoder_count: struct_.sharedDecoderCount,
+            shared_encoder_count: struct_.sharedEncoderCount,
+            shared_jpeg_count: struct_.sharedJpegCount,
+            shared_ofa_count: struct_.sharedOfaCount,
+        }
+    }
+}
+
+/// Returned from `GpuInstance.info()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GpuInstanceInfo {
+    /// The ID of the GPU instance, unique within its GPU.
+    pub id: u32,
+    /// The ID of the GPU instance's profile; see `GpuInstanceProfileInfo.id`.
+    pub profile_id: u32,
+    pub placement: InstancePlacement,
+}
+
+impl From<nvmlGpuInstanceInfo_t> for GpuInstanceInfo {
+    fn from(struct_: nvmlGpuInstanceInfo_t) -> Self {
+        Self {
+            id: struct_.id,
+            profile_id: struct_.profileId,
+            placement: struct_.placement.into(),
+        }
+    }
+}
+
+/// Returned from `ComputeInstance.info()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComputeInstanceInfo {
+    /// The ID of the compute instance, unique within its GPU instance.
+    pub id: u32,
+    /// The ID of the compute instance's profile; see
+    /// `ComputeInstanceProfileInfo.id`.
+    pub profile_id: u32,
+    pub placement: InstancePlacement,
+}
+
+impl From<nvmlComputeInstanceInfo_t> for ComputeInstanceInfo {
+    fn from(struct_: nvmlComputeInstanceInfo_t) -> Self {
+        Self {
+            id: struct_.id,
+            profile_id: struct_.profileId,
+            placement: struct_.placement.into(),
+        }
+    }
+}
+
+/// A vGPU software feature that can be licensed on a guest, as reported by
+/// `Device.licensable_features()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LicensableFeature {
+    pub code: LicenseFeatureCode,
+    /// Whether or not a license is held for this feature.
+    pub is_licensed: bool,
+    /// Whether or not this feature is enabled.
+    pub is_enabled: bool,
+    /// Information about the license, such as its version.
+    pub license_info: String,
+    /// The name of the product this feature belongs to.
+    pub product_name: String,
+    /// When the license expires.
+    pub expiry: LicenseExpiry,
+}
+
+impl TryFrom<nvmlGridLicensableFeature_t> for LicensableFeature {
+    type Error = NvmlError;
+
+    /**
+    Construct `LicensableFeature` from the corresponding C struct.
+
+    # Errors
+
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Utf8Error`, if strings obtained from the C struct are not valid Utf8
+    */
+    fn try_from(value: nvmlGridLicensableFeature_t) -> Result<Self, Self::Error> {
+        unsafe {
+            let license_info_raw = CStr::from_ptr(value.licenseInfo.as_ptr());
+            let product_name_raw = CStr::from_ptr(value.productName.as_ptr());
+
+            Ok(Self {
+                code: LicenseFeatureCode::try_from(value.featureCode)?,
+                is_licensed: value.featureState != 0,
+                is_enabled: value.featureEnabled != 0,
+                license_info: license_info_raw.to_str()?.into(),
+                product_name: product_name_raw.to_str()?.into(),
+                expiry: LicenseExpiry::try_from(value.licenseExpiry)?,
+            })
+        }
+    }
+}
+
+/// The vGPU license state of a guest `Device`, returned from
+/// `Device.licensable_features()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LicensableFeatures {
+    /// Whether or not vGPU software licensing is supported on the device.
+    pub is_licensing_supported: bool,
+    pub features: Vec<LicensableFeature>,
+}
+
+impl LicensableFeatures {
+    /// Whether or not any feature is currently licensed.
+    ///
+    /// Unlicensed guests run with reduced performance once the licensing grace
+    /// period runs out.
+    pub fn is_licensed(&self) -> bool {
+        self.features.iter().any(|f| f.is_licensed)
+    }
+}
+
+impl TryFrom<nvmlGridLicensableFeatures_t> for LicensableFeatures {
+    type Error = NvmlError;
+
+    /**
+    Construct `LicensableFeatures` from the corresponding C struct.
+
+    # Errors
+
+    * `UnexpectedVariant`, for which you can read the docs for
+    * `Utf8Error`, if strings obtained from the C struct are not valid Utf8
+    */
+    fn try_from(value: nvmlGridLicensableFeatures_t) -> Result<Self, Self::Error> {
+        let count =
+            (value.licensableFeaturesCount as usize).min(value.gridLicensableFeatures.len());
+
+        Ok(Self {
+            is_licensing_supported: value.isGridLicenseSupported != 0,
+            features: value.gridLicensableFeatures[..count]
+                .iter()
+                .map(|f| LicensableFeature::try_from(*f))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/**
+A performance mode (P-state) of a `Device` along with the clock ranges it
+allows, returned from `Device.performance_modes()`.
+
+NVML doesn't report every value on every GPU and may add new ones in newer
+drivers, so all of the fields are optional and values this wrapper doesn't
+understand are kept in `unknown_tokens`.
+*/
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PerformanceMode {
+    pub performance_state: Option<PerformanceState>,
+    pub graphics_clock_min: Option<Megahertz>,
+    pub graphics_clock_max: Option<Megahertz>,
+    pub memory_clock_min: Option<Megahertz>,
+    pub memory_clock_max: Option<Megahertz>,
+    /// `token=value` pairs that weren't recognized, in the order NVML
+    /// reported them.
+    pub unknown_tokens: Vec<(String, String)>,
+}
+
+impl PerformanceMode {
+    fn parse(entry: &str) -> Self {
+        let mut mode = Self::default();
+        // Only reported for backwards compatibility, as the minimum clocks
+        let (mut graphics_clock, mut memory_clock) = (None, None);
+
+        for (token, value) in perf_tokens(entry) {
+            let known = match token {
+                "perf" => parse_pstate(value).map(|s| mode.performance_state = Some(s)),
+                "nvclock" => parse_mhz(value).map(|c| graphics_clock = Some(c)),
+                "nvclockmin" => parse_mhz(value).map(|c| mode.graphics_clock_min = Some(c)),
+                "nvclockmax" => parse_mhz(value).map(|c| mode.graphics_clock_max = Some(c)),
+                "memclock" => parse_mhz(value).map(|c| memory_clock = Some(c)),
+                "memclockmin" => parse_mhz(value).map(|c| mode.memory_clock_min = Some(c)),
+                "memclockmax" => parse_mhz(value).map(|c| mode.memory_clock_max = Some(c)),
+                _ => None,
+            };
+
+            if known.is_none() {
+                mode.unknown_tokens.push((token.into(), value.into()));
+            }
+        }
+
+        mode.graphics_clock_min = mode.graphics_clock_min.or(graphics_clock);
+        mode.memory_clock_min = mode.memory_clock_min.or(memory_clock);
+        mode
+    }
+}
+
+/**
+Parse the `;`-separated performance modes in the given C struct.
+
+# Errors
+
+* `Utf8Error`, if the string obtained from the C struct is not valid Utf8
+*/
+pub(crate) fn performance_modes_from_c(
+    value: &nvmlDevicePerfModes_t,
+) -> Result<Vec<PerformanceMode>, NvmlError> {
+    let modes = unsafe { CStr::from_ptr(value.str.as_ptr()) }.to_str()?;
+
+    Ok(modes
+        .split(';')
+        .map(str::trim)
+        .filter(|mode| !mode.is_empty())
+        .map(PerformanceMode::parse)
+        .collect())
+}
+
+/**
+The clocks a `Device` is currently running at, returned from
+`Device.current_clock_freqs()`.
+
+As with `PerformanceMode`, all of the fields are optional and values this
+wrapper doesn't understand are kept in `unknown_tokens`.
+*/
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CurrentClockFreqs {
+    pub performance_state: Option<PerformanceState>,
+    pub graphics_clock: Option<Megahertz>,
+    pub memory_clock: Option<Megahertz>,
+    /// `token=value` pairs that weren't recognized, in the order NVML
+    /// reported them.
+    pub unknown_tokens: Vec<(String, String)>,
+}
+
+impl TryFrom<nvmlDeviceCurrentClockFreqs_t> for CurrentClockFreqs {
+    type Error = NvmlError;
+
+    /**
+    Construct `CurrentClockFreqs` from the corresponding C struct.
+
+    # Errors
+
+    * `Utf8Error`, if the string obtained from the C struct is not valid Utf8
+    */
+    fn try_from(value: nvmlDeviceCurrentClockFreqs_t) -> Result<Self, Self::Error> {
+        let freqs = unsafe { CStr::from_ptr(value.str.as_ptr()) }.to_str()?;
+        let mut clocks = Self::default();
+
+        for (token, value) in perf_tokens(freqs) {
+            let known = match token {
+                "perf" => parse_pstate(value).map(|s| clocks.performance_state = Some(s)),
+                "nvclock" => parse_mhz(value).map(|c| clocks.graphics_clock = Some(c)),
+                "memclock" => parse_mhz(value).map(|c| clocks.memory_clock = Some(c)),
+                _ => None,
+            };
+
+            if known.is_none() {
+                clocks.unknown_tokens.push((token.into(), value.into()));
+            }
+        }
+
+        Ok(clocks)
+    }
+}
+
+// Split a `token=value, token=value` list from the performance mode strings
+// into its pairs.
+fn perf_tokens(list: &str) -> impl Iterator<Item = (&str, &str)> + '_ {
+    list.split(',').filter_map(|pair| {
+        let (token, value) = pair.split_once('=')?;
+        Some((token.trim(), value.trim()))
+    })
+}
+
+fn parse_pstate(value: &str) -> Option<PerformanceState> {
+    PerformanceState::try_from(value.parse::<nvmlPStates_enum>().ok()?).ok()
+}
+
+fn parse_mhz(value: &str) -> Option<Megahertz> {
+    value.parse().ok().map(Megahertz)
+}
+
+/// Get the profiles whose IDs are set in the given mask, in ID order.
+pub(crate) fn power_profiles_from_mask(
+    mask: &nvmlMask255_t,
+) -> Result<Vec<PowerProfile>, NvmlError> {
+    (0..NVML_WORKLOAD_POWER_MAX_PROFILES)
+        .filter(|&id| mask_bit(mask, id))
+        .map(PowerProfile::try_from)
+        .collect()
+}
+
+/// Build a mask with the IDs of the given profiles set.
+pub(crate) fn power_profiles_to_mask(profiles: &[PowerProfile]) -> nvmlMask255_t {
+    let mut mask = nvmlMask255_t {
+        mask: [0; NVML_255_MASK_NUM_ELEMS as usize],
+    };
+
+    for profile in profiles {
+        let id = profile.as_c();
+        mask.mask[(id / NVML_255_MASK_BITS_PER_ELEM) as usize] |=
+            1 << (id % NVML_255_MASK_BITS_PER_ELEM);
+    }
+
+    mask
+}
+
+pub(crate) fn mask_bit(mask: &nvmlMask255_t, id: u32) -> bool {
+    mask.mask[(id / NVML_255_MASK_BITS_PER_ELEM) as usize]
+        & (1 << (id % NVML_255_MASK_BITS_PER_ELEM))
+        != 0
+}
+
 #[cfg(test)]
 #[allow(unused_variables, unused_imports)]
 mod tests {
     use crate::error::*;
     use crate::ffi::bindings::*;
     use crate::test_utils::*;
-    use std::convert::TryInto;
+    use std::convert::{TryFrom, TryInto};
     use std::mem;
+    use std::os::raw::c_char;
 
     #[test]
     fn pci_info_from_to_c() {
@@ -693,4 +1444,243 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn pci_bus_id() {
+        use super::{ParsePciBusIdError, PciBusId};
+
+        let id = PciBusId {
+            domain: 0,
+            bus: 0x3b,
+            device: 0,
+            function: 1,
+        };
+
+        for s in &["00000000:3B:00.1", "0000:3b:00.1", "3b:00.1"] {
+            assert_eq!(s.parse(), Ok(id), "{}", s);
+        }
+
+        assert_eq!(id.to_string(), "00000000:3B:00.1");
+        assert_eq!(id.to_sysfs(), "0000:3b:00.1");
+
+        for s in &[
+            "",
+            "3b:00",
+            "3b:20.0",
+            "3b:00.8",
+            "100:00.0",
+            "0:0:3b:00.0",
+            "3b:0g.0",
+            "0000:+1:00.0",
+            "+0000000:01:+0.0",
+            "3b:00.+1",
+        ] {
+            assert_eq!(
+                s.parse::<PciBusId>(),
+                Err(ParsePciBusIdError(s.to_string())),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn pci_info_display() {
+        use super::PciInfo;
+
+        let info = PciInfo {
+            bus: 1,
+            bus_id: "00000000:01:00.0".into(),
+            device: 0,
+            domain: 0,
+            pci_device_id: 0x2206_10de,
+            pci_sub_system_id: None,
+        };
+
+        assert_eq!(info.to_string(), "00000000:01:00.0 [10de:2206]");
+        assert_eq!(info.parsed_bus_id().map(|id| id.bus), Ok(1));
+    }
+
+    #[test]
+    fn clocks_event_reasons_status() {
+        use super::ClocksEventReasonsStatus;
+        use crate::bitmasks::device::ClocksEventReasons;
+
+        let unknown = 1 << 63;
+        let status = ClocksEventReasonsStatus {
+            supported: ClocksEventReasons::from_bits_retain(
+                (ClocksEventReasons::GPU_IDLE | ClocksEventReasons::SW_POWER_CAP).bits() | unknown,
+            ),
+            active: ClocksEventReasons::SW_POWER_CAP | ClocksEventReasons::HW_SLOWDOWN,
+        };
+
+        assert_eq!(
+            status.inactive().bits(),
+            ClocksEventReasons::GPU_IDLE.bits() | unknown
+        );
+        assert_eq!(status.unexpected(), ClocksEventReasons::HW_SLOWDOWN);
+        assert!(status.can_report(ClocksEventReasons::SW_POWER_CAP));
+        assert!(status.can_report(ClocksEventReasons::NONE));
+        assert!(!status.can_report(ClocksEventReasons::SW_THERMAL_SLOWDOWN));
+    }
+
+    #[test]
+    fn licensable_features_from_c() {
+        use super::LicensableFeatures;
+        use crate::enum_wrappers::device::LicenseFeatureCode;
+        use crate::enums::device::LicenseExpiry;
+
+        let mut raw: nvmlGridLicensableFeatures_t = unsafe { mem::zeroed() };
+        raw.isGridLicenseSupported = 1;
+        raw.licensableFeaturesCount = 1;
+
+        let feature = &mut raw.gridLicensableFeatures[0];
+        feature.featureCode = nvmlGridLicenseFeatureCode_t_NVML_GRID_LICENSE_FEATURE_CODE_VGPU;
+        feature.featureState = 1;
+        feature.featureEnabled = 1;
+        feature.productName[0] = b'v' as _;
+        feature.licenseExpiry.year = 2026;
+        feature.licenseExpiry.month = 10;
+        feature.licenseExpiry.day = 15;
+        feature.licenseExpiry.status = NVML_GRID_LICENSE_EXPIRY_VALID as _;
+
+        let features = LicensableFeatures::try_from(raw).unwrap();
+
+        assert!(features.is_licensing_supported);
+        assert!(features.is_licensed());
+        assert_eq!(features.features.len(), 1);
+        assert_eq!(features.features[0].code, LicenseFeatureCode::Vgpu);
+        assert_eq!(features.features[0].product_name, "v");
+        assert_eq!(
+            features.features[0].expiry,
+            LicenseExpiry::Valid {
+                year: 2026,
+                month: 10,
+                day: 15,
+                hour: 0,
+                min: 0,
+                sec: 0
+            }
+        );
+
+        raw.gridLicensableFeatures[0].licenseExpiry.status = 5;
+        assert!(matches!(
+            LicensableFeatures::try_from(raw),
+            Err(NvmlError::UnexpectedVariant(5))
+        ));
+    }
+
+    #[test]
+    fn power_profile_masks() {
+        use super::{power_profiles_from_mask, power_profiles_to_mask};
+        use crate::enum_wrappers::device::PowerProfile;
+
+        let profiles = vec![PowerProfile::MaxQ, PowerProfile::Mig];
+        let mask = power_profiles_to_mask(&profiles);
+
+        assert_eq!(mask.mask[0], (1 << 1) | (1 << 14));
+        assert_eq!(&mask.mask[1..], &[0; 7]);
+        assert_eq!(power_profiles_from_mask(&mask).unwrap(), profiles);
+
+        let mut unknown = mask;
+        unknown.mask[7] = 1 << 30;
+        assert!(matches!(
+            power_profiles_from_mask(&unknown),
+            Err(NvmlError::UnexpectedVariant(254))
+        ));
+    }
+
+    #[test]
+    fn fabric_info_from_c() {
+        use super::FabricInfo;
+        use crate::enums::device::FabricState;
+
+        let mut raw: nvmlGpuFabricInfo_t = unsafe { mem::zeroed() };
+        raw.clusterUuid[15] = 1;
+        raw.cliqueId = 7;
+        raw.state = NVML_GPU_FABRIC_STATE_COMPLETED as _;
+
+        let info = FabricInfo::try_from(raw).unwrap();
+
+        assert_eq!(info.cluster_uuid.0[15], 1);
+        assert_eq!(info.clique_id, 7);
+        assert_eq!(info.state, FabricState::Completed);
+
+        // The status is only meaningful once registration has completed
+        raw.status = nvmlReturn_enum_NVML_ERROR_UNKNOWN;
+        assert!(matches!(FabricInfo::try_from(raw), Err(NvmlError::Unknown)));
+
+        raw.state = NVML_GPU_FABRIC_STATE_IN_PROGRESS as _;
+        assert_eq!(
+            FabricInfo::try_from(raw).unwrap().state,
+            FabricState::InProgress
+        );
+    }
+
+    fn perf_string(s: &str) -> [c_char; NVML_PERF_MODES_BUFFER_SIZE as usize] {
+        let mut raw = [0; NVML_PERF_MODES_BUFFER_SIZE as usize];
+        for (dst, src) in raw.iter_mut().zip(s.bytes()) {
+            *dst = src as c_char;
+        }
+
+        raw
+    }
+
+    #[test]
+    fn performance_modes_from_c() {
+        use super::{performance_modes_from_c, PerformanceMode};
+        use crate::enum_wrappers::device::PerformanceState;
+        use crate::units::Megahertz;
+
+        let mut raw: nvmlDevicePerfModes_t = unsafe { mem::zeroed() };
+        raw.str = perf_string(
+            "perf=0, nvclock=324, nvclockmin=324, nvclockmax=1980, memclock=405, \
+             memclockmin=405, memclockmax=405; perf=8, nvclock=210, memclock=5001, future=1;",
+        );
+
+        let modes = performance_modes_from_c(&raw).unwrap();
+
+        assert_eq!(
+            modes,
+            vec![
+                PerformanceMode {
+                    performance_state: Some(PerformanceState::Zero),
+                    graphics_clock_min: Some(Megahertz(324)),
+                    graphics_clock_max: Some(Megahertz(1980)),
+                    memory_clock_min: Some(Megahertz(405)),
+                    memory_clock_max: Some(Megahertz(405)),
+                    unknown_tokens: vec![],
+                },
+                // Older drivers only report the minimum clocks
+                PerformanceMode {
+                    performance_state: Some(PerformanceState::Eight),
+                    graphics_clock_min: Some(Megahertz(210)),
+                    memory_clock_min: Some(Megahertz(5001)),
+                    unknown_tokens: vec![("future".into(), "1".into())],
+                    ..PerformanceMode::default()
+                },
+            ]
+        );
+
+        raw.str = perf_string("");
+        assert_eq!(performance_modes_from_c(&raw).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn current_clock_freqs_from_c() {
+        use super::CurrentClockFreqs;
+        use crate::units::Megahertz;
+
+        let mut raw: nvmlDeviceCurrentClockFreqs_t = unsafe { mem::zeroed() };
+        raw.str = perf_string("nvclock=1410, memclock=oops");
+
+        assert_eq!(
+            CurrentClockFreqs::try_from(raw).unwrap(),
+            CurrentClockFreqs {
+                graphics_clock: Some(Megahertz(1410)),
+                unknown_tokens: vec![("memclock".into(), "oops".into())],
+                ..CurrentClockFreqs::default()
+            }
+        );
+    }
 }
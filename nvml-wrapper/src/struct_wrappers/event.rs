@@ -23,15 +23,29 @@ pub struct EventData<'nvml> {
     `None` in the case of any other event type.
     */
     pub event_data: Option<XidError>,
+    /**
+    The GPU instance the event is attributable to.
+
+    `None` if MIG is disabled or the event isn't attributable to a single GPU
+    instance. Compare with `MigDevice.gpu_instance_id()`.
+    */
+    pub gpu_instance_id: Option<u32>,
+    /**
+    The compute instance the event is attributable to.
+
+    `None` if MIG is disabled or the event isn't attributable to a single
+    compute instance. Compare with `MigDevice.compute_instance_id()`.
+    */
+    pub compute_instance_id: Option<u32>,
 }
 
 impl<'nvml> EventData<'nvml> {
     /**
     Create a new `EventData` wrapper.
 
-    The `event_type` bitmask is created via the `EventTypes::from_bits_truncate`
-    method, meaning that any bits that don't correspond to flags present in this
-    version of the wrapper will be dropped.
+    Any bits in the `event_type` bitmask that don't correspond to flags present in
+    this version of the wrapper are kept and can be read with
+    `EventTypes.unknown_bits()`.
 
     # Safety
 
@@ -41,7 +55,7 @@ impl<'nvml> EventData<'nvml> {
     // Clippy bug, see https://github.com/rust-lang/rust-clippy/issues/5593
     #[allow(clippy::missing_safety_doc)]
     pub unsafe fn new(event_data: nvmlEventData_t, nvml: &'nvml Nvml) -> Self {
-        let event_type = EventTypes::from_bits_truncate(event_data.eventType);
+        let event_type = EventTypes::from_bits_retain(event_data.eventType);
 
         EventData {
             // SAFETY: it is the callers responsibility to ensure that `event_data`
@@ -56,6 +70,16 @@ impl<'nvml> EventData<'nvml> {
             } else {
                 None
             },
+            gpu_instance_id: instance_id(event_data.gpuInstanceId),
+            compute_instance_id: instance_id(event_data.computeInstanceId),
         }
     }
 }
+
+// NVML reports `0xFFFFFFFF` when an event isn't attributable to an instance.
+fn instance_id(id: u32) -> Option<u32> {
+    match id {
+        u32::MAX => None,
+        id => Some(id),
+    }
+}
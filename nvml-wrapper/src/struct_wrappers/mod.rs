@@ -39,3 +39,41 @@ impl TryFrom<nvmlExcludedDeviceInfo_t> for ExcludedDeviceInfo {
         }
     }
 }
+
+/// A range of vGPU versions, inclusive on both ends.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VgpuVersionRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl From<nvmlVgpuVersion_t> for VgpuVersionRange {
+    fn from(value: nvmlVgpuVersion_t) -> Self {
+        Self {
+            min: value.minVersion,
+            max: value.maxVersion,
+        }
+    }
+}
+
+impl From<VgpuVersionRange> for nvmlVgpuVersion_t {
+    fn from(value: VgpuVersionRange) -> Self {
+        Self {
+            minVersion: value.min,
+            maxVersion: value.max,
+        }
+    }
+}
+
+/// The vGPU versions supported by the host, returned from
+/// `Nvml.vgpu_version()`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VgpuVersions {
+    /// The range of versions the vGPU manager was built to support.
+    pub supported: VgpuVersionRange,
+    /// The range of versions currently allowed, which is the supported range
+    /// unless it has been narrowed with `Nvml.set_vgpu_version()`.
+    pub current: VgpuVersionRange,
+}
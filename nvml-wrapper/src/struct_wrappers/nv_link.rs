@@ -31,9 +31,9 @@ impl TryFrom<nvmlNvLinkUtilizationControl_t> for UtilizationControl {
     /**
     Construct `UtilizationControl` from the corresponding C struct.
 
-    The `packet_filter` bitmask is created via the `PacketTypes::from_bits_truncate`
-    method, meaning that any bits that don't correspond to flags present in this
-    version of the wrapper will be dropped.
+    Any bits in the `packet_filter` bitmask that don't correspond to flags present in
+    this version of the wrapper are kept and can be read with
+    `PacketTypes.unknown_bits()`.
 
     # Errors
 
@@ -44,7 +44,7 @@ impl TryFrom<nvmlNvLinkUtilizationControl_t> for UtilizationControl {
 
         Ok(UtilizationControl {
             units: UtilizationCountUnit::try_from(value.units)?,
-            packet_filter: PacketTypes::from_bits_truncate(bits),
+            packet_filter: PacketTypes::from_bits_retain(bits),
         })
     }
 }
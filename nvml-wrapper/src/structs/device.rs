@@ -1,6 +1,10 @@
 #[cfg(target_os = "windows")]
 use crate::enum_wrappers::device::DriverModel;
-use crate::enum_wrappers::device::OperationMode;
+use crate::enum_wrappers::device::{Brand, OperationMode, RetirementCause, Sampling};
+use crate::enums::device::{DeviceArchitecture, GpuLockedClocksSetting, LockedClocks};
+use crate::ffi::bindings::field_id::*;
+use crate::struct_wrappers::device::PciInfo;
+use crate::units::{Megahertz, Milliwatts};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -28,7 +32,7 @@ pub struct UtilizationInfo {
 }
 
 /// Returned from `Device.driver_model()`
-#[derive(Debug)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg(target_os = "windows")]
 pub struct DriverModelState {
@@ -36,6 +40,28 @@ pub struct DriverModelState {
     pub pending: DriverModel,
 }
 
+/**
+Returned from `Device.identity()`.
+
+Values that some devices don't support querying are `None` for those devices.
+*/
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceIdentity {
+    pub name: String,
+    pub uuid: String,
+    /// Only available on products with an infoROM.
+    pub serial: Option<String>,
+    pub pci_info: PciInfo,
+    /// Always `None` on platforms other than Linux.
+    pub minor_number: Option<u32>,
+    pub architecture: DeviceArchitecture,
+    pub brand: Brand,
+    pub vbios_version: String,
+    /// Only available on products with an infoROM.
+    pub info_rom_image_version: Option<String>,
+}
+
 /// Returned from `Device.is_ecc_enabled()`
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -44,22 +70,152 @@ pub struct EccModeState {
     pub pending_enabled: bool,
 }
 
+/// Returned from `Device.is_mig_enabled()`
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MigModeState {
+    pub currently_enabled: bool,
+    pub pending_enabled: bool,
+}
+
 /// Returned from `Device.gpu_operation_mode()`
-#[derive(Debug)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OperationModeState {
     pub current: OperationMode,
     pub pending: OperationMode,
 }
 
+/**
+Returned from `Device.pending_changes()`.
+
+Each field holds the current and pending values of a setting whose pending
+value differs from its current one, and is `None` if the setting won't change
+or the `Device` doesn't support it.
+*/
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PendingChanges {
+    /// Takes effect after a reboot.
+    pub ecc: Option<EccModeState>,
+    /// Takes effect after a GPU reset (or a reboot).
+    pub mig: Option<MigModeState>,
+    /// Takes effect after a reboot.
+    pub operation_mode: Option<OperationModeState>,
+    /// Takes effect after a reboot.
+    #[cfg(target_os = "windows")]
+    pub driver_model: Option<DriverModelState>,
+}
+
+impl PendingChanges {
+    /// Whether or not no setting will change.
+    pub fn is_empty(&self) -> bool {
+        !self.requires_reboot() && self.mig.is_none()
+    }
+
+    /// Whether or not a reboot is needed to apply a pending change.
+    ///
+    /// A pending MIG mode change alone doesn't need one; a GPU reset suffices.
+    pub fn requires_reboot(&self) -> bool {
+        #[cfg(target_os = "windows")]
+        let driver_model = self.driver_model.is_some();
+        #[cfg(not(target_os = "windows"))]
+        let driver_model = false;
+
+        self.ecc.is_some() || self.operation_mode.is_some() || driver_model
+    }
+}
+
 /// Returned from `Device.power_management_limit_constraints()`
 ///
 /// Values are in milliwatts.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PowerManagementConstraints {
-    pub min_limit: u32,
-    pub max_limit: u32,
+    pub min_limit: Milliwatts,
+    pub max_limit: Milliwatts,
+}
+
+/// Returned from `Device.fan_speed_constraints()`
+///
+/// Values are percentages of the maximum fan speed, like those passed to
+/// `Device.set_fan_speed()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FanSpeedConstraints {
+    pub min_speed: u32,
+    pub max_speed: u32,
+}
+
+/// The applications clocks stored in a `ClockProfile`. See
+/// `Device.set_applications_clocks()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ApplicationsClocks {
+    pub memory: Megahertz,
+    pub graphics: Megahertz,
+}
+
+/// The memory clock range stored in a `ClockProfile`. See
+/// `Device.set_mem_locked_clocks()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MemLockedClocks {
+    pub min_clock_mhz: u32,
+    pub max_clock_mhz: u32,
+}
+
+/**
+The clock-related settings of a `Device`, returned from
+`Device.clock_profile()` and applied with `Device.apply_clock_profile()`.
+
+Every setting is optional; those that are `None` (or
+`LockedClocks::Unchanged`) are left as they are when the profile is applied.
+With the `serde` feature, profiles can be saved to disk and restored after a
+reboot.
+
+NVML has no way of reading locked clocks back, so `Device.clock_profile()`
+records them as `LockedClocks::Reset`. Restoring a saved profile therefore
+removes locked clocks applied in the meantime, but can't bring back locked
+clocks that were in effect when the profile was taken.
+*/
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockProfile {
+    pub applications_clocks: Option<ApplicationsClocks>,
+    /// Whether or not auto boosted clocks are enabled.
+    pub auto_boosted_clocks: Option<bool>,
+    pub gpu_locked_clocks: LockedClocks<GpuLockedClocksSetting>,
+    pub mem_locked_clocks: LockedClocks<MemLockedClocks>,
+    /// The GPU clock VF offset in MHz. See `Device.gpc_clk_vf_offset()`.
+    pub gpc_clk_vf_offset: Option<i32>,
+    /// The memory clock VF offset in MHz. See `Device.mem_clk_vf_offset()`.
+    pub mem_clk_vf_offset: Option<i32>,
+}
+
+/// Returned from `Device.vgpu_type_supported_placements()` and
+/// `Device.vgpu_type_creatable_placements()`
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VgpuPlacements {
+    /// The number of slots a vGPU of the type occupies.
+    pub placement_size: u32,
+    /// The IDs of the placements, i.e. the first slot of each placement.
+    pub placement_ids: Vec<u32>,
+}
+
+/// Returned from `Device.engine_counts()`
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EngineCounts {
+    pub copy_engines: u32,
+    /// Video decoders (NVDEC).
+    pub decoders: u32,
+    /// Video encoders (NVENC).
+    pub encoders: u32,
+    pub jpeg_decoders: u32,
+    /// Optical flow accelerators.
+    pub ofas: u32,
 }
 
 /// Returned from `Device.encoder_stats()`
@@ -95,9 +251,116 @@ pub struct RetiredPage {
     pub timestamp: u64,
 }
 
-/// Populate this newtype with the constants `nvml_wrapper::sys_exports::field_id::*`.
+/// Returned from `Device.retired_page_counts()`
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RetiredPageCounts {
+    /// Pages retired due to multiple single bit ECC errors.
+    pub single_bit_ecc: u32,
+    /// Pages retired due to a double bit ECC error.
+    pub double_bit_ecc: u32,
+}
+
+impl RetiredPageCounts {
+    /// The number of pages retired due to the given cause.
+    pub fn get(&self, cause: RetirementCause) -> u32 {
+        match cause {
+            RetirementCause::MultipleSingleBitEccErrors => self.single_bit_ecc,
+            RetirementCause::DoubleBitEccError => self.double_bit_ecc,
+        }
+    }
+
+    /// The number of pages retired for any cause.
+    pub fn total(&self) -> u32 {
+        self.single_bit_ecc + self.double_bit_ecc
+    }
+}
+
+/// Returned from `Device.retired_pages_by_cause()`
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RetiredPagesByCause {
+    /// Pages retired due to multiple single bit ECC errors.
+    pub single_bit_ecc: Vec<RetiredPage>,
+    /// Pages retired due to a double bit ECC error.
+    pub double_bit_ecc: Vec<RetiredPage>,
+}
+
+impl RetiredPagesByCause {
+    /// The pages retired due to the given cause.
+    pub fn get(&self, cause: RetirementCause) -> &[RetiredPage] {
+        match cause {
+            RetirementCause::MultipleSingleBitEccErrors => &self.single_bit_ecc,
+            RetirementCause::DoubleBitEccError => &self.double_bit_ecc,
+        }
+    }
+
+    /// The number of pages retired due to each cause.
+    pub fn counts(&self) -> RetiredPageCounts {
+        RetiredPageCounts {
+            single_bit_ecc: self.single_bit_ecc.len() as u32,
+            double_bit_ecc: self.double_bit_ecc.len() as u32,
+        }
+    }
+}
+
+/// A change in clock speed, returned from `Device.clock_transitions()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockTransition {
+    /// CPU timestamp in μs of the first sample at the new speed.
+    pub timestamp: u64,
+    /// Either `Sampling::ProcessorClock` or `Sampling::MemoryClock`.
+    pub clock: Sampling,
+    /// The previous clock speed in MHz.
+    pub from: u32,
+    /// The new clock speed in MHz.
+    pub to: u32,
+}
+
+/// Populate this newtype with the constants `nvml_wrapper::sys_exports::field_id::*`,
+/// or use one of the associated constants for commonly used fields.
 ///
 /// Used in `FieldValue` and `Device.field_values_for()`.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FieldId(pub u32);
+
+impl FieldId {
+    /// Whether ECC is currently enabled.
+    pub const ECC_CURRENT: Self = Self(NVML_FI_DEV_ECC_CURRENT);
+    /// Total single bit volatile ECC errors.
+    pub const ECC_SBE_VOL_TOTAL: Self = Self(NVML_FI_DEV_ECC_SBE_VOL_TOTAL);
+    /// Total double bit volatile ECC errors.
+    pub const ECC_DBE_VOL_TOTAL: Self = Self(NVML_FI_DEV_ECC_DBE_VOL_TOTAL);
+    /// Total single bit aggregate (persistent) ECC errors.
+    pub const ECC_SBE_AGG_TOTAL: Self = Self(NVML_FI_DEV_ECC_SBE_AGG_TOTAL);
+    /// Total double bit aggregate (persistent) ECC errors.
+    pub const ECC_DBE_AGG_TOTAL: Self = Self(NVML_FI_DEV_ECC_DBE_AGG_TOTAL);
+    /// Number of retired pages because of single bit errors.
+    pub const RETIRED_SBE: Self = Self(NVML_FI_DEV_RETIRED_SBE);
+    /// Number of retired pages because of double bit errors.
+    pub const RETIRED_DBE: Self = Self(NVML_FI_DEV_RETIRED_DBE);
+    /// Whether there are pages pending retirement.
+    pub const RETIRED_PENDING: Self = Self(NVML_FI_DEV_RETIRED_PENDING);
+    /// Number of remapped rows for correctable errors.
+    pub const REMAPPED_COR: Self = Self(NVML_FI_DEV_REMAPPED_COR);
+    /// Number of remapped rows for uncorrectable errors.
+    pub const REMAPPED_UNC: Self = Self(NVML_FI_DEV_REMAPPED_UNC);
+    /// Memory temperature in °C.
+    pub const MEMORY_TEMP: Self = Self(NVML_FI_DEV_MEMORY_TEMP);
+    /// Total energy consumption in mJ since the driver was last reloaded.
+    pub const TOTAL_ENERGY_CONSUMPTION: Self = Self(NVML_FI_DEV_TOTAL_ENERGY_CONSUMPTION);
+    /// PCIe replay counter.
+    pub const PCIE_REPLAY_COUNTER: Self = Self(NVML_FI_DEV_PCIE_REPLAY_COUNTER);
+    /// Number of NvLinks present on the device.
+    pub const NVLINK_LINK_COUNT: Self = Self(NVML_FI_DEV_NVLINK_LINK_COUNT);
+    /// NvLink data (payload) transmitted, in KiB.
+    pub const NVLINK_THROUGHPUT_DATA_TX: Self = Self(NVML_FI_DEV_NVLINK_THROUGHPUT_DATA_TX);
+    /// NvLink data (payload) received, in KiB.
+    pub const NVLINK_THROUGHPUT_DATA_RX: Self = Self(NVML_FI_DEV_NVLINK_THROUGHPUT_DATA_RX);
+    /// NvLink data transmitted including protocol overhead, in KiB.
+    pub const NVLINK_THROUGHPUT_RAW_TX: Self = Self(NVML_FI_DEV_NVLINK_THROUGHPUT_RAW_TX);
+    /// NvLink data received including protocol overhead, in KiB.
+    pub const NVLINK_THROUGHPUT_RAW_RX: Self = Self(NVML_FI_DEV_NVLINK_THROUGHPUT_RAW_RX);
+}
@@ -1,5 +1,6 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Returned by `NvLink.utilization_counter()`
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -10,3 +11,17 @@ pub struct UtilizationCounter {
     /// Send counter value
     pub send: u64,
 }
+
+/// Returned by `Device.nvlink_throughput()`
+///
+/// Rates only count data, not protocol overhead.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NvLinkThroughput {
+    /// Bytes received per second across all links
+    pub rx: u64,
+    /// Bytes sent per second across all links
+    pub tx: u64,
+    /// The time between the samples that the rates were computed from
+    pub interval: Duration,
+}
@@ -0,0 +1,664 @@
+/*!
+Which devices support which NVML functions.
+
+The descriptions are taken from the "Device Support" sections of the docs in
+this crate (which are in turn taken from NVIDIA's docs) and are used to give
+context to `NvmlError::NotSupported` errors.
+*/
+
+use crate::enums::device::DeviceArchitecture::{self, *};
+
+// (function, device support, minimum architecture), sorted by function.
+//
+// A minimum architecture of `None` means that every architecture known to
+// this crate is supported or that support does not depend on architecture.
+static SUPPORT: &[(&str, &str, Option<DeviceArchitecture>)] = &[
+    (
+        "nvmlDeviceClearAccountingPids",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceClearCpuAffinity",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceClearEccErrorCounts",
+        "Supports Kepler and newer fully supported devices. Only applicable to devices with ECC. Requires `InfoRom::ECC` version 2.0 or higher to clear aggregate location-based ECC counts. Requires `InfoRom::ECC` version 1.0 or higher to clear all other ECC counts.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceDiscoverGpus",
+        "Supports Pascal and newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceFreezeNvLinkUtilizationCounter",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetAPIRestriction",
+        "Supports all _fully supported_ products.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetAccountingBufferSize",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetAccountingMode",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetAccountingStats",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetActiveVgpus",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetApplicationsClock",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetAttributes_v2",
+        "Supports Ampere and newer fully supported devices.",
+        Some(Ampere),
+    ),
+    (
+        "nvmlDeviceGetAutoBoostedClocksEnabled",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetBAR1MemoryInfo",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetBoardId",
+        "Supports Fermi or newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetBridgeChipInfo",
+        "Supports all _fully supported_ devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetClock",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetClockInfo",
+        "Supports Fermi or newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetCpuAffinity",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetCreatableVgpus",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetCurrPcieLinkGeneration",
+        "Supports Fermi or newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetCurrPcieLinkWidth",
+        "Supports Fermi or newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetCurrentClocksEventReasons",
+        "Supports all _fully supported_ devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetDecoderUtilization",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetDefaultApplicationsClock",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetDriverModel",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetEccMode",
+        "Supports Fermi and newer fully supported devices. Only applicable to devices with ECC. Requires `InfoRom::ECC` version 1.0 or higher.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetEncoderCapacity",
+        "Supports Maxwell or newer fully supported devices.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceGetEncoderSessions",
+        "Supports Maxwell or newer fully supported devices.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceGetEncoderStats",
+        "Supports Maxwell or newer fully supported devices.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceGetEncoderUtilization",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetEnforcedPowerLimit",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetFBCSessions",
+        "Supports Maxwell or newer fully supported devices.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceGetFBCStats",
+        "Supports Maxwell or newer fully supported devices.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceGetFanControlPolicy_v2",
+        "Supports Maxwell or newer fully supported devices with fans.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceGetFanSpeed_v2",
+        "Supports all discrete products with dedicated fans.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetFieldValues",
+        "Device support varies per `FieldId` that you pass in.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetGpuOperationMode",
+        "Supports GK110 M-class and X-class Tesla products from the Kepler family. Modes `LowDP` and `AllOn` are supported on fully supported GeForce products. Not supported on Quadro and Tesla C-class products.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetInforomConfigurationChecksum",
+        "Supports all devices with an infoROM.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetInforomImageVersion",
+        "Supports all devices with an infoROM.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetInforomVersion",
+        "Supports all devices with an infoROM.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetMaxClockInfo",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetMaxCustomerBoostClock",
+        "Supports Pascal and newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetMaxPcieLinkGeneration",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetMaxPcieLinkWidth",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetMemoryErrorCounter",
+        "Supports Fermi and newer fully supported devices. Requires `InfoRom::ECC` version 2.0 or higher to report aggregate location-based memory error counts. Requires `InfoRom::ECC version 1.0 or higher to report all other memory error counts.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetMinMaxFanSpeed",
+        "Supports all CUDA-capable discrete products with fans.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetMultiGpuBoard",
+        "Supports Fermi or newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetNumFans",
+        "Supports all discrete products with dedicated fans.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetNvLinkCapability",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetNvLinkErrorCounter",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetNvLinkRemotePciInfo_v2",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetNvLinkState",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetNvLinkUtilizationControl",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetNvLinkUtilizationCounter",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetNvLinkVersion",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetPcieReplayCounter",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetPcieThroughput",
+        "Supports Maxwell and newer fully supported devices.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceGetPerformanceState",
+        "Supports Fermi or newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetPowerManagementDefaultLimit",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetPowerManagementLimit",
+        "Supports Fermi or newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetPowerManagementLimitConstraints",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetPowerUsage",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetProcessUtilization",
+        "Supports Maxwell or newer fully supported devices.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceGetRetiredPagesPendingStatus",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetRetiredPages_v2",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetSamples",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetSerial",
+        "Supports all products with an infoROM.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetSupportedClocksEventReasons",
+        "Supports all _fully supported_ devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetSupportedEventTypes",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetSupportedGraphicsClocks",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetSupportedMemoryClocks",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetSupportedVgpus",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetTargetFanSpeed",
+        "Supports all discrete products with dedicated fans.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetTemperatureThreshold",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceGetTotalEccErrors",
+        "Supports Fermi and newer fully supported devices. Requires `InfoRom::ECC` version 1.0 or higher. Requires ECC mode to be enabled.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetTotalEnergyConsumption",
+        "Supports Pascal and newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceGetUtilizationRates",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceGetVgpuTypeCreatablePlacements",
+        "Supports Ampere and newer fully supported devices.",
+        Some(Ampere),
+    ),
+    (
+        "nvmlDeviceGetVgpuTypeSupportedPlacements",
+        "Supports Ampere and newer fully supported devices.",
+        Some(Ampere),
+    ),
+    (
+        "nvmlDeviceGetViolationStatus",
+        "Supports Kepler or newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceModifyDrainState",
+        "Supports Pascal and newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceQueryDrainState",
+        "Supports Pascal and newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceRegisterEvents",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceRemoveGpu_v2",
+        "Supports Pascal and newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceResetApplicationsClocks",
+        "Supports Fermi and newer non-GeForce fully supported devices and Maxwell or newer GeForce devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceResetGpuLockedClocks",
+        "Supports Volta and newer fully supported devices.",
+        Some(Volta),
+    ),
+    (
+        "nvmlDeviceResetMemoryLockedClocks",
+        "Supports Ampere and newer fully supported devices.",
+        Some(Ampere),
+    ),
+    (
+        "nvmlDeviceResetNvLinkErrorCounters",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceResetNvLinkUtilizationCounter",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceSetAPIRestriction",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetAccountingMode",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetApplicationsClocks",
+        "Supports Kepler and newer non-GeForce fully supported devices and Maxwell or newer GeForce devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetAutoBoostedClocksEnabled",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetCpuAffinity",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetDefaultAutoBoostedClocksEnabled",
+        "Supports Kepler or newer non-GeForce fully supported devices and Maxwell or newer GeForce devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetDefaultFanSpeed_v2",
+        "Supports all CUDA-capable discrete products with fans.",
+        None,
+    ),
+    (
+        "nvmlDeviceSetDriverModel",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlDeviceSetEccMode",
+        "Supports Kepler and newer fully supported devices. Requires `InfoRom::ECC` version 1.0 or higher.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetFanControlPolicy",
+        "Supports Maxwell or newer fully supported devices with fans.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceSetFanSpeed_v2",
+        "Supports Maxwell and newer CUDA-capable discrete products with fans.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceSetGpuLockedClocks",
+        "Supports Volta and newer fully supported devices.",
+        Some(Volta),
+    ),
+    (
+        "nvmlDeviceSetGpuOperationMode",
+        "Supports GK110 M-class and X-class Tesla products from the Kepler family. Modes `LowDP` and `AllOn` are supported on fully supported GeForce products. Not supported on Quadro and Tesla C-class products.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetMemoryLockedClocks",
+        "Supports Ampere and newer fully supported devices.",
+        Some(Ampere),
+    ),
+    (
+        "nvmlDeviceSetNvLinkUtilizationControl",
+        "Supports Pascal or newer fully supported devices.",
+        Some(Pascal),
+    ),
+    (
+        "nvmlDeviceSetPowerManagementLimit",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetPowerManagementLimit_v2",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+    (
+        "nvmlDeviceSetTemperatureThreshold",
+        "Supports Maxwell and newer fully supported devices.",
+        Some(Maxwell),
+    ),
+    (
+        "nvmlDeviceValidateInforom",
+        "Supports all devices with an infoROM.",
+        None,
+    ),
+    (
+        "nvmlEventSetCreate",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlEventSetWait_v2",
+        "Supports Fermi and newer fully supported devices.",
+        None,
+    ),
+    (
+        "nvmlGetExcludedDeviceCount",
+        "Supports all devices.",
+        None,
+    ),
+    (
+        "nvmlGetExcludedDeviceInfoByIndex",
+        "Supports all devices.",
+        None,
+    ),
+    (
+        "nvmlSystemGetHicVersion",
+        "Supports S-class products.",
+        None,
+    ),
+    (
+        "nvmlUnitGetCount",
+        "Supports S-class products.",
+        None,
+    ),
+    (
+        "nvmlUnitGetDevices",
+        "For S-class products.",
+        None,
+    ),
+    (
+        "nvmlUnitGetFanSpeedInfo",
+        "For S-class products.",
+        None,
+    ),
+    (
+        "nvmlUnitGetHandleByIndex",
+        "For S-class products.",
+        None,
+    ),
+    (
+        "nvmlUnitGetLedState",
+        "For S-class products.",
+        None,
+    ),
+    (
+        "nvmlUnitGetPsuInfo",
+        "For S-class products.",
+        None,
+    ),
+    (
+        "nvmlUnitGetTemperature",
+        "For S-class products. Available readings depend on the product.",
+        None,
+    ),
+    (
+        "nvmlUnitGetUnitInfo",
+        "For S-class products.",
+        None,
+    ),
+    (
+        "nvmlUnitSetLedState",
+        "For S-class products.",
+        None,
+    ),    (
+        "nvmlVgpuTypeGetMaxInstances",
+        "Supports Kepler and newer fully supported devices.",
+        Some(Kepler),
+    ),
+];
+
+/// Look up the device support description and minimum architecture for the
+/// given NVML function.
+pub(crate) fn lookup(function: &str) -> Option<(&'static str, Option<DeviceArchitecture>)> {
+    SUPPORT
+        .binary_search_by_key(&function, |(f, _, _)| f)
+        .ok()
+        .map(|i| (SUPPORT[i].1, SUPPORT[i].2.clone()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(SUPPORT.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn lookup_known_function() {
+        let (support, arch) = lookup("nvmlDeviceGetFBCStats").expect("entry");
+
+        assert!(support.contains("Maxwell"));
+        assert_eq!(arch, Some(DeviceArchitecture::Maxwell));
+        assert_eq!(lookup("nvmlDeviceGetPowerUsage").map(|l| l.1), Some(None));
+        assert!(lookup("nvmlNotAFunction").is_none());
+    }
+}
@@ -1,4 +1,8 @@
+use crate::cpu_set::CpuSet;
+use crate::gpu_model::GpuModel;
+use crate::Board;
 use crate::Device;
+use crate::LibraryCapabilities;
 use crate::NvLink;
 use crate::Nvml;
 use crate::Unit;
@@ -8,11 +12,27 @@ use crate::bitmasks::{device::*, event::*};
 use crate::enum_wrappers::device::*;
 use crate::enums::device::BusType;
 use crate::enums::device::DeviceArchitecture;
+use crate::enums::device::DisplayState;
+use crate::enums::device::FanControlPolicy;
 use crate::enums::device::PcieLinkMaxSpeed;
 use crate::enums::device::PowerSource;
 use crate::enums::unit::*;
 use crate::error::NvmlError;
 use crate::event::EventSet;
+use crate::high_level::device_group::GroupHealth;
+use crate::high_level::encoder_latency::EncoderSummary;
+use crate::high_level::link_change::LinkEvent;
+use crate::high_level::process_utilization::ProcessUtilization;
+use crate::high_level::query::DeviceSnapshot;
+use crate::high_level::sampler::TimedValue;
+#[cfg(all(feature = "shm", target_os = "linux"))]
+use crate::high_level::shm::SharedSnapshot;
+use crate::high_level::temperature_alert::Alert;
+use crate::high_level::top::TopView;
+#[cfg(target_os = "linux")]
+use crate::high_level::{CrossCheckReport, ResetReadiness, TopologyGraph};
+use crate::high_level::{MetricUpdate, QueryResult, RegistryEvent};
+use crate::mig_device::MigDeviceSnapshot;
 use std::fmt::Debug;
 
 use crate::struct_wrappers::nv_link::*;
@@ -20,6 +40,8 @@ use crate::struct_wrappers::{device::*, event::*, unit::*, *};
 
 use crate::structs::device::*;
 use crate::structs::nv_link::*;
+use crate::units::{Celsius, Megahertz, Milliwatts};
+use crate::uuid::DeviceUuid;
 
 #[cfg(target_os = "windows")]
 use crate::structs::device::DriverModelState;
@@ -57,17 +79,32 @@ impl<'nvml> ShouldPrint for EventSet<'nvml> {
 impl ShouldPrint for bool {}
 impl ShouldPrint for u32 {}
 impl ShouldPrint for i32 {}
+impl ShouldPrint for i64 {}
 impl ShouldPrint for (u32, u32) {}
+impl ShouldPrint for Milliwatts {}
+impl ShouldPrint for Celsius {}
+impl ShouldPrint for (Celsius, Celsius) {}
+impl ShouldPrint for Megahertz {}
 impl ShouldPrint for u64 {}
 impl ShouldPrint for String {}
 impl ShouldPrint for Brand {}
 impl ShouldPrint for [i8; 16] {}
 impl ShouldPrint for Vec<ProcessInfo> {}
 impl ShouldPrint for Vec<ProcessUtilizationSample> {}
+impl ShouldPrint for Vec<ProcessUtilization> {}
 impl<'nvml> ShouldPrint for Vec<Device<'nvml>> {}
+impl<'nvml> ShouldPrint for Vec<Board<'nvml>> {}
 impl ShouldPrint for Vec<u32> {}
 impl ShouldPrint for Vec<u64> {}
+impl ShouldPrint for Vec<DeviceAttributes> {}
+impl ShouldPrint for Vec<MigDeviceSnapshot> {}
+impl ShouldPrint for Vec<Alert> {}
+impl ShouldPrint for Vec<LinkEvent> {}
+impl ShouldPrint for Option<EncoderSummary> {}
+impl ShouldPrint for Vec<TimedValue> {}
+impl ShouldPrint for CpuSet {}
 impl ShouldPrint for Vec<Sample> {}
+impl ShouldPrint for Vec<ClockTransition> {}
 impl ShouldPrint for Vec<Result<FieldValueSample, NvmlError>> {}
 impl ShouldPrint for Vec<HwbcEntry> {}
 impl ShouldPrint for Utilization {}
@@ -81,15 +118,26 @@ impl ShouldPrint for BridgeChipHierarchy {}
 impl ShouldPrint for ComputeMode {}
 impl ShouldPrint for UtilizationInfo {}
 impl ShouldPrint for EccModeState {}
+impl ShouldPrint for MigModeState {}
+impl ShouldPrint for PendingChanges {}
 impl ShouldPrint for OperationModeState {}
 impl ShouldPrint for InfoRom {}
 impl ShouldPrint for Vec<RetiredPage> {}
+impl ShouldPrint for RetiredPageCounts {}
+impl ShouldPrint for RetiredPagesByCause {}
 impl ShouldPrint for ExcludedDeviceInfo {}
+impl ShouldPrint for VgpuVersions {}
 impl ShouldPrint for MemoryInfo {}
 impl ShouldPrint for PciInfo {}
 impl ShouldPrint for PerformanceState {}
 impl ShouldPrint for PowerManagementConstraints {}
-impl ShouldPrint for ThrottleReasons {}
+impl ShouldPrint for FanSpeedConstraints {}
+impl ShouldPrint for ClockProfile {}
+impl ShouldPrint for Vec<PerformanceMode> {}
+impl ShouldPrint for CurrentClockFreqs {}
+impl ShouldPrint for EngineCounts {}
+impl ShouldPrint for Vec<VgpuPlacements> {}
+impl ShouldPrint for ClocksEventReasons {}
 impl ShouldPrint for ViolationTime {}
 impl ShouldPrint for AccountingStats {}
 impl ShouldPrint for EventTypes {}
@@ -102,8 +150,40 @@ impl ShouldPrint for UtilizationControl {}
 impl ShouldPrint for UtilizationCounter {}
 impl ShouldPrint for BusType {}
 impl ShouldPrint for PowerSource {}
+impl ShouldPrint for Vec<FanControlPolicy> {}
+impl ShouldPrint for DisplayState {}
+impl ShouldPrint for GpuModel {}
+impl ShouldPrint for LicensableFeatures {}
 impl ShouldPrint for DeviceArchitecture {}
 impl ShouldPrint for PcieLinkMaxSpeed {}
+impl ShouldPrint for QueryResult {}
+impl ShouldPrint for HotMetrics {}
+impl ShouldPrint for ClocksEventReasonsStatus {}
+impl ShouldPrint for Vec<RegistryEvent> {}
+impl ShouldPrint for Vec<MetricUpdate> {}
+impl ShouldPrint for LibraryCapabilities {}
+#[cfg(target_os = "linux")]
+impl ShouldPrint for TopologyGraph {}
+impl ShouldPrint for Vec<WorkloadPowerProfileInfo> {}
+impl ShouldPrint for CurrentPowerProfiles {}
+impl ShouldPrint for FabricInfo {}
+impl ShouldPrint for GpuInstanceProfileInfo {}
+impl ShouldPrint for Vec<GpuInstanceInfo> {}
+impl ShouldPrint for Vec<ComputeInstanceInfo> {}
+impl ShouldPrint for DeviceIdentity {}
+impl ShouldPrint for Option<NvLinkThroughput> {}
+impl ShouldPrint for Option<u32> {}
+#[cfg(target_os = "linux")]
+impl ShouldPrint for CrossCheckReport {}
+#[cfg(target_os = "linux")]
+impl ShouldPrint for ResetReadiness {}
+#[cfg(all(feature = "shm", target_os = "linux"))]
+impl ShouldPrint for Vec<SharedSnapshot> {}
+impl ShouldPrint for Vec<DeviceSnapshot> {}
+impl ShouldPrint for DeviceSnapshot {}
+impl ShouldPrint for GroupHealth {}
+impl ShouldPrint for TopView {}
+impl ShouldPrint for DeviceUuid {}
 
 #[cfg(target_os = "windows")]
 impl ShouldPrint for DriverModelState {}
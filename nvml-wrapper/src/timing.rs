@@ -0,0 +1,113 @@
+/*!
+Measure how long calls take.
+
+Useful for picking a polling interval that leaves enough headroom for the
+calls made on every poll, especially on systems with many devices:
+
+```no_run
+# use nvml_wrapper::Nvml;
+# use nvml_wrapper::error::*;
+# fn main() -> Result<(), NvmlError> {
+use nvml_wrapper::timing;
+
+let nvml = Nvml::init()?;
+let device = nvml.device_by_index(0)?;
+
+let timings = timing::measure(100, || device.power_usage())?;
+println!("power_usage: {:?} on average, {:?} at worst", timings.mean, timings.max);
+# Ok(())
+# }
+```
+
+For rigorous numbers, see the `ffi_overhead` benchmark in this crate's
+repository (`cargo bench --bench ffi_overhead`).
+*/
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Timings for a call measured by `measure()`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Timings {
+    /// The number of times the call was made.
+    pub iterations: u32,
+    pub min: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+/**
+Call `f` the given number of times, timing each call.
+
+# Errors
+
+Stops at and returns the first error returned by `f`.
+
+# Panics
+
+Panics if `iterations` is `0`.
+*/
+pub fn measure<T, E, F>(iterations: u32, mut f: F) -> Result<Timings, E>
+where
+    F: FnMut() -> Result<T, E>,
+{
+    assert!(iterations > 0, "iterations must be greater than 0");
+
+    let mut min = Duration::from_secs(u64::MAX);
+    let mut max = Duration::from_secs(0);
+    let mut total = Duration::from_secs(0);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        f()?;
+        let elapsed = start.elapsed();
+
+        min = min.min(elapsed);
+        max = max.max(elapsed);
+        total += elapsed;
+    }
+
+    Ok(Timings {
+        iterations,
+        min,
+        mean: total / iterations,
+        max,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn measure_stats() {
+        let timings = measure(3, || {
+            thread::sleep(Duration::from_millis(1));
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+
+        assert_eq!(timings.iterations, 3);
+        assert!(timings.min >= Duration::from_millis(1));
+        assert!(timings.min <= timings.mean && timings.mean <= timings.max);
+    }
+
+    #[test]
+    fn measure_stops_at_error() {
+        let mut calls = 0;
+        let result = measure(5, || {
+            calls += 1;
+            if calls == 2 {
+                Err("failed")
+            } else {
+                Ok(())
+            }
+        });
+
+        assert_eq!(result, Err("failed"));
+        assert_eq!(calls, 2);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::device::Device;
 use crate::enum_wrappers::unit::LedColor;
 use crate::enums::unit::{LedState, TemperatureReading};
-use crate::error::{nvml_sym, nvml_try, NvmlError};
+use crate::error::{nvml_fn, nvml_try_for, NvmlError};
 use crate::ffi::bindings::*;
 use crate::struct_wrappers::unit::{FansInfo, PsuInfo, UnitInfo};
 use crate::Nvml;
@@ -82,6 +82,29 @@ impl<'nvml> Unit<'nvml> {
         self.unit
     }
 
+    /**
+    Wrap a unit handle obtained from other NVML FFI code.
+
+    Equivalent to `Unit::new()`.
+
+    # Safety
+
+    The handle must be valid and obtained from the same NVML library `nvml`
+    has loaded.
+    */
+    pub unsafe fn from_raw(unit: nvmlUnit_t, nvml: &'nvml Nvml) -> Self {
+        Self::new(unit, nvml)
+    }
+
+    /// Get the raw unit handle, to pass to other NVML FFI code.
+    ///
+    /// # Safety
+    ///
+    /// This is unsafe to prevent it from being used without care.
+    pub unsafe fn as_raw(&self) -> nvmlUnit_t {
+        self.unit
+    }
+
     /**
     Gets the set of GPU devices that are attached to this `Unit`.
 
@@ -104,7 +127,7 @@ impl<'nvml> Unit<'nvml> {
     // Tested
     #[doc(alias = "nvmlUnitGetDevices")]
     pub fn devices(&self) -> Result<Vec<Device>, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlUnitGetDevices.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlUnitGetDevices)?;
 
         unsafe {
             let mut count: c_uint = match self.device_count()? {
@@ -113,7 +136,8 @@ impl<'nvml> Unit<'nvml> {
             };
             let mut devices: Vec<nvmlDevice_t> = vec![mem::zeroed(); count as usize];
 
-            nvml_try(sym(self.unit, &mut count, devices.as_mut_ptr()))?;
+            nvml_try_for(function, sym(self.unit, &mut count, devices.as_mut_ptr()))
+                .map_err(|e| e.with_required_size(count))?;
 
             Ok(devices
                 .into_iter()
@@ -143,7 +167,7 @@ impl<'nvml> Unit<'nvml> {
     // Tested as part of the above
     #[doc(alias = "nvmlUnitGetDevices")]
     pub fn device_count(&self) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlUnitGetDevices.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlUnitGetDevices)?;
 
         unsafe {
             /*
@@ -167,7 +191,7 @@ impl<'nvml> Unit<'nvml> {
                     Ok(count)
                 }
                 // We know that this will be an error
-                other => nvml_try(other).map(|_| 0),
+                other => nvml_try_for(function, other).map(|_| 0),
             }
         }
     }
@@ -191,11 +215,11 @@ impl<'nvml> Unit<'nvml> {
     // Tested
     #[doc(alias = "nvmlUnitGetFanSpeedInfo")]
     pub fn fan_info(&self) -> Result<FansInfo, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlUnitGetFanSpeedInfo.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlUnitGetFanSpeedInfo)?;
 
         unsafe {
             let mut fans_info: nvmlUnitFanSpeeds_t = mem::zeroed();
-            nvml_try(sym(self.unit, &mut fans_info))?;
+            nvml_try_for(function, sym(self.unit, &mut fans_info))?;
 
             FansInfo::try_from(fans_info)
         }
@@ -220,11 +244,11 @@ impl<'nvml> Unit<'nvml> {
     // Tested
     #[doc(alias = "nvmlUnitGetLedState")]
     pub fn led_state(&self) -> Result<LedState, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlUnitGetLedState.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlUnitGetLedState)?;
 
         unsafe {
             let mut state: nvmlLedState_t = mem::zeroed();
-            nvml_try(sym(self.unit, &mut state))?;
+            nvml_try_for(function, sym(self.unit, &mut state))?;
 
             LedState::try_from(state)
         }
@@ -249,10 +273,10 @@ impl<'nvml> Unit<'nvml> {
     // Tested
     #[doc(alias = "nvmlUnitGetPsuInfo")]
     pub fn psu_info(&self) -> Result<PsuInfo, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlUnitGetPsuInfo.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlUnitGetPsuInfo)?;
         unsafe {
             let mut info: nvmlPSUInfo_t = mem::zeroed();
-            nvml_try(sym(self.unit, &mut info))?;
+            nvml_try_for(function, sym(self.unit, &mut info))?;
 
             PsuInfo::try_from(info)
         }
@@ -278,12 +302,12 @@ impl<'nvml> Unit<'nvml> {
     // Tested
     #[doc(alias = "nvmlUnitGetTemperature")]
     pub fn temperature(&self, reading_type: TemperatureReading) -> Result<u32, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlUnitGetTemperature.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlUnitGetTemperature)?;
 
         unsafe {
             let mut temp: c_uint = mem::zeroed();
 
-            nvml_try(sym(self.unit, reading_type as c_uint, &mut temp))?;
+            nvml_try_for(function, sym(self.unit, reading_type as c_uint, &mut temp))?;
 
             Ok(temp)
         }
@@ -306,11 +330,11 @@ impl<'nvml> Unit<'nvml> {
     // Tested
     #[doc(alias = "nvmlUnitGetUnitInfo")]
     pub fn info(&self) -> Result<UnitInfo, NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlUnitGetUnitInfo.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlUnitGetUnitInfo)?;
 
         unsafe {
             let mut info: nvmlUnitInfo_t = mem::zeroed();
-            nvml_try(sym(self.unit, &mut info))?;
+            nvml_try_for(function, sym(self.unit, &mut info))?;
 
             UnitInfo::try_from(info)
         }
@@ -343,9 +367,9 @@ impl<'nvml> Unit<'nvml> {
     // Tested (no-run)
     #[doc(alias = "nvmlUnitSetLedState")]
     pub fn set_led_color(&mut self, color: LedColor) -> Result<(), NvmlError> {
-        let sym = nvml_sym(self.nvml.lib.nvmlUnitSetLedState.as_ref())?;
+        let (sym, function) = nvml_fn!(self.nvml.lib, nvmlUnitSetLedState)?;
 
-        unsafe { nvml_try(sym(self.unit, color.as_c())) }
+        unsafe { nvml_try_for(function, sym(self.unit, color.as_c())) }
     }
 }
 
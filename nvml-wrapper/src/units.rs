@@ -0,0 +1,277 @@
+/*!
+Strongly typed units for the values NVML reports.
+
+NVML reports power in milliwatts, temperatures in °C and clocks in MHz, all as
+bare integers, which makes it easy to mix them up (or to mistake milliwatts for
+watts). The wrapper returns these values as the newtypes in this module
+instead. Each one converts to and from the bare integer and supports the
+arithmetic that makes sense for it:
+
+```
+use nvml_wrapper::units::{Megahertz, Milliwatts, Watts};
+
+let usage = Milliwatts(215_500);
+
+assert_eq!(usage.as_watts(), Watts(215.5));
+assert_eq!(usage + Milliwatts(500), Milliwatts(216_000));
+assert_eq!(Milliwatts::from(Watts(250.0)), Milliwatts(250_000));
+assert_eq!(usage.to_string(), "215500 mW");
+
+let clock = Megahertz(1410);
+assert_eq!(u32::from(clock), 1410);
+```
+
+Timeouts are taken as `std::time::Duration`s. Timestamps are kept as the
+microseconds since the Unix epoch that NVML uses, as they are passed back to
+NVML when sampling; `system_time()` converts them.
+
+With the `serde` feature, the newtypes serialize as their bare values.
+*/
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Sub, SubAssign},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// Defines an integer newtype for a unit with the given symbol.
+macro_rules! integer_unit {
+    ($(#[$attr:meta])* $name:ident($inner:ty), $symbol:literal) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        #[cfg_attr(feature = "serde", serde(transparent))]
+        pub struct $name(pub $inner);
+
+        impl $name {
+            /// Subtract `other`, stopping at zero instead of overflowing.
+            pub fn saturating_sub(self, other: Self) -> Self {
+                Self(self.0.saturating_sub(other.0))
+            }
+        }
+
+        impl From<$inner> for $name {
+            fn from(value: $inner) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for $inner {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl From<$name> for f64 {
+            fn from(value: $name) -> Self {
+                value.0.into()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!("{} ", $symbol), self.0)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self(self.0 - other.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                self.0 += other.0;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                self.0 -= other.0;
+            }
+        }
+
+        impl Mul<$inner> for $name {
+            type Output = Self;
+
+            fn mul(self, factor: $inner) -> Self {
+                Self(self.0 * factor)
+            }
+        }
+
+        impl Div<$inner> for $name {
+            type Output = Self;
+
+            fn div(self, divisor: $inner) -> Self {
+                Self(self.0 / divisor)
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self(iter.map(|v| v.0).sum())
+            }
+        }
+    };
+}
+
+integer_unit! {
+    /// Power in milliwatts, as NVML reports it.
+    Milliwatts(u32), "mW"
+}
+
+integer_unit! {
+    /// A temperature in degrees Celsius.
+    Celsius(u32), "°C"
+}
+
+integer_unit! {
+    /// A clock frequency in megahertz.
+    Megahertz(u32), "MHz"
+}
+
+impl Milliwatts {
+    /// Convert to watts.
+    pub fn as_watts(self) -> Watts {
+        Watts(f64::from(self.0) / 1000.0)
+    }
+}
+
+impl Megahertz {
+    /// Convert to hertz.
+    pub fn as_hertz(self) -> u64 {
+        u64::from(self.0) * 1_000_000
+    }
+}
+
+/// Power in watts.
+///
+/// NVML works in whole milliwatts; use `Milliwatts` to exchange values with it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Watts(pub f64);
+
+impl From<Milliwatts> for Watts {
+    fn from(value: Milliwatts) -> Self {
+        value.as_watts()
+    }
+}
+
+/// Rounds to the nearest milliwatt, saturating at `0` and `u32::MAX`.
+impl From<Watts> for Milliwatts {
+    fn from(value: Watts) -> Self {
+        // Float to integer `as` casts saturate
+        Milliwatts((value.0 * 1000.0).round() as u32)
+    }
+}
+
+impl fmt::Display for Watts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} W", self.0)
+    }
+}
+
+impl Add for Watts {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for Watts {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Mul<f64> for Watts {
+    type Output = Self;
+
+    fn mul(self, factor: f64) -> Self {
+        Self(self.0 * factor)
+    }
+}
+
+impl Div<f64> for Watts {
+    type Output = Self;
+
+    fn div(self, divisor: f64) -> Self {
+        Self(self.0 / divisor)
+    }
+}
+
+impl Sum for Watts {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|v| v.0).sum())
+    }
+}
+
+/// Convert a timestamp in microseconds since the Unix epoch, as used by NVML,
+/// to a `SystemTime`.
+pub fn system_time(timestamp: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_micros(timestamp)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Milliwatts(1500).as_watts(), Watts(1.5));
+        assert_eq!(Watts::from(Milliwatts(250)), Watts(0.25));
+        assert_eq!(Milliwatts::from(Watts(0.0014)), Milliwatts(1));
+        assert_eq!(Milliwatts::from(Watts(-1.0)), Milliwatts(0));
+        assert_eq!(Megahertz(1410).as_hertz(), 1_410_000_000);
+        assert_eq!(u32::from(Celsius(65)), 65);
+        assert_eq!(Celsius::from(65), Celsius(65));
+        assert_eq!(
+            system_time(1_500_000),
+            UNIX_EPOCH + Duration::from_millis(1500)
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut power = Milliwatts(100) + Milliwatts(50);
+        power -= Milliwatts(30);
+        assert_eq!(power, Milliwatts(120));
+        assert_eq!(power * 2 / 3, Milliwatts(80));
+        assert_eq!(Celsius(40).saturating_sub(Celsius(50)), Celsius(0));
+        assert_eq!(
+            [Milliwatts(1), Milliwatts(2)]
+                .iter()
+                .copied()
+                .sum::<Milliwatts>(),
+            Milliwatts(3)
+        );
+        assert_eq!(Watts(1.5) + Watts(0.5), Watts(2.0));
+        assert!(Megahertz(1200) < Megahertz(1410));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Milliwatts(215500).to_string(), "215500 mW");
+        assert_eq!(Watts(215.5).to_string(), "215.5 W");
+        assert_eq!(Celsius(65).to_string(), "65 °C");
+        assert_eq!(Megahertz(1410).to_string(), "1410 MHz");
+    }
+}
@@ -0,0 +1,318 @@
+/*!
+Parsing and formatting of GPU and MIG device UUIDs.
+
+Device UUIDs come in three spellings:
+
+* `GPU-<uuid>` identifies a physical GPU.
+* `MIG-GPU-<uuid>/<gi>/<ci>` identifies a MIG device by its parent GPU and its
+  GPU and compute instance IDs. Used by drivers before R470.
+* `MIG-<uuid>` identifies a MIG device by its own UUID. Used by R470 and newer
+  drivers.
+
+NVML, Kubernetes device plugins, Slurm, and `CUDA_VISIBLE_DEVICES` each accept
+or emit different subsets of these, so it's common to have to convert between
+them:
+
+```
+use nvml_wrapper::uuid::DeviceUuid;
+
+let legacy: DeviceUuid = "MIG-GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5/1/0"
+    .parse()
+    .unwrap();
+
+assert!(legacy.is_mig());
+assert_eq!(
+    legacy.parent_gpu().unwrap().to_string(),
+    "GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5"
+);
+```
+
+Converting between the two MIG spellings requires asking the driver, as the
+newer spelling doesn't encode the parent GPU or instance IDs.
+
+With the `uuid` feature, the bare `Uuid` converts to and from `uuid::Uuid`, so
+device identities can be shared with code built on the `uuid` crate:
+
+```
+# #[cfg(feature = "uuid")]
+# {
+use nvml_wrapper::uuid::DeviceUuid;
+
+let device: DeviceUuid = "GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5".parse().unwrap();
+let uuid: uuid::Uuid = device.uuid().into();
+
+assert_eq!(uuid.to_string(), "b8ea3855-276c-c9cb-b366-c6fa655957c5");
+# }
+```
+*/
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+/// Returned when parsing a `Uuid` or `DeviceUuid` fails.
+#[derive(Error, Debug, Clone, Eq, PartialEq, Hash)]
+#[error("invalid device UUID: {0:?}")]
+pub struct ParseUuidError(pub String);
+
+/**
+A bare 128-bit UUID without any prefix.
+
+Parses from and formats as the usual `8-4-4-4-12` hex digit groups. Parsing is
+case-insensitive; formatting uses lowercase, matching NVML.
+*/
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Uuid(pub [u8; 16]);
+
+impl FromStr for Uuid {
+    type Err = ParseUuidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseUuidError(s.into());
+        let groups: Vec<&str> = s.split('-').collect();
+
+        if groups
+            .iter()
+            .map(|g| g.len())
+            .ne([8, 4, 4, 4, 12].iter().copied())
+        {
+            return Err(err());
+        }
+
+        let hex = groups.concat();
+        // `from_str_radix()` would accept a sign as well
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(err());
+        }
+
+        let mut bytes = [0; 16];
+
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = hex
+                .get(i * 2..i * 2 + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(err)?;
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if let 4 | 6 | 8 | 10 = i {
+                f.write_str("-")?;
+            }
+
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<Uuid> for ::uuid::Uuid {
+    fn from(uuid: Uuid) -> Self {
+        ::uuid::Uuid::from_bytes(uuid.0)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<::uuid::Uuid> for Uuid {
+    fn from(uuid: ::uuid::Uuid) -> Self {
+        Self(*uuid.as_bytes())
+    }
+}
+
+/// A GPU or MIG device UUID in any of the spellings described in the
+/// module-level docs.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeviceUuid {
+    /// `GPU-<uuid>`
+    Gpu(Uuid),
+    /// `MIG-GPU-<uuid>/<gi>/<ci>`, as used by drivers before R470.
+    LegacyMig {
+        /// The UUID of the parent GPU.
+        gpu: Uuid,
+        gpu_instance_id: u32,
+        compute_instance_id: u32,
+    },
+    /// `MIG-<uuid>`, as used by R470 and newer drivers.
+    Mig(Uuid),
+}
+
+impl DeviceUuid {
+    /// Whether or not this identifies a MIG device.
+    pub fn is_mig(&self) -> bool {
+        !matches!(self, DeviceUuid::Gpu(_))
+    }
+
+    /**
+    Get the UUID of the physical GPU this identifies or belongs to, where that
+    can be determined from the UUID alone.
+
+    Returns `None` for the newer `MIG-<uuid>` spelling, which doesn't encode
+    the parent GPU; only the driver can map those to their parent (through
+    `nvmlDeviceGetDeviceHandleFromMigDeviceHandle`).
+    */
+    pub fn parent_gpu(&self) -> Option<DeviceUuid> {
+        match *self {
+            DeviceUuid::Gpu(uuid) | DeviceUuid::LegacyMig { gpu: uuid, .. } => {
+                Some(DeviceUuid::Gpu(uuid))
+            }
+            DeviceUuid::Mig(_) => None,
+        }
+    }
+
+    /// Get the bare UUID, i.e. the one following the `GPU-` or `MIG-` prefix.
+    ///
+    /// For the legacy MIG spelling this is the UUID of the parent GPU.
+    pub fn uuid(&self) -> Uuid {
+        match *self {
+            DeviceUuid::Gpu(uuid) | DeviceUuid::Mig(uuid) => uuid,
+            DeviceUuid::LegacyMig { gpu, .. } => gpu,
+        }
+    }
+}
+
+impl FromStr for DeviceUuid {
+    type Err = ParseUuidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseUuidError(s.into());
+
+        if let Some(rest) = s.strip_prefix("MIG-GPU-") {
+            let mut parts = rest.split('/');
+            let mut next = || parts.next().ok_or_else(err);
+
+            // `parse()` would accept a sign as well
+            let id = |s: &str| {
+                if s.bytes().all(|b| b.is_ascii_digit()) {
+                    s.parse().map_err(|_| err())
+                } else {
+                    Err(err())
+                }
+            };
+
+            let gpu = next()?.parse().map_err(|_| err())?;
+            let gpu_instance_id = id(next()?)?;
+            let compute_instance_id = id(next()?)?;
+
+            if parts.next().is_some() {
+                return Err(err());
+            }
+
+            Ok(DeviceUuid::LegacyMig {
+                gpu,
+                gpu_instance_id,
+                compute_instance_id,
+            })
+        } else if let Some(rest) = s.strip_prefix("MIG-") {
+            Ok(DeviceUuid::Mig(rest.parse().map_err(|_| err())?))
+        } else if let Some(rest) = s.strip_prefix("GPU-") {
+            Ok(DeviceUuid::Gpu(rest.parse().map_err(|_| err())?))
+        } else {
+            Err(err())
+        }
+    }
+}
+
+impl fmt::Display for DeviceUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceUuid::Gpu(uuid) => write!(f, "GPU-{}", uuid),
+            DeviceUuid::LegacyMig {
+                gpu,
+                gpu_instance_id,
+                compute_instance_id,
+            } => write!(
+                f,
+                "MIG-GPU-{}/{}/{}",
+                gpu, gpu_instance_id, compute_instance_id
+            ),
+            DeviceUuid::Mig(uuid) => write!(f, "MIG-{}", uuid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GPU: &str = "GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5";
+    const LEGACY_MIG: &str = "MIG-GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5/1/0";
+    const MIG: &str = "MIG-5e93e4a1-0a9e-5f6b-9b3a-2f1c7d4e8a10";
+
+    #[test]
+    fn roundtrip() {
+        for s in &[GPU, LEGACY_MIG, MIG] {
+            assert_eq!(s.parse::<DeviceUuid>().unwrap().to_string(), *s);
+        }
+
+        assert_eq!(
+            "GPU-B8EA3855-276C-C9CB-B366-C6FA655957C5"
+                .parse::<DeviceUuid>()
+                .unwrap()
+                .to_string(),
+            GPU
+        );
+    }
+
+    #[test]
+    fn variants() {
+        let gpu: DeviceUuid = GPU.parse().unwrap();
+        let legacy: DeviceUuid = LEGACY_MIG.parse().unwrap();
+        let mig: DeviceUuid = MIG.parse().unwrap();
+
+        assert!(!gpu.is_mig());
+        assert!(legacy.is_mig());
+        assert!(mig.is_mig());
+
+        assert_eq!(gpu.parent_gpu(), Some(gpu));
+        assert_eq!(legacy.parent_gpu(), Some(gpu));
+        assert_eq!(mig.parent_gpu(), None);
+
+        match legacy {
+            DeviceUuid::LegacyMig {
+                gpu_instance_id,
+                compute_instance_id,
+                ..
+            } => assert_eq!((gpu_instance_id, compute_instance_id), (1, 0)),
+            other => panic!("unexpected variant: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_crate() {
+        let device: DeviceUuid = MIG.parse().unwrap();
+        let converted = ::uuid::Uuid::from(device.uuid());
+
+        assert_eq!(converted.to_string(), &MIG[4..]);
+        assert_eq!(Uuid::from(converted), device.uuid());
+    }
+
+    #[test]
+    fn invalid() {
+        for s in &[
+            "",
+            "b8ea3855-276c-c9cb-b366-c6fa655957c5",
+            "GPU-b8ea3855-276c-c9cb-b366",
+            "GPU-b8ea3855-276c-c9cb-b366-c6fa655957cg",
+            "GPU-b8ea3855276cc9cbb366c6fa655957c5",
+            "GPU-+8ea3855-276c-c9cb-b366-c6fa655957c5",
+            "GPU-b8ea3855-276c-c9cb-b366-c6fa655957+5",
+            "MIG-GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5/+1/0",
+            "MIG-GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5/1",
+            "MIG-GPU-b8ea3855-276c-c9cb-b366-c6fa655957c5/1/0/2",
+            "gpu-b8ea3855-276c-c9cb-b366-c6fa655957c5",
+        ] {
+            assert!(s.parse::<DeviceUuid>().is_err(), "{:?}", s);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+/*!
+Detection of WSL2 and the NVML functions that are unavailable there.
+
+Under WSL2, NVML talks to the Windows driver through a paravirtualized
+interface that doesn't expose everything a native driver does. Functions in
+`UNAVAILABLE` fail there with `NotSupported`, which `Device` reports with an
+`UnsupportedContext` saying so. Some driver versions fail them with `Unknown`
+or `NoPermission` instead; those errors are passed through unchanged, as they
+can't be told apart from genuine failures.
+*/
+
+#[cfg(target_os = "linux")]
+use std::{fs, path::Path};
+
+// NVML functions known not to work under WSL2, sorted.
+//
+// NVIDIA's CUDA on WSL user guide lists process information, accounting,
+// affinity, topology, events, and page retirement as unavailable.
+static UNAVAILABLE: &[&str] = &[
+    "nvmlDeviceGetAccountingBufferSize",
+    "nvmlDeviceGetAccountingMode",
+    "nvmlDeviceGetAccountingPids",
+    "nvmlDeviceGetAccountingStats",
+    "nvmlDeviceGetComputeRunningProcesses",
+    "nvmlDeviceGetComputeRunningProcesses_v3",
+    "nvmlDeviceGetCpuAffinity",
+    "nvmlDeviceGetEncoderSessions",
+    "nvmlDeviceGetFBCSessions",
+    "nvmlDeviceGetFBCStats",
+    "nvmlDeviceGetGraphicsRunningProcesses",
+    "nvmlDeviceGetGraphicsRunningProcesses_v3",
+    "nvmlDeviceGetMinorNumber",
+    "nvmlDeviceGetPersistenceMode",
+    "nvmlDeviceGetProcessUtilization",
+    "nvmlDeviceGetRetiredPages",
+    "nvmlDeviceGetRetiredPagesPendingStatus",
+    "nvmlDeviceGetRetiredPages_v2",
+    "nvmlDeviceGetSupportedEventTypes",
+    "nvmlDeviceGetTopologyCommonAncestor",
+    "nvmlDeviceGetTopologyNearestGpus",
+    "nvmlDeviceRegisterEvents",
+    "nvmlDeviceSetPersistenceMode",
+];
+
+/// The NVML functions known not to work under WSL2, sorted.
+pub(crate) fn unavailable() -> &'static [&'static str] {
+    UNAVAILABLE
+}
+
+/// Whether or not the given NVML function is known not to work under WSL2.
+pub(crate) fn is_unavailable(function: &str) -> bool {
+    UNAVAILABLE.binary_search(&function).is_ok()
+}
+
+/// Whether or not this process is running under WSL2.
+#[cfg(target_os = "linux")]
+pub(crate) fn detect() -> bool {
+    let release = fs::read_to_string("/proc/sys/kernel/osrelease").unwrap_or_default();
+
+    // `/dev/dxg` is the paravirtualized GPU WSL2 exposes NVML through
+    is_wsl2_release(&release) || Path::new("/dev/dxg").exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn detect() -> bool {
+    false
+}
+
+// WSL2 kernels are released as e.g. `5.15.90.1-microsoft-standard-WSL2`. WSL1
+// reports the Windows build instead (`4.4.0-19041-Microsoft`) and has no GPU
+// support at all.
+#[cfg(any(target_os = "linux", test))]
+fn is_wsl2_release(release: &str) -> bool {
+    let release = release.trim().to_ascii_lowercase();
+    release.contains("microsoft-standard") || release.ends_with("-wsl2")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(UNAVAILABLE.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn lookup() {
+        assert!(is_unavailable("nvmlDeviceGetComputeRunningProcesses_v3"));
+        assert!(!is_unavailable("nvmlDeviceGetTemperature"));
+    }
+
+    #[test]
+    fn releases() {
+        assert!(is_wsl2_release("5.15.90.1-microsoft-standard-WSL2\n"));
+        assert!(is_wsl2_release("5.10.16.3-microsoft-standard-WSL2"));
+        assert!(is_wsl2_release("6.1.21.2-custom-WSL2"));
+        assert!(!is_wsl2_release("4.4.0-19041-Microsoft"));
+        assert!(!is_wsl2_release("6.5.0-14-generic"));
+    }
+}